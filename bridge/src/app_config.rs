@@ -21,6 +21,32 @@ pub struct InstanceConfig {
     pub shares_per_min: Option<u32>,
     pub var_diff_stats: Option<bool>,
     pub pow2_clamp: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>, // Optional TLS listener serving the same difficulty tier as stratum_port
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sv2: Option<Sv2Config>, // Optional Stratum V2 listener serving the same difficulty tier as stratum_port
+}
+
+/// TLS stratum listener configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TlsConfig {
+    #[serde(deserialize_with = "deserialize_port")]
+    pub port: String,
+    pub cert_path: String, // PEM certificate chain
+    pub key_path: String,  // PEM private key
+    #[serde(default, deserialize_with = "deserialize_optional_duration_ms", serialize_with = "serialize_optional_duration_ms")]
+    pub reload_interval: Option<Duration>, // Reload the certificate when its files change, checked at this interval
+}
+
+/// Stratum V2 listener configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Sv2Config {
+    #[serde(deserialize_with = "deserialize_port")]
+    pub port: String,
+    pub authority_key_env: String, // Env var holding the hex authority secret key; an ephemeral key is used if unset
+    #[serde(deserialize_with = "deserialize_duration_ms", serialize_with = "serialize_duration_ms")]
+    pub certificate_validity: Duration, // Validity of the certificate signed at startup
 }
 
 /// Global configuration (shared across all instances)
@@ -28,6 +54,8 @@ pub struct InstanceConfig {
 #[serde(default)]
 pub struct GlobalConfig {
     pub kaspad_address: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub kaspad_failover_addresses: Vec<String>, // Additional upstream nodes, used when kaspad_address is unhealthy
     #[serde(deserialize_with = "deserialize_duration_ms", serialize_with = "serialize_duration_ms")]
     pub block_wait_time: Duration,
     pub print_stats: bool,
@@ -42,6 +70,35 @@ pub struct GlobalConfig {
     pub pow2_clamp: bool,
     #[serde(deserialize_with = "deserialize_coinbase_tag_suffix")]
     pub coinbase_tag_suffix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool: Option<PoolConfig>, // PPLNS pool mode, disabled (solo per worker) when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history: Option<HistoryConfig>, // Persistent share and block history, disabled when missing
+}
+
+/// PPLNS pool mode configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PoolConfig {
+    pub address: String,     // All workers mine to this address
+    pub fee_percent: f64,    // Pool fee deducted from every block reward
+    pub pplns_window: usize, // Number of last shares (N) sharing a block reward
+    pub ledger_path: String, // Append-only share and payout ledger
+    pub min_payout: u64,     // Minimum balance in sompi for being paid
+    #[serde(deserialize_with = "deserialize_duration_ms", serialize_with = "serialize_duration_ms")]
+    pub payout_interval: Duration,
+    pub payout_private_key_env: String, // Env var holding the hex private key of the pool address
+}
+
+/// Persistent share, block and worker history configuration
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub path: String, // Directory holding one JSON lines file per UTC day
+    #[serde(deserialize_with = "deserialize_duration_ms", serialize_with = "serialize_duration_ms")]
+    pub retention: Duration, // Day files entirely older than this are deleted
+    #[serde(deserialize_with = "deserialize_duration_ms", serialize_with = "serialize_duration_ms")]
+    pub hashrate_sample_interval: Duration, // Interval between two per-worker hashrate samples
 }
 
 /// Bridge configuration (supports both single and multi-instance modes)
@@ -182,12 +239,17 @@ struct BridgeConfigRaw {
     min_share_diff: Option<u32>,
     #[serde(default, deserialize_with = "deserialize_optional_port")]
     prom_port: Option<String>,
+    #[serde(default)]
+    tls: Option<TlsConfig>,
+    #[serde(default)]
+    sv2: Option<Sv2Config>,
 }
 
 impl Default for GlobalConfig {
     fn default() -> Self {
         Self {
             kaspad_address: "localhost:16110".to_string(),
+            kaspad_failover_addresses: Vec::new(),
             block_wait_time: Duration::from_millis(1000),
             print_stats: true,
             log_to_file: true,
@@ -199,6 +261,55 @@ impl Default for GlobalConfig {
             extranonce_size: 0,
             pow2_clamp: false,
             coinbase_tag_suffix: None,
+            pool: None,
+            history: None,
+        }
+    }
+}
+
+impl GlobalConfig {
+    /// All upstream node addresses, primary first, without duplicates
+    pub fn upstream_addresses(&self) -> Vec<String> {
+        let mut addresses = vec![self.kaspad_address.clone()];
+        for address in &self.kaspad_failover_addresses {
+            if !address.trim().is_empty() && !addresses.contains(address) {
+                addresses.push(address.clone());
+            }
+        }
+        addresses
+    }
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            address: String::new(),
+            fee_percent: 1.0,
+            pplns_window: 100_000,
+            ledger_path: "pool_ledger.jsonl".to_string(),
+            min_payout: 100_000_000,
+            payout_interval: Duration::from_millis(3_600_000),
+            payout_private_key_env: "RKSTRATUM_POOL_PRIVATE_KEY".to_string(),
+        }
+    }
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            path: "history".to_string(),
+            retention: Duration::from_millis(7 * 24 * 3_600_000),
+            hashrate_sample_interval: Duration::from_millis(60_000),
+        }
+    }
+}
+
+impl Default for Sv2Config {
+    fn default() -> Self {
+        Self {
+            port: String::new(),
+            authority_key_env: "RKSTRATUM_SV2_AUTHORITY_KEY".to_string(),
+            certificate_validity: Duration::from_millis(30 * 24 * 3_600_000),
         }
     }
 }
@@ -216,6 +327,8 @@ impl Default for InstanceConfig {
             shares_per_min: None,
             var_diff_stats: None,
             pow2_clamp: None,
+            tls: None,
+            sv2: None,
         }
     }
 }
@@ -253,7 +366,7 @@ impl BridgeConfig {
             instances
         } else {
             // Single-instance mode (backward compatible)
-            let mut instance = InstanceConfig { prom_port: raw.prom_port, ..InstanceConfig::default() };
+            let mut instance = InstanceConfig { prom_port: raw.prom_port, tls: raw.tls, sv2: raw.sv2, ..InstanceConfig::default() };
             if let Some(stratum_port) = raw.stratum_port {
                 instance.stratum_port = stratum_port;
             }
@@ -271,6 +384,54 @@ impl BridgeConfig {
                 return Err(anyhow::anyhow!("Duplicate stratum_port: {}", instance.stratum_port));
             }
         }
+        for instance in &instances {
+            if let Some(tls) = &instance.tls {
+                if tls.port.is_empty() {
+                    return Err(anyhow::anyhow!("TLS listener of {} missing required 'port'", instance.stratum_port));
+                }
+                if tls.cert_path.is_empty() || tls.key_path.is_empty() {
+                    return Err(anyhow::anyhow!("TLS listener {} requires 'cert_path' and 'key_path'", tls.port));
+                }
+                if !ports.insert(&tls.port) {
+                    return Err(anyhow::anyhow!("Duplicate stratum_port: {}", tls.port));
+                }
+            }
+            if let Some(sv2) = &instance.sv2 {
+                if sv2.port.is_empty() {
+                    return Err(anyhow::anyhow!("Stratum V2 listener of {} missing required 'port'", instance.stratum_port));
+                }
+                if sv2.certificate_validity.is_zero() {
+                    return Err(anyhow::anyhow!("Stratum V2 listener {} requires a positive 'certificate_validity'", sv2.port));
+                }
+                if !ports.insert(&sv2.port) {
+                    return Err(anyhow::anyhow!("Duplicate stratum_port: {}", sv2.port));
+                }
+            }
+        }
+
+        if let Some(pool) = &raw.global.pool {
+            if pool.address.trim().is_empty() {
+                return Err(anyhow::anyhow!("pool mode requires a pool 'address'"));
+            }
+            if !(0.0..=100.0).contains(&pool.fee_percent) {
+                return Err(anyhow::anyhow!("pool 'fee_percent' must be between 0 and 100"));
+            }
+            if pool.pplns_window == 0 {
+                return Err(anyhow::anyhow!("pool 'pplns_window' must be positive"));
+            }
+        }
+
+        if let Some(history) = &raw.global.history {
+            if history.path.trim().is_empty() {
+                return Err(anyhow::anyhow!("history requires a 'path'"));
+            }
+            if history.retention.is_zero() {
+                return Err(anyhow::anyhow!("history 'retention' must be positive"));
+            }
+            if history.hashrate_sample_interval < Duration::from_secs(1) {
+                return Err(anyhow::anyhow!("history 'hashrate_sample_interval' must be at least 1000 ms"));
+            }
+        }
 
         Ok(BridgeConfig { global: raw.global, instances })
     }
@@ -124,6 +124,9 @@ pub struct Cli {
     #[arg(long)]
     pub kaspad_address: Option<String>,
 
+    #[arg(long = "kaspad-failover-address", help = "Additional upstream kaspad node used for failover (repeatable)")]
+    pub kaspad_failover_addresses: Vec<String>,
+
     #[arg(long)]
     pub block_wait_time: Option<u64>,
 
@@ -224,6 +227,9 @@ pub fn apply_cli_overrides(config: &mut BridgeConfig, cli: &Cli) -> Result<(), a
     if let Some(addr) = cli.kaspad_address.as_deref() {
         config.global.kaspad_address = addr.to_string();
     }
+    if !cli.kaspad_failover_addresses.is_empty() {
+        config.global.kaspad_failover_addresses = cli.kaspad_failover_addresses.clone();
+    }
     if let Some(dur) = cli.block_wait_duration() {
         config.global.block_wait_time = dur;
     }
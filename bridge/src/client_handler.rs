@@ -33,7 +33,8 @@ pub struct ClientHandler {
     last_template_time: Arc<Mutex<Instant>>,
     last_balance_check: Arc<Mutex<Instant>>,
     share_handler: Arc<ShareHandler>,
-    instance_id: String, // Instance identifier for logging
+    instance_id: String,          // Instance identifier for logging
+    pool_address: Option<String>, // In pool mode, all templates pay this address instead of the worker wallet
 }
 
 impl ClientHandler {
@@ -50,9 +51,16 @@ impl ClientHandler {
             last_balance_check: Arc::new(Mutex::new(Instant::now())),
             share_handler,
             instance_id,
+            pool_address: None,
         }
     }
 
+    /// Requests block templates paying the pool address rather than each worker wallet
+    pub fn with_pool_address(mut self, pool_address: String) -> Self {
+        self.pool_address = Some(pool_address);
+        self
+    }
+
     pub fn on_connect(&self, ctx: Arc<StratumContext>) {
         let idx = self.client_counter.fetch_add(1, Ordering::Relaxed);
 
@@ -197,6 +205,7 @@ impl ClientHandler {
         let share_handler = Arc::clone(&self.share_handler);
         let min_diff = self.min_share_diff;
         let instance_id = self.instance_id.clone();
+        let pool_address = self.pool_address.clone();
 
         tokio::spawn(async move {
             // Get per-client mining state from context
@@ -213,7 +222,8 @@ impl ClientHandler {
             debug!("send_immediate_job: fetching block template for client {} (wallet: {})", client_clone.remote_addr, wallet_addr);
 
             // Get block template
-            let template_result = kaspa_api_clone.get_block_template(&wallet_addr, &remote_app, &canxium_addr).await;
+            let template_result =
+                kaspa_api_clone.get_block_template(pool_address.as_deref().unwrap_or(&wallet_addr), &remote_app, &canxium_addr).await;
 
             let block = match template_result {
                 Ok(block) => {
@@ -500,6 +510,7 @@ impl ClientHandler {
             let share_handler = Arc::clone(&self.share_handler);
             let min_diff = self.min_share_diff;
             let instance_id = self.instance_id.clone();
+            let pool_address = self.pool_address.clone();
 
             tokio::spawn(async move {
                 // Get per-client mining state from context
@@ -538,7 +549,9 @@ impl ClientHandler {
                     (wallet, app, canx)
                 };
 
-                let template_result = kaspa_api_clone.get_block_template(&wallet_addr, &remote_app, &canxium_addr).await;
+                let template_result = kaspa_api_clone
+                    .get_block_template(pool_address.as_deref().unwrap_or(&wallet_addr), &remote_app, &canxium_addr)
+                    .await;
 
                 let block = match template_result {
                     Ok(block) => {
@@ -323,7 +323,7 @@ fn process_canxium_address(address: &str) -> String {
 }
 
 /// Clean and validate wallet address
-fn clean_wallet(input: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+pub(crate) fn clean_wallet(input: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     // Try to decode as Kaspa address (supports kaspa:, kaspatest:, kaspadev:)
     if Address::try_from(input).is_ok() {
         return Ok(input.to_string());
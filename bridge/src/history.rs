@@ -0,0 +1,693 @@
+//! Persistent share, block and worker history.
+//!
+//! Valid shares, rejected shares with their reason, found blocks with their blue/red status and periodic
+//! per-worker hashrate samples are appended to one JSON lines file per UTC day in the history directory.
+//! Unlike the in-memory stats and Prometheus gauges, this history survives restarts, and the dashboard
+//! queries it by time range through `/api/history/<kind>`. Day files older than the retention period are
+//! deleted.
+//!
+//! Records are queued in memory and written in batches by the maintenance task, so share handling never waits
+//! on the disk; queries first write out whatever is still queued and run on the blocking thread pool.
+
+use crate::app_config::HistoryConfig;
+use crate::log_colors::LogColors;
+use crate::prom::WorkerContext;
+use crate::time_utils::unix_now;
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, watch};
+use tracing::{debug, info, warn};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+const DAY_FILE_PREFIX: &str = "history-";
+const DAY_FILE_SUFFIX: &str = ".jsonl";
+
+/// Interval between two checks for expired day files
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3_600);
+
+/// Range queried when `from` is not given
+const DEFAULT_QUERY_RANGE_SECS: u64 = SECONDS_PER_DAY;
+
+/// Number of raw records returned when `limit` is not given
+const DEFAULT_QUERY_LIMIT: usize = 1_000;
+
+/// Maximum number of raw records or buckets in a single response
+const MAX_QUERY_POINTS: usize = 10_000;
+
+fn day_file_name(timestamp: u64) -> String {
+    let date = chrono::DateTime::from_timestamp(timestamp as i64, 0).unwrap_or_default().date_naive();
+    format!("{}{}{}", DAY_FILE_PREFIX, date.format("%Y-%m-%d"), DAY_FILE_SUFFIX)
+}
+
+/// Returns the unix timestamp of the start of the day stored in the history file `name`
+fn parse_day_file_name(name: &str) -> Option<u64> {
+    let date = name.strip_prefix(DAY_FILE_PREFIX)?.strip_suffix(DAY_FILE_SUFFIX)?;
+    let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    u64::try_from(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp()).ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockStatus {
+    Blue,
+    Red,
+}
+
+/// A single line of a history day file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HistoryRecord {
+    Share {
+        timestamp: u64,
+        instance: String,
+        worker: String,
+        wallet: String,
+        difficulty: f64,
+    },
+    Rejected {
+        timestamp: u64,
+        instance: String,
+        worker: String,
+        wallet: String,
+        reason: String,
+    },
+    Block {
+        timestamp: u64,
+        instance: String,
+        worker: String,
+        wallet: String,
+        hash: String,
+        nonce: u64,
+        blue_score: u64,
+        status: BlockStatus,
+    },
+    Hashrate {
+        timestamp: u64,
+        instance: String,
+        worker: String,
+        wallet: String,
+        hashrate_ghs: f64,
+    },
+}
+
+impl HistoryRecord {
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            Self::Share { timestamp, .. }
+            | Self::Rejected { timestamp, .. }
+            | Self::Block { timestamp, .. }
+            | Self::Hashrate { timestamp, .. } => *timestamp,
+        }
+    }
+
+    pub fn kind(&self) -> HistoryKind {
+        match self {
+            Self::Share { .. } => HistoryKind::Shares,
+            Self::Rejected { .. } => HistoryKind::Rejected,
+            Self::Block { .. } => HistoryKind::Blocks,
+            Self::Hashrate { .. } => HistoryKind::Hashrate,
+        }
+    }
+
+    /// Returns the instance, worker and wallet the record belongs to
+    fn source(&self) -> (&str, &str, &str) {
+        match self {
+            Self::Share { instance, worker, wallet, .. }
+            | Self::Rejected { instance, worker, wallet, .. }
+            | Self::Block { instance, worker, wallet, .. }
+            | Self::Hashrate { instance, worker, wallet, .. } => (instance, worker, wallet),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryKind {
+    Shares,
+    Rejected,
+    Blocks,
+    Hashrate,
+}
+
+impl HistoryKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "shares" => Some(Self::Shares),
+            "rejected" => Some(Self::Rejected),
+            "blocks" => Some(Self::Blocks),
+            "hashrate" => Some(Self::Hashrate),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Shares => "shares",
+            Self::Rejected => "rejected",
+            Self::Blocks => "blocks",
+            Self::Hashrate => "hashrate",
+        }
+    }
+}
+
+/// Time-ranged history query, parsed from `/api/history/<kind>?from=&to=&instance=&worker=&wallet=&bucket=&limit=`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryQuery {
+    pub kind: HistoryKind,
+    pub from: u64, // Unix seconds, inclusive
+    pub to: u64,   // Unix seconds, inclusive
+    pub instance: Option<String>,
+    pub worker: Option<String>,
+    pub wallet: Option<String>,
+    pub bucket: Option<u64>, // Aggregate into buckets of this many seconds instead of returning raw records
+    pub limit: usize,        // Maximum number of raw records, the most recent ones are kept
+}
+
+impl HistoryQuery {
+    /// Parses a query from a request path. `to` defaults to `now` and `from` to a day before `to`.
+    pub fn from_path(path: &str, now: u64) -> Result<Self, String> {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let kind = path.strip_prefix("/api/history/").unwrap_or_default();
+        let kind = HistoryKind::parse(kind).ok_or_else(|| format!("unknown history kind '{}'", kind))?;
+
+        let params: HashMap<String, String> = form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+        let number = |key: &str| -> Result<Option<u64>, String> {
+            params
+                .get(key)
+                .filter(|v| !v.is_empty())
+                .map(|v| v.parse::<u64>().map_err(|_| format!("invalid '{}': {}", key, v)))
+                .transpose()
+        };
+        let text = |key: &str| params.get(key).filter(|v| !v.is_empty()).cloned();
+
+        let to = number("to")?.unwrap_or(now);
+        let from = number("from")?.unwrap_or_else(|| to.saturating_sub(DEFAULT_QUERY_RANGE_SECS));
+        if from > to {
+            return Err("'from' must not be after 'to'".to_string());
+        }
+        let bucket = number("bucket")?;
+        if let Some(bucket) = bucket {
+            if bucket == 0 {
+                return Err("'bucket' must be positive".to_string());
+            }
+            if (to - from) / bucket >= MAX_QUERY_POINTS as u64 {
+                return Err(format!(
+                    "too many buckets, use a 'bucket' of at least {} seconds",
+                    (to - from) / MAX_QUERY_POINTS as u64 + 1
+                ));
+            }
+        }
+        let limit = number("limit")?.map(|limit| limit as usize).unwrap_or(DEFAULT_QUERY_LIMIT).clamp(1, MAX_QUERY_POINTS);
+
+        Ok(Self { kind, from, to, instance: text("instance"), worker: text("worker"), wallet: text("wallet"), bucket, limit })
+    }
+
+    fn matches(&self, record: &HistoryRecord) -> bool {
+        let (instance, worker, wallet) = record.source();
+        record.kind() == self.kind
+            && (self.from..=self.to).contains(&record.timestamp())
+            && self.instance.as_deref().is_none_or(|v| v == instance)
+            && self.worker.as_deref().is_none_or(|v| v == worker)
+            && self.wallet.as_deref().is_none_or(|v| v == wallet)
+    }
+}
+
+/// Aggregated history over one bucket of a time-ranged query
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryPoint {
+    pub timestamp: u64, // Start of the bucket
+    pub count: u64,     // Number of records
+    // Shares: total difficulty; rejected: number of rejected shares; blocks: number of blue blocks;
+    // hashrate: total of the average hashrate (GH/s) of each worker
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryResponse {
+    pub kind: &'static str,
+    pub from: u64,
+    pub to: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub records: Option<Vec<HistoryRecord>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub points: Option<Vec<HistoryPoint>>,
+    pub truncated: bool, // Older records were left out because of `limit`
+}
+
+/// Share value accumulated by a worker since the last hashrate sample
+type WorkerKey = (String, String, String);
+
+/// Persistent history backed by one append-only JSON lines file per UTC day
+pub struct HistoryStore {
+    dir: PathBuf,
+    retention: Duration,
+    file: Mutex<Option<(String, File)>>, // Name and handle of the day file currently appended to
+    share_values: Mutex<HashMap<WorkerKey, f64>>,
+    queued: Mutex<Vec<HistoryRecord>>, // Records not written yet, in order
+    queued_notify: Notify,
+}
+
+impl HistoryStore {
+    /// Opens the history stored in the directory `dir`, creating it if missing
+    pub fn open(dir: &Path, retention: Duration) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating history directory {}", dir.display()))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            retention,
+            file: Mutex::new(None),
+            share_values: Mutex::new(HashMap::new()),
+            queued: Mutex::new(Vec::new()),
+            queued_notify: Notify::new(),
+        })
+    }
+
+    /// Appends the records to their day files with one write per day
+    fn write_records(&self, file: &mut Option<(String, File)>, records: &[HistoryRecord]) -> Result<()> {
+        for day in records.chunk_by(|a, b| a.timestamp() / SECONDS_PER_DAY == b.timestamp() / SECONDS_PER_DAY) {
+            let mut lines = String::new();
+            for record in day {
+                lines.push_str(&serde_json::to_string(record)?);
+                lines.push('\n');
+            }
+            let name = day_file_name(day[0].timestamp());
+            if file.as_ref().is_none_or(|(current, _)| *current != name) {
+                let path = self.dir.join(&name);
+                let handle =
+                    OpenOptions::new().create(true).append(true).open(&path).with_context(|| format!("opening {}", path.display()))?;
+                *file = Some((name, handle));
+            }
+            if let Some((_, handle)) = file.as_mut() {
+                handle.write_all(lines.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Queues a record for the maintenance task to write
+    fn queue(&self, record: HistoryRecord) {
+        self.queued.lock().push(record);
+        self.queued_notify.notify_one();
+    }
+
+    /// Writes out the queued records
+    pub fn flush(&self) {
+        // Holding the file lock while taking the queue keeps concurrent flushes in order
+        let mut file = self.file.lock();
+        let records = std::mem::take(&mut *self.queued.lock());
+        if let Err(e) = self.write_records(&mut file, &records) {
+            warn!("{} failed to record {} history record(s): {}", LogColors::label("[HISTORY]"), records.len(), e);
+        }
+    }
+
+    /// Records a valid share, `difficulty` is the share value the worker hashrate is computed from
+    pub fn record_share(&self, worker: &WorkerContext, difficulty: f64) {
+        *self
+            .share_values
+            .lock()
+            .entry((worker.instance_id.clone(), worker.worker_name.clone(), worker.wallet.clone()))
+            .or_default() += difficulty;
+        self.queue(HistoryRecord::Share {
+            timestamp: unix_now(),
+            instance: worker.instance_id.clone(),
+            worker: worker.worker_name.clone(),
+            wallet: worker.wallet.clone(),
+            difficulty,
+        });
+    }
+
+    pub fn record_rejected(&self, worker: &WorkerContext, reason: &str) {
+        self.queue(HistoryRecord::Rejected {
+            timestamp: unix_now(),
+            instance: worker.instance_id.clone(),
+            worker: worker.worker_name.clone(),
+            wallet: worker.wallet.clone(),
+            reason: reason.to_string(),
+        });
+    }
+
+    pub fn record_block(&self, worker: &WorkerContext, hash: &str, nonce: u64, blue_score: u64, status: BlockStatus) {
+        self.queue(HistoryRecord::Block {
+            timestamp: unix_now(),
+            instance: worker.instance_id.clone(),
+            worker: worker.worker_name.clone(),
+            wallet: worker.wallet.clone(),
+            hash: hash.to_string(),
+            nonce,
+            blue_score,
+            status,
+        });
+    }
+
+    /// Records the hashrate of every worker over the last `elapsed`. A worker that stopped submitting
+    /// shares gets a single zero sample, then no more samples until it submits again.
+    pub fn sample_hashrates(&self, elapsed: Duration) {
+        let elapsed = elapsed.as_secs_f64();
+        if elapsed <= 0.0 {
+            return;
+        }
+        let timestamp = unix_now();
+        let samples: Vec<_> = {
+            let mut share_values = self.share_values.lock();
+            let samples = share_values.iter().map(|(key, value)| (key.clone(), value / elapsed)).collect();
+            share_values.retain(|_, value| *value > 0.0);
+            share_values.values_mut().for_each(|value| *value = 0.0);
+            samples
+        };
+        for ((instance, worker, wallet), hashrate_ghs) in samples {
+            self.queue(HistoryRecord::Hashrate { timestamp, instance, worker, wallet, hashrate_ghs });
+        }
+    }
+
+    /// Returns the day files sorted by day, along with the unix timestamp the day starts at
+    fn day_files(&self) -> Result<Vec<(u64, PathBuf)>> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.dir).with_context(|| format!("reading history directory {}", self.dir.display()))? {
+            let entry = entry?;
+            if let Some(day) = entry.file_name().to_str().and_then(parse_day_file_name) {
+                files.push((day, entry.path()));
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Deletes the day files entirely older than the retention period and returns how many were deleted
+    pub fn prune(&self, now: u64) -> Result<usize> {
+        let cutoff = now.saturating_sub(self.retention.as_secs());
+        let mut pruned = 0;
+        for (day, path) in self.day_files()? {
+            if day + SECONDS_PER_DAY > cutoff {
+                continue;
+            }
+            let mut file = self.file.lock();
+            if file.as_ref().is_some_and(|(name, _)| path.file_name().is_some_and(|n| n == name.as_str())) {
+                *file = None;
+            }
+            std::fs::remove_file(&path).with_context(|| format!("deleting {}", path.display()))?;
+            pruned += 1;
+        }
+        Ok(pruned)
+    }
+
+    /// Runs a time-ranged query over the day files. This reads from disk, so async callers should run it on the
+    /// blocking thread pool.
+    pub fn query(&self, query: &HistoryQuery) -> Result<HistoryResponse> {
+        self.flush();
+        let mut records = VecDeque::new();
+        let mut truncated = false;
+        let mut points = query.bucket.map(|bucket| Buckets::new(query.from, query.to, bucket));
+
+        for (day, path) in self.day_files()? {
+            if day + SECONDS_PER_DAY <= query.from || day > query.to {
+                continue;
+            }
+            let reader = BufReader::new(File::open(&path).with_context(|| format!("opening {}", path.display()))?);
+            for line in reader.lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let record = match serde_json::from_str::<HistoryRecord>(&line) {
+                    Ok(record) => record,
+                    // The last line may still be being written
+                    Err(e) => {
+                        debug!("Skipping malformed history line in {}: {}", path.display(), e);
+                        continue;
+                    }
+                };
+                if !query.matches(&record) {
+                    continue;
+                }
+                match points.as_mut() {
+                    Some(points) => points.add(&record),
+                    None => {
+                        records.push_back(record);
+                        if records.len() > query.limit {
+                            records.pop_front();
+                            truncated = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(HistoryResponse {
+            kind: query.kind.as_str(),
+            from: query.from,
+            to: query.to,
+            bucket: query.bucket,
+            records: if points.is_none() { Some(records.into()) } else { None },
+            points: points.map(Buckets::finish),
+            truncated,
+        })
+    }
+
+    /// Writes queued records, samples worker hashrates on the configured interval and deletes expired day files
+    /// until shutdown
+    pub fn start_maintenance_thread(self: &Arc<Self>, hashrate_sample_interval: Duration, mut shutdown_rx: watch::Receiver<bool>) {
+        let history = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut sample_interval = tokio::time::interval(hashrate_sample_interval);
+            sample_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut prune_interval = tokio::time::interval(PRUNE_INTERVAL);
+            prune_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            // The first sample tick completes immediately, before any share could be recorded
+            sample_interval.tick().await;
+            let mut last_sample = tokio::time::Instant::now();
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.wait_for(|v| *v) => break,
+                    // Records queued while a batch is being written go out together with the next one
+                    _ = history.queued_notify.notified() => {
+                        let history = Arc::clone(&history);
+                        let _ = tokio::task::spawn_blocking(move || history.flush()).await;
+                    }
+                    _ = sample_interval.tick() => {
+                        let now = tokio::time::Instant::now();
+                        history.sample_hashrates(now - last_sample);
+                        last_sample = now;
+                    }
+                    _ = prune_interval.tick() => {
+                        let pruning = Arc::clone(&history);
+                        let pruned = tokio::task::spawn_blocking(move || pruning.prune(unix_now()))
+                            .await
+                            .unwrap_or_else(|e| Err(e.into()));
+                        match pruned {
+                            Ok(0) => {}
+                            Ok(pruned) => info!("{} deleted {} expired history file(s)", LogColors::label("[HISTORY]"), pruned),
+                            Err(e) => warn!("{} failed to delete expired history: {}", LogColors::label("[HISTORY]"), e),
+                        }
+                    }
+                }
+            }
+            let _ = tokio::task::spawn_blocking(move || history.flush()).await;
+            debug!("history maintenance thread stopped");
+        });
+    }
+}
+
+/// Accumulates the records of a bucketed query
+struct Buckets {
+    from: u64,
+    bucket: u64,
+    points: Vec<HistoryPoint>,
+    // Per bucket hashrate sample total and count of each worker
+    hashrates: Vec<HashMap<WorkerKey, (f64, u64)>>,
+}
+
+impl Buckets {
+    fn new(from: u64, to: u64, bucket: u64) -> Self {
+        let len = ((to - from) / bucket + 1) as usize;
+        let points = (0..len as u64).map(|i| HistoryPoint { timestamp: from + i * bucket, count: 0, value: 0.0 }).collect();
+        Self { from, bucket, points, hashrates: vec![HashMap::new(); len] }
+    }
+
+    fn add(&mut self, record: &HistoryRecord) {
+        let idx = ((record.timestamp() - self.from) / self.bucket) as usize;
+        let Some(point) = self.points.get_mut(idx) else {
+            return;
+        };
+        point.count += 1;
+        match record {
+            HistoryRecord::Share { difficulty, .. } => point.value += difficulty,
+            HistoryRecord::Rejected { .. } => point.value += 1.0,
+            HistoryRecord::Block { status, .. } => point.value += if *status == BlockStatus::Blue { 1.0 } else { 0.0 },
+            HistoryRecord::Hashrate { instance, worker, wallet, hashrate_ghs, .. } => {
+                let entry = self.hashrates[idx].entry((instance.clone(), worker.clone(), wallet.clone())).or_default();
+                entry.0 += hashrate_ghs;
+                entry.1 += 1;
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<HistoryPoint> {
+        for (point, hashrates) in self.points.iter_mut().zip(self.hashrates) {
+            if !hashrates.is_empty() {
+                point.value = hashrates.values().map(|(total, count)| total / *count as f64).sum();
+            }
+        }
+        self.points
+    }
+}
+
+/// Opens the history store configured in `config` and starts its maintenance thread
+pub fn start_history(config: &HistoryConfig, shutdown_rx: watch::Receiver<bool>) -> Result<Arc<HistoryStore>> {
+    let history = Arc::new(HistoryStore::open(Path::new(&config.path), config.retention)?);
+    history.start_maintenance_thread(config.hashrate_sample_interval, shutdown_rx);
+    info!(
+        "{} recording share and block history to {} (retention {} day(s))",
+        LogColors::label("[HISTORY]"),
+        config.path,
+        config.retention.as_secs_f64() / SECONDS_PER_DAY as f64
+    );
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_history_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ks_bridge_history_{}_{}_{}", name, std::process::id(), unix_now()))
+    }
+
+    fn share(timestamp: u64, worker: &str, difficulty: f64) -> HistoryRecord {
+        HistoryRecord::Share {
+            timestamp,
+            instance: "Instance 1".to_string(),
+            worker: worker.to_string(),
+            wallet: "kaspa:wallet".to_string(),
+            difficulty,
+        }
+    }
+
+    fn hashrate(timestamp: u64, worker: &str, hashrate_ghs: f64) -> HistoryRecord {
+        HistoryRecord::Hashrate {
+            timestamp,
+            instance: "Instance 1".to_string(),
+            worker: worker.to_string(),
+            wallet: "kaspa:wallet".to_string(),
+            hashrate_ghs,
+        }
+    }
+
+    #[test]
+    fn test_day_file_names() {
+        assert_eq!(day_file_name(0), "history-1970-01-01.jsonl");
+        assert_eq!(day_file_name(2 * SECONDS_PER_DAY - 1), "history-1970-01-02.jsonl");
+        assert_eq!(parse_day_file_name("history-1970-01-02.jsonl"), Some(SECONDS_PER_DAY));
+        assert_eq!(parse_day_file_name("pool_ledger.jsonl"), None);
+    }
+
+    #[test]
+    fn test_query_parsing() {
+        let query = HistoryQuery::from_path("/api/history/rejected?from=100&to=200&worker=rig%201&bucket=10", 1_000).unwrap();
+        assert_eq!(query.kind, HistoryKind::Rejected);
+        assert_eq!((query.from, query.to), (100, 200));
+        assert_eq!(query.worker.as_deref(), Some("rig 1"));
+        assert_eq!(query.bucket, Some(10));
+        assert_eq!(query.limit, DEFAULT_QUERY_LIMIT);
+
+        let query = HistoryQuery::from_path("/api/history/shares", SECONDS_PER_DAY + 5).unwrap();
+        assert_eq!((query.from, query.to), (5, SECONDS_PER_DAY + 5));
+        assert!(query.worker.is_none() && query.bucket.is_none());
+
+        assert!(HistoryQuery::from_path("/api/history/unknown", 0).is_err());
+        assert!(HistoryQuery::from_path("/api/history/shares?from=200&to=100", 0).is_err());
+        assert!(HistoryQuery::from_path("/api/history/shares?from=x", 0).is_err());
+        assert!(HistoryQuery::from_path("/api/history/shares?from=0&to=100000&bucket=1", 0).is_err());
+    }
+
+    #[test]
+    fn test_store_query_and_prune() {
+        let dir = temp_history_dir("query");
+        let history = HistoryStore::open(&dir, Duration::from_secs(2 * SECONDS_PER_DAY)).unwrap();
+        // Records span three days
+        history.queue(share(10, "rig1", 1.0));
+        history.queue(share(SECONDS_PER_DAY + 10, "rig1", 2.0));
+        history.queue(share(SECONDS_PER_DAY + 20, "rig2", 4.0));
+        history.queue(hashrate(SECONDS_PER_DAY + 30, "rig1", 3.0));
+        history.queue(hashrate(SECONDS_PER_DAY + 40, "rig1", 5.0));
+        history.queue(hashrate(SECONDS_PER_DAY + 50, "rig2", 1.0));
+        history.queue(share(2 * SECONDS_PER_DAY + 10, "rig1", 8.0));
+        history.flush();
+
+        let mut query = HistoryQuery::from_path("/api/history/shares?from=0&to=1000000", 0).unwrap();
+        let response = history.query(&query).unwrap();
+        assert_eq!(response.records.unwrap().len(), 4);
+        assert!(!response.truncated);
+
+        // Only the most recent records are kept past the limit
+        query.limit = 1;
+        query.worker = Some("rig1".to_string());
+        let response = history.query(&query).unwrap();
+        assert_eq!(response.records.unwrap(), vec![share(2 * SECONDS_PER_DAY + 10, "rig1", 8.0)]);
+        assert!(response.truncated);
+
+        let query =
+            HistoryQuery::from_path(&format!("/api/history/shares?from={}&to={}&bucket=15", SECONDS_PER_DAY, SECONDS_PER_DAY + 29), 0)
+                .unwrap();
+        let points = history.query(&query).unwrap().points.unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!((points[0].count, points[0].value), (1, 2.0));
+        assert_eq!((points[1].count, points[1].value), (1, 4.0));
+
+        // Hashrate buckets add up the average hashrate of each worker
+        let query = HistoryQuery::from_path(
+            &format!("/api/history/hashrate?from={}&to={}&bucket=60", SECONDS_PER_DAY, SECONDS_PER_DAY + 59),
+            0,
+        )
+        .unwrap();
+        let points = history.query(&query).unwrap().points.unwrap();
+        assert_eq!((points[0].count, points[0].value), (3, 5.0));
+
+        // The first day is entirely past the retention period
+        assert_eq!(history.prune(3 * SECONDS_PER_DAY).unwrap(), 1);
+        let query = HistoryQuery::from_path("/api/history/shares?from=0&to=1000000", 0).unwrap();
+        assert_eq!(history.query(&query).unwrap().records.unwrap().len(), 3);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_hashrate_samples() {
+        let dir = temp_history_dir("samples");
+        let history = HistoryStore::open(&dir, Duration::from_secs(SECONDS_PER_DAY)).unwrap();
+        let worker = WorkerContext {
+            instance_id: "Instance 1".to_string(),
+            worker_name: "rig1".to_string(),
+            miner: String::new(),
+            wallet: "kaspa:wallet".to_string(),
+            ip: String::new(),
+        };
+        history.record_share(&worker, 30.0);
+        history.record_share(&worker, 30.0);
+        history.sample_hashrates(Duration::from_secs(60));
+        // An idle worker is sampled once at zero, then dropped
+        history.sample_hashrates(Duration::from_secs(60));
+        history.sample_hashrates(Duration::from_secs(60));
+
+        let query = HistoryQuery::from_path("/api/history/hashrate", unix_now() + 1).unwrap();
+        let samples: Vec<f64> = history
+            .query(&query)
+            .unwrap()
+            .records
+            .unwrap()
+            .into_iter()
+            .map(|record| match record {
+                HistoryRecord::Hashrate { hashrate_ghs, .. } => hashrate_ghs,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(samples, vec![1.0, 0.0]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
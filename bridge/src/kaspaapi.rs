@@ -1,20 +1,26 @@
 use crate::log_colors::LogColors;
+use crate::pool::PayoutApi;
+use crate::prom::record_active_upstream;
 use crate::share_handler::KaspaApiTrait;
 use anyhow::{Context, Result};
 use kaspa_addresses::Address;
 use kaspa_consensus_core::block::Block;
+use kaspa_consensus_core::network::NetworkId;
+use kaspa_consensus_core::tx::{Transaction, TransactionId};
 use kaspa_grpc_client::GrpcClient;
 use kaspa_notify::{listener::ListenerId, scope::NewBlockTemplateScope};
 use kaspa_rpc_core::notify::mode::NotificationMode;
 use kaspa_rpc_core::{
-    GetBlockDagInfoRequest, GetBlockTemplateRequest, GetConnectedPeerInfoRequest, GetCurrentBlockColorRequest, GetInfoRequest,
-    GetServerInfoRequest, Notification, RpcHash, RpcRawBlock, SubmitBlockRequest, SubmitBlockResponse, api::rpc::RpcApi,
+    GetBlockDagInfoRequest, GetBlockRewardInfoRequest, GetBlockTemplateRequest, GetConnectedPeerInfoRequest,
+    GetCurrentBlockColorRequest, GetInfoRequest, GetServerInfoRequest, Notification, RpcBlockColor, RpcError, RpcHash, RpcRawBlock,
+    RpcResult, RpcUtxosByAddressesEntry, SubmitBlockRequest, SubmitBlockResponse, api::rpc::RpcApi,
 };
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use std::collections::{HashMap, VecDeque};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio::sync::watch;
@@ -128,10 +134,32 @@ pub struct NodeStatusSnapshot {
 
 pub static NODE_STATUS: Lazy<Mutex<NodeStatusSnapshot>> = Lazy::new(|| Mutex::new(NodeStatusSnapshot::default()));
 
+/// Maximum virtual DAA score lag behind the most advanced upstream for a synced upstream to remain eligible
+const UPSTREAM_MAX_DAA_LAG: u64 = 100;
+
+/// Health of an upstream node as seen by the last health check
+#[derive(Clone, Debug, Default)]
+struct UpstreamHealth {
+    connected: bool,
+    synced: bool,
+    virtual_daa_score: u64,
+}
+
+/// A kaspad node the bridge fetches job templates from and submits blocks to
+struct Upstream {
+    address: String,
+    client: OnceLock<Arc<GrpcClient>>, // Set once the initial connection succeeds
+    health: Mutex<UpstreamHealth>,
+}
+
 /// Kaspa API client wrapper using RPC client
 /// Both use gRPC under the hood, but through an RPC client wrapper abstraction
+///
+/// Multiple upstream nodes may be configured: job templates come from the active (healthiest synced)
+/// upstream while found blocks are submitted to all of them.
 pub struct KaspaApi {
-    client: Arc<GrpcClient>,
+    upstreams: Vec<Arc<Upstream>>,
+    active: Arc<AtomicUsize>,
     notification_rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<Notification>>>>,
     connected: Arc<Mutex<bool>>,
     coinbase_tag: Vec<u8>,
@@ -139,16 +167,99 @@ pub struct KaspaApi {
 
 impl KaspaApi {
     /// Create a new Kaspa API client
+    /// Returns as soon as one of the upstream nodes is connected, the others keep connecting in the background
     pub async fn new(
-        address: String,
+        addresses: Vec<String>,
         coinbase_tag_suffix: Option<String>,
         mut shutdown_rx: watch::Receiver<bool>,
     ) -> Result<Arc<Self>> {
+        if addresses.is_empty() {
+            return Err(anyhow::anyhow!("at least one kaspad address is required"));
+        }
+
+        let upstreams: Vec<Arc<Upstream>> = addresses
+            .iter()
+            .map(|address| {
+                Arc::new(Upstream { address: address.clone(), client: OnceLock::new(), health: Mutex::new(UpstreamHealth::default()) })
+            })
+            .collect();
+        let active = Arc::new(AtomicUsize::new(usize::MAX));
+
+        // Notifications of all upstreams are merged, but only those of the active one are forwarded
+        let (notification_tx, notification_rx) = mpsc::unbounded_channel();
+        let (connected_tx, mut connected_rx) = mpsc::unbounded_channel();
+        for (idx, upstream) in upstreams.iter().enumerate() {
+            let upstream = Arc::clone(upstream);
+            let active = Arc::clone(&active);
+            let notification_tx = notification_tx.clone();
+            let connected_tx = connected_tx.clone();
+            let shutdown_rx = shutdown_rx.clone();
+            tokio::spawn(async move {
+                let client = match Self::connect_upstream(&upstream.address, shutdown_rx).await {
+                    Ok(client) => client,
+                    Err(e) => {
+                        debug!("stopped connecting to kaspa node at {}: {}", upstream.address, e);
+                        return;
+                    }
+                };
+                let _ = upstream.client.set(Arc::clone(&client));
+                upstream.health.lock().connected = true;
+                let _ = connected_tx.send(idx);
+
+                // Convert async_channel::Receiver to tokio::sync::mpsc::UnboundedReceiver
+                let receiver = client.notification_channel_receiver();
+                while let Ok(notification) = receiver.recv().await {
+                    if active.load(Ordering::Relaxed) == idx {
+                        let _ = notification_tx.send(notification);
+                    }
+                }
+            });
+        }
+        drop(connected_tx);
+
+        let first_connected = tokio::select! {
+            _ = shutdown_rx.wait_for(|v| *v) => {
+                return Err(anyhow::anyhow!("shutdown requested"));
+            }
+            idx = connected_rx.recv() => idx.ok_or_else(|| anyhow::anyhow!("failed to connect to any kaspa node"))?,
+        };
+        active.store(first_connected, Ordering::Relaxed);
+
+        let coinbase_tag = build_coinbase_tag_bytes(coinbase_tag_suffix.as_deref());
+        let api = Arc::new(Self {
+            upstreams,
+            active,
+            notification_rx: Arc::new(Mutex::new(Some(notification_rx))),
+            connected: Arc::new(Mutex::new(true)),
+            coinbase_tag,
+        });
+        record_active_upstream(api.active_address(), &addresses);
+        if addresses.len() > 1 {
+            info!("Using kaspa node at {} as active upstream ({} configured)", api.active_address(), addresses.len());
+        }
+
+        // Start network stats thread
+        let api_clone = Arc::clone(&api);
+        tokio::spawn(async move {
+            api_clone.start_stats_thread().await;
+        });
+
+        // Start node status polling thread (for console status display and upstream health checks)
+        let api_clone = Arc::clone(&api);
+        tokio::spawn(async move {
+            api_clone.start_node_status_thread().await;
+        });
+
+        Ok(api)
+    }
+
+    /// Connect to a single kaspa node and subscribe to its block template notifications, retrying until shutdown
+    async fn connect_upstream(address: &str, mut shutdown_rx: watch::Receiver<bool>) -> Result<Arc<GrpcClient>> {
         info!("Connecting to Kaspa node at {}", address);
 
         // GrpcClient requires explicit "grpc://" prefix for connection
         // Always add it if not present (avoids unnecessary connection failure)
-        let grpc_address = if address.starts_with("grpc://") { address.clone() } else { format!("grpc://{}", address) };
+        let grpc_address = if address.starts_with("grpc://") { address.to_string() } else { format!("grpc://{}", address) };
 
         // Log connection attempt (detailed logs moved to debug)
         debug!("{} {}", LogColors::api("[API]"), LogColors::label("Establishing RPC connection to Kaspa node:"));
@@ -232,7 +343,8 @@ impl KaspaApi {
                 Err(e) => {
                     let backoff = Duration::from_millis(backoff_ms);
                     warn!(
-                        "failed to subscribe to block template notifications (attempt {}): {}, retrying in {:.2}s",
+                        "failed to subscribe to block template notifications of {} (attempt {}): {}, retrying in {:.2}s",
+                        grpc_address,
                         attempt,
                         e,
                         backoff.as_secs_f64()
@@ -249,36 +361,7 @@ impl KaspaApi {
             }
         }
 
-        // Start receiving notifications
-        let notification_rx = {
-            let receiver = client.notification_channel_receiver();
-            // Convert async_channel::Receiver to tokio::sync::mpsc::UnboundedReceiver
-            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-            let receiver_clone = receiver.clone();
-            tokio::spawn(async move {
-                while let Ok(notification) = receiver_clone.recv().await {
-                    let _ = tx.send(notification);
-                }
-            });
-            Arc::new(Mutex::new(Some(rx)))
-        };
-
-        let coinbase_tag = build_coinbase_tag_bytes(coinbase_tag_suffix.as_deref());
-        let api = Arc::new(Self { client, notification_rx, connected: Arc::new(Mutex::new(true)), coinbase_tag });
-
-        // Start network stats thread
-        let api_clone = Arc::clone(&api);
-        tokio::spawn(async move {
-            api_clone.start_stats_thread().await;
-        });
-
-        // Start node status polling thread (for console status display)
-        let api_clone = Arc::clone(&api);
-        tokio::spawn(async move {
-            api_clone.start_node_status_thread().await;
-        });
-
-        Ok(api)
+        Ok(client)
     }
 
     /// Start network stats thread
@@ -293,7 +376,8 @@ impl KaspaApi {
 
             // Get block DAG info
             // GetBlockDagInfoRequest is a unit struct, construct directly
-            let dag_response = match self.client.get_block_dag_info_call(None, GetBlockDagInfoRequest {}).await {
+            let client = self.client();
+            let dag_response = match client.get_block_dag_info_call(None, GetBlockDagInfoRequest {}).await {
                 Ok(r) => r,
                 Err(e) => {
                     warn!("failed to get network hashrate from kaspa, prom stats will be out of date: {}", e);
@@ -314,8 +398,7 @@ impl KaspaApi {
             // Estimate network hashes per second
             // new(window_size: u32, start_hash: Option<RpcHash>)
             // RpcHash is the same as Hash, so we can use tip_hash directly
-            let hashrate_response = match self
-                .client
+            let hashrate_response = match client
                 .estimate_network_hashes_per_second_call(None, EstimateNetworkHashesPerSecondRequest::new(1000, tip_hash))
                 .await
             {
@@ -336,12 +419,16 @@ impl KaspaApi {
         loop {
             interval.tick().await;
 
-            let connected = self.client.is_connected();
+            // Health check all upstreams first so the snapshot below reflects the (possibly new) active one
+            self.refresh_upstream_health().await;
+
+            let client = self.client();
+            let connected = client.is_connected();
 
-            let server_info_fut = self.client.get_server_info_call(None, GetServerInfoRequest {});
-            let dag_info_fut = self.client.get_block_dag_info_call(None, GetBlockDagInfoRequest {});
-            let peers_fut = self.client.get_connected_peer_info_call(None, GetConnectedPeerInfoRequest {});
-            let info_fut = self.client.get_info_call(None, GetInfoRequest {});
+            let server_info_fut = client.get_server_info_call(None, GetServerInfoRequest {});
+            let dag_info_fut = client.get_block_dag_info_call(None, GetBlockDagInfoRequest {});
+            let peers_fut = client.get_connected_peer_info_call(None, GetConnectedPeerInfoRequest {});
+            let info_fut = client.get_info_call(None, GetInfoRequest {});
 
             let (server_info, dag_info, peers_info, info_resp) = tokio::join!(server_info_fut, dag_info_fut, peers_fut, info_fut);
 
@@ -415,10 +502,9 @@ impl KaspaApi {
         // Convert Block to RpcRawBlock (use reference)
         let rpc_block: RpcRawBlock = (&block).into();
 
-        // Submit block (don't allow non-DAA blocks)
+        // Submit block (don't allow non-DAA blocks) to all connected upstreams for faster propagation
         debug!("{} {}", LogColors::api("[API]"), "Calling submit_block via RPC client...");
-        let result =
-            self.client.submit_block_call(None, SubmitBlockRequest::new(rpc_block, false)).await.context("Failed to submit block");
+        let result = self.submit_block_to_upstreams(rpc_block).await.context("Failed to submit block");
 
         if let Err(e) = &result {
             let error_str = e.to_string();
@@ -486,7 +572,7 @@ impl KaspaApi {
 
                 // Optional: Check if block appears in tip hashes (verifies propagation)
                 // This is informational only - block may still propagate even if not immediately in tips
-                let client_clone = Arc::clone(&self.client);
+                let client_clone = self.client();
                 let block_hash_clone = block_hash.clone();
                 let block_hash_for_check = header::hash(&block.header); // Use the actual Hash type
                 tokio::spawn(async move {
@@ -582,7 +668,7 @@ impl KaspaApi {
     /// Wait for node to sync
     async fn wait_for_sync(&self) -> Result<()> {
         loop {
-            match self.client.get_sync_status().await {
+            match self.client().get_sync_status().await {
                 Ok(is_synced) => {
                     if is_synced {
                         break;
@@ -593,6 +679,11 @@ impl KaspaApi {
                 }
             }
 
+            // Fail over right away if another upstream is healthy
+            if self.refresh_upstream_health().await {
+                continue;
+            }
+
             sleep(Duration::from_secs(10)).await;
         }
 
@@ -603,7 +694,8 @@ impl KaspaApi {
         debug!("checking kaspad sync state");
 
         loop {
-            let sync_fut = self.client.get_sync_status();
+            let client = self.client();
+            let sync_fut = client.get_sync_status();
             let sync_res = tokio::select! {
                 _ = shutdown_rx.wait_for(|v| *v) => {
                     return Err(anyhow::anyhow!("shutdown requested"));
@@ -623,6 +715,10 @@ impl KaspaApi {
                 }
             }
 
+            if self.refresh_upstream_health().await {
+                continue;
+            }
+
             warn!("Kaspa is not synced, waiting for sync before starting bridge");
 
             tokio::select! {
@@ -641,7 +737,113 @@ impl KaspaApi {
         *self.connected.lock()
     }
 
-    /// Get block template for a client
+    /// Client of the active upstream
+    fn client(&self) -> Arc<GrpcClient> {
+        let active = self.active.load(Ordering::Relaxed);
+        // The active index only ever points to an upstream whose connection was established
+        Arc::clone(self.upstreams[active].client.get().expect("active upstream is connected"))
+    }
+
+    /// Address of the active upstream
+    pub fn active_address(&self) -> &str {
+        &self.upstreams[self.active.load(Ordering::Relaxed)].address
+    }
+
+    /// Clients of all the currently connected upstreams, along with their index
+    fn connected_upstreams(&self) -> Vec<(usize, Arc<GrpcClient>)> {
+        self.upstreams
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, upstream)| {
+                upstream.client.get().filter(|client| client.is_connected()).map(|client| (idx, Arc::clone(client)))
+            })
+            .collect()
+    }
+
+    /// Polls the sync state of every upstream and makes the healthiest one active.
+    /// Returns true if the active upstream changed. Miners stay connected across a switch,
+    /// their next job simply comes from the new upstream.
+    async fn refresh_upstream_health(&self) -> bool {
+        let checks = self.upstreams.iter().map(|upstream| async move {
+            let health = match upstream.client.get() {
+                Some(client) if client.is_connected() => match client.get_server_info_call(None, GetServerInfoRequest {}).await {
+                    Ok(info) => UpstreamHealth { connected: true, synced: info.is_synced, virtual_daa_score: info.virtual_daa_score },
+                    Err(e) => {
+                        debug!("health check of kaspa node at {} failed: {}", upstream.address, e);
+                        UpstreamHealth { connected: true, ..Default::default() }
+                    }
+                },
+                _ => UpstreamHealth::default(),
+            };
+            *upstream.health.lock() = health;
+        });
+        futures_util::future::join_all(checks).await;
+
+        let health: Vec<UpstreamHealth> = self.upstreams.iter().map(|upstream| upstream.health.lock().clone()).collect();
+        let current = self.active.load(Ordering::Relaxed);
+        let Some(selected) = select_upstream(&health, current) else {
+            return false;
+        };
+        if selected == current {
+            return false;
+        }
+
+        warn!(
+            "{} {}",
+            LogColors::api("[API]"),
+            LogColors::validation(&format!(
+                "Switching upstream kaspa node from {} to {}",
+                self.upstreams[current].address, self.upstreams[selected].address
+            ))
+        );
+        self.active.store(selected, Ordering::Relaxed);
+        let addresses: Vec<String> = self.upstreams.iter().map(|upstream| upstream.address.clone()).collect();
+        record_active_upstream(&self.upstreams[selected].address, &addresses);
+        true
+    }
+
+    /// Submits a block to all connected upstreams. The block counts as accepted if any upstream accepts it;
+    /// otherwise the response of the active upstream is returned.
+    async fn submit_block_to_upstreams(&self, rpc_block: RpcRawBlock) -> RpcResult<SubmitBlockResponse> {
+        let active = self.active.load(Ordering::Relaxed);
+        let submissions = self.connected_upstreams().into_iter().map(|(idx, client)| {
+            let request = SubmitBlockRequest::new(rpc_block.clone(), false);
+            async move { (idx, client.submit_block_call(None, request).await) }
+        });
+        let mut results = futures_util::future::join_all(submissions).await;
+        if results.is_empty() {
+            // No upstream reports as connected, still try the active one (it reconnects on demand)
+            results.push((active, self.client().submit_block_call(None, SubmitBlockRequest::new(rpc_block, false)).await));
+        }
+
+        let mut active_result = None;
+        let mut accepted_elsewhere = None;
+        for (idx, result) in results {
+            if idx == active {
+                active_result = Some(result);
+                continue;
+            }
+            match result {
+                Ok(response) if response.report.is_success() => {
+                    accepted_elsewhere.get_or_insert((idx, response));
+                }
+                Ok(response) => debug!("block rejected by upstream {}: {:?}", self.upstreams[idx].address, response.report),
+                Err(e) => debug!("block submission to upstream {} failed: {}", self.upstreams[idx].address, e),
+            }
+        }
+
+        match (active_result, accepted_elsewhere) {
+            (Some(Ok(response)), _) if response.report.is_success() => Ok(response),
+            (_, Some((idx, response))) => {
+                info!("{} Block accepted by upstream {}", LogColors::api("[API]"), self.upstreams[idx].address);
+                Ok(response)
+            }
+            (Some(result), None) => result,
+            (None, None) => Err(RpcError::General("active upstream is not connected".to_string())),
+        }
+    }
+
+    /// Get block template for a client (from the active upstream)
     pub async fn get_block_template(&self, wallet_addr: &str, _remote_app: &str, _canxium_addr: &str) -> Result<Block> {
         // Retry up to 3 times if we get "Odd number of digits" error
         // This error can occur if the block template has malformed hash fields
@@ -655,7 +857,7 @@ impl KaspaApi {
 
             // Request block template using RPC client wrapper
             let response = match self
-                .client
+                .client()
                 .get_block_template_call(None, GetBlockTemplateRequest::new(address, self.coinbase_tag.clone()))
                 .await
             {
@@ -742,7 +944,7 @@ impl KaspaApi {
         let addresses = parsed_addresses.map_err(|e| anyhow::anyhow!("Failed to parse addresses: {:?}", e))?;
 
         let utxos = self
-            .client
+            .client()
             .get_utxos_by_addresses_call(None, kaspa_rpc_core::GetUtxosByAddressesRequest::new(addresses))
             .await
             .context("Failed to get UTXOs by addresses")?;
@@ -765,13 +967,27 @@ impl KaspaApi {
     pub async fn get_current_block_color(&self, block_hash: &str) -> Result<bool> {
         let hash = RpcHash::from_str(block_hash).context("Failed to parse block hash")?;
         let resp = self
-            .client
+            .client()
             .get_current_block_color_call(None, GetCurrentBlockColorRequest { hash })
             .await
             .context("Failed to query current block color")?;
         Ok(resp.blue)
     }
 
+    /// Returns the reward paid for a blue block by the chain block merging it, `None` until it is merged
+    pub async fn get_block_reward(&self, block_hash: &str) -> Result<Option<u64>> {
+        let hash = RpcHash::from_str(block_hash).context("Failed to parse block hash")?;
+        let resp = self
+            .client()
+            .get_block_reward_info_call(None, GetBlockRewardInfoRequest { hash })
+            .await
+            .context("Failed to query block reward info")?;
+        Ok(match resp.block_color {
+            RpcBlockColor::Blue => resp.reward_amount,
+            RpcBlockColor::Unknown | RpcBlockColor::Red => None,
+        })
+    }
+
     /// Start listening for block template notifications
     /// Uses RegisterForNewBlockTemplateNotifications with ticker fallback
     /// This provides immediate notifications when new blocks are available, with polling as fallback
@@ -908,6 +1124,18 @@ impl KaspaApi {
     }
 }
 
+/// Picks the upstream to use given the health of every upstream: among the connected and synced ones not
+/// lagging too far behind the most advanced, keeps the current upstream if eligible, otherwise takes the
+/// first in configuration order. Returns None if no upstream is eligible.
+fn select_upstream(health: &[UpstreamHealth], current: usize) -> Option<usize> {
+    let max_daa_score = health.iter().filter(|h| h.connected && h.synced).map(|h| h.virtual_daa_score).max()?;
+    let eligible = |h: &UpstreamHealth| h.connected && h.synced && h.virtual_daa_score + UPSTREAM_MAX_DAA_LAG >= max_daa_score;
+    if health.get(current).is_some_and(eligible) {
+        return Some(current);
+    }
+    health.iter().position(eligible)
+}
+
 #[async_trait::async_trait]
 impl KaspaApiTrait for KaspaApi {
     async fn get_block_template(
@@ -945,4 +1173,65 @@ impl KaspaApiTrait for KaspaApi {
             .await
             .map_err(|e| Box::new(std::io::Error::other(e.to_string())) as Box<dyn std::error::Error + Send + Sync>)
     }
+
+    async fn get_block_reward(&self, block_hash: &str) -> Result<Option<u64>, Box<dyn std::error::Error + Send + Sync>> {
+        KaspaApi::get_block_reward(self, block_hash)
+            .await
+            .map_err(|e| Box::new(std::io::Error::other(e.to_string())) as Box<dyn std::error::Error + Send + Sync>)
+    }
+}
+
+// Payouts are built and submitted through the upstream active at the time of each call, so they follow the failovers
+#[async_trait::async_trait]
+impl PayoutApi for KaspaApi {
+    async fn get_network_info(&self) -> Result<(NetworkId, u64)> {
+        let server_info = self.client().get_server_info().await.context("Failed to get server info")?;
+        Ok((server_info.network_id, server_info.virtual_daa_score))
+    }
+
+    async fn get_utxos_by_address(&self, address: &Address) -> Result<Vec<RpcUtxosByAddressesEntry>> {
+        self.client().get_utxos_by_addresses(vec![address.clone()]).await.context("Failed to get UTXOs by addresses")
+    }
+
+    async fn submit_transaction(&self, transaction: &Transaction) -> Result<()> {
+        self.client().submit_transaction(transaction.into(), false).await.context("Failed to submit transaction")?;
+        Ok(())
+    }
+
+    async fn is_in_mempool(&self, transaction_id: TransactionId) -> bool {
+        self.client().get_mempool_entry(transaction_id, false, false).await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(connected: bool, synced: bool, virtual_daa_score: u64) -> UpstreamHealth {
+        UpstreamHealth { connected, synced, virtual_daa_score }
+    }
+
+    #[test]
+    fn test_select_upstream() {
+        // Keeps the current upstream while it is healthy, even if another one is slightly ahead
+        let upstreams = vec![health(true, true, 1_000), health(true, true, 1_010)];
+        assert_eq!(select_upstream(&upstreams, 0), Some(0));
+        assert_eq!(select_upstream(&upstreams, 1), Some(1));
+
+        // Fails over when the current upstream is unsynced, disconnected or lagging behind
+        let upstreams = vec![health(true, false, 1_000), health(true, true, 1_000)];
+        assert_eq!(select_upstream(&upstreams, 0), Some(1));
+        let upstreams = vec![health(false, true, 1_000), health(true, true, 1_000)];
+        assert_eq!(select_upstream(&upstreams, 0), Some(1));
+        let upstreams = vec![health(true, true, 1_000), health(true, true, 1_000 + UPSTREAM_MAX_DAA_LAG + 1)];
+        assert_eq!(select_upstream(&upstreams, 0), Some(1));
+
+        // Prefers configuration order among the eligible upstreams
+        let upstreams = vec![health(true, true, 1_000), health(true, false, 0), health(true, true, 1_000)];
+        assert_eq!(select_upstream(&upstreams, 1), Some(0));
+
+        // No eligible upstream
+        let upstreams = vec![health(true, false, 1_000), health(false, false, 0)];
+        assert_eq!(select_upstream(&upstreams, 0), None);
+    }
 }
@@ -3,11 +3,13 @@ pub mod client_handler;
 pub mod default_client;
 pub mod errors;
 pub mod hasher;
+pub mod history;
 pub mod jsonrpc_event;
 pub mod kaspaapi;
 pub mod log_colors;
 pub mod mining_state;
 pub mod net_utils;
+pub mod pool;
 pub mod pow_diagnostic;
 pub mod prom;
 #[cfg(feature = "rkstratum_cpu_miner")]
@@ -16,6 +18,11 @@ pub mod share_handler;
 pub mod stratum_context;
 pub mod stratum_listener;
 pub mod stratum_server;
+pub mod stratum_tls;
+pub mod sv2_messages;
+pub mod sv2_noise;
+pub mod sv2_server;
+pub mod time_utils;
 
 pub use app_config::{BridgeConfig, InstanceConfig};
 pub use client_handler::*;
@@ -25,6 +32,7 @@ pub use hasher::*;
 pub use jsonrpc_event::*;
 pub use kaspaapi::*;
 pub use mining_state::*;
+pub use pool::*;
 pub use prom::{WorkerContext, *};
 #[cfg(feature = "rkstratum_cpu_miner")]
 pub use rkstratum_cpu_miner::*;
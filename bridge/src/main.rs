@@ -2,7 +2,9 @@ use clap::Parser;
 use futures_util::future::try_join_all;
 use kaspa_alloc::init_allocator_with_default_settings;
 use kaspa_stratum_bridge::log_colors::LogColors;
-use kaspa_stratum_bridge::{KaspaApi, StratumServerBridgeConfig as StratumBridgeConfig, listen_and_serve_with_shutdown, prom};
+use kaspa_stratum_bridge::{
+    KaspaApi, PayoutApi, PoolMode, StratumServerBridgeConfig as StratumBridgeConfig, history, listen_and_serve_with_shutdown, prom,
+};
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 #[cfg(windows)]
@@ -152,6 +154,9 @@ fn log_bridge_configuration(config: &BridgeConfig) {
     tracing::info!("----------------------------------");
     tracing::info!("initializing bridge ({} instance{})", instance_count, if instance_count > 1 { "s" } else { "" });
     tracing::info!("\tkaspad:          {} (shared)", config.global.kaspad_address);
+    for address in &config.global.kaspad_failover_addresses {
+        tracing::info!("\tkaspad failover: {}", address);
+    }
     tracing::info!("\tblock wait:      {:?}", config.global.block_wait_time);
     tracing::info!("\tprint stats:     {}", config.global.print_stats);
     tracing::info!("\tvar diff:        {}", config.global.var_diff);
@@ -160,10 +165,22 @@ fn log_bridge_configuration(config: &BridgeConfig) {
     tracing::info!("\tpow2 clamp:      {}", config.global.pow2_clamp);
     tracing::info!("\textranonce:      auto-detected per client");
     tracing::info!("\thealth check:    {}", config.global.health_check_port);
+    if let Some(ref pool) = config.global.pool {
+        tracing::info!("\tpool address:    {}", pool.address);
+        tracing::info!("\tpool fee:        {}%", pool.fee_percent);
+        tracing::info!("\tpplns window:    {} shares", pool.pplns_window);
+        tracing::info!("\tpayout interval: {:?}", pool.payout_interval);
+    }
 
     for (idx, instance) in config.instances.iter().enumerate() {
         tracing::info!("\t--- Instance {} ---", idx + 1);
         tracing::info!("\t  stratum:       {}", instance.stratum_port);
+        if let Some(ref tls) = instance.tls {
+            tracing::info!("\t  stratum (TLS): {}", tls.port);
+        }
+        if let Some(ref sv2) = instance.sv2 {
+            tracing::info!("\t  stratum (V2):  {}", sv2.port);
+        }
         tracing::info!("\t  min diff:      {}", instance.min_share_diff);
         if let Some(ref prom_port) = instance.prom_port {
             tracing::info!("\t  prom:          {}", prom_port);
@@ -280,10 +297,9 @@ async fn main() -> Result<(), anyhow::Error> {
     }
 
     // Create shared kaspa API client (all instances use the same node)
-    let kaspa_api =
-        KaspaApi::new(config.global.kaspad_address.clone(), config.global.coinbase_tag_suffix.clone(), shutdown_rx.clone())
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to create Kaspa API client: {}", e))?;
+    let kaspa_api = KaspaApi::new(config.global.upstream_addresses(), config.global.coinbase_tag_suffix.clone(), shutdown_rx.clone())
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create Kaspa API client: {}", e))?;
 
     if !config.global.web_dashboard_port.is_empty() {
         let web_dashboard_port = config.global.web_dashboard_port.clone();
@@ -301,6 +317,24 @@ async fn main() -> Result<(), anyhow::Error> {
         .map_err(|e| anyhow::anyhow!("Failed while waiting for node sync: {}", e))?;
     tracing::info!("Node is synced, starting stratum listeners");
 
+    // Optional: persistent share, block and worker history (shared across all instances)
+    if let Some(history_config) = &config.global.history {
+        let history = history::start_history(history_config, shutdown_rx.clone())?;
+        prom::set_history(history);
+    }
+
+    // Optional: PPLNS pool mode (shared ledger across all instances)
+    let pool = match config.global.pool.clone() {
+        Some(pool_config) => {
+            let pool = PoolMode::new(pool_config)?;
+            prom::set_web_pool(Arc::clone(&pool));
+            pool.start_ledger_thread(shutdown_rx.clone());
+            pool.start_payout_thread(Arc::clone(&kaspa_api) as Arc<dyn PayoutApi>, shutdown_rx.clone());
+            Some(pool)
+        }
+        None => None,
+    };
+
     // Optional: internal CPU miner (feature-gated)
     #[cfg(feature = "rkstratum_cpu_miner")]
     #[cfg(feature = "rkstratum_cpu_miner")]
@@ -390,6 +424,7 @@ async fn main() -> Result<(), anyhow::Error> {
         let global = config.global.clone();
         let kaspa_api_clone = Arc::clone(&kaspa_api);
         let instance_shutdown_rx = shutdown_rx.clone();
+        let instance_pool = pool.clone();
 
         let is_first_instance = idx == 0;
 
@@ -428,6 +463,9 @@ async fn main() -> Result<(), anyhow::Error> {
                 extranonce_size: instance.extranonce_size.unwrap_or(global.extranonce_size),
                 pow2_clamp: instance.pow2_clamp.unwrap_or(global.pow2_clamp),
                 coinbase_tag_suffix: global.coinbase_tag_suffix.clone(),
+                pool: instance_pool,
+                tls: instance.tls.clone(),
+                sv2: instance.sv2.clone(),
             };
 
             listen_and_serve_with_shutdown(
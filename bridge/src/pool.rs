@@ -0,0 +1,963 @@
+//! PPLNS pool mode.
+//!
+//! When enabled, every worker mines to the single pool address and each valid share is recorded along with
+//! its difficulty in an append-only ledger file. Once a found block is merged blue by a chain block, the reward
+//! paid for it is split between the miners of the last N shares (PPLNS) proportionally to their share difficulty,
+//! minus the pool fee. Miner balances are then periodically paid out from the pool address.
+//!
+//! A payout is recorded as pending, along with its signed transactions, before being submitted, and pending
+//! payouts are reconciled against the node on startup. The ledger is compacted into a balance snapshot followed
+//! by the shares of the PPLNS window once the shares it holds exceed the window.
+//!
+//! Records are applied to the in-memory state right away, then queued and written in batches by the ledger task,
+//! so share accounting never waits on the disk. Compaction is done by the same task outside of the state lock, and
+//! a pending payout is only submitted once it has been written out.
+
+use crate::app_config::PoolConfig;
+use crate::log_colors::LogColors;
+use crate::prom::{record_pool_balances, record_pool_block_credited, record_pool_payout};
+use crate::share_handler::{BLOCK_CONFIRM_MAX_ATTEMPTS, BLOCK_CONFIRM_RETRY_DELAY, KaspaApiTrait};
+use crate::time_utils::unix_now;
+use anyhow::{Context, Result};
+use borsh::BorshDeserialize;
+use kaspa_addresses::Address;
+use kaspa_consensus_core::config::params::Params;
+use kaspa_consensus_core::network::NetworkId;
+use kaspa_consensus_core::tx::{Transaction, TransactionId, TransactionOutpoint};
+use kaspa_rpc_core::RpcUtxosByAddressesEntry;
+use kaspa_wallet_core::tx::{Fees, Generator, GeneratorSettings, PaymentOutput, PaymentOutputs};
+use kaspa_wallet_core::utxo::UtxoEntryReference;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{Notify, watch};
+use tracing::{debug, info, warn};
+
+/// Maximum number of payouts (one output each) sent in a single payout round
+const MAX_PAYOUTS_PER_ROUND: usize = 50;
+
+/// Number of recent blocks and payouts kept in memory for the dashboard
+const RECENT_HISTORY_LEN: usize = 100;
+
+/// The ledger is compacted once it holds this many times the records of the PPLNS window
+const LEDGER_COMPACTION_FACTOR: usize = 2;
+
+/// A single line of the pool ledger file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum LedgerRecord {
+    Share { address: String, worker: String, difficulty: f64, timestamp: u64 },
+    Block { hash: String, reward: u64, fee: u64, credits: Vec<(String, u64)>, timestamp: u64 },
+    PayoutPending(PendingPayout),
+    PayoutSent { tx_id: String, timestamp: u64 },
+    PayoutCancelled { tx_id: String, timestamp: u64 },
+    Snapshot(LedgerSnapshot),
+}
+
+/// Node calls made by the payout rounds, each call going to the upstream active at the time
+#[async_trait::async_trait]
+pub trait PayoutApi: Send + Sync {
+    /// Network of the node along with its virtual DAA score
+    async fn get_network_info(&self) -> Result<(NetworkId, u64)>;
+
+    async fn get_utxos_by_address(&self, address: &Address) -> Result<Vec<RpcUtxosByAddressesEntry>>;
+
+    async fn submit_transaction(&self, transaction: &Transaction) -> Result<()>;
+
+    async fn is_in_mempool(&self, transaction_id: TransactionId) -> bool;
+}
+
+/// Payout round whose transactions may not have reached the node yet, the paid amounts being
+/// deducted from the balances until it is either sent or cancelled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingPayout {
+    tx_id: String,
+    payouts: Vec<(String, u64)>,
+    /// Hex encoded signed transactions, in submission order, the last one paying the miners
+    transactions: Vec<String>,
+    timestamp: u64,
+}
+
+impl PendingPayout {
+    fn new(payouts: Vec<(String, u64)>, transactions: &[Transaction]) -> Result<Self> {
+        let tx_id = transactions.last().context("the payout has no transaction")?.id().to_string();
+        let transactions = transactions.iter().map(|tx| borsh::to_vec(tx).map(hex::encode)).collect::<std::io::Result<_>>()?;
+        Ok(Self { tx_id, payouts, transactions, timestamp: unix_now() })
+    }
+
+    fn transactions(&self) -> Result<Vec<Transaction>> {
+        self.transactions
+            .iter()
+            .map(|tx| Ok(Transaction::try_from_slice(&hex::decode(tx)?)?))
+            .collect::<Result<_>>()
+            .with_context(|| format!("decoding the transactions of pending payout {}", self.tx_id))
+    }
+}
+
+/// State of the ledger replacing all the records preceding it, but the shares of the PPLNS window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LedgerSnapshot {
+    balances: Vec<(String, u64)>,
+    paid: Vec<(String, u64)>,
+    total_fees: u64,
+    blocks: Vec<PoolBlock>,
+    payouts: Vec<PoolPayout>,
+    pending: Vec<PendingPayout>,
+    timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolBlock {
+    pub hash: String,
+    pub reward: u64,
+    pub fee: u64,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolPayout {
+    pub address: String,
+    pub amount: u64,
+    pub tx_id: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolBalance {
+    pub address: String,
+    pub balance: u64,
+    pub paid: u64,
+}
+
+/// Snapshot of the pool state exposed on the web dashboard
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolSummary {
+    pub address: String,
+    pub fee_percent: f64,
+    pub pplns_window: usize,
+    pub window_shares: usize,
+    pub total_fees: u64,
+    pub balances: Vec<PoolBalance>,
+    pub blocks: Vec<PoolBlock>,
+    pub payouts: Vec<PoolPayout>,
+}
+
+/// Share difficulty per miner address over the PPLNS window at the time a block was found
+#[derive(Debug, Clone, Default)]
+pub struct PplnsWindow {
+    pub weights: HashMap<String, f64>,
+}
+
+/// Splits `reward` between the addresses of the window proportionally to their weight, after deducting
+/// the pool fee. Returns the credits sorted by address along with the amount kept by the pool, which
+/// includes the rounding dust and the whole reward if the window is empty.
+pub fn compute_pplns_rewards(window: &PplnsWindow, reward: u64, fee_percent: f64) -> (Vec<(String, u64)>, u64) {
+    let total_weight: f64 = window.weights.values().filter(|w| **w > 0.0).sum();
+    if total_weight <= 0.0 {
+        return (vec![], reward);
+    }
+    let fee = ((reward as f64) * fee_percent.clamp(0.0, 100.0) / 100.0) as u64;
+    let distributable = reward - fee;
+    let mut credits: Vec<(String, u64)> = window
+        .weights
+        .iter()
+        .filter(|(_, w)| **w > 0.0)
+        .map(|(address, w)| (address.clone(), ((distributable as f64) * (w / total_weight)) as u64))
+        .filter(|(_, amount)| *amount > 0)
+        .collect();
+    credits.sort();
+    let credited: u64 = credits.iter().map(|(_, amount)| amount).sum();
+    (credits, reward - credited.min(reward))
+}
+
+#[derive(Clone)]
+struct WindowShare {
+    address: String,
+    worker: String,
+    difficulty: f64,
+    timestamp: u64,
+}
+
+#[derive(Default)]
+struct LedgerState {
+    window: VecDeque<WindowShare>,
+    balances: HashMap<String, u64>,
+    paid: HashMap<String, u64>,
+    total_fees: u64,
+    blocks: VecDeque<PoolBlock>,
+    payouts: VecDeque<PoolPayout>,
+    pending: Vec<PendingPayout>,
+    /// Number of records held by the ledger file
+    records: usize,
+}
+
+impl LedgerState {
+    fn apply(&mut self, record: LedgerRecord, window_size: usize) {
+        self.records += 1;
+        match record {
+            LedgerRecord::Share { address, worker, difficulty, timestamp } => {
+                self.window.push_back(WindowShare { address, worker, difficulty, timestamp });
+                while self.window.len() > window_size {
+                    self.window.pop_front();
+                }
+            }
+            LedgerRecord::Block { hash, reward, fee, credits, timestamp } => {
+                for (address, amount) in credits {
+                    *self.balances.entry(address).or_default() += amount;
+                }
+                self.total_fees += fee;
+                push_recent(&mut self.blocks, PoolBlock { hash, reward, fee, timestamp });
+            }
+            LedgerRecord::PayoutPending(pending) => {
+                for (address, amount) in pending.payouts.iter() {
+                    let balance = self.balances.entry(address.clone()).or_default();
+                    *balance = balance.saturating_sub(*amount);
+                }
+                self.pending.push(pending);
+            }
+            LedgerRecord::PayoutSent { tx_id, timestamp } => {
+                if let Some(pending) = self.take_pending(&tx_id) {
+                    for (address, amount) in pending.payouts {
+                        *self.paid.entry(address.clone()).or_default() += amount;
+                        push_recent(&mut self.payouts, PoolPayout { address, amount, tx_id: tx_id.clone(), timestamp });
+                    }
+                }
+            }
+            LedgerRecord::PayoutCancelled { tx_id, .. } => {
+                if let Some(pending) = self.take_pending(&tx_id) {
+                    for (address, amount) in pending.payouts {
+                        *self.balances.entry(address).or_default() += amount;
+                    }
+                }
+            }
+            LedgerRecord::Snapshot(snapshot) => {
+                *self = Self {
+                    balances: snapshot.balances.into_iter().collect(),
+                    paid: snapshot.paid.into_iter().collect(),
+                    total_fees: snapshot.total_fees,
+                    blocks: snapshot.blocks.into(),
+                    payouts: snapshot.payouts.into(),
+                    pending: snapshot.pending,
+                    records: self.records,
+                    ..Default::default()
+                };
+            }
+        }
+    }
+
+    fn take_pending(&mut self, tx_id: &str) -> Option<PendingPayout> {
+        let index = self.pending.iter().position(|pending| pending.tx_id == tx_id)?;
+        Some(self.pending.remove(index))
+    }
+
+    fn snapshot(&self) -> LedgerSnapshot {
+        let sorted = |map: &HashMap<String, u64>| {
+            let mut entries: Vec<_> = map.iter().map(|(a, b)| (a.clone(), *b)).collect();
+            entries.sort();
+            entries
+        };
+        LedgerSnapshot {
+            balances: sorted(&self.balances),
+            paid: sorted(&self.paid),
+            total_fees: self.total_fees,
+            blocks: self.blocks.iter().cloned().collect(),
+            payouts: self.payouts.iter().cloned().collect(),
+            pending: self.pending.clone(),
+            timestamp: unix_now(),
+        }
+    }
+}
+
+fn record_line(record: &LedgerRecord) -> Result<String> {
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    Ok(line)
+}
+
+fn push_recent<T>(history: &mut VecDeque<T>, item: T) {
+    history.push_back(item);
+    while history.len() > RECENT_HISTORY_LEN {
+        history.pop_front();
+    }
+}
+
+/// Persistent PPLNS ledger backed by an append-only JSON lines file
+pub struct PoolLedger {
+    path: PathBuf,
+    window_size: usize,
+    state: Mutex<LedgerState>,
+    file: Mutex<File>,
+    queued: Mutex<String>, // Lines of the records applied but not written yet, in order
+    queued_notify: Notify,
+}
+
+impl PoolLedger {
+    /// Opens the ledger at `path`, creating it if missing, and replays its records
+    pub fn open(path: &Path, window_size: usize) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).with_context(|| format!("creating pool ledger directory {}", parent.display()))?;
+        }
+        let window_size = window_size.max(1);
+        let mut state = LedgerState::default();
+        if path.exists() {
+            let reader = BufReader::new(File::open(path).with_context(|| format!("opening pool ledger {}", path.display()))?);
+            for (idx, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<LedgerRecord>(&line) {
+                    Ok(record) => state.apply(record, window_size),
+                    // A partially written last line is expected after a crash
+                    Err(e) => warn!("Skipping malformed pool ledger line {} in {}: {}", idx + 1, path.display(), e),
+                }
+            }
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let ledger = Self {
+            path: path.to_path_buf(),
+            window_size,
+            state: Mutex::new(state),
+            file: Mutex::new(file),
+            queued: Mutex::new(String::new()),
+            queued_notify: Notify::new(),
+        };
+        ledger.flush()?;
+        Ok(ledger)
+    }
+
+    /// Applies a record to the state and queues it for the ledger task to write
+    fn append_and_apply(&self, record: LedgerRecord) -> Result<()> {
+        let line = record_line(&record)?;
+        let mut state = self.state.lock();
+        state.apply(record, self.window_size);
+        // Queued under the state lock so that the queue order matches the order the records were applied in
+        self.queued.lock().push_str(&line);
+        drop(state);
+        self.queued_notify.notify_one();
+        Ok(())
+    }
+
+    /// Writes out the queued records, or compacts the ledger instead once it holds too many records
+    pub fn flush(&self) -> Result<()> {
+        // Holding the file lock while taking the queue keeps concurrent flushes in order
+        let mut file = self.file.lock();
+        let (lines, compaction) = {
+            let mut state = self.state.lock();
+            let lines = std::mem::take(&mut *self.queued.lock());
+            // The snapshot already covers the queued records, so the state lock is only held to copy it
+            let compaction = (state.records > LEDGER_COMPACTION_FACTOR * self.window_size).then(|| {
+                state.records = 1 + state.window.len();
+                (state.snapshot(), state.window.iter().cloned().collect::<Vec<_>>())
+            });
+            (lines, compaction)
+        };
+        if let Some((snapshot, window)) = compaction {
+            match self.compact(&mut file, snapshot, window) {
+                Ok(()) => {
+                    debug!("{} compacted the ledger {}", LogColors::label("[POOL]"), self.path.display());
+                    return Ok(());
+                }
+                Err(e) => warn!("{} failed to compact the ledger {}: {}", LogColors::label("[POOL]"), self.path.display(), e),
+            }
+        }
+        if lines.is_empty() {
+            return Ok(());
+        }
+        if let Err(e) = file.write_all(lines.as_bytes()) {
+            // Written again along with the next batch
+            self.queued.lock().insert_str(0, &lines);
+            return Err(e).with_context(|| format!("writing to pool ledger {}", self.path.display()));
+        }
+        Ok(())
+    }
+
+    /// Rewrites the ledger as a snapshot of the state followed by the shares of the PPLNS window
+    fn compact(&self, file: &mut File, snapshot: LedgerSnapshot, window: Vec<WindowShare>) -> Result<()> {
+        let compacted_path = self.path.with_extension("compacting");
+        let compacted = File::create(&compacted_path)?;
+        let mut writer = BufWriter::new(&compacted);
+        writer.write_all(record_line(&LedgerRecord::Snapshot(snapshot))?.as_bytes())?;
+        for WindowShare { address, worker, difficulty, timestamp } in window {
+            writer.write_all(record_line(&LedgerRecord::Share { address, worker, difficulty, timestamp })?.as_bytes())?;
+        }
+        writer.flush()?;
+        drop(writer);
+        compacted.sync_all()?;
+
+        std::fs::rename(&compacted_path, &self.path)?;
+        // The handle is positioned at the end of the compacted ledger, so it keeps appending to it
+        *file = compacted;
+        Ok(())
+    }
+
+    pub fn record_share(&self, address: &str, worker: &str, difficulty: f64) -> Result<()> {
+        self.append_and_apply(LedgerRecord::Share {
+            address: address.to_string(),
+            worker: worker.to_string(),
+            difficulty,
+            timestamp: unix_now(),
+        })
+    }
+
+    /// Returns the current PPLNS window aggregated by miner address
+    pub fn window(&self) -> PplnsWindow {
+        let state = self.state.lock();
+        let mut weights = HashMap::new();
+        for share in state.window.iter() {
+            *weights.entry(share.address.clone()).or_default() += share.difficulty;
+        }
+        PplnsWindow { weights }
+    }
+
+    /// Credits the reward of a blue block to the miners of `window` and returns the credits
+    pub fn credit_block(&self, hash: &str, reward: u64, fee_percent: f64, window: &PplnsWindow) -> Result<Vec<(String, u64)>> {
+        let (credits, fee) = compute_pplns_rewards(window, reward, fee_percent);
+        self.append_and_apply(LedgerRecord::Block {
+            hash: hash.to_string(),
+            reward,
+            fee,
+            credits: credits.clone(),
+            timestamp: unix_now(),
+        })?;
+        Ok(credits)
+    }
+
+    fn record_pending_payout(&self, pending: PendingPayout) -> Result<()> {
+        self.append_and_apply(LedgerRecord::PayoutPending(pending))
+    }
+
+    /// Records a pending payout as sent, crediting the paid amounts
+    pub fn record_payout_sent(&self, tx_id: &str) -> Result<()> {
+        self.append_and_apply(LedgerRecord::PayoutSent { tx_id: tx_id.to_string(), timestamp: unix_now() })
+    }
+
+    /// Records a pending payout as never sent, restoring the balances
+    pub fn record_payout_cancelled(&self, tx_id: &str) -> Result<()> {
+        self.append_and_apply(LedgerRecord::PayoutCancelled { tx_id: tx_id.to_string(), timestamp: unix_now() })
+    }
+
+    fn pending_payouts(&self) -> Vec<PendingPayout> {
+        self.state.lock().pending.clone()
+    }
+
+    pub fn balance(&self, address: &str) -> u64 {
+        self.state.lock().balances.get(address).copied().unwrap_or_default()
+    }
+
+    pub fn balances(&self) -> Vec<(String, u64)> {
+        let mut balances: Vec<_> = self.state.lock().balances.iter().map(|(a, b)| (a.clone(), *b)).collect();
+        balances.sort();
+        balances
+    }
+
+    /// Returns the balances of at least `min_payout`, largest first
+    pub fn payable_balances(&self, min_payout: u64, limit: usize) -> Vec<(String, u64)> {
+        let mut payable: Vec<_> = self.balances().into_iter().filter(|(_, b)| *b >= min_payout.max(1)).collect();
+        payable.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        payable.truncate(limit);
+        payable
+    }
+
+    pub fn summary(&self, config: &PoolConfig) -> PoolSummary {
+        let state = self.state.lock();
+        let mut balances: Vec<_> = state
+            .balances
+            .keys()
+            .chain(state.paid.keys())
+            .map(|address| PoolBalance {
+                address: address.clone(),
+                balance: state.balances.get(address).copied().unwrap_or_default(),
+                paid: state.paid.get(address).copied().unwrap_or_default(),
+            })
+            .collect();
+        balances.sort_by(|a, b| a.address.cmp(&b.address));
+        balances.dedup_by(|a, b| a.address == b.address);
+        PoolSummary {
+            address: config.address.clone(),
+            fee_percent: config.fee_percent,
+            pplns_window: self.window_size,
+            window_shares: state.window.len(),
+            total_fees: state.total_fees,
+            balances,
+            blocks: state.blocks.iter().rev().cloned().collect(),
+            payouts: state.payouts.iter().rev().cloned().collect(),
+        }
+    }
+}
+
+/// Pool mode state shared by all the bridge instances
+pub struct PoolMode {
+    pub config: PoolConfig,
+    pub ledger: PoolLedger,
+}
+
+impl PoolMode {
+    pub fn new(config: PoolConfig) -> Result<Arc<Self>> {
+        Address::try_from(config.address.as_str()).map_err(|e| anyhow::anyhow!("invalid pool address {}: {}", config.address, e))?;
+        let ledger = PoolLedger::open(Path::new(&config.ledger_path), config.pplns_window)?;
+        let pool = Arc::new(Self { config, ledger });
+        record_pool_balances(&pool.ledger.balances());
+        Ok(pool)
+    }
+
+    /// Records a valid share submitted by the miner `address`
+    pub fn record_share(&self, address: &str, worker: &str, difficulty: f64) {
+        if let Err(e) = self.ledger.record_share(address, worker, difficulty) {
+            warn!("{} failed to record share of {}: {}", LogColors::label("[POOL]"), address, e);
+        }
+    }
+
+    /// Credits a found block confirmed blue with the reward actually paid to the pool address by the chain block
+    /// merging it. A coinbase pays the blues of the block mergeset, so the found block own outputs are unrelated.
+    pub async fn credit_merged_block(&self, kaspa_api: &dyn KaspaApiTrait, hash: &str, window: &PplnsWindow) {
+        for _ in 0..BLOCK_CONFIRM_MAX_ATTEMPTS {
+            match kaspa_api.get_block_reward(hash).await {
+                Ok(Some(reward)) => return self.credit_block(hash, reward, window),
+                Ok(None) => {}
+                Err(e) => debug!("{} failed to query the reward of block {}: {}", LogColors::label("[POOL]"), hash, e),
+            }
+            tokio::time::sleep(BLOCK_CONFIRM_RETRY_DELAY).await;
+        }
+        warn!("{} block {} was not merged by a chain block, its reward is not credited", LogColors::label("[POOL]"), hash);
+    }
+
+    /// Credits the reward of a found block once it has been merged by a chain block
+    pub fn credit_block(&self, hash: &str, reward: u64, window: &PplnsWindow) {
+        match self.ledger.credit_block(hash, reward, self.config.fee_percent, window) {
+            Ok(credits) => {
+                info!(
+                    "{} credited block {} reward of {} sompi to {} miner(s)",
+                    LogColors::label("[POOL]"),
+                    hash,
+                    reward,
+                    credits.len()
+                );
+                record_pool_block_credited(reward);
+                record_pool_balances(&self.ledger.balances());
+            }
+            Err(e) => warn!("{} failed to credit block {}: {}", LogColors::label("[POOL]"), hash, e),
+        }
+    }
+
+    pub fn summary(&self) -> PoolSummary {
+        self.ledger.summary(&self.config)
+    }
+
+    /// Writes out the queued ledger records on the blocking thread pool
+    async fn flush_ledger(self: &Arc<Self>) -> Result<()> {
+        let pool = Arc::clone(self);
+        tokio::task::spawn_blocking(move || pool.ledger.flush()).await?
+    }
+
+    /// Writes the queued ledger records in batches until shutdown
+    pub fn start_ledger_thread(self: &Arc<Self>, mut shutdown_rx: watch::Receiver<bool>) {
+        let pool = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.wait_for(|v| *v) => break,
+                    // Records queued while a batch is being written go out together with the next one
+                    _ = pool.ledger.queued_notify.notified() => {
+                        if let Err(e) = pool.flush_ledger().await {
+                            warn!("{} failed to write the ledger records: {}", LogColors::label("[POOL]"), e);
+                        }
+                    }
+                }
+            }
+            if let Err(e) = pool.flush_ledger().await {
+                warn!("{} failed to write the ledger records: {}", LogColors::label("[POOL]"), e);
+            }
+            debug!("pool ledger thread stopped");
+        });
+    }
+
+    /// Pays the balances above the payout threshold from the pool address in a single transaction
+    ///
+    /// No payout is made while a pending payout cannot be reconciled, since it may still spend the pool UTXOs.
+    pub async fn run_payouts(self: &Arc<Self>, rpc: &dyn PayoutApi, private_key: &[u8; 32]) -> Result<()> {
+        if !self.reconcile_pending_payouts(rpc).await? {
+            return Ok(());
+        }
+        let payable = self.ledger.payable_balances(self.config.min_payout, MAX_PAYOUTS_PER_ROUND);
+        if payable.is_empty() {
+            return Ok(());
+        }
+
+        let pool_address = Address::try_from(self.config.address.as_str())?;
+        let (network_id, virtual_daa_score) = rpc.get_network_info().await?;
+        let coinbase_maturity = Params::from(network_id).coinbase_maturity();
+
+        // Coinbase outputs cannot be spent before reaching maturity
+        let utxos = rpc.get_utxos_by_address(&pool_address).await?;
+        let utxos: Vec<UtxoEntryReference> = utxos
+            .into_iter()
+            .filter(|entry| !entry.utxo_entry.is_coinbase || entry.utxo_entry.block_daa_score + coinbase_maturity <= virtual_daa_score)
+            .map(UtxoEntryReference::from)
+            .collect();
+
+        let outputs = payable
+            .iter()
+            .map(|(address, amount)| {
+                Address::try_from(address.as_str())
+                    .map(|address| PaymentOutput::new(address, *amount))
+                    .map_err(|e| anyhow::anyhow!("invalid miner address {}: {}", address, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The pool pays the network fees so miners receive their full balance
+        let settings = GeneratorSettings::try_new_with_iterator(
+            network_id,
+            Box::new(utxos.into_iter()),
+            None,
+            pool_address,
+            1,
+            1,
+            PaymentOutputs { outputs }.into(),
+            None,
+            Fees::SenderPays(0),
+            None,
+            None,
+        )?;
+        let generator = Generator::try_new(settings, None, None)?;
+
+        let mut transactions = vec![];
+        for transaction in generator.iter() {
+            let transaction = transaction?;
+            transaction.try_sign_with_keys(&[*private_key], Some(true))?;
+            transactions.push(transaction.transaction());
+        }
+
+        // The payout is recorded before submitting so that a crash cannot lead to paying the same balances twice
+        let pending = PendingPayout::new(payable.clone(), &transactions)?;
+        let tx_id = pending.tx_id.clone();
+        self.ledger.record_pending_payout(pending)?;
+        self.flush_ledger().await?;
+        record_pool_balances(&self.ledger.balances());
+        for transaction in transactions.iter() {
+            rpc.submit_transaction(transaction).await?;
+        }
+
+        self.ledger.record_payout_sent(&tx_id)?;
+        for (address, amount) in payable.iter() {
+            record_pool_payout(address, *amount);
+        }
+        info!("{} paid {} miner(s) in transaction {}", LogColors::label("[POOL]"), payable.len(), tx_id);
+        Ok(())
+    }
+
+    /// Settles the pending payouts depending on whether their transactions reached the node, and returns
+    /// whether all of them could be settled
+    pub async fn reconcile_pending_payouts(&self, rpc: &dyn PayoutApi) -> Result<bool> {
+        let pending_payouts = self.ledger.pending_payouts();
+        if pending_payouts.is_empty() {
+            return Ok(true);
+        }
+
+        let pool_address = Address::try_from(self.config.address.as_str())?;
+        let mut settled = true;
+        for pending in pending_payouts {
+            match pending_payout_sent(rpc, &pool_address, &pending).await? {
+                Some(true) => {
+                    self.ledger.record_payout_sent(&pending.tx_id)?;
+                    for (address, amount) in pending.payouts.iter() {
+                        record_pool_payout(address, *amount);
+                    }
+                    info!("{} pending payout {} was sent", LogColors::label("[POOL]"), pending.tx_id);
+                }
+                Some(false) => {
+                    self.ledger.record_payout_cancelled(&pending.tx_id)?;
+                    info!("{} pending payout {} was not sent, its balances are restored", LogColors::label("[POOL]"), pending.tx_id);
+                }
+                None => {
+                    settled = false;
+                    warn!(
+                        "{} pending payout {} is only partially spent, payouts are suspended until it settles",
+                        LogColors::label("[POOL]"),
+                        pending.tx_id
+                    );
+                }
+            }
+        }
+        record_pool_balances(&self.ledger.balances());
+        Ok(settled)
+    }
+
+    /// Runs the payout rounds on the configured schedule until shutdown
+    pub fn start_payout_thread(self: &Arc<Self>, rpc: Arc<dyn PayoutApi>, shutdown_rx: watch::Receiver<bool>) {
+        let private_key = match std::env::var(&self.config.payout_private_key_env).ok().and_then(|hex_key| parse_private_key(&hex_key))
+        {
+            Some(private_key) => private_key,
+            None => {
+                warn!(
+                    "{} {} is not set to a hex encoded private key, pool payouts are disabled",
+                    LogColors::label("[POOL]"),
+                    self.config.payout_private_key_env
+                );
+                return;
+            }
+        };
+        self.spawn_payout_rounds(private_key, rpc, shutdown_rx);
+    }
+
+    fn spawn_payout_rounds(self: &Arc<Self>, private_key: [u8; 32], rpc: Arc<dyn PayoutApi>, mut shutdown_rx: watch::Receiver<bool>) {
+        let pool = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(pool.config.payout_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            // The first tick completes immediately, skip it to let the node connection settle
+            interval.tick().await;
+            if let Err(e) = pool.reconcile_pending_payouts(rpc.as_ref()).await {
+                warn!("{} pending payouts reconciliation failed: {}", LogColors::label("[POOL]"), e);
+            }
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.wait_for(|v| *v) => break,
+                    _ = interval.tick() => {
+                        if let Err(e) = pool.run_payouts(rpc.as_ref(), &private_key).await {
+                            warn!("{} payout round failed: {}", LogColors::label("[POOL]"), e);
+                        }
+                    }
+                }
+            }
+            debug!("pool payout thread stopped");
+        });
+    }
+}
+
+/// Resubmits the transactions of a pending payout, then tells whether the payout was sent, `None` meaning unknown.
+///
+/// The pool key only signs payouts, so the pool UTXOs spent by the payout transactions cannot be spent otherwise.
+async fn pending_payout_sent(rpc: &dyn PayoutApi, pool_address: &Address, pending: &PendingPayout) -> Result<Option<bool>> {
+    let transactions = pending.transactions()?;
+    let Some(payout_transaction) = transactions.last() else {
+        return Ok(Some(false));
+    };
+
+    // The bridge may have stopped while submitting, the transactions already known by the node being rejected
+    for transaction in transactions.iter() {
+        if let Err(e) = rpc.submit_transaction(transaction).await {
+            debug!("{} resubmitting payout transaction {} failed: {}", LogColors::label("[POOL]"), transaction.id(), e);
+        }
+    }
+    if rpc.is_in_mempool(payout_transaction.id()).await {
+        return Ok(Some(true));
+    }
+
+    let unspent: HashSet<TransactionOutpoint> =
+        rpc.get_utxos_by_address(pool_address).await?.into_iter().map(|entry| entry.outpoint.into()).collect();
+    if payout_transaction.inputs.iter().any(|input| unspent.contains(&input.previous_outpoint)) {
+        return Ok(Some(false));
+    }
+    let batch_ids: HashSet<_> = transactions.iter().map(|tx| tx.id()).collect();
+    let all_spent = transactions
+        .iter()
+        .flat_map(|tx| tx.inputs.iter())
+        .filter(|input| !batch_ids.contains(&input.previous_outpoint.transaction_id))
+        .all(|input| !unspent.contains(&input.previous_outpoint));
+    Ok(all_spent.then_some(true))
+}
+
+fn parse_private_key(hex_key: &str) -> Option<[u8; 32]> {
+    let mut private_key = [0u8; 32];
+    hex::decode_to_slice(hex_key.trim(), &mut private_key).ok()?;
+    Some(private_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(weights: &[(&str, f64)]) -> PplnsWindow {
+        PplnsWindow { weights: weights.iter().map(|(a, w)| (a.to_string(), *w)).collect() }
+    }
+
+    fn pending_payout(tx_id: &str, payouts: &[(&str, u64)]) -> PendingPayout {
+        PendingPayout {
+            tx_id: tx_id.to_string(),
+            payouts: payouts.iter().map(|(a, amount)| (a.to_string(), *amount)).collect(),
+            transactions: vec![],
+            timestamp: unix_now(),
+        }
+    }
+
+    fn temp_ledger_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("ks_bridge_{}_{}_{}.jsonl", name, std::process::id(), unix_now()))
+    }
+
+    #[test]
+    fn test_compute_pplns_rewards() {
+        let (credits, fee) = compute_pplns_rewards(&window(&[("a", 3.0), ("b", 1.0)]), 1_000_000, 1.0);
+        assert_eq!(credits, vec![("a".to_string(), 742_500), ("b".to_string(), 247_500)]);
+        assert_eq!(fee, 10_000);
+
+        // Rounding dust goes to the pool
+        let (credits, fee) = compute_pplns_rewards(&window(&[("a", 1.0), ("b", 1.0), ("c", 1.0)]), 100, 0.0);
+        assert_eq!(credits.iter().map(|(_, amount)| amount).sum::<u64>() + fee, 100);
+        assert_eq!(fee, 1);
+
+        // An empty window leaves the whole reward to the pool
+        let (credits, fee) = compute_pplns_rewards(&PplnsWindow::default(), 100, 1.0);
+        assert!(credits.is_empty());
+        assert_eq!(fee, 100);
+    }
+
+    #[test]
+    fn test_ledger_replay() {
+        let path = temp_ledger_path("replay");
+        {
+            let ledger = PoolLedger::open(&path, 2).unwrap();
+            ledger.record_share("a", "rig1", 1.0).unwrap();
+            ledger.record_share("b", "rig2", 2.0).unwrap();
+            ledger.record_share("b", "rig2", 2.0).unwrap();
+
+            // Only the last 2 shares are part of the window
+            let window = ledger.window();
+            assert_eq!(window.weights.get("a"), None);
+            assert_eq!(window.weights.get("b"), Some(&4.0));
+
+            ledger.credit_block("hash", 1_000, 0.0, &window).unwrap();
+            ledger.record_pending_payout(pending_payout("txid", &[("b", 400)])).unwrap();
+            ledger.record_payout_sent("txid").unwrap();
+            ledger.flush().unwrap();
+        }
+
+        let ledger = PoolLedger::open(&path, 2).unwrap();
+        assert_eq!(ledger.balance("b"), 600);
+        assert_eq!(ledger.payable_balances(500, 10), vec![("b".to_string(), 600)]);
+        assert!(ledger.payable_balances(601, 10).is_empty());
+        assert_eq!(ledger.window().weights.get("b"), Some(&4.0));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_ledger_pending_payouts() {
+        let path = temp_ledger_path("pending");
+        {
+            let ledger = PoolLedger::open(&path, 10).unwrap();
+            ledger.record_share("a", "rig1", 1.0).unwrap();
+            ledger.credit_block("hash", 1_000, 0.0, &ledger.window()).unwrap();
+
+            // Pending amounts are not payable anymore
+            ledger.record_pending_payout(pending_payout("tx1", &[("a", 600)])).unwrap();
+            assert_eq!(ledger.balance("a"), 400);
+            ledger.record_payout_cancelled("tx1").unwrap();
+            assert_eq!(ledger.balance("a"), 1_000);
+
+            ledger.record_pending_payout(pending_payout("tx2", &[("a", 700)])).unwrap();
+            ledger.flush().unwrap();
+        }
+
+        // A payout left pending by a restart is reconciled later
+        let ledger = PoolLedger::open(&path, 10).unwrap();
+        assert_eq!(ledger.balance("a"), 300);
+        assert_eq!(ledger.pending_payouts().iter().map(|p| p.tx_id.as_str()).collect::<Vec<_>>(), vec!["tx2"]);
+        ledger.record_payout_sent("tx2").unwrap();
+        assert!(ledger.pending_payouts().is_empty());
+        let summary = ledger.summary(&PoolConfig::default());
+        assert_eq!(summary.balances.iter().map(|b| (b.balance, b.paid)).collect::<Vec<_>>(), vec![(300, 700)]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_ledger_compaction() {
+        let path = temp_ledger_path("compaction");
+        {
+            let ledger = PoolLedger::open(&path, 2).unwrap();
+            ledger.record_share("a", "rig1", 1.0).unwrap();
+            ledger.credit_block("hash", 1_000, 0.0, &ledger.window()).unwrap();
+            ledger.record_pending_payout(pending_payout("tx1", &[("a", 100)])).unwrap();
+            for _ in 0..5 {
+                ledger.record_share("b", "rig2", 2.0).unwrap();
+            }
+
+            // Records are only written, and the ledger compacted, once flushed
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+            ledger.flush().unwrap();
+        }
+
+        // Only the snapshot and the shares of the window are kept
+        let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+        assert!(lines <= 1 + 2 + LEDGER_COMPACTION_FACTOR * 2, "the ledger was not compacted: {lines} lines");
+
+        let ledger = PoolLedger::open(&path, 2).unwrap();
+        assert_eq!(ledger.balance("a"), 900);
+        assert_eq!(ledger.window().weights.get("b"), Some(&4.0));
+        assert_eq!(ledger.summary(&PoolConfig::default()).blocks.len(), 1);
+        ledger.record_payout_sent("tx1").unwrap();
+        assert_eq!(ledger.summary(&PoolConfig::default()).payouts.len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    /// Node whose mempool accepts every submitted transaction
+    #[derive(Default)]
+    struct PayoutApiMock {
+        submitted: Mutex<Vec<TransactionId>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PayoutApi for PayoutApiMock {
+        async fn get_network_info(&self) -> Result<(NetworkId, u64)> {
+            Ok((NetworkId::new(kaspa_consensus_core::network::NetworkType::Mainnet), 0))
+        }
+
+        async fn get_utxos_by_address(&self, _address: &Address) -> Result<Vec<RpcUtxosByAddressesEntry>> {
+            Ok(vec![])
+        }
+
+        async fn submit_transaction(&self, transaction: &Transaction) -> Result<()> {
+            self.submitted.lock().push(transaction.id());
+            Ok(())
+        }
+
+        async fn is_in_mempool(&self, transaction_id: TransactionId) -> bool {
+            self.submitted.lock().contains(&transaction_id)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_payout_rounds_settle_pending_payouts() {
+        use kaspa_consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+        use std::time::Duration;
+
+        let path = temp_ledger_path("payout_rounds");
+        let config = PoolConfig {
+            address: "kaspa:qz0c9jqnyuxypxzjzfm2dhfgx8qdl6rcg3dq8ag5ywvz0wcqmvdq2ecql0f3g".to_string(),
+            ledger_path: path.to_string_lossy().to_string(),
+            payout_interval: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let pool = PoolMode::new(config).unwrap();
+        pool.ledger.record_share("a", "rig1", 1.0).unwrap();
+        pool.ledger.credit_block("hash", 1_000, 0.0, &pool.ledger.window()).unwrap();
+
+        // A payout left pending by a restart is resubmitted, then settled once the node knows it
+        let transaction = Transaction::new(0, vec![], vec![], 0, SUBNETWORK_ID_NATIVE, 0, vec![]);
+        let tx_id = transaction.id();
+        pool.ledger.record_pending_payout(PendingPayout::new(vec![("a".to_string(), 1_000)], &[transaction]).unwrap()).unwrap();
+        assert_eq!(pool.ledger.balance("a"), 0);
+
+        let rpc = Arc::new(PayoutApiMock::default());
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        pool.spawn_payout_rounds([1; 32], rpc.clone(), shutdown_rx);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !pool.ledger.pending_payouts().is_empty() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("the pending payout should be settled");
+
+        assert!(rpc.submitted.lock().contains(&tx_id));
+        let summary = pool.summary();
+        assert_eq!(summary.balances.iter().map(|b| (b.balance, b.paid)).collect::<Vec<_>>(), vec![(0, 1_000)]);
+        assert_eq!(summary.payouts.iter().map(|p| p.tx_id.clone()).collect::<Vec<_>>(), vec![tx_id.to_string()]);
+
+        shutdown_tx.send(true).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
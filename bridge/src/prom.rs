@@ -1,7 +1,5 @@
 use prometheus::proto::MetricFamily;
-#[cfg(feature = "rkstratum_cpu_miner")]
-use prometheus::{Counter, register_counter};
-use prometheus::{CounterVec, Gauge, GaugeVec, register_counter_vec, register_gauge, register_gauge_vec};
+use prometheus::{Counter, CounterVec, Gauge, GaugeVec, register_counter, register_counter_vec, register_gauge, register_gauge_vec};
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "rkstratum_cpu_miner")]
 use std::collections::VecDeque;
@@ -10,7 +8,9 @@ use std::sync::OnceLock;
 use std::time::{Duration, Instant};
 
 use crate::app_config::BridgeConfig;
+use crate::history::{BlockStatus, HistoryQuery, HistoryStore};
 use crate::net_utils::bind_addr_from_port;
+use crate::time_utils::unix_now;
 use std::path::PathBuf;
 
 /// Worker labels for Prometheus metrics
@@ -28,6 +28,12 @@ const ERROR_LABELS: &[&str] = &["instance", "wallet", "error"];
 /// Balance labels
 const BALANCE_LABELS: &[&str] = &["instance", "wallet"];
 
+/// Pool ledger labels (the pool is shared by all instances)
+const POOL_WALLET_LABELS: &[&str] = &["wallet"];
+
+/// Label names for upstream node metrics
+const UPSTREAM_LABELS: &[&str] = &["address"];
+
 /// Share counter - number of valid shares found by worker
 static SHARE_COUNTER: OnceLock<CounterVec> = OnceLock::new();
 
@@ -68,12 +74,22 @@ static NETWORK_DIFFICULTY: OnceLock<Gauge> = OnceLock::new();
 /// Network block count gauge
 static NETWORK_BLOCK_COUNT: OnceLock<Gauge> = OnceLock::new();
 
+/// Active upstream gauge (1 for the kaspad node job templates are fetched from, 0 for the others)
+static ACTIVE_UPSTREAM: OnceLock<GaugeVec> = OnceLock::new();
+
 /// Worker start time gauge (Unix timestamp in seconds)
 static WORKER_START_TIME: OnceLock<GaugeVec> = OnceLock::new();
 
 /// Worker current difficulty gauge (current mining difficulty assigned to worker)
 static WORKER_CURRENT_DIFFICULTY: OnceLock<GaugeVec> = OnceLock::new();
 
+/// Pool mode - unpaid PPLNS balance by miner wallet (KAS)
+static POOL_BALANCE_GAUGE: OnceLock<GaugeVec> = OnceLock::new();
+/// Pool mode - total paid out by miner wallet (KAS)
+static POOL_PAID_COUNTER: OnceLock<CounterVec> = OnceLock::new();
+/// Pool mode - total block rewards credited (KAS)
+static POOL_REWARDS_COUNTER: OnceLock<Counter> = OnceLock::new();
+
 /// Worker last activity time - tracks when each worker last submitted a share
 /// Key: "instance:worker:wallet", Value: Instant of last activity
 static WORKER_LAST_ACTIVITY: OnceLock<parking_lot::Mutex<HashMap<String, Instant>>> = OnceLock::new();
@@ -178,6 +194,25 @@ pub fn init_metrics() {
         register_gauge_vec!("ks_worker_current_difficulty", "Current mining difficulty assigned to worker", WORKER_LABELS).unwrap()
     });
 
+    ACTIVE_UPSTREAM.get_or_init(|| {
+        register_gauge_vec!("ks_active_upstream", "Whether the kaspad upstream is currently used for job templates", UPSTREAM_LABELS)
+            .unwrap()
+    });
+
+    POOL_BALANCE_GAUGE.get_or_init(|| {
+        register_gauge_vec!("ks_pool_balance_by_wallet_gauge", "Unpaid PPLNS pool balance by miner wallet (KAS)", POOL_WALLET_LABELS)
+            .unwrap()
+    });
+
+    POOL_PAID_COUNTER.get_or_init(|| {
+        register_counter_vec!("ks_pool_paid_by_wallet_counter", "Total pool payouts by miner wallet (KAS)", POOL_WALLET_LABELS)
+            .unwrap()
+    });
+
+    POOL_REWARDS_COUNTER.get_or_init(|| {
+        register_counter!("ks_pool_rewards_credited_counter", "Total block rewards credited to pool miners (KAS)").unwrap()
+    });
+
     // Internal CPU miner metrics (no labels; there is only one internal miner per process)
     #[cfg(feature = "rkstratum_cpu_miner")]
     {
@@ -411,6 +446,51 @@ async fn handle_http_request(
         return Ok(());
     }
 
+    if request.starts_with("GET /api/pool") {
+        let json = match WEB_POOL.get() {
+            Some(pool) => serde_json::to_string(&pool.summary()).unwrap_or_else(|_| "{}".to_string()),
+            None => "null".to_string(),
+        };
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
+            json.len(),
+            json
+        );
+        stream.write_all(response.as_bytes()).await?;
+        return Ok(());
+    }
+
+    if request.starts_with("GET /api/history") {
+        let (status, json) = match HISTORY.get() {
+            Some(history) => {
+                match HistoryQuery::from_path(path, unix_now()) {
+                    Ok(mut query) => {
+                        // An instance dashboard only sees the history of its own workers
+                        if let HttpMode::Instance { instance_id, .. } = mode {
+                            query.instance = Some(instance_id.clone());
+                        }
+                        // Queries read the day files from disk, off the async runtime
+                        let history = std::sync::Arc::clone(history);
+                        match tokio::task::spawn_blocking(move || history.query(&query)).await.unwrap_or_else(|e| Err(e.into())) {
+                            Ok(response) => ("200 OK", serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string())),
+                            Err(e) => ("500 Internal Server Error", serde_json::json!({ "error": e.to_string() }).to_string()),
+                        }
+                    }
+                    Err(e) => ("400 Bad Request", serde_json::json!({ "error": e }).to_string()),
+                }
+            }
+            None => ("200 OK", "null".to_string()),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            json.len(),
+            json
+        );
+        stream.write_all(response.as_bytes()).await?;
+        return Ok(());
+    }
+
     if matches!(mode, HttpMode::Instance { .. }) && request.starts_with("GET /api/config") {
         let config_json = get_config_json().await;
         let response = format!(
@@ -554,10 +634,13 @@ pub fn record_block_accepted_by_node(worker: &WorkerContext) {
     }
 }
 
-pub fn record_block_not_confirmed_blue(worker: &WorkerContext) {
+pub fn record_block_not_confirmed_blue(worker: &WorkerContext, nonce: u64, bluescore: u64, hash: &str) {
     if let Some(counter) = BLOCK_NOT_CONFIRMED_BLUE_COUNTER.get() {
         counter.with_label_values(&worker.labels()).inc();
     }
+    if let Some(history) = HISTORY.get() {
+        history.record_block(worker, hash, nonce, bluescore, BlockStatus::Red);
+    }
 }
 
 /// Record a valid share found
@@ -568,6 +651,9 @@ pub fn record_share_found(worker: &WorkerContext, share_diff: f64) {
     if let Some(counter) = SHARE_DIFF_COUNTER.get() {
         counter.with_label_values(&worker.labels()).inc_by(share_diff);
     }
+    if let Some(history) = HISTORY.get() {
+        history.record_share(worker, share_diff);
+    }
     // Update last activity time for this worker
     update_worker_activity(worker);
 }
@@ -579,6 +665,9 @@ pub fn record_stale_share(worker: &WorkerContext) {
         labels.push("stale");
         counter.with_label_values(&labels).inc();
     }
+    if let Some(history) = HISTORY.get() {
+        history.record_rejected(worker, "stale");
+    }
     // Update activity time - worker is still connected even if share is stale
     update_worker_activity(worker);
 }
@@ -590,6 +679,9 @@ pub fn record_dupe_share(worker: &WorkerContext) {
         labels.push("duplicate");
         counter.with_label_values(&labels).inc();
     }
+    if let Some(history) = HISTORY.get() {
+        history.record_rejected(worker, "duplicate");
+    }
     // Update activity time - worker is still connected even if share is duplicate
     update_worker_activity(worker);
 }
@@ -601,6 +693,9 @@ pub fn record_invalid_share(worker: &WorkerContext) {
         labels.push("invalid");
         counter.with_label_values(&labels).inc();
     }
+    if let Some(history) = HISTORY.get() {
+        history.record_rejected(worker, "invalid");
+    }
     // Update activity time - worker is still connected even if share is invalid
     update_worker_activity(worker);
 }
@@ -612,6 +707,9 @@ pub fn record_weak_share(worker: &WorkerContext) {
         labels.push("weak");
         counter.with_label_values(&labels).inc();
     }
+    if let Some(history) = HISTORY.get() {
+        history.record_rejected(worker, "weak");
+    }
     // Update activity time - worker is still connected even if share is weak
     update_worker_activity(worker);
 }
@@ -640,6 +738,9 @@ pub fn record_block_found(worker: &WorkerContext, nonce: u64, bluescore: u64, ha
         labels.push(&hash);
         gauge.with_label_values(&labels).set(1.0);
     }
+    if let Some(history) = HISTORY.get() {
+        history.record_block(worker, &hash, nonce, bluescore, BlockStatus::Blue);
+    }
 }
 
 /// Record a disconnect
@@ -674,6 +775,15 @@ pub fn record_network_stats(hashrate: u64, block_count: u64, difficulty: f64) {
     }
 }
 
+/// Record which of the configured upstream nodes is currently active
+pub fn record_active_upstream(active: &str, addresses: &[String]) {
+    if let Some(gauge) = ACTIVE_UPSTREAM.get() {
+        for address in addresses {
+            gauge.with_label_values(&[address]).set(if address == active { 1.0 } else { 0.0 });
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct WebStatusResponse {
     kaspad_address: String,
@@ -691,6 +801,18 @@ struct WebStatusConfig {
 static WEB_STATUS_CONFIG: OnceLock<parking_lot::RwLock<WebStatusConfig>> = OnceLock::new();
 static WEB_CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
 static WEB_CONFIG_WRITE_LOCK: OnceLock<parking_lot::Mutex<()>> = OnceLock::new();
+static WEB_POOL: OnceLock<std::sync::Arc<crate::pool::PoolMode>> = OnceLock::new();
+static HISTORY: OnceLock<std::sync::Arc<HistoryStore>> = OnceLock::new();
+
+/// Set the pool whose balances and payouts are served by `/api/pool`.
+pub fn set_web_pool(pool: std::sync::Arc<crate::pool::PoolMode>) {
+    let _ = WEB_POOL.set(pool);
+}
+
+/// Set the history store fed by the share and block records and served by `/api/history`.
+pub fn set_history(history: std::sync::Arc<HistoryStore>) {
+    let _ = HISTORY.set(history);
+}
 
 /// Set which config file `/api/config` reads/writes.
 /// If not set, it falls back to `config.yaml` in the current working directory.
@@ -744,6 +866,29 @@ pub fn record_balances(instance_id: &str, balances: &[(String, u64)]) {
     }
 }
 
+/// Record the unpaid pool balances of miner wallets
+pub fn record_pool_balances(balances: &[(String, u64)]) {
+    if let Some(gauge) = POOL_BALANCE_GAUGE.get() {
+        for (address, balance) in balances {
+            gauge.with_label_values(&[address]).set(*balance as f64 / 100_000_000.0);
+        }
+    }
+}
+
+/// Record a pool payout to a miner wallet
+pub fn record_pool_payout(address: &str, amount: u64) {
+    if let Some(counter) = POOL_PAID_COUNTER.get() {
+        counter.with_label_values(&[address]).inc_by(amount as f64 / 100_000_000.0);
+    }
+}
+
+/// Record the reward of a blue block credited to pool miners
+pub fn record_pool_block_credited(reward: u64) {
+    if let Some(counter) = POOL_REWARDS_COUNTER.get() {
+        counter.inc_by(reward as f64 / 100_000_000.0);
+    }
+}
+
 fn metric_matches_instance(metric: &prometheus::proto::Metric, instance_id: &str) -> bool {
     metric.get_label().iter().any(|label| label.name() == "instance" && label.value() == instance_id)
 }
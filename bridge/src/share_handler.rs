@@ -4,6 +4,7 @@ use crate::{
     kaspaapi::NODE_STATUS,
     log_colors::LogColors,
     mining_state::GetMiningState,
+    pool::{PoolMode, PplnsWindow},
     prom::*,
     stratum_context::StratumContext,
 };
@@ -31,8 +32,8 @@ const VAR_DIFF_THREAD_SLEEP: u64 = 10;
 const WORK_WINDOW: u64 = 80;
 const STATS_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
 const STATS_PRINT_INTERVAL: Duration = Duration::from_secs(10);
-const BLOCK_CONFIRM_RETRY_DELAY: Duration = Duration::from_secs(2);
-const BLOCK_CONFIRM_MAX_ATTEMPTS: usize = 30;
+pub(crate) const BLOCK_CONFIRM_RETRY_DELAY: Duration = Duration::from_secs(2);
+pub(crate) const BLOCK_CONFIRM_MAX_ATTEMPTS: usize = 30;
 
 // VarDiff tunables
 const VARDIFF_MIN_ELAPSED_SECS: f64 = 30.0;
@@ -241,6 +242,7 @@ pub struct ShareHandler {
     overall: Arc<WorkStats>,
     instance_id: String, // Instance identifier for logging
     duplicate_submit_guard: Arc<Mutex<DuplicateSubmitGuard>>,
+    pool: Option<Arc<PoolMode>>, // PPLNS pool mode ledger, if enabled
 }
 
 impl ShareHandler {
@@ -251,9 +253,16 @@ impl ShareHandler {
             overall: Arc::new(WorkStats::new("overall".to_string())),
             instance_id,
             duplicate_submit_guard: Arc::new(Mutex::new(DuplicateSubmitGuard::new(Duration::from_secs(180), 50_000))),
+            pool: None,
         }
     }
 
+    /// Records valid shares and credits blue blocks in the PPLNS ledger of the pool
+    pub fn with_pool(mut self, pool: Arc<PoolMode>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
     fn log_prefix(&self) -> String {
         format!("[{}]", self.instance_id)
     }
@@ -841,43 +850,24 @@ impl ShareHandler {
                         let kaspa_api = Arc::clone(&kaspa_api);
                         let block_hash_for_confirm = block_hash.clone();
 
+                        // In pool mode, the reward is split over the PPLNS window as of the time the block was found
+                        let pool_credit = self.pool.as_ref().map(|pool| (Arc::clone(pool), pool.ledger.window()));
+
                         tokio::spawn(async move {
-                            for _ in 0..BLOCK_CONFIRM_MAX_ATTEMPTS {
-                                match kaspa_api.get_current_block_color(&block_hash_for_confirm).await {
-                                    Ok(true) => {
-                                        *stats.blocks_found.lock() += 1;
-                                        *overall.blocks_found.lock() += 1;
-                                        record_block_found(&prom_worker, nonce_val, blue_score, block_hash_for_confirm.clone());
-                                        info!(
-                                            "[{}] {} {}",
-                                            instance_id,
-                                            LogColors::block("[BLOCK]"),
-                                            LogColors::block(&format!(
-                                                "Block confirmed BLUE in DAG! Hash: {}",
-                                                block_hash_for_confirm
-                                            ))
-                                        );
-                                        return;
-                                    }
-                                    Ok(false) => {
-                                        tokio::time::sleep(BLOCK_CONFIRM_RETRY_DELAY).await;
-                                    }
-                                    Err(_) => {
-                                        tokio::time::sleep(BLOCK_CONFIRM_RETRY_DELAY).await;
-                                    }
-                                }
+                            let blue = confirm_found_block(
+                                kaspa_api.as_ref(),
+                                &instance_id,
+                                &prom_worker,
+                                &block_hash_for_confirm,
+                                nonce_val,
+                                blue_score,
+                                pool_credit,
+                            )
+                            .await;
+                            if blue {
+                                *stats.blocks_found.lock() += 1;
+                                *overall.blocks_found.lock() += 1;
                             }
-
-                            record_block_not_confirmed_blue(&prom_worker);
-                            info!(
-                                "[{}] {} {}",
-                                instance_id,
-                                LogColors::block("[BLOCK]"),
-                                LogColors::label(&format!(
-                                    "Block not confirmed blue after {} attempts (not counted as Blocks). Hash: {}",
-                                    BLOCK_CONFIRM_MAX_ATTEMPTS, block_hash_for_confirm
-                                ))
-                            );
                         });
 
                         // Return allows HandleSubmit to record share (blocks are shares too!)
@@ -1092,6 +1082,11 @@ impl ShareHandler {
 
         record_share_found(&self.worker_prom_context(&ctx, ""), hash_value);
 
+        if let Some(pool) = &self.pool {
+            let diff_value = state.stratum_diff().map(|d| d.diff_value).unwrap_or(0.0);
+            pool.record_share(&ctx.wallet_addr.lock().clone(), &ctx.effective_worker_name(), diff_value);
+        }
+
         {
             let now = Instant::now();
             let mut guard = self.duplicate_submit_guard.lock();
@@ -1644,6 +1639,49 @@ fn format_hashrate(ghs: f64) -> String {
     }
 }
 
+/// Waits for a submitted block to be confirmed blue, then records it as found and, in pool mode, credits the
+/// reward paid for it to the miners of `pool_credit` window. Returns whether the block was confirmed blue.
+///
+/// Shared by the Stratum v1 and v2 submission paths.
+pub(crate) async fn confirm_found_block(
+    kaspa_api: &dyn KaspaApiTrait,
+    instance_id: &str,
+    prom_worker: &WorkerContext,
+    block_hash: &str,
+    nonce: u64,
+    blue_score: u64,
+    pool_credit: Option<(Arc<PoolMode>, PplnsWindow)>,
+) -> bool {
+    for _ in 0..BLOCK_CONFIRM_MAX_ATTEMPTS {
+        if let Ok(true) = kaspa_api.get_current_block_color(block_hash).await {
+            record_block_found(prom_worker, nonce, blue_score, block_hash.to_string());
+            if let Some((pool, window)) = &pool_credit {
+                pool.credit_merged_block(kaspa_api, block_hash, window).await;
+            }
+            info!(
+                "[{}] {} {}",
+                instance_id,
+                LogColors::block("[BLOCK]"),
+                LogColors::block(&format!("Block confirmed BLUE in DAG! Hash: {}", block_hash))
+            );
+            return true;
+        }
+        tokio::time::sleep(BLOCK_CONFIRM_RETRY_DELAY).await;
+    }
+
+    record_block_not_confirmed_blue(prom_worker, nonce, blue_score, block_hash);
+    info!(
+        "[{}] {} {}",
+        instance_id,
+        LogColors::block("[BLOCK]"),
+        LogColors::label(&format!(
+            "Block not confirmed blue after {} attempts (not counted as Blocks). Hash: {}",
+            BLOCK_CONFIRM_MAX_ATTEMPTS, block_hash
+        ))
+    );
+    false
+}
+
 // Trait for kaspa API operations
 #[async_trait::async_trait]
 pub trait KaspaApiTrait: Send + Sync {
@@ -1667,6 +1705,9 @@ pub trait KaspaApiTrait: Send + Sync {
     ) -> Result<Vec<(String, u64)>, Box<dyn std::error::Error + Send + Sync>>;
 
     async fn get_current_block_color(&self, block_hash: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Reward paid for a blue block by its merging chain block, `None` until it is merged
+    async fn get_block_reward(&self, block_hash: &str) -> Result<Option<u64>, Box<dyn std::error::Error + Send + Sync>>;
 }
 
 #[cfg(test)]
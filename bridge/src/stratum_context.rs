@@ -5,10 +5,16 @@ use parking_lot::Mutex;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
-use tokio::io::AsyncWriteExt;
-use tokio::net::TcpStream;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 use tokio::sync::mpsc;
 
+/// Byte stream of a stratum connection (plain TCP or TLS)
+pub trait StratumStream: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin + 'static> StratumStream for T {}
+
+type BoxedStratumStream = Box<dyn StratumStream>;
+
 /// Error for disconnected clients
 #[derive(Debug, thiserror::Error)]
 #[error("disconnecting")]
@@ -37,8 +43,8 @@ pub struct StratumContext {
     pub state: Arc<crate::mining_state::MiningState>,
     disconnecting: Arc<AtomicBool>,
     write_lock: Arc<AtomicBool>,
-    read_half: Arc<Mutex<Option<tokio::io::ReadHalf<TcpStream>>>>,
-    write_half: Arc<Mutex<Option<tokio::io::WriteHalf<TcpStream>>>>,
+    read_half: Arc<Mutex<Option<tokio::io::ReadHalf<BoxedStratumStream>>>>,
+    write_half: Arc<Mutex<Option<tokio::io::WriteHalf<BoxedStratumStream>>>>,
     on_disconnect: mpsc::UnboundedSender<Arc<StratumContext>>,
 }
 
@@ -46,11 +52,11 @@ impl StratumContext {
     pub fn new(
         remote_addr: String,
         remote_port: u16,
-        stream: TcpStream,
+        stream: impl StratumStream,
         state: Arc<crate::mining_state::MiningState>,
         on_disconnect: mpsc::UnboundedSender<Arc<StratumContext>>,
     ) -> Arc<Self> {
-        let (read_half, write_half) = tokio::io::split(stream);
+        let (read_half, write_half) = tokio::io::split(Box::new(stream) as BoxedStratumStream);
         Arc::new(Self {
             remote_addr,
             remote_port,
@@ -639,7 +645,7 @@ impl StratumContext {
     }
 
     /// Get a reference to the read half (for reading)
-    pub fn get_read_half(&self) -> parking_lot::MutexGuard<'_, Option<tokio::io::ReadHalf<TcpStream>>> {
+    pub fn get_read_half(&self) -> parking_lot::MutexGuard<'_, Option<tokio::io::ReadHalf<BoxedStratumStream>>> {
         self.read_half.lock()
     }
 }
@@ -1,10 +1,15 @@
+use crate::app_config::TlsConfig;
 use crate::jsonrpc_event::JsonRpcEvent;
 use crate::log_colors::LogColors;
+use crate::mining_state::MiningState;
 use crate::net_utils::bind_addr_from_port;
-use crate::stratum_context::StratumContext;
+use crate::stratum_context::{StratumContext, StratumStream};
+use crate::stratum_tls::ReloadableTlsAcceptor;
 use hex;
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::AsyncReadExt;
 use tokio::net::TcpListener;
 use tokio::sync::mpsc;
@@ -16,6 +21,9 @@ use tracing::{debug, error, info, warn};
 /// memory growth when a client sends data without a newline.
 pub const MAX_STRATUM_LINE_BYTES: usize = 64 * 1024;
 
+/// Maximum time a client may take to complete the TLS handshake on a TLS stratum port
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Append received data to the line buffer. Returns `false` if the append would exceed
 /// [`MAX_STRATUM_LINE_BYTES`], leaving the buffer unchanged.
 pub fn append_line_data(line_buffer: &mut String, data: &str) -> bool {
@@ -58,6 +66,7 @@ pub struct StratumListenerConfig {
     pub on_connect: Arc<dyn Fn(Arc<StratumContext>) + Send + Sync>,
     pub on_disconnect: Arc<dyn Fn(Arc<StratumContext>) + Send + Sync>,
     pub port: String,
+    pub tls: Option<TlsConfig>, // Additional TLS port served with the same handlers
 }
 
 /// Stratum TCP listener
@@ -103,8 +112,20 @@ impl StratumListener {
 
         debug!("Stratum listener started on {}", self.config.port);
 
+        let tls_acceptor = match &self.config.tls {
+            Some(tls) => {
+                let acceptor = ReloadableTlsAcceptor::new(tls.clone())
+                    .map_err(|e| format!("failed loading TLS certificate for stratum port {}: {:#}", tls.port, e))?;
+                let tls_listener = TcpListener::bind(bind_addr_from_port(&tls.port))
+                    .await
+                    .map_err(|e| format!("failed listening to socket {}: {}", tls.port, e))?;
+                info!("Stratum TLS listener started on {}", tls.port);
+                Some((acceptor, tls_listener))
+            }
+            None => None,
+        };
+
         let (disconnect_tx, mut disconnect_rx) = mpsc::unbounded_channel::<Arc<StratumContext>>();
-        let disconnect_tx_clone = disconnect_tx.clone();
         let on_disconnect = Arc::clone(&self.config.on_disconnect);
         let stats = self.stats.clone();
 
@@ -140,131 +161,105 @@ impl StratumListener {
             }
         });
 
+        if let Some((acceptor, tls_listener)) = tls_acceptor {
+            acceptor.start_reload_thread(shutdown_rx.clone());
+            tokio::spawn(Self::accept_loop(
+                tls_listener,
+                Some(acceptor),
+                self.config.handler_map.clone(),
+                Arc::clone(&self.config.on_connect),
+                disconnect_tx.clone(),
+                shutdown_rx.clone(),
+                Arc::clone(&self.shutting_down),
+            ));
+        }
+
+        Self::accept_loop(
+            listener,
+            None,
+            self.config.handler_map.clone(),
+            Arc::clone(&self.config.on_connect),
+            disconnect_tx,
+            shutdown_rx,
+            Arc::clone(&self.shutting_down),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Accept loop shared by the plain and TLS ports. The TLS handshake, if any, runs in the connection task
+    /// so that a slow client cannot stall the loop, and clients are then handled the same way on both ports.
+    async fn accept_loop(
+        listener: TcpListener,
+        tls_acceptor: Option<Arc<ReloadableTlsAcceptor>>,
+        handler_map: Arc<HashMap<String, EventHandler>>,
+        on_connect: Arc<dyn Fn(Arc<StratumContext>) + Send + Sync>,
+        disconnect_tx: mpsc::UnboundedSender<Arc<StratumContext>>,
+        mut shutdown_rx: Option<watch::Receiver<bool>>,
+        shutting_down: Arc<std::sync::atomic::AtomicBool>,
+    ) {
         loop {
-            if let Some(ref mut rx) = shutdown_rx {
+            let accepted = if let Some(ref mut rx) = shutdown_rx {
                 tokio::select! {
                     _ = rx.changed() => {
                         if *rx.borrow() {
-                            self.shutting_down.store(true, std::sync::atomic::Ordering::Release);
+                            shutting_down.store(true, std::sync::atomic::Ordering::Release);
                             break;
                         }
+                        continue;
                     }
-                    result = listener.accept() => {
-                        match result {
-                            Ok((stream, addr)) => {
-                            let remote_addr = addr.ip().to_string();
-                            let remote_port = addr.port();
-
-                            debug!("[CONNECTION] new client connecting - {}:{}", remote_addr, remote_port);
-                            debug!("[CONNECTION] ===== TCP CONNECTION ESTABLISHED =====");
-                            debug!("[CONNECTION] Remote address: {}:{}", remote_addr, remote_port);
-                            debug!("[CONNECTION] Local address: {:?}", stream.local_addr());
-                            debug!("[CONNECTION] Connection accepted successfully");
-
-                            // Create new MiningState for each client
-                            // Each client gets its own isolated state, just like in Go
-                            use crate::mining_state::MiningState;
-                            let state = Arc::new(MiningState::new());
-
-                            // Clone for logging after move
-                            let remote_addr_for_log = remote_addr.clone();
-                            let remote_port_for_log = remote_port;
-
-                            debug!("[CONNECTION] Creating StratumContext for {}:{}", remote_addr_for_log, remote_port_for_log);
-                            let ctx = StratumContext::new(
-                                remote_addr,
-                                remote_port,
-                                stream,
-                                state,
-                                disconnect_tx_clone.clone(),
-                            );
-                            debug!("[CONNECTION] StratumContext created successfully");
-
-                            debug!("[CONNECTION] Calling on_connect handler");
-                            (self.config.on_connect)(ctx.clone());
-                            debug!("[CONNECTION] on_connect handler completed");
-
-                            // Spawn client handler
-                            debug!("[CONNECTION] Spawning client listener task for {}:{}", remote_addr_for_log, remote_port_for_log);
-                            let ctx_clone = ctx.clone();
-                            let handler_map = self.config.handler_map.clone();
-                            tokio::spawn(async move {
-                                debug!("[CONNECTION] Client listener task started for {}:{}", ctx_clone.remote_addr, ctx_clone.remote_port);
-                                Self::spawn_client_listener(ctx_clone, &handler_map).await;
-                                debug!("[CONNECTION] Client listener task ended");
-                            });
-                            debug!("[CONNECTION] ===== CONNECTION SETUP COMPLETE FOR {}:{} =====", remote_addr_for_log, remote_port_for_log);
-                        }
-                            Err(e) => {
-                            if self.shutting_down.load(std::sync::atomic::Ordering::Acquire) {
-                                info!("stopping listening due to server shutdown");
-                                break;
-                            }
-                            error!("[CONNECTION] ===== FAILED TO ACCEPT INCOMING CONNECTION =====");
-                            error!("[CONNECTION] Error: {}", e);
-                            error!("[CONNECTION] Error kind: {:?}", e.kind());
-                            error!("[CONNECTION] Failed to accept connection: {} (kind: {:?})", e, e.kind());
-                            }
-                        }
-                    }
+                    result = listener.accept() => result,
                 }
             } else {
-                match listener.accept().await {
-                    Ok((stream, addr)) => {
-                        let remote_addr = addr.ip().to_string();
-                        let remote_port = addr.port();
-
-                        debug!("[CONNECTION] new client connecting - {}:{}", remote_addr, remote_port);
-                        debug!("[CONNECTION] ===== TCP CONNECTION ESTABLISHED =====");
-                        debug!("[CONNECTION] Remote address: {}:{}", remote_addr, remote_port);
-                        debug!("[CONNECTION] Local address: {:?}", stream.local_addr());
-                        debug!("[CONNECTION] Connection accepted successfully");
-
-                        use crate::mining_state::MiningState;
-                        let state = Arc::new(MiningState::new());
-
-                        let remote_addr_for_log = remote_addr.clone();
-                        let remote_port_for_log = remote_port;
-
-                        debug!("[CONNECTION] Creating StratumContext for {}:{}", remote_addr_for_log, remote_port_for_log);
-                        let ctx = StratumContext::new(remote_addr, remote_port, stream, state, disconnect_tx_clone.clone());
-                        debug!("[CONNECTION] StratumContext created successfully");
-
-                        debug!("[CONNECTION] Calling on_connect handler");
-                        (self.config.on_connect)(ctx.clone());
-                        debug!("[CONNECTION] on_connect handler completed");
-
-                        debug!("[CONNECTION] Spawning client listener task for {}:{}", remote_addr_for_log, remote_port_for_log);
-                        let ctx_clone = ctx.clone();
-                        let handler_map = self.config.handler_map.clone();
-                        tokio::spawn(async move {
-                            debug!(
-                                "[CONNECTION] Client listener task started for {}:{}",
-                                ctx_clone.remote_addr, ctx_clone.remote_port
-                            );
-                            Self::spawn_client_listener(ctx_clone, &handler_map).await;
-                            debug!("[CONNECTION] Client listener task ended");
-                        });
-                        debug!(
-                            "[CONNECTION] ===== CONNECTION SETUP COMPLETE FOR {}:{} =====",
-                            remote_addr_for_log, remote_port_for_log
-                        );
-                    }
-                    Err(e) => {
-                        if self.shutting_down.load(std::sync::atomic::Ordering::Acquire) {
-                            info!("stopping listening due to server shutdown");
-                            break;
-                        }
-                        error!("[CONNECTION] ===== FAILED TO ACCEPT INCOMING CONNECTION =====");
-                        error!("[CONNECTION] Error: {}", e);
-                        error!("[CONNECTION] Error kind: {:?}", e.kind());
-                        error!("[CONNECTION] Failed to accept connection: {} (kind: {:?})", e, e.kind());
+                listener.accept().await
+            };
+
+            let (stream, addr) = match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    if shutting_down.load(std::sync::atomic::Ordering::Acquire) {
+                        info!("stopping listening due to server shutdown");
+                        break;
                     }
+                    error!("[CONNECTION] Failed to accept connection: {} (kind: {:?})", e, e.kind());
+                    continue;
                 }
-            }
+            };
+            debug!("[CONNECTION] new client connecting - {}", addr);
+
+            let tls_acceptor = tls_acceptor.as_ref().map(|acceptor| acceptor.acceptor());
+            let handler_map = handler_map.clone();
+            let on_connect = Arc::clone(&on_connect);
+            let disconnect_tx = disconnect_tx.clone();
+            tokio::spawn(async move {
+                let Some(tls_acceptor) = tls_acceptor else {
+                    return Self::serve_client(stream, addr, handler_map, on_connect, disconnect_tx).await;
+                };
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, tls_acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => Self::serve_client(stream, addr, handler_map, on_connect, disconnect_tx).await,
+                    Ok(Err(e)) => debug!("[CONNECTION] TLS handshake with {} failed: {}", addr, e),
+                    Err(_) => debug!("[CONNECTION] TLS handshake with {} timed out", addr),
+                }
+            });
         }
+    }
 
-        Ok(())
+    /// Creates the context of an accepted client and serves it until it disconnects
+    async fn serve_client(
+        stream: impl StratumStream,
+        addr: SocketAddr,
+        handler_map: Arc<HashMap<String, EventHandler>>,
+        on_connect: Arc<dyn Fn(Arc<StratumContext>) + Send + Sync>,
+        disconnect_tx: mpsc::UnboundedSender<Arc<StratumContext>>,
+    ) {
+        // Each client gets its own isolated mining state, just like in Go
+        let state = Arc::new(MiningState::new());
+        let ctx = StratumContext::new(addr.ip().to_string(), addr.port(), stream, state, disconnect_tx);
+        on_connect(ctx.clone());
+        debug!("[CONNECTION] Client listener task started for {}", addr);
+        Self::spawn_client_listener(ctx, &handler_map).await;
+        debug!("[CONNECTION] Client listener task ended for {}", addr);
     }
 
     /// Spawn a client listener task
@@ -1,11 +1,14 @@
 use crate::{
+    app_config::{Sv2Config, TlsConfig},
     client_handler::ClientHandler,
     default_client::{default_handlers, handle_authorize, handle_subscribe},
     jsonrpc_event::JsonRpcEvent,
     kaspaapi::KaspaApi,
+    pool::PoolMode,
     share_handler::{KaspaApiTrait, ShareHandler},
     stratum_context::StratumContext,
     stratum_listener::{StratumListener, StratumListenerConfig},
+    sv2_server::{Sv2Server, load_responder},
 };
 use std::sync::Arc;
 use std::time::Duration;
@@ -28,6 +31,9 @@ pub struct BridgeConfig {
     pub extranonce_size: u8,
     pub pow2_clamp: bool,
     pub coinbase_tag_suffix: Option<String>,
+    pub pool: Option<Arc<PoolMode>>, // PPLNS pool mode, shared by all instances
+    pub tls: Option<TlsConfig>,      // Optional TLS port serving the same difficulty tier
+    pub sv2: Option<Sv2Config>,      // Optional Stratum V2 port serving the same difficulty tier
 }
 
 /// Start block template listener with concrete KaspaApi
@@ -99,15 +105,42 @@ async fn listen_and_serve_impl<T: KaspaApiTrait + Send + Sync + 'static>(
 
     // Create share handler with instance identifier
     let instance_id = config.instance_id.clone();
-    let share_handler = Arc::new(ShareHandler::new(instance_id.clone()));
+    let mut share_handler = ShareHandler::new(instance_id.clone());
+    if let Some(pool) = &config.pool {
+        share_handler = share_handler.with_pool(Arc::clone(pool));
+    }
+    let share_handler = Arc::new(share_handler);
 
     // Create client handler
     // Note: extranonce_size parameter is now only used for backward compatibility
     // Actual extranonce assignment happens per-client in handle_subscribe based on detected miner type
-    let client_handler = Arc::new(ClientHandler::new(Arc::clone(&share_handler), min_diff, extranonce_size, instance_id.clone()));
+    let mut client_handler = ClientHandler::new(Arc::clone(&share_handler), min_diff, extranonce_size, instance_id.clone());
+    if let Some(pool) = &config.pool {
+        client_handler = client_handler.with_pool_address(pool.config.address.clone());
+    }
+    let client_handler = Arc::new(client_handler);
 
     let shutdown_rx_for_bg = shutdown_rx.clone();
 
+    // Start the Stratum V2 listener; it receives new templates from the same block template listener
+    let sv2_server = match &config.sv2 {
+        Some(sv2) => {
+            let responder = load_responder(sv2).map_err(|e| format!("failed setting up Stratum V2 on {}: {:#}", sv2.port, e))?;
+            let server = Sv2Server::new(instance_id.clone(), min_diff, responder, config.pool.clone());
+            let port = sv2.port.clone();
+            let kaspa_api = Arc::clone(&kaspa_api);
+            let shutdown_rx = shutdown_rx_for_bg.clone();
+            let listener = Arc::clone(&server);
+            tokio::spawn(async move {
+                if let Err(e) = listener.listen(port.clone(), kaspa_api, shutdown_rx).await {
+                    warn!("Stratum V2 listener on {} stopped: {}", port, e);
+                }
+            });
+            Some(server)
+        }
+        None => None,
+    };
+
     // Setup default handlers
     let mut handlers = default_handlers();
 
@@ -171,6 +204,7 @@ async fn listen_and_serve_impl<T: KaspaApiTrait + Send + Sync + 'static>(
     // Each client gets its own isolated state
     let listener_config = StratumListenerConfig {
         port: config.stratum_port.clone(),
+        tls: config.tls.clone(),
         handler_map: Arc::new(handlers),
         on_connect: Arc::new({
             let client_handler = Arc::clone(&client_handler);
@@ -222,8 +256,12 @@ async fn listen_and_serve_impl<T: KaspaApiTrait + Send + Sync + 'static>(
         // We have concrete KaspaApi - use notification-based listener
         let client_handler_cb = Arc::clone(&client_handler);
         let kaspa_api_cb = Arc::clone(&kaspa_api);
+        let sv2_server_cb = sv2_server.clone();
 
         let block_cb = move || {
+            if let Some(sv2_server) = &sv2_server_cb {
+                sv2_server.new_block_available();
+            }
             let client_handler = Arc::clone(&client_handler_cb);
             let kaspa_api = Arc::clone(&kaspa_api_cb);
             tokio::spawn(async move {
@@ -253,6 +291,7 @@ async fn listen_and_serve_impl<T: KaspaApiTrait + Send + Sync + 'static>(
 
         let client_handler_poll = Arc::clone(&client_handler);
         let kaspa_api_poll = Arc::clone(&kaspa_api);
+        let sv2_server_poll = sv2_server.clone();
         let mut shutdown_rx_poll = shutdown_rx_for_bg;
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(config.block_wait_time);
@@ -266,11 +305,17 @@ async fn listen_and_serve_impl<T: KaspaApiTrait + Send + Sync + 'static>(
                             }
                         }
                         _ = interval.tick() => {
+                            if let Some(sv2_server) = &sv2_server_poll {
+                                sv2_server.new_block_available();
+                            }
                             client_handler_poll.new_block_available(Arc::clone(&kaspa_api_poll)).await;
                         }
                     }
                 } else {
                     interval.tick().await;
+                    if let Some(sv2_server) = &sv2_server_poll {
+                        sv2_server.new_block_available();
+                    }
                     client_handler_poll.new_block_available(Arc::clone(&kaspa_api_poll)).await;
                 }
             }
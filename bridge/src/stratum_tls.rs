@@ -0,0 +1,237 @@
+//! TLS support for stratum listeners.
+//!
+//! Miners connecting over the internet would otherwise send their wallet addresses and worker names in
+//! cleartext. A TLS listener terminates TLS with a PEM certificate and key, optionally reloading them when
+//! the files change on disk (e.g. after a certificate renewal) without dropping connected miners.
+
+use crate::app_config::TlsConfig;
+use anyhow::{Context, Result};
+use parking_lot::{Mutex, RwLock};
+use rustls::ServerConfig;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::watch;
+use tokio_rustls::TlsAcceptor;
+use tracing::{info, warn};
+
+/// Builds a rustls server configuration from PEM certificate chain and private key files
+pub fn load_server_config(cert_path: &str, key_path: &str) -> Result<Arc<ServerConfig>> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .with_context(|| format!("failed to open TLS certificate {}", cert_path))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("failed to parse TLS certificate {}", cert_path))?;
+    if certs.is_empty() {
+        return Err(anyhow::anyhow!("no certificate found in {}", cert_path));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path).with_context(|| format!("failed to load TLS private key {}", key_path))?;
+
+    let config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .context("failed to select TLS protocol versions")?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("invalid TLS certificate or private key")?;
+    Ok(Arc::new(config))
+}
+
+/// TLS acceptor whose certificate can be swapped while the listener is running
+pub struct ReloadableTlsAcceptor {
+    config: TlsConfig,
+    acceptor: RwLock<TlsAcceptor>,
+    modified: Mutex<Option<(SystemTime, SystemTime)>>, // Modification times of the loaded certificate and key
+}
+
+impl ReloadableTlsAcceptor {
+    pub fn new(config: TlsConfig) -> Result<Arc<Self>> {
+        let modified = Self::files_modified(&config);
+        let server_config = load_server_config(&config.cert_path, &config.key_path)?;
+        Ok(Arc::new(Self { config, acceptor: RwLock::new(TlsAcceptor::from(server_config)), modified: Mutex::new(modified) }))
+    }
+
+    /// Acceptor for a new connection, using the most recently loaded certificate
+    pub fn acceptor(&self) -> TlsAcceptor {
+        self.acceptor.read().clone()
+    }
+
+    fn files_modified(config: &TlsConfig) -> Option<(SystemTime, SystemTime)> {
+        let cert = std::fs::metadata(&config.cert_path).and_then(|m| m.modified()).ok()?;
+        let key = std::fs::metadata(&config.key_path).and_then(|m| m.modified()).ok()?;
+        Some((cert, key))
+    }
+
+    /// Reloads the certificate if its files changed since the last load. An invalid new certificate is
+    /// reported and the previous one is kept. Returns true if a new certificate was loaded.
+    pub fn reload_if_changed(&self) -> bool {
+        let modified = Self::files_modified(&self.config);
+        if modified.is_none() || modified == *self.modified.lock() {
+            return false;
+        }
+
+        match load_server_config(&self.config.cert_path, &self.config.key_path) {
+            Ok(server_config) => {
+                *self.acceptor.write() = TlsAcceptor::from(server_config);
+                *self.modified.lock() = modified;
+                info!("reloaded TLS certificate for stratum port {}", self.config.port);
+                true
+            }
+            Err(e) => {
+                // Remember the failed files so that the error is not logged on every check
+                *self.modified.lock() = modified;
+                warn!("failed to reload TLS certificate for stratum port {}, keeping the previous one: {:#}", self.config.port, e);
+                false
+            }
+        }
+    }
+
+    /// Periodically checks the certificate files for changes if a reload interval is configured
+    pub fn start_reload_thread(self: &Arc<Self>, mut shutdown_rx: Option<watch::Receiver<bool>>) {
+        let Some(interval) = self.config.reload_interval else {
+            return;
+        };
+        let acceptor = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            ticker.tick().await;
+            loop {
+                if let Some(ref mut rx) = shutdown_rx {
+                    tokio::select! {
+                        _ = rx.changed() => {
+                            if *rx.borrow() {
+                                break;
+                            }
+                            continue;
+                        }
+                        _ = ticker.tick() => {}
+                    }
+                } else {
+                    ticker.tick().await;
+                }
+                acceptor.reload_if_changed();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+    use rustls::crypto::{CryptoProvider, verify_tls12_signature, verify_tls13_signature};
+    use rustls::pki_types::{ServerName, UnixTime};
+    use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+    use std::fs::File;
+    use std::path::Path;
+    use std::time::{Duration, UNIX_EPOCH};
+    use tokio_rustls::TlsConnector;
+
+    // Self-signed certificates for `localhost`, generated with
+    // `openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:prime256v1 -nodes -days 36500 -subj /CN=localhost`
+    const FIRST_CERT: &str = include_str!("fixtures/tls_first.pem");
+    const FIRST_KEY: &str = include_str!("fixtures/tls_first.key");
+    const SECOND_CERT: &str = include_str!("fixtures/tls_second.pem");
+    const SECOND_KEY: &str = include_str!("fixtures/tls_second.key");
+
+    /// Accepts any server certificate, the test checks which one was served instead
+    #[derive(Debug)]
+    struct AnyServerCert(Arc<CryptoProvider>);
+
+    impl ServerCertVerifier for AnyServerCert {
+        fn verify_server_cert(
+            &self,
+            _end_entity: &CertificateDer<'_>,
+            _intermediates: &[CertificateDer<'_>],
+            _server_name: &ServerName<'_>,
+            _ocsp_response: &[u8],
+            _now: UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            Ok(ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.0.signature_verification_algorithms.supported_schemes()
+        }
+    }
+
+    /// Writes `contents` to `path` with a given modification time, so that successive writes are always seen as changes
+    fn write_file(path: &Path, contents: &str, modified_secs: u64) {
+        std::fs::write(path, contents).unwrap();
+        File::options().write(true).open(path).unwrap().set_modified(UNIX_EPOCH + Duration::from_secs(modified_secs)).unwrap();
+    }
+
+    /// Completes a TLS handshake with the acceptor and returns the certificate it served
+    async fn served_certificate(acceptor: &ReloadableTlsAcceptor) -> CertificateDer<'static> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let client_config = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AnyServerCert(provider)))
+            .with_no_client_auth();
+        let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let (client, server) = tokio::join!(
+            TlsConnector::from(Arc::new(client_config)).connect(server_name, client_io),
+            acceptor.acceptor().accept(server_io)
+        );
+        server.expect("the server side of the handshake should complete");
+        let client = client.expect("the client side of the handshake should complete");
+        client.get_ref().1.peer_certificates().expect("the server should send its certificate")[0].clone().into_owned()
+    }
+
+    fn pem_certificate(pem: &str) -> CertificateDer<'static> {
+        CertificateDer::from_pem_slice(pem.as_bytes()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_tls_certificate_reload() {
+        let dir = std::env::temp_dir().join(format!("ks_bridge_tls_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (cert_path, key_path) = (dir.join("bridge.pem"), dir.join("bridge.key"));
+        write_file(&cert_path, FIRST_CERT, 1_000);
+        write_file(&key_path, FIRST_KEY, 1_000);
+
+        let acceptor = ReloadableTlsAcceptor::new(TlsConfig {
+            port: ":5565".to_string(),
+            cert_path: cert_path.to_string_lossy().to_string(),
+            key_path: key_path.to_string_lossy().to_string(),
+            reload_interval: None,
+        })
+        .unwrap();
+        assert_eq!(served_certificate(&acceptor).await, pem_certificate(FIRST_CERT));
+        assert!(!acceptor.reload_if_changed(), "unchanged files should not be reloaded");
+
+        // A renewed certificate is served to the next connections
+        write_file(&cert_path, SECOND_CERT, 2_000);
+        write_file(&key_path, SECOND_KEY, 2_000);
+        assert!(acceptor.reload_if_changed());
+        assert_eq!(served_certificate(&acceptor).await, pem_certificate(SECOND_CERT));
+
+        // An invalid replacement keeps the previous certificate
+        write_file(&cert_path, "not a certificate", 3_000);
+        assert!(!acceptor.reload_if_changed());
+        assert_eq!(served_certificate(&acceptor).await, pem_certificate(SECOND_CERT));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
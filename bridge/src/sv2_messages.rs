@@ -0,0 +1,674 @@
+//! Stratum V2 binary framing and the subset of the common and mining protocol messages served by the bridge.
+//!
+//! A frame is a 6-byte header (extension type, message type, 24-bit payload length) followed by the payload.
+//! On an encrypted connection the header is encrypted on its own and the payload in chunks of at most 64 KiB,
+//! each chunk carrying its own MAC.
+
+use crate::sv2_noise::{MAC_SIZE, NoiseError, NoiseReceiver, NoiseSender};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+pub const HEADER_SIZE: usize = 6;
+pub const ENCRYPTED_HEADER_SIZE: usize = HEADER_SIZE + MAC_SIZE;
+pub const MAX_CHUNK_PLAINTEXT_SIZE: usize = u16::MAX as usize - MAC_SIZE;
+/// Largest payload accepted from a peer; mining messages are far smaller
+pub const MAX_PAYLOAD_SIZE: usize = 1 << 20;
+
+const CHANNEL_MSG_BIT: u16 = 0x8000;
+
+pub const MSG_SETUP_CONNECTION: u8 = 0x00;
+pub const MSG_SETUP_CONNECTION_SUCCESS: u8 = 0x01;
+pub const MSG_SETUP_CONNECTION_ERROR: u8 = 0x02;
+pub const MSG_OPEN_STANDARD_MINING_CHANNEL: u8 = 0x10;
+pub const MSG_OPEN_STANDARD_MINING_CHANNEL_SUCCESS: u8 = 0x11;
+pub const MSG_OPEN_MINING_CHANNEL_ERROR: u8 = 0x12;
+pub const MSG_OPEN_EXTENDED_MINING_CHANNEL: u8 = 0x13;
+pub const MSG_OPEN_EXTENDED_MINING_CHANNEL_SUCCESS: u8 = 0x14;
+pub const MSG_NEW_MINING_JOB: u8 = 0x15;
+pub const MSG_SUBMIT_SHARES_STANDARD: u8 = 0x1a;
+pub const MSG_SUBMIT_SHARES_EXTENDED: u8 = 0x1b;
+pub const MSG_SUBMIT_SHARES_SUCCESS: u8 = 0x1c;
+pub const MSG_SUBMIT_SHARES_ERROR: u8 = 0x1d;
+pub const MSG_NEW_EXTENDED_MINING_JOB: u8 = 0x1f;
+pub const MSG_SET_TARGET: u8 = 0x21;
+
+#[derive(Debug, Error)]
+pub enum Sv2Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Noise(#[from] NoiseError),
+    #[error("message truncated")]
+    Truncated,
+    #[error("{0} trailing bytes after message")]
+    TrailingBytes(usize),
+    #[error("field {0} is too long")]
+    FieldTooLong(&'static str),
+    #[error("invalid utf-8 string")]
+    InvalidString,
+    #[error("payload of {0} bytes exceeds the maximum size")]
+    PayloadTooLarge(usize),
+    #[error("unsupported extension type {0:#06x}")]
+    UnsupportedExtension(u16),
+    #[error("unknown message type {0:#04x}")]
+    UnknownMessage(u8),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupConnection {
+    pub protocol: u8,
+    pub min_version: u16,
+    pub max_version: u16,
+    pub flags: u32,
+    pub endpoint_host: String,
+    pub endpoint_port: u16,
+    pub vendor: String,
+    pub hardware_version: String,
+    pub firmware: String,
+    pub device_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupConnectionSuccess {
+    pub used_version: u16,
+    pub flags: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupConnectionError {
+    pub flags: u32,
+    pub error_code: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenStandardMiningChannel {
+    pub request_id: u32,
+    pub user_identity: String,
+    pub nominal_hash_rate: f32,
+    pub max_target: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenStandardMiningChannelSuccess {
+    pub request_id: u32,
+    pub channel_id: u32,
+    pub target: [u8; 32],
+    pub extranonce_prefix: Vec<u8>,
+    pub group_channel_id: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenMiningChannelError {
+    pub request_id: u32,
+    pub error_code: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenExtendedMiningChannel {
+    pub request_id: u32,
+    pub user_identity: String,
+    pub nominal_hash_rate: f32,
+    pub max_target: [u8; 32],
+    pub min_extranonce_size: u16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenExtendedMiningChannelSuccess {
+    pub request_id: u32,
+    pub channel_id: u32,
+    pub target: [u8; 32],
+    pub extranonce_size: u16,
+    pub extranonce_prefix: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewMiningJob {
+    pub channel_id: u32,
+    pub job_id: u32,
+    pub min_ntime: Option<u32>,
+    pub version: u32,
+    pub merkle_root: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewExtendedMiningJob {
+    pub channel_id: u32,
+    pub job_id: u32,
+    pub min_ntime: Option<u32>,
+    pub version: u32,
+    pub version_rolling_allowed: bool,
+    pub merkle_path: Vec<[u8; 32]>,
+    pub coinbase_tx_prefix: Vec<u8>,
+    pub coinbase_tx_suffix: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmitSharesStandard {
+    pub channel_id: u32,
+    pub sequence_number: u32,
+    pub job_id: u32,
+    pub nonce: u32,
+    pub ntime: u32,
+    pub version: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmitSharesExtended {
+    pub channel_id: u32,
+    pub sequence_number: u32,
+    pub job_id: u32,
+    pub nonce: u32,
+    pub ntime: u32,
+    pub version: u32,
+    pub extranonce: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmitSharesSuccess {
+    pub channel_id: u32,
+    pub last_sequence_number: u32,
+    pub new_submits_accepted_count: u32,
+    pub new_shares_sum: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmitSharesError {
+    pub channel_id: u32,
+    pub sequence_number: u32,
+    pub error_code: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetTarget {
+    pub channel_id: u32,
+    pub maximum_target: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sv2Message {
+    SetupConnection(SetupConnection),
+    SetupConnectionSuccess(SetupConnectionSuccess),
+    SetupConnectionError(SetupConnectionError),
+    OpenStandardMiningChannel(OpenStandardMiningChannel),
+    OpenStandardMiningChannelSuccess(OpenStandardMiningChannelSuccess),
+    OpenMiningChannelError(OpenMiningChannelError),
+    OpenExtendedMiningChannel(OpenExtendedMiningChannel),
+    OpenExtendedMiningChannelSuccess(OpenExtendedMiningChannelSuccess),
+    NewMiningJob(NewMiningJob),
+    NewExtendedMiningJob(NewExtendedMiningJob),
+    SubmitSharesStandard(SubmitSharesStandard),
+    SubmitSharesExtended(SubmitSharesExtended),
+    SubmitSharesSuccess(SubmitSharesSuccess),
+    SubmitSharesError(SubmitSharesError),
+    SetTarget(SetTarget),
+}
+
+/// Little-endian encoder for the Stratum V2 data types
+#[derive(Default)]
+struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.buf.push(value as u8);
+    }
+
+    fn u256(&mut self, value: &[u8; 32]) {
+        self.buf.extend_from_slice(value);
+    }
+
+    fn str0_255(&mut self, name: &'static str, value: &str) -> Result<(), Sv2Error> {
+        self.b0_255(name, value.as_bytes(), 255)
+    }
+
+    /// Bytes prefixed with a one-byte length, limited to `max` bytes (B0_32, STR0_255)
+    fn b0_255(&mut self, name: &'static str, value: &[u8], max: usize) -> Result<(), Sv2Error> {
+        if value.len() > max {
+            return Err(Sv2Error::FieldTooLong(name));
+        }
+        self.u8(value.len() as u8);
+        self.buf.extend_from_slice(value);
+        Ok(())
+    }
+
+    fn b0_64k(&mut self, name: &'static str, value: &[u8]) -> Result<(), Sv2Error> {
+        if value.len() > u16::MAX as usize {
+            return Err(Sv2Error::FieldTooLong(name));
+        }
+        self.u16(value.len() as u16);
+        self.buf.extend_from_slice(value);
+        Ok(())
+    }
+
+    fn option_u32(&mut self, value: Option<u32>) {
+        match value {
+            Some(value) => {
+                self.u8(1);
+                self.u32(value);
+            }
+            None => self.u8(0),
+        }
+    }
+
+    fn seq0_255_u256(&mut self, name: &'static str, values: &[[u8; 32]]) -> Result<(), Sv2Error> {
+        if values.len() > 255 {
+            return Err(Sv2Error::FieldTooLong(name));
+        }
+        self.u8(values.len() as u8);
+        for value in values {
+            self.u256(value);
+        }
+        Ok(())
+    }
+}
+
+/// Little-endian decoder for the Stratum V2 data types
+struct Decoder<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Sv2Error> {
+        if self.buf.len() < len {
+            return Err(Sv2Error::Truncated);
+        }
+        let (head, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, Sv2Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Sv2Error> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, Sv2Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, Sv2Error> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, Sv2Error> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn bool(&mut self) -> Result<bool, Sv2Error> {
+        Ok(self.u8()? & 1 == 1)
+    }
+
+    fn u256(&mut self) -> Result<[u8; 32], Sv2Error> {
+        Ok(self.take(32)?.try_into().unwrap())
+    }
+
+    fn str0_255(&mut self) -> Result<String, Sv2Error> {
+        let len = self.u8()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| Sv2Error::InvalidString)
+    }
+
+    fn b0_32(&mut self, name: &'static str) -> Result<Vec<u8>, Sv2Error> {
+        let len = self.u8()? as usize;
+        if len > 32 {
+            return Err(Sv2Error::FieldTooLong(name));
+        }
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn b0_64k(&mut self) -> Result<Vec<u8>, Sv2Error> {
+        let len = self.u16()? as usize;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn option_u32(&mut self) -> Result<Option<u32>, Sv2Error> {
+        match self.u8()? {
+            0 => Ok(None),
+            _ => Ok(Some(self.u32()?)),
+        }
+    }
+
+    fn seq0_255_u256(&mut self) -> Result<Vec<[u8; 32]>, Sv2Error> {
+        let len = self.u8()? as usize;
+        (0..len).map(|_| self.u256()).collect()
+    }
+
+    fn finish(self) -> Result<(), Sv2Error> {
+        if self.buf.is_empty() { Ok(()) } else { Err(Sv2Error::TrailingBytes(self.buf.len())) }
+    }
+}
+
+impl Sv2Message {
+    pub fn msg_type(&self) -> u8 {
+        match self {
+            Self::SetupConnection(_) => MSG_SETUP_CONNECTION,
+            Self::SetupConnectionSuccess(_) => MSG_SETUP_CONNECTION_SUCCESS,
+            Self::SetupConnectionError(_) => MSG_SETUP_CONNECTION_ERROR,
+            Self::OpenStandardMiningChannel(_) => MSG_OPEN_STANDARD_MINING_CHANNEL,
+            Self::OpenStandardMiningChannelSuccess(_) => MSG_OPEN_STANDARD_MINING_CHANNEL_SUCCESS,
+            Self::OpenMiningChannelError(_) => MSG_OPEN_MINING_CHANNEL_ERROR,
+            Self::OpenExtendedMiningChannel(_) => MSG_OPEN_EXTENDED_MINING_CHANNEL,
+            Self::OpenExtendedMiningChannelSuccess(_) => MSG_OPEN_EXTENDED_MINING_CHANNEL_SUCCESS,
+            Self::NewMiningJob(_) => MSG_NEW_MINING_JOB,
+            Self::NewExtendedMiningJob(_) => MSG_NEW_EXTENDED_MINING_JOB,
+            Self::SubmitSharesStandard(_) => MSG_SUBMIT_SHARES_STANDARD,
+            Self::SubmitSharesExtended(_) => MSG_SUBMIT_SHARES_EXTENDED,
+            Self::SubmitSharesSuccess(_) => MSG_SUBMIT_SHARES_SUCCESS,
+            Self::SubmitSharesError(_) => MSG_SUBMIT_SHARES_ERROR,
+            Self::SetTarget(_) => MSG_SET_TARGET,
+        }
+    }
+
+    /// Messages addressed to a specific channel carry the channel bit in their extension type
+    fn is_channel_message(&self) -> bool {
+        matches!(
+            self,
+            Self::NewMiningJob(_)
+                | Self::NewExtendedMiningJob(_)
+                | Self::SubmitSharesStandard(_)
+                | Self::SubmitSharesExtended(_)
+                | Self::SubmitSharesSuccess(_)
+                | Self::SubmitSharesError(_)
+                | Self::SetTarget(_)
+        )
+    }
+
+    pub fn encode_payload(&self) -> Result<Vec<u8>, Sv2Error> {
+        let mut e = Encoder::default();
+        match self {
+            Self::SetupConnection(m) => {
+                e.u8(m.protocol);
+                e.u16(m.min_version);
+                e.u16(m.max_version);
+                e.u32(m.flags);
+                e.str0_255("endpoint_host", &m.endpoint_host)?;
+                e.u16(m.endpoint_port);
+                e.str0_255("vendor", &m.vendor)?;
+                e.str0_255("hardware_version", &m.hardware_version)?;
+                e.str0_255("firmware", &m.firmware)?;
+                e.str0_255("device_id", &m.device_id)?;
+            }
+            Self::SetupConnectionSuccess(m) => {
+                e.u16(m.used_version);
+                e.u32(m.flags);
+            }
+            Self::SetupConnectionError(m) => {
+                e.u32(m.flags);
+                e.str0_255("error_code", &m.error_code)?;
+            }
+            Self::OpenStandardMiningChannel(m) => {
+                e.u32(m.request_id);
+                e.str0_255("user_identity", &m.user_identity)?;
+                e.f32(m.nominal_hash_rate);
+                e.u256(&m.max_target);
+            }
+            Self::OpenStandardMiningChannelSuccess(m) => {
+                e.u32(m.request_id);
+                e.u32(m.channel_id);
+                e.u256(&m.target);
+                e.b0_255("extranonce_prefix", &m.extranonce_prefix, 32)?;
+                e.u32(m.group_channel_id);
+            }
+            Self::OpenMiningChannelError(m) => {
+                e.u32(m.request_id);
+                e.str0_255("error_code", &m.error_code)?;
+            }
+            Self::OpenExtendedMiningChannel(m) => {
+                e.u32(m.request_id);
+                e.str0_255("user_identity", &m.user_identity)?;
+                e.f32(m.nominal_hash_rate);
+                e.u256(&m.max_target);
+                e.u16(m.min_extranonce_size);
+            }
+            Self::OpenExtendedMiningChannelSuccess(m) => {
+                e.u32(m.request_id);
+                e.u32(m.channel_id);
+                e.u256(&m.target);
+                e.u16(m.extranonce_size);
+                e.b0_255("extranonce_prefix", &m.extranonce_prefix, 32)?;
+            }
+            Self::NewMiningJob(m) => {
+                e.u32(m.channel_id);
+                e.u32(m.job_id);
+                e.option_u32(m.min_ntime);
+                e.u32(m.version);
+                e.u256(&m.merkle_root);
+            }
+            Self::NewExtendedMiningJob(m) => {
+                e.u32(m.channel_id);
+                e.u32(m.job_id);
+                e.option_u32(m.min_ntime);
+                e.u32(m.version);
+                e.bool(m.version_rolling_allowed);
+                e.seq0_255_u256("merkle_path", &m.merkle_path)?;
+                e.b0_64k("coinbase_tx_prefix", &m.coinbase_tx_prefix)?;
+                e.b0_64k("coinbase_tx_suffix", &m.coinbase_tx_suffix)?;
+            }
+            Self::SubmitSharesStandard(m) => {
+                e.u32(m.channel_id);
+                e.u32(m.sequence_number);
+                e.u32(m.job_id);
+                e.u32(m.nonce);
+                e.u32(m.ntime);
+                e.u32(m.version);
+            }
+            Self::SubmitSharesExtended(m) => {
+                e.u32(m.channel_id);
+                e.u32(m.sequence_number);
+                e.u32(m.job_id);
+                e.u32(m.nonce);
+                e.u32(m.ntime);
+                e.u32(m.version);
+                e.b0_255("extranonce", &m.extranonce, 32)?;
+            }
+            Self::SubmitSharesSuccess(m) => {
+                e.u32(m.channel_id);
+                e.u32(m.last_sequence_number);
+                e.u32(m.new_submits_accepted_count);
+                e.u64(m.new_shares_sum);
+            }
+            Self::SubmitSharesError(m) => {
+                e.u32(m.channel_id);
+                e.u32(m.sequence_number);
+                e.str0_255("error_code", &m.error_code)?;
+            }
+            Self::SetTarget(m) => {
+                e.u32(m.channel_id);
+                e.u256(&m.maximum_target);
+            }
+        }
+        Ok(e.buf)
+    }
+
+    pub fn decode(msg_type: u8, payload: &[u8]) -> Result<Self, Sv2Error> {
+        let mut d = Decoder { buf: payload };
+        let message = match msg_type {
+            MSG_SETUP_CONNECTION => Self::SetupConnection(SetupConnection {
+                protocol: d.u8()?,
+                min_version: d.u16()?,
+                max_version: d.u16()?,
+                flags: d.u32()?,
+                endpoint_host: d.str0_255()?,
+                endpoint_port: d.u16()?,
+                vendor: d.str0_255()?,
+                hardware_version: d.str0_255()?,
+                firmware: d.str0_255()?,
+                device_id: d.str0_255()?,
+            }),
+            MSG_SETUP_CONNECTION_SUCCESS => {
+                Self::SetupConnectionSuccess(SetupConnectionSuccess { used_version: d.u16()?, flags: d.u32()? })
+            }
+            MSG_SETUP_CONNECTION_ERROR => {
+                Self::SetupConnectionError(SetupConnectionError { flags: d.u32()?, error_code: d.str0_255()? })
+            }
+            MSG_OPEN_STANDARD_MINING_CHANNEL => Self::OpenStandardMiningChannel(OpenStandardMiningChannel {
+                request_id: d.u32()?,
+                user_identity: d.str0_255()?,
+                nominal_hash_rate: d.f32()?,
+                max_target: d.u256()?,
+            }),
+            MSG_OPEN_STANDARD_MINING_CHANNEL_SUCCESS => Self::OpenStandardMiningChannelSuccess(OpenStandardMiningChannelSuccess {
+                request_id: d.u32()?,
+                channel_id: d.u32()?,
+                target: d.u256()?,
+                extranonce_prefix: d.b0_32("extranonce_prefix")?,
+                group_channel_id: d.u32()?,
+            }),
+            MSG_OPEN_MINING_CHANNEL_ERROR => {
+                Self::OpenMiningChannelError(OpenMiningChannelError { request_id: d.u32()?, error_code: d.str0_255()? })
+            }
+            MSG_OPEN_EXTENDED_MINING_CHANNEL => Self::OpenExtendedMiningChannel(OpenExtendedMiningChannel {
+                request_id: d.u32()?,
+                user_identity: d.str0_255()?,
+                nominal_hash_rate: d.f32()?,
+                max_target: d.u256()?,
+                min_extranonce_size: d.u16()?,
+            }),
+            MSG_OPEN_EXTENDED_MINING_CHANNEL_SUCCESS => Self::OpenExtendedMiningChannelSuccess(OpenExtendedMiningChannelSuccess {
+                request_id: d.u32()?,
+                channel_id: d.u32()?,
+                target: d.u256()?,
+                extranonce_size: d.u16()?,
+                extranonce_prefix: d.b0_32("extranonce_prefix")?,
+            }),
+            MSG_NEW_MINING_JOB => Self::NewMiningJob(NewMiningJob {
+                channel_id: d.u32()?,
+                job_id: d.u32()?,
+                min_ntime: d.option_u32()?,
+                version: d.u32()?,
+                merkle_root: d.u256()?,
+            }),
+            MSG_NEW_EXTENDED_MINING_JOB => Self::NewExtendedMiningJob(NewExtendedMiningJob {
+                channel_id: d.u32()?,
+                job_id: d.u32()?,
+                min_ntime: d.option_u32()?,
+                version: d.u32()?,
+                version_rolling_allowed: d.bool()?,
+                merkle_path: d.seq0_255_u256()?,
+                coinbase_tx_prefix: d.b0_64k()?,
+                coinbase_tx_suffix: d.b0_64k()?,
+            }),
+            MSG_SUBMIT_SHARES_STANDARD => Self::SubmitSharesStandard(SubmitSharesStandard {
+                channel_id: d.u32()?,
+                sequence_number: d.u32()?,
+                job_id: d.u32()?,
+                nonce: d.u32()?,
+                ntime: d.u32()?,
+                version: d.u32()?,
+            }),
+            MSG_SUBMIT_SHARES_EXTENDED => Self::SubmitSharesExtended(SubmitSharesExtended {
+                channel_id: d.u32()?,
+                sequence_number: d.u32()?,
+                job_id: d.u32()?,
+                nonce: d.u32()?,
+                ntime: d.u32()?,
+                version: d.u32()?,
+                extranonce: d.b0_32("extranonce")?,
+            }),
+            MSG_SUBMIT_SHARES_SUCCESS => Self::SubmitSharesSuccess(SubmitSharesSuccess {
+                channel_id: d.u32()?,
+                last_sequence_number: d.u32()?,
+                new_submits_accepted_count: d.u32()?,
+                new_shares_sum: d.u64()?,
+            }),
+            MSG_SUBMIT_SHARES_ERROR => Self::SubmitSharesError(SubmitSharesError {
+                channel_id: d.u32()?,
+                sequence_number: d.u32()?,
+                error_code: d.str0_255()?,
+            }),
+            MSG_SET_TARGET => Self::SetTarget(SetTarget { channel_id: d.u32()?, maximum_target: d.u256()? }),
+            other => return Err(Sv2Error::UnknownMessage(other)),
+        };
+        d.finish()?;
+        Ok(message)
+    }
+
+    /// Plaintext frame: header followed by the payload
+    pub fn encode_frame(&self) -> Result<Vec<u8>, Sv2Error> {
+        let payload = self.encode_payload()?;
+        let mut frame = encode_header(self.is_channel_message(), self.msg_type(), payload.len())?.to_vec();
+        frame.extend(payload);
+        Ok(frame)
+    }
+}
+
+fn encode_header(channel_message: bool, msg_type: u8, len: usize) -> Result<[u8; HEADER_SIZE], Sv2Error> {
+    if len > MAX_PAYLOAD_SIZE {
+        return Err(Sv2Error::PayloadTooLarge(len));
+    }
+    let ext_type: u16 = if channel_message { CHANNEL_MSG_BIT } else { 0 };
+    let mut header = [0u8; HEADER_SIZE];
+    header[0..2].copy_from_slice(&ext_type.to_le_bytes());
+    header[2] = msg_type;
+    header[3..6].copy_from_slice(&(len as u32).to_le_bytes()[..3]);
+    Ok(header)
+}
+
+/// Encrypts and writes one message
+pub async fn write_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    sender: &mut NoiseSender,
+    message: &Sv2Message,
+) -> Result<(), Sv2Error> {
+    let payload = message.encode_payload()?;
+    let header = encode_header(message.is_channel_message(), message.msg_type(), payload.len())?;
+    let mut frame = sender.encrypt(&header)?;
+    for chunk in payload.chunks(MAX_CHUNK_PLAINTEXT_SIZE) {
+        frame.extend(sender.encrypt(chunk)?);
+    }
+    writer.write_all(&frame).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Reads and decrypts one message. Unknown message types are consumed in full and reported as
+/// [`Sv2Error::UnknownMessage`], so the stream stays usable.
+pub async fn read_message<R: AsyncRead + Unpin>(reader: &mut R, receiver: &mut NoiseReceiver) -> Result<Sv2Message, Sv2Error> {
+    let mut encrypted_header = [0u8; ENCRYPTED_HEADER_SIZE];
+    reader.read_exact(&mut encrypted_header).await?;
+    let header = receiver.decrypt(&encrypted_header)?;
+    let ext_type = u16::from_le_bytes([header[0], header[1]]);
+    let msg_type = header[2];
+    let len = u32::from_le_bytes([header[3], header[4], header[5], 0]) as usize;
+    if len > MAX_PAYLOAD_SIZE {
+        return Err(Sv2Error::PayloadTooLarge(len));
+    }
+
+    let mut payload = Vec::with_capacity(len);
+    let mut remaining = len;
+    while remaining > 0 {
+        let chunk_len = remaining.min(MAX_CHUNK_PLAINTEXT_SIZE);
+        let mut chunk = vec![0u8; chunk_len + MAC_SIZE];
+        reader.read_exact(&mut chunk).await?;
+        payload.extend(receiver.decrypt(&chunk)?);
+        remaining -= chunk_len;
+    }
+
+    if ext_type & !CHANNEL_MSG_BIT != 0 {
+        return Err(Sv2Error::UnsupportedExtension(ext_type & !CHANNEL_MSG_BIT));
+    }
+    Sv2Message::decode(msg_type, &payload)
+}
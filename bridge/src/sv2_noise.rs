@@ -0,0 +1,358 @@
+//! Noise handshake and transport encryption for Stratum V2 connections.
+//!
+//! Implements `Noise_NX_Secp256k1+EllSwift_ChaChaPoly_SHA256` as specified by Stratum V2: the client (initiator)
+//! sends an ephemeral key, the bridge (responder) answers with its own ephemeral key, its encrypted static key
+//! and a certificate signed by the pool authority key. Clients that know the authority public key can therefore
+//! verify that jobs really come from this bridge.
+
+use crate::time_utils::unix_now;
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+use hmac::{Hmac, Mac};
+use secp256k1::ellswift::{ElligatorSwift, ElligatorSwiftParty};
+use secp256k1::{Keypair, Message, PublicKey, SECP256K1, SecretKey, XOnlyPublicKey, schnorr};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use thiserror::Error;
+
+pub const PROTOCOL_NAME: &[u8] = b"Noise_NX_Secp256k1+EllSwift_ChaChaPoly_SHA256";
+pub const ELLSWIFT_KEY_SIZE: usize = 64;
+pub const MAC_SIZE: usize = 16;
+pub const SIGNATURE_MESSAGE_SIZE: usize = 74;
+pub const INITIATOR_MESSAGE_SIZE: usize = ELLSWIFT_KEY_SIZE;
+pub const RESPONDER_MESSAGE_SIZE: usize = ELLSWIFT_KEY_SIZE + ELLSWIFT_KEY_SIZE + MAC_SIZE + SIGNATURE_MESSAGE_SIZE + MAC_SIZE;
+
+const CERTIFICATE_VERSION: u16 = 0;
+
+#[derive(Debug, Error)]
+pub enum NoiseError {
+    #[error("invalid handshake message length {0}")]
+    InvalidLength(usize),
+    #[error("failed to encrypt noise message")]
+    Encrypt,
+    #[error("failed to decrypt noise message")]
+    Decrypt,
+    #[error("invalid certificate signature")]
+    InvalidCertificate,
+    #[error("certificate is not valid at this time")]
+    CertificateExpired,
+    #[error("noise nonce exhausted")]
+    NonceExhausted,
+}
+
+fn sha256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+/// HKDF with two outputs, as defined by the Noise specification
+fn hkdf2(chaining_key: &[u8; 32], input_key_material: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let temp_key = hmac_sha256(chaining_key, &[input_key_material]);
+    let output1 = hmac_sha256(&temp_key, &[&[0x01]]);
+    let output2 = hmac_sha256(&temp_key, &[&output1, &[0x02]]);
+    (output1, output2)
+}
+
+/// BIP324-style x-only ECDH between an initiator key and a responder key
+fn ecdh(initiator: ElligatorSwift, responder: ElligatorSwift, secret: SecretKey, party: ElligatorSwiftParty) -> [u8; 32] {
+    ElligatorSwift::shared_secret(initiator, responder, secret, party, None).to_secret_bytes()
+}
+
+fn generate_keypair() -> Keypair {
+    Keypair::new(SECP256K1, &mut secp256k1::rand::thread_rng())
+}
+
+/// ChaChaPoly cipher with the Noise nonce counter
+struct CipherState {
+    key: Option<[u8; 32]>,
+    nonce: u64,
+}
+
+impl CipherState {
+    fn empty() -> Self {
+        Self { key: None, nonce: 0 }
+    }
+
+    fn with_key(key: [u8; 32]) -> Self {
+        Self { key: Some(key), nonce: 0 }
+    }
+
+    fn next_nonce(&mut self) -> Result<Nonce, NoiseError> {
+        if self.nonce == u64::MAX {
+            return Err(NoiseError::NonceExhausted);
+        }
+        let mut nonce = [0u8; 12];
+        nonce[4..].copy_from_slice(&self.nonce.to_le_bytes());
+        self.nonce += 1;
+        Ok(*Nonce::from_slice(&nonce))
+    }
+
+    fn encrypt_with_ad(&mut self, ad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let Some(key) = self.key else {
+            return Ok(plaintext.to_vec());
+        };
+        let nonce = self.next_nonce()?;
+        ChaCha20Poly1305::new(Key::from_slice(&key))
+            .encrypt(&nonce, Payload { msg: plaintext, aad: ad })
+            .map_err(|_| NoiseError::Encrypt)
+    }
+
+    fn decrypt_with_ad(&mut self, ad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let Some(key) = self.key else {
+            return Ok(ciphertext.to_vec());
+        };
+        let nonce = self.next_nonce()?;
+        ChaCha20Poly1305::new(Key::from_slice(&key))
+            .decrypt(&nonce, Payload { msg: ciphertext, aad: ad })
+            .map_err(|_| NoiseError::Decrypt)
+    }
+}
+
+/// Handshake hash and chaining key shared by both sides of the handshake
+struct SymmetricState {
+    chaining_key: [u8; 32],
+    hash: [u8; 32],
+    cipher: CipherState,
+}
+
+impl SymmetricState {
+    fn new() -> Self {
+        // The protocol name is longer than 32 bytes, so it is hashed
+        let hash = sha256(&[PROTOCOL_NAME]);
+        let mut state = Self { chaining_key: hash, hash, cipher: CipherState::empty() };
+        state.mix_hash(&[]); // Empty prologue
+        state
+    }
+
+    fn mix_hash(&mut self, data: &[u8]) {
+        self.hash = sha256(&[&self.hash, data]);
+    }
+
+    fn mix_key(&mut self, input_key_material: &[u8]) {
+        let (chaining_key, key) = hkdf2(&self.chaining_key, input_key_material);
+        self.chaining_key = chaining_key;
+        self.cipher = CipherState::with_key(key);
+    }
+
+    fn encrypt_and_hash(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let ciphertext = self.cipher.encrypt_with_ad(&self.hash, plaintext)?;
+        self.mix_hash(&ciphertext);
+        Ok(ciphertext)
+    }
+
+    fn decrypt_and_hash(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        let plaintext = self.cipher.decrypt_with_ad(&self.hash, ciphertext)?;
+        self.mix_hash(ciphertext);
+        Ok(plaintext)
+    }
+
+    /// Initiator-to-responder and responder-to-initiator ciphers
+    fn split(&self) -> (CipherState, CipherState) {
+        let (initiator_key, responder_key) = hkdf2(&self.chaining_key, &[]);
+        (CipherState::with_key(initiator_key), CipherState::with_key(responder_key))
+    }
+}
+
+/// Certificate sent by the responder: the authority's signature over the responder static key and a validity period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureNoiseMessage {
+    pub version: u16,
+    pub valid_from: u32,
+    pub not_valid_after: u32,
+    pub signature: [u8; 64],
+}
+
+impl SignatureNoiseMessage {
+    fn signed_hash(version: u16, valid_from: u32, not_valid_after: u32, server_key: &XOnlyPublicKey) -> [u8; 32] {
+        sha256(&[&version.to_le_bytes(), &valid_from.to_le_bytes(), &not_valid_after.to_le_bytes(), &server_key.serialize()])
+    }
+
+    /// Signs `server_key` with the authority key, valid from now for `validity`
+    pub fn sign(authority: &Keypair, server_key: &XOnlyPublicKey, validity: Duration) -> Self {
+        let valid_from = unix_now() as u32;
+        let not_valid_after = valid_from.saturating_add(validity.as_secs().min(u32::MAX as u64) as u32);
+        let hash = Self::signed_hash(CERTIFICATE_VERSION, valid_from, not_valid_after, server_key);
+        let signature: [u8; 64] = *authority.sign_schnorr(Message::from_digest(hash)).as_ref();
+        Self { version: CERTIFICATE_VERSION, valid_from, not_valid_after, signature }
+    }
+
+    /// Checks the signature against the authority key and the validity period against `now`
+    pub fn verify(&self, server_key: &XOnlyPublicKey, authority: &XOnlyPublicKey, now: u32) -> Result<(), NoiseError> {
+        let hash = Self::signed_hash(self.version, self.valid_from, self.not_valid_after, server_key);
+        let signature = schnorr::Signature::from_slice(&self.signature).map_err(|_| NoiseError::InvalidCertificate)?;
+        SECP256K1.verify_schnorr(&signature, &Message::from_digest(hash), authority).map_err(|_| NoiseError::InvalidCertificate)?;
+        if now < self.valid_from || now > self.not_valid_after {
+            return Err(NoiseError::CertificateExpired);
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_MESSAGE_SIZE] {
+        let mut bytes = [0u8; SIGNATURE_MESSAGE_SIZE];
+        bytes[0..2].copy_from_slice(&self.version.to_le_bytes());
+        bytes[2..6].copy_from_slice(&self.valid_from.to_le_bytes());
+        bytes[6..10].copy_from_slice(&self.not_valid_after.to_le_bytes());
+        bytes[10..].copy_from_slice(&self.signature);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, NoiseError> {
+        if bytes.len() != SIGNATURE_MESSAGE_SIZE {
+            return Err(NoiseError::InvalidLength(bytes.len()));
+        }
+        let mut signature = [0u8; 64];
+        signature.copy_from_slice(&bytes[10..]);
+        Ok(Self {
+            version: u16::from_le_bytes([bytes[0], bytes[1]]),
+            valid_from: u32::from_le_bytes(bytes[2..6].try_into().unwrap()),
+            not_valid_after: u32::from_le_bytes(bytes[6..10].try_into().unwrap()),
+            signature,
+        })
+    }
+}
+
+/// Encrypts outgoing transport messages after the handshake
+pub struct NoiseSender {
+    cipher: CipherState,
+}
+
+impl NoiseSender {
+    pub fn encrypt(&mut self, plaintext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        self.cipher.encrypt_with_ad(&[], plaintext)
+    }
+}
+
+/// Decrypts incoming transport messages after the handshake
+pub struct NoiseReceiver {
+    cipher: CipherState,
+}
+
+impl NoiseReceiver {
+    pub fn decrypt(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>, NoiseError> {
+        self.cipher.decrypt_with_ad(&[], ciphertext)
+    }
+}
+
+/// Transport ciphers of a completed handshake, split so that reading and writing can happen on different tasks
+pub struct NoiseTransport {
+    pub sender: NoiseSender,
+    pub receiver: NoiseReceiver,
+}
+
+/// Bridge side of the handshake
+pub struct Responder {
+    static_keypair: Keypair,
+    certificate: SignatureNoiseMessage,
+}
+
+impl Responder {
+    pub fn new(static_keypair: Keypair, certificate: SignatureNoiseMessage) -> Self {
+        Self { static_keypair, certificate }
+    }
+
+    /// Generates a fresh static key and signs it with the authority key
+    pub fn with_authority(authority: &Keypair, validity: Duration) -> Self {
+        let static_keypair = generate_keypair();
+        let certificate = SignatureNoiseMessage::sign(authority, &static_keypair.x_only_public_key().0, validity);
+        Self::new(static_keypair, certificate)
+    }
+
+    pub fn certificate(&self) -> &SignatureNoiseMessage {
+        &self.certificate
+    }
+
+    /// Processes the initiator's ephemeral key and returns the reply message with the transport ciphers
+    pub fn respond(&self, message: &[u8]) -> Result<(Vec<u8>, NoiseTransport), NoiseError> {
+        let initiator_ephemeral: [u8; ELLSWIFT_KEY_SIZE] = message.try_into().map_err(|_| NoiseError::InvalidLength(message.len()))?;
+        let initiator_ephemeral = ElligatorSwift::from_array(initiator_ephemeral);
+
+        let mut state = SymmetricState::new();
+        state.mix_hash(message);
+        state.mix_hash(&[]); // Empty payload of the first message
+
+        let mut reply = Vec::with_capacity(RESPONDER_MESSAGE_SIZE);
+
+        // -> e, ee
+        let ephemeral = generate_keypair();
+        let ephemeral_public = ElligatorSwift::from_pubkey(ephemeral.public_key());
+        reply.extend_from_slice(&ephemeral_public.to_array());
+        state.mix_hash(&ephemeral_public.to_array());
+        state.mix_key(&ecdh(initiator_ephemeral, ephemeral_public, ephemeral.secret_key(), ElligatorSwiftParty::B));
+
+        // -> s, es
+        let static_public = ElligatorSwift::from_pubkey(self.static_keypair.public_key());
+        reply.extend(state.encrypt_and_hash(&static_public.to_array())?);
+        state.mix_key(&ecdh(initiator_ephemeral, static_public, self.static_keypair.secret_key(), ElligatorSwiftParty::B));
+
+        // Certificate payload
+        reply.extend(state.encrypt_and_hash(&self.certificate.to_bytes())?);
+
+        let (initiator_cipher, responder_cipher) = state.split();
+        Ok((
+            reply,
+            NoiseTransport { sender: NoiseSender { cipher: responder_cipher }, receiver: NoiseReceiver { cipher: initiator_cipher } },
+        ))
+    }
+}
+
+/// Miner side of the handshake
+pub struct Initiator {
+    authority: Option<XOnlyPublicKey>,
+    state: SymmetricState,
+    ephemeral: Keypair,
+    ephemeral_public: ElligatorSwift,
+}
+
+impl Initiator {
+    /// `authority` is the pool authority key used to verify the responder certificate, if known
+    pub fn new(authority: Option<XOnlyPublicKey>) -> Self {
+        let ephemeral = generate_keypair();
+        let ephemeral_public = ElligatorSwift::from_pubkey(ephemeral.public_key());
+        Self { authority, state: SymmetricState::new(), ephemeral, ephemeral_public }
+    }
+
+    pub fn first_message(&mut self) -> [u8; INITIATOR_MESSAGE_SIZE] {
+        let message = self.ephemeral_public.to_array();
+        self.state.mix_hash(&message);
+        self.state.mix_hash(&[]);
+        message
+    }
+
+    /// Processes the responder reply, verifying its certificate, and returns the transport ciphers
+    pub fn finish(mut self, message: &[u8]) -> Result<NoiseTransport, NoiseError> {
+        if message.len() != RESPONDER_MESSAGE_SIZE {
+            return Err(NoiseError::InvalidLength(message.len()));
+        }
+        let (responder_ephemeral, rest) = message.split_at(ELLSWIFT_KEY_SIZE);
+        let (encrypted_static, encrypted_certificate) = rest.split_at(ELLSWIFT_KEY_SIZE + MAC_SIZE);
+
+        let responder_ephemeral_key = ElligatorSwift::from_array(responder_ephemeral.try_into().unwrap());
+        self.state.mix_hash(responder_ephemeral);
+        self.state.mix_key(&ecdh(self.ephemeral_public, responder_ephemeral_key, self.ephemeral.secret_key(), ElligatorSwiftParty::A));
+
+        let responder_static: [u8; ELLSWIFT_KEY_SIZE] =
+            self.state.decrypt_and_hash(encrypted_static)?.try_into().map_err(|_| NoiseError::Decrypt)?;
+        let responder_static = ElligatorSwift::from_array(responder_static);
+        self.state.mix_key(&ecdh(self.ephemeral_public, responder_static, self.ephemeral.secret_key(), ElligatorSwiftParty::A));
+
+        let certificate = SignatureNoiseMessage::from_bytes(&self.state.decrypt_and_hash(encrypted_certificate)?)?;
+        if let Some(authority) = &self.authority {
+            let server_key = PublicKey::from_ellswift(responder_static).x_only_public_key().0;
+            certificate.verify(&server_key, authority, unix_now() as u32)?;
+        }
+
+        let (initiator_cipher, responder_cipher) = self.state.split();
+        Ok(NoiseTransport { sender: NoiseSender { cipher: initiator_cipher }, receiver: NoiseReceiver { cipher: responder_cipher } })
+    }
+}
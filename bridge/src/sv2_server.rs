@@ -0,0 +1,742 @@
+//! Stratum V2 listener.
+//!
+//! Serves the Stratum V2 mining protocol over Noise-encrypted connections next to the JSON stratum port of an
+//! instance, with the same difficulty tier. Standard and extended channels are supported; each channel owns a
+//! `MiningState` filled from the node's block templates exactly like a Stratum v1 client.
+//!
+//! Kaspa headers map onto Stratum V2 jobs as follows: the pre-PoW hash is sent as the job merkle root (or as the
+//! coinbase prefix of extended jobs, with an empty merkle path), `ntime` is the header timestamp in seconds and
+//! the 64-bit Kaspa nonce is the 4-byte extranonce (channel prefix, plus the miner part on extended channels)
+//! followed by the 32-bit share nonce.
+
+use crate::{
+    app_config::Sv2Config,
+    default_client::clean_wallet,
+    errors::ErrorShortCode,
+    hasher::{diff_to_target, serialize_block_header},
+    log_colors::LogColors,
+    mining_state::{Job, MiningState},
+    net_utils::bind_addr_from_port,
+    pool::PoolMode,
+    prom::*,
+    share_handler::{KaspaApiTrait, confirm_found_block},
+    sv2_messages::*,
+    sv2_noise::{INITIATOR_MESSAGE_SIZE, NoiseSender, NoiseTransport, Responder},
+};
+use anyhow::Context;
+use kaspa_consensus_core::block::Block;
+use kaspa_pow::State as PowState;
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+use parking_lot::Mutex;
+use secp256k1::{Keypair, SECP256K1};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, WriteHalf};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, error, info, warn};
+
+pub const SV2_PROTOCOL_VERSION: u16 = 2;
+pub const MINING_PROTOCOL: u8 = 0;
+const SV2_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// SetupConnection flags of the mining protocol
+const REQUIRES_WORK_SELECTION: u32 = 1 << 1;
+const REQUIRES_VERSION_ROLLING: u32 = 1 << 2;
+// SetupConnection.Success flag: the header version cannot be rolled
+const REQUIRES_FIXED_VERSION: u32 = 1 << 0;
+
+/// Extranonce bytes assigned by the bridge on extended channels; the miner controls the rest of the high nonce word.
+/// Standard channels get the whole 4-byte high word and only roll the 32-bit share nonce and ntime, so a fresh job is
+/// sent with every template; miners hashing faster than that should open extended channels.
+const EXTENDED_PREFIX_SIZE: usize = 2;
+const EXTENDED_EXTRANONCE_SIZE: usize = 4 - EXTENDED_PREFIX_SIZE;
+/// Channels a single connection may keep open
+const MAX_CHANNELS_PER_CONNECTION: usize = 64;
+/// How far past the template timestamp a share's ntime may be rolled, in seconds
+const MAX_NTIME_ROLL_SECS: u32 = 60;
+/// Submitted shares remembered per channel for duplicate detection
+const MAX_TRACKED_SHARES: usize = 10_000;
+
+/// Kaspa nonce of a share: the 4-byte extranonce as the high word, the share nonce as the low word
+pub fn compose_nonce(extranonce: &[u8; 4], nonce: u32) -> u64 {
+    ((u32::from_be_bytes(*extranonce) as u64) << 32) | nonce as u64
+}
+
+/// Stratum V2 targets are 256-bit little-endian integers
+pub fn target_to_u256(target: &BigUint) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let le = target.to_bytes_le();
+    let len = le.len().min(32);
+    bytes[..len].copy_from_slice(&le[..len]);
+    bytes
+}
+
+/// Loads the authority key from the configured environment variable, or generates an ephemeral one.
+/// Returns the handshake responder with a freshly signed certificate.
+pub fn load_responder(config: &Sv2Config) -> anyhow::Result<Responder> {
+    let authority = match std::env::var(&config.authority_key_env) {
+        Ok(hex_key) if !hex_key.trim().is_empty() => {
+            let secret = hex::decode(hex_key.trim()).context("authority key is not valid hex")?;
+            Keypair::from_seckey_slice(SECP256K1, &secret).context("invalid authority secret key")?
+        }
+        _ => {
+            let keypair = Keypair::new(SECP256K1, &mut secp256k1::rand::thread_rng());
+            warn!(
+                "{} {} is not set, using an ephemeral authority key; miners cannot pin it across restarts",
+                LogColors::label("[SV2]"),
+                config.authority_key_env
+            );
+            keypair
+        }
+    };
+    info!("{} authority public key: {}", LogColors::label("[SV2]"), hex::encode(authority.x_only_public_key().0.serialize()));
+    Ok(Responder::with_authority(&authority, config.certificate_validity))
+}
+
+/// Extranonce prefix slots of the open channels. Each channel owns a distinct non-zero 16-bit slot: extended
+/// channels use it as their 2-byte prefix and standard channels as the top half of their 4-byte prefix, so no two
+/// open channels search the same nonces.
+#[derive(Default)]
+struct ExtranonceSlots {
+    used: HashSet<u16>,
+    next: u16,
+}
+
+impl ExtranonceSlots {
+    /// Returns a free slot, or None once every slot is taken
+    fn allocate(&mut self) -> Option<u16> {
+        if self.used.len() >= u16::MAX as usize {
+            return None;
+        }
+        loop {
+            self.next = self.next.wrapping_add(1).max(1);
+            if self.used.insert(self.next) {
+                return Some(self.next);
+            }
+        }
+    }
+
+    fn release(&mut self, slot: u16) {
+        self.used.remove(&slot);
+    }
+}
+
+/// A mining channel opened by a Stratum V2 client
+struct Sv2Channel {
+    extended: bool,
+    extranonce_slot: u16,
+    wallet: String,
+    worker: String,
+    extranonce_prefix: Vec<u8>,
+    target: BigUint,
+    share_diff: f64,
+    state: Arc<MiningState>,
+    seen_shares: HashSet<(u32, u64, u32)>,
+}
+
+impl Sv2Channel {
+    fn worker_context(&self, instance_id: &str, remote: &str) -> WorkerContext {
+        WorkerContext {
+            instance_id: instance_id.to_string(),
+            worker_name: self.worker.clone(),
+            miner: "sv2".to_string(),
+            wallet: self.wallet.clone(),
+            ip: remote.to_string(),
+        }
+    }
+}
+
+pub struct Sv2Server {
+    instance_id: String,
+    min_share_diff: f64,
+    pool: Option<Arc<PoolMode>>,
+    responder: Responder,
+    templates: watch::Sender<u64>,
+    next_channel_id: AtomicU32,
+    extranonce_slots: Mutex<ExtranonceSlots>,
+}
+
+impl Sv2Server {
+    pub fn new(instance_id: String, min_share_diff: f64, responder: Responder, pool: Option<Arc<PoolMode>>) -> Arc<Self> {
+        Arc::new(Self {
+            instance_id,
+            min_share_diff,
+            pool,
+            responder,
+            templates: watch::channel(0).0,
+            next_channel_id: AtomicU32::new(1),
+            extranonce_slots: Mutex::new(ExtranonceSlots::default()),
+        })
+    }
+
+    /// Sends a fresh job to every open channel
+    pub fn new_block_available(&self) {
+        self.templates.send_modify(|generation| *generation = generation.wrapping_add(1));
+    }
+
+    /// Share target of a new channel and the difficulty credited for each share. The client may ask for a
+    /// harder target than the instance difficulty, in which case shares are credited proportionally more.
+    fn channel_target(&self, max_target: &[u8; 32]) -> (BigUint, f64) {
+        let max_u256 = (BigUint::one() << 256u32) - 1u32;
+        let target = diff_to_target(self.min_share_diff).min(max_u256);
+        let client_max = BigUint::from_bytes_le(max_target);
+        if client_max.is_zero() || target <= client_max {
+            return (target, self.min_share_diff);
+        }
+        let ratio = target.to_f64().unwrap_or(1.0) / client_max.to_f64().unwrap_or(1.0);
+        (client_max, self.min_share_diff * ratio)
+    }
+
+    /// Accepts Stratum V2 connections on `port` until shutdown
+    pub async fn listen<T: KaspaApiTrait + Send + Sync + 'static>(
+        self: Arc<Self>,
+        port: String,
+        kaspa_api: Arc<T>,
+        mut shutdown_rx: Option<watch::Receiver<bool>>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let listener =
+            TcpListener::bind(bind_addr_from_port(&port)).await.map_err(|e| format!("failed listening to socket {}: {}", port, e))?;
+        info!("{} Stratum V2 listener started on {}", self.instance_id, port);
+
+        loop {
+            let accepted = if let Some(ref mut rx) = shutdown_rx {
+                tokio::select! {
+                    _ = rx.changed() => {
+                        if *rx.borrow() {
+                            break;
+                        }
+                        continue;
+                    }
+                    result = listener.accept() => result,
+                }
+            } else {
+                listener.accept().await
+            };
+
+            let (stream, addr) = match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    error!("[SV2] Failed to accept connection: {} (kind: {:?})", e, e.kind());
+                    continue;
+                }
+            };
+            let _ = stream.set_nodelay(true);
+            debug!("[SV2] new client connecting - {}", addr);
+
+            let server = Arc::clone(&self);
+            let kaspa_api = Arc::clone(&kaspa_api);
+            tokio::spawn(async move {
+                if let Err(e) = server.serve_connection(stream, addr.to_string(), kaspa_api).await {
+                    debug!("[SV2] connection {} closed: {}", addr, e);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Runs the handshake and the mining protocol on an accepted connection
+    pub async fn serve_connection<S, T>(self: Arc<Self>, stream: S, remote: String, kaspa_api: Arc<T>) -> Result<(), Sv2Error>
+    where
+        S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+        T: KaspaApiTrait + Send + Sync + 'static,
+    {
+        let (mut reader, mut writer) = tokio::io::split(stream);
+
+        let mut handshake = [0u8; INITIATOR_MESSAGE_SIZE];
+        tokio::time::timeout(SV2_HANDSHAKE_TIMEOUT, reader.read_exact(&mut handshake))
+            .await
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "noise handshake timed out"))??;
+        let (reply, NoiseTransport { sender, mut receiver }) = self.responder.respond(&handshake)?;
+        writer.write_all(&reply).await?;
+        writer.flush().await?;
+
+        let (message_tx, mut message_rx) = mpsc::unbounded_channel();
+        let reader_remote = remote.clone();
+        let reader_task = tokio::spawn(async move {
+            loop {
+                match read_message(&mut reader, &mut receiver).await {
+                    Ok(message) => {
+                        if message_tx.send(message).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e @ (Sv2Error::UnknownMessage(_) | Sv2Error::UnsupportedExtension(_))) => {
+                        debug!("[SV2] ignoring message from {}: {}", reader_remote, e);
+                    }
+                    Err(e) => {
+                        debug!("[SV2] read from {} failed: {}", reader_remote, e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut templates = self.templates.subscribe();
+        let mut connection =
+            Sv2Connection { server: self, kaspa_api, remote, writer, sender, setup_done: false, channels: HashMap::new() };
+        let result = loop {
+            tokio::select! {
+                message = message_rx.recv() => {
+                    let Some(message) = message else {
+                        break Ok(());
+                    };
+                    if let Err(e) = connection.handle_message(message).await {
+                        break Err(e);
+                    }
+                }
+                changed = templates.changed() => {
+                    if changed.is_err() {
+                        break Ok(());
+                    }
+                    if let Err(e) = connection.send_jobs().await {
+                        break Err(e);
+                    }
+                }
+            }
+        };
+
+        reader_task.abort();
+        let mut slots = connection.server.extranonce_slots.lock();
+        for channel in connection.channels.values() {
+            slots.release(channel.extranonce_slot);
+            record_disconnect(&channel.worker_context(&connection.server.instance_id, &connection.remote));
+        }
+        drop(slots);
+        info!("[SV2] client disconnected - {}", connection.remote);
+        result
+    }
+}
+
+/// Outcome of a share that passed validation
+struct AcceptedShare {
+    share_diff: f64,
+    block: Option<Block>,
+}
+
+struct Sv2Connection<S, T> {
+    server: Arc<Sv2Server>,
+    kaspa_api: Arc<T>,
+    remote: String,
+    writer: WriteHalf<S>,
+    sender: NoiseSender,
+    setup_done: bool,
+    channels: HashMap<u32, Sv2Channel>,
+}
+
+impl<S, T> Sv2Connection<S, T>
+where
+    S: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    T: KaspaApiTrait + Send + Sync + 'static,
+{
+    async fn send(&mut self, message: Sv2Message) -> Result<(), Sv2Error> {
+        write_message(&mut self.writer, &mut self.sender, &message).await
+    }
+
+    async fn handle_message(&mut self, message: Sv2Message) -> Result<(), Sv2Error> {
+        match message {
+            Sv2Message::SetupConnection(m) => self.handle_setup_connection(m).await,
+            Sv2Message::OpenStandardMiningChannel(m) => self.open_channel(m.request_id, &m.user_identity, &m.max_target, None).await,
+            Sv2Message::OpenExtendedMiningChannel(m) => {
+                self.open_channel(m.request_id, &m.user_identity, &m.max_target, Some(m.min_extranonce_size)).await
+            }
+            Sv2Message::SubmitSharesStandard(m) => {
+                self.handle_submit(m.channel_id, m.sequence_number, m.job_id, m.nonce, m.ntime, None).await
+            }
+            Sv2Message::SubmitSharesExtended(m) => {
+                self.handle_submit(m.channel_id, m.sequence_number, m.job_id, m.nonce, m.ntime, Some(m.extranonce)).await
+            }
+            other => {
+                debug!("[SV2] unexpected message {:#04x} from {}", other.msg_type(), self.remote);
+                Ok(())
+            }
+        }
+    }
+
+    async fn handle_setup_connection(&mut self, m: SetupConnection) -> Result<(), Sv2Error> {
+        let error_code = if m.protocol != MINING_PROTOCOL {
+            Some("unsupported-protocol")
+        } else if !(m.min_version..=m.max_version).contains(&SV2_PROTOCOL_VERSION) {
+            Some("protocol-version-mismatch")
+        } else if m.flags & (REQUIRES_WORK_SELECTION | REQUIRES_VERSION_ROLLING) != 0 {
+            Some("unsupported-feature-flags")
+        } else {
+            None
+        };
+
+        if let Some(error_code) = error_code {
+            self.send(Sv2Message::SetupConnectionError(SetupConnectionError { flags: 0, error_code: error_code.to_string() })).await?;
+            return Err(Sv2Error::Io(std::io::Error::other(format!("setup connection rejected: {}", error_code))));
+        }
+
+        info!("[SV2] setup connection from {} - vendor='{}' firmware='{}'", self.remote, m.vendor, m.firmware);
+        self.setup_done = true;
+        self.send(Sv2Message::SetupConnectionSuccess(SetupConnectionSuccess {
+            used_version: SV2_PROTOCOL_VERSION,
+            flags: REQUIRES_FIXED_VERSION,
+        }))
+        .await
+    }
+
+    /// Opens a standard channel, or an extended one when `min_extranonce_size` is given
+    async fn open_channel(
+        &mut self,
+        request_id: u32,
+        user_identity: &str,
+        max_target: &[u8; 32],
+        min_extranonce_size: Option<u16>,
+    ) -> Result<(), Sv2Error> {
+        let error_code = if !self.setup_done {
+            Some("setup-connection-required")
+        } else if min_extranonce_size.is_some_and(|size| size as usize > EXTENDED_EXTRANONCE_SIZE) {
+            Some("min-extranonce-size-too-large")
+        } else if self.channels.len() >= MAX_CHANNELS_PER_CONNECTION {
+            Some("too-many-channels")
+        } else {
+            None
+        };
+        if let Some(error_code) = error_code {
+            return self
+                .send(Sv2Message::OpenMiningChannelError(OpenMiningChannelError { request_id, error_code: error_code.to_string() }))
+                .await;
+        }
+
+        // user_identity follows the v1 authorize format: wallet[.worker]
+        let mut parts = user_identity.splitn(2, '.');
+        let wallet = clean_wallet(parts.next().unwrap_or_default()).ok();
+        let wallet = match wallet {
+            Some(wallet) => wallet,
+            None => {
+                record_worker_error(&self.server.instance_id, user_identity, ErrorShortCode::InvalidAddressFmt.as_str());
+                return self
+                    .send(Sv2Message::OpenMiningChannelError(OpenMiningChannelError {
+                        request_id,
+                        error_code: "invalid-user-identity".to_string(),
+                    }))
+                    .await;
+            }
+        };
+        let Some(extranonce_slot) = self.server.extranonce_slots.lock().allocate() else {
+            warn!("[SV2] no free extranonce prefix for a channel from {}", self.remote);
+            return self
+                .send(Sv2Message::OpenMiningChannelError(OpenMiningChannelError {
+                    request_id,
+                    error_code: "extranonce-prefixes-exhausted".to_string(),
+                }))
+                .await;
+        };
+        let channel_id = self.server.next_channel_id.fetch_add(1, Ordering::Relaxed);
+        let worker = parts.next().filter(|w| !w.is_empty()).map(str::to_string).unwrap_or_else(|| format!("sv2-{}", channel_id));
+
+        let extended = min_extranonce_size.is_some();
+        let extranonce_prefix = if extended {
+            extranonce_slot.to_be_bytes()[..EXTENDED_PREFIX_SIZE].to_vec()
+        } else {
+            ((extranonce_slot as u32) << 16).to_be_bytes().to_vec()
+        };
+        let (target, share_diff) = self.server.channel_target(max_target);
+        let reply_target = target_to_u256(&target);
+        // Tracked before replying so the slot is released with the connection whatever happens next
+        self.channels.insert(
+            channel_id,
+            Sv2Channel {
+                extended,
+                extranonce_slot,
+                wallet: wallet.clone(),
+                worker: worker.clone(),
+                extranonce_prefix: extranonce_prefix.clone(),
+                target,
+                share_diff,
+                state: Arc::new(MiningState::new()),
+                seen_shares: HashSet::new(),
+            },
+        );
+
+        let reply = if extended {
+            Sv2Message::OpenExtendedMiningChannelSuccess(OpenExtendedMiningChannelSuccess {
+                request_id,
+                channel_id,
+                target: reply_target,
+                extranonce_size: EXTENDED_EXTRANONCE_SIZE as u16,
+                extranonce_prefix,
+            })
+        } else {
+            Sv2Message::OpenStandardMiningChannelSuccess(OpenStandardMiningChannelSuccess {
+                request_id,
+                channel_id,
+                target: reply_target,
+                extranonce_prefix,
+                group_channel_id: 0,
+            })
+        };
+        self.send(reply).await?;
+
+        info!(
+            "[SV2] {} channel {} opened for {} worker='{}' diff={}",
+            if extended { "extended" } else { "standard" },
+            channel_id,
+            self.remote,
+            worker,
+            share_diff
+        );
+        match self.fetch_template(&wallet).await {
+            Some(template) => self.send_job(channel_id, template).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Sends a job to every open channel, fetching one template per payout address; in pool mode all
+    /// channels share a single template
+    async fn send_jobs(&mut self) -> Result<(), Sv2Error> {
+        let mut templates: HashMap<String, Option<Block>> = HashMap::new();
+        let channels: Vec<(u32, String)> = self.channels.iter().map(|(id, channel)| (*id, channel.wallet.clone())).collect();
+        for (channel_id, wallet) in channels {
+            let address = self.template_address(&wallet);
+            let template = match templates.get(&address) {
+                Some(template) => template.clone(),
+                None => {
+                    let template = self.fetch_template(&wallet).await;
+                    templates.insert(address, template.clone());
+                    template
+                }
+            };
+            if let Some(template) = template {
+                self.send_job(channel_id, template).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Address paid by the templates of a channel mining to `wallet`
+    fn template_address(&self, wallet: &str) -> String {
+        self.server.pool.as_ref().map(|pool| pool.config.address.clone()).unwrap_or_else(|| wallet.to_string())
+    }
+
+    /// Fetches a template paying the channel's wallet (or the pool address)
+    async fn fetch_template(&self, wallet: &str) -> Option<Block> {
+        match self.kaspa_api.get_block_template(&self.template_address(wallet), "", "").await {
+            Ok(template) => Some(template),
+            Err(e) => {
+                warn!("[SV2] failed fetching block template for {}: {}", self.remote, e);
+                record_worker_error(&self.server.instance_id, wallet, ErrorShortCode::FailedBlockFetch.as_str());
+                None
+            }
+        }
+    }
+
+    /// Stores the template in the channel's mining state and sends it as a job
+    async fn send_job(&mut self, channel_id: u32, template: Block) -> Result<(), Sv2Error> {
+        let Some(channel) = self.channels.get(&channel_id) else {
+            return Ok(());
+        };
+        let state = Arc::clone(&channel.state);
+        let extended = channel.extended;
+
+        // Share timestamps are whole seconds, so the template timestamp is rounded down to match
+        let mut header = (*template.header).clone();
+        header.timestamp -= header.timestamp % 1000;
+        header.finalize();
+        let block = Block::from_arcs(Arc::new(header), template.transactions);
+        let pre_pow_hash = match serialize_block_header(&block) {
+            Ok(hash) => hash,
+            Err(e) => {
+                warn!("[SV2] failed serializing block header for channel {}: {}", channel_id, e);
+                return Ok(());
+            }
+        };
+        let min_ntime = (block.header.timestamp / 1000) as u32;
+        let version = block.header.version as u32;
+        let job_id = state.add_job(Job { block, pre_pow_hash }) as u32;
+
+        let job = if extended {
+            Sv2Message::NewExtendedMiningJob(NewExtendedMiningJob {
+                channel_id,
+                job_id,
+                min_ntime: Some(min_ntime),
+                version,
+                version_rolling_allowed: false,
+                merkle_path: Vec::new(),
+                coinbase_tx_prefix: pre_pow_hash.as_bytes().to_vec(),
+                coinbase_tx_suffix: Vec::new(),
+            })
+        } else {
+            Sv2Message::NewMiningJob(NewMiningJob {
+                channel_id,
+                job_id,
+                min_ntime: Some(min_ntime),
+                version,
+                merkle_root: pre_pow_hash.as_bytes(),
+            })
+        };
+        self.send(job).await?;
+        if let Some(channel) = self.channels.get(&channel_id) {
+            record_new_job(&channel.worker_context(&self.server.instance_id, &self.remote));
+        }
+        Ok(())
+    }
+
+    async fn handle_submit(
+        &mut self,
+        channel_id: u32,
+        sequence_number: u32,
+        job_id: u32,
+        nonce: u32,
+        ntime: u32,
+        extranonce: Option<Vec<u8>>,
+    ) -> Result<(), Sv2Error> {
+        match self.check_share(channel_id, job_id, nonce, ntime, extranonce) {
+            Ok(accepted) => {
+                if let Some(block) = accepted.block
+                    && let Some(channel) = self.channels.get(&channel_id)
+                {
+                    self.submit_block(block, channel.worker_context(&self.server.instance_id, &self.remote));
+                }
+                self.send(Sv2Message::SubmitSharesSuccess(SubmitSharesSuccess {
+                    channel_id,
+                    last_sequence_number: sequence_number,
+                    new_submits_accepted_count: 1,
+                    new_shares_sum: accepted.share_diff.round() as u64,
+                }))
+                .await
+            }
+            Err(error_code) => {
+                debug!("[SV2] share rejected on channel {} from {}: {}", channel_id, self.remote, error_code);
+                self.send(Sv2Message::SubmitSharesError(SubmitSharesError {
+                    channel_id,
+                    sequence_number,
+                    error_code: error_code.to_string(),
+                }))
+                .await
+            }
+        }
+    }
+
+    /// Validates a share against its job and the channel target, returning the Stratum V2 error code on rejection
+    fn check_share(
+        &mut self,
+        channel_id: u32,
+        job_id: u32,
+        nonce: u32,
+        ntime: u32,
+        extranonce: Option<Vec<u8>>,
+    ) -> Result<AcceptedShare, &'static str> {
+        let instance_id = self.server.instance_id.clone();
+        let Some(channel) = self.channels.get_mut(&channel_id) else {
+            return Err("invalid-channel-id");
+        };
+        let prom_worker = channel.worker_context(&instance_id, &self.remote);
+
+        let mut full_extranonce = channel.extranonce_prefix.clone();
+        if let Some(extranonce) = extranonce {
+            if !channel.extended || extranonce.len() != EXTENDED_EXTRANONCE_SIZE {
+                record_invalid_share(&prom_worker);
+                return Err("invalid-extranonce");
+            }
+            full_extranonce.extend(extranonce);
+        }
+        let Ok(full_extranonce) = <[u8; 4]>::try_from(full_extranonce.as_slice()) else {
+            record_invalid_share(&prom_worker);
+            return Err("invalid-extranonce");
+        };
+
+        // Job ids map to a ring of slots; an id whose slot was reused is stale
+        let job = match channel.state.get_job(job_id as u64) {
+            Some(job) if channel.state.get_job_id_at_slot(job_id as u64) == Some(job_id as u64) => job,
+            _ => {
+                record_stale_share(&prom_worker);
+                return Err("stale-share");
+            }
+        };
+
+        let min_ntime = (job.block.header.timestamp / 1000) as u32;
+        if ntime < min_ntime || ntime > min_ntime.saturating_add(MAX_NTIME_ROLL_SECS) {
+            record_invalid_share(&prom_worker);
+            return Err("invalid-ntime");
+        }
+
+        let nonce = compose_nonce(&full_extranonce, nonce);
+        if channel.seen_shares.len() >= MAX_TRACKED_SHARES {
+            channel.seen_shares.clear();
+        }
+        if !channel.seen_shares.insert((job_id, nonce, ntime)) {
+            record_dupe_share(&prom_worker);
+            return Err("duplicate-share");
+        }
+
+        let mut header = (*job.block.header).clone();
+        header.timestamp = ntime as u64 * 1000;
+        header.nonce = nonce;
+        let (meets_network_target, pow_value) = PowState::new(&header).check_pow(nonce);
+        let pow_value = BigUint::from_bytes_be(&pow_value.to_be_bytes());
+        if pow_value > channel.target {
+            record_weak_share(&prom_worker);
+            return Err("difficulty-too-low");
+        }
+
+        record_share_found(&prom_worker, channel.share_diff);
+        if let Some(pool) = &self.server.pool {
+            pool.record_share(&channel.wallet, &channel.worker, channel.share_diff);
+        }
+
+        let block = meets_network_target.then(|| {
+            header.finalize();
+            Block::from_arcs(Arc::new(header), job.block.transactions.clone())
+        });
+        Ok(AcceptedShare { share_diff: channel.share_diff, block })
+    }
+
+    /// Submits a found block and credits it once confirmed blue, like Stratum v1 blocks
+    fn submit_block(&self, block: Block, prom_worker: WorkerContext) {
+        let kaspa_api = Arc::clone(&self.kaspa_api);
+        let instance_id = self.server.instance_id.clone();
+        let pool = self.server.pool.clone();
+        tokio::spawn(async move {
+            let block_hash = block.header.hash.to_string();
+            let nonce = block.header.nonce;
+            let blue_score = block.header.blue_score;
+            info!(
+                "[{}] {} {}",
+                instance_id,
+                LogColors::block("===== BLOCK FOUND! ====="),
+                format!("SV2 worker: {}, Hash: {}", prom_worker.worker_name, block_hash)
+            );
+
+            // In pool mode, the reward is split over the PPLNS window as of the time the block was found
+            let pool_credit = pool.map(|pool| {
+                let window = pool.ledger.window();
+                (pool, window)
+            });
+
+            match kaspa_api.submit_block(block).await {
+                Ok(response) if response.report.is_success() => {
+                    record_block_accepted_by_node(&prom_worker);
+                    info!(
+                        "[{}] {} {}",
+                        instance_id,
+                        LogColors::block("[BLOCK]"),
+                        LogColors::block(&format!("BLOCK ACCEPTED BY NODE! Hash: {}", block_hash))
+                    );
+                }
+                Ok(response) => {
+                    warn!(
+                        "[{}] {} block {} rejected by node: {:?}",
+                        instance_id,
+                        LogColors::block("[BLOCK]"),
+                        block_hash,
+                        response.report
+                    );
+                    return;
+                }
+                Err(e) => {
+                    error!("[{}] {} block {} submission failed: {}", instance_id, LogColors::block("[BLOCK]"), block_hash, e);
+                    return;
+                }
+            }
+
+            confirm_found_block(kaspa_api.as_ref(), &instance_id, &prom_worker, &block_hash, nonce, blue_score, pool_credit).await;
+        });
+    }
+}
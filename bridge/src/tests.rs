@@ -247,6 +247,89 @@ instances:
     assert!(config.unwrap_err().to_string().contains("Duplicate stratum_port"));
 }
 
+#[cfg(test)]
+#[test]
+fn test_config_tls_listener() {
+    // A TLS port serves the same difficulty tier as the plain port of its instance
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+instances:
+  - stratum_port: ":5555"
+    min_share_diff: 8192
+    tls:
+      port: "5565"
+      cert_path: "certs/bridge.pem"
+      key_path: "certs/bridge.key"
+      reload_interval: 60000
+  - stratum_port: ":5556"
+    min_share_diff: 4096
+"#;
+
+    let config = BridgeConfig::from_yaml(yaml).unwrap();
+    let tls = config.instances[0].tls.as_ref().expect("tls config should be parsed");
+    assert_eq!(tls.port, ":5565");
+    assert_eq!(tls.cert_path, "certs/bridge.pem");
+    assert_eq!(tls.reload_interval, Some(std::time::Duration::from_secs(60)));
+    assert!(config.instances[1].tls.is_none());
+
+    // The TLS port must not clash with any other stratum port
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+instances:
+  - stratum_port: ":5555"
+    min_share_diff: 8192
+    tls:
+      port: ":5556"
+      cert_path: "certs/bridge.pem"
+      key_path: "certs/bridge.key"
+  - stratum_port: ":5556"
+    min_share_diff: 4096
+"#;
+    let config = BridgeConfig::from_yaml(yaml);
+    assert!(config.unwrap_err().to_string().contains("Duplicate stratum_port"));
+}
+
+#[cfg(test)]
+#[test]
+fn test_config_sv2_listener() {
+    // The Stratum V2 port serves the same difficulty tier; unset fields fall back to their defaults
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+stratum_port: ":5555"
+min_share_diff: 8192
+sv2:
+  port: "3336"
+"#;
+
+    let config = BridgeConfig::from_yaml(yaml).unwrap();
+    let sv2 = config.instances[0].sv2.as_ref().expect("sv2 config should be parsed");
+    assert_eq!(sv2.port, ":3336");
+    assert_eq!(sv2.authority_key_env, "RKSTRATUM_SV2_AUTHORITY_KEY");
+    assert_eq!(sv2.certificate_validity, std::time::Duration::from_secs(30 * 24 * 3600));
+
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+instances:
+  - stratum_port: ":5555"
+    min_share_diff: 8192
+    sv2:
+      port: ":5555"
+"#;
+    let config = BridgeConfig::from_yaml(yaml);
+    assert!(config.unwrap_err().to_string().contains("Duplicate stratum_port"));
+
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+instances:
+  - stratum_port: ":5555"
+    min_share_diff: 8192
+    sv2:
+      authority_key_env: "MY_KEY"
+"#;
+    let config = BridgeConfig::from_yaml(yaml);
+    assert!(config.unwrap_err().to_string().contains("missing required 'port'"));
+}
+
 #[cfg(test)]
 #[test]
 fn test_config_coinbase_tag_suffix_empty_string() {
@@ -279,6 +362,112 @@ coinbase_tag_suffix: "test"
     assert_eq!(config.global.coinbase_tag_suffix, Some("test".to_string()));
 }
 
+#[cfg(test)]
+#[test]
+fn test_config_upstream_addresses() {
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+kaspad_failover_addresses:
+  - "10.0.0.2:16110"
+  - "127.0.0.1:16110"
+  - "10.0.0.3:16110"
+stratum_port: ":5555"
+min_share_diff: 8192
+"#;
+
+    let config = BridgeConfig::from_yaml(yaml).unwrap();
+    assert_eq!(config.global.upstream_addresses(), vec!["127.0.0.1:16110", "10.0.0.2:16110", "10.0.0.3:16110"]);
+
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+stratum_port: ":5555"
+min_share_diff: 8192
+"#;
+    let config = BridgeConfig::from_yaml(yaml).unwrap();
+    assert!(config.global.kaspad_failover_addresses.is_empty());
+    assert_eq!(config.global.upstream_addresses(), vec!["127.0.0.1:16110"]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_config_pool_mode_parsing() {
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+stratum_port: ":5555"
+min_share_diff: 8192
+pool:
+  address: "kaspa:qz0c9jqnyuxypxzjzfm2dhfgx8qdl6rcg3dq8ag5ywvz0wcqmvdq2ecql0f3g"
+  fee_percent: 2.5
+  pplns_window: 5000
+  payout_interval: 600000
+"#;
+
+    let config = BridgeConfig::from_yaml(yaml).unwrap();
+    let pool = config.global.pool.expect("pool config should be parsed");
+    assert_eq!(pool.fee_percent, 2.5);
+    assert_eq!(pool.pplns_window, 5000);
+    assert_eq!(pool.payout_interval, std::time::Duration::from_secs(600));
+    assert_eq!(pool.ledger_path, "pool_ledger.jsonl");
+
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+stratum_port: ":5555"
+min_share_diff: 8192
+"#;
+    assert!(BridgeConfig::from_yaml(yaml).unwrap().global.pool.is_none());
+}
+
+#[cfg(test)]
+#[test]
+fn test_config_pool_mode_invalid_fee_error() {
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+stratum_port: ":5555"
+min_share_diff: 8192
+pool:
+  address: "kaspa:qz0c9jqnyuxypxzjzfm2dhfgx8qdl6rcg3dq8ag5ywvz0wcqmvdq2ecql0f3g"
+  fee_percent: 150
+"#;
+
+    let config = BridgeConfig::from_yaml(yaml);
+    assert!(config.unwrap_err().to_string().contains("fee_percent"));
+}
+
+#[cfg(test)]
+#[test]
+fn test_config_history_parsing() {
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+stratum_port: ":5555"
+min_share_diff: 8192
+history:
+  path: "/var/lib/rkstratum/history"
+  retention: 259200000
+"#;
+
+    let config = BridgeConfig::from_yaml(yaml).unwrap();
+    let history = config.global.history.expect("history config should be parsed");
+    assert_eq!(history.path, "/var/lib/rkstratum/history");
+    assert_eq!(history.retention, std::time::Duration::from_secs(3 * 24 * 3600));
+    assert_eq!(history.hashrate_sample_interval, std::time::Duration::from_secs(60));
+
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+stratum_port: ":5555"
+min_share_diff: 8192
+history:
+  retention: 0
+"#;
+    assert!(BridgeConfig::from_yaml(yaml).unwrap_err().to_string().contains("retention"));
+
+    let yaml = r#"
+kaspad_address: "127.0.0.1:16110"
+stratum_port: ":5555"
+min_share_diff: 8192
+"#;
+    assert!(BridgeConfig::from_yaml(yaml).unwrap().global.history.is_none());
+}
+
 #[cfg(test)]
 #[test]
 fn test_config_var_diff_parsing() {
@@ -1124,7 +1313,7 @@ mod integration {
 
         // Create KaspaApi client
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
-        let kaspa_api = KaspaApi::new(rpc_address.clone(), None, shutdown_rx.clone()).await.unwrap();
+        let kaspa_api = KaspaApi::new(vec![rpc_address.clone()], None, shutdown_rx.clone()).await.unwrap();
 
         // Create bridge config
         let bridge_config = StratumBridgeConfig {
@@ -1143,6 +1332,9 @@ mod integration {
             extranonce_size: 4,
             pow2_clamp: false,
             coinbase_tag_suffix: None,
+            pool: None,
+            tls: None,
+            sv2: None,
         };
 
         // Start the bridge server (with a timeout to prevent hanging)
@@ -1197,7 +1389,7 @@ mod integration {
 
         // Create KaspaApi client
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
-        let kaspa_api = KaspaApi::new(rpc_address.clone(), None, shutdown_rx.clone()).await.unwrap();
+        let kaspa_api = KaspaApi::new(vec![rpc_address.clone()], None, shutdown_rx.clone()).await.unwrap();
 
         // Test that CPU miner module is available when feature is enabled
         use kaspa_stratum_bridge::InternalCpuMinerConfig;
@@ -1230,6 +1422,9 @@ mod integration {
             extranonce_size: 4,
             pow2_clamp: false,
             coinbase_tag_suffix: None,
+            pool: None,
+            tls: None,
+            sv2: None,
         };
 
         // Start the bridge server
@@ -2988,3 +3183,478 @@ mod comprehensive_tests {
         assert_eq!(summary.remote_app, "BzMiner", "Summary should contain remote app");
     }
 }
+
+// ============================================================================
+// STRATUM V2 TESTS
+// ============================================================================
+// A minimal pure-Rust Stratum V2 client: it performs the Noise handshake as the
+// initiator, verifies the bridge certificate against the authority key and then
+// speaks the binary mining protocol against `Sv2Server` over an in-memory stream.
+// ============================================================================
+
+#[cfg(test)]
+mod sv2_tests {
+    use kaspa_consensus_core::block::Block;
+    use kaspa_consensus_core::header::Header;
+    use kaspa_consensus_core::subnets::SubnetworkId;
+    use kaspa_consensus_core::tx::{ScriptPublicKey, Transaction, TransactionOutput};
+    use kaspa_hashes::Hash;
+    use kaspa_rpc_core::{SubmitBlockReport, SubmitBlockResponse};
+    use kaspa_stratum_bridge::share_handler::KaspaApiTrait;
+    use kaspa_stratum_bridge::sv2_messages::*;
+    use kaspa_stratum_bridge::sv2_noise::{Initiator, NoiseError, NoiseTransport, RESPONDER_MESSAGE_SIZE, Responder};
+    use kaspa_stratum_bridge::sv2_server::{MINING_PROTOCOL, SV2_PROTOCOL_VERSION, Sv2Server, compose_nonce};
+    use parking_lot::Mutex;
+    use secp256k1::{Keypair, SECP256K1};
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream, ReadHalf, WriteHalf};
+
+    const WALLET: &str = "kaspatest:qr8example123456789012345678901234567890123456789012345678901234567890";
+    const TEMPLATE_TIMESTAMP_MS: u64 = 1_700_000_000_123;
+
+    /// Node stub serving a fixed template with an easy network target and recording submitted blocks
+    struct MockSv2Api {
+        template: Block,
+        submitted: Mutex<Vec<Block>>,
+    }
+
+    impl MockSv2Api {
+        fn new() -> Arc<Self> {
+            let mut header = Header::from_precomputed_hash(Hash::from_bytes([1; 32]), vec![]);
+            header.timestamp = TEMPLATE_TIMESTAMP_MS;
+            header.bits = 0x207fffff; // Roughly every second hash meets the network target
+            let tx = Transaction::new(
+                0,
+                vec![],
+                vec![TransactionOutput::new(50_000_000_000, ScriptPublicKey::from_vec(0, vec![]))],
+                0,
+                SubnetworkId::from_bytes([0; 20]),
+                0,
+                vec![],
+            );
+            Arc::new(Self { template: Block::from_arcs(Arc::new(header), Arc::new(vec![tx])), submitted: Mutex::new(Vec::new()) })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl KaspaApiTrait for MockSv2Api {
+        async fn get_block_template(
+            &self,
+            _wallet_addr: &str,
+            _remote_app: &str,
+            _canxium_addr: &str,
+        ) -> Result<Block, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(self.template.clone())
+        }
+
+        async fn submit_block(&self, block: Block) -> Result<SubmitBlockResponse, Box<dyn std::error::Error + Send + Sync>> {
+            self.submitted.lock().push(block);
+            Ok(SubmitBlockResponse { report: SubmitBlockReport::Success })
+        }
+
+        async fn get_balances_by_addresses(
+            &self,
+            _addresses: &[String],
+        ) -> Result<Vec<(String, u64)>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(vec![])
+        }
+
+        async fn get_current_block_color(&self, _block_hash: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(true)
+        }
+
+        async fn get_block_reward(&self, _block_hash: &str) -> Result<Option<u64>, Box<dyn std::error::Error + Send + Sync>> {
+            Ok(Some(50_000_000_000))
+        }
+    }
+
+    /// Test client holding the encrypted transport of an established connection
+    struct TestClient {
+        reader: ReadHalf<DuplexStream>,
+        writer: WriteHalf<DuplexStream>,
+        transport: NoiseTransport,
+    }
+
+    impl TestClient {
+        async fn send(&mut self, message: Sv2Message) {
+            write_message(&mut self.writer, &mut self.transport.sender, &message).await.unwrap();
+        }
+
+        async fn recv(&mut self) -> Sv2Message {
+            tokio::time::timeout(Duration::from_secs(5), read_message(&mut self.reader, &mut self.transport.receiver))
+                .await
+                .expect("timed out waiting for a message")
+                .unwrap()
+        }
+
+        async fn setup(&mut self) {
+            self.send(Sv2Message::SetupConnection(SetupConnection {
+                protocol: MINING_PROTOCOL,
+                min_version: SV2_PROTOCOL_VERSION,
+                max_version: SV2_PROTOCOL_VERSION,
+                flags: 0,
+                endpoint_host: "127.0.0.1".to_string(),
+                endpoint_port: 3336,
+                vendor: "test".to_string(),
+                hardware_version: String::new(),
+                firmware: "0.1".to_string(),
+                device_id: String::new(),
+            }))
+            .await;
+            match self.recv().await {
+                Sv2Message::SetupConnectionSuccess(m) => assert_eq!(m.used_version, SV2_PROTOCOL_VERSION),
+                other => panic!("expected SetupConnection.Success, got {:?}", other),
+            }
+        }
+    }
+
+    fn authority() -> Keypair {
+        Keypair::new(SECP256K1, &mut secp256k1::rand::thread_rng())
+    }
+
+    /// Starts a server connection over an in-memory stream and completes the handshake as the initiator
+    async fn connect(api: Arc<MockSv2Api>) -> TestClient {
+        let authority = authority();
+        let responder = Responder::with_authority(&authority, Duration::from_secs(3600));
+        // A tiny difficulty makes every share valid, so the tests do not depend on hashing luck
+        let server = Sv2Server::new("test-instance".to_string(), 1e-12, responder, None);
+
+        let (client_stream, server_stream) = tokio::io::duplex(1 << 20);
+        tokio::spawn(server.serve_connection(server_stream, "127.0.0.1:40000".to_string(), api));
+
+        let (mut reader, mut writer) = tokio::io::split(client_stream);
+        let mut initiator = Initiator::new(Some(authority.x_only_public_key().0));
+        writer.write_all(&initiator.first_message()).await.unwrap();
+        let mut reply = [0u8; RESPONDER_MESSAGE_SIZE];
+        reader.read_exact(&mut reply).await.unwrap();
+        let transport = initiator.finish(&reply).expect("handshake should succeed");
+        TestClient { reader, writer, transport }
+    }
+
+    /// Finds a share nonce whose Kaspa PoW meets the template's network target
+    fn mine_block_nonce(api: &MockSv2Api, extranonce: &[u8; 4], ntime: u32) -> u32 {
+        let mut header = (*api.template.header).clone();
+        header.timestamp = ntime as u64 * 1000;
+        let state = kaspa_pow::State::new(&header);
+        (0..u32::MAX).find(|nonce| state.check_pow(compose_nonce(extranonce, *nonce)).0).unwrap()
+    }
+
+    #[test]
+    fn test_sv2_noise_handshake_and_transport() {
+        let authority = authority();
+        let responder = Responder::with_authority(&authority, Duration::from_secs(3600));
+
+        let mut initiator = Initiator::new(Some(authority.x_only_public_key().0));
+        let (reply, mut server) = responder.respond(&initiator.first_message()).unwrap();
+        assert_eq!(reply.len(), RESPONDER_MESSAGE_SIZE);
+        let mut client = initiator.finish(&reply).unwrap();
+
+        let ciphertext = client.sender.encrypt(b"hello bridge").unwrap();
+        assert_ne!(&ciphertext[..12], b"hello bridge");
+        assert_eq!(server.receiver.decrypt(&ciphertext).unwrap(), b"hello bridge");
+        let ciphertext = server.sender.encrypt(b"hello miner").unwrap();
+        assert_eq!(client.receiver.decrypt(&ciphertext).unwrap(), b"hello miner");
+
+        // Tampered ciphertext is rejected
+        let mut tampered = client.sender.encrypt(b"share").unwrap();
+        tampered[0] ^= 1;
+        assert!(matches!(server.receiver.decrypt(&tampered), Err(NoiseError::Decrypt)));
+
+        // A client pinned to another authority refuses the certificate
+        let mut initiator = Initiator::new(Some(self::authority().x_only_public_key().0));
+        let (reply, _) = responder.respond(&initiator.first_message()).unwrap();
+        assert!(matches!(initiator.finish(&reply), Err(NoiseError::InvalidCertificate)));
+
+        // Malformed handshake messages are rejected
+        assert!(matches!(responder.respond(&[0u8; 10]), Err(NoiseError::InvalidLength(10))));
+    }
+
+    #[test]
+    fn test_sv2_message_encoding_round_trip() {
+        let messages = vec![
+            Sv2Message::OpenExtendedMiningChannel(OpenExtendedMiningChannel {
+                request_id: 7,
+                user_identity: format!("{}.rig1", WALLET),
+                nominal_hash_rate: 1.5e12,
+                max_target: [0xff; 32],
+                min_extranonce_size: 2,
+            }),
+            Sv2Message::NewMiningJob(NewMiningJob { channel_id: 1, job_id: 2, min_ntime: Some(3), version: 1, merkle_root: [9; 32] }),
+            Sv2Message::NewExtendedMiningJob(NewExtendedMiningJob {
+                channel_id: 1,
+                job_id: 2,
+                min_ntime: None,
+                version: 1,
+                version_rolling_allowed: false,
+                merkle_path: vec![[1; 32], [2; 32]],
+                coinbase_tx_prefix: vec![3; 40],
+                coinbase_tx_suffix: vec![],
+            }),
+            Sv2Message::SubmitSharesExtended(SubmitSharesExtended {
+                channel_id: 1,
+                sequence_number: 5,
+                job_id: 2,
+                nonce: 0xdeadbeef,
+                ntime: 1_700_000_000,
+                version: 1,
+                extranonce: vec![0xaa, 0xbb],
+            }),
+            Sv2Message::SubmitSharesError(SubmitSharesError {
+                channel_id: 1,
+                sequence_number: 5,
+                error_code: "stale-share".to_string(),
+            }),
+        ];
+        for message in messages {
+            let frame = message.encode_frame().unwrap();
+            let payload_len = u32::from_le_bytes([frame[3], frame[4], frame[5], 0]) as usize;
+            assert_eq!(frame.len(), HEADER_SIZE + payload_len);
+            assert_eq!(frame[2], message.msg_type());
+            assert_eq!(Sv2Message::decode(frame[2], &frame[HEADER_SIZE..]).unwrap(), message);
+        }
+
+        // Channel messages carry the channel bit, connection-level ones do not
+        let job = Sv2Message::SetTarget(SetTarget { channel_id: 1, maximum_target: [0; 32] }).encode_frame().unwrap();
+        assert_eq!(u16::from_le_bytes([job[0], job[1]]), 0x8000);
+
+        // Truncated payloads and oversized fields are errors rather than panics
+        assert!(matches!(Sv2Message::decode(MSG_SUBMIT_SHARES_STANDARD, &[0; 10]), Err(Sv2Error::Truncated)));
+        let too_long = Sv2Message::SetupConnectionError(SetupConnectionError { flags: 0, error_code: "x".repeat(256) });
+        assert!(matches!(too_long.encode_payload(), Err(Sv2Error::FieldTooLong("error_code"))));
+    }
+
+    #[tokio::test]
+    async fn test_sv2_encrypted_frames_split_into_chunks() {
+        let authority = authority();
+        let responder = Responder::with_authority(&authority, Duration::from_secs(3600));
+        let mut initiator = Initiator::new(None);
+        let (reply, mut server) = responder.respond(&initiator.first_message()).unwrap();
+        let mut client = initiator.finish(&reply).unwrap();
+
+        // Larger than one encrypted chunk
+        let message = Sv2Message::NewExtendedMiningJob(NewExtendedMiningJob {
+            channel_id: 1,
+            job_id: 1,
+            min_ntime: Some(1),
+            version: 1,
+            version_rolling_allowed: false,
+            merkle_path: vec![],
+            coinbase_tx_prefix: vec![0x11; 60_000],
+            coinbase_tx_suffix: vec![0x22; 60_000],
+        });
+        let (mut a, mut b) = tokio::io::duplex(1 << 20);
+        write_message(&mut a, &mut client.sender, &message).await.unwrap();
+        assert_eq!(read_message(&mut b, &mut server.receiver).await.unwrap(), message);
+    }
+
+    #[tokio::test]
+    async fn test_sv2_standard_channel_flow() {
+        let api = MockSv2Api::new();
+        let mut client = connect(Arc::clone(&api)).await;
+        client.setup().await;
+
+        client
+            .send(Sv2Message::OpenStandardMiningChannel(OpenStandardMiningChannel {
+                request_id: 1,
+                user_identity: format!("{}.rig1", WALLET),
+                nominal_hash_rate: 1e12,
+                max_target: [0xff; 32],
+            }))
+            .await;
+        let (channel_id, prefix) = match client.recv().await {
+            Sv2Message::OpenStandardMiningChannelSuccess(m) => {
+                assert_eq!(m.request_id, 1);
+                (m.channel_id, <[u8; 4]>::try_from(m.extranonce_prefix.as_slice()).expect("4-byte prefix"))
+            }
+            other => panic!("expected OpenStandardMiningChannel.Success, got {:?}", other),
+        };
+
+        // The first job follows immediately, with the template timestamp rounded down to seconds
+        let job = match client.recv().await {
+            Sv2Message::NewMiningJob(job) => job,
+            other => panic!("expected NewMiningJob, got {:?}", other),
+        };
+        assert_eq!(job.channel_id, channel_id);
+        assert_eq!(job.min_ntime, Some((TEMPLATE_TIMESTAMP_MS / 1000) as u32));
+        assert_ne!(job.merkle_root, [0; 32]);
+
+        // A share meeting the network target is accepted and submitted as a block
+        let ntime = job.min_ntime.unwrap();
+        let nonce = mine_block_nonce(&api, &prefix, ntime);
+        let share = SubmitSharesStandard { channel_id, sequence_number: 1, job_id: job.job_id, nonce, ntime, version: job.version };
+        client.send(Sv2Message::SubmitSharesStandard(share.clone())).await;
+        match client.recv().await {
+            Sv2Message::SubmitSharesSuccess(m) => {
+                assert_eq!(m.channel_id, channel_id);
+                assert_eq!(m.last_sequence_number, 1);
+                assert_eq!(m.new_submits_accepted_count, 1);
+            }
+            other => panic!("expected SubmitShares.Success, got {:?}", other),
+        }
+        for _ in 0..100 {
+            if !api.submitted.lock().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let submitted = api.submitted.lock().clone();
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(submitted[0].header.nonce, compose_nonce(&prefix, nonce));
+        assert_eq!(submitted[0].header.timestamp, ntime as u64 * 1000);
+
+        // Resubmitting the same share is a duplicate
+        client.send(Sv2Message::SubmitSharesStandard(SubmitSharesStandard { sequence_number: 2, ..share.clone() })).await;
+        match client.recv().await {
+            Sv2Message::SubmitSharesError(m) => assert_eq!(m.error_code, "duplicate-share"),
+            other => panic!("expected SubmitShares.Error, got {:?}", other),
+        }
+
+        // Unknown jobs, unknown channels and out-of-range ntime are rejected
+        client.send(Sv2Message::SubmitSharesStandard(SubmitSharesStandard { sequence_number: 3, job_id: 999, ..share.clone() })).await;
+        match client.recv().await {
+            Sv2Message::SubmitSharesError(m) => assert_eq!(m.error_code, "stale-share"),
+            other => panic!("expected SubmitShares.Error, got {:?}", other),
+        }
+        client
+            .send(Sv2Message::SubmitSharesStandard(SubmitSharesStandard { sequence_number: 4, channel_id: 999, ..share.clone() }))
+            .await;
+        match client.recv().await {
+            Sv2Message::SubmitSharesError(m) => assert_eq!(m.error_code, "invalid-channel-id"),
+            other => panic!("expected SubmitShares.Error, got {:?}", other),
+        }
+        client.send(Sv2Message::SubmitSharesStandard(SubmitSharesStandard { sequence_number: 5, ntime: ntime - 1, ..share })).await;
+        match client.recv().await {
+            Sv2Message::SubmitSharesError(m) => assert_eq!(m.error_code, "invalid-ntime"),
+            other => panic!("expected SubmitShares.Error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sv2_extended_channel_flow() {
+        let api = MockSv2Api::new();
+        let mut client = connect(Arc::clone(&api)).await;
+        client.setup().await;
+
+        // The bridge leaves two extranonce bytes to the miner
+        client
+            .send(Sv2Message::OpenExtendedMiningChannel(OpenExtendedMiningChannel {
+                request_id: 1,
+                user_identity: WALLET.to_string(),
+                nominal_hash_rate: 1e12,
+                max_target: [0xff; 32],
+                min_extranonce_size: 8,
+            }))
+            .await;
+        match client.recv().await {
+            Sv2Message::OpenMiningChannelError(m) => assert_eq!(m.error_code, "min-extranonce-size-too-large"),
+            other => panic!("expected OpenMiningChannel.Error, got {:?}", other),
+        }
+
+        client
+            .send(Sv2Message::OpenExtendedMiningChannel(OpenExtendedMiningChannel {
+                request_id: 2,
+                user_identity: WALLET.to_string(),
+                nominal_hash_rate: 1e12,
+                max_target: [0xff; 32],
+                min_extranonce_size: 2,
+            }))
+            .await;
+        let (channel_id, prefix) = match client.recv().await {
+            Sv2Message::OpenExtendedMiningChannelSuccess(m) => {
+                assert_eq!(m.extranonce_size, 2);
+                assert_eq!(m.extranonce_prefix.len(), 2);
+                (m.channel_id, m.extranonce_prefix)
+            }
+            other => panic!("expected OpenExtendedMiningChannel.Success, got {:?}", other),
+        };
+        let job = match client.recv().await {
+            Sv2Message::NewExtendedMiningJob(job) => job,
+            other => panic!("expected NewExtendedMiningJob, got {:?}", other),
+        };
+        assert_eq!(job.coinbase_tx_prefix.len(), 32);
+        assert!(job.merkle_path.is_empty());
+        assert!(!job.version_rolling_allowed);
+
+        let ntime = job.min_ntime.unwrap();
+        let share = SubmitSharesExtended {
+            channel_id,
+            sequence_number: 1,
+            job_id: job.job_id,
+            nonce: 42,
+            ntime,
+            version: job.version,
+            extranonce: vec![0x01, 0x02, 0x03],
+        };
+        client.send(Sv2Message::SubmitSharesExtended(share.clone())).await;
+        match client.recv().await {
+            Sv2Message::SubmitSharesError(m) => assert_eq!(m.error_code, "invalid-extranonce"),
+            other => panic!("expected SubmitShares.Error, got {:?}", other),
+        }
+
+        let extranonce: [u8; 4] = [prefix[0], prefix[1], 0x01, 0x02];
+        let nonce = mine_block_nonce(&api, &extranonce, ntime);
+        client
+            .send(Sv2Message::SubmitSharesExtended(SubmitSharesExtended {
+                sequence_number: 2,
+                nonce,
+                extranonce: vec![0x01, 0x02],
+                ..share
+            }))
+            .await;
+        match client.recv().await {
+            Sv2Message::SubmitSharesSuccess(m) => assert_eq!(m.last_sequence_number, 2),
+            other => panic!("expected SubmitShares.Success, got {:?}", other),
+        }
+        for _ in 0..100 {
+            if !api.submitted.lock().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(api.submitted.lock()[0].header.nonce, compose_nonce(&extranonce, nonce));
+    }
+
+    #[tokio::test]
+    async fn test_sv2_channel_prefixes_do_not_overlap() {
+        let api = MockSv2Api::new();
+        let mut client = connect(Arc::clone(&api)).await;
+        client.setup().await;
+
+        // Every channel owns a distinct high nonce half-word, whatever its kind
+        let mut high_words = HashSet::new();
+        for request_id in 0..4 {
+            if request_id % 2 == 0 {
+                client
+                    .send(Sv2Message::OpenExtendedMiningChannel(OpenExtendedMiningChannel {
+                        request_id,
+                        user_identity: WALLET.to_string(),
+                        nominal_hash_rate: 1e12,
+                        max_target: [0xff; 32],
+                        min_extranonce_size: 2,
+                    }))
+                    .await;
+            } else {
+                client
+                    .send(Sv2Message::OpenStandardMiningChannel(OpenStandardMiningChannel {
+                        request_id,
+                        user_identity: WALLET.to_string(),
+                        nominal_hash_rate: 1e12,
+                        max_target: [0xff; 32],
+                    }))
+                    .await;
+            }
+            let prefix = match client.recv().await {
+                Sv2Message::OpenExtendedMiningChannelSuccess(m) => m.extranonce_prefix,
+                Sv2Message::OpenStandardMiningChannelSuccess(m) => {
+                    assert_eq!(m.extranonce_prefix[2..], [0, 0]);
+                    m.extranonce_prefix
+                }
+                other => panic!("expected OpenMiningChannel.Success, got {:?}", other),
+            };
+            assert!(high_words.insert([prefix[0], prefix[1]]), "duplicate prefix {:?}", prefix);
+            client.recv().await; // first job
+        }
+    }
+}
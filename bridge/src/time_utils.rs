@@ -0,0 +1,7 @@
+//! Shared wall clock helper for the timestamps persisted in the history, the pool ledger and SV2 certificates.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current unix time in seconds, or 0 if the system clock is before the epoch
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
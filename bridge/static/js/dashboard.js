@@ -765,6 +765,172 @@ function updateBlocksChartFromBlocks(blocks, totalAllBlocks, walletFilter) {
   renderDonutChart('blocksPie', 'blocksPieLegend', top, emptyMessage);
 }
 
+function formatSompi(sompi) {
+  const n = Number(sompi) || 0;
+  return (n / 1e8).toLocaleString(undefined, { maximumFractionDigits: 8 });
+}
+
+async function refreshPool() {
+  const section = document.getElementById('poolSection');
+  if (!section) return;
+
+  let pool = null;
+  try {
+    const res = await fetch('api/pool', { cache: 'no-store' });
+    if (res.ok) pool = await res.json();
+  } catch {
+    pool = null;
+  }
+
+  if (!pool || typeof pool !== 'object') {
+    section.classList.add('hidden');
+    return;
+  }
+  section.classList.remove('hidden');
+
+  setText('poolFee', `${pool.fee_percent}%`);
+  setText('poolWindow', `${pool.window_shares} / ${pool.pplns_window} shares`);
+  setText('poolTotalFees', `${formatSompi(pool.total_fees)} KAS`);
+
+  const balancesBody = document.getElementById('poolBalancesBody');
+  balancesBody.innerHTML = '';
+  (pool.balances || []).forEach(b => {
+    const tr = document.createElement('tr');
+    tr.className = 'border-b border-card/50';
+    tr.innerHTML = `
+      <td class="py-1.5 pr-3 truncate" title="${escapeHtmlAttr(b.address)}">${escapeHtmlAttr(b.address)}</td>
+      <td class="py-1.5 pr-3 tabular-nums">${formatSompi(b.balance)}</td>
+      <td class="py-1.5 pr-3 tabular-nums">${formatSompi(b.paid)}</td>
+    `;
+    balancesBody.appendChild(tr);
+  });
+
+  const payoutsBody = document.getElementById('poolPayoutsBody');
+  payoutsBody.innerHTML = '';
+  (pool.payouts || []).forEach(p => {
+    const tr = document.createElement('tr');
+    tr.className = 'border-b border-card/50';
+    tr.innerHTML = `
+      <td class="py-1.5 pr-3">${formatUnixSeconds(p.timestamp)}</td>
+      <td class="py-1.5 pr-3 truncate" title="${escapeHtmlAttr(p.address)}">${escapeHtmlAttr(p.address)}</td>
+      <td class="py-1.5 pr-3 tabular-nums">${formatSompi(p.amount)}</td>
+      <td class="py-1.5 pr-3" title="${escapeHtmlAttr(p.tx_id)}">${shortHash(p.tx_id || '')}</td>
+    `;
+    payoutsBody.appendChild(tr);
+  });
+}
+
+const HISTORY_CHART_BUCKETS = 120;
+const HISTORY_TABLE_ROWS = 50;
+let lastHistoryRefreshMs = 0;
+
+function historyRecordDetails(r) {
+  switch (r.type) {
+    case 'share': return `difficulty ${formatDifficulty(r.difficulty)}`;
+    case 'rejected': return escapeHtmlAttr(r.reason);
+    case 'block': return `${r.status} &middot; <span title="${escapeHtmlAttr(r.hash)}">${shortHash(r.hash)}</span>`;
+    case 'hashrate': return formatHashrateHs(r.hashrate_ghs * 1e9);
+    default: return '-';
+  }
+}
+
+function formatHistoryValue(kind, value) {
+  if (kind === 'hashrate') return formatHashrateHs(value * 1e9);
+  if (kind === 'shares') return formatDifficulty(value);
+  return String(Math.round(value));
+}
+
+function renderHistoryChart(kind, points) {
+  const container = document.getElementById('historyChart');
+  if (!container) return;
+  const values = points.map(p => Number(p.value) || 0);
+  const max = Math.max(0, ...values);
+  if (!points.length || max <= 0) {
+    container.innerHTML = '<div class="text-sm text-gray-400">No history recorded for this range yet.</div>';
+    return;
+  }
+
+  const width = 600;
+  const height = 160;
+  const barWidth = width / points.length;
+  const bars = points.map((p, idx) => {
+    const h = (values[idx] / max) * (height - 10);
+    const title = `${formatUnixSeconds(p.timestamp)}: ${formatHistoryValue(kind, values[idx])}`;
+    return `<rect x="${(idx * barWidth).toFixed(2)}" y="${(height - h).toFixed(2)}" width="${Math.max(barWidth - 1, 1).toFixed(2)}" height="${h.toFixed(2)}" fill="#70c7ba"><title>${escapeHtmlAttr(title)}</title></rect>`;
+  }).join('');
+
+  container.innerHTML = `
+    <div class="flex justify-between text-xs text-gray-400 mb-1">
+      <span>max ${formatHistoryValue(kind, max)}</span>
+    </div>
+    <svg viewBox="0 0 ${width} ${height}" preserveAspectRatio="none" class="w-full h-40 bg-surface-1 rounded">${bars}</svg>
+    <div class="flex justify-between text-xs text-gray-400 mt-1">
+      <span>${formatUnixSeconds(points[0].timestamp)}</span>
+      <span>${formatUnixSeconds(points[points.length - 1].timestamp)}</span>
+    </div>
+  `;
+}
+
+async function refreshHistory(force = false) {
+  const section = document.getElementById('historySection');
+  if (!section) return;
+  // The history is read from disk, so it is refreshed less often than the live stats
+  if (!force && Date.now() - lastHistoryRefreshMs < 30000) return;
+  lastHistoryRefreshMs = Date.now();
+
+  const kind = document.getElementById('historyKind').value;
+  const range = Number(document.getElementById('historyRange').value) || 86400;
+  const worker = document.getElementById('historyWorker').value.trim();
+  const to = Math.floor(Date.now() / 1000);
+  const from = to - range;
+  const bucket = Math.max(1, Math.ceil(range / HISTORY_CHART_BUCKETS));
+  const params = new URLSearchParams({ from, to });
+  if (worker) params.set('worker', worker);
+
+  let points = null;
+  let records = null;
+  try {
+    const pointsParams = new URLSearchParams(params);
+    pointsParams.set('bucket', bucket);
+    const recordsParams = new URLSearchParams(params);
+    recordsParams.set('limit', HISTORY_TABLE_ROWS);
+    const [pointsRes, recordsRes] = await Promise.all([
+      fetch(`api/history/${kind}?${pointsParams}`, { cache: 'no-store' }),
+      fetch(`api/history/${kind}?${recordsParams}`, { cache: 'no-store' }),
+    ]);
+    if (pointsRes.ok) points = await pointsRes.json();
+    if (recordsRes.ok) records = await recordsRes.json();
+  } catch {
+    points = null;
+  }
+
+  if (!points || typeof points !== 'object') {
+    section.classList.add('hidden');
+    return;
+  }
+  section.classList.remove('hidden');
+  renderHistoryChart(kind, points.points || []);
+
+  const body = document.getElementById('historyRecordsBody');
+  body.innerHTML = '';
+  ((records && records.records) || []).slice().reverse().forEach(r => {
+    const tr = document.createElement('tr');
+    tr.className = 'border-b border-card/50';
+    tr.innerHTML = `
+      <td class="py-1.5 pr-3">${formatUnixSeconds(r.timestamp)}</td>
+      <td class="py-1.5 pr-3 truncate" title="${escapeHtmlAttr(r.instance)}">${escapeHtmlAttr(r.worker)}</td>
+      <td class="py-1.5 pr-3 truncate" title="${escapeHtmlAttr(r.wallet)}">${escapeHtmlAttr(r.wallet)}</td>
+      <td class="py-1.5 pr-3">${historyRecordDetails(r)}</td>
+    `;
+    body.appendChild(tr);
+  });
+}
+
+['historyKind', 'historyRange'].forEach(id => {
+  document.getElementById(id)?.addEventListener('change', () => refreshHistory(true));
+});
+document.getElementById('historyWorker')?.addEventListener('change', () => refreshHistory(true));
+
 async function refresh() {
   const loader = document.getElementById('status-loader');
   const statusText = document.getElementById('status-text');
@@ -840,6 +1006,8 @@ async function refresh() {
     const dayFilter = getBlocksDayFilter();
 
     renderWalletSummary(mergedStats, filter);
+    refreshPool();
+    refreshHistory();
 
     let blocks = (mergedStats.blocks || []).filter(b => !filter || (b.wallet || '').includes(filter));
     blocks = filterBlocksByDays(blocks, dayFilter);
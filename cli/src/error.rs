@@ -33,6 +33,15 @@ pub enum Error {
     #[error(transparent)]
     RpcError(#[from] kaspa_rpc_core::RpcError),
 
+    #[error(transparent)]
+    ScriptAsmError(#[from] kaspa_txscript::asm::AsmError),
+
+    #[error(transparent)]
+    ScriptError(#[from] kaspa_txscript_errors::TxScriptError),
+
+    #[error(transparent)]
+    ConsensusClient(#[from] kaspa_consensus_client::error::Error),
+
     #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
 
@@ -1,6 +1,7 @@
 use crate::imports::*;
 use convert_case::{Case, Casing};
 use kaspa_rpc_core::api::ops::RpcApiOps;
+use kaspa_rpc_core::api::rpc::MAX_HEADERS_LIMIT;
 
 #[derive(Default, Handler)]
 #[help("Execute RPC commands against the connected Kaspa node")]
@@ -118,10 +119,16 @@ impl Rpc {
                 let result = rpc.get_block_call(None, GetBlockRequest { hash, include_transactions }).await?;
                 self.println(&ctx, result);
             }
-            // RpcApiOps::GetSubnetwork => {
-            //     let result = rpc.get_subnetwork_call(GetSubnetworkRequest {  }).await?;
-            //     self.println(&ctx, result);
-            // }
+            RpcApiOps::GetSubnetwork => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing subnetworkId argument"));
+                };
+                let subnetwork_id =
+                    RpcSubnetworkId::from_hex(argv.remove(0).as_str()).map_err(|err| Error::custom(err.to_string()))?;
+                let block_hash = argv.first().map(|x| RpcHash::from_hex(x.as_str())).transpose()?;
+                let result = rpc.get_subnetwork_call(None, GetSubnetworkRequest::new(subnetwork_id, block_hash)).await?;
+                self.println(&ctx, result);
+            }
             RpcApiOps::GetVirtualChainFromBlock => {
                 if argv.is_empty() {
                     return Err(Error::custom("Missing startHash argument"));
@@ -162,16 +169,22 @@ impl Rpc {
                 let result = rpc.shutdown_call(None, ShutdownRequest {}).await?;
                 self.println(&ctx, result);
             }
-            // RpcApiOps::GetHeaders => {
-            //     let result = rpc.get_headers_call(GetHeadersRequest {  }).await?;
-            //     self.println(&ctx, result);
-            // }
+            RpcApiOps::GetHeaders => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing startHash argument"));
+                };
+                let start_hash = RpcHash::from_hex(argv.remove(0).as_str())?;
+                let limit = argv.first().and_then(|x| x.parse::<u64>().ok()).unwrap_or(MAX_HEADERS_LIMIT);
+                let is_ascending = argv.get(1).and_then(|x| x.parse::<bool>().ok()).unwrap_or(true);
+                let result = rpc.get_headers_call(None, GetHeadersRequest { start_hash, limit, is_ascending }).await?;
+                self.println(&ctx, result);
+            }
             RpcApiOps::GetUtxosByAddresses => {
                 if argv.is_empty() {
                     return Err(Error::custom("Please specify at least one address"));
                 }
                 let addresses = argv.iter().map(|s| Address::try_from(s.as_str())).collect::<std::result::Result<Vec<_>, _>>()?;
-                let result = rpc.get_utxos_by_addresses_call(None, GetUtxosByAddressesRequest { addresses }).await?;
+                let result = rpc.get_utxos_by_addresses_call(None, GetUtxosByAddressesRequest::new(addresses)).await?;
                 self.println(&ctx, result);
             }
             RpcApiOps::GetBalanceByAddress => {
@@ -284,6 +297,24 @@ impl Rpc {
                 let result = rpc.get_block_reward_info_call(None, GetBlockRewardInfoRequest::new(hash)).await?;
                 self.println(&ctx, result);
             }
+            RpcApiOps::GetTransaction => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing transaction id argument"));
+                }
+                let transaction_id = argv.remove(0);
+                let transaction_id = RpcHash::from_hex(transaction_id.as_str())?;
+                let result = rpc.get_transaction_call(None, GetTransactionRequest::new(transaction_id, true)).await?;
+                self.println(&ctx, result);
+            }
+            RpcApiOps::GetAddressHistory => {
+                if argv.is_empty() {
+                    return Err(Error::custom("Missing address argument"));
+                }
+                let address = Address::try_from(argv.remove(0).as_str())?;
+                let result =
+                    rpc.get_address_history_call(None, GetAddressHistoryRequest::new(address, None, None, None, None)).await?;
+                self.println(&ctx, result);
+            }
             RpcApiOps::GetUtxoReturnAddress => {
                 if argv.is_empty() || argv.len() != 2 {
                     return Err(Error::custom("Please specify a txid and a accepting_block_daa_score"));
@@ -0,0 +1,250 @@
+use crate::imports::*;
+use kaspa_consensus_client::{numeric, string};
+use kaspa_consensus_core::tx::SignableTransaction;
+use kaspa_txscript::EngineFlags;
+use kaspa_txscript::asm::{AsmError, assemble, disassemble, parse_opcode};
+use kaspa_txscript::debugger::{Breakpoint, ScriptDebugger, StepSnapshot};
+
+#[derive(Default)]
+pub struct Script;
+
+#[async_trait]
+impl Handler for Script {
+    fn verb(&self, _ctx: &Arc<dyn Context>) -> Option<&'static str> {
+        Some("script")
+    }
+
+    fn help(&self, _ctx: &Arc<dyn Context>) -> &'static str {
+        "Assemble, disassemble or debug transaction scripts"
+    }
+
+    async fn handle(self: Arc<Self>, ctx: &Arc<dyn Context>, argv: Vec<String>, cmd: &str) -> cli::Result<()> {
+        let ctx = ctx.clone().downcast_arc::<KaspaCli>()?;
+        self.main(ctx, argv, cmd).await.map_err(|e| e.into())
+    }
+}
+
+impl Script {
+    async fn main(self: Arc<Self>, ctx: Arc<KaspaCli>, argv: Vec<String>, _cmd: &str) -> Result<()> {
+        if argv.len() < 2 {
+            return self.display_help(ctx, argv).await;
+        }
+
+        match argv.first().unwrap().as_str() {
+            "asm" => {
+                let source = argv[1..].join(" ");
+                match assemble(&source) {
+                    Ok(script) => {
+                        tprintln!(ctx, "{}", faster_hex::hex_string(&script));
+                    }
+                    Err(err) => {
+                        self.display_error(&ctx, &source, &err);
+                        return Err(err.into());
+                    }
+                }
+            }
+            "disasm" => {
+                if argv.len() != 2 {
+                    return self.display_help(ctx, argv).await;
+                }
+                let hex = argv[1].trim_start_matches("0x");
+                let mut script = vec![0u8; hex.len() / 2];
+                faster_hex::hex_decode(hex.as_bytes(), &mut script)?;
+                tprintln!(ctx, "{}", disassemble(&script)?);
+            }
+            "debug" => {
+                let input_index = argv[1].parse::<usize>()?;
+                let covenants_enabled = match argv.get(2).map(String::as_str) {
+                    None => false,
+                    Some("covenants") => true,
+                    Some(v) => return Err(Error::UnrecognizedArgument(v.to_string(), "covenants".to_string())),
+                };
+                let json = ctx.term().ask(false, "Transaction JSON: ").await?;
+                let tx = Self::parse_transaction(json.trim())?;
+                let flags = EngineFlags { covenants_enabled, ..Default::default() };
+                let debugger = ScriptDebugger::trace_transaction_input(&tx.as_verifiable(), input_index, flags)?;
+                self.debug(ctx, debugger).await?;
+            }
+            "validate" => {
+                let json = argv[1..].join(" ");
+                let tx = Self::parse_transaction(json.trim())?;
+                self.validate(ctx, tx).await?;
+            }
+            v => {
+                tprintln!(ctx, "unknown command: '{v}'\r\n");
+                return self.display_help(ctx, argv).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Accepts both the numeric and the "safe" (string) JSON transaction schemas, inputs carrying their UTXO entries.
+    fn parse_transaction(json: &str) -> Result<SignableTransaction> {
+        match string::SerializableTransaction::deserialize_from_json(json) {
+            Ok(tx) => Ok(tx.try_into()?),
+            Err(_) => Ok(numeric::SerializableTransaction::deserialize_from_json(json)?.try_into()?),
+        }
+    }
+
+    async fn validate(self: &Arc<Self>, ctx: Arc<KaspaCli>, tx: SignableTransaction) -> Result<()> {
+        // Provided UTXO entries are only used if complete, otherwise the node looks up all of them
+        let utxo_entries =
+            tx.entries.iter().map(|entry| entry.clone().map(RpcUtxoEntry::from)).collect::<Option<Vec<_>>>().unwrap_or_default();
+        let response = ctx.wallet().rpc_api().validate_transaction_scripts((&tx.tx).into(), utxo_entries).await?;
+
+        tprintln!(ctx, "transaction {}", response.transaction_id);
+        match (&response.error, response.fee) {
+            (Some(err), _) => tprintln!(ctx, "  rejected: {err}"),
+            (None, Some(fee)) => tprintln!(ctx, "  fee: {fee}"),
+            (None, None) => {}
+        }
+        tprintln!(
+            ctx,
+            "  compute mass: {}, transient mass: {}, storage mass: {}",
+            response.compute_mass,
+            response.transient_mass,
+            response.storage_mass
+        );
+        for (index, input) in response.inputs.iter().enumerate() {
+            let outcome = input.error.as_deref().map(|err| format!("failed: {err}")).unwrap_or_else(|| "ok".to_string());
+            tprintln!(ctx, "  input #{index} {outcome} (script units: {}, sig ops: {})", input.used_script_units, input.used_sig_ops);
+        }
+
+        Ok(())
+    }
+
+    async fn debug(self: &Arc<Self>, ctx: Arc<KaspaCli>, mut debugger: ScriptDebugger) -> Result<()> {
+        tprintln!(ctx, "recorded {} steps, type 'help' for debugger commands", debugger.steps().len());
+        self.display_position(&ctx, &debugger);
+
+        loop {
+            let line = ctx.term().ask(false, "debug> ").await?;
+            let args = line.split_whitespace().collect::<Vec<_>>();
+            match args.as_slice() {
+                [] | ["s"] | ["step"] => {
+                    debugger.step();
+                }
+                ["b"] | ["back"] => {
+                    debugger.step_back();
+                }
+                ["c"] | ["continue"] => {
+                    debugger.resume();
+                }
+                ["r"] | ["reset"] => debugger.reset(),
+                ["p"] | ["print"] => {}
+                ["break", location] => {
+                    debugger.add_breakpoint(Self::parse_breakpoint(location)?);
+                    continue;
+                }
+                ["delete", location] => {
+                    if !debugger.remove_breakpoint(&Self::parse_breakpoint(location)?) {
+                        tprintln!(ctx, "no such breakpoint");
+                    }
+                    continue;
+                }
+                ["breakpoints"] => {
+                    for breakpoint in debugger.breakpoints() {
+                        match breakpoint {
+                            Breakpoint::Location { script_index, opcode_index } => tprintln!(ctx, "{script_index}:{opcode_index}"),
+                            Breakpoint::Opcode(opcode) => tprintln!(ctx, "{}", kaspa_txscript::opcodes::opcode_name(*opcode)),
+                        }
+                    }
+                    continue;
+                }
+                ["q"] | ["quit"] => break,
+                _ => {
+                    ctx.term().help(
+                        &[
+                            ("step | s", "Execute the next opcode (default)"),
+                            ("back | b", "Revert the last executed opcode"),
+                            ("continue | c", "Execute until the next breakpoint or the end of execution"),
+                            ("break <script>:<opcode> | <name>", "Break at an opcode location or at every occurrence of an opcode"),
+                            ("delete <script>:<opcode> | <name>", "Remove a breakpoint"),
+                            ("breakpoints", "List breakpoints"),
+                            ("print | p", "Display the current state"),
+                            ("reset | r", "Rewind to the first opcode"),
+                            ("quit | q", "Exit the debugger"),
+                        ],
+                        None,
+                    )?;
+                    continue;
+                }
+            }
+            self.display_position(&ctx, &debugger);
+        }
+
+        Ok(())
+    }
+
+    fn parse_breakpoint(location: &str) -> Result<Breakpoint> {
+        if let Some((script_index, opcode_index)) = location.split_once(':') {
+            Ok(Breakpoint::Location { script_index: script_index.parse()?, opcode_index: opcode_index.parse()? })
+        } else {
+            parse_opcode(location).map(Breakpoint::Opcode).ok_or_else(|| Error::custom(format!("unknown opcode '{location}'")))
+        }
+    }
+
+    fn display_position(&self, ctx: &Arc<KaspaCli>, debugger: &ScriptDebugger) {
+        let format_stack =
+            |stack: &[Vec<u8>]| stack.iter().map(|item| format!("0x{}", faster_hex::hex_string(item))).collect::<Vec<_>>().join(" ");
+
+        match debugger.current() {
+            Some(step) => {
+                let StepSnapshot { script_index, opcode_index, data, executing, num_ops, used_sig_ops, used_script_units, .. } = step;
+                let data = if data.is_empty() { String::new() } else { format!(" 0x{}", faster_hex::hex_string(data)) };
+                let skipped = if *executing { "" } else { " (skipped)" };
+                tprintln!(
+                    ctx,
+                    "[{}/{}] {script_index}:{opcode_index} {}{data}{skipped}",
+                    debugger.position(),
+                    debugger.steps().len(),
+                    step.opcode_name()
+                );
+                tprintln!(ctx, "  dstack: [{}]", format_stack(&step.dstack));
+                tprintln!(ctx, "  astack: [{}]", format_stack(&step.astack));
+                tprintln!(ctx, "  cond stack: {:?}", step.cond_stack);
+                tprintln!(ctx, "  ops: {num_ops}, sig ops: {used_sig_ops}, script units: {}", used_script_units.0);
+            }
+            None => match debugger.outcome() {
+                Some(outcome) => {
+                    match &outcome.result {
+                        Ok(()) => tprintln!(ctx, "execution succeeded"),
+                        Err(err) => tprintln!(ctx, "execution failed: {err}"),
+                    }
+                    tprintln!(ctx, "  dstack: [{}]", format_stack(&outcome.dstack));
+                }
+                None => tprintln!(ctx, "execution did not complete"),
+            },
+        }
+    }
+
+    fn display_error(&self, ctx: &Arc<KaspaCli>, source: &str, err: &AsmError) {
+        if let Some(span) = err.span() {
+            let width = source[span.clone()].chars().count().max(1);
+            let offset = source[..span.start].chars().count();
+            tprintln!(ctx, "{source}");
+            tprintln!(ctx, "{}{}", " ".repeat(offset), "^".repeat(width));
+        }
+    }
+
+    async fn display_help(self: Arc<Self>, ctx: Arc<KaspaCli>, _argv: Vec<String>) -> Result<()> {
+        ctx.term().help(
+            &[
+                ("asm <script>", "Assemble a script from opcode names, 0x prefixed hex data pushes and numbers"),
+                ("disasm <hex>", "Disassemble a hex encoded script"),
+                (
+                    "validate <transaction json>",
+                    "Validate the scripts of a transaction against the UTXO set of the connected node, without submitting it",
+                ),
+                (
+                    "debug <input index> [covenants]",
+                    "Step through the scripts of a transaction input. Prompts for the transaction JSON, inputs including their UTXO entries.",
+                ),
+            ],
+            None,
+        )?;
+
+        Ok(())
+    }
+}
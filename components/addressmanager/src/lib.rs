@@ -16,17 +16,26 @@ use itertools::{
 use kaspa_consensus_core::config::Config;
 use kaspa_core::{debug, info, task::tick::TickService, time::unix_now, warn};
 use kaspa_database::prelude::{CachePolicy, DB, StoreResultExt};
-use kaspa_utils::networking::IpAddress;
+use kaspa_utils::networking::{IpAddress, OnionNetAddress};
 use local_ip_address::list_afinet_netifas;
 use parking_lot::Mutex;
-use stores::banned_address_store::{BannedAddressesStore, BannedAddressesStoreReader, ConnectionBanTimestamp, DbBannedAddressesStore};
+use stores::banned_address_store::{
+    BannedAddressesStore, BannedAddressesStoreReader, ConnectionBanDetails, ConnectionBanTimestamp, DbBannedAddressesStore,
+};
 use thiserror::Error;
 
 pub use stores::NetAddress;
 
 const MAX_ADDRESSES: usize = 4096;
+const MAX_ONION_ADDRESSES: usize = 1024;
 const MAX_CONNECTION_FAILED_COUNT: u64 = 3;
 
+/// The duration of bans which do not specify one, such as manual bans and bans recorded without details
+pub const DEFAULT_BAN_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The reason recorded for manual bans
+const MANUAL_BAN_REASON: &str = "manual ban";
+
 const UPNP_DEADLINE_SEC: u64 = 2 * 60;
 const UPNP_EXTEND_PERIOD: u64 = UPNP_DEADLINE_SEC / 2;
 
@@ -51,9 +60,21 @@ pub enum UpnpError {
     GetExternalIpError(#[from] GetExternalIpError),
 }
 
+/// An active ban of an IP address
+#[derive(Clone, Debug)]
+pub struct BannedAddress {
+    pub ip: IpAddress,
+    /// Unix timestamp (in milliseconds) at which the ban was recorded
+    pub timestamp: u64,
+    /// Unix timestamp (in milliseconds) at which the ban expires
+    pub expiry: u64,
+    pub reason: String,
+}
+
 pub struct AddressManager {
     banned_address_store: DbBannedAddressesStore,
     address_store: address_store_with_cache::Store,
+    onion_address_store: onion_address_store_with_cache::Store,
     config: Arc<Config>,
     local_net_addresses: Vec<NetAddress>,
 }
@@ -62,7 +83,8 @@ impl AddressManager {
     pub fn new(config: Arc<Config>, db: Arc<DB>, tick_service: Arc<TickService>) -> (Arc<Mutex<Self>>, Option<Extender>) {
         let mut instance = Self {
             banned_address_store: DbBannedAddressesStore::new(db.clone(), CachePolicy::Count(MAX_ADDRESSES)),
-            address_store: address_store_with_cache::new(db),
+            address_store: address_store_with_cache::new(db.clone()),
+            onion_address_store: onion_address_store_with_cache::new(db),
             local_net_addresses: Vec::new(),
             config,
         };
@@ -264,6 +286,12 @@ impl AddressManager {
             return;
         }
 
+        // Onion peers are tracked by their onion address rather than by their placeholder IP, and the placeholder
+        // IPs of proxied hostnames cannot be connected to
+        if address.ip.is_onion_placeholder() || address.ip.is_hostname_placeholder() {
+            return;
+        }
+
         if self.address_store.has(address) {
             return;
         }
@@ -304,9 +332,58 @@ impl AddressManager {
         self.address_store.iterate_prioritized_random_addresses(exceptions)
     }
 
+    /// Onion addresses are tracked separately from IP addresses since they can only be reached through a proxy
+    pub fn add_onion_address(&mut self, address: OnionNetAddress) {
+        if self.onion_address_store.has(address) {
+            return;
+        }
+
+        // We mark `connection_failed_count` as 0 only after first success
+        self.onion_address_store.set(address, 1);
+    }
+
+    pub fn mark_onion_connection_failure(&mut self, address: OnionNetAddress) {
+        let Some(connection_failed_count) = self.onion_address_store.get(address) else {
+            return;
+        };
+
+        if connection_failed_count + 1 > MAX_CONNECTION_FAILED_COUNT {
+            self.onion_address_store.remove(address);
+        } else {
+            self.onion_address_store.set(address, connection_failed_count + 1);
+        }
+    }
+
+    pub fn mark_onion_connection_success(&mut self, address: OnionNetAddress) {
+        if self.onion_address_store.has(address) {
+            self.onion_address_store.set(address, 0);
+        }
+    }
+
+    pub fn iterate_onion_addresses(&self) -> impl Iterator<Item = OnionNetAddress> + '_ {
+        self.onion_address_store.iterate_addresses()
+    }
+
+    /// Returns the onion addresses in random order, preferring ones with fewer connection failures
+    pub fn iterate_prioritized_random_onion_addresses(
+        &self,
+        exceptions: HashSet<OnionNetAddress>,
+    ) -> impl ExactSizeIterator<Item = OnionNetAddress> + 'static {
+        self.onion_address_store.iterate_prioritized_random_addresses(exceptions)
+    }
+
     pub fn ban(&mut self, ip: IpAddress) {
-        self.banned_address_store.set(ip.into(), ConnectionBanTimestamp(unix_now())).unwrap();
+        self.ban_for(ip, DEFAULT_BAN_DURATION, MANUAL_BAN_REASON.to_owned());
+    }
+
+    /// Bans `ip` for `duration`. Bans are expired lazily, when queried.
+    pub fn ban_for(&mut self, ip: IpAddress, duration: Duration, reason: String) {
+        let now = unix_now();
+        let expiry = now.saturating_add(duration.as_millis() as u64);
+        self.banned_address_store.set_details(ip.into(), ConnectionBanDetails { expiry, reason }).unwrap();
+        self.banned_address_store.set(ip.into(), ConnectionBanTimestamp(now)).unwrap();
         self.address_store.remove_by_ip(ip.into());
+        self.onion_address_store.remove_by_placeholder_ip(ip);
     }
 
     pub fn unban(&mut self, ip: IpAddress) {
@@ -314,18 +391,27 @@ impl AddressManager {
     }
 
     pub fn is_banned(&mut self, ip: IpAddress) -> bool {
-        const MAX_BANNED_TIME: u64 = 24 * 60 * 60 * 1000;
-        match self.banned_address_store.get(ip.into()).optional().unwrap() {
-            Some(timestamp) => {
-                if unix_now() - timestamp.0 > MAX_BANNED_TIME {
-                    self.unban(ip);
-                    false
-                } else {
-                    true
-                }
-            }
-            None => false,
+        self.get_ban(ip).is_some()
+    }
+
+    /// Returns the ban of `ip` if it is active, removing it if it has expired
+    pub fn get_ban(&mut self, ip: IpAddress) -> Option<BannedAddress> {
+        let timestamp = self.banned_address_store.get(ip.into()).optional().unwrap()?.0;
+        let (expiry, reason) = match self.banned_address_store.get_details(ip.into()).optional().unwrap() {
+            Some(details) => (details.expiry, details.reason),
+            None => (timestamp.saturating_add(DEFAULT_BAN_DURATION.as_millis() as u64), MANUAL_BAN_REASON.to_owned()),
+        };
+        if unix_now() > expiry {
+            self.unban(ip);
+            return None;
         }
+        Some(BannedAddress { ip, timestamp, expiry, reason })
+    }
+
+    /// Returns all active bans, removing the expired ones
+    pub fn get_active_bans(&mut self) -> Vec<BannedAddress> {
+        let addresses = self.get_all_banned_addresses();
+        addresses.into_iter().filter_map(|ip| self.get_ban(ip)).collect()
     }
 
     pub fn get_all_addresses(&self) -> Vec<NetAddress> {
@@ -335,6 +421,140 @@ impl AddressManager {
     pub fn get_all_banned_addresses(&self) -> Vec<IpAddress> {
         self.banned_address_store.iterator().map(|x| IpAddress::from(x.unwrap().0)).collect_vec()
     }
+
+    pub fn get_all_onion_addresses(&self) -> Vec<OnionNetAddress> {
+        self.onion_address_store.iterate_addresses().collect_vec()
+    }
+}
+
+mod onion_address_store_with_cache {
+    // Same as `address_store_with_cache`, we keep a copy of the (limited) database onion addresses in memory.
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    };
+
+    use itertools::Itertools;
+    use kaspa_database::prelude::{CachePolicy, DB};
+    use kaspa_utils::networking::{IpAddress, OnionNetAddress};
+    use rand::seq::SliceRandom;
+
+    use crate::{
+        MAX_ONION_ADDRESSES,
+        stores::onion_address_store::{DbOnionAddressesStore, OnionAddressesStore, OnionEntry},
+    };
+
+    pub struct Store {
+        db_store: DbOnionAddressesStore,
+        addresses: HashMap<OnionNetAddress, OnionEntry>,
+    }
+
+    impl Store {
+        fn new(db: Arc<DB>) -> Self {
+            // We manage the cache ourselves on this level, so we disable the inner builtin cache
+            let db_store = DbOnionAddressesStore::new(db, CachePolicy::Empty);
+            let addresses = db_store.iterator().map(|res| res.unwrap()).collect();
+            Self { db_store, addresses }
+        }
+
+        pub fn has(&self, address: OnionNetAddress) -> bool {
+            self.addresses.contains_key(&address)
+        }
+
+        pub fn get(&self, address: OnionNetAddress) -> Option<u64> {
+            self.addresses.get(&address).map(|entry| entry.connection_failed_count)
+        }
+
+        pub fn set(&mut self, address: OnionNetAddress, connection_failed_count: u64) {
+            let entry = OnionEntry { connection_failed_count };
+            self.db_store.set(address, entry).unwrap();
+            self.addresses.insert(address, entry);
+            self.keep_limit();
+        }
+
+        fn keep_limit(&mut self) {
+            while self.addresses.len() > MAX_ONION_ADDRESSES {
+                let to_remove = *self.addresses.iter().max_by_key(|(_, entry)| entry.connection_failed_count).unwrap().0;
+                self.remove(to_remove);
+            }
+        }
+
+        pub fn remove(&mut self, address: OnionNetAddress) {
+            self.addresses.remove(&address);
+            self.db_store.remove(address).unwrap()
+        }
+
+        pub fn remove_by_placeholder_ip(&mut self, ip: IpAddress) {
+            for address in self.addresses.keys().filter(|address| address.onion.placeholder_ip() == ip).copied().collect_vec() {
+                self.remove(address);
+            }
+        }
+
+        pub fn iterate_addresses(&self) -> impl Iterator<Item = OnionNetAddress> + '_ {
+            self.addresses.keys().copied()
+        }
+
+        pub fn iterate_prioritized_random_addresses(
+            &self,
+            exceptions: HashSet<OnionNetAddress>,
+        ) -> impl ExactSizeIterator<Item = OnionNetAddress> + 'static {
+            let mut addresses = self.addresses.iter().filter(|(address, _)| !exceptions.contains(address)).collect_vec();
+            addresses.shuffle(&mut rand::thread_rng());
+            // The sort is stable, so addresses with equal failure counts remain shuffled
+            addresses.sort_by_key(|(_, entry)| entry.connection_failed_count);
+            addresses.into_iter().map(|(address, _)| *address).collect_vec().into_iter()
+        }
+    }
+
+    pub fn new(db: Arc<DB>) -> Store {
+        Store::new(db)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::str::FromStr;
+
+        use super::*;
+        use crate::MAX_CONNECTION_FAILED_COUNT;
+        use address_manager::AddressManager;
+        use kaspa_consensus_core::config::{Config, params::SIMNET_PARAMS};
+        use kaspa_core::task::tick::TickService;
+        use kaspa_database::{create_temp_db, prelude::ConnBuilder};
+
+        #[test]
+        fn test_onion_addresses() {
+            let db = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+            let mut config = Config::new(SIMNET_PARAMS);
+            config.disable_upnp = true;
+            let (am, _) = AddressManager::new(Arc::new(config), db.1.clone(), Arc::new(TickService::default()));
+            let mut am = am.lock();
+
+            let onion = OnionNetAddress::from_str("2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion:16111").unwrap();
+            let other = OnionNetAddress::new(onion.onion, 16112);
+            am.add_onion_address(onion);
+            am.add_onion_address(other);
+            am.mark_onion_connection_success(onion);
+
+            // Onion addresses are kept apart from IP addresses
+            assert!(am.get_all_addresses().is_empty());
+            assert_eq!(am.get_all_onion_addresses().len(), 2);
+            assert_eq!(am.iterate_prioritized_random_onion_addresses(HashSet::new()).collect_vec(), vec![onion, other]);
+            assert_eq!(am.iterate_prioritized_random_onion_addresses(HashSet::from([onion])).collect_vec(), vec![other]);
+
+            // The store survives restarts
+            assert_eq!(Store::new(db.1.clone()).get(onion), Some(0));
+
+            // Repeated failures evict the address
+            for _ in 0..MAX_CONNECTION_FAILED_COUNT {
+                am.mark_onion_connection_failure(other);
+            }
+            assert_eq!(am.get_all_onion_addresses(), vec![onion]);
+
+            // Banning the placeholder IP of an onion service drops its addresses
+            am.ban(onion.onion.placeholder_ip());
+            assert!(am.get_all_onion_addresses().is_empty());
+        }
+    }
 }
 
 mod address_store_with_cache {
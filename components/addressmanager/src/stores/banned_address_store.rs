@@ -13,12 +13,25 @@ pub struct ConnectionBanTimestamp(pub u64);
 
 impl MemSizeEstimator for ConnectionBanTimestamp {}
 
+/// Details of a ban, stored alongside its [`ConnectionBanTimestamp`]. Bans recorded before
+/// details were introduced have none.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConnectionBanDetails {
+    /// Unix timestamp (in milliseconds) at which the ban expires
+    pub expiry: u64,
+    pub reason: String,
+}
+
+impl MemSizeEstimator for ConnectionBanDetails {}
+
 pub trait BannedAddressesStoreReader {
     fn get(&self, address: IpAddr) -> Result<ConnectionBanTimestamp, StoreError>;
+    fn get_details(&self, address: IpAddr) -> Result<ConnectionBanDetails, StoreError>;
 }
 
 pub trait BannedAddressesStore: BannedAddressesStoreReader {
     fn set(&mut self, ip: IpAddr, timestamp: ConnectionBanTimestamp) -> StoreResult<()>;
+    fn set_details(&mut self, ip: IpAddr, details: ConnectionBanDetails) -> StoreResult<()>;
     fn remove(&mut self, ip: IpAddr) -> StoreResult<()>;
 }
 
@@ -70,11 +83,16 @@ impl From<AddressKey> for IpAddr {
 pub struct DbBannedAddressesStore {
     db: Arc<DB>,
     access: CachedDbAccess<AddressKey, ConnectionBanTimestamp>,
+    details_access: CachedDbAccess<AddressKey, ConnectionBanDetails>,
 }
 
 impl DbBannedAddressesStore {
     pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
-        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::BannedAddresses.into()) }
+        Self {
+            db: Arc::clone(&db),
+            access: CachedDbAccess::new(db.clone(), cache_policy, DatabaseStorePrefixes::BannedAddresses.into()),
+            details_access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::BannedAddressDetails.into()),
+        }
     }
 
     pub fn iterator(&self) -> impl Iterator<Item = Result<(IpAddr, ConnectionBanTimestamp), Box<dyn Error>>> + '_ {
@@ -96,6 +114,10 @@ impl BannedAddressesStoreReader for DbBannedAddressesStore {
     fn get(&self, ip: IpAddr) -> Result<ConnectionBanTimestamp, StoreError> {
         self.access.read(ip.into())
     }
+
+    fn get_details(&self, ip: IpAddr) -> Result<ConnectionBanDetails, StoreError> {
+        self.details_access.read(ip.into())
+    }
 }
 
 impl BannedAddressesStore for DbBannedAddressesStore {
@@ -103,7 +125,12 @@ impl BannedAddressesStore for DbBannedAddressesStore {
         self.access.write(DirectDbWriter::new(&self.db), ip.into(), timestamp)
     }
 
+    fn set_details(&mut self, ip: IpAddr, details: ConnectionBanDetails) -> StoreResult<()> {
+        self.details_access.write(DirectDbWriter::new(&self.db), ip.into(), details)
+    }
+
     fn remove(&mut self, ip: IpAddr) -> StoreResult<()> {
+        self.details_access.delete(DirectDbWriter::new(&self.db), ip.into())?;
         self.access.delete(DirectDbWriter::new(&self.db), ip.into())
     }
 }
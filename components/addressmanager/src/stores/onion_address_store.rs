@@ -0,0 +1,92 @@
+use kaspa_database::{
+    prelude::{CachePolicy, StoreResult},
+    prelude::{CachedDbAccess, DB, DirectDbWriter},
+    registry::DatabaseStorePrefixes,
+};
+use kaspa_utils::{
+    mem_size::MemSizeEstimator,
+    networking::{ONION_V3_PUBKEY_LEN, OnionAddress, OnionNetAddress},
+};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt::Display, sync::Arc};
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct OnionEntry {
+    pub connection_failed_count: u64,
+}
+
+impl MemSizeEstimator for OnionEntry {}
+
+pub trait OnionAddressesStore {
+    fn set(&mut self, address: OnionNetAddress, entry: OnionEntry) -> StoreResult<()>;
+    fn remove(&mut self, address: OnionNetAddress) -> StoreResult<()>;
+}
+
+const PORT_LEN: usize = 2;
+const ONION_ADDRESS_KEY_SIZE: usize = ONION_V3_PUBKEY_LEN + PORT_LEN;
+
+#[derive(Eq, Hash, PartialEq, Debug, Copy, Clone)]
+struct DbOnionAddressKey([u8; ONION_ADDRESS_KEY_SIZE]);
+
+impl AsRef<[u8]> for DbOnionAddressKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for DbOnionAddressKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let address: OnionNetAddress = (*self).into();
+        write!(f, "{address}")
+    }
+}
+
+impl From<OnionNetAddress> for DbOnionAddressKey {
+    fn from(address: OnionNetAddress) -> Self {
+        let mut bytes = [0; ONION_ADDRESS_KEY_SIZE];
+        bytes[..ONION_V3_PUBKEY_LEN].copy_from_slice(address.onion.pubkey());
+        bytes[ONION_V3_PUBKEY_LEN..].copy_from_slice(&address.port.to_le_bytes());
+        Self(bytes)
+    }
+}
+
+impl From<DbOnionAddressKey> for OnionNetAddress {
+    fn from(k: DbOnionAddressKey) -> Self {
+        let pubkey: [u8; ONION_V3_PUBKEY_LEN] = k.0[..ONION_V3_PUBKEY_LEN].try_into().unwrap();
+        let port_byte_array: [u8; PORT_LEN] = k.0[ONION_V3_PUBKEY_LEN..].try_into().unwrap();
+        OnionNetAddress::new(OnionAddress::new(pubkey), u16::from_le_bytes(port_byte_array))
+    }
+}
+
+/// Stores onion addresses separately from IP addresses, since they can only be dialed through a proxy
+#[derive(Clone)]
+pub struct DbOnionAddressesStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<DbOnionAddressKey, OnionEntry>,
+}
+
+impl DbOnionAddressesStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::OnionAddresses.into()) }
+    }
+
+    pub fn iterator(&self) -> impl Iterator<Item = Result<(OnionNetAddress, OnionEntry), Box<dyn Error>>> + '_ {
+        self.access.iterator().map(|iter_result| match iter_result {
+            Ok((key_bytes, entry)) => match <[u8; ONION_ADDRESS_KEY_SIZE]>::try_from(&key_bytes[..]) {
+                Ok(address_key_slice) => Ok((DbOnionAddressKey(address_key_slice).into(), entry)),
+                Err(e) => Err(e.into()),
+            },
+            Err(e) => Err(e),
+        })
+    }
+}
+
+impl OnionAddressesStore for DbOnionAddressesStore {
+    fn set(&mut self, address: OnionNetAddress, entry: OnionEntry) -> StoreResult<()> {
+        self.access.write(DirectDbWriter::new(&self.db), address.into(), entry)
+    }
+
+    fn remove(&mut self, address: OnionNetAddress) -> StoreResult<()> {
+        self.access.delete(DirectDbWriter::new(&self.db), address.into())
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     cmp::min,
     collections::{HashMap, HashSet},
+    fmt::Display,
     net::{IpAddr, SocketAddr, ToSocketAddrs},
     sync::Arc,
     time::{Duration, SystemTime},
@@ -12,7 +13,7 @@ use itertools::Itertools;
 use kaspa_addressmanager::{AddressManager, NetAddress};
 use kaspa_core::{debug, info, warn};
 use kaspa_p2p_lib::{ConnectionError, Peer, common::ProtocolError};
-use kaspa_utils::triggers::SingleTrigger;
+use kaspa_utils::{networking::OnionNetAddress, triggers::SingleTrigger};
 use parking_lot::Mutex as ParkingLotMutex;
 use rand::{seq::SliceRandom, thread_rng};
 use tokio::{
@@ -49,6 +50,39 @@ impl ConnectionRequest {
     }
 }
 
+/// A candidate address for an outbound connection
+#[derive(Clone, Copy, Debug)]
+enum OutboundAddress {
+    Ip(NetAddress),
+    /// Onion addresses are only dialed through a proxy
+    Onion(OnionNetAddress),
+}
+
+impl OutboundAddress {
+    fn mark_connection_success(self, address_manager: &mut AddressManager) {
+        match self {
+            OutboundAddress::Ip(address) => address_manager.mark_connection_success(address),
+            OutboundAddress::Onion(address) => address_manager.mark_onion_connection_success(address),
+        }
+    }
+
+    fn mark_connection_failure(self, address_manager: &mut AddressManager) {
+        match self {
+            OutboundAddress::Ip(address) => address_manager.mark_connection_failure(address),
+            OutboundAddress::Onion(address) => address_manager.mark_onion_connection_failure(address),
+        }
+    }
+}
+
+impl Display for OutboundAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutboundAddress::Ip(address) => SocketAddr::new(address.ip.into(), address.port).fmt(f),
+            OutboundAddress::Onion(address) => address.fmt(f),
+        }
+    }
+}
+
 impl ConnectionManager {
     pub fn new(
         p2p_adaptor: Arc<kaspa_p2p_lib::Adaptor>,
@@ -173,7 +207,23 @@ impl ConnectionManager {
         }
 
         let mut missing_connections = self.outbound_target - active_outbound.len();
-        let mut addr_iter = self.address_manager.lock().iterate_prioritized_random_addresses(active_outbound);
+        let mut addr_iter = {
+            let address_manager = self.address_manager.lock();
+            let ip_addresses = address_manager.iterate_prioritized_random_addresses(active_outbound.clone()).map(OutboundAddress::Ip);
+            if self.p2p_adaptor.is_proxied() {
+                // Connected onion peers are identified by their placeholder address
+                let active_onion = address_manager
+                    .iterate_onion_addresses()
+                    .filter(|address| active_outbound.contains(&address.placeholder_address()))
+                    .collect();
+                let onion_addresses =
+                    address_manager.iterate_prioritized_random_onion_addresses(active_onion).map(OutboundAddress::Onion);
+                ip_addresses.interleave(onion_addresses).collect_vec()
+            } else {
+                ip_addresses.collect_vec()
+            }
+        }
+        .into_iter();
         let mut progressing = true;
         let mut connecting = true;
         while connecting && missing_connections > 0 {
@@ -187,10 +237,10 @@ impl ConnectionManager {
                     connecting = false;
                     break;
                 };
-                let socket_addr = SocketAddr::new(net_addr.ip.into(), net_addr.port).to_string();
-                debug!("Connecting to {}", &socket_addr);
+                let peer_address = net_addr.to_string();
+                debug!("Connecting to {}", &peer_address);
                 addrs_to_connect.push(net_addr);
-                jobs.push(self.p2p_adaptor.connect_peer(socket_addr.clone()));
+                jobs.push(self.p2p_adaptor.connect_peer(peer_address));
             }
 
             if progressing && !jobs.is_empty() {
@@ -214,7 +264,7 @@ impl ConnectionManager {
             for (res, net_addr) in (join_all(jobs).await).into_iter().zip(addrs_to_connect) {
                 match res {
                     Ok(_) => {
-                        self.address_manager.lock().mark_connection_success(net_addr);
+                        net_addr.mark_connection_success(&mut self.address_manager.lock());
                         missing_connections -= 1;
                         progressing = true;
                     }
@@ -224,7 +274,7 @@ impl ConnectionManager {
                     }
                     Err(err) => {
                         debug!("Failed connecting to {:?}, err: {}", net_addr, err);
-                        self.address_manager.lock().mark_connection_failure(net_addr);
+                        net_addr.mark_connection_failure(&mut self.address_manager.lock());
                     }
                 }
             }
@@ -319,12 +369,27 @@ impl ConnectionManager {
         if self.ip_has_permanent_connection(ip).await {
             return;
         }
+        self.terminate_peers_by_ip(ip).await;
+        self.address_manager.lock().ban(ip.into());
+    }
+
+    /// Bans the given IP for `duration` and disconnects from all the peers with that IP.
+    /// Returns false if the IP has a permanent connection and thus was not banned.
+    pub async fn ban_for(&self, ip: IpAddr, duration: Duration, reason: String) -> bool {
+        if self.ip_has_permanent_connection(ip).await {
+            return false;
+        }
+        self.terminate_peers_by_ip(ip).await;
+        self.address_manager.lock().ban_for(ip.into(), duration, reason);
+        true
+    }
+
+    async fn terminate_peers_by_ip(&self, ip: IpAddr) {
         for peer in self.p2p_adaptor.active_peers() {
             if peer.net_address().ip() == ip {
                 self.p2p_adaptor.terminate(peer.key()).await;
             }
         }
-        self.address_manager.lock().ban(ip.into());
     }
 
     /// Returns whether the given address is banned.
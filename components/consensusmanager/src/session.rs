@@ -7,7 +7,7 @@ use kaspa_consensus_core::{
     acceptance_data::{AcceptanceData, MergedBlockContext, MergesetBlockAcceptanceData},
     api::{
         BlockCount, BlockValidationFutures, ConsensusApi, ConsensusStats, DynConsensus, ImportLane, ImportLaneBatchIterator,
-        SeqCommitLaneProof,
+        SeqCommitLaneProof, SeqCommitLaneProofs, SeqCommitLaneState, TransactionScriptsReport,
     },
     block::Block,
     blockstatus::BlockStatus,
@@ -17,7 +17,10 @@ use kaspa_consensus_core::{
     mass::{ContextualMasses, NonContextualMasses},
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList},
     trusted::{ExternalGhostdagData, TrustedBlock},
-    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, TransactionQueryResult, TransactionType, UtxoEntry},
+    tx::{
+        MutableTransaction, Transaction, TransactionId, TransactionIndexType, TransactionOutpoint, TransactionQueryResult,
+        TransactionType, UtxoEntry,
+    },
 };
 use kaspa_utils::sync::rwlock::*;
 use std::{ops::Deref, sync::Arc};
@@ -204,6 +207,11 @@ impl ConsensusSessionOwned {
         self.consensus.calculate_transaction_contextual_masses(transaction)
     }
 
+    /// Validates the transaction along with all of its input scripts against the virtual state without persisting anything
+    pub async fn async_validate_transaction_scripts(&self, mut transaction: MutableTransaction) -> TxResult<TransactionScriptsReport> {
+        self.clone().spawn_blocking(move |c| c.validate_transaction_scripts(&mut transaction)).await
+    }
+
     pub fn get_virtual_daa_score(&self) -> u64 {
         // Accessing cached virtual fields is lock-free and does not require spawn_blocking
         self.consensus.get_virtual_daa_score()
@@ -390,6 +398,14 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_block_body(hash)).await
     }
 
+    pub async fn async_get_block_transactions(
+        &self,
+        hash: Hash,
+        indices: Option<Vec<TransactionIndexType>>,
+    ) -> ConsensusResult<Vec<Transaction>> {
+        self.clone().spawn_blocking(move |c| c.get_block_transactions(hash, indices)).await
+    }
+
     pub async fn async_get_block_even_if_header_only(&self, hash: Hash) -> ConsensusResult<Block> {
         self.clone().spawn_blocking(move |c| c.get_block_even_if_header_only(hash)).await
     }
@@ -433,6 +449,18 @@ impl ConsensusSessionOwned {
         self.clone().spawn_blocking(move |c| c.get_seq_commit_lane_proof(block_hash, lane_key)).await
     }
 
+    pub async fn async_get_seq_commit_lane_proofs(
+        &self,
+        block_hash: Hash,
+        lane_keys: Vec<Hash>,
+    ) -> ConsensusResult<SeqCommitLaneProofs> {
+        self.clone().spawn_blocking(move |c| c.get_seq_commit_lane_proofs(block_hash, lane_keys)).await
+    }
+
+    pub async fn async_get_seq_commit_lane_state(&self, block_hash: Hash, lane_key: Hash) -> ConsensusResult<SeqCommitLaneState> {
+        self.clone().spawn_blocking(move |c| c.get_seq_commit_lane_state(block_hash, lane_key)).await
+    }
+
     pub async fn async_get_pruning_point_utxos(
         &self,
         expected_pruning_point: Hash,
@@ -18,6 +18,9 @@ pub enum Error {
     #[error(transparent)]
     ScriptBuilder(#[from] kaspa_txscript::script_builder::ScriptBuilderError),
 
+    #[error(transparent)]
+    TxScript(#[from] kaspa_txscript::error::Error),
+
     #[error("{0}")]
     ParseInt(#[from] std::num::ParseIntError),
 
@@ -6,11 +6,13 @@
 
 use crate::imports::*;
 use crate::result::Result;
+use crate::{Transaction, TransactionT};
 use kaspa_addresses::*;
 use kaspa_consensus_core::{
     network::{NetworkType, NetworkTypeT},
-    tx::ScriptPublicKeyT,
+    tx::{PopulatedTransaction, ScriptPublicKeyT},
 };
+use kaspa_txscript::wasm::{ScriptDebugger, ScriptDebuggerOptions};
 use kaspa_txscript::{script_class::ScriptClass, standard};
 use kaspa_utils::hex::ToHex;
 use kaspa_wasm_core::types::{BinaryT, HexString};
@@ -85,3 +87,22 @@ pub fn is_script_pay_to_script_hash(script: BinaryT) -> Result<bool> {
     let script = script.try_as_vec_u8()?;
     Ok(ScriptClass::is_pay_to_script_hash(script.as_slice()))
 }
+
+/// Records the script execution of a transaction input for step-through debugging.
+/// All transaction inputs must be populated with their UTXO entries.
+/// @param tx - The transaction ({@link Transaction} or {@link ITransaction}).
+/// @param input_index - Index of the debugged input.
+/// @param options - Script engine options.
+/// @see {@link ScriptDebugger}
+/// @category Wallet SDK
+#[wasm_bindgen(js_name = debugTransactionInput)]
+pub fn debug_transaction_input(
+    tx: &TransactionT,
+    input_index: usize,
+    options: Option<ScriptDebuggerOptions>,
+) -> Result<ScriptDebugger> {
+    let tx = Transaction::try_cast_from(tx)?;
+    let (tx, utxos) = tx.as_ref().tx_and_utxos()?;
+    let populated = PopulatedTransaction::new(&tx, utxos);
+    Ok(ScriptDebugger::try_from_transaction_input(&populated, input_index, options)?)
+}
@@ -18,7 +18,7 @@ use crate::{
         tx::TxResult,
     },
     header::Header,
-    mass::{ContextualMasses, NonContextualMasses},
+    mass::{ContextualMasses, NonContextualMasses, ScriptUnits},
     pruning::{PruningPointProof, PruningPointTrustedData, PruningPointsList, PruningProofMetadata},
     trusted::{ExternalGhostdagData, TrustedBlock},
     tx::{
@@ -27,6 +27,7 @@ use crate::{
     },
 };
 use kaspa_hashes::Hash;
+use kaspa_txscript_errors::TxScriptError;
 
 pub use self::stats::{BlockCount, ConsensusStats};
 
@@ -87,6 +88,17 @@ pub struct SeqCommitLaneEntry {
     pub blue_score: u64,
 }
 
+/// State of a single lane as of a canonical block.
+///
+/// `lane` holds the latest canonical version of the lane at or below the block, or `None`
+/// if the lane was never touched (or its versions were already pruned). `is_active` is set
+/// iff the lane was touched within the inactivity threshold (finality depth) of the block.
+#[derive(Clone, Debug)]
+pub struct SeqCommitLaneState {
+    pub lane: Option<SeqCommitLaneEntry>,
+    pub is_active: bool,
+}
+
 /// Witness for verifying a single lane against the `seq_commit` of a canonical block.
 ///
 /// Given the block's header (which carries `seq_commit` in `accepted_id_merkle_root`),
@@ -108,6 +120,41 @@ pub struct SeqCommitLaneProof {
     pub inactivity_shortcut: Hash,
 }
 
+/// Witness for verifying several lanes at once against the `seq_commit` of a canonical block.
+///
+/// Same as [`SeqCommitLaneProof`], except that a single [`SmtMultiProof`](kaspa_smt::multiproof::SmtMultiProof)
+/// covers all requested lanes. `lanes` lists each distinct lane key in ascending order,
+/// paired with its entry (`None` for lanes absent at this POV).
+#[derive(Clone, Debug)]
+pub struct SeqCommitLaneProofs {
+    pub smt_proof: kaspa_smt::multiproof::SmtMultiProof,
+    pub lanes: Vec<(Hash, Option<SeqCommitLaneEntry>)>,
+    pub payload_and_ctx_digest: Hash,
+    pub parent_seq_commit: Hash,
+    pub inactivity_shortcut: Hash,
+}
+
+/// Outcome of executing the script of a single transaction input.
+#[derive(Clone, Debug)]
+pub struct InputScriptReport {
+    pub result: Result<(), TxScriptError>,
+    pub used_script_units: ScriptUnits,
+    pub used_sig_ops: u16,
+}
+
+/// Report of validating a transaction against the virtual UTXO set without inserting it anywhere.
+///
+/// Input scripts are executed independently, so a failing input does not hide the outcome of the
+/// following ones. `fee` holds the violated non-script UTXO-context rule (coinbase maturity, amounts,
+/// sequence locks), if any.
+#[derive(Clone, Debug)]
+pub struct TransactionScriptsReport {
+    pub fee: TxResult<u64>,
+    pub non_contextual_masses: NonContextualMasses,
+    pub contextual_masses: ContextualMasses,
+    pub inputs: Vec<InputScriptReport>,
+}
+
 /// Abstracts the consensus external API
 #[allow(unused_variables)]
 pub trait ConsensusApi: Send + Sync {
@@ -134,6 +181,13 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Populates the transaction with maximally found UTXO entry data (previously populated entries are kept) and,
+    /// if all are found, runs the UTXO-context validation along with every input script using the virtual state.
+    /// Nothing is persisted. Isolation, header-context and missing-outpoint errors are returned as errors.
+    fn validate_transaction_scripts(&self, transaction: &mut MutableTransaction) -> TxResult<TransactionScriptsReport> {
+        unimplemented!()
+    }
+
     /// Populates the mempool transactions with maximally found UTXO entry data and proceeds to full transactions
     /// validation if all are found. If validation is successful, also `transaction.calculated_fee` is expected to be populated.
     fn validate_mempool_transactions_in_parallel(
@@ -482,6 +536,21 @@ pub trait ConsensusApi: Send + Sync {
         unimplemented!()
     }
 
+    /// Batched version of [`Self::get_seq_commit_lane_proof`]: returns a single witness covering
+    /// all `lane_keys` against the `seq_commit` carried in `block_hash`'s header, subject to the
+    /// same restrictions on `block_hash`. Duplicate lane keys are proven once.
+    fn get_seq_commit_lane_proofs(&self, block_hash: Hash, lane_keys: Vec<Hash>) -> ConsensusResult<SeqCommitLaneProofs> {
+        unimplemented!()
+    }
+
+    /// Returns the state of the lane `lane_key` as of `block_hash`. The block must be a
+    /// post-Toccata chain (selected-parent) block at or after the current pruning point;
+    /// non-canonical blocks are rejected with [`ConsensusError::BlockNotInSelectedChain`]
+    /// and too-deep blocks with [`ConsensusError::BlockTooDeep`].
+    fn get_seq_commit_lane_state(&self, block_hash: Hash, lane_key: Hash) -> ConsensusResult<SeqCommitLaneState> {
+        unimplemented!()
+    }
+
     fn get_pruning_point_utxos(
         &self,
         expected_pruning_point: Hash,
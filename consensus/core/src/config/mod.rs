@@ -42,6 +42,12 @@ pub struct Config {
     /// Enable the UTXO index
     pub utxoindex: bool,
 
+    /// Enable the transaction index
+    pub txindex: bool,
+
+    /// Enable the address history index
+    pub addressindex: bool,
+
     /// Enable RPC commands which affect the state of the node
     pub unsafe_rpc: bool,
 
@@ -69,6 +75,18 @@ pub struct Config {
 
     pub disable_upnp: bool,
 
+    /// A SOCKS5 proxy through which outbound p2p connections are made. Onion peers are only dialed when set
+    pub proxy: Option<NetAddress>,
+
+    /// Use distinct proxy credentials per connection, so that Tor routes each peer over a separate circuit
+    pub proxy_stream_isolation: bool,
+
+    /// The misbehavior score at which peers are automatically banned. Zero disables automatic banning
+    pub ban_threshold: u32,
+
+    /// The duration (in seconds) of automatic bans of misbehaving peers
+    pub ban_duration: u64,
+
     /// A scale factor to apply to memory allocation bounds
     pub ram_scale: f64,
 
@@ -89,6 +107,8 @@ impl Config {
             is_archival: false,
             enable_sanity_checks: false,
             utxoindex: false,
+            txindex: false,
+            addressindex: false,
             unsafe_rpc: false,
             enable_unsynced_mining: false,
             enable_mainnet_mining: false,
@@ -101,6 +121,10 @@ impl Config {
             #[cfg(feature = "devnet-prealloc")]
             initial_utxo_set: Default::default(),
             disable_upnp: false,
+            proxy: None,
+            proxy_stream_isolation: true,
+            ban_threshold: 100,
+            ban_duration: 24 * 60 * 60,
             ram_scale: 1.0,
             retention_period_days: None,
         }
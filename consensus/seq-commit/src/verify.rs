@@ -1,11 +1,14 @@
-//! IBD SMT verification: metadata check.
+//! IBD SMT verification: metadata check, and `no_std` verification of lane multi-proofs.
 //!
 //! Proof verification with branch caching uses [`SmtProof::compute_root_with_visitor`]
 //! from `kaspa-smt` with `&mut ProofBranchCache` as the visitor.
 
-use crate::hashing::{activity_root_hash, seq_state_root};
-use crate::types::SeqState;
-use kaspa_hashes::{Hash, HasherBase, SeqCommitMerkleBranch};
+use alloc::vec::Vec;
+
+use crate::hashing::{activity_root_hash, seq_state_root, smt_leaf_hash};
+use crate::types::{SeqState, SmtLeafInput};
+use kaspa_hashes::{Hash, HasherBase, SeqCommitActiveNode, SeqCommitMerkleBranch};
+use kaspa_smt::multiproof::SmtMultiProof;
 
 /// Metadata sent before lane entries, verified against the pruning point header.
 #[derive(Clone, Copy, Debug)]
@@ -62,6 +65,41 @@ pub fn verify_smt_metadata(
     Ok(())
 }
 
+/// Claimed state of a single lane covered by a lane multi-proof.
+#[derive(Clone, Copy, Debug)]
+pub struct LaneClaim<'a> {
+    pub lane_key: &'a Hash,
+    /// `(lane_tip, blue_score)` of the lane, or `None` if the lane is claimed absent.
+    pub lane: Option<(&'a Hash, u64)>,
+}
+
+/// Compute the `lanes_root` implied by `proof` for the claimed lanes (in any order).
+pub fn compute_lanes_root(proof: &SmtMultiProof, claims: &[LaneClaim<'_>]) -> Result<Hash, SmtVerifyError> {
+    let leaves = claims
+        .iter()
+        .map(|claim| {
+            let leaf = claim.lane.map(|(lane_tip, blue_score)| smt_leaf_hash(&SmtLeafInput { lane_tip, blue_score }));
+            (*claim.lane_key, leaf)
+        })
+        .collect::<Vec<_>>();
+    Ok(proof.compute_root::<SeqCommitActiveNode>(&leaves)?)
+}
+
+/// Verify the claimed lanes against the `seq_commit` of a block, folding the `lanes_root`
+/// implied by `proof` together with the block's remaining commitment inputs.
+pub fn verify_lanes_multi_proof(
+    proof: &SmtMultiProof,
+    claims: &[LaneClaim<'_>],
+    payload_and_ctx_digest: &Hash,
+    parent_seq_commit: &Hash,
+    inactivity_shortcut: Hash,
+    expected_seq_commit: Hash,
+) -> Result<(), SmtVerifyError> {
+    let lanes_root = compute_lanes_root(proof, claims)?;
+    let metadata = SmtMetadata { lanes_root: &lanes_root, payload_and_ctx_digest, parent_seq_commit };
+    verify_smt_metadata(&metadata, inactivity_shortcut, expected_seq_commit, *parent_seq_commit)
+}
+
 #[cfg(test)]
 mod tests {
     extern crate std;
@@ -214,4 +252,45 @@ mod tests {
         assert!(branches.len() >= after_first);
         assert!(branches.len() < 512);
     }
+
+    #[test]
+    fn lanes_multi_proof_verifies_against_seq_commit() {
+        let entries = [(lid(1), tip(10), 100), (lid(2), tip(20), 200), (lid(3), tip(30), 300)];
+        let (root, tree) = build_ref(&entries);
+        let (pd, ps, shortcut) = (Hash::from_bytes([3; 32]), Hash::from_bytes([4; 32]), sample_shortcut());
+        let sc = build_expected_seq_commit(&root, &pd, &ps, shortcut);
+
+        let keys = [lane_key(&lid(3)), lane_key(&lid(1)), lane_key(&lid(9))];
+        let proof = tree.prove_many(&keys).unwrap();
+        let claims = [
+            LaneClaim { lane_key: &keys[0], lane: Some((&entries[2].1, 300)) },
+            LaneClaim { lane_key: &keys[1], lane: Some((&entries[0].1, 100)) },
+            LaneClaim { lane_key: &keys[2], lane: None },
+        ];
+        assert_eq!(compute_lanes_root(&proof, &claims).unwrap(), root);
+        assert!(verify_lanes_multi_proof(&proof, &claims, &pd, &ps, shortcut, sc).is_ok());
+    }
+
+    #[test]
+    fn lanes_multi_proof_wrong_claim() {
+        let entries = [(lid(1), tip(10), 100), (lid(2), tip(20), 200)];
+        let (root, tree) = build_ref(&entries);
+        let (pd, ps, shortcut) = (Hash::from_bytes([3; 32]), Hash::from_bytes([4; 32]), sample_shortcut());
+        let sc = build_expected_seq_commit(&root, &pd, &ps, shortcut);
+
+        let keys = [lane_key(&lid(1)), lane_key(&lid(2))];
+        let proof = tree.prove_many(&keys).unwrap();
+
+        let stale = [
+            LaneClaim { lane_key: &keys[0], lane: Some((&entries[0].1, 99)) },
+            LaneClaim { lane_key: &keys[1], lane: Some((&entries[1].1, 200)) },
+        ];
+        assert!(matches!(
+            verify_lanes_multi_proof(&proof, &stale, &pd, &ps, shortcut, sc),
+            Err(SmtVerifyError::SeqCommitMismatch { .. })
+        ));
+
+        let missing = [LaneClaim { lane_key: &keys[0], lane: Some((&entries[0].1, 100)) }];
+        assert!(matches!(verify_lanes_multi_proof(&proof, &missing, &pd, &ps, shortcut, sc), Err(SmtVerifyError::ProofError(_))));
+    }
 }
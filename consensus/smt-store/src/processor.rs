@@ -20,6 +20,7 @@ use kaspa_database::prelude::{BatchDbWriter, DB, DirectDbWriter, StoreError, Sto
 use kaspa_hashes::{Hash, SeqCommitActiveNode, ZERO_HASH};
 use kaspa_seq_commit::hashing::smt_leaf_hash;
 use kaspa_seq_commit::types::SmtLeafInput;
+use kaspa_smt::multiproof::SmtMultiProof;
 use kaspa_smt::proof::OwnedSmtProof;
 use kaspa_smt::store::{BranchKey, CollapsedLeaf, Node, SmtStore, SortedLeafUpdates};
 use kaspa_smt::streaming::{ChildInfo, MergeSink, StreamError, StreamingSmtBuilder};
@@ -272,6 +273,19 @@ impl SmtStores {
         tree.prove(lane_key)
     }
 
+    /// Generate a single multi-proof covering all `lane_keys` in the canonical tree as of
+    /// `target_blue_score`. Each lane may be active or absent.
+    pub fn prove_lanes(
+        &self,
+        lane_keys: &[Hash],
+        bounds: SmtReadBounds,
+        is_canonical: impl Fn(Hash) -> bool,
+    ) -> StoreResult<SmtMultiProof> {
+        let reader = VersionedBranchReader { stores: self, bounds, is_canonical };
+        let tree = SparseMerkleTree::<SeqCommitActiveNode, _>::with_store(reader);
+        tree.prove_many(lane_keys)
+    }
+
     pub fn evict_caches_below_score(&self, min_score: u64) {
         self.branch_cache.lock().evict_below_score(min_score);
         self.lane_cache.lock().evict_below_score(min_score);
@@ -47,7 +47,8 @@ use kaspa_consensus_core::{
     BlockHashSet, BlueWorkType, ChainPath, HashMapCustomHasher,
     acceptance_data::{AcceptanceData, MergedBlockContext, MergesetBlockAcceptanceData},
     api::{
-        BlockValidationFutures, ConsensusApi, ConsensusStats, ImportLaneBatchIterator, SeqCommitLaneProof,
+        BlockValidationFutures, ConsensusApi, ConsensusStats, ImportLaneBatchIterator, SeqCommitLaneProof, SeqCommitLaneProofs,
+        SeqCommitLaneState, TransactionScriptsReport,
         args::{TransactionValidationArgs, TransactionValidationBatchArgs},
         stats::BlockCount,
     },
@@ -445,6 +446,37 @@ impl Consensus {
         }
     }
 
+    /// Validates that `block_hash` can serve as a point of view for sequencing-commitment lane queries,
+    /// i.e., that it is a post-Toccata chain block at or after the current pruning point. Returns its header.
+    ///
+    /// Note: the caller is expected to hold the pruning lock
+    fn validate_seq_commit_pov(&self, block_hash: Hash) -> ConsensusResult<Arc<Header>> {
+        self.validate_block_exists(block_hash)?;
+
+        // Canonicality: must be a selected-parent-chain block (ancestor of or equal to sink).
+        let sink = self.get_sink();
+        if !self.services.reachability_service.is_chain_ancestor_of(block_hash, sink) {
+            return Err(ConsensusError::BlockNotInSelectedChain(block_hash));
+        }
+
+        // Depth: block must be at or after the current pruning point. Blocks before
+        // the pruning point may have had their SMT versions pruned.
+        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
+        if !self.services.reachability_service.is_chain_ancestor_of(pruning_point, block_hash) {
+            return Err(ConsensusError::BlockTooDeep(block_hash));
+        }
+
+        let header = self.headers_store.get_header(block_hash).unwrap();
+
+        // KIP-21 activity_root only exists post-Toccata. Drop the gate after all
+        // nets activate.
+        if !self.config.params.toccata_activation.is_active(header.daa_score) {
+            return Err(ConsensusError::GeneralOwned(format!("toccata is not active at block {block_hash}")));
+        }
+
+        Ok(header)
+    }
+
     fn estimate_network_hashes_per_second_impl(&self, ghostdag_data: &GhostdagData, window_size: usize) -> ConsensusResult<u64> {
         let window = match self.services.window_manager.block_window(ghostdag_data, WindowType::VaryingWindow(window_size)) {
             Ok(w) => w,
@@ -677,6 +709,11 @@ impl ConsensusApi for Consensus {
         self.virtual_processor.validate_mempool_transactions_in_parallel(transactions, args)
     }
 
+    fn validate_transaction_scripts(&self, transaction: &mut MutableTransaction) -> TxResult<TransactionScriptsReport> {
+        transaction.calculated_non_contextual_masses = Some(self.calculate_transaction_non_contextual_masses(&transaction.tx)?);
+        self.virtual_processor.validate_transaction_scripts(transaction)
+    }
+
     fn populate_mempool_transaction(&self, transaction: &mut MutableTransaction) -> TxResult<()> {
         self.virtual_processor.populate_mempool_transaction(transaction)?;
         Ok(())
@@ -1510,26 +1547,7 @@ impl ConsensusApi for Consensus {
             return Err(ConsensusError::BlockIsGenesis(block_hash));
         }
 
-        // Canonicality: must be a selected-parent-chain block (ancestor of or equal to sink).
-        let sink = self.get_sink();
-        if !self.services.reachability_service.is_chain_ancestor_of(block_hash, sink) {
-            return Err(ConsensusError::BlockNotInSelectedChain(block_hash));
-        }
-
-        // Depth: block must be at or after the current pruning point. Blocks before
-        // the pruning point may have had their SMT versions pruned.
-        let pruning_point = self.pruning_point_store.read().pruning_point().unwrap();
-        if !self.services.reachability_service.is_chain_ancestor_of(pruning_point, block_hash) {
-            return Err(ConsensusError::BlockTooDeep(block_hash));
-        }
-
-        let header = self.headers_store.get_header(block_hash).unwrap();
-
-        // KIP-21 activity_root only exists post-Toccata. Drop the gate after all
-        // nets activate.
-        if !self.config.params.toccata_activation.is_active(header.daa_score) {
-            return Err(ConsensusError::GeneralOwned(format!("toccata is not active at block {block_hash}")));
-        }
+        let header = self.validate_seq_commit_pov(block_hash)?;
 
         let selected_parent = header.post_toccata_chainblock_selected_parent();
         let parent_header = self.headers_store.get_header(selected_parent).unwrap();
@@ -1596,6 +1614,96 @@ impl ConsensusApi for Consensus {
         })
     }
 
+    fn get_seq_commit_lane_proofs(&self, block_hash: Hash, mut lane_keys: Vec<Hash>) -> ConsensusResult<SeqCommitLaneProofs> {
+        let _guard = self.pruning_lock.blocking_read();
+        self.validate_block_exists(block_hash)?;
+
+        // Genesis has no selected parent; reject before we try to dereference one.
+        if block_hash == self.config.params.genesis.hash {
+            return Err(ConsensusError::BlockIsGenesis(block_hash));
+        }
+
+        let header = self.validate_seq_commit_pov(block_hash)?;
+
+        let selected_parent = header.post_toccata_chainblock_selected_parent();
+        let parent_header = self.headers_store.get_header(selected_parent).unwrap();
+
+        let finality_depth = self.config.params.finality_depth();
+        let current_bounds = SmtReadBounds::for_pov(header.blue_score, finality_depth);
+        let virtual_processor = self.virtual_processor.clone();
+        let is_canonical = |bh| virtual_processor.is_smt_canonical(bh, block_hash);
+
+        // The multi-proof orders keys ascending; keep `lanes` aligned with it
+        lane_keys.sort();
+        lane_keys.dedup();
+
+        let smt_proof = self
+            .storage
+            .smt_stores
+            .prove_lanes(&lane_keys, current_bounds, is_canonical)
+            .map_err(|e| ConsensusError::GeneralOwned(format!("prove_lanes: {e}")))?;
+
+        let lanes = lane_keys
+            .iter()
+            .map(|&lane_key| {
+                let lane = self
+                    .storage
+                    .smt_stores
+                    .get_lane(lane_key, current_bounds, is_canonical)
+                    .map(|v| SeqCommitLaneEntry { tip: *v.data(), blue_score: v.blue_score() });
+                (lane_key, lane)
+            })
+            .collect::<Vec<_>>();
+
+        let metadata =
+            self.storage.smt_metadata_store.get(block_hash).map_err(|e| ConsensusError::GeneralOwned(format!("smt_metadata: {e}")))?;
+
+        // See `get_seq_commit_lane_proof` for why the shortcut block header is available.
+        let inactivity_shortcut = self.virtual_processor.inactivity_shortcut(metadata.inactivity_shortcut_block());
+
+        let parent_seq_commit = parent_header.accepted_id_merkle_root;
+
+        // In debug builds, verify the proof is consistent with the stored lanes_root.
+        debug_assert!({
+            use kaspa_hashes::SeqCommitActiveNode;
+            use kaspa_seq_commit::{hashing::smt_leaf_hash, types::SmtLeafInput};
+            let lanes_root = self.storage.smt_stores.get_lanes_root(current_bounds, is_canonical);
+            let leaves = lanes
+                .iter()
+                .map(|(key, lane)| {
+                    (*key, lane.as_ref().map(|l| smt_leaf_hash(&SmtLeafInput { lane_tip: &l.tip, blue_score: l.blue_score })))
+                })
+                .collect::<Vec<_>>();
+            lanes.is_empty() || smt_proof.compute_root::<SeqCommitActiveNode>(&leaves).unwrap() == lanes_root
+        });
+
+        Ok(SeqCommitLaneProofs {
+            smt_proof,
+            lanes,
+            payload_and_ctx_digest: metadata.payload_and_ctx_digest(),
+            parent_seq_commit,
+            inactivity_shortcut,
+        })
+    }
+
+    fn get_seq_commit_lane_state(&self, block_hash: Hash, lane_key: Hash) -> ConsensusResult<SeqCommitLaneState> {
+        let _guard = self.pruning_lock.blocking_read();
+        let header = self.validate_seq_commit_pov(block_hash)?;
+
+        let virtual_processor = self.virtual_processor.clone();
+        let is_canonical = |bh| virtual_processor.is_smt_canonical(bh, block_hash);
+
+        // A lane is active iff it was touched within the inactivity window of the POV block
+        let active_bounds = SmtReadBounds::for_pov(header.blue_score, self.config.params.finality_depth());
+        let (lane, is_active) = match self.storage.smt_stores.get_lane(lane_key, active_bounds, is_canonical) {
+            Some(lane) => (Some(lane), true),
+            // Otherwise, fall back to the latest version which was not pruned yet (if any)
+            None => (self.storage.smt_stores.get_lane(lane_key, SmtReadBounds::new(header.blue_score, 0), is_canonical), false),
+        };
+
+        Ok(SeqCommitLaneState { lane: lane.map(|v| SeqCommitLaneEntry { tip: *v.data(), blue_score: v.blue_score() }), is_active })
+    }
+
     fn get_missing_block_body_hashes(&self, high: Hash) -> ConsensusResult<Vec<Hash>> {
         let _guard = self.pruning_lock.blocking_read();
         self.validate_block_exists(high)?;
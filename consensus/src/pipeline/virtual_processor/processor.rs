@@ -52,7 +52,10 @@ use crate::{
 use kaspa_consensus_core::{
     BlockHashSet, ChainPath,
     acceptance_data::AcceptanceData,
-    api::args::{TransactionValidationArgs, TransactionValidationBatchArgs},
+    api::{
+        TransactionScriptsReport,
+        args::{TransactionValidationArgs, TransactionValidationBatchArgs},
+    },
     block::{BlockTemplate, MutableBlock, TemplateBuildMode, TemplateTransactionSelector},
     blockstatus::BlockStatus::{StatusDisqualifiedFromChain, StatusUTXOValid},
     coinbase::MinerData,
@@ -1253,6 +1256,25 @@ impl VirtualStateProcessor {
         })
     }
 
+    pub fn validate_transaction_scripts(&self, mutable_tx: &mut MutableTransaction) -> TxResult<TransactionScriptsReport> {
+        let virtual_read = self.virtual_stores.read();
+        let virtual_state = virtual_read.state.get().unwrap();
+        let virtual_utxo_view = &virtual_read.utxo_set;
+        let virtual_daa_score = virtual_state.daa_score;
+        let virtual_past_median_time = virtual_state.past_median_time;
+
+        let sp = virtual_state.ghostdag_data.selected_parent;
+        self.thread_pool.install(|| {
+            self.transaction_validator.validate_tx_in_isolation(&mutable_tx.tx)?;
+            self.transaction_validator.validate_tx_in_header_context_with_args(
+                &mutable_tx.tx,
+                virtual_daa_score,
+                virtual_past_median_time,
+            )?;
+            self.validate_transaction_scripts_in_utxo_context(mutable_tx, virtual_utxo_view, virtual_daa_score, sp)
+        })
+    }
+
     pub fn validate_mempool_transactions_in_parallel(
         &self,
         mutable_txs: &mut [MutableTransaction],
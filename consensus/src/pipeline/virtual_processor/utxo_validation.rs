@@ -24,7 +24,7 @@ use crate::{
 use kaspa_consensus_core::{
     BlockHashMap, BlockHashSet, HashMapCustomHasher,
     acceptance_data::{AcceptedTxEntry, MergesetBlockAcceptanceData},
-    api::args::TransactionValidationArgs,
+    api::{TransactionScriptsReport, args::TransactionValidationArgs},
     coinbase::*,
     hashing,
     header::Header,
@@ -384,17 +384,7 @@ impl VirtualStateProcessor {
 
         let populated_tx = PopulatedTransaction::new(transaction, entries);
 
-        let seq_commit_accessor = if self.toccata_activation.is_active(pov_daa_score) {
-            Some(SeqCommitAccessor::new(
-                selected_parent,
-                &self.reachability_service,
-                &self.headers_store,
-                self.toccata_activation,
-                self.finality_depth,
-            ))
-        } else {
-            None
-        };
+        let seq_commit_accessor = self.seq_commit_accessor(pov_daa_score, selected_parent);
         let res = self.transaction_validator.validate_populated_transaction_and_get_fee(
             &populated_tx,
             pov_daa_score,
@@ -465,17 +455,7 @@ impl VirtualStateProcessor {
             (mass.normalized_max(&self.mempool_mass_cofactors.get(pov_daa_score)), threshold)
         });
 
-        let seq_commit_accessor = if self.toccata_activation.is_active(pov_daa_score) {
-            Some(SeqCommitAccessor::new(
-                selected_parent,
-                &self.reachability_service,
-                &self.headers_store,
-                self.toccata_activation,
-                self.finality_depth,
-            ))
-        } else {
-            None
-        };
+        let seq_commit_accessor = self.seq_commit_accessor(pov_daa_score, selected_parent);
 
         let calculated_fee = self.transaction_validator.validate_populated_transaction_and_get_fee(
             &mutable_tx.as_verifiable(),
@@ -489,6 +469,60 @@ impl VirtualStateProcessor {
         Ok(())
     }
 
+    /// Populates the transaction with maximally found UTXO entry data and, if all found, reports the outcome
+    /// of the UTXO-context validation and of every input script, without any mempool related checks
+    pub(super) fn validate_transaction_scripts_in_utxo_context(
+        &self,
+        mutable_tx: &mut MutableTransaction,
+        utxo_view: &impl UtxoView,
+        pov_daa_score: u64,
+        selected_parent: Hash,
+    ) -> TxResult<TransactionScriptsReport> {
+        self.populate_mempool_transaction_in_utxo_context(mutable_tx, utxo_view)?;
+
+        let contextual_masses = self
+            .transaction_validator
+            .mass_calculator
+            .calc_contextual_masses(&mutable_tx.as_verifiable())
+            .ok_or(TxRuleError::MassIncomputable)?;
+        mutable_tx.tx.set_storage_mass(contextual_masses.storage_mass);
+
+        let seq_commit_accessor = self.seq_commit_accessor(pov_daa_score, selected_parent);
+        let verifiable_tx = mutable_tx.as_verifiable();
+        let fee = self.transaction_validator.validate_populated_transaction_and_get_fee(
+            &verifiable_tx,
+            pov_daa_score,
+            pov_daa_score,
+            TxValidationFlags::SkipScriptChecks,
+            None,
+            seq_commit_accessor.as_ref().map(|v| v as _),
+        );
+        let inputs = self.transaction_validator.check_scripts_per_input(
+            &verifiable_tx,
+            pov_daa_score,
+            seq_commit_accessor.as_ref().map(|v| v as _),
+        )?;
+
+        Ok(TransactionScriptsReport {
+            fee,
+            non_contextual_masses: mutable_tx.calculated_non_contextual_masses.unwrap(),
+            contextual_masses,
+            inputs,
+        })
+    }
+
+    fn seq_commit_accessor(&self, pov_daa_score: u64, selected_parent: Hash) -> Option<SeqCommitAccessor<'_>> {
+        self.toccata_activation.is_active(pov_daa_score).then(|| {
+            SeqCommitAccessor::new(
+                selected_parent,
+                &self.reachability_service,
+                &self.headers_store,
+                self.toccata_activation,
+                self.finality_depth,
+            )
+        })
+    }
+
     // =========================================================================
     // KIP-21: Sequencing commitment — shared helpers
     // =========================================================================
@@ -1,5 +1,6 @@
 use crate::constants::{MAX_SOMPI, SEQUENCE_LOCK_TIME_DISABLED, SEQUENCE_LOCK_TIME_MASK};
 use kaspa_consensus_core::{
+    api::InputScriptReport,
     hashing::sighash::{SigHashReusedValuesSync, SigHashReusedValuesUnsync},
     mass::Gram,
     tx::{TransactionInput, VerifiableTransaction},
@@ -168,10 +169,39 @@ impl TransactionValidator {
         seq_commit_accessor: Option<&dyn SeqCommitAccessor>,
     ) -> TxResult<()> {
         let ctx = EngineCtx::new(&self.sig_cache).with_covenants_ctx(&covenants_ctx).with_seq_commit_accessor_opt(seq_commit_accessor);
-        let covenants_enabled = self.toccata_activation.is_active(block_daa_score);
-        let flags: EngineFlags = EngineFlags { covenants_enabled, sigop_script_units: Gram(self.mass_per_sig_op).into() };
+        check_scripts(tx, ctx, self.engine_flags(block_daa_score))
+    }
 
-        check_scripts(tx, ctx, flags)
+    /// Executes the script of every input independently and reports the outcome and resource
+    /// consumption of each, rather than failing on the first invalid input like [`Self::check_scripts`].
+    pub fn check_scripts_per_input(
+        &self,
+        tx: &impl VerifiableTransaction,
+        block_daa_score: u64,
+        seq_commit_accessor: Option<&dyn SeqCommitAccessor>,
+    ) -> TxResult<Vec<InputScriptReport>> {
+        let covenants_ctx = self.check_covenant_info(tx, block_daa_score)?;
+        let reused_values = SigHashReusedValuesUnsync::new();
+        let ctx = EngineCtx::new(&self.sig_cache)
+            .with_covenants_ctx(&covenants_ctx)
+            .with_seq_commit_accessor_opt(seq_commit_accessor)
+            .with_reused(&reused_values);
+        let flags = self.engine_flags(block_daa_score);
+
+        let mut reports = Vec::with_capacity(tx.inputs().len());
+        for (i, (input, entry)) in tx.populated_inputs().enumerate() {
+            let script_units_limit = input.compute_commit.allowed_script_units();
+            let mut vm =
+                TxScriptEngine::from_transaction_input_with_script_units_limit(tx, input, i, entry, ctx, flags, script_units_limit);
+            let result = vm.execute();
+            reports.push(InputScriptReport { result, used_script_units: vm.used_script_units(), used_sig_ops: vm.used_sig_ops() });
+        }
+        Ok(reports)
+    }
+
+    fn engine_flags(&self, block_daa_score: u64) -> EngineFlags {
+        let covenants_enabled = self.toccata_activation.is_active(block_daa_score);
+        EngineFlags { covenants_enabled, sigop_script_units: Gram(self.mass_per_sig_op).into() }
     }
 
     fn check_covenant_info(&self, tx: &impl VerifiableTransaction, block_daa_score: u64) -> TxResult<CovenantsContext> {
@@ -345,6 +375,39 @@ mod tests {
         check_scripts(&populated_tx, EngineCtx::new(&sig_cache), flags).expect("should succeed");
     }
 
+    #[test]
+    fn check_scripts_per_input_reports_every_input() {
+        let params = MAINNET_PARAMS.clone();
+        let tv = TransactionValidator::new(
+            params.max_tx_inputs,
+            params.max_tx_outputs,
+            params.max_signature_script_len(),
+            params.max_script_public_key_len,
+            params.coinbase_payload_script_public_key_max_len,
+            params.coinbase_maturity(),
+            params.ghostdag_k,
+            Default::default(),
+            MassCalculator::new(0, 0, 0),
+            ForkActivation::always(),
+            params.mass_per_sig_op,
+        );
+
+        // The middle input is over budget, which must not hide the outcome of the last one
+        let (mut tx, entries) = build_parallel_push_budget_test_tx(3);
+        tx.inputs[1].compute_commit = ComputeBudget(0).into();
+        let populated_tx = PopulatedTransaction::new(&tx, entries);
+        let reports = tv.check_scripts_per_input(&populated_tx, 0, None).unwrap();
+
+        assert_eq!(reports.len(), 3);
+        assert!(reports[0].result.is_ok());
+        assert_match!(
+            reports[1].result,
+            Err(TxScriptError::ExceededCommittedScriptUnits { limit, .. }) if limit == free_script_units_per_input().0
+        );
+        assert!(reports[2].result.is_ok());
+        assert!(reports.iter().all(|report| report.used_script_units.0 > 0 && report.used_sig_ops == 0));
+    }
+
     #[test]
     fn validate_populated_transaction_sigop_budget_enforced_for_v0_and_v1_with_covenants_enabled() {
         let params = MAINNET_PARAMS.clone();
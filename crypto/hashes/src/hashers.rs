@@ -47,6 +47,10 @@ blake3_hasher! {
     struct SeqCommitMergesetContext => b"SeqCommitMergesetContext",
     struct SeqCommitMinerPayloadLeaf => b"SeqCommitMinerPayloadLeaf",
     struct SeqCommitActivityRoot => b"SeqCommitActivityRoot",
+    struct CompactBlockShortId => b"CompactBlockShortId",
+    struct PruningPointSnapshotChecksum => b"PruningPointSnapshotChecksum",
+    struct P2pTransportKey => b"P2pTransportKey",
+    struct P2pTransportSessionId => b"P2pTransportSessionId",
 
     struct SeqCommitActiveLeaf => b"SeqCommitActiveLeaf",
     struct SeqCommitActiveNode => b"SeqCommitActiveNode",
@@ -5,7 +5,7 @@
 //! ## Feature flags
 //!
 //! - **`std`** (default) — enables full tree construction via [`tree::SparseMerkleTree`].
-//! - Without `std` — only proof verification ([`proof::SmtProof`]) is available,
+//! - Without `std` — only proof verification ([`proof::SmtProof`], [`multiproof::SmtMultiProof`]) is available,
 //!   suitable for `no_std` environments and ZK guest programs.
 //!
 //! ## Proof compression
@@ -14,6 +14,9 @@
 //! Only non-empty siblings are stored, reducing typical proof size from
 //! 8 KiB (256 × 32 bytes) to ~32 + 32 × log₂(N) bytes.
 //!
+//! Proofs for several keys can be batched into a [`multiproof::SmtMultiProof`],
+//! which stores siblings shared between the keys' paths only once.
+//!
 //! ## Node hashing
 //!
 //! The tree is generic over the internal node hasher `H: SmtHasher`. Callers
@@ -28,6 +31,7 @@ extern crate core;
 #[cfg(feature = "std")]
 extern crate std;
 
+pub mod multiproof;
 pub mod proof;
 pub mod store;
 pub mod streaming;
@@ -0,0 +1,409 @@
+//! Compressed multi-key proofs and `no_std` verification.
+//!
+//! # Proof format
+//!
+//! An [`SmtMultiProof`] witnesses the values (or absence) of a set of keys against a
+//! single root. It carries the same information as the individual [`OwnedSmtProof`]s
+//! of its keys, with two kinds of redundancy removed:
+//! - siblings above the depth at which key paths diverge are shared and stored once;
+//! - siblings lying on the path of another proven key are omitted, because the verifier
+//!   recomputes them from that key's own subtree.
+//!
+//! The remaining siblings are ordered by a depth-first, left-first walk over the paths of
+//! the keys in ascending order: whenever all keys under a branch continue on the same side,
+//! the sibling of that side is emitted before descending. As with single proofs, empty
+//! siblings are elided and marked in a bitmap.
+//!
+//! Every key carries its own [`ProofTerminal`]. Keys that end in the same collapsed or
+//! empty subtree must reconstruct the same subtree hash, otherwise verification fails
+//! with [`SmtProofError::InconsistentTerminals`].
+
+use alloc::vec::Vec;
+use kaspa_hashes::Hash;
+
+use crate::proof::{OwnedSmtProof, ProofTerminal, SmtProofError, bitmap_clear_count_before, is_empty_at_depth, terminal_seed};
+use crate::{DEPTH, SmtHasher, bit_at, hash_node};
+
+/// Compressed proof for several keys against the same root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SmtMultiProof {
+    /// How proof traversal ended for each proven key, in ascending key order.
+    pub terminals: Vec<ProofTerminal>,
+    /// Siblings in walk order; `None` stands for the canonical empty subtree hash of its level.
+    pub siblings: Vec<Option<Hash>>,
+}
+
+impl SmtMultiProof {
+    /// Merge single-key proofs, all generated against the same root, into a multi-proof.
+    ///
+    /// Proofs may be given in any order; duplicate keys are merged.
+    pub fn from_proofs(proofs: impl IntoIterator<Item = (Hash, OwnedSmtProof)>) -> Result<Self, SmtProofError> {
+        let mut proofs = proofs.into_iter().collect::<Vec<_>>();
+        proofs.sort_by_key(|(key, _)| *key);
+        proofs.dedup_by_key(|(key, _)| *key);
+
+        let keys = proofs.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+        let terminals = proofs.iter().map(|(_, proof)| proof.terminal).collect::<Vec<_>>();
+        let paths = proofs.iter().map(|(_, proof)| expand_path(proof)).collect::<Result<Vec<_>, _>>()?;
+
+        let mut siblings = Vec::new();
+        if !keys.is_empty() {
+            collect_siblings(0, &keys, &terminals, &paths, &mut siblings)?;
+        }
+        Ok(Self { terminals, siblings })
+    }
+
+    /// Reconstruct the Merkle root this proof implies for the given `(key, leaf_hash)` claims,
+    /// where `None` claims non-membership. Claims may be given in any order.
+    pub fn compute_root<H: SmtHasher>(&self, leaves: &[(Hash, Option<Hash>)]) -> Result<Hash, SmtProofError> {
+        let mut leaves = leaves.to_vec();
+        leaves.sort_by_key(|(key, _)| *key);
+        if let Some(pair) = leaves.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(SmtProofError::DuplicateKey(pair[0].0));
+        }
+        if leaves.is_empty() || leaves.len() != self.terminals.len() {
+            return Err(SmtProofError::KeyCountMismatch { expected: self.terminals.len(), actual: leaves.len() });
+        }
+
+        let mut siblings = self.siblings.iter();
+        let root = compute_subtree::<H>(0, &leaves, &self.terminals, &mut siblings)?;
+        let remaining = siblings.len();
+        if remaining != 0 {
+            return Err(SmtProofError::SiblingCountMismatch {
+                expected: self.siblings.len() - remaining,
+                actual: self.siblings.len(),
+            });
+        }
+        Ok(root)
+    }
+
+    /// Verify that the given `(key, leaf_hash)` claims are consistent with `root`.
+    pub fn verify<H: SmtHasher>(&self, leaves: &[(Hash, Option<Hash>)], root: Hash) -> Result<bool, SmtProofError> {
+        Ok(self.compute_root::<H>(leaves)? == root)
+    }
+
+    /// Number of proven keys.
+    pub fn key_count(&self) -> usize {
+        self.terminals.len()
+    }
+
+    /// Number of non-empty siblings stored in this proof.
+    pub fn non_empty_count(&self) -> usize {
+        self.siblings.iter().filter(|sibling| sibling.is_some()).count()
+    }
+
+    /// Number of empty siblings elided from this proof.
+    pub fn empty_count(&self) -> usize {
+        self.siblings.len() - self.non_empty_count()
+    }
+
+    /// Parse from wire format:
+    /// `key_count[u32 LE] || terminals || position_count[u32 LE] || empty_bitmap[⌈position_count / 8⌉] || siblings[N × 32]`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, SmtProofError> {
+        let (&key_count, mut rem) = data.split_first_chunk::<4>().ok_or(SmtProofError::MalformedEncoding)?;
+        // Every terminal takes at least one byte, so the count cannot exceed the remaining input
+        let key_count = u32::from_le_bytes(key_count) as usize;
+        if key_count > rem.len() {
+            return Err(SmtProofError::MalformedEncoding);
+        }
+        let mut terminals = Vec::with_capacity(key_count);
+        for _ in 0..key_count {
+            let (terminal, next) = ProofTerminal::read_from(rem).ok_or(SmtProofError::MalformedEncoding)?;
+            terminals.push(terminal);
+            rem = next;
+        }
+
+        let (&position_count, rem) = rem.split_first_chunk::<4>().ok_or(SmtProofError::MalformedEncoding)?;
+        let position_count = u32::from_le_bytes(position_count) as usize;
+        let bitmap_len = position_count.div_ceil(8);
+        if rem.len() < bitmap_len {
+            return Err(SmtProofError::MalformedEncoding);
+        }
+        let (bitmap, sibling_bytes) = rem.split_at(bitmap_len);
+        let (chunks, tail) = sibling_bytes.as_chunks::<32>();
+        if !tail.is_empty() {
+            return Err(SmtProofError::MalformedEncoding);
+        }
+
+        let is_empty = |i: usize| bitmap[i / 8] & (1 << (i % 8)) != 0;
+        let expected = (0..position_count).filter(|&i| !is_empty(i)).count();
+        if chunks.len() != expected {
+            return Err(SmtProofError::SiblingCountMismatch { expected, actual: chunks.len() });
+        }
+        let mut chunks = chunks.iter().copied().map(Hash::from_bytes);
+        let siblings = (0..position_count).map(|i| if is_empty(i) { None } else { chunks.next() }).collect();
+        Ok(Self { terminals, siblings })
+    }
+
+    /// Serialize to wire format:
+    /// `key_count[u32 LE] || terminals || position_count[u32 LE] || empty_bitmap[⌈position_count / 8⌉] || siblings[N × 32]`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let bitmap_len = self.siblings.len().div_ceil(8);
+        let mut out = Vec::with_capacity(8 + self.terminals.len() * 66 + bitmap_len + self.non_empty_count() * 32);
+        out.extend_from_slice(&(self.terminals.len() as u32).to_le_bytes());
+        for terminal in &self.terminals {
+            terminal.write_to(&mut out);
+        }
+        out.extend_from_slice(&(self.siblings.len() as u32).to_le_bytes());
+        let mut bitmap = alloc::vec![0u8; bitmap_len];
+        for (i, sibling) in self.siblings.iter().enumerate() {
+            if sibling.is_none() {
+                bitmap[i / 8] |= 1 << (i % 8);
+            }
+        }
+        out.extend_from_slice(&bitmap);
+        for sibling in self.siblings.iter().flatten() {
+            out.extend_from_slice(sibling.as_bytes().as_slice());
+        }
+        out
+    }
+}
+
+/// Expand a single proof into its per-depth siblings (`None` = empty), down to its terminal depth.
+fn expand_path(proof: &OwnedSmtProof) -> Result<Vec<Option<Hash>>, SmtProofError> {
+    let expected = bitmap_clear_count_before(&proof.bitmap, proof.terminal);
+    if proof.siblings.len() != expected {
+        return Err(SmtProofError::SiblingCountMismatch { expected, actual: proof.siblings.len() });
+    }
+    let mut siblings = proof.siblings.iter().copied();
+    Ok((0..proof.terminal.depth()).map(|d| if is_empty_at_depth(&proof.bitmap, d) { None } else { siblings.next() }).collect())
+}
+
+/// Returns `true` if all keys under the current branch terminate at `depth`, `false` if none do.
+fn terminates_at(depth: usize, terminals: &[ProofTerminal]) -> Result<bool, SmtProofError> {
+    match terminals.iter().filter(|terminal| terminal.depth() == depth).count() {
+        0 => Ok(false),
+        count if count == terminals.len() => Ok(true),
+        _ => Err(SmtProofError::InconsistentTerminals { depth }),
+    }
+}
+
+/// Emit the siblings needed below the branch at `depth` shared by `keys`, in walk order.
+fn collect_siblings(
+    depth: usize,
+    keys: &[Hash],
+    terminals: &[ProofTerminal],
+    paths: &[Vec<Option<Hash>>],
+    out: &mut Vec<Option<Hash>>,
+) -> Result<(), SmtProofError> {
+    if terminates_at(depth, terminals)? {
+        return Ok(());
+    }
+    let split = keys.partition_point(|key| !bit_at(key, depth));
+    if split == 0 || split == keys.len() {
+        out.push(paths[0][depth]);
+        collect_siblings(depth + 1, keys, terminals, paths, out)
+    } else {
+        collect_siblings(depth + 1, &keys[..split], &terminals[..split], &paths[..split], out)?;
+        collect_siblings(depth + 1, &keys[split..], &terminals[split..], &paths[split..], out)
+    }
+}
+
+/// Compute the hash of the branch at `depth` shared by the sorted `leaves`, consuming siblings in walk order.
+fn compute_subtree<H: SmtHasher>(
+    depth: usize,
+    leaves: &[(Hash, Option<Hash>)],
+    terminals: &[ProofTerminal],
+    siblings: &mut core::slice::Iter<'_, Option<Hash>>,
+) -> Result<Hash, SmtProofError> {
+    if terminates_at(depth, terminals)? {
+        // All keys ending here must describe the very same subtree
+        let (key, leaf_hash) = leaves[0];
+        let seed = terminal_seed::<H>(terminals[0], &key, leaf_hash);
+        for (&(key, leaf_hash), &terminal) in leaves.iter().zip(terminals).skip(1) {
+            if terminal_seed::<H>(terminal, &key, leaf_hash) != seed {
+                return Err(SmtProofError::InconsistentTerminals { depth });
+            }
+        }
+        return Ok(seed);
+    }
+    let split = leaves.partition_point(|(key, _)| !bit_at(key, depth));
+    let (left, right) = if split == 0 || split == leaves.len() {
+        let sibling = siblings.next().ok_or(SmtProofError::MissingSiblings)?;
+        let sibling = sibling.unwrap_or(H::EMPTY_HASHES[DEPTH - 1 - depth]);
+        let child = compute_subtree::<H>(depth + 1, leaves, terminals, siblings)?;
+        if split == 0 { (sibling, child) } else { (child, sibling) }
+    } else {
+        let left = compute_subtree::<H>(depth + 1, &leaves[..split], &terminals[..split], siblings)?;
+        let right = compute_subtree::<H>(depth + 1, &leaves[split..], &terminals[split..], siblings)?;
+        (left, right)
+    };
+    Ok(hash_node::<H>(left, right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::SparseMerkleTree;
+    use alloc::vec;
+    use kaspa_hashes::{HasherBase, SeqCommitActiveNode};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    type TestHasher = SeqCommitActiveNode;
+    type Smt = SparseMerkleTree<TestHasher>;
+
+    fn test_hash(domain: &[u8], seed: &[u8]) -> Hash {
+        let mut h = TestHasher::default();
+        h.update(domain);
+        h.update(seed);
+        h.finalize()
+    }
+
+    fn tree_with(entries: &[(Hash, Hash)]) -> Smt {
+        let mut tree = Smt::new();
+        for &(key, leaf) in entries {
+            tree.insert(key, leaf);
+        }
+        tree
+    }
+
+    fn claims(tree: &Smt, keys: &[Hash]) -> Vec<(Hash, Option<Hash>)> {
+        keys.iter().map(|key| (*key, tree.get(key))).collect()
+    }
+
+    fn random_entries(rng: &mut StdRng, count: usize) -> Vec<(Hash, Hash)> {
+        (0..count).map(|_| (Hash::from_bytes(rng.r#gen()), Hash::from_bytes(rng.r#gen()))).collect()
+    }
+
+    #[test]
+    fn test_multi_proof_matches_tree_root() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let entries = random_entries(&mut rng, 64);
+        let tree = tree_with(&entries);
+
+        let mut keys = entries.iter().step_by(3).map(|(key, _)| *key).collect::<Vec<_>>();
+        keys.extend((0..8).map(|_| Hash::from_bytes(rng.r#gen())));
+        let proof = tree.prove_many(&keys).unwrap();
+
+        assert_eq!(proof.key_count(), keys.len());
+        assert!(proof.verify::<TestHasher>(&claims(&tree, &keys), tree.root()).unwrap());
+    }
+
+    #[test]
+    fn test_multi_proof_smaller_than_single_proofs() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let entries = random_entries(&mut rng, 256);
+        let tree = tree_with(&entries);
+
+        let keys = entries.iter().take(32).map(|(key, _)| *key).collect::<Vec<_>>();
+        let multi = tree.prove_many(&keys).unwrap();
+        let singles = keys.iter().map(|key| tree.prove(key).unwrap().non_empty_count()).sum::<usize>();
+        assert!(multi.non_empty_count() < singles);
+    }
+
+    #[test]
+    fn test_multi_proof_single_key_matches_single_proof() {
+        let entries = (0u8..10).map(|i| (test_hash(b"key:", &[i]), test_hash(b"leaf:", &[i]))).collect::<Vec<_>>();
+        let tree = tree_with(&entries);
+        let absent = test_hash(b"key:", b"absent");
+
+        for key in [entries[4].0, absent] {
+            let single = tree.prove(&key).unwrap();
+            let multi = tree.prove_many(&[key]).unwrap();
+            assert_eq!(multi.terminals, vec![single.terminal]);
+            assert_eq!(multi.non_empty_count(), single.non_empty_count());
+            assert_eq!(multi.compute_root::<TestHasher>(&claims(&tree, &[key])).unwrap(), tree.root());
+        }
+    }
+
+    #[test]
+    fn test_multi_proof_empty_tree() {
+        let tree = Smt::new();
+        let keys = [test_hash(b"key:", b"a"), test_hash(b"key:", b"b")];
+        let proof = tree.prove_many(&keys).unwrap();
+        assert_eq!(proof.non_empty_count(), 0);
+        assert!(proof.verify::<TestHasher>(&[(keys[0], None), (keys[1], None)], tree.root()).unwrap());
+    }
+
+    #[test]
+    fn test_multi_proof_keys_sharing_collapsed_subtree() {
+        // Both absent keys share the 0xF0 prefix, terminating in the same collapsed subtree
+        let mut present = [0u8; 32];
+        present[0] = 0xF0;
+        let mut absent = [0u8; 32];
+        absent[0] = 0xF0;
+        absent[31] = 0x01;
+        let mut other = [0u8; 32];
+        other[0] = 0x0F;
+        let (present, absent, other) = (Hash::from_bytes(present), Hash::from_bytes(absent), Hash::from_bytes(other));
+        let tree = tree_with(&[(present, test_hash(b"leaf:", b"p")), (other, test_hash(b"leaf:", b"o"))]);
+
+        let keys = [absent, present, other];
+        let proof = tree.prove_many(&keys).unwrap();
+        assert!(proof.verify::<TestHasher>(&claims(&tree, &keys), tree.root()).unwrap());
+
+        // Claiming the collapsed leaf is absent contradicts the neighbouring key's terminal
+        let forged = [(absent, None), (present, None), (other, tree.get(&other))];
+        assert_eq!(proof.compute_root::<TestHasher>(&forged), Err(SmtProofError::InconsistentTerminals { depth: 1 }));
+    }
+
+    #[test]
+    fn test_multi_proof_rejects_wrong_claims() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let entries = random_entries(&mut rng, 32);
+        let tree = tree_with(&entries);
+        let keys = entries.iter().take(5).map(|(key, _)| *key).collect::<Vec<_>>();
+        let proof = tree.prove_many(&keys).unwrap();
+        let root = tree.root();
+
+        let mut wrong_leaf = claims(&tree, &keys);
+        wrong_leaf[2].1 = Some(test_hash(b"leaf:", b"forged"));
+        assert!(!proof.verify::<TestHasher>(&wrong_leaf, root).unwrap());
+
+        let mut non_inclusion = claims(&tree, &keys);
+        non_inclusion[0].1 = None;
+        assert!(!proof.verify::<TestHasher>(&non_inclusion, root).unwrap());
+
+        let missing = &claims(&tree, &keys)[1..];
+        assert_eq!(proof.compute_root::<TestHasher>(missing), Err(SmtProofError::KeyCountMismatch { expected: 5, actual: 4 }));
+
+        let mut duplicate = claims(&tree, &keys);
+        duplicate[1] = duplicate[0];
+        assert_eq!(proof.compute_root::<TestHasher>(&duplicate), Err(SmtProofError::DuplicateKey(keys[0])));
+    }
+
+    #[test]
+    fn test_multi_proof_rejects_tampered_siblings() {
+        let mut rng = StdRng::seed_from_u64(17);
+        let entries = random_entries(&mut rng, 32);
+        let tree = tree_with(&entries);
+        let keys = entries.iter().take(4).map(|(key, _)| *key).collect::<Vec<_>>();
+        let proof = tree.prove_many(&keys).unwrap();
+        let leaves = claims(&tree, &keys);
+
+        let mut tampered = proof.clone();
+        let index = tampered.siblings.iter().position(Option::is_some).unwrap();
+        tampered.siblings[index] = Some(test_hash(b"sibling:", b"forged"));
+        assert!(!tampered.verify::<TestHasher>(&leaves, tree.root()).unwrap());
+
+        let mut truncated = proof.clone();
+        truncated.siblings.pop();
+        assert_eq!(truncated.compute_root::<TestHasher>(&leaves), Err(SmtProofError::MissingSiblings));
+
+        let mut extended = proof;
+        extended.siblings.push(None);
+        let len = extended.siblings.len();
+        assert_eq!(
+            extended.compute_root::<TestHasher>(&leaves),
+            Err(SmtProofError::SiblingCountMismatch { expected: len - 1, actual: len })
+        );
+    }
+
+    #[test]
+    fn test_multi_proof_wire_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(19);
+        let entries = random_entries(&mut rng, 48);
+        let tree = tree_with(&entries);
+        let mut keys = entries.iter().take(6).map(|(key, _)| *key).collect::<Vec<_>>();
+        keys.push(Hash::from_bytes(rng.r#gen()));
+        let proof = tree.prove_many(&keys).unwrap();
+
+        let bytes = proof.to_bytes();
+        let decoded = SmtMultiProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(decoded.verify::<TestHasher>(&claims(&tree, &keys), tree.root()).unwrap());
+
+        assert_eq!(SmtMultiProof::from_bytes(&bytes[..bytes.len() - 1]), Err(SmtProofError::MalformedEncoding));
+        assert_eq!(SmtMultiProof::from_bytes(&[0xff, 0xff, 0xff, 0xff]), Err(SmtProofError::MalformedEncoding));
+    }
+}
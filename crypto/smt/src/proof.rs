@@ -47,12 +47,22 @@ use crate::{DEPTH, SmtHasher, bit_at, hash_node};
 pub enum SmtProofError {
     #[error("sibling count mismatch: bitmap implies {expected} non-empty siblings, but got {actual}")]
     SiblingCountMismatch { expected: usize, actual: usize },
+    #[error("key count mismatch: multi-proof covers {expected} keys, but got {actual}")]
+    KeyCountMismatch { expected: usize, actual: usize },
+    #[error("duplicate key {0} in multi-proof query")]
+    DuplicateKey(Hash),
+    #[error("inconsistent terminals: keys ending at depth {depth} do not agree on their subtree")]
+    InconsistentTerminals { depth: usize },
+    #[error("multi-proof ran out of siblings")]
+    MissingSiblings,
+    #[error("malformed multi-proof encoding")]
+    MalformedEncoding,
 }
 
 /// Returns `true` if the sibling at depth `d` is empty (its bitmap bit is set),
 /// meaning it equals the canonical empty-subtree hash and was elided from the proof.
 #[inline]
-fn is_empty_at_depth(bitmap: &[u8; 32], d: usize) -> bool {
+pub(crate) fn is_empty_at_depth(bitmap: &[u8; 32], d: usize) -> bool {
     bitmap[d / 8] & (1 << (d % 8)) != 0
 }
 
@@ -114,6 +124,44 @@ impl ProofTerminal {
             Self::Collapsed { depth } | Self::CollapsedOther { depth, .. } => depth as usize,
         }
     }
+
+    /// Append the wire encoding `tag[1] || payload` of this terminal to `out`.
+    pub(crate) fn write_to(self, out: &mut Vec<u8>) {
+        match self {
+            Self::Full => out.push(Self::FULL_TAG),
+            Self::Collapsed { depth } => {
+                out.push(Self::COLLAPSED_TAG);
+                out.push(depth);
+            }
+            Self::CollapsedOther { depth, leaf } => {
+                out.push(Self::COLLAPSED_OTHER_TAG);
+                out.push(depth);
+                out.extend_from_slice(leaf.lane_key.as_bytes().as_slice());
+                out.extend_from_slice(leaf.leaf_hash.as_bytes().as_slice());
+            }
+        }
+    }
+
+    /// Parse a terminal from the front of `data`. Returns the terminal and the remaining bytes,
+    /// or `None` if the tag is unknown or the payload is truncated.
+    pub(crate) fn read_from(data: &[u8]) -> Option<(Self, &[u8])> {
+        let (&tag, rem) = data.split_first()?;
+        match tag {
+            Self::FULL_TAG => Some((Self::Full, rem)),
+            Self::COLLAPSED_TAG => {
+                let (&depth, rem) = rem.split_first()?;
+                Some((Self::Collapsed { depth }, rem))
+            }
+            Self::COLLAPSED_OTHER_TAG => {
+                let (&depth, rem) = rem.split_first()?;
+                let (&lane_key, rem) = rem.split_first_chunk::<32>()?;
+                let (&leaf_hash, rem) = rem.split_first_chunk::<32>()?;
+                let leaf = CollapsedLeaf { lane_key: Hash::from_bytes(lane_key), leaf_hash: Hash::from_bytes(leaf_hash) };
+                Some((Self::CollapsedOther { depth, leaf }, rem))
+            }
+            _ => None,
+        }
+    }
 }
 
 /// Count non-empty siblings in `bitmap` up to (but not including) the terminal depth.
@@ -121,11 +169,28 @@ impl ProofTerminal {
 /// Only levels `0..terminal.depth()` contribute siblings to the proof. Levels at or
 /// beyond the terminal depth are either inside the collapsed subtree or absent, so
 /// their bitmap bits are ignored for sibling-count validation.
-fn bitmap_clear_count_before(bitmap: &[u8; 32], terminal: ProofTerminal) -> usize {
+pub(crate) fn bitmap_clear_count_before(bitmap: &[u8; 32], terminal: ProofTerminal) -> usize {
     let limit = terminal.depth();
     (0..limit).filter(|&d| !is_empty_at_depth(bitmap, d)).count()
 }
 
+/// Seed the hash of the subtree at which proof traversal for `key` terminated,
+/// based on the terminal variant and the queried leaf (see [`compute_root_inner`]).
+pub(crate) fn terminal_seed<H: SmtHasher>(terminal: ProofTerminal, key: &Hash, leaf_hash: Option<Hash>) -> Hash {
+    match (terminal, leaf_hash) {
+        // Non-inclusion: collapsed subtree holds a different key → start from foreign leaf hash.
+        (ProofTerminal::CollapsedOther { leaf, .. }, None) if leaf.lane_key != *key => {
+            hash_node::<H::CollapsedHasher>(leaf.lane_key, leaf.leaf_hash)
+        }
+        // Edge case: CollapsedOther but the key matches → treat as empty (non-membership).
+        (ProofTerminal::CollapsedOther { .. }, None) => ZERO_HASH,
+        // Inclusion proof: hash the queried key with its leaf value.
+        (_, Some(leaf_hash)) => hash_node::<H::CollapsedHasher>(*key, leaf_hash),
+        // Non-inclusion: subtree is empty.
+        (_, None) => ZERO_HASH,
+    }
+}
+
 /// Reconstruct the Merkle root from a proof, optionally using a branch cache.
 ///
 /// # Terminal-dependent initial state
@@ -158,19 +223,7 @@ fn compute_root_inner<H: SmtHasher>(
         return Err(SmtProofError::SiblingCountMismatch { expected, actual: siblings.len() });
     }
 
-    // Seed the initial hash based on the terminal variant and queried leaf.
-    let mut current = match (terminal, leaf_hash) {
-        // Non-inclusion: collapsed subtree holds a different key → start from foreign leaf hash.
-        (ProofTerminal::CollapsedOther { leaf, .. }, None) if leaf.lane_key != *key => {
-            hash_node::<H::CollapsedHasher>(leaf.lane_key, leaf.leaf_hash)
-        }
-        // Edge case: CollapsedOther but the key matches → treat as empty (non-membership).
-        (ProofTerminal::CollapsedOther { .. }, None) => ZERO_HASH,
-        // Inclusion proof: hash the queried key with its leaf value.
-        (_, Some(leaf_hash)) => hash_node::<H::CollapsedHasher>(*key, leaf_hash),
-        // Non-inclusion: subtree is empty.
-        (_, None) => ZERO_HASH,
-    };
+    let mut current = terminal_seed::<H>(terminal, key, leaf_hash);
     let mut sib_idx = siblings.len();
     let limit = terminal.depth();
 
@@ -350,19 +403,7 @@ impl OwnedSmtProof {
         };
         let mut out = Vec::with_capacity(32 + terminal_len + self.siblings.len() * 32);
         out.extend_from_slice(&self.bitmap);
-        match self.terminal {
-            ProofTerminal::Full => out.push(ProofTerminal::FULL_TAG),
-            ProofTerminal::Collapsed { depth } => {
-                out.push(ProofTerminal::COLLAPSED_TAG);
-                out.push(depth);
-            }
-            ProofTerminal::CollapsedOther { depth, leaf } => {
-                out.push(ProofTerminal::COLLAPSED_OTHER_TAG);
-                out.push(depth);
-                out.extend_from_slice(leaf.lane_key.as_bytes().as_slice());
-                out.extend_from_slice(leaf.leaf_hash.as_bytes().as_slice());
-            }
-        }
+        self.terminal.write_to(&mut out);
         for sibling in &self.siblings {
             out.extend_from_slice(sibling.as_bytes().as_slice());
         }
@@ -16,6 +16,7 @@ use alloc::vec::Vec;
 use core::marker::PhantomData;
 use kaspa_hashes::Hash;
 
+use crate::multiproof::SmtMultiProof;
 use crate::proof::{OwnedSmtProof, ProofTerminal};
 use crate::store::{BTreeSmtStore, BranchKey, CollapsedLeaf, LeafUpdate, Node, SmtStore, SortedLeafUpdates, SortedLeafUpdatesRef};
 use crate::{DEPTH, SmtHasher, bit_at, hash_node};
@@ -136,6 +137,14 @@ impl<H: SmtHasher, S: SmtStore> SparseMerkleTree<H, S> {
 
         Ok(OwnedSmtProof { bitmap, siblings, terminal })
     }
+
+    /// Generate a single compressed proof covering all the given keys (see [`SmtMultiProof`]).
+    ///
+    /// Each key may be present or absent; duplicate keys are proven once.
+    pub fn prove_many(&self, keys: &[Hash]) -> Result<SmtMultiProof, S::Error> {
+        let proofs = keys.iter().map(|key| Ok((*key, self.prove(key)?))).collect::<Result<Vec<_>, S::Error>>()?;
+        Ok(SmtMultiProof::from_proofs(proofs).expect("proofs generated from the same tree are consistent"))
+    }
 }
 
 #[allow(clippy::len_without_is_empty)]
@@ -0,0 +1,407 @@
+//!
+//! Human-readable assembly syntax for scripts.
+//!
+//! A script is written as a whitespace separated sequence of tokens:
+//!
+//! - opcode names such as `OpCheckSig`, matched case-insensitively while ignoring
+//!   underscores, the `Op` prefix being optional (`CHECKSIG`, `op_check_sig`);
+//! - data pushes written as `0x` prefixed hex (`0xdeadbeef`), encoded with the
+//!   smallest push opcode fitting their length;
+//! - decimal numbers (`-1`, `42`), encoded the same way as [`ScriptBuilder::add_i64`];
+//! - push opcodes followed by their payload (`OpPushData1 0x01`), which keep the
+//!   given opcode even if a smaller one would fit.
+//!
+//! Everything following a `#` up to the end of the line is a comment.
+//!
+//! [`disassemble`] produces this syntax out of script bytes, in a way that
+//! [`assemble`] turns its output back into the very same bytes.
+//!
+//! [`ScriptBuilder::add_i64`]: crate::script_builder::ScriptBuilder::add_i64
+//!
+
+use crate::opcodes::{OP_DATA_MAX_VAL, OP_DATA_MIN_VAL, codes, opcode_name, opcode_names};
+use crate::serialize_i64;
+use kaspa_utils::hex::{FromHex, ToHex};
+use std::{collections::HashMap, iter::once, ops::Range, sync::LazyLock};
+use thiserror::Error;
+
+/// Byte range of a token within an assembly source.
+pub type Span = Range<usize>;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AsmError {
+    #[error("unknown opcode `{token}` at {span:?}")]
+    UnknownOpcode { token: String, span: Span },
+
+    #[error("invalid hex data `{token}` at {span:?}")]
+    InvalidHex { token: String, span: Span },
+
+    #[error("invalid number `{token}` at {span:?}")]
+    InvalidNumber { token: String, span: Span },
+
+    #[error("opcode {opcode} at {span:?} must be followed by 0x prefixed data")]
+    MissingOperand { opcode: &'static str, span: Span },
+
+    #[error("opcode {opcode} at {span:?} cannot push {len} bytes of data")]
+    InvalidOperandLength { opcode: &'static str, len: usize, span: Span },
+
+    #[error("opcode {opcode} at offset {offset} pushes {expected} bytes while only {available} are left in the script")]
+    TruncatedPush { opcode: &'static str, offset: usize, expected: usize, available: usize },
+}
+
+impl AsmError {
+    /// Returns the span of the offending source tokens, if any.
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            AsmError::UnknownOpcode { span, .. }
+            | AsmError::InvalidHex { span, .. }
+            | AsmError::InvalidNumber { span, .. }
+            | AsmError::MissingOperand { span, .. }
+            | AsmError::InvalidOperandLength { span, .. } => Some(span),
+            AsmError::TruncatedPush { .. } => None,
+        }
+    }
+}
+
+pub type AsmResult<T> = std::result::Result<T, AsmError>;
+
+/// Opcodes by normalized name, with and without the `op` prefix.
+static OPCODES_BY_NAME: LazyLock<HashMap<String, u8>> = LazyLock::new(|| {
+    opcode_names()
+        .flat_map(|(name, opcode)| {
+            let name = normalize(name);
+            let short_name = name.strip_prefix("op").map(|short_name| (short_name.to_owned(), opcode));
+            once((name, opcode)).chain(short_name)
+        })
+        .collect()
+});
+
+fn normalize(name: &str) -> String {
+    name.replace('_', "").to_lowercase()
+}
+
+/// Payload encoding of a push opcode.
+enum Payload {
+    /// The payload length is implied by the opcode
+    Fixed(usize),
+    /// The payload is preceded by its little endian length, encoded over this many bytes
+    Prefixed(usize),
+}
+
+fn payload(opcode: u8) -> Option<Payload> {
+    match opcode {
+        OP_DATA_MIN_VAL..=OP_DATA_MAX_VAL => Some(Payload::Fixed(opcode as usize)),
+        codes::OpPushData1 => Some(Payload::Prefixed(1)),
+        codes::OpPushData2 => Some(Payload::Prefixed(2)),
+        codes::OpPushData4 => Some(Payload::Prefixed(4)),
+        _ => None,
+    }
+}
+
+/// Returns the smallest opcode able to push `len` bytes of data.
+fn data_push_opcode(len: usize) -> u8 {
+    if len == 0 {
+        codes::Op0
+    } else if len <= OP_DATA_MAX_VAL as usize {
+        (OP_DATA_MIN_VAL - 1) + len as u8
+    } else if len <= u8::MAX as usize {
+        codes::OpPushData1
+    } else if len <= u16::MAX as usize {
+        codes::OpPushData2
+    } else {
+        codes::OpPushData4
+    }
+}
+
+/// Appends a push of `data` using the push opcode `opcode`, returning false if `data` does not fit it.
+fn push_with_opcode(script: &mut Vec<u8>, opcode: u8, data: &[u8]) -> bool {
+    match payload(opcode) {
+        Some(Payload::Fixed(len)) if len == data.len() => script.push(opcode),
+        Some(Payload::Prefixed(size)) if (data.len() as u64) < 1 << (8 * size) => {
+            script.push(opcode);
+            script.extend_from_slice(&(data.len() as u64).to_le_bytes()[..size]);
+        }
+        _ => return false,
+    }
+    script.extend_from_slice(data);
+    true
+}
+
+fn push_data(script: &mut Vec<u8>, data: &[u8]) {
+    match data_push_opcode(data.len()) {
+        codes::Op0 => script.push(codes::Op0),
+        opcode => {
+            push_with_opcode(script, opcode, data);
+        }
+    }
+}
+
+/// Iterator over the tokens of an assembly source along with their spans, skipping comments.
+struct Tokens<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = (&'a str, Span);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.source.as_bytes();
+        loop {
+            match *bytes.get(self.position)? {
+                byte if byte.is_ascii_whitespace() => self.position += 1,
+                b'#' => {
+                    while bytes.get(self.position).is_some_and(|byte| *byte != b'\n') {
+                        self.position += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+        let start = self.position;
+        while bytes.get(self.position).is_some_and(|byte| !byte.is_ascii_whitespace() && *byte != b'#') {
+            self.position += 1;
+        }
+        Some((&self.source[start..self.position], start..self.position))
+    }
+}
+
+fn parse_hex(token: &str, span: &Span) -> AsmResult<Vec<u8>> {
+    token
+        .strip_prefix("0x")
+        .and_then(|hex| Vec::<u8>::from_hex(hex).ok())
+        .ok_or_else(|| AsmError::InvalidHex { token: token.to_owned(), span: span.clone() })
+}
+
+/// Looks up an opcode by name, with the same leniency as [`assemble`].
+pub fn parse_opcode(name: &str) -> Option<u8> {
+    OPCODES_BY_NAME.get(&normalize(name)).copied()
+}
+
+/// Assembles a script out of its textual representation (see the [module documentation](self) for the syntax).
+pub fn assemble(source: &str) -> AsmResult<Vec<u8>> {
+    let mut script = Vec::new();
+    let mut tokens = Tokens { source, position: 0 }.peekable();
+    while let Some((token, span)) = tokens.next() {
+        if token.starts_with("0x") {
+            push_data(&mut script, &parse_hex(token, &span)?);
+        } else if token.strip_prefix('-').unwrap_or(token).starts_with(|c: char| c.is_ascii_digit()) {
+            let value: i64 = token.parse().map_err(|_| AsmError::InvalidNumber { token: token.to_owned(), span: span.clone() })?;
+            match value {
+                0 => script.push(codes::Op0),
+                -1 => script.push(codes::Op1Negate),
+                1..=16 => script.push((codes::Op1 - 1) + value as u8),
+                _ => {
+                    let data = serialize_i64(value, None)
+                        .map_err(|_| AsmError::InvalidNumber { token: token.to_owned(), span: span.clone() })?;
+                    push_data(&mut script, &data);
+                }
+            }
+        } else {
+            let opcode = parse_opcode(token).ok_or_else(|| AsmError::UnknownOpcode { token: token.to_owned(), span: span.clone() })?;
+            if payload(opcode).is_none() {
+                script.push(opcode);
+                continue;
+            }
+            let (operand, operand_span) = tokens
+                .next_if(|(operand, _)| operand.starts_with("0x"))
+                .ok_or_else(|| AsmError::MissingOperand { opcode: opcode_name(opcode), span: span.clone() })?;
+            let data = parse_hex(operand, &operand_span)?;
+            if !push_with_opcode(&mut script, opcode, &data) {
+                return Err(AsmError::InvalidOperandLength {
+                    opcode: opcode_name(opcode),
+                    len: data.len(),
+                    span: span.start..operand_span.end,
+                });
+            }
+        }
+    }
+    Ok(script)
+}
+
+/// Disassembles a script into its textual representation (see the [module documentation](self) for the syntax).
+///
+/// Data pushes using the smallest fitting push opcode are written as plain hex, other pushes
+/// being prefixed by their opcode so that the result assembles back into the same script.
+pub fn disassemble(script: &[u8]) -> AsmResult<String> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    while let Some(&opcode) = script.get(position) {
+        let offset = position;
+        position += 1;
+        let Some(payload) = payload(opcode) else {
+            tokens.push(opcode_name(opcode).to_owned());
+            continue;
+        };
+
+        let truncated = |expected: usize, position: usize| AsmError::TruncatedPush {
+            opcode: opcode_name(opcode),
+            offset,
+            expected,
+            available: script.len() - position,
+        };
+        let len = match payload {
+            Payload::Fixed(len) => len,
+            Payload::Prefixed(size) => {
+                let prefix = script.get(position..position + size).ok_or_else(|| truncated(size, position))?;
+                position += size;
+                prefix.iter().rev().fold(0usize, |len, byte| (len << 8) | *byte as usize)
+            }
+        };
+        let data = script.get(position..position.saturating_add(len)).ok_or_else(|| truncated(len, position))?;
+        position += len;
+
+        if opcode == data_push_opcode(len) {
+            tokens.push(format!("0x{}", data.to_hex()));
+        } else {
+            tokens.push(format!("{} 0x{}", opcode_name(opcode), data.to_hex()));
+        }
+    }
+    Ok(tokens.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opcodes::codes::*;
+    use crate::script_builder::ScriptBuilder;
+
+    #[test]
+    fn test_assemble() {
+        struct Test {
+            name: &'static str,
+            source: &'static str,
+            expected: Vec<u8>,
+        }
+
+        let pub_key = [0x02; 32];
+        let tests = vec![
+            Test { name: "empty", source: " \n # nothing to see here\n", expected: vec![] },
+            Test {
+                name: "pay to pubkey",
+                source: "0x0202020202020202020202020202020202020202020202020202020202020202 OpCheckSig",
+                expected: ScriptBuilder::new().add_data(&pub_key).unwrap().add_op(OpCheckSig).unwrap().drain(),
+            },
+            Test {
+                name: "name variants and comments",
+                source: "OP_DUP # duplicate\nblake2b equal_verify OpCheckSigFromStack",
+                expected: vec![OpDup, OpBlake2b, OpEqualVerify, OpCheckSigFromStack],
+            },
+            Test {
+                name: "numbers",
+                source: "0 -1 1 16 17 -2 1000 OpTrue OpFalse",
+                expected: ScriptBuilder::new()
+                    .add_i64(0)
+                    .unwrap()
+                    .add_i64(-1)
+                    .unwrap()
+                    .add_i64(1)
+                    .unwrap()
+                    .add_i64(16)
+                    .unwrap()
+                    .add_i64(17)
+                    .unwrap()
+                    .add_i64(-2)
+                    .unwrap()
+                    .add_i64(1000)
+                    .unwrap()
+                    .add_ops(&[OpTrue, OpFalse])
+                    .unwrap()
+                    .drain(),
+            },
+            Test { name: "single byte data is not a small integer", source: "0x05 0x", expected: vec![OpData1, 0x05, Op0] },
+            Test {
+                name: "explicit push opcodes",
+                source: "OpPushData1 0xabcd OpPushData2 0x OpData2 0x0102",
+                expected: vec![OpPushData1, 0x02, 0xab, 0xcd, OpPushData2, 0x00, 0x00, OpData2, 0x01, 0x02],
+            },
+        ];
+
+        for test in tests {
+            assert_eq!(assemble(test.source), Ok(test.expected), "test '{}' failed", test.name);
+        }
+
+        let large_data = format!("0x{}", "ab".repeat(300));
+        assert_eq!(assemble(&large_data), Ok([vec![OpPushData2, 0x2c, 0x01], vec![0xab; 300]].concat()));
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        struct Test {
+            name: &'static str,
+            source: &'static str,
+            expected: AsmError,
+        }
+
+        let tests = vec![
+            Test {
+                name: "unknown opcode",
+                source: "OpDup OpFoo",
+                expected: AsmError::UnknownOpcode { token: "OpFoo".to_owned(), span: 6..11 },
+            },
+            Test { name: "odd hex length", source: "0xabc", expected: AsmError::InvalidHex { token: "0xabc".to_owned(), span: 0..5 } },
+            Test {
+                name: "non hex data",
+                source: "OpDup\n  0xzz",
+                expected: AsmError::InvalidHex { token: "0xzz".to_owned(), span: 8..12 },
+            },
+            Test {
+                name: "number overflow",
+                source: "99999999999999999999",
+                expected: AsmError::InvalidNumber { token: "99999999999999999999".to_owned(), span: 0..20 },
+            },
+            Test {
+                name: "missing operand",
+                source: "OpPushData1 OpDup",
+                expected: AsmError::MissingOperand { opcode: "OpPushData1", span: 0..11 },
+            },
+            Test {
+                name: "operand length mismatch",
+                source: "OpDup OpData2 0x01",
+                expected: AsmError::InvalidOperandLength { opcode: "OpData2", len: 1, span: 6..18 },
+            },
+        ];
+
+        for test in tests {
+            let result = assemble(test.source);
+            assert_eq!(result, Err(test.expected), "test '{}' failed", test.name);
+            let span = result.unwrap_err().span().cloned().unwrap();
+            assert!(span.end <= test.source.len(), "test '{}' has a span out of the source", test.name);
+        }
+    }
+
+    #[test]
+    fn test_disassemble_roundtrip() {
+        let scripts = vec![
+            vec![],
+            ScriptBuilder::new().add_data(&[0x02; 33]).unwrap().add_op(OpCheckSigECDSA).unwrap().drain(),
+            vec![OpFalse, OpTrue, Op16, Op1Negate, OpData1, 0x05, OpIf, OpCovOutputCount, OpElse, OpZkPrecompile, OpEndIf],
+            vec![OpPushData1, 0x02, 0xab, 0xcd, OpPushData2, 0x00, 0x00, OpPushData4, 0x01, 0x00, 0x00, 0x00, 0xff],
+            [vec![OpPushData1, 0xff], vec![0x11; 0xff], vec![OpPushData2, 0x00, 0x01], vec![0x22; 0x100]].concat(),
+            (0..=u8::MAX).filter(|opcode| payload(*opcode).is_none()).collect(),
+        ];
+
+        for script in scripts {
+            let text = disassemble(&script).unwrap();
+            assert_eq!(assemble(&text).unwrap(), script, "roundtrip failed for `{text}`");
+        }
+
+        assert_eq!(disassemble(&[OpDup, OpData2, 0x01, 0x02, OpPushData1, 0x01, 0x03]).unwrap(), "OpDup 0x0102 OpPushData1 0x03");
+    }
+
+    #[test]
+    fn test_disassemble_truncated() {
+        let tests = vec![
+            (vec![OpDup, OpData2, 0x01], AsmError::TruncatedPush { opcode: "OpData2", offset: 1, expected: 2, available: 1 }),
+            (vec![OpPushData2, 0x01], AsmError::TruncatedPush { opcode: "OpPushData2", offset: 0, expected: 2, available: 1 }),
+            (
+                vec![OpPushData4, 0xff, 0xff, 0xff, 0xff, 0x00],
+                AsmError::TruncatedPush { opcode: "OpPushData4", offset: 0, expected: u32::MAX as usize, available: 1 },
+            ),
+        ];
+
+        for (script, expected) in tests {
+            assert_eq!(disassemble(&script), Err(expected));
+        }
+    }
+}
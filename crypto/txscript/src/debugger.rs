@@ -0,0 +1,357 @@
+//!
+//! Structured execution tracing and a step-through debugger for [`TxScriptEngine`].
+//!
+//! A [`ScriptTracer`] attached via [`TxScriptEngine::with_tracer`] is notified before every
+//! opcode with the full engine state. [`TraceRecorder`] captures these notifications and
+//! [`ScriptDebugger`] replays them, supporting breakpoints as well as stepping in both directions.
+//!
+
+use crate::caches::Cache;
+use crate::covenants::CovenantsContext;
+use crate::data_stack::StackEntry;
+use crate::opcodes::{OpCond, opcode_name};
+use crate::{EngineCtx, EngineFlags, ExecutionStacksView, TxScriptEngine};
+use kaspa_consensus_core::hashing::sighash::SigHashReusedValuesUnsync;
+use kaspa_consensus_core::mass::ScriptUnits;
+use kaspa_consensus_core::tx::{PopulatedTransaction, VerifiableTransaction};
+use kaspa_txscript_errors::TxScriptError;
+
+/// Receives engine state notifications during script execution.
+pub trait ScriptTracer {
+    /// Called before each opcode is executed, including opcodes skipped by a false conditional branch.
+    fn on_step(&mut self, step: &TraceStep<'_>);
+
+    /// Called once when execution completes, with the execution result and the final stacks.
+    fn on_finish(&mut self, _result: &Result<(), TxScriptError>, _stacks: ExecutionStacksView<'_>) {}
+}
+
+/// Engine state right before an opcode is executed
+pub struct TraceStep<'a> {
+    /// Index of the script being executed: the signature script is `0`, the script public key
+    /// is `1` and a P2SH redeem script follows the last of them
+    pub script_index: usize,
+    /// Index of the opcode within its script
+    pub opcode_index: usize,
+    /// Opcode value
+    pub opcode: u8,
+    /// Data pushed by the opcode, empty for non-push opcodes
+    pub data: &'a [u8],
+    /// Whether the opcode is executed, `false` when inside a branch not taken
+    pub executing: bool,
+    pub dstack: &'a [StackEntry],
+    pub astack: &'a [StackEntry],
+    pub cond_stack: &'a [OpCond],
+    /// Number of non-push operations executed so far by the current script
+    pub num_ops: i32,
+    pub used_sig_ops: u16,
+    pub used_script_units: ScriptUnits,
+}
+
+impl TraceStep<'_> {
+    pub fn opcode_name(&self) -> &'static str {
+        opcode_name(self.opcode)
+    }
+}
+
+/// Owned copy of a [`TraceStep`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StepSnapshot {
+    pub script_index: usize,
+    pub opcode_index: usize,
+    pub opcode: u8,
+    pub data: Vec<u8>,
+    pub executing: bool,
+    pub dstack: Vec<Vec<u8>>,
+    pub astack: Vec<Vec<u8>>,
+    pub cond_stack: Vec<OpCond>,
+    pub num_ops: i32,
+    pub used_sig_ops: u16,
+    pub used_script_units: ScriptUnits,
+}
+
+impl StepSnapshot {
+    pub fn opcode_name(&self) -> &'static str {
+        opcode_name(self.opcode)
+    }
+}
+
+impl From<&TraceStep<'_>> for StepSnapshot {
+    fn from(step: &TraceStep<'_>) -> Self {
+        Self {
+            script_index: step.script_index,
+            opcode_index: step.opcode_index,
+            opcode: step.opcode,
+            data: step.data.to_vec(),
+            executing: step.executing,
+            dstack: step.dstack.iter().map(|item| item.to_vec()).collect(),
+            astack: step.astack.iter().map(|item| item.to_vec()).collect(),
+            cond_stack: step.cond_stack.to_vec(),
+            num_ops: step.num_ops,
+            used_sig_ops: step.used_sig_ops,
+            used_script_units: step.used_script_units,
+        }
+    }
+}
+
+/// Result of a traced execution along with the final stacks
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceOutcome {
+    pub result: Result<(), TxScriptError>,
+    pub dstack: Vec<Vec<u8>>,
+    pub astack: Vec<Vec<u8>>,
+}
+
+/// A [`ScriptTracer`] recording a snapshot of every step
+#[derive(Clone, Debug, Default)]
+pub struct TraceRecorder {
+    steps: Vec<StepSnapshot>,
+    outcome: Option<TraceOutcome>,
+}
+
+impl TraceRecorder {
+    pub fn steps(&self) -> &[StepSnapshot] {
+        &self.steps
+    }
+
+    /// Returns the execution outcome, `None` if execution did not complete yet
+    pub fn outcome(&self) -> Option<&TraceOutcome> {
+        self.outcome.as_ref()
+    }
+}
+
+impl ScriptTracer for TraceRecorder {
+    fn on_step(&mut self, step: &TraceStep<'_>) {
+        self.steps.push(step.into());
+    }
+
+    fn on_finish(&mut self, result: &Result<(), TxScriptError>, stacks: ExecutionStacksView<'_>) {
+        self.outcome = Some(TraceOutcome {
+            result: result.clone(),
+            dstack: stacks.dstack.iter().map(|item| item.to_vec()).collect(),
+            astack: stacks.astack.iter().map(|item| item.to_vec()).collect(),
+        });
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Breakpoint {
+    /// Breaks before the opcode at `opcode_index` of the script at `script_index`
+    Location { script_index: usize, opcode_index: usize },
+    /// Breaks before every occurrence of the opcode
+    Opcode(u8),
+}
+
+impl Breakpoint {
+    pub fn matches(&self, step: &StepSnapshot) -> bool {
+        match *self {
+            Breakpoint::Location { script_index, opcode_index } => {
+                step.script_index == script_index && step.opcode_index == opcode_index
+            }
+            Breakpoint::Opcode(opcode) => step.opcode == opcode,
+        }
+    }
+}
+
+/// Step-through debugger over a recorded script execution.
+///
+/// Execution is deterministic, so the debugger replays the recorded steps rather than suspending
+/// the engine. The cursor points at the step about to be executed, or past the last step once
+/// execution finished, in which case [`ScriptDebugger::outcome`] holds the final state.
+#[derive(Clone, Debug)]
+pub struct ScriptDebugger {
+    steps: Vec<StepSnapshot>,
+    outcome: Option<TraceOutcome>,
+    position: usize,
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl ScriptDebugger {
+    pub fn new(recorder: TraceRecorder) -> Self {
+        Self { steps: recorder.steps, outcome: recorder.outcome, position: 0, breakpoints: vec![] }
+    }
+
+    /// Records the execution of the standalone `script`
+    pub fn trace_script(script: &[u8], flags: EngineFlags) -> Self {
+        let sig_cache = Cache::new(0);
+        let reused_values = SigHashReusedValuesUnsync::new();
+        let mut recorder = TraceRecorder::default();
+        let _ = TxScriptEngine::<PopulatedTransaction<'_>, _>::from_script(script, &reused_values, &sig_cache, flags)
+            .with_tracer(&mut recorder)
+            .execute();
+        Self::new(recorder)
+    }
+
+    /// Records the validation of the input at `input_idx` of a populated transaction, the same way
+    /// consensus does, including the input script units limit and the covenants context.
+    pub fn trace_transaction_input(
+        tx: &impl VerifiableTransaction,
+        input_idx: usize,
+        flags: EngineFlags,
+    ) -> Result<Self, TxScriptError> {
+        let inputs = tx.inputs().len();
+        if input_idx >= inputs {
+            return Err(TxScriptError::InvalidInputIndex(input_idx as i32, inputs));
+        }
+        let covenants_ctx = if flags.covenants_enabled { CovenantsContext::from_tx(tx)? } else { Default::default() };
+        let sig_cache = Cache::new(0);
+        let reused_values = SigHashReusedValuesUnsync::new();
+        let ctx = EngineCtx::new(&sig_cache).with_covenants_ctx(&covenants_ctx).with_reused(&reused_values);
+        let (input, entry) = tx.populated_input(input_idx);
+        let mut recorder = TraceRecorder::default();
+        let _ = TxScriptEngine::from_transaction_input_with_script_units_limit(
+            tx,
+            input,
+            input_idx,
+            entry,
+            ctx,
+            flags,
+            input.compute_commit.allowed_script_units(),
+        )
+        .with_tracer(&mut recorder)
+        .execute();
+        Ok(Self::new(recorder))
+    }
+
+    pub fn steps(&self) -> &[StepSnapshot] {
+        &self.steps
+    }
+
+    pub fn outcome(&self) -> Option<&TraceOutcome> {
+        self.outcome.as_ref()
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the step about to be executed, `None` once execution finished
+    pub fn current(&self) -> Option<&StepSnapshot> {
+        self.steps.get(self.position)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.steps.len()
+    }
+
+    pub fn reset(&mut self) {
+        self.position = 0;
+    }
+
+    /// Executes a single opcode
+    pub fn step(&mut self) -> Option<&StepSnapshot> {
+        self.position = (self.position + 1).min(self.steps.len());
+        self.current()
+    }
+
+    /// Reverts the last executed opcode
+    pub fn step_back(&mut self) -> Option<&StepSnapshot> {
+        self.position = self.position.saturating_sub(1);
+        self.current()
+    }
+
+    /// Executes opcodes until the next breakpoint or the end of execution
+    pub fn resume(&mut self) -> Option<&StepSnapshot> {
+        let next = self.position + 1;
+        self.position = match self.steps.get(next..) {
+            Some(remaining) => remaining
+                .iter()
+                .position(|step| self.breakpoints.iter().any(|breakpoint| breakpoint.matches(step)))
+                .map_or(self.steps.len(), |offset| next + offset),
+            None => self.steps.len(),
+        };
+        self.current()
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    /// Removes a breakpoint, returns `false` if it was not set
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        let len = self.breakpoints.len();
+        self.breakpoints.retain(|b| b != breakpoint);
+        self.breakpoints.len() != len
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm::assemble;
+    use crate::opcodes::codes;
+
+    fn debugger(source: &str) -> ScriptDebugger {
+        ScriptDebugger::trace_script(&assemble(source).unwrap(), EngineFlags::default())
+    }
+
+    #[test]
+    fn test_trace_steps() {
+        let debugger = debugger("OpTrue OpIf 2 OpElse 3 OpEndIf OpDup OpToAltStack OpFromAltStack OpEqual");
+        let steps = debugger.steps();
+        assert_eq!(steps.len(), 10);
+        assert!(steps.iter().enumerate().all(|(i, step)| step.script_index == 0 && step.opcode_index == i));
+
+        // Before OpElse: the true branch was taken and pushed 2
+        assert_eq!(steps[3].opcode, codes::OpElse);
+        assert_eq!(steps[3].cond_stack, vec![OpCond::True]);
+        assert_eq!(steps[3].dstack, vec![vec![2u8]]);
+        // 3 is skipped
+        assert_eq!(steps[4].opcode, codes::Op3);
+        assert!(!steps[4].executing);
+        assert_eq!(steps[4].cond_stack, vec![OpCond::False]);
+        // Before OpFromAltStack
+        assert_eq!(steps[8].dstack, vec![vec![2u8]]);
+        assert_eq!(steps[8].astack, vec![vec![2u8]]);
+        assert_eq!(steps[8].num_ops, 5);
+
+        let outcome = debugger.outcome().unwrap();
+        assert_eq!(outcome.result, Ok(()));
+        assert!(outcome.dstack.is_empty());
+    }
+
+    #[test]
+    fn test_trace_failure() {
+        let debugger = debugger("OpTrue OpVerify OpFalse OpVerify OpTrue");
+        // Tracing stops at the failing opcode
+        assert_eq!(debugger.steps().len(), 4);
+        assert_eq!(debugger.outcome().unwrap().result, Err(TxScriptError::VerifyError));
+    }
+
+    #[test]
+    fn test_stepping_and_breakpoints() {
+        let mut debugger = debugger("1 2 OpAdd OpDup OpAdd 6 OpEqual");
+        assert_eq!(debugger.current().unwrap().opcode, codes::OpTrue);
+
+        assert_eq!(debugger.step().unwrap().opcode, codes::Op2);
+        assert_eq!(debugger.step().unwrap().dstack, vec![vec![1u8], vec![2u8]]);
+        assert_eq!(debugger.step_back().unwrap().opcode, codes::Op2);
+
+        debugger.add_breakpoint(Breakpoint::Opcode(codes::OpAdd));
+        debugger.add_breakpoint(Breakpoint::Location { script_index: 0, opcode_index: 6 });
+        assert_eq!(debugger.breakpoints().len(), 2);
+
+        assert_eq!(debugger.resume().unwrap().opcode_index, 2);
+        let step = debugger.resume().unwrap();
+        assert_eq!((step.opcode_index, step.dstack.clone()), (4, vec![vec![3u8], vec![3u8]]));
+        assert_eq!(debugger.resume().unwrap().opcode, codes::OpEqual);
+        assert!(debugger.resume().is_none());
+        assert!(debugger.is_finished());
+        assert!(debugger.step().is_none());
+
+        assert!(debugger.remove_breakpoint(&Breakpoint::Opcode(codes::OpAdd)));
+        assert!(!debugger.remove_breakpoint(&Breakpoint::Opcode(codes::OpAdd)));
+        debugger.reset();
+        assert_eq!(debugger.resume().unwrap().opcode_index, 6);
+        assert_eq!(debugger.outcome().unwrap().result, Ok(()));
+    }
+}
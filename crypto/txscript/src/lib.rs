@@ -1,8 +1,10 @@
 extern crate alloc;
 extern crate core;
+pub mod asm;
 pub mod caches;
 pub mod covenants;
 mod data_stack;
+pub mod debugger;
 pub mod error;
 pub mod hex;
 pub mod opcodes;
@@ -39,6 +41,7 @@ use std::ops::Deref;
 use crate::caches::Cache;
 use crate::covenants::CovenantsContext;
 use crate::data_stack::{Stack, StackEntry};
+use crate::debugger::{ScriptTracer, TraceStep};
 use crate::opcodes::{OpCodeImplementation, deserialize_next_opcode};
 use crate::zk_precompiles::compute_zk_cost;
 use crate::zk_precompiles::tags::ZkTag;
@@ -167,6 +170,7 @@ pub struct TxScriptEngine<'a, T: VerifiableTransaction, Reused: SigHashReusedVal
     num_ops: i32,
     runtime_resource_meter: RuntimeResourceMeter,
     opcode_execution_log_buffer: Option<&'a mut dyn Write>,
+    tracer: Option<&'a mut dyn ScriptTracer>,
     flags: EngineFlags,
 }
 
@@ -471,6 +475,7 @@ impl<'a, T: VerifiableTransaction, Reused: SigHashReusedValues> TxScriptEngine<'
             runtime_resource_meter,
             flags,
             opcode_execution_log_buffer: None,
+            tracer: None,
         }
     }
 
@@ -498,6 +503,13 @@ impl<'a, T: VerifiableTransaction, Reused: SigHashReusedValues> TxScriptEngine<'
         self
     }
 
+    /// Attaches a tracer which is notified before every opcode and once execution completes.
+    /// See [`debugger::ScriptDebugger`] for a breakpoint and stepping capable consumer.
+    pub fn with_tracer(mut self, tracer: &'a mut dyn ScriptTracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
     /// Returns a read-only view of the execution stacks
     pub fn stacks(&self) -> ExecutionStacksView<'_> {
         ExecutionStacksView { dstack: &self.dstack, astack: &self.astack }
@@ -558,6 +570,7 @@ impl<'a, T: VerifiableTransaction, Reused: SigHashReusedValues> TxScriptEngine<'
             num_ops: 0,
             runtime_resource_meter,
             opcode_execution_log_buffer: None,
+            tracer: None,
             flags,
         }
     }
@@ -592,6 +605,7 @@ impl<'a, T: VerifiableTransaction, Reused: SigHashReusedValues> TxScriptEngine<'
             num_ops: 0,
             runtime_resource_meter,
             opcode_execution_log_buffer: None,
+            tracer: None,
             flags,
         }
     }
@@ -653,9 +667,38 @@ impl<'a, T: VerifiableTransaction, Reused: SigHashReusedValues> TxScriptEngine<'
         .unwrap();
     }
 
-    fn execute_script(&mut self, script: &[u8], verify_only_push: bool) -> Result<(), TxScriptError> {
-        let script_result = parse_script(script).try_for_each(|opcode| {
+    fn trace_opcode_execution(&mut self, script_index: usize, opcode_index: usize, opcode: &DynOpcodeImplementation<T, Reused>) {
+        let executing = self.is_executing() || opcode.is_conditional();
+        let Some(tracer) = self.tracer.as_mut() else {
+            return;
+        };
+
+        let step = TraceStep {
+            script_index,
+            opcode_index,
+            opcode: opcode.value(),
+            data: opcode.get_data(),
+            executing,
+            dstack: &self.dstack,
+            astack: &self.astack,
+            cond_stack: &self.cond_stack,
+            num_ops: self.num_ops,
+            used_sig_ops: self.runtime_resource_meter.used_sig_ops(),
+            used_script_units: self.runtime_resource_meter.used_script_units(),
+        };
+        tracer.on_step(&step);
+    }
+
+    fn trace_finish(&mut self, result: &Result<(), TxScriptError>) {
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.on_finish(result, ExecutionStacksView { dstack: &self.dstack, astack: &self.astack });
+        }
+    }
+
+    fn execute_script(&mut self, script_index: usize, script: &[u8], verify_only_push: bool) -> Result<(), TxScriptError> {
+        let script_result = parse_script(script).enumerate().try_for_each(|(opcode_index, opcode)| {
             let opcode = opcode?;
+            self.trace_opcode_execution(script_index, opcode_index, &opcode);
             if opcode.is_disabled(self.flags) {
                 return Err(TxScriptError::OpcodeDisabled(format!("{:?}", opcode)));
             }
@@ -739,29 +782,33 @@ impl<'a, T: VerifiableTransaction, Reused: SigHashReusedValues> TxScriptEngine<'
             if is_p2sh && idx == 1 {
                 saved_stack = Some(self.dstack.clone());
             }
-            self.execute_script(s, verify_only_push)
+            self.execute_script(idx, s, verify_only_push)
         })?;
 
         if is_p2sh {
             self.check_error_condition(false)?;
             self.dstack = saved_stack.ok_or(TxScriptError::EmptyStack)?;
             let script = self.dstack.pop()?;
-            self.execute_script(script.as_slice(), false)?
+            self.execute_script(scripts.len(), script.as_slice(), false)?
         }
         Ok(ScriptExecutionOutput::Executed)
     }
 
     pub fn execute(&mut self) -> Result<(), TxScriptError> {
-        match self.execute_inner()? {
+        let result = self.execute_inner().and_then(|output| match output {
             ScriptExecutionOutput::Executed => self.check_error_condition(true),
             // Unknown script versions are accepted without execution, indepedently of the stack state. There's no need to check the error condition.
             ScriptExecutionOutput::AcceptedUnknownVersion => Ok(()),
-        }
+        });
+        self.trace_finish(&result);
+        result
     }
 
     /// Executes the scripts without the final error condition checks and returns both stacks in raw vector form.
     pub fn execute_and_return_stacks(mut self) -> Result<ExecutionStacks, TxScriptError> {
-        let _ = self.execute_inner()?;
+        let result = self.execute_inner().map(|_| ());
+        self.trace_finish(&result);
+        result?;
         Ok(ExecutionStacks { dstack: self.dstack.into(), astack: self.astack.into() })
     }
 
@@ -133,6 +133,28 @@ macro_rules! opcode_list {
             }
         }
 
+        /// Returns the canonical name of an opcode.
+        pub fn opcode_name(opcode: u8) -> &'static str {
+            match opcode {
+                $(
+                    $num => stringify!($name),
+                )*
+            }
+        }
+
+        /// Returns all opcode names, including aliases, along with their values.
+        pub fn opcode_names() -> impl Iterator<Item = (&'static str, u8)> {
+            [
+                $(
+                    (stringify!($name), $num),
+                    $(
+                        (stringify!($alias), $num),
+                    )?
+                )*
+            ]
+            .into_iter()
+        }
+
         use crate::script_builder::{ScriptBuilder, ScriptBuilderResult};
 
         #[allow(unused_comparisons)]
@@ -32,8 +32,9 @@ pub const OP_DATA_MAX_VAL: u8 = self::codes::OpData75;
 /// Minus 1 value
 pub const OP_1_NEGATE_VAL: u8 = 0x81;
 
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) enum OpCond {
+/// State of an entry of the conditional execution stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpCond {
     False,
     True,
     Skip,
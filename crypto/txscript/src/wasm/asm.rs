@@ -0,0 +1,30 @@
+use crate::{asm, result::Result};
+use kaspa_wasm_core::types::{BinaryT, HexString};
+use wasm_bindgen::prelude::wasm_bindgen;
+use workflow_wasm::prelude::*;
+
+/// Assembles a script from its human-readable representation, made of opcode names
+/// (`OpCheckSig`), `0x` prefixed hex data pushes and decimal numbers.
+/// Everything following a `#` up to the end of the line is a comment.
+///
+/// Throws an error describing the offending token and its position in the source.
+///
+/// @see {@link disassembleScript}
+/// @category Consensus
+#[wasm_bindgen(js_name = "assembleScript")]
+pub fn assemble_script(source: &str) -> Result<HexString> {
+    Ok(HexString::from(asm::assemble(source)?.as_slice()))
+}
+
+/// Disassembles a script into its human-readable representation.
+/// Supplied script can be represented as an `Uint8Array` or a `HexString`.
+///
+/// The result assembles back into the very same script.
+///
+/// @see {@link assembleScript}
+/// @category Consensus
+#[wasm_bindgen(js_name = "disassembleScript")]
+pub fn disassemble_script(script: BinaryT) -> Result<String> {
+    let script = script.try_as_vec_u8()?;
+    Ok(asm::disassemble(&script)?)
+}
@@ -0,0 +1,263 @@
+use crate::debugger::{self as native, Breakpoint, StepSnapshot, TraceOutcome};
+use crate::opcodes::OpCond;
+use crate::wasm::ScriptBuilderOptions;
+use crate::{EngineFlags, result::Result};
+use kaspa_consensus_core::tx::VerifiableTransaction;
+use kaspa_utils::hex::ToHex;
+use kaspa_wasm_core::types::BinaryT;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use workflow_wasm::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_SCRIPT_DEBUGGER: &'static str = r#"
+/**
+ * Script debugger options.
+ *
+ * @category TxScript
+ */
+export interface ScriptDebuggerOptions {
+    /** Engine flags used when executing the script. */
+    flags?: ScriptBuilderFlags;
+}
+
+/**
+ * Script engine state right before an opcode is executed.
+ *
+ * @category TxScript
+ */
+export interface IScriptDebuggerStep {
+    /** Index of the executed script: signature script (0), script public key (1), P2SH redeem script (2). */
+    scriptIndex: number;
+    /** Index of the opcode within its script. */
+    opcodeIndex: number;
+    opcode: number;
+    opcodeName: string;
+    /** Data pushed by the opcode. */
+    data: HexString;
+    /** Whether the opcode is executed, false when inside a branch not taken. */
+    executing: boolean;
+    dstack: HexString[];
+    astack: HexString[];
+    condStack: ("true" | "false" | "skip")[];
+    numOps: number;
+    usedSigOps: number;
+    usedScriptUnits: number;
+}
+
+/**
+ * Final state of a debugged script execution.
+ *
+ * @category TxScript
+ */
+export interface IScriptDebuggerOutcome {
+    /** Error message, undefined if the execution succeeded. */
+    error?: string;
+    dstack: HexString[];
+    astack: HexString[];
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "ScriptDebuggerOptions")]
+    pub type ScriptDebuggerOptions;
+    #[wasm_bindgen(typescript_type = "IScriptDebuggerStep | undefined")]
+    pub type ScriptDebuggerStepT;
+    #[wasm_bindgen(typescript_type = "IScriptDebuggerOutcome | undefined")]
+    pub type ScriptDebuggerOutcomeT;
+}
+
+impl TryFrom<ScriptDebuggerOptions> for EngineFlags {
+    type Error = crate::error::Error;
+
+    fn try_from(value: ScriptDebuggerOptions) -> Result<Self> {
+        EngineFlags::try_from(value.unchecked_into::<ScriptBuilderOptions>())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StepInfo {
+    script_index: usize,
+    opcode_index: usize,
+    opcode: u8,
+    opcode_name: &'static str,
+    data: String,
+    executing: bool,
+    dstack: Vec<String>,
+    astack: Vec<String>,
+    cond_stack: Vec<&'static str>,
+    num_ops: i32,
+    used_sig_ops: u16,
+    used_script_units: u64,
+}
+
+impl From<&StepSnapshot> for StepInfo {
+    fn from(step: &StepSnapshot) -> Self {
+        Self {
+            script_index: step.script_index,
+            opcode_index: step.opcode_index,
+            opcode: step.opcode,
+            opcode_name: step.opcode_name(),
+            data: step.data.to_hex(),
+            executing: step.executing,
+            dstack: step.dstack.iter().map(|item| item.to_hex()).collect(),
+            astack: step.astack.iter().map(|item| item.to_hex()).collect(),
+            cond_stack: step
+                .cond_stack
+                .iter()
+                .map(|cond| match cond {
+                    OpCond::True => "true",
+                    OpCond::False => "false",
+                    OpCond::Skip => "skip",
+                })
+                .collect(),
+            num_ops: step.num_ops,
+            used_sig_ops: step.used_sig_ops,
+            used_script_units: step.used_script_units.0,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutcomeInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    dstack: Vec<String>,
+    astack: Vec<String>,
+}
+
+impl From<&TraceOutcome> for OutcomeInfo {
+    fn from(outcome: &TraceOutcome) -> Self {
+        Self {
+            error: outcome.result.as_ref().err().map(|err| err.to_string()),
+            dstack: outcome.dstack.iter().map(|item| item.to_hex()).collect(),
+            astack: outcome.astack.iter().map(|item| item.to_hex()).collect(),
+        }
+    }
+}
+
+/// Step-through debugger over a recorded script execution. Supports breakpoints
+/// as well as stepping forward and backward through the executed opcodes.
+///
+/// @see {@link debugTransactionInput}
+/// @category Consensus
+#[wasm_bindgen]
+pub struct ScriptDebugger {
+    inner: native::ScriptDebugger,
+}
+
+impl ScriptDebugger {
+    /// Records the validation of a populated transaction input.
+    pub fn try_from_transaction_input(
+        tx: &impl VerifiableTransaction,
+        input_index: usize,
+        options: Option<ScriptDebuggerOptions>,
+    ) -> Result<Self> {
+        let flags = options.map(EngineFlags::try_from).transpose()?.unwrap_or_default();
+        let inner = native::ScriptDebugger::trace_transaction_input(tx, input_index, flags).map_err(|err| err.to_string())?;
+        Ok(Self { inner })
+    }
+
+    pub fn inner(&self) -> &native::ScriptDebugger {
+        &self.inner
+    }
+
+    fn current_step(&self) -> Result<ScriptDebuggerStepT> {
+        let step = self.inner.current().map(StepInfo::from);
+        Ok(serde_wasm_bindgen::to_value(&step)?.unchecked_into())
+    }
+}
+
+#[wasm_bindgen]
+impl ScriptDebugger {
+    /// Records the execution of a standalone script.
+    /// Supplied script can be represented as an `Uint8Array` or a `HexString`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(script: BinaryT, options: Option<ScriptDebuggerOptions>) -> Result<ScriptDebugger> {
+        let flags = options.map(EngineFlags::try_from).transpose()?.unwrap_or_default();
+        let script = script.try_as_vec_u8()?;
+        Ok(Self { inner: native::ScriptDebugger::trace_script(&script, flags) })
+    }
+
+    /// Number of recorded steps.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.inner.steps().len()
+    }
+
+    /// Index of the step about to be executed.
+    #[wasm_bindgen(getter)]
+    pub fn position(&self) -> usize {
+        self.inner.position()
+    }
+
+    #[wasm_bindgen(getter, js_name = "isFinished")]
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    /// Returns the step about to be executed, `undefined` once execution finished.
+    pub fn current(&self) -> Result<ScriptDebuggerStepT> {
+        self.current_step()
+    }
+
+    /// Executes a single opcode and returns the next step.
+    pub fn step(&mut self) -> Result<ScriptDebuggerStepT> {
+        self.inner.step();
+        self.current_step()
+    }
+
+    /// Reverts the last executed opcode and returns its step.
+    #[wasm_bindgen(js_name = "stepBack")]
+    pub fn step_back(&mut self) -> Result<ScriptDebuggerStepT> {
+        self.inner.step_back();
+        self.current_step()
+    }
+
+    /// Executes opcodes until the next breakpoint or the end of execution.
+    pub fn resume(&mut self) -> Result<ScriptDebuggerStepT> {
+        self.inner.resume();
+        self.current_step()
+    }
+
+    /// Rewinds to the first step.
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    /// Returns the final execution state, `undefined` if the execution did not complete.
+    pub fn outcome(&self) -> Result<ScriptDebuggerOutcomeT> {
+        let outcome = self.inner.outcome().map(OutcomeInfo::from);
+        Ok(serde_wasm_bindgen::to_value(&outcome)?.unchecked_into())
+    }
+
+    /// Breaks before the opcode at `opcodeIndex` of the script at `scriptIndex`.
+    #[wasm_bindgen(js_name = "addBreakpoint")]
+    pub fn add_breakpoint(&mut self, script_index: usize, opcode_index: usize) {
+        self.inner.add_breakpoint(Breakpoint::Location { script_index, opcode_index });
+    }
+
+    /// Breaks before every occurrence of `opcode`.
+    #[wasm_bindgen(js_name = "addOpcodeBreakpoint")]
+    pub fn add_opcode_breakpoint(&mut self, opcode: u8) {
+        self.inner.add_breakpoint(Breakpoint::Opcode(opcode));
+    }
+
+    #[wasm_bindgen(js_name = "removeBreakpoint")]
+    pub fn remove_breakpoint(&mut self, script_index: usize, opcode_index: usize) -> bool {
+        self.inner.remove_breakpoint(&Breakpoint::Location { script_index, opcode_index })
+    }
+
+    #[wasm_bindgen(js_name = "removeOpcodeBreakpoint")]
+    pub fn remove_opcode_breakpoint(&mut self, opcode: u8) -> bool {
+        self.inner.remove_breakpoint(&Breakpoint::Opcode(opcode))
+    }
+
+    #[wasm_bindgen(js_name = "clearBreakpoints")]
+    pub fn clear_breakpoints(&mut self) {
+        self.inner.clear_breakpoints();
+    }
+}
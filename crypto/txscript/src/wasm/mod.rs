@@ -8,8 +8,12 @@ cfg_if! {
     if #[cfg(any(feature = "wasm32-sdk", feature = "wasm32-core"))] {
         pub mod opcodes;
         pub mod builder;
+        pub mod asm;
+        pub mod debugger;
 
         pub use self::opcodes::*;
         pub use self::builder::*;
+        pub use self::asm::*;
+        pub use self::debugger::*;
     }
 }
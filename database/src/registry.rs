@@ -69,11 +69,19 @@ pub enum DatabaseStorePrefixes {
     // ---- Components ----
     Addresses = 128,
     BannedAddresses = 129,
+    BannedAddressDetails = 130,
+    OnionAddresses = 131,
 
     // ---- Indexes ----
     UtxoIndex = 192,
     UtxoIndexTips = 193,
     CirculatingSupply = 194,
+    TxIndexEntries = 195,
+    TxIndexAcceptingBlocks = 196,
+    TxIndexSink = 197,
+    AddressIndexHistory = 198,
+    AddressIndexAcceptingBlocks = 199,
+    AddressIndexSink = 200,
 
     // ---- SMT Versioned Store ----
     SmtBranchVersions = 71,
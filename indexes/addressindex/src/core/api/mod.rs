@@ -0,0 +1,86 @@
+use kaspa_consensus_core::tx::ScriptPublicKey;
+use kaspa_consensusmanager::spawn_blocking;
+use kaspa_database::prelude::StoreResult;
+use kaspa_hashes::Hash;
+use parking_lot::RwLock;
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{
+    errors::AddressIndexResult,
+    model::{AddressHistoryCursor, AddressHistoryEntry},
+};
+
+///Addressindex API targeted at retrieval calls.
+pub trait AddressIndexApi: Send + Sync + Debug {
+    /// Retrieve up to `limit` history entries of a script public key accepted within the inclusive
+    /// `[from_daa_score, to_daa_score]` range, ordered by accepting DAA score and positioned after `cursor` (if provided).
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_history(
+        &self,
+        script_public_key: &ScriptPublicKey,
+        from_daa_score: u64,
+        to_daa_score: u64,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> StoreResult<Vec<AddressHistoryEntry>>;
+
+    /// Retrieve the chain block the addressindex is synced to (used for testing purposes).
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_sink(&self) -> StoreResult<Hash>;
+
+    /// Checks if the addressindex's db is synced with consensus.
+    ///
+    /// Note:
+    /// 1) Use a read lock when accessing this method
+    /// 2) due to potential sync-gaps is_synced is unreliable while consensus is actively resolving virtual states.
+    fn is_synced(&self) -> AddressIndexResult<bool>;
+
+    /// Update the addressindex with the given virtual chain changes.
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn update(
+        &mut self,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+    ) -> AddressIndexResult<()>;
+
+    /// Resync the addressindex from the consensus db
+    ///
+    /// Note: Use a write lock when accessing this method
+    fn resync(&mut self) -> AddressIndexResult<()>;
+}
+
+/// Async proxy for the address history index
+#[derive(Debug, Clone)]
+pub struct AddressIndexProxy {
+    inner: Arc<RwLock<dyn AddressIndexApi>>,
+}
+
+impl AddressIndexProxy {
+    pub fn new(inner: Arc<RwLock<dyn AddressIndexApi>>) -> Self {
+        Self { inner }
+    }
+
+    pub async fn get_history(
+        self,
+        script_public_key: ScriptPublicKey,
+        from_daa_score: u64,
+        to_daa_score: u64,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> StoreResult<Vec<AddressHistoryEntry>> {
+        spawn_blocking(move || self.inner.read().get_history(&script_public_key, from_daa_score, to_daa_score, cursor, limit))
+            .await
+            .unwrap()
+    }
+
+    pub async fn update(
+        self,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+    ) -> AddressIndexResult<()> {
+        spawn_blocking(move || self.inner.write().update(removed_chain_block_hashes, added_chain_block_hashes)).await.unwrap()
+    }
+}
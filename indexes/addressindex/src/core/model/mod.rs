@@ -0,0 +1,41 @@
+use kaspa_consensus_core::tx::TransactionId;
+use kaspa_hashes::Hash;
+use kaspa_utils::mem_size::MemSizeEstimator;
+use serde::{Deserialize, Serialize};
+
+/// An accepted transaction touching an address, either by funding it or by spending from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AddressHistoryEntry {
+    pub transaction_id: TransactionId,
+    /// The chain block whose mergeset accepted the transaction
+    pub accepting_block_hash: Hash,
+    /// The DAA score of the accepting chain block
+    pub accepting_daa_score: u64,
+    /// The total amount of the transaction outputs paying to the address
+    pub received_amount: u64,
+    /// The total amount of the transaction inputs spending from the address
+    pub spent_amount: u64,
+}
+
+impl AddressHistoryEntry {
+    pub fn cursor(&self) -> AddressHistoryCursor {
+        AddressHistoryCursor { accepting_daa_score: self.accepting_daa_score, transaction_id: self.transaction_id }
+    }
+}
+
+/// [`AddressHistoryEntry`] as stored in the index, the DAA score and transaction id being part of its key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactAddressHistoryEntry {
+    pub accepting_block_hash: Hash,
+    pub received_amount: u64,
+    pub spent_amount: u64,
+}
+
+impl MemSizeEstimator for CompactAddressHistoryEntry {}
+
+/// Position within the history of an address. History entries are ordered by accepting DAA score and then by transaction id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AddressHistoryCursor {
+    pub accepting_daa_score: u64,
+    pub transaction_id: TransactionId,
+}
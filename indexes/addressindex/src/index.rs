@@ -0,0 +1,366 @@
+use crate::{
+    IDENT,
+    api::AddressIndexApi,
+    errors::{AddressIndexError, AddressIndexResult},
+    model::{AddressHistoryCursor, AddressHistoryEntry},
+    stores::store_manager::Store,
+};
+use kaspa_consensus_core::{
+    api::ConsensusApi,
+    tx::{ScriptPublicKey, TransactionQueryResult, TransactionType},
+};
+use kaspa_consensusmanager::{ConsensusManager, ConsensusResetHandler};
+use kaspa_core::{info, trace};
+use kaspa_database::prelude::{DB, StoreError, StoreResult};
+use kaspa_hashes::Hash;
+use parking_lot::RwLock;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Weak},
+};
+
+const RESYNC_CHUNK_SIZE: usize = 1024; // Amount of chain blocks fetched from consensus per resync batch.
+const PRUNE_CHUNK_SIZE: usize = 1024; // Amount of chain blocks removed from the index per pruning batch.
+
+/// AddressIndex records, per script public key, every accepted transaction funding or spending from it, following the
+/// virtual selected chain down to the consensus retention period root.
+/// Note: The AddressIndex struct by itself is not thread safe, only correct usage of the supplied RwLock via `new` makes it so.
+/// please follow guidelines found in the comments under `addressindex::core::api::AddressIndexApi` for proper thread safety.
+pub struct AddressIndex {
+    consensus_manager: Arc<ConsensusManager>,
+    store: Store,
+    /// A runtime value holding the retention period root the index was last pruned to.
+    retention_period_root: Option<Hash>,
+}
+
+impl AddressIndex {
+    /// Creates a new [`AddressIndex`] within a [`RwLock`]
+    pub fn new(consensus_manager: Arc<ConsensusManager>, db: Arc<DB>) -> AddressIndexResult<Arc<RwLock<Self>>> {
+        let mut addressindex =
+            Self { consensus_manager: consensus_manager.clone(), store: Store::new(db), retention_period_root: None };
+        if !addressindex.is_synced()? {
+            addressindex.resync()?;
+        }
+        let addressindex = Arc::new(RwLock::new(addressindex));
+        consensus_manager
+            .register_consensus_reset_handler(Arc::new(AddressIndexConsensusResetHandler::new(Arc::downgrade(&addressindex))));
+        Ok(addressindex)
+    }
+
+    /// Indexes the transactions accepted by each of the given chain blocks under the script public keys they touch.
+    fn add_chain_blocks(
+        &mut self,
+        consensus: &dyn ConsensusApi,
+        chain_block_hashes: &[Hash],
+        try_reset_on_err: bool,
+    ) -> AddressIndexResult<()> {
+        for &accepting_block_hash in chain_block_hashes.iter() {
+            let accepting_daa_score = consensus.get_header(accepting_block_hash)?.daa_score;
+            let TransactionQueryResult::SignableTransaction(transactions) =
+                consensus.get_transactions_by_accepting_block(accepting_block_hash, None, TransactionType::SignableTransaction)?
+            else {
+                unreachable!("signable transactions were queried")
+            };
+
+            let mut entries = Vec::new();
+            for transaction in transactions.iter() {
+                // Amounts received and spent by each script public key touched by the transaction
+                let mut amounts: HashMap<ScriptPublicKey, (u64, u64)> = HashMap::new();
+                for output in transaction.tx.outputs.iter() {
+                    amounts.entry(output.script_public_key.clone()).or_default().0 += output.value;
+                }
+                for utxo_entry in transaction.entries.iter().flatten() {
+                    amounts.entry(utxo_entry.script_public_key.clone()).or_default().1 += utxo_entry.amount;
+                }
+
+                let transaction_id = transaction.id();
+                entries.extend(amounts.into_iter().map(|(script_public_key, (received_amount, spent_amount))| {
+                    (
+                        script_public_key,
+                        AddressHistoryEntry {
+                            transaction_id,
+                            accepting_block_hash,
+                            accepting_daa_score,
+                            received_amount,
+                            spent_amount,
+                        },
+                    )
+                }));
+            }
+            trace!("[{0}] adding {1} history entries accepted by {2}", IDENT, entries.len(), accepting_block_hash);
+            self.store.add_chain_block(accepting_daa_score, accepting_block_hash, &entries, try_reset_on_err)?;
+        }
+        Ok(())
+    }
+
+    /// Removes all chain blocks below the consensus retention period root from the index, if the root has moved.
+    fn prune(&mut self, consensus: &dyn ConsensusApi, try_reset_on_err: bool) -> AddressIndexResult<()> {
+        let retention_period_root = consensus.get_retention_period_root();
+        if self.retention_period_root == Some(retention_period_root) {
+            return Ok(());
+        }
+
+        let retention_period_root_daa_score = consensus.get_header(retention_period_root)?.daa_score;
+        trace!("[{0}] pruning chain blocks below DAA score {1}", IDENT, retention_period_root_daa_score);
+        loop {
+            let pruned = self.store.prune_chain_blocks_below(retention_period_root_daa_score, PRUNE_CHUNK_SIZE, try_reset_on_err)?;
+            if pruned < PRUNE_CHUNK_SIZE {
+                break;
+            }
+        }
+
+        self.retention_period_root = Some(retention_period_root);
+        Ok(())
+    }
+}
+
+impl AddressIndexApi for AddressIndex {
+    /// Retrieve the history of a script public key from the addressindex db.
+    fn get_history(
+        &self,
+        script_public_key: &ScriptPublicKey,
+        from_daa_score: u64,
+        to_daa_score: u64,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> StoreResult<Vec<AddressHistoryEntry>> {
+        trace!("[{0}] retrieving history of script public key {1:?}", IDENT, script_public_key);
+
+        self.store.get_history(script_public_key, from_daa_score, to_daa_score, cursor, limit)
+    }
+
+    /// Retrieve the chain block the addressindex is synced to.
+    fn get_sink(&self) -> StoreResult<Hash> {
+        trace!("[{0}] retrieving sink", IDENT);
+
+        self.store.get_sink()
+    }
+
+    /// Checks to see if the [AddressIndex] is sync'd. This is done via comparing the addressindex committed sink with the one of the consensus database.
+    ///
+    /// **Note:** Due to sync gaps between the addressindex and consensus, this function is only reliable while consensus is not processing new blocks.
+    fn is_synced(&self) -> AddressIndexResult<bool> {
+        trace!("[{0}] checking sync status...", IDENT);
+
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        match self.store.get_sink() {
+            Ok(addressindex_sink) => {
+                let res = addressindex_sink == session.get_sink();
+                trace!("[{0}] sync status is {1}", IDENT, res);
+                Ok(res)
+            }
+            Err(error) => match error {
+                StoreError::KeyNotFound(_) => {
+                    //Means addressindex sink database is empty i.e. not sync'd.
+                    trace!("[{0}] sync status is {1}", IDENT, false);
+                    Ok(false)
+                }
+                other_store_errors => Err(AddressIndexError::StoreAccessError(other_store_errors)),
+            },
+        }
+    }
+
+    /// Updates the [AddressIndex] via the virtual chain changes supplied:
+    /// 1) Removes the history entries added by the removed chain blocks.
+    /// 2) Adds the history entries of the transactions accepted by the added chain blocks and commits the new sink.
+    /// 3) Prunes chain blocks which fell below the retention period root.
+    fn update(
+        &mut self,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+    ) -> AddressIndexResult<()> {
+        trace!("[{0}] updating...", IDENT);
+        trace!("[{0}] adding {1} chain blocks", IDENT, added_chain_block_hashes.len());
+        trace!("[{0}] removing {1} chain blocks", IDENT, removed_chain_block_hashes.len());
+
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        // A transaction can be accepted both by a removed and by an added chain block. Thus
+        // we must first apply removals and then additions (so it will be re-added in the addition phase)
+        for &removed_chain_block_hash in removed_chain_block_hashes.iter() {
+            let accepting_daa_score = session.get_header(removed_chain_block_hash)?.daa_score;
+            self.store.remove_chain_block(accepting_daa_score, removed_chain_block_hash, false)?;
+        }
+
+        self.add_chain_blocks(&*session, &added_chain_block_hashes, false)?;
+
+        if let Some(&sink) = added_chain_block_hashes.last() {
+            self.store.set_sink(sink, false)?;
+        }
+
+        self.prune(&*session, false)
+    }
+
+    /// Deletes and reinstates the addressindex database, syncing it from scratch via the consensus database.
+    ///
+    /// **Notes:**
+    /// 1) Only chain blocks above the consensus retention period root are indexed.
+    /// 2) resyncing while consensus notifies of virtual chain changes, may result in a corrupted db.
+    fn resync(&mut self) -> AddressIndexResult<()> {
+        info!("Resyncing the addressindex...");
+
+        self.store.delete_all()?;
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let retention_period_root = session.get_retention_period_root();
+        let mut low = retention_period_root;
+        loop {
+            let chain_path = session.get_virtual_chain_from_block(low, Some(RESYNC_CHUNK_SIZE))?;
+            let Some(&last) = chain_path.added.last() else {
+                break;
+            };
+            trace!("[{0}] resyncing with batch of {1} chain blocks from consensus db", IDENT, chain_path.added.len());
+            self.add_chain_blocks(&*session, &chain_path.added, true)?;
+            low = last;
+        }
+
+        // Commit to the remaining stores.
+
+        trace!("[{0}] committing sink {1} from consensus db", IDENT, low);
+        self.store.set_sink(low, true)?;
+        self.retention_period_root = Some(retention_period_root);
+
+        Ok(())
+    }
+}
+
+impl Debug for AddressIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AddressIndex").finish()
+    }
+}
+
+struct AddressIndexConsensusResetHandler {
+    addressindex: Weak<RwLock<AddressIndex>>,
+}
+
+impl AddressIndexConsensusResetHandler {
+    fn new(addressindex: Weak<RwLock<AddressIndex>>) -> Self {
+        Self { addressindex }
+    }
+}
+
+impl ConsensusResetHandler for AddressIndexConsensusResetHandler {
+    fn handle_consensus_reset(&self) {
+        if let Some(addressindex) = self.addressindex.upgrade() {
+            addressindex.write().resync().unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AddressIndex, api::AddressIndexApi, model::AddressHistoryCursor};
+    use kaspa_consensus::{config::Config, consensus::test_consensus::TestConsensus, params::DEVNET_PARAMS};
+    use kaspa_consensus_core::{
+        api::ConsensusApi,
+        tx::{ScriptPublicKey, TransactionQueryResult, TransactionType},
+    };
+    use kaspa_consensusmanager::ConsensusManager;
+    use kaspa_database::create_temp_db;
+    use kaspa_database::prelude::ConnBuilder;
+    use kaspa_hashes::Hash;
+    use std::{collections::HashMap, sync::Arc};
+
+    #[tokio::test]
+    async fn test_addressindex() {
+        kaspa_core::log::try_init_logger("INFO");
+
+        let chain_length = 10;
+
+        // Initialize all components and build a simple chain of blocks
+        let (_addressindex_db_lifetime, addressindex_db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let config = Config::new(DEVNET_PARAMS);
+        let tc = TestConsensus::new(&config);
+        let wait_handles = tc.init();
+
+        let mut chain = vec![config.genesis.hash];
+        for i in 1..=chain_length {
+            let hash = Hash::from_u64_word(i);
+            tc.add_utxo_valid_block_with_parents(hash, vec![*chain.last().unwrap()], vec![]).await.unwrap();
+            chain.push(hash);
+        }
+
+        // Sync addressindex from scratch
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(tc.consensus_clone()));
+        let addressindex = AddressIndex::new(consensus_manager, addressindex_db).unwrap();
+        assert!(addressindex.read().is_synced().expect("expected bool"));
+        assert_eq!(addressindex.read().get_sink().expect("expected sink"), tc.get_sink());
+
+        // Collect the expected history of every script public key touched by the given chain blocks
+        let expected_history = |chain_blocks: &[Hash]| {
+            let mut history: HashMap<ScriptPublicKey, Vec<AddressHistoryCursor>> = HashMap::new();
+            for &accepting_block_hash in chain_blocks {
+                let accepting_daa_score = tc.get_header(accepting_block_hash).unwrap().daa_score;
+                let TransactionQueryResult::SignableTransaction(transactions) =
+                    tc.get_transactions_by_accepting_block(accepting_block_hash, None, TransactionType::SignableTransaction).unwrap()
+                else {
+                    unreachable!()
+                };
+                for transaction in transactions.iter() {
+                    let cursor = AddressHistoryCursor { accepting_daa_score, transaction_id: transaction.id() };
+                    for output in transaction.tx.outputs.iter() {
+                        let entries = history.entry(output.script_public_key.clone()).or_default();
+                        if !entries.contains(&cursor) {
+                            entries.push(cursor);
+                        }
+                    }
+                }
+            }
+            history.values_mut().for_each(|entries| entries.sort());
+            history
+        };
+        let assert_history = |history: &HashMap<ScriptPublicKey, Vec<AddressHistoryCursor>>| {
+            for (script_public_key, expected) in history.iter() {
+                let entries = addressindex.read().get_history(script_public_key, 0, u64::MAX, None, usize::MAX).unwrap();
+                assert_eq!(&entries.iter().map(|entry| entry.cursor()).collect::<Vec<_>>(), expected);
+            }
+        };
+
+        let history = expected_history(&chain[1..]);
+        assert!(history.values().any(|entries| entries.len() > 1));
+        assert_history(&history);
+
+        // Test cursor-based pagination and the DAA score range filter
+        let (script_public_key, expected) = history.iter().max_by_key(|(_, entries)| entries.len()).unwrap();
+        let mut paginated = vec![];
+        let mut cursor = None;
+        loop {
+            let page = addressindex.read().get_history(script_public_key, 0, u64::MAX, cursor, 2).unwrap();
+            let Some(last) = page.last() else { break };
+            cursor = Some(last.cursor());
+            paginated.extend(page.iter().map(|entry| entry.cursor()));
+        }
+        assert_eq!(&paginated, expected);
+
+        let (from, to) = (expected[1].accepting_daa_score, expected[expected.len() - 2].accepting_daa_score);
+        let filtered = addressindex.read().get_history(script_public_key, from, to, None, usize::MAX).unwrap();
+        assert_eq!(
+            filtered.iter().map(|entry| entry.cursor()).collect::<Vec<_>>(),
+            expected.iter().filter(|cursor| (from..=to).contains(&cursor.accepting_daa_score)).copied().collect::<Vec<_>>()
+        );
+
+        // Test update: remove the sink from the chain and add it back
+        let sink = *chain.last().unwrap();
+        addressindex.write().update(Arc::new(vec![sink]), Arc::new(vec![])).expect("expected update");
+        assert_history(&expected_history(&chain[1..chain.len() - 1]));
+
+        addressindex.write().update(Arc::new(vec![]), Arc::new(vec![sink])).expect("expected update");
+        assert_history(&history);
+        assert_eq!(addressindex.read().get_sink().expect("expected sink"), sink);
+
+        // Test if resync restores the same state
+        addressindex.write().resync().expect("expected resync");
+        assert_history(&history);
+        assert!(addressindex.read().is_synced().expect("expected bool"));
+
+        // Deconstruct
+        drop(addressindex);
+        tc.shutdown(wait_handles);
+    }
+}
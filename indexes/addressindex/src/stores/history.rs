@@ -0,0 +1,224 @@
+use crate::core::model::{AddressHistoryCursor, AddressHistoryEntry, CompactAddressHistoryEntry};
+
+use kaspa_consensus_core::tx::{ScriptPublicKey, ScriptPublicKeyVersion, TransactionId};
+use kaspa_database::prelude::{CachePolicy, CachedDbAccess, DB, DirectDbWriter, StoreError, StoreResult};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_hashes::{HASH_SIZE, Hash};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::Arc;
+
+pub const VERSION_TYPE_SIZE: usize = size_of::<ScriptPublicKeyVersion>();
+
+/// [`ScriptPublicKeyBucket`].
+/// Consists of 2 bytes of little endian [ScriptPublicKeyVersion] bytes, followed by the script length (8) and by a variable size of script bytes.
+/// Note: this matches the bucket layout used by the utxoindex.
+#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+struct ScriptPublicKeyBucket(Vec<u8>);
+
+impl From<&ScriptPublicKey> for ScriptPublicKeyBucket {
+    fn from(script_public_key: &ScriptPublicKey) -> Self {
+        // version (2) + length (8) + dynamic script
+        let mut bytes: Vec<u8> = Vec::with_capacity(VERSION_TYPE_SIZE + size_of::<u64>() + script_public_key.script().len());
+        bytes.extend_from_slice(&script_public_key.version().to_le_bytes());
+        bytes.extend_from_slice(&(script_public_key.script().len() as u64).to_le_bytes());
+        bytes.extend_from_slice(script_public_key.script());
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for ScriptPublicKeyBucket {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+// Keys:
+
+/// Size of the [HistoryPositionKey] in bytes.
+pub const HISTORY_POSITION_KEY_SIZE: usize = size_of::<u64>() + HASH_SIZE;
+
+/// Position of an entry within a [ScriptPublicKeyBucket].
+/// Consists of 8 bytes of big endian accepting DAA score, followed by 32 bytes of [TransactionId],
+/// so that iterating a bucket yields its history in ascending DAA score order.
+#[derive(Eq, Hash, PartialEq, Debug, Copy, Clone)]
+struct HistoryPositionKey([u8; HISTORY_POSITION_KEY_SIZE]);
+
+impl From<&AddressHistoryCursor> for HistoryPositionKey {
+    fn from(cursor: &AddressHistoryCursor) -> Self {
+        let mut bytes = [0; HISTORY_POSITION_KEY_SIZE];
+        bytes[..size_of::<u64>()].copy_from_slice(&cursor.accepting_daa_score.to_be_bytes());
+        bytes[size_of::<u64>()..].copy_from_slice(&cursor.transaction_id.as_bytes());
+        Self(bytes)
+    }
+}
+
+impl From<HistoryPositionKey> for AddressHistoryCursor {
+    fn from(key: HistoryPositionKey) -> Self {
+        Self {
+            accepting_daa_score: u64::from_be_bytes(key.0[..size_of::<u64>()].try_into().unwrap()),
+            transaction_id: Hash::from_slice(&key.0[size_of::<u64>()..]),
+        }
+    }
+}
+
+impl AsRef<[u8]> for HistoryPositionKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Full [CompactAddressHistoryEntry] access key.
+/// Consists of variable amount of bytes of [ScriptPublicKeyBucket], and 40 bytes of [HistoryPositionKey]
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Serialize, Deserialize)]
+struct HistoryEntryFullAccessKey(Arc<Vec<u8>>);
+
+impl Display for HistoryEntryFullAccessKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl HistoryEntryFullAccessKey {
+    /// Creates a new [HistoryEntryFullAccessKey] from a [ScriptPublicKeyBucket] and [HistoryPositionKey].
+    pub fn new(script_public_key_bucket: ScriptPublicKeyBucket, history_position_key: HistoryPositionKey) -> Self {
+        let mut bytes = Vec::with_capacity(script_public_key_bucket.as_ref().len() + HISTORY_POSITION_KEY_SIZE);
+        bytes.extend_from_slice(script_public_key_bucket.as_ref());
+        bytes.extend_from_slice(history_position_key.as_ref());
+        Self(Arc::new(bytes))
+    }
+}
+
+impl AsRef<[u8]> for HistoryEntryFullAccessKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+// Traits:
+
+pub trait AddressHistoryStoreReader {
+    /// Get up to `limit` history entries of a [ScriptPublicKey], in ascending (DAA score, transaction id) order.
+    ///
+    /// Only entries accepted within the inclusive `[from_daa_score, to_daa_score]` range and positioned strictly
+    /// after `cursor` (if provided) are returned.
+    fn get_history(
+        &self,
+        script_public_key: &ScriptPublicKey,
+        from_daa_score: u64,
+        to_daa_score: u64,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> StoreResult<Vec<AddressHistoryEntry>>;
+}
+
+pub trait AddressHistoryStore: AddressHistoryStoreReader {
+    /// add [AddressHistoryEntry]s of the given [ScriptPublicKey]s into the [AddressHistoryStore].
+    fn add_entries(&mut self, entries: &[(ScriptPublicKey, AddressHistoryEntry)]) -> StoreResult<()>;
+
+    /// remove the entries at the given positions from the [AddressHistoryStore].
+    fn remove_entries(&mut self, positions: &[(ScriptPublicKey, AddressHistoryCursor)]) -> StoreResult<()>;
+
+    /// removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()>;
+}
+
+// Implementations:
+
+#[derive(Clone)]
+pub struct DbAddressHistoryStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<HistoryEntryFullAccessKey, CompactAddressHistoryEntry>,
+}
+
+impl DbAddressHistoryStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::AddressIndexHistory.into()) }
+    }
+}
+
+impl AddressHistoryStoreReader for DbAddressHistoryStore {
+    fn get_history(
+        &self,
+        script_public_key: &ScriptPublicKey,
+        from_daa_score: u64,
+        to_daa_score: u64,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> StoreResult<Vec<AddressHistoryEntry>> {
+        let script_public_key_bucket = ScriptPublicKeyBucket::from(script_public_key);
+        let start = AddressHistoryCursor { accepting_daa_score: from_daa_score, transaction_id: TransactionId::default() };
+        let start = cursor.map_or(start, |cursor| cursor.max(start));
+        let seek_key = HistoryEntryFullAccessKey::new(script_public_key_bucket.clone(), HistoryPositionKey::from(&start));
+
+        let mut entries = Vec::new();
+        for res in self.access.seek_iterator(Some(script_public_key_bucket.as_ref()), Some(seek_key), usize::MAX, false) {
+            if entries.len() >= limit {
+                break;
+            }
+            let (key, entry) = res.map_err(|err| StoreError::DataInconsistency(err.to_string()))?;
+            let position =
+                AddressHistoryCursor::from(HistoryPositionKey(<[u8; HISTORY_POSITION_KEY_SIZE]>::try_from(&key[..]).unwrap()));
+            // The cursor itself was already returned by a previous query
+            if cursor.is_some_and(|cursor| position <= cursor) {
+                continue;
+            }
+            if position.accepting_daa_score > to_daa_score {
+                break;
+            }
+            entries.push(AddressHistoryEntry {
+                transaction_id: position.transaction_id,
+                accepting_block_hash: entry.accepting_block_hash,
+                accepting_daa_score: position.accepting_daa_score,
+                received_amount: entry.received_amount,
+                spent_amount: entry.spent_amount,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+impl AddressHistoryStore for DbAddressHistoryStore {
+    fn add_entries(&mut self, entries: &[(ScriptPublicKey, AddressHistoryEntry)]) -> StoreResult<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut writer = DirectDbWriter::new(&self.db);
+
+        let mut to_add = entries.iter().map(|(script_public_key, entry)| {
+            (
+                HistoryEntryFullAccessKey::new(
+                    ScriptPublicKeyBucket::from(script_public_key),
+                    HistoryPositionKey::from(&entry.cursor()),
+                ),
+                CompactAddressHistoryEntry {
+                    accepting_block_hash: entry.accepting_block_hash,
+                    received_amount: entry.received_amount,
+                    spent_amount: entry.spent_amount,
+                },
+            )
+        });
+
+        self.access.write_many(&mut writer, &mut to_add)
+    }
+
+    fn remove_entries(&mut self, positions: &[(ScriptPublicKey, AddressHistoryCursor)]) -> StoreResult<()> {
+        if positions.is_empty() {
+            return Ok(());
+        }
+
+        let mut writer = DirectDbWriter::new(&self.db);
+
+        let mut to_remove = positions.iter().map(|(script_public_key, position)| {
+            HistoryEntryFullAccessKey::new(ScriptPublicKeyBucket::from(script_public_key), HistoryPositionKey::from(position))
+        });
+
+        self.access.delete_many(&mut writer, &mut to_remove)
+    }
+
+    /// Removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()> {
+        self.access.delete_all(DirectDbWriter::new(&self.db))
+    }
+}
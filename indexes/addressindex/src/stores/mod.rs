@@ -0,0 +1,2 @@
+mod history;
+pub mod store_manager;
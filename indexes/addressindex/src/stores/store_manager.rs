@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use kaspa_consensus_core::tx::{ScriptPublicKey, TransactionId};
+use kaspa_core::trace;
+use kaspa_database::prelude::{CachePolicy, DB, StoreResult, StoreResultExt};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_hashes::Hash;
+use kaspa_index_core::stores::{
+    accepting_blocks::{AcceptingBlockEntries, AcceptingBlocksStore, AcceptingBlocksStoreReader, DbAcceptingBlocksStore},
+    sink::{DbIndexSinkStore, IndexSinkStore, IndexSinkStoreReader},
+};
+
+use crate::{
+    IDENT,
+    model::{AddressHistoryCursor, AddressHistoryEntry},
+    stores::history::{AddressHistoryStore, AddressHistoryStoreReader, DbAddressHistoryStore},
+};
+
+#[derive(Clone)]
+pub struct Store {
+    sink_store: DbIndexSinkStore,
+    history_store: DbAddressHistoryStore,
+    /// The (script public key, transaction id) pairs added by each chain block
+    accepting_blocks_store: DbAcceptingBlocksStore<(ScriptPublicKey, TransactionId)>,
+}
+
+impl Store {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self {
+            sink_store: DbIndexSinkStore::new(db.clone(), DatabaseStorePrefixes::AddressIndexSink),
+            history_store: DbAddressHistoryStore::new(db.clone(), CachePolicy::Empty),
+            accepting_blocks_store: DbAcceptingBlocksStore::new(
+                db,
+                CachePolicy::Empty,
+                DatabaseStorePrefixes::AddressIndexAcceptingBlocks,
+            ),
+        }
+    }
+
+    pub fn get_history(
+        &self,
+        script_public_key: &ScriptPublicKey,
+        from_daa_score: u64,
+        to_daa_score: u64,
+        cursor: Option<AddressHistoryCursor>,
+        limit: usize,
+    ) -> StoreResult<Vec<AddressHistoryEntry>> {
+        self.history_store.get_history(script_public_key, from_daa_score, to_daa_score, cursor, limit)
+    }
+
+    /// Indexes the history entries added by a chain block.
+    pub fn add_chain_block(
+        &mut self,
+        accepting_daa_score: u64,
+        accepting_block_hash: Hash,
+        entries: &[(ScriptPublicKey, AddressHistoryEntry)],
+        try_reset_on_err: bool,
+    ) -> StoreResult<()> {
+        let accepted_history = AcceptingBlockEntries {
+            accepting_daa_score,
+            accepting_block_hash,
+            entries: Arc::new(
+                entries.iter().map(|(script_public_key, entry)| (script_public_key.clone(), entry.transaction_id)).collect(),
+            ),
+        };
+        // The accepting block record is written first so that a partially applied chain block can always be removed
+        let mut res = self.accepting_blocks_store.insert(&accepted_history);
+        if res.is_ok() {
+            res = self.history_store.add_entries(entries);
+        }
+        if try_reset_on_err && res.is_err() {
+            self.delete_all()?;
+        }
+        res
+    }
+
+    /// Removes the history entries added by a chain block from the index. Returns the number of removed entries.
+    pub fn remove_chain_block(
+        &mut self,
+        accepting_daa_score: u64,
+        accepting_block_hash: Hash,
+        try_reset_on_err: bool,
+    ) -> StoreResult<usize> {
+        let res = self.accepting_blocks_store.get(accepting_daa_score, accepting_block_hash).optional().and_then(|entries| {
+            // A chain block might not be indexed if it was already pruned from the index
+            let Some(entries) = entries else { return Ok(0) };
+            self.history_store.remove_entries(&Self::positions(accepting_daa_score, &entries))?;
+            self.accepting_blocks_store.delete(accepting_daa_score, accepting_block_hash)?;
+            Ok(entries.len())
+        });
+        if try_reset_on_err && res.is_err() {
+            self.delete_all()?;
+        }
+        res
+    }
+
+    /// Removes up to `limit` chain blocks with DAA score lower than `daa_score` from the index.
+    /// Returns the number of removed chain blocks.
+    pub fn prune_chain_blocks_below(&mut self, daa_score: u64, limit: usize, try_reset_on_err: bool) -> StoreResult<usize> {
+        let res = self.accepting_blocks_store.get_below_daa_score(daa_score, limit).and_then(|accepted| {
+            for accepted_history in accepted.iter() {
+                self.history_store
+                    .remove_entries(&Self::positions(accepted_history.accepting_daa_score, &accepted_history.entries))?;
+                self.accepting_blocks_store.delete(accepted_history.accepting_daa_score, accepted_history.accepting_block_hash)?;
+            }
+            Ok(accepted.len())
+        });
+        if try_reset_on_err && res.is_err() {
+            self.delete_all()?;
+        }
+        res
+    }
+
+    fn positions(
+        accepting_daa_score: u64,
+        entries: &[(ScriptPublicKey, TransactionId)],
+    ) -> Vec<(ScriptPublicKey, AddressHistoryCursor)> {
+        entries
+            .iter()
+            .map(|(script_public_key, transaction_id)| {
+                (script_public_key.clone(), AddressHistoryCursor { accepting_daa_score, transaction_id: *transaction_id })
+            })
+            .collect()
+    }
+
+    pub fn get_sink(&self) -> StoreResult<Hash> {
+        self.sink_store.get()
+    }
+
+    pub fn set_sink(&mut self, sink: Hash, try_reset_on_err: bool) -> StoreResult<()> {
+        let res = self.sink_store.set(sink);
+        if try_reset_on_err && res.is_err() {
+            self.delete_all()?;
+        }
+        res
+    }
+
+    /// Resets the addressindex database:
+    pub fn delete_all(&mut self) -> StoreResult<()> {
+        trace!("[{0}] attempting to clear addressindex database...", IDENT);
+
+        // Clear all
+        self.sink_store.remove()?;
+        self.accepting_blocks_store.delete_all()?;
+        self.history_store.delete_all()?;
+
+        trace!("[{0}] clearing addressindex database - success!", IDENT);
+
+        Ok(())
+    }
+}
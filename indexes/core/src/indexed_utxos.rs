@@ -16,6 +16,41 @@ pub type UtxoSetByScriptPublicKey = HashMap<ScriptPublicKey, CompactUtxoCollecti
 /// A map of balance by script public key
 pub type BalanceByScriptPublicKey = HashMap<ScriptPublicKey, u64>;
 
+/// A position within the utxos of a set of script public keys, used for paginating utxo queries.
+///
+/// Utxos are ordered by script public key, following the order of the query, and then by the
+/// byte representation of their outpoint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UtxoCursor {
+    pub script_public_key: ScriptPublicKey,
+    pub outpoint: TransactionOutpoint,
+}
+
+impl UtxoCursor {
+    pub fn new(script_public_key: ScriptPublicKey, outpoint: TransactionOutpoint) -> Self {
+        Self { script_public_key, outpoint }
+    }
+}
+
+/// A page of utxos grouped by [`ScriptPublicKey`], retaining the utxo order described in [`UtxoCursor`].
+#[derive(Clone, Debug, Default)]
+pub struct UtxoPage {
+    pub entries: Vec<(ScriptPublicKey, Vec<(TransactionOutpoint, CompactUtxoEntry)>)>,
+    /// Position of the last returned utxo, set only when more utxos follow it
+    pub next_cursor: Option<UtxoCursor>,
+}
+
+impl UtxoPage {
+    /// Number of utxos in the page
+    pub fn len(&self) -> usize {
+        self.entries.iter().map(|(_, utxos)| utxos.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
 // Note: memory optimization compared to go-lang kaspad:
 // Unlike `consensus_core::tx::UtxoEntry` the utxoindex utilizes a compacted utxo form, where `script_public_key` field is removed.
 // This utxo structure can be utilized in the utxoindex, since utxos are implicitly key'd via its script public key (and outpoint) at all times.
@@ -2,3 +2,5 @@ pub mod connection;
 pub mod indexed_utxos;
 pub mod notification;
 pub mod notifier;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod stores; //stores of chain block data shared by indexes.
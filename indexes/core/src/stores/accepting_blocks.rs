@@ -0,0 +1,129 @@
+use kaspa_database::prelude::{CachePolicy, CachedDbAccess, DB, DirectDbWriter, StoreError, StoreResult};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_hashes::{HASH_SIZE, Hash};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// Size of the [AcceptingBlockKey] in bytes.
+pub const ACCEPTING_BLOCK_KEY_SIZE: usize = size_of::<u64>() + HASH_SIZE;
+
+/// Key of the entries indexed for a chain block.
+/// Consists of 8 bytes of big endian accepting DAA score, followed by 32 bytes of the accepting block hash,
+/// so that iterating the store yields accepting blocks in ascending DAA score order.
+#[derive(Eq, Hash, PartialEq, Debug, Copy, Clone)]
+struct AcceptingBlockKey([u8; ACCEPTING_BLOCK_KEY_SIZE]);
+
+impl AcceptingBlockKey {
+    fn new(accepting_daa_score: u64, accepting_block_hash: Hash) -> Self {
+        let mut bytes = [0; ACCEPTING_BLOCK_KEY_SIZE];
+        bytes[..size_of::<u64>()].copy_from_slice(&accepting_daa_score.to_be_bytes());
+        bytes[size_of::<u64>()..].copy_from_slice(&accepting_block_hash.as_bytes());
+        Self(bytes)
+    }
+
+    fn accepting_daa_score(&self) -> u64 {
+        u64::from_be_bytes(self.0[..size_of::<u64>()].try_into().unwrap())
+    }
+
+    fn accepting_block_hash(&self) -> Hash {
+        Hash::from_slice(&self.0[size_of::<u64>()..])
+    }
+}
+
+impl AsRef<[u8]> for AcceptingBlockKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Display for AcceptingBlockKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.accepting_daa_score(), self.accepting_block_hash())
+    }
+}
+
+/// The entries indexed for a single chain block, identifying what to remove once the block is pruned or
+/// leaves the selected chain.
+pub struct AcceptingBlockEntries<T> {
+    pub accepting_daa_score: u64,
+    pub accepting_block_hash: Hash,
+    pub entries: Arc<Vec<T>>,
+}
+
+// Traits:
+
+pub trait AcceptingBlocksStoreReader<T> {
+    /// Get the entries indexed for the given chain block.
+    fn get(&self, accepting_daa_score: u64, accepting_block_hash: Hash) -> StoreResult<Arc<Vec<T>>>;
+
+    /// Get up to `limit` accepting blocks with a DAA score lower than `daa_score`, in ascending DAA score order.
+    fn get_below_daa_score(&self, daa_score: u64, limit: usize) -> StoreResult<Vec<AcceptingBlockEntries<T>>>;
+}
+
+pub trait AcceptingBlocksStore<T>: AcceptingBlocksStoreReader<T> {
+    fn insert(&mut self, accepting_block_entries: &AcceptingBlockEntries<T>) -> StoreResult<()>;
+
+    fn delete(&mut self, accepting_daa_score: u64, accepting_block_hash: Hash) -> StoreResult<()>;
+
+    /// removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()>;
+}
+
+// Implementations:
+
+/// A DB + cache implementation of the `AcceptingBlocksStore` trait, shared by indexes of chain block data
+/// which differ only by their indexed entry type and DB prefix
+#[derive(Clone)]
+pub struct DbAcceptingBlocksStore<T: Send + Sync> {
+    db: Arc<DB>,
+    access: CachedDbAccess<AcceptingBlockKey, Arc<Vec<T>>>,
+}
+
+impl<T: Send + Sync> DbAcceptingBlocksStore<T> {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy, prefix: DatabaseStorePrefixes) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, prefix.into()) }
+    }
+}
+
+impl<T: Send + Sync + DeserializeOwned> AcceptingBlocksStoreReader<T> for DbAcceptingBlocksStore<T> {
+    fn get(&self, accepting_daa_score: u64, accepting_block_hash: Hash) -> StoreResult<Arc<Vec<T>>> {
+        self.access.read(AcceptingBlockKey::new(accepting_daa_score, accepting_block_hash))
+    }
+
+    fn get_below_daa_score(&self, daa_score: u64, limit: usize) -> StoreResult<Vec<AcceptingBlockEntries<T>>> {
+        let mut accepted = Vec::new();
+        for res in self.access.seek_iterator(None, None, limit, false) {
+            let (key, entries) = res.map_err(|err| StoreError::DataInconsistency(err.to_string()))?;
+            let key = AcceptingBlockKey(<[u8; ACCEPTING_BLOCK_KEY_SIZE]>::try_from(&key[..]).unwrap());
+            if key.accepting_daa_score() >= daa_score {
+                break;
+            }
+            accepted.push(AcceptingBlockEntries {
+                accepting_daa_score: key.accepting_daa_score(),
+                accepting_block_hash: key.accepting_block_hash(),
+                entries,
+            });
+        }
+        Ok(accepted)
+    }
+}
+
+impl<T: Send + Sync + Serialize + DeserializeOwned> AcceptingBlocksStore<T> for DbAcceptingBlocksStore<T> {
+    fn insert(&mut self, accepting_block_entries: &AcceptingBlockEntries<T>) -> StoreResult<()> {
+        self.access.write(
+            DirectDbWriter::new(&self.db),
+            AcceptingBlockKey::new(accepting_block_entries.accepting_daa_score, accepting_block_entries.accepting_block_hash),
+            accepting_block_entries.entries.clone(),
+        )
+    }
+
+    fn delete(&mut self, accepting_daa_score: u64, accepting_block_hash: Hash) -> StoreResult<()> {
+        self.access.delete(DirectDbWriter::new(&self.db), AcceptingBlockKey::new(accepting_daa_score, accepting_block_hash))
+    }
+
+    /// Removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()> {
+        self.access.delete_all(DirectDbWriter::new(&self.db))
+    }
+}
@@ -0,0 +1,2 @@
+pub mod accepting_blocks;
+pub mod sink;
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use kaspa_database::{
+    prelude::{CachedDbItem, DB, DirectDbWriter, StoreError, StoreResult},
+    registry::DatabaseStorePrefixes,
+};
+use kaspa_hashes::Hash;
+
+/// Reader API for `IndexSinkStore`.
+pub trait IndexSinkStoreReader {
+    fn get(&self) -> StoreResult<Hash>;
+}
+
+pub trait IndexSinkStore: IndexSinkStoreReader {
+    fn set(&mut self, sink: Hash) -> StoreResult<()>;
+    fn remove(&mut self) -> Result<(), StoreError>;
+}
+
+/// A DB + cache implementation of `IndexSinkStore` trait, holding the sink an index was last synced to
+#[derive(Clone)]
+pub struct DbIndexSinkStore {
+    db: Arc<DB>,
+    access: CachedDbItem<Hash>,
+}
+
+impl DbIndexSinkStore {
+    pub fn new(db: Arc<DB>, prefix: DatabaseStorePrefixes) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbItem::new(db.clone(), prefix.into()) }
+    }
+}
+
+impl IndexSinkStoreReader for DbIndexSinkStore {
+    fn get(&self) -> StoreResult<Hash> {
+        self.access.read()
+    }
+}
+
+impl IndexSinkStore for DbIndexSinkStore {
+    fn set(&mut self, sink: Hash) -> Result<(), StoreError> {
+        self.access.write(DirectDbWriter::new(&self.db), &sink)
+    }
+
+    fn remove(&mut self) -> Result<(), StoreError> {
+        self.access.remove(DirectDbWriter::new(&self.db))
+    }
+}
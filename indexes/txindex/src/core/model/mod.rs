@@ -0,0 +1,19 @@
+use kaspa_consensus_core::tx::TransactionIndexType;
+use kaspa_hashes::Hash;
+use kaspa_utils::mem_size::MemSizeEstimator;
+use serde::{Deserialize, Serialize};
+
+/// Location of an accepted transaction within the DAG.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxIndexEntry {
+    /// The block whose body contains the transaction
+    pub including_block_hash: Hash,
+    /// The position of the transaction within the including block body
+    pub index_within_block: TransactionIndexType,
+    /// The chain block whose mergeset accepted the transaction
+    pub accepting_block_hash: Hash,
+    /// The DAA score of the accepting chain block
+    pub accepting_daa_score: u64,
+}
+
+impl MemSizeEstimator for TxIndexEntry {}
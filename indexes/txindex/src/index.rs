@@ -0,0 +1,313 @@
+use crate::{
+    IDENT,
+    api::TxIndexApi,
+    errors::{TxIndexError, TxIndexResult},
+    model::TxIndexEntry,
+    stores::store_manager::Store,
+};
+use kaspa_consensus_core::{acceptance_data::AcceptanceData, api::ConsensusApi, tx::TransactionId};
+use kaspa_consensusmanager::{ConsensusManager, ConsensusResetHandler};
+use kaspa_core::{info, trace};
+use kaspa_database::prelude::{DB, StoreError, StoreResult};
+use kaspa_hashes::Hash;
+use parking_lot::RwLock;
+use std::{
+    fmt::Debug,
+    sync::{Arc, Weak},
+};
+
+const RESYNC_CHUNK_SIZE: usize = 1024; // Amount of chain blocks fetched from consensus per resync batch.
+const PRUNE_CHUNK_SIZE: usize = 1024; // Amount of chain blocks removed from the index per pruning batch.
+
+/// TxIndex maps accepted transaction ids to their including and accepting blocks, following the virtual selected chain
+/// down to the consensus retention period root.
+/// Note: The TxIndex struct by itself is not thread safe, only correct usage of the supplied RwLock via `new` makes it so.
+/// please follow guidelines found in the comments under `txindex::core::api::TxIndexApi` for proper thread safety.
+pub struct TxIndex {
+    consensus_manager: Arc<ConsensusManager>,
+    store: Store,
+    /// A runtime value holding the retention period root the index was last pruned to.
+    retention_period_root: Option<Hash>,
+}
+
+impl TxIndex {
+    /// Creates a new [`TxIndex`] within a [`RwLock`]
+    pub fn new(consensus_manager: Arc<ConsensusManager>, db: Arc<DB>) -> TxIndexResult<Arc<RwLock<Self>>> {
+        let mut txindex = Self { consensus_manager: consensus_manager.clone(), store: Store::new(db), retention_period_root: None };
+        if !txindex.is_synced()? {
+            txindex.resync()?;
+        }
+        let txindex = Arc::new(RwLock::new(txindex));
+        consensus_manager.register_consensus_reset_handler(Arc::new(TxIndexConsensusResetHandler::new(Arc::downgrade(&txindex))));
+        Ok(txindex)
+    }
+
+    /// Indexes the transactions accepted by each of the given chain blocks.
+    fn add_chain_blocks(
+        &mut self,
+        consensus: &dyn ConsensusApi,
+        chain_block_hashes: &[Hash],
+        chain_blocks_acceptance_data: &[Arc<AcceptanceData>],
+        try_reset_on_err: bool,
+    ) -> TxIndexResult<()> {
+        for (&accepting_block_hash, acceptance_data) in chain_block_hashes.iter().zip(chain_blocks_acceptance_data.iter()) {
+            let accepting_daa_score = consensus.get_header(accepting_block_hash)?.daa_score;
+            let entries: Vec<(TransactionId, TxIndexEntry)> = acceptance_data
+                .iter()
+                .flat_map(|mergeset_block| {
+                    mergeset_block.accepted_transactions.iter().map(move |accepted_tx| {
+                        (
+                            accepted_tx.transaction_id,
+                            TxIndexEntry {
+                                including_block_hash: mergeset_block.block_hash,
+                                index_within_block: accepted_tx.index_within_block,
+                                accepting_block_hash,
+                                accepting_daa_score,
+                            },
+                        )
+                    })
+                })
+                .collect();
+            trace!("[{0}] adding {1} transactions accepted by {2}", IDENT, entries.len(), accepting_block_hash);
+            self.store.add_chain_block(accepting_daa_score, accepting_block_hash, &entries, try_reset_on_err)?;
+        }
+        Ok(())
+    }
+
+    /// Removes all chain blocks below the consensus retention period root from the index, if the root has moved.
+    fn prune(&mut self, consensus: &dyn ConsensusApi, try_reset_on_err: bool) -> TxIndexResult<()> {
+        let retention_period_root = consensus.get_retention_period_root();
+        if self.retention_period_root == Some(retention_period_root) {
+            return Ok(());
+        }
+
+        let retention_period_root_daa_score = consensus.get_header(retention_period_root)?.daa_score;
+        trace!("[{0}] pruning chain blocks below DAA score {1}", IDENT, retention_period_root_daa_score);
+        loop {
+            let pruned = self.store.prune_chain_blocks_below(retention_period_root_daa_score, PRUNE_CHUNK_SIZE, try_reset_on_err)?;
+            if pruned < PRUNE_CHUNK_SIZE {
+                break;
+            }
+        }
+
+        self.retention_period_root = Some(retention_period_root);
+        Ok(())
+    }
+}
+
+impl TxIndexApi for TxIndex {
+    /// Retrieve the index entry of an accepted transaction from the txindex db.
+    fn get_transaction_entry(&self, transaction_id: TransactionId) -> StoreResult<Option<TxIndexEntry>> {
+        trace!("[{0}] retrieving entry of transaction {1}", IDENT, transaction_id);
+
+        self.store.get_transaction_entry(transaction_id)
+    }
+
+    /// Retrieve the chain block the txindex is synced to.
+    fn get_sink(&self) -> StoreResult<Hash> {
+        trace!("[{0}] retrieving sink", IDENT);
+
+        self.store.get_sink()
+    }
+
+    /// Checks to see if the [TxIndex] is sync'd. This is done via comparing the txindex committed sink with the one of the consensus database.
+    ///
+    /// **Note:** Due to sync gaps between the txindex and consensus, this function is only reliable while consensus is not processing new blocks.
+    fn is_synced(&self) -> TxIndexResult<bool> {
+        trace!("[{0}] checking sync status...", IDENT);
+
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        match self.store.get_sink() {
+            Ok(txindex_sink) => {
+                let res = txindex_sink == session.get_sink();
+                trace!("[{0}] sync status is {1}", IDENT, res);
+                Ok(res)
+            }
+            Err(error) => match error {
+                StoreError::KeyNotFound(_) => {
+                    //Means txindex sink database is empty i.e. not sync'd.
+                    trace!("[{0}] sync status is {1}", IDENT, false);
+                    Ok(false)
+                }
+                other_store_errors => Err(TxIndexError::StoreAccessError(other_store_errors)),
+            },
+        }
+    }
+
+    /// Updates the [TxIndex] via the virtual chain changes supplied:
+    /// 1) Removes the transactions accepted by the removed chain blocks.
+    /// 2) Adds the transactions accepted by the added chain blocks and commits the new sink.
+    /// 3) Prunes chain blocks which fell below the retention period root.
+    fn update(
+        &mut self,
+        removed_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_block_hashes: Arc<Vec<Hash>>,
+        added_chain_blocks_acceptance_data: Arc<Vec<Arc<AcceptanceData>>>,
+    ) -> TxIndexResult<()> {
+        trace!("[{0}] updating...", IDENT);
+        trace!("[{0}] adding {1} chain blocks", IDENT, added_chain_block_hashes.len());
+        trace!("[{0}] removing {1} chain blocks", IDENT, removed_chain_block_hashes.len());
+
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        // A transaction can be accepted both by a removed and by an added chain block. Thus
+        // we must first apply removals and then additions (so it will be re-added in the addition phase)
+        for &removed_chain_block_hash in removed_chain_block_hashes.iter() {
+            let accepting_daa_score = session.get_header(removed_chain_block_hash)?.daa_score;
+            self.store.remove_chain_block(accepting_daa_score, removed_chain_block_hash, false)?;
+        }
+
+        self.add_chain_blocks(&*session, &added_chain_block_hashes, &added_chain_blocks_acceptance_data, false)?;
+
+        if let Some(&sink) = added_chain_block_hashes.last() {
+            self.store.set_sink(sink, false)?;
+        }
+
+        self.prune(&*session, false)
+    }
+
+    /// Deletes and reinstates the txindex database, syncing it from scratch via the consensus database.
+    ///
+    /// **Notes:**
+    /// 1) Only chain blocks above the consensus retention period root are indexed.
+    /// 2) resyncing while consensus notifies of virtual chain changes, may result in a corrupted db.
+    fn resync(&mut self) -> TxIndexResult<()> {
+        info!("Resyncing the txindex...");
+
+        self.store.delete_all()?;
+        let consensus = self.consensus_manager.consensus();
+        let session = futures::executor::block_on(consensus.session_blocking());
+
+        let retention_period_root = session.get_retention_period_root();
+        let mut low = retention_period_root;
+        loop {
+            let chain_path = session.get_virtual_chain_from_block(low, Some(RESYNC_CHUNK_SIZE))?;
+            let Some(&last) = chain_path.added.last() else {
+                break;
+            };
+            trace!("[{0}] resyncing with batch of {1} chain blocks from consensus db", IDENT, chain_path.added.len());
+            let acceptance_data = session.get_blocks_acceptance_data(&chain_path.added, None)?;
+            self.add_chain_blocks(&*session, &chain_path.added, &acceptance_data, true)?;
+            low = last;
+        }
+
+        // Commit to the remaining stores.
+
+        trace!("[{0}] committing sink {1} from consensus db", IDENT, low);
+        self.store.set_sink(low, true)?;
+        self.retention_period_root = Some(retention_period_root);
+
+        Ok(())
+    }
+}
+
+impl Debug for TxIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TxIndex").finish()
+    }
+}
+
+struct TxIndexConsensusResetHandler {
+    txindex: Weak<RwLock<TxIndex>>,
+}
+
+impl TxIndexConsensusResetHandler {
+    fn new(txindex: Weak<RwLock<TxIndex>>) -> Self {
+        Self { txindex }
+    }
+}
+
+impl ConsensusResetHandler for TxIndexConsensusResetHandler {
+    fn handle_consensus_reset(&self) {
+        if let Some(txindex) = self.txindex.upgrade() {
+            txindex.write().resync().unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{TxIndex, api::TxIndexApi};
+    use kaspa_consensus::{config::Config, consensus::test_consensus::TestConsensus, params::DEVNET_PARAMS};
+    use kaspa_consensus_core::api::ConsensusApi;
+    use kaspa_consensusmanager::ConsensusManager;
+    use kaspa_database::create_temp_db;
+    use kaspa_database::prelude::ConnBuilder;
+    use kaspa_hashes::Hash;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_txindex() {
+        kaspa_core::log::try_init_logger("INFO");
+
+        let chain_length = 10;
+
+        // Initialize all components and build a simple chain of blocks
+        let (_txindex_db_lifetime, txindex_db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let config = Config::new(DEVNET_PARAMS);
+        let tc = TestConsensus::new(&config);
+        let wait_handles = tc.init();
+
+        let mut chain = vec![config.genesis.hash];
+        for i in 1..=chain_length {
+            let hash = Hash::from_u64_word(i);
+            tc.add_utxo_valid_block_with_parents(hash, vec![*chain.last().unwrap()], vec![]).await.unwrap();
+            chain.push(hash);
+        }
+
+        // Sync txindex from scratch
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(tc.consensus_clone()));
+        let txindex = TxIndex::new(consensus_manager, txindex_db).unwrap();
+        assert!(txindex.read().is_synced().expect("expected bool"));
+        assert_eq!(txindex.read().get_sink().expect("expected sink"), tc.get_sink());
+
+        let assert_chain_block_indexed = |accepting_block_hash: Hash, indexed: bool| {
+            let accepting_daa_score = tc.get_header(accepting_block_hash).unwrap().daa_score;
+            let acceptance_data = tc.get_block_acceptance_data(accepting_block_hash).unwrap();
+            assert!(acceptance_data.iter().any(|mergeset_block| !mergeset_block.accepted_transactions.is_empty()));
+            for mergeset_block in acceptance_data.iter() {
+                for accepted_tx in mergeset_block.accepted_transactions.iter() {
+                    let entry = txindex.read().get_transaction_entry(accepted_tx.transaction_id).unwrap();
+                    if !indexed {
+                        assert!(entry.is_none());
+                        continue;
+                    }
+                    let entry = entry.expect("expected an indexed transaction");
+                    assert_eq!(entry.including_block_hash, mergeset_block.block_hash);
+                    assert_eq!(entry.index_within_block, accepted_tx.index_within_block);
+                    assert_eq!(entry.accepting_block_hash, accepting_block_hash);
+                    assert_eq!(entry.accepting_daa_score, accepting_daa_score);
+                }
+            }
+        };
+
+        for &chain_block in chain.iter().skip(1) {
+            assert_chain_block_indexed(chain_block, true);
+        }
+
+        // Test update: remove the sink from the chain and add it back
+        let sink = *chain.last().unwrap();
+        txindex.write().update(Arc::new(vec![sink]), Arc::new(vec![]), Arc::new(vec![])).expect("expected update");
+        assert_chain_block_indexed(sink, false);
+        assert_chain_block_indexed(chain[chain.len() - 2], true);
+
+        let sink_acceptance_data = tc.get_block_acceptance_data(sink).unwrap();
+        txindex.write().update(Arc::new(vec![]), Arc::new(vec![sink]), Arc::new(vec![sink_acceptance_data])).expect("expected update");
+        assert_chain_block_indexed(sink, true);
+        assert_eq!(txindex.read().get_sink().expect("expected sink"), sink);
+
+        // Test if resync restores the same state
+        txindex.write().resync().expect("expected resync");
+        for &chain_block in chain.iter().skip(1) {
+            assert_chain_block_indexed(chain_block, true);
+        }
+        assert!(txindex.read().is_synced().expect("expected bool"));
+
+        // Deconstruct
+        drop(txindex);
+        tc.shutdown(wait_handles);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::core::model::TxIndexEntry;
+
+use kaspa_consensus_core::tx::TransactionId;
+use kaspa_database::prelude::{CachePolicy, CachedDbAccess, DB, DirectDbWriter, StoreResult, StoreResultExt};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use std::sync::Arc;
+
+// Traits:
+
+pub trait TxIndexEntriesStoreReader {
+    /// Get the [TxIndexEntry] of an accepted transaction, if indexed.
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<Option<TxIndexEntry>>;
+}
+
+pub trait TxIndexEntriesStore: TxIndexEntriesStoreReader {
+    /// add [TxIndexEntry]s into the [TxIndexEntriesStore].
+    fn add_entries(&mut self, entries: &[(TransactionId, TxIndexEntry)]) -> StoreResult<()>;
+
+    /// remove the entries of the given transaction ids from the [TxIndexEntriesStore].
+    fn remove_entries(&mut self, transaction_ids: &[TransactionId]) -> StoreResult<()>;
+
+    /// removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()>;
+}
+
+// Implementations:
+
+#[derive(Clone)]
+pub struct DbTxIndexEntriesStore {
+    db: Arc<DB>,
+    access: CachedDbAccess<TransactionId, TxIndexEntry>,
+}
+
+impl DbTxIndexEntriesStore {
+    pub fn new(db: Arc<DB>, cache_policy: CachePolicy) -> Self {
+        Self { db: Arc::clone(&db), access: CachedDbAccess::new(db, cache_policy, DatabaseStorePrefixes::TxIndexEntries.into()) }
+    }
+}
+
+impl TxIndexEntriesStoreReader for DbTxIndexEntriesStore {
+    fn get(&self, transaction_id: TransactionId) -> StoreResult<Option<TxIndexEntry>> {
+        self.access.read(transaction_id).optional()
+    }
+}
+
+impl TxIndexEntriesStore for DbTxIndexEntriesStore {
+    fn add_entries(&mut self, entries: &[(TransactionId, TxIndexEntry)]) -> StoreResult<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut writer = DirectDbWriter::new(&self.db);
+        self.access.write_many(&mut writer, &mut entries.iter().copied())
+    }
+
+    fn remove_entries(&mut self, transaction_ids: &[TransactionId]) -> StoreResult<()> {
+        if transaction_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut writer = DirectDbWriter::new(&self.db);
+        self.access.delete_many(&mut writer, &mut transaction_ids.iter().copied())
+    }
+
+    /// Removes all entries in the cache and db, besides prefixes themselves.
+    fn delete_all(&mut self) -> StoreResult<()> {
+        self.access.delete_all(DirectDbWriter::new(&self.db))
+    }
+}
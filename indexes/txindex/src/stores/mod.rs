@@ -0,0 +1,2 @@
+mod entries;
+pub mod store_manager;
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use kaspa_consensus_core::tx::TransactionId;
+use kaspa_core::trace;
+use kaspa_database::prelude::{CachePolicy, DB, StoreResult, StoreResultExt};
+use kaspa_database::registry::DatabaseStorePrefixes;
+use kaspa_hashes::Hash;
+use kaspa_index_core::stores::{
+    accepting_blocks::{AcceptingBlockEntries, AcceptingBlocksStore, AcceptingBlocksStoreReader, DbAcceptingBlocksStore},
+    sink::{DbIndexSinkStore, IndexSinkStore, IndexSinkStoreReader},
+};
+
+use crate::{
+    IDENT,
+    model::TxIndexEntry,
+    stores::entries::{DbTxIndexEntriesStore, TxIndexEntriesStore, TxIndexEntriesStoreReader},
+};
+
+#[derive(Clone)]
+pub struct Store {
+    sink_store: DbIndexSinkStore,
+    entries_store: DbTxIndexEntriesStore,
+    accepting_blocks_store: DbAcceptingBlocksStore<TransactionId>,
+}
+
+impl Store {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self {
+            sink_store: DbIndexSinkStore::new(db.clone(), DatabaseStorePrefixes::TxIndexSink),
+            entries_store: DbTxIndexEntriesStore::new(db.clone(), CachePolicy::Empty),
+            accepting_blocks_store: DbAcceptingBlocksStore::new(db, CachePolicy::Empty, DatabaseStorePrefixes::TxIndexAcceptingBlocks),
+        }
+    }
+
+    pub fn get_transaction_entry(&self, transaction_id: TransactionId) -> StoreResult<Option<TxIndexEntry>> {
+        self.entries_store.get(transaction_id)
+    }
+
+    /// Indexes the transactions accepted by a chain block.
+    pub fn add_chain_block(
+        &mut self,
+        accepting_daa_score: u64,
+        accepting_block_hash: Hash,
+        entries: &[(TransactionId, TxIndexEntry)],
+        try_reset_on_err: bool,
+    ) -> StoreResult<()> {
+        let accepted_transactions = AcceptingBlockEntries {
+            accepting_daa_score,
+            accepting_block_hash,
+            entries: Arc::new(entries.iter().map(|(transaction_id, _)| *transaction_id).collect()),
+        };
+        // The accepting block record is written first so that a partially applied chain block can always be removed
+        let mut res = self.accepting_blocks_store.insert(&accepted_transactions);
+        if res.is_ok() {
+            res = self.entries_store.add_entries(entries);
+        }
+        if try_reset_on_err && res.is_err() {
+            self.delete_all()?;
+        }
+        res
+    }
+
+    /// Removes the transactions accepted by a chain block from the index. Returns the number of removed entries.
+    pub fn remove_chain_block(
+        &mut self,
+        accepting_daa_score: u64,
+        accepting_block_hash: Hash,
+        try_reset_on_err: bool,
+    ) -> StoreResult<usize> {
+        let res = self.accepting_blocks_store.get(accepting_daa_score, accepting_block_hash).optional().and_then(|transaction_ids| {
+            // A chain block might not be indexed if it was already pruned from the index
+            let Some(transaction_ids) = transaction_ids else { return Ok(0) };
+            self.entries_store.remove_entries(&transaction_ids)?;
+            self.accepting_blocks_store.delete(accepting_daa_score, accepting_block_hash)?;
+            Ok(transaction_ids.len())
+        });
+        if try_reset_on_err && res.is_err() {
+            self.delete_all()?;
+        }
+        res
+    }
+
+    /// Removes up to `limit` chain blocks with DAA score lower than `daa_score` from the index.
+    /// Returns the number of removed chain blocks.
+    pub fn prune_chain_blocks_below(&mut self, daa_score: u64, limit: usize, try_reset_on_err: bool) -> StoreResult<usize> {
+        let res = self.accepting_blocks_store.get_below_daa_score(daa_score, limit).and_then(|accepted| {
+            for accepted_transactions in accepted.iter() {
+                self.entries_store.remove_entries(&accepted_transactions.entries)?;
+                self.accepting_blocks_store
+                    .delete(accepted_transactions.accepting_daa_score, accepted_transactions.accepting_block_hash)?;
+            }
+            Ok(accepted.len())
+        });
+        if try_reset_on_err && res.is_err() {
+            self.delete_all()?;
+        }
+        res
+    }
+
+    pub fn get_sink(&self) -> StoreResult<Hash> {
+        self.sink_store.get()
+    }
+
+    pub fn set_sink(&mut self, sink: Hash, try_reset_on_err: bool) -> StoreResult<()> {
+        let res = self.sink_store.set(sink);
+        if try_reset_on_err && res.is_err() {
+            self.delete_all()?;
+        }
+        res
+    }
+
+    /// Resets the txindex database:
+    pub fn delete_all(&mut self) -> StoreResult<()> {
+        trace!("[{0}] attempting to clear txindex database...", IDENT);
+
+        // Clear all
+        self.sink_store.remove()?;
+        self.accepting_blocks_store.delete_all()?;
+        self.entries_store.delete_all()?;
+
+        trace!("[{0}] clearing txindex database - success!", IDENT);
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use kaspa_consensus_core::{
     BlockHashSet,
-    tx::{ScriptPublicKeys, TransactionOutpoint},
+    tx::{ScriptPublicKey, ScriptPublicKeys, TransactionOutpoint},
     utxo::utxo_diff::UtxoDiff,
 };
 use kaspa_consensusmanager::spawn_blocking;
@@ -12,7 +12,7 @@ use std::{collections::HashSet, fmt::Debug, sync::Arc};
 
 use crate::{
     errors::UtxoIndexResult,
-    model::{UtxoChanges, UtxoSetByScriptPublicKey},
+    model::{UtxoChanges, UtxoCursor, UtxoPage, UtxoSetByScriptPublicKey},
 };
 
 ///Utxoindex API targeted at retrieval calls.
@@ -27,6 +27,20 @@ pub trait UtxoIndexApi: Send + Sync + Debug {
     /// Note: Use a read lock when accessing this method
     fn get_utxos_by_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<UtxoSetByScriptPublicKey>;
 
+    /// Retrieve a page of at most `limit` utxos worth at least `min_amount` from the utxoindex db.
+    ///
+    /// Utxos are returned in the order of `script_public_keys`, resuming right after `resume_from` when provided.
+    /// The page yields a cursor to the next page if more utxos follow.
+    ///
+    /// Note: Use a read lock when accessing this method
+    fn get_utxos_page_by_script_public_keys(
+        &self,
+        script_public_keys: &[ScriptPublicKey],
+        resume_from: Option<&UtxoCursor>,
+        min_amount: u64,
+        limit: usize,
+    ) -> StoreResult<UtxoPage>;
+
     fn get_balance_by_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<BalanceByScriptPublicKey>;
 
     // This can have a big memory footprint, so it should be used only for tests.
@@ -74,6 +88,20 @@ impl UtxoIndexProxy {
         spawn_blocking(move || self.inner.read().get_utxos_by_script_public_keys(script_public_keys)).await.unwrap()
     }
 
+    pub async fn get_utxos_page_by_script_public_keys(
+        self,
+        script_public_keys: Vec<ScriptPublicKey>,
+        resume_from: Option<UtxoCursor>,
+        min_amount: u64,
+        limit: usize,
+    ) -> StoreResult<UtxoPage> {
+        spawn_blocking(move || {
+            self.inner.read().get_utxos_page_by_script_public_keys(&script_public_keys, resume_from.as_ref(), min_amount, limit)
+        })
+        .await
+        .unwrap()
+    }
+
     pub async fn get_balance_by_script_public_keys(
         self,
         script_public_keys: ScriptPublicKeys,
@@ -2,11 +2,15 @@ use crate::{
     IDENT,
     api::UtxoIndexApi,
     errors::{UtxoIndexError, UtxoIndexResult},
-    model::{CirculatingSupply, UtxoChanges, UtxoSetByScriptPublicKey},
+    model::{CirculatingSupply, UtxoChanges, UtxoCursor, UtxoPage, UtxoSetByScriptPublicKey},
     stores::store_manager::Store,
     update_container::UtxoIndexChanges,
 };
-use kaspa_consensus_core::{BlockHashSet, tx::ScriptPublicKeys, utxo::utxo_diff::UtxoDiff};
+use kaspa_consensus_core::{
+    BlockHashSet,
+    tx::{ScriptPublicKey, ScriptPublicKeys},
+    utxo::utxo_diff::UtxoDiff,
+};
 use kaspa_consensusmanager::{ConsensusManager, ConsensusResetHandler};
 use kaspa_core::{info, trace};
 use kaspa_database::prelude::{DB, StoreError, StoreResult};
@@ -64,6 +68,19 @@ impl UtxoIndexApi for UtxoIndex {
         self.store.get_utxos_by_script_public_key(script_public_keys)
     }
 
+    /// Retrieve a page of utxos by script public keys from the utxoindex db.
+    fn get_utxos_page_by_script_public_keys(
+        &self,
+        script_public_keys: &[ScriptPublicKey],
+        resume_from: Option<&UtxoCursor>,
+        min_amount: u64,
+        limit: usize,
+    ) -> StoreResult<UtxoPage> {
+        trace!("[{0}] retrieving a page of at most {1} utxos from {2} script public keys", IDENT, limit, script_public_keys.len());
+
+        self.store.get_utxos_page_by_script_public_keys(script_public_keys, resume_from, min_amount, limit)
+    }
+
     /// Retrieve utxos by script public keys from the utxoindex db.
     fn get_balance_by_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<BalanceByScriptPublicKey> {
         trace!("[{0}] retrieving utxos from {1} script public keys", IDENT, script_public_keys.len());
@@ -234,13 +251,18 @@ mod tests {
     };
     use kaspa_consensus_core::{
         api::ConsensusApi,
+        tx::TransactionOutpoint,
         utxo::{utxo_collection::UtxoCollection, utxo_diff::UtxoDiff},
     };
     use kaspa_consensusmanager::ConsensusManager;
     use kaspa_core::info;
     use kaspa_database::create_temp_db;
     use kaspa_database::prelude::ConnBuilder;
-    use std::{collections::HashSet, sync::Arc, time::Instant};
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+        time::Instant,
+    };
 
     /// TODO: use proper Simnet when implemented.
     #[test]
@@ -390,4 +412,81 @@ mod tests {
         drop(utxoindex);
         drop(tc);
     }
+    #[test]
+    fn test_utxoindex_pagination() {
+        kaspa_core::log::try_init_logger("INFO");
+
+        let utxo_collection_size = 1_000;
+        let script_public_key_pool_size = 20;
+        let page_size = 37;
+
+        let mut virtual_change_emulator = VirtualChangeEmulator::new();
+        let (_utxoindex_db_lifetime, utxoindex_db) = create_temp_db!(ConnBuilder::default().with_files_limit(10));
+        let config = Config::new(DEVNET_PARAMS);
+        let tc = Arc::new(TestConsensus::new(&config));
+        let consensus_manager = Arc::new(ConsensusManager::from_consensus(tc.consensus_clone()));
+        let utxoindex = UtxoIndex::new(consensus_manager, utxoindex_db).unwrap();
+
+        virtual_change_emulator.fill_utxo_collection(utxo_collection_size, script_public_key_pool_size);
+        let test_consensus_virtual_state = Arc::new(VirtualState {
+            daa_score: 0,
+            parents: Vec::from_iter(virtual_change_emulator.tips.clone()),
+            utxo_diff: UtxoDiff::new(virtual_change_emulator.utxo_collection.clone(), UtxoCollection::new()),
+            ..Default::default()
+        });
+        tc.virtual_stores.write().utxo_set.write_diff(&test_consensus_virtual_state.utxo_diff).expect("expected write diff");
+        tc.virtual_stores.write().state.set(test_consensus_virtual_state).expect("setting of state");
+        utxoindex.write().resync().expect("expected resync");
+
+        let script_public_keys = virtual_change_emulator.script_public_key_pool.clone();
+        // Utxos of a script public key are ordered by their outpoint as stored in the db key
+        let outpoint_key =
+            |outpoint: &TransactionOutpoint| [outpoint.transaction_id.as_bytes().as_slice(), &outpoint.index.to_le_bytes()].concat();
+        let median_amount = {
+            let mut amounts = virtual_change_emulator.utxo_collection.values().map(|entry| entry.amount).collect::<Vec<_>>();
+            amounts.sort_unstable();
+            amounts[amounts.len() / 2]
+        };
+
+        for min_amount in [0, median_amount] {
+            let expected_utxos = virtual_change_emulator
+                .utxo_collection
+                .iter()
+                .filter(|(_, entry)| entry.amount >= min_amount)
+                .map(|(outpoint, entry)| (*outpoint, entry.script_public_key.clone()))
+                .collect::<HashMap<_, _>>();
+
+            // Walk through all pages and check that every utxo is returned exactly once and in the expected order
+            let mut paged_utxos = HashMap::new();
+            let mut resume_from = None;
+            let mut page_count = 0;
+            loop {
+                let page = utxoindex
+                    .read()
+                    .get_utxos_page_by_script_public_keys(&script_public_keys, resume_from.as_ref(), min_amount, page_size)
+                    .expect("expected a utxo page");
+                page_count += 1;
+                assert!(page.len() <= page_size);
+                for (script_public_key, utxos) in page.entries.iter() {
+                    assert!(utxos.windows(2).all(|w| outpoint_key(&w[0].0) < outpoint_key(&w[1].0)));
+                    for (outpoint, entry) in utxos.iter() {
+                        assert!(entry.amount >= min_amount);
+                        assert!(paged_utxos.insert(*outpoint, script_public_key.clone()).is_none(), "expected no duplicate utxo");
+                    }
+                }
+                match page.next_cursor {
+                    Some(cursor) => {
+                        assert_eq!(page.len(), page_size);
+                        resume_from = Some(cursor);
+                    }
+                    None => break,
+                }
+            }
+            assert_eq!(paged_utxos, expected_utxos);
+            assert_eq!(page_count, expected_utxos.len().div_ceil(page_size).max(1));
+        }
+
+        drop(utxoindex);
+        drop(tc);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::core::model::{CompactUtxoCollection, CompactUtxoEntry, UtxoSetByScriptPublicKey};
+use crate::core::model::{CompactUtxoCollection, CompactUtxoEntry, UtxoCursor, UtxoPage, UtxoSetByScriptPublicKey};
 
 use kaspa_consensus_core::tx::{
     ScriptPublicKey, ScriptPublicKeyVersion, ScriptPublicKeys, ScriptVec, TransactionIndexType, TransactionOutpoint,
@@ -125,6 +125,15 @@ pub trait UtxoSetByScriptPublicKeyStoreReader {
     /// Get [UtxoSetByScriptPublicKey] set by queried [ScriptPublicKeys],
     fn get_utxos_from_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<UtxoSetByScriptPublicKey>;
     fn get_balance_from_script_public_keys(&self, script_public_keys: ScriptPublicKeys) -> StoreResult<BalanceByScriptPublicKey>;
+    /// Get a [UtxoPage] of at most `limit` utxos worth at least `min_amount`, following the order of `script_public_keys`
+    /// and resuming after `resume_from` when provided. The script public key of `resume_from` is expected to be queried.
+    fn get_utxos_page_from_script_public_keys(
+        &self,
+        script_public_keys: &[ScriptPublicKey],
+        resume_from: Option<&UtxoCursor>,
+        min_amount: u64,
+        limit: usize,
+    ) -> StoreResult<UtxoPage>;
     fn get_all_outpoints(&self) -> StoreResult<HashSet<TransactionOutpoint>>; // This can have a big memory footprint, so it should be used only for tests.
 }
 
@@ -197,6 +206,71 @@ impl UtxoSetByScriptPublicKeyStoreReader for DbUtxoSetByScriptPublicKeyStore {
         Ok(balance_by_script_public_keys)
     }
 
+    fn get_utxos_page_from_script_public_keys(
+        &self,
+        script_public_keys: &[ScriptPublicKey],
+        resume_from: Option<&UtxoCursor>,
+        min_amount: u64,
+        limit: usize,
+    ) -> StoreResult<UtxoPage> {
+        let start = match resume_from {
+            Some(cursor) => match script_public_keys.iter().position(|spk| *spk == cursor.script_public_key) {
+                Some(position) => position,
+                None => return Ok(UtxoPage::default()),
+            },
+            None => 0,
+        };
+
+        // Collect one extra entry to find out whether another page follows
+        let mut remaining = limit.saturating_add(1);
+        let mut page = UtxoPage::default();
+        for script_public_key in script_public_keys[start..].iter() {
+            if remaining == 0 {
+                break;
+            }
+            let script_public_key_bucket = ScriptPublicKeyBucket::from(script_public_key);
+            // The seek lands on the cursor entry itself when it is still unspent, so skip it explicitly
+            let resume_from = resume_from.filter(|cursor| cursor.script_public_key == *script_public_key);
+            let seek_from = resume_from.map(|cursor| {
+                UtxoEntryFullAccessKey::new(script_public_key_bucket.clone(), TransactionOutpointKey::from(&cursor.outpoint))
+            });
+            let utxos: Vec<(TransactionOutpoint, CompactUtxoEntry)> = self
+                .access
+                .seek_iterator(Some(script_public_key_bucket.as_ref()), seek_from, usize::MAX, false)
+                .map(|res| {
+                    let (key, entry) = res.unwrap();
+                    (TransactionOutpointKey(<[u8; TRANSACTION_OUTPOINT_KEY_SIZE]>::try_from(&key[..]).unwrap()).into(), entry)
+                })
+                .filter(|(outpoint, entry)| {
+                    entry.amount >= min_amount && resume_from.is_none_or(|cursor| cursor.outpoint != *outpoint)
+                })
+                .take(remaining)
+                .collect();
+            if !utxos.is_empty() {
+                remaining -= utxos.len();
+                page.entries.push((script_public_key.clone(), utxos));
+            }
+        }
+
+        if remaining == 0 {
+            let (_, utxos) = page.entries.last_mut().unwrap();
+            utxos.pop();
+            if utxos.is_empty() {
+                page.entries.pop();
+            }
+            page.next_cursor = page
+                .entries
+                .last()
+                .map(|(script_public_key, utxos)| UtxoCursor::new(script_public_key.clone(), utxos.last().unwrap().0));
+        }
+        debug!(
+            "IDXPRC, Executed a paginated query for the utxo set of {} script public keys yielding {} entries",
+            script_public_keys.len() - start,
+            page.len()
+        );
+        Ok(page)
+    }
+
     // This can have a big memory footprint, so it should be used only for tests.
     fn get_all_outpoints(&self) -> StoreResult<HashSet<TransactionOutpoint>> {
         Ok(HashSet::from_iter(
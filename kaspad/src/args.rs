@@ -4,9 +4,10 @@ use kaspa_consensus_core::{
     network::{NetworkId, NetworkType},
 };
 use kaspa_core::kaspad_env::version;
+use kaspa_mining::build_template_selection_policy;
 use kaspa_notify::address::tracker::Tracker;
 use kaspa_p2p_flows::user_agent_rule::UserAgentRule;
-use kaspa_utils::networking::ContextualNetAddress;
+use kaspa_utils::networking::{ContextualNetAddress, OnionNetAddress};
 use kaspa_wrpc_server::address::WrpcNetAddress;
 use serde::Deserialize;
 use serde_with::{DisplayFromStr, serde_as};
@@ -22,6 +23,9 @@ use kaspa_txscript::pay_to_address_script;
 #[cfg(feature = "devnet-prealloc")]
 use std::sync::Arc;
 
+/// The default SOCKS port of Tor
+const DEFAULT_PROXY_PORT: u16 = 9050;
+
 #[serde_as]
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
@@ -54,7 +58,10 @@ pub struct Args {
     #[serde(rename = "uacomment")]
     pub user_agent_comments: Vec<String>,
     pub ua_rule: Vec<String>,
+    pub template_policy: Vec<String>,
     pub utxoindex: bool,
+    pub txindex: bool,
+    pub addressindex: bool,
     pub reset_db: bool,
     #[serde(rename = "outpeers")]
     pub outbound_target: usize,
@@ -87,10 +94,22 @@ pub struct Args {
     pub prealloc_amount: u64,
 
     pub disable_upnp: bool,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub proxy: Option<ContextualNetAddress>,
+    pub disable_proxy_isolation: bool,
+    #[serde(rename = "onionpeer")]
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    pub onion_peers: Vec<OnionNetAddress>,
     #[serde(rename = "nodnsseed")]
     pub disable_dns_seeding: bool,
     #[serde(rename = "nogrpc")]
     pub disable_grpc: bool,
+    pub persist_mempool: bool,
+    pub persist_orphans: bool,
+    pub export_snapshot: Option<String>,
+    pub import_snapshot: Option<String>,
+    pub ban_threshold: u32,
+    pub ban_duration: u64,
     pub ram_scale: f64,
     pub retention_period_days: Option<f64>,
 
@@ -111,6 +130,8 @@ impl Default for Args {
             unsafe_rpc: false,
             async_threads: num_cpus::get(),
             utxoindex: false,
+            txindex: false,
+            addressindex: false,
             reset_db: false,
             outbound_target: 8,
             inbound_limit: 128,
@@ -133,6 +154,7 @@ impl Default for Args {
             listen: None,
             user_agent_comments: vec![],
             ua_rule: vec![],
+            template_policy: vec![],
             yes: false,
             perf_metrics: false,
             perf_metrics_interval_sec: 10,
@@ -147,8 +169,17 @@ impl Default for Args {
             prealloc_amount: 10_000_000_000,
 
             disable_upnp: false,
+            proxy: None,
+            disable_proxy_isolation: false,
+            onion_peers: vec![],
             disable_dns_seeding: false,
             disable_grpc: false,
+            persist_mempool: false,
+            persist_orphans: false,
+            export_snapshot: None,
+            import_snapshot: None,
+            ban_threshold: 100,
+            ban_duration: 24 * 60 * 60,
             ram_scale: 1.0,
             retention_period_days: None,
             override_params_file: None,
@@ -162,7 +193,11 @@ impl Default for Args {
 impl Args {
     pub fn apply_to_config(&self, config: &mut Config) {
         config.utxoindex = self.utxoindex;
+        config.txindex = self.txindex;
+        config.addressindex = self.addressindex;
         config.disable_upnp = self.disable_upnp;
+        config.proxy = self.proxy.map(|v| v.normalize(DEFAULT_PROXY_PORT));
+        config.proxy_stream_isolation = !self.disable_proxy_isolation;
         config.unsafe_rpc = self.unsafe_rpc;
         config.enable_unsynced_mining = self.enable_unsynced_mining;
         config.enable_mainnet_mining = self.enable_mainnet_mining;
@@ -174,6 +209,8 @@ impl Args {
         config.block_template_cache_lifetime = self.block_template_cache_lifetime;
         config.p2p_listen_address = self.listen.unwrap_or(ContextualNetAddress::unspecified());
         config.externalip = self.externalip.map(|v| v.normalize(config.default_p2p_port()));
+        config.ban_threshold = self.ban_threshold;
+        config.ban_duration = self.ban_duration;
         config.ram_scale = self.ram_scale;
         config.retention_period_days = self.retention_period_days;
 
@@ -346,6 +383,8 @@ pub fn cli() -> Command {
                 .help("Allow mainnet mining (currently enabled by default while the flag is kept for backwards compatibility)"),
         )
         .arg(arg!(--utxoindex "Enable the UTXO index").env("KASPAD_UTXOINDEX"))
+        .arg(arg!(--txindex "Enable the transaction index").env("KASPAD_TXINDEX"))
+        .arg(arg!(--addressindex "Enable the address history index").env("KASPAD_ADDRESSINDEX"))
         .arg(
             Arg::new("max-tracked-addresses")
                 .long("max-tracked-addresses")
@@ -388,6 +427,15 @@ Setting to 0 prevents the preallocation and sets the maximum to {}, leading to 0
                 .require_equals(true)
                 .help("User agent admission rule. Forms: allow;regex:<regex>, reject;regex:<regex>, allow;ver:<name><op><version>, reject;ver:<name><op><version>. Version operators: <, <=, >, >=, ==. Example: --ua-rule='reject;ver:kaspad<1.1.1'. Policy: if allow rules exist and none match, reject; if any reject rule matches, reject; otherwise accept."),
         )
+        .arg(
+            Arg::new("template_policy")
+                .long("template-policy")
+                .env("KASPAD_TEMPLATE_POLICY")
+                .value_name("POLICY")
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .help("Block template transaction selection policy, may be repeated to combine policies. Forms: feerate (default), address-priority=<address>[,<address>...], address-exclude=<address>[,<address>...], subnetwork-quota=<subnetwork id>:<reserved percent>:<max percent>. Example: --template-policy=address-priority=kaspa:qz..."),
+        )
         .arg(
             Arg::new("externalip")
                 .long("externalip")
@@ -408,8 +456,73 @@ Setting to 0 prevents the preallocation and sets the maximum to {}, leading to 0
                 .help("Interval in seconds for performance metrics collection."),
         )
         .arg(arg!(--"disable-upnp" "Disable upnp").env("KASPAD_DISABLE_UPNP"))
+        .arg(
+            Arg::new("proxy")
+                .long("proxy")
+                .env("KASPAD_PROXY")
+                .value_name("IP[:PORT]")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(ContextualNetAddress))
+                .help("Connect to peers through a SOCKS5 proxy, such as Tor (default port: 9050). Required for reaching onion peers. Disables DNS seeding."),
+        )
+        .arg(
+            arg!(--"disable-proxy-isolation" "Use the same proxy credentials for all connections, disabling Tor stream isolation")
+                .env("KASPAD_DISABLE_PROXY_ISOLATION"),
+        )
+        .arg(
+            Arg::new("onion-peers")
+                .long("onion-peer")
+                .env("KASPAD_ONION_PEERS")
+                .value_name("ONION:PORT")
+                .action(ArgAction::Append)
+                .require_equals(true)
+                .value_parser(clap::value_parser!(OnionNetAddress))
+                .help("Add an onion v3 peer address (<address>.onion:<port>) to the address manager. Dialed only through --proxy."),
+        )
         .arg(arg!(--"nodnsseed" "Disable DNS seeding for peers").env("KASPAD_NODNSSEED"))
         .arg(arg!(--"nogrpc" "Disable gRPC server").env("KASPAD_NOGRPC"))
+        .arg(
+            arg!(--"persist-mempool" "Save the mempool on shutdown and revalidate it on the next startup")
+                .env("KASPAD_PERSIST_MEMPOOL"),
+        )
+        .arg(
+            arg!(--"persist-orphans" "Also save the orphan pool when persisting the mempool (requires --persist-mempool)")
+                .env("KASPAD_PERSIST_ORPHANS")
+                .requires("persist-mempool"),
+        )
+        .arg(
+            Arg::new("export-snapshot")
+                .long("export-snapshot")
+                .env("KASPAD_EXPORT_SNAPSHOT")
+                .value_name("FILE")
+                .require_equals(true)
+                .conflicts_with("import-snapshot")
+                .help("Export the pruning point proof, trusted data, SMT state and UTXO set of the node into a checksummed snapshot file, then exit."),
+        )
+        .arg(
+            Arg::new("import-snapshot")
+                .long("import-snapshot")
+                .env("KASPAD_IMPORT_SNAPSHOT")
+                .value_name("FILE")
+                .require_equals(true)
+                .help("Bootstrap the node from a snapshot file created with --export-snapshot, then continue syncing from peers."),
+        )
+        .arg(
+            Arg::new("ban-threshold")
+                .long("ban-threshold")
+                .env("KASPAD_BAN_THRESHOLD")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u32))
+                .help("Misbehavior score at which peers are disconnected and banned. Set to 0 to disable automatic banning (default: 100)."),
+        )
+        .arg(
+            Arg::new("ban-duration")
+                .long("ban-duration")
+                .env("KASPAD_BAN_DURATION")
+                .require_equals(true)
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help("Duration in seconds of automatic bans of misbehaving peers (default: 86400)."),
+        )
         .arg(
             Arg::new("ram-scale")
                 .long("ram-scale")
@@ -522,6 +635,8 @@ impl Args {
             enable_unsynced_mining: arg_match_unwrap_or::<bool>(&m, "enable-unsynced-mining", defaults.enable_unsynced_mining),
             enable_mainnet_mining: arg_match_unwrap_or::<bool>(&m, "enable-mainnet-mining", defaults.enable_mainnet_mining),
             utxoindex: arg_match_unwrap_or::<bool>(&m, "utxoindex", defaults.utxoindex),
+            txindex: arg_match_unwrap_or::<bool>(&m, "txindex", defaults.txindex),
+            addressindex: arg_match_unwrap_or::<bool>(&m, "addressindex", defaults.addressindex),
             testnet: arg_match_unwrap_or::<bool>(&m, "testnet", defaults.testnet),
             testnet_suffix: arg_match_unwrap_or::<u32>(&m, "netsuffix", defaults.testnet_suffix),
             devnet: arg_match_unwrap_or::<bool>(&m, "devnet", defaults.devnet),
@@ -531,14 +646,24 @@ impl Args {
             yes: arg_match_unwrap_or::<bool>(&m, "yes", defaults.yes),
             user_agent_comments: arg_match_many_unwrap_or::<String>(&m, "user_agent_comments", defaults.user_agent_comments),
             ua_rule: arg_match_many_unwrap_or::<String>(&m, "ua_rule", defaults.ua_rule),
+            template_policy: arg_match_many_unwrap_or::<String>(&m, "template_policy", defaults.template_policy),
             externalip: m.get_one::<ContextualNetAddress>("externalip").cloned(),
             perf_metrics: arg_match_unwrap_or::<bool>(&m, "perf-metrics", defaults.perf_metrics),
             perf_metrics_interval_sec: arg_match_unwrap_or::<u64>(&m, "perf-metrics-interval-sec", defaults.perf_metrics_interval_sec),
             // Note: currently used programmatically by benchmarks and not exposed to CLI users
             block_template_cache_lifetime: defaults.block_template_cache_lifetime,
             disable_upnp: arg_match_unwrap_or::<bool>(&m, "disable-upnp", defaults.disable_upnp),
+            proxy: m.get_one::<ContextualNetAddress>("proxy").cloned().or(defaults.proxy),
+            disable_proxy_isolation: arg_match_unwrap_or::<bool>(&m, "disable-proxy-isolation", defaults.disable_proxy_isolation),
+            onion_peers: arg_match_many_unwrap_or::<OnionNetAddress>(&m, "onion-peers", defaults.onion_peers),
             disable_dns_seeding: arg_match_unwrap_or::<bool>(&m, "nodnsseed", defaults.disable_dns_seeding),
             disable_grpc: arg_match_unwrap_or::<bool>(&m, "nogrpc", defaults.disable_grpc),
+            persist_mempool: arg_match_unwrap_or::<bool>(&m, "persist-mempool", defaults.persist_mempool),
+            persist_orphans: arg_match_unwrap_or::<bool>(&m, "persist-orphans", defaults.persist_orphans),
+            export_snapshot: m.get_one::<String>("export-snapshot").cloned().or(defaults.export_snapshot),
+            import_snapshot: m.get_one::<String>("import-snapshot").cloned().or(defaults.import_snapshot),
+            ban_threshold: arg_match_unwrap_or::<u32>(&m, "ban-threshold", defaults.ban_threshold),
+            ban_duration: arg_match_unwrap_or::<u64>(&m, "ban-duration", defaults.ban_duration),
             ram_scale: arg_match_unwrap_or::<f64>(&m, "ram-scale", defaults.ram_scale),
             retention_period_days: m.get_one::<f64>("retention-period-days").cloned().or(defaults.retention_period_days),
 
@@ -559,6 +684,7 @@ impl Args {
         }
 
         validate_ua_rules(&args.ua_rule)?;
+        validate_template_policy(&args.template_policy)?;
 
         Ok(args)
     }
@@ -586,9 +712,17 @@ fn validate_ua_rules(rules: &[String]) -> Result<(), clap::Error> {
     Ok(())
 }
 
+fn validate_template_policy(specs: &[String]) -> Result<(), clap::Error> {
+    build_template_selection_policy(specs).map_err(|err| {
+        clap::Error::raw(clap::error::ErrorKind::ValueValidation, format!("invalid --template-policy: {}", err))
+    })?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::Args;
+    use kaspa_consensus_core::config::{Config, params::MAINNET_PARAMS};
 
     #[test]
     fn parses_ua_rules() {
@@ -603,6 +737,76 @@ mod tests {
 
         assert!(err.to_string().contains("invalid --ua-rule"));
     }
+
+    #[test]
+    fn parses_ban_settings() {
+        let args = Args::parse(["kaspad", "--ban-threshold=0", "--ban-duration=3600"]).unwrap();
+
+        assert_eq!(args.ban_threshold, 0);
+        assert_eq!(args.ban_duration, 3600);
+        assert!(Args::parse(["kaspad", "--ban-duration=0"]).is_err());
+    }
+
+    #[test]
+    fn parses_proxy_settings() {
+        let onion = "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion:16111";
+        let onion_arg = format!("--onion-peer={onion}");
+        let args = Args::parse(["kaspad", "--proxy=127.0.0.1", "--disable-proxy-isolation", onion_arg.as_str()]).unwrap();
+
+        let mut config = Config::new(MAINNET_PARAMS);
+        args.apply_to_config(&mut config);
+        assert_eq!(config.proxy, Some("127.0.0.1:9050".parse().unwrap()));
+        assert!(!config.proxy_stream_isolation);
+        assert_eq!(args.onion_peers, vec![onion.parse().unwrap()]);
+        assert!(Args::parse(["kaspad", "--onion-peer=example.onion:16111"]).is_err());
+    }
+
+    #[test]
+    fn parses_snapshot_settings() {
+        let args = Args::parse(["kaspad", "--export-snapshot=/tmp/kaspa.snapshot"]).unwrap();
+        assert_eq!(args.export_snapshot.as_deref(), Some("/tmp/kaspa.snapshot"));
+        assert_eq!(args.import_snapshot, None);
+
+        let args = Args::parse(["kaspad", "--import-snapshot=/tmp/kaspa.snapshot"]).unwrap();
+        assert_eq!(args.import_snapshot.as_deref(), Some("/tmp/kaspa.snapshot"));
+
+        assert!(Args::parse(["kaspad", "--export-snapshot=a.snapshot", "--import-snapshot=b.snapshot"]).is_err());
+    }
+
+    #[test]
+    fn parses_persist_mempool_settings() {
+        let args = Args::parse(["kaspad", "--persist-mempool", "--persist-orphans"]).unwrap();
+        assert!(args.persist_mempool);
+        assert!(args.persist_orphans);
+
+        assert!(Args::parse(["kaspad", "--persist-orphans"]).is_err());
+    }
+
+    #[test]
+    fn parses_template_policy() {
+        let args = Args::parse([
+            "kaspad",
+            "--template-policy=address-priority=kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j",
+            "--template-policy=subnetwork-quota=0100000000000000000000000000000000000000:5:25",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            args.template_policy,
+            vec![
+                "address-priority=kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j",
+                "subnetwork-quota=0100000000000000000000000000000000000000:5:25"
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_template_policy() {
+        let err = Args::parse(["kaspad", "--template-policy=subnetwork-quota=0100000000000000000000000000000000000000:30:20"])
+            .unwrap_err();
+
+        assert!(err.to_string().contains("invalid --template-policy"));
+    }
 }
 
 /*
@@ -676,6 +880,8 @@ mod tests {
       --maxutxocachesize=                   Max size of loaded UTXO into ram from the disk in bytes (default:
                                             5000000000)
       --utxoindex                           Enable the UTXO index
+      --txindex                             Enable the transaction index
+      --addressindex                        Enable the address history index
       --archival                            Run as an archival node: don't delete old block data when moving the
                                             pruning point (Warning: heavy disk usage)'
       --protocol-version=                   Use non default p2p protocol version (default: 5)
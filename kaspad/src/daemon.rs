@@ -1,6 +1,13 @@
-use std::{fs, path::PathBuf, process::exit, sync::Arc, time::Duration};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+    sync::Arc,
+    time::Duration,
+};
 
 use async_channel::unbounded;
+use kaspa_addressindex::{AddressIndex, api::AddressIndexProxy};
 use kaspa_build_info::git;
 use kaspa_consensus_core::{
     config::ConfigBuilder,
@@ -9,7 +16,7 @@ use kaspa_consensus_core::{
     mining_rules::MiningRules,
 };
 use kaspa_consensus_notify::{root::ConsensusNotificationRoot, service::NotifyService};
-use kaspa_core::{core::Core, debug, info};
+use kaspa_core::{core::Core, debug, error, info};
 use kaspa_core::{kaspad_env::version, task::tick::TickService};
 use kaspa_database::{
     prelude::{CachePolicy, DbWriter, DirectDbWriter, RocksDbPreset},
@@ -17,7 +24,7 @@ use kaspa_database::{
 };
 use kaspa_grpc_server::service::GrpcService;
 use kaspa_notify::{address::tracker::Tracker, subscription::context::SubscriptionContext};
-use kaspa_p2p_lib::Hub;
+use kaspa_p2p_lib::{Hub, Socks5Proxy};
 use kaspa_p2p_mining::rule_engine::MiningRuleEngine;
 use kaspa_rpc_service::service::RpcCoreService;
 use kaspa_system_info::SystemInfo;
@@ -38,13 +45,15 @@ use kaspa_consensusmanager::ConsensusManager;
 use kaspa_core::task::runtime::AsyncRuntime;
 use kaspa_index_processor::service::IndexService;
 use kaspa_mining::{
-    MiningCounters,
+    MiningCounters, build_template_selection_policy,
     manager::{MiningManager, MiningManagerProxy},
     monitor::MiningMonitor,
+    persistence::{MEMPOOL_SNAPSHOT_FILE_NAME, MempoolPersistence},
 };
-use kaspa_p2p_flows::{flow_context::FlowContext, service::P2pService};
+use kaspa_p2p_flows::{flow_context::FlowContext, ibd::export_snapshot, service::P2pService};
 
 use kaspa_perf_monitor::{builder::Builder as PerfMonitorBuilder, counters::CountersSnapshot};
+use kaspa_txindex::{TxIndex, api::TxIndexProxy};
 use kaspa_utxoindex::{UtxoIndex, api::UtxoIndexProxy};
 use kaspa_wrpc_server::service::{Options as WrpcServerOptions, WebSocketCounters as WrpcServerCounters, WrpcEncoding, WrpcService};
 
@@ -67,6 +76,8 @@ use crate::args::Args;
 const DEFAULT_DATA_DIR: &str = "datadir";
 const CONSENSUS_DB: &str = "consensus";
 const UTXOINDEX_DB: &str = "utxoindex";
+const TXINDEX_DB: &str = "txindex";
+const ADDRESSINDEX_DB: &str = "addressindex";
 const META_DB: &str = "meta";
 const META_DB_FILE_LIMIT: i32 = 5;
 const DEFAULT_LOG_DIR: &str = "logs";
@@ -290,6 +301,20 @@ pub fn create_core_with_runtime(runtime: &Runtime, args: &Args, fd_total_budget:
     } else {
         0
     };
+    let tx_files_limit = if args.txindex {
+        let tx_files_limit = fd_remaining / 10;
+        fd_remaining -= tx_files_limit;
+        tx_files_limit
+    } else {
+        0
+    };
+    let address_files_limit = if args.addressindex {
+        let address_files_limit = fd_remaining / 10;
+        fd_remaining -= address_files_limit;
+        address_files_limit
+    } else {
+        0
+    };
 
     // Configure RocksDB parameters
     let (rocksdb_preset, cache_budget, wal_dir) = configure_rocksdb(args);
@@ -347,6 +372,8 @@ pub fn create_core_with_runtime(runtime: &Runtime, args: &Args, fd_total_budget:
 
     let consensus_db_dir = db_dir.join(CONSENSUS_DB);
     let utxoindex_db_dir = db_dir.join(UTXOINDEX_DB);
+    let txindex_db_dir = db_dir.join(TXINDEX_DB);
+    let addressindex_db_dir = db_dir.join(ADDRESSINDEX_DB);
     let meta_db_dir = db_dir.join(META_DB);
 
     let mut is_db_reset_needed = args.reset_db;
@@ -366,6 +393,14 @@ do you confirm? (answer y/n or pass --yes to the Kaspad command line to confirm
         info!("Utxoindex Data directory {}", utxoindex_db_dir.display());
         fs::create_dir_all(utxoindex_db_dir.as_path()).unwrap();
     }
+    if args.txindex {
+        info!("Txindex Data directory {}", txindex_db_dir.display());
+        fs::create_dir_all(txindex_db_dir.as_path()).unwrap();
+    }
+    if args.addressindex {
+        info!("Addressindex Data directory {}", addressindex_db_dir.display());
+        fs::create_dir_all(addressindex_db_dir.as_path()).unwrap();
+    }
 
     if !args.archival
         && let Some(retention_period_days) = args.retention_period_days
@@ -537,6 +572,12 @@ Do you confirm? (y/n)";
         if args.utxoindex {
             fs::create_dir_all(utxoindex_db_dir.as_path()).unwrap();
         }
+        if args.txindex {
+            fs::create_dir_all(txindex_db_dir.as_path()).unwrap();
+        }
+        if args.addressindex {
+            fs::create_dir_all(addressindex_db_dir.as_path()).unwrap();
+        }
 
         // Reopen the DB
         meta_db = kaspa_database::prelude::ConnBuilder::default()
@@ -562,7 +603,12 @@ Do you confirm? (y/n)";
     // connect_peers means no DNS seeding and no outbound/inbound peers
     let outbound_target = if connect_peers.is_empty() { args.outbound_target } else { 0 };
     let inbound_limit = if connect_peers.is_empty() { args.inbound_limit } else { 0 };
-    let dns_seeders = if connect_peers.is_empty() && !args.disable_dns_seeding { config.dns_seeders } else { &[] };
+    // DNS seeders are resolved locally, which would leak the node identity when connecting through a proxy
+    let dns_seeders =
+        if connect_peers.is_empty() && !args.disable_dns_seeding && config.proxy.is_none() { config.dns_seeders } else { &[] };
+    if config.proxy.is_some() && connect_peers.is_empty() && !args.disable_dns_seeding {
+        info!("DNS seeding is disabled when connecting through a proxy, use --addpeer or --onion-peer to provide initial peers");
+    }
 
     let grpc_server_addr = args.rpclisten.unwrap_or(ContextualNetAddress::loopback()).normalize(config.default_rpc_port());
 
@@ -601,6 +647,16 @@ Do you confirm? (y/n)";
         cache_budget,
     ));
     let consensus_manager = Arc::new(ConsensusManager::new(consensus_factory));
+
+    if let Some(snapshot_path) = &args.export_snapshot {
+        // Exporting is a standalone operation performed over the stored consensus state, before any processing starts
+        let session = consensus_manager.consensus().unguarded_session_blocking();
+        if let Err(err) = export_snapshot(&*session, &config, Path::new(snapshot_path)) {
+            error!("Failed exporting a snapshot to {}: {}", snapshot_path, err);
+            exit(1);
+        }
+        exit(0);
+    }
     let consensus_monitor = Arc::new(ConsensusMonitor::new(processing_counters.clone(), tick_service.clone()));
 
     let perf_monitor_builder = PerfMonitorBuilder::new()
@@ -621,37 +677,85 @@ Do you confirm? (y/n)";
     let system_info = SystemInfo::new(git::hash(), git::short_hash(), git::version());
 
     let notify_service = Arc::new(NotifyService::new(notification_root.clone(), notification_recv, subscription_context.clone()));
-    let index_service: Option<Arc<IndexService>> = if args.utxoindex {
+    let index_service: Option<Arc<IndexService>> = if args.utxoindex || args.txindex || args.addressindex {
         // Use only a single thread for none-consensus databases
-        let utxoindex_db = kaspa_database::prelude::ConnBuilder::default()
-            .with_db_path(utxoindex_db_dir)
-            .with_files_limit(utxo_files_limit)
-            .with_preset(rocksdb_preset)
-            .with_wal_dir(wal_dir.clone())
-            .with_cache_budget(cache_budget)
-            .build()
-            .unwrap();
-        let utxoindex = UtxoIndexProxy::new(UtxoIndex::new(consensus_manager.clone(), utxoindex_db).unwrap());
-        let index_service = Arc::new(IndexService::new(&notify_service.notifier(), subscription_context.clone(), Some(utxoindex)));
+        let utxoindex = args.utxoindex.then(|| {
+            let utxoindex_db = kaspa_database::prelude::ConnBuilder::default()
+                .with_db_path(utxoindex_db_dir)
+                .with_files_limit(utxo_files_limit)
+                .with_preset(rocksdb_preset)
+                .with_wal_dir(wal_dir.clone())
+                .with_cache_budget(cache_budget)
+                .build()
+                .unwrap();
+            UtxoIndexProxy::new(UtxoIndex::new(consensus_manager.clone(), utxoindex_db).unwrap())
+        });
+        let txindex = args.txindex.then(|| {
+            let txindex_db = kaspa_database::prelude::ConnBuilder::default()
+                .with_db_path(txindex_db_dir)
+                .with_files_limit(tx_files_limit)
+                .with_preset(rocksdb_preset)
+                .with_wal_dir(wal_dir.clone())
+                .with_cache_budget(cache_budget)
+                .build()
+                .unwrap();
+            TxIndexProxy::new(TxIndex::new(consensus_manager.clone(), txindex_db).unwrap())
+        });
+        let addressindex = args.addressindex.then(|| {
+            let addressindex_db = kaspa_database::prelude::ConnBuilder::default()
+                .with_db_path(addressindex_db_dir)
+                .with_files_limit(address_files_limit)
+                .with_preset(rocksdb_preset)
+                .with_wal_dir(wal_dir.clone())
+                .with_cache_budget(cache_budget)
+                .build()
+                .unwrap();
+            AddressIndexProxy::new(AddressIndex::new(consensus_manager.clone(), addressindex_db).unwrap())
+        });
+        let index_service =
+            Arc::new(IndexService::new(&notify_service.notifier(), subscription_context.clone(), utxoindex, txindex, addressindex));
         Some(index_service)
     } else {
         None
     };
 
     let (address_manager, port_mapping_extender_svc) = AddressManager::new(config.clone(), meta_db, tick_service.clone());
+    for onion_peer in args.onion_peers.iter().copied() {
+        address_manager.lock().add_onion_address(onion_peer);
+    }
+    let p2p_proxy = config.proxy.map(|proxy| Socks5Proxy::new(proxy.into(), config.proxy_stream_isolation));
+    if let Some(proxy) = &p2p_proxy {
+        info!("Routing outbound P2P connections through SOCKS5 proxy {}", proxy.address);
+    }
 
-    let mining_manager = MiningManagerProxy::new(Arc::new(MiningManager::new_with_extended_config(
-        config.target_time_per_block(),
-        false,
-        config.mempool_block_mass_limits(),
-        config.toccata_activation,
-        config.block_lane_limits,
-        config.ram_scale,
-        config.block_template_cache_lifetime,
-        mining_counters.clone(),
-    )));
+    // Validated while parsing the args
+    let template_selection_policy = build_template_selection_policy(&args.template_policy).unwrap();
+    info!("Block template selection policy: {}", template_selection_policy.name());
+    let (mempool_notification_send, mempool_notification_recv) = unbounded();
+    let mining_manager = MiningManagerProxy::new(Arc::new(
+        MiningManager::new_with_extended_config(
+            config.target_time_per_block(),
+            false,
+            config.mempool_block_mass_limits(),
+            config.toccata_activation,
+            config.block_lane_limits,
+            config.ram_scale,
+            config.block_template_cache_lifetime,
+            mining_counters.clone(),
+        )
+        .with_notification_sender(mempool_notification_send)
+        .with_selection_policy(template_selection_policy),
+    ));
     let mining_monitor =
         Arc::new(MiningMonitor::new(mining_manager.clone(), mining_counters, tx_script_cache_counters.clone(), tick_service.clone()));
+    let mempool_persistence = args.persist_mempool.then(|| {
+        Arc::new(MempoolPersistence::new(
+            consensus_manager.clone(),
+            mining_manager.clone(),
+            db_dir.join(MEMPOOL_SNAPSHOT_FILE_NAME),
+            args.persist_orphans,
+        ))
+    });
 
     let hub = Hub::new();
     let mining_rule_engine = Arc::new(MiningRuleEngine::new(
@@ -682,16 +786,21 @@ Do you confirm? (y/n)";
         dns_seeders,
         config.default_p2p_port(),
         p2p_tower_counters.clone(),
+        p2p_proxy,
+        args.import_snapshot.as_ref().map(PathBuf::from),
     ));
 
     let rpc_core_service = Arc::new(RpcCoreService::new(
         consensus_manager.clone(),
         notify_service.notifier(),
-        index_service.as_ref().map(|x| x.notifier()),
+        index_service.as_ref().filter(|x| x.utxoindex().is_some()).map(|x| x.notifier()),
         mining_manager,
+        Some(mempool_notification_recv),
         flow_context,
         subscription_context,
-        index_service.as_ref().map(|x| x.utxoindex().unwrap()),
+        index_service.as_ref().and_then(|x| x.utxoindex()),
+        index_service.as_ref().and_then(|x| x.txindex()),
+        index_service.as_ref().and_then(|x| x.addressindex()),
         config.clone(),
         core.clone(),
         processing_counters,
@@ -734,6 +843,9 @@ Do you confirm? (y/n)";
     async_runtime.register(p2p_service);
     async_runtime.register(consensus_monitor);
     async_runtime.register(mining_monitor);
+    if let Some(mempool_persistence) = mempool_persistence {
+        async_runtime.register(mempool_persistence)
+    }
     async_runtime.register(perf_monitor);
     async_runtime.register(mining_rule_engine);
 
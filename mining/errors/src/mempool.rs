@@ -93,6 +93,21 @@ pub enum RuleError {
 
     #[error("Rejected tx {0} from mempool due to incomputable storage mass")]
     RejectStorageMassIncomputable(TransactionId),
+
+    #[error("transaction package is empty")]
+    RejectEmptyPackage,
+
+    #[error("transaction package of {0} transactions is larger than max allowed size of {1}")]
+    RejectPackageSize(usize, usize),
+
+    #[error("transaction {0} appears more than once in the package")]
+    RejectDuplicateInPackage(TransactionId),
+
+    #[error("output {0} spent by transaction {1} is already spent by another transaction of the package")]
+    RejectDoubleSpendInPackage(TransactionOutpoint, TransactionId),
+
+    #[error("transaction package has {0} fees which is under the required amount of {1} for mass {2}")]
+    RejectInsufficientPackageFee(u64, u64, u64),
 }
 
 impl From<NonStandardError> for RuleError {
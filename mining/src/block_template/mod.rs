@@ -1,3 +1,4 @@
 pub(crate) mod builder;
 pub(crate) mod errors;
 pub(crate) mod policy;
+pub(crate) mod selection;
@@ -0,0 +1,582 @@
+use crate::{
+    Policy,
+    mempool::model::frontier::{feerate_key::FeerateTransactionKey, selectors::TakeAllSelector},
+};
+use kaspa_addresses::Address;
+use kaspa_consensus_core::{
+    block::TemplateTransactionSelector,
+    subnets::SubnetworkId,
+    tx::{MutableTransaction, ScriptPublicKey, Transaction, TransactionId},
+};
+use kaspa_txscript::pay_to_address_script;
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    fmt::Debug,
+    sync::Arc,
+};
+use thiserror::Error;
+
+/// A ready mempool transaction presented to a [`TemplateSelectionPolicy`] for classification
+pub struct SelectionCandidate<'a> {
+    /// The transaction along with its populated UTXO entries
+    pub transaction: &'a MutableTransaction,
+    /// The mass accounted for this transaction in block templates
+    pub mass: u64,
+    /// The fee paid by this transaction
+    pub fee: u64,
+}
+
+impl SelectionCandidate<'_> {
+    pub fn feerate(&self) -> f64 {
+        self.fee as f64 / self.mass as f64
+    }
+
+    /// Returns whether the transaction pays to, or spends from, `script_public_key`
+    pub fn involves_script(&self, script_public_key: &ScriptPublicKey) -> bool {
+        self.transaction.tx.outputs.iter().any(|output| output.script_public_key == *script_public_key)
+            || self.transaction.entries.iter().flatten().any(|entry| entry.script_public_key == *script_public_key)
+    }
+}
+
+/// The way a [`TemplateSelectionPolicy`] wants a candidate to be handled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateVerdict {
+    /// Leave the candidate to the regular feerate-weighted selection
+    Feerate,
+    /// Place the candidate in the template ahead of the feerate-weighted selection
+    Prioritize,
+    /// Never place the candidate in the template
+    Exclude,
+}
+
+/// Block mass shares of a subnetwork lane, expressed in percents of the block mass limit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubnetworkQuota {
+    pub subnetwork_id: SubnetworkId,
+    /// Share of the block mass filled by the best transactions of the lane before any feerate-weighted selection
+    pub reserved_percent: u64,
+    /// Maximum share of the block mass the lane may occupy
+    pub max_percent: u64,
+}
+
+impl SubnetworkQuota {
+    pub fn new(subnetwork_id: SubnetworkId, reserved_percent: u64, max_percent: u64) -> TemplateSelectionPolicyResult<Self> {
+        if max_percent > 100 || reserved_percent > max_percent {
+            return Err(TemplateSelectionPolicyError::InvalidQuota(
+                subnetwork_id.to_string(),
+                "expected 0 <= reserved percent <= max percent <= 100",
+            ));
+        }
+        Ok(Self { subnetwork_id, reserved_percent, max_percent })
+    }
+
+    pub fn reserved_mass(&self, max_block_mass: u64) -> u64 {
+        max_block_mass.saturating_mul(self.reserved_percent) / 100
+    }
+
+    pub fn max_mass(&self, max_block_mass: u64) -> u64 {
+        max_block_mass.saturating_mul(self.max_percent) / 100
+    }
+}
+
+/// A policy consulted by [`crate::manager::MiningManager::get_block_template`] when selecting the
+/// transactions of a block template, on top of the feerate-weighted frontier selection.
+///
+/// Classification requires a full scan of the ready transactions on every template build, so
+/// policies which do not classify candidates should keep [`Self::classifies_candidates`] false.
+pub trait TemplateSelectionPolicy: Debug + Send + Sync {
+    /// A short human-readable description of the policy
+    fn name(&self) -> String;
+
+    /// Whether [`Self::classify`] should be called for every ready transaction
+    fn classifies_candidates(&self) -> bool {
+        false
+    }
+
+    fn classify(&self, _candidate: &SelectionCandidate) -> CandidateVerdict {
+        CandidateVerdict::Feerate
+    }
+
+    /// Mass quotas applied to specific subnetwork lanes
+    fn subnetwork_quotas(&self) -> &[SubnetworkQuota] {
+        &[]
+    }
+}
+
+/// The default policy: transactions are selected purely by feerate
+#[derive(Clone, Debug, Default)]
+pub struct FeeratePolicy;
+
+impl TemplateSelectionPolicy for FeeratePolicy {
+    fn name(&self) -> String {
+        "feerate".to_string()
+    }
+}
+
+/// Prioritizes transactions paying to, or spending from, a list of addresses and excludes
+/// transactions involving another list of addresses
+#[derive(Clone, Debug, Default)]
+pub struct AddressPriorityPolicy {
+    prioritized: Vec<ScriptPublicKey>,
+    excluded: Vec<ScriptPublicKey>,
+}
+
+impl AddressPriorityPolicy {
+    pub fn new(prioritized: &[Address], excluded: &[Address]) -> Self {
+        Self::from_scripts(
+            prioritized.iter().map(pay_to_address_script).collect(),
+            excluded.iter().map(pay_to_address_script).collect(),
+        )
+    }
+
+    pub fn from_scripts(prioritized: Vec<ScriptPublicKey>, excluded: Vec<ScriptPublicKey>) -> Self {
+        Self { prioritized, excluded }
+    }
+}
+
+impl TemplateSelectionPolicy for AddressPriorityPolicy {
+    fn name(&self) -> String {
+        format!("address-priority ({} prioritized, {} excluded scripts)", self.prioritized.len(), self.excluded.len())
+    }
+
+    fn classifies_candidates(&self) -> bool {
+        !self.prioritized.is_empty() || !self.excluded.is_empty()
+    }
+
+    fn classify(&self, candidate: &SelectionCandidate) -> CandidateVerdict {
+        // Exclusion wins over prioritization
+        if self.excluded.iter().any(|script| candidate.involves_script(script)) {
+            CandidateVerdict::Exclude
+        } else if self.prioritized.iter().any(|script| candidate.involves_script(script)) {
+            CandidateVerdict::Prioritize
+        } else {
+            CandidateVerdict::Feerate
+        }
+    }
+}
+
+/// Reserves and caps block mass for specific subnetwork lanes
+#[derive(Clone, Debug, Default)]
+pub struct SubnetworkQuotaPolicy {
+    quotas: Vec<SubnetworkQuota>,
+}
+
+impl SubnetworkQuotaPolicy {
+    pub fn new(quotas: Vec<SubnetworkQuota>) -> TemplateSelectionPolicyResult<Self> {
+        let mut seen = HashSet::with_capacity(quotas.len());
+        if let Some(quota) = quotas.iter().find(|quota| !seen.insert(quota.subnetwork_id)) {
+            return Err(TemplateSelectionPolicyError::DuplicateQuota(quota.subnetwork_id));
+        }
+        Ok(Self { quotas })
+    }
+}
+
+impl TemplateSelectionPolicy for SubnetworkQuotaPolicy {
+    fn name(&self) -> String {
+        format!("subnetwork-quota ({} lanes)", self.quotas.len())
+    }
+
+    fn subnetwork_quotas(&self) -> &[SubnetworkQuota] {
+        &self.quotas
+    }
+}
+
+/// Combines several policies: an exclusion by any policy dominates a prioritization by another
+#[derive(Debug)]
+pub struct CompositeSelectionPolicy {
+    policies: Vec<Arc<dyn TemplateSelectionPolicy>>,
+    quotas: Vec<SubnetworkQuota>,
+}
+
+impl CompositeSelectionPolicy {
+    pub fn new(policies: Vec<Arc<dyn TemplateSelectionPolicy>>) -> TemplateSelectionPolicyResult<Self> {
+        let quotas =
+            SubnetworkQuotaPolicy::new(policies.iter().flat_map(|policy| policy.subnetwork_quotas().iter().cloned()).collect())?
+                .quotas;
+        Ok(Self { policies, quotas })
+    }
+}
+
+impl TemplateSelectionPolicy for CompositeSelectionPolicy {
+    fn name(&self) -> String {
+        self.policies.iter().map(|policy| policy.name()).collect::<Vec<_>>().join(" + ")
+    }
+
+    fn classifies_candidates(&self) -> bool {
+        self.policies.iter().any(|policy| policy.classifies_candidates())
+    }
+
+    fn classify(&self, candidate: &SelectionCandidate) -> CandidateVerdict {
+        let mut verdict = CandidateVerdict::Feerate;
+        for policy in self.policies.iter().filter(|policy| policy.classifies_candidates()) {
+            match policy.classify(candidate) {
+                CandidateVerdict::Exclude => return CandidateVerdict::Exclude,
+                CandidateVerdict::Prioritize => verdict = CandidateVerdict::Prioritize,
+                CandidateVerdict::Feerate => {}
+            }
+        }
+        verdict
+    }
+
+    fn subnetwork_quotas(&self) -> &[SubnetworkQuota] {
+        &self.quotas
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum TemplateSelectionPolicyError {
+    #[error("unknown policy `{0}` (expected feerate, address-priority, address-exclude or subnetwork-quota)")]
+    UnknownPolicy(String),
+
+    #[error("policy `{0}` requires a value")]
+    MissingValue(String),
+
+    #[error("invalid address `{0}`: {1}")]
+    InvalidAddress(String, String),
+
+    #[error("invalid subnetwork quota `{0}`: {1}")]
+    InvalidQuota(String, &'static str),
+
+    #[error("subnetwork {0} has more than one quota")]
+    DuplicateQuota(SubnetworkId),
+}
+
+pub type TemplateSelectionPolicyResult<T> = std::result::Result<T, TemplateSelectionPolicyError>;
+
+/// Builds the policy described by a list of specs, each being one of:
+///
+/// - `feerate`
+/// - `address-priority=<address>[,<address>...]`
+/// - `address-exclude=<address>[,<address>...]`
+/// - `subnetwork-quota=<subnetwork id>:<reserved percent>:<max percent>`
+///
+/// An empty list results in the default [`FeeratePolicy`].
+pub fn build_template_selection_policy(specs: &[String]) -> TemplateSelectionPolicyResult<Arc<dyn TemplateSelectionPolicy>> {
+    let mut prioritized = Vec::new();
+    let mut excluded = Vec::new();
+    let mut quotas = Vec::new();
+    for spec in specs.iter().map(|spec| spec.trim()) {
+        let (kind, value) = match spec.split_once('=') {
+            Some((kind, value)) => (kind.trim(), value.trim()),
+            None => (spec, ""),
+        };
+        match kind {
+            "feerate" => continue,
+            "address-priority" | "address-exclude" | "subnetwork-quota" if value.is_empty() => {
+                return Err(TemplateSelectionPolicyError::MissingValue(kind.to_string()));
+            }
+            "address-priority" => prioritized.extend(parse_addresses(value)?),
+            "address-exclude" => excluded.extend(parse_addresses(value)?),
+            "subnetwork-quota" => quotas.push(parse_quota(value)?),
+            _ => return Err(TemplateSelectionPolicyError::UnknownPolicy(spec.to_string())),
+        }
+    }
+
+    let mut policies: Vec<Arc<dyn TemplateSelectionPolicy>> = Vec::new();
+    if !prioritized.is_empty() || !excluded.is_empty() {
+        policies.push(Arc::new(AddressPriorityPolicy::new(&prioritized, &excluded)));
+    }
+    if !quotas.is_empty() {
+        policies.push(Arc::new(SubnetworkQuotaPolicy::new(quotas)?));
+    }
+    Ok(match policies.len() {
+        0 => Arc::new(FeeratePolicy),
+        1 => policies.pop().unwrap(),
+        _ => Arc::new(CompositeSelectionPolicy::new(policies)?),
+    })
+}
+
+fn parse_addresses(value: &str) -> TemplateSelectionPolicyResult<Vec<Address>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            Address::try_from(address)
+                .map_err(|err| TemplateSelectionPolicyError::InvalidAddress(address.to_string(), err.to_string()))
+        })
+        .collect()
+}
+
+fn parse_quota(value: &str) -> TemplateSelectionPolicyResult<SubnetworkQuota> {
+    let invalid = |reason: &'static str| TemplateSelectionPolicyError::InvalidQuota(value.to_string(), reason);
+    let mut parts = value.split(':').map(str::trim);
+    let (Some(subnetwork_id), Some(reserved_percent), Some(max_percent), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid("expected <subnetwork id>:<reserved percent>:<max percent>"));
+    };
+    let subnetwork_id = subnetwork_id.parse::<SubnetworkId>().map_err(|_| invalid("subnetwork id must be 20 hex-encoded bytes"))?;
+    let reserved_percent = reserved_percent.parse::<u64>().map_err(|_| invalid("reserved percent must be an integer"))?;
+    let max_percent = max_percent.parse::<u64>().map_err(|_| invalid("max percent must be an integer"))?;
+    SubnetworkQuota::new(subnetwork_id, reserved_percent, max_percent).map_err(|_| invalid("expected 0 <= reserved <= max <= 100"))
+}
+
+#[derive(Default)]
+struct LaneUsage {
+    tx_count: usize,
+    gas: u64,
+}
+
+/// Lane, gas and quota occupation of the transactions selected so far
+#[derive(Default)]
+struct TemplateUsage {
+    lanes: HashMap<SubnetworkId, LaneUsage>,
+    quota_mass: HashMap<SubnetworkId, u64>,
+}
+
+impl TemplateUsage {
+    fn try_select(
+        &mut self,
+        policy: &Policy,
+        quota_limits: &HashMap<SubnetworkId, u64>,
+        lane: SubnetworkId,
+        gas: u64,
+        mass: u64,
+    ) -> bool {
+        if let Some(&limit) = quota_limits.get(&lane)
+            && self.quota_mass.get(&lane).copied().unwrap_or_default().saturating_add(mass) > limit
+        {
+            return false;
+        }
+        let occupied_len = self.lanes.len();
+        match self.lanes.entry(lane) {
+            Entry::Occupied(mut entry) => {
+                let usage = entry.get_mut();
+                if usage.gas.saturating_add(gas) > policy.gas_per_lane_limit {
+                    return false;
+                }
+                usage.tx_count += 1;
+                usage.gas += gas;
+            }
+            Entry::Vacant(entry) => {
+                if occupied_len >= policy.lanes_per_block_limit || gas > policy.gas_per_lane_limit {
+                    return false;
+                }
+                entry.insert(LaneUsage { tx_count: 1, gas });
+            }
+        }
+        if quota_limits.contains_key(&lane) {
+            *self.quota_mass.entry(lane).or_default() += mass;
+        }
+        true
+    }
+
+    fn release(&mut self, lane: SubnetworkId, gas: u64, mass: u64) {
+        let usage = self.lanes.get_mut(&lane).expect("previously selected txs occupy a lane");
+        usage.tx_count -= 1;
+        usage.gas -= gas;
+        if usage.tx_count == 0 {
+            self.lanes.remove(&lane);
+        }
+        if let Some(quota_mass) = self.quota_mass.get_mut(&lane) {
+            *quota_mass -= mass;
+        }
+    }
+}
+
+struct PolicySelection {
+    lane: SubnetworkId,
+    gas: u64,
+    mass: u64,
+    /// Whether the transaction was selected by the inner frontier selector
+    from_inner: bool,
+}
+
+/// Collects the prioritized, reserved and excluded transactions of a [`TemplateSelectionPolicy`]
+/// and wraps the frontier selector built for the remaining block mass
+pub(crate) struct PolicySelectorBuilder {
+    policy: Policy,
+    quota_limits: HashMap<SubnetworkId, u64>,
+    usage: TemplateUsage,
+    prioritized: Vec<Arc<Transaction>>,
+    prioritized_mass: u64,
+    selections: HashMap<TransactionId, PolicySelection>,
+    excluded: HashSet<TransactionId>,
+    /// Masses of the frontier transactions in lanes with a quota
+    masses: HashMap<TransactionId, u64>,
+}
+
+impl PolicySelectorBuilder {
+    pub(crate) fn new(policy: &Policy, quotas: &[SubnetworkQuota]) -> Self {
+        Self {
+            policy: policy.clone(),
+            quota_limits: quotas.iter().map(|quota| (quota.subnetwork_id, quota.max_mass(policy.max_block_mass))).collect(),
+            usage: Default::default(),
+            prioritized: Default::default(),
+            prioritized_mass: 0,
+            selections: Default::default(),
+            excluded: Default::default(),
+            masses: Default::default(),
+        }
+    }
+
+    /// Places the transaction ahead of the feerate-weighted selection if it fits the block limits
+    pub(crate) fn prioritize(&mut self, key: &FeerateTransactionKey) -> bool {
+        let id = key.tx.id();
+        if self.selections.contains_key(&id)
+            || self.excluded.contains(&id)
+            || self.prioritized_mass.saturating_add(key.mass) > self.policy.max_block_mass
+            || !self.usage.try_select(&self.policy, &self.quota_limits, key.lane(), key.tx.gas, key.mass)
+        {
+            return false;
+        }
+        self.prioritized_mass += key.mass;
+        self.selections.insert(id, PolicySelection { lane: key.lane(), gas: key.tx.gas, mass: key.mass, from_inner: false });
+        self.prioritized.push(key.tx.clone());
+        true
+    }
+
+    pub(crate) fn exclude(&mut self, id: TransactionId) {
+        self.excluded.insert(id);
+    }
+
+    /// Fills the reserved share of `quota` with the lane transactions, expected in decreasing feerate order
+    pub(crate) fn reserve<'a>(&mut self, quota: &SubnetworkQuota, lane_keys: impl Iterator<Item = &'a FeerateTransactionKey>) {
+        let reserved_mass = quota.reserved_mass(self.policy.max_block_mass);
+        for key in lane_keys {
+            self.masses.insert(key.tx.id(), key.mass);
+            let used = self.usage.quota_mass.get(&quota.subnetwork_id).copied().unwrap_or_default();
+            if used.saturating_add(key.mass) <= reserved_mass {
+                self.prioritize(key);
+            }
+        }
+    }
+
+    /// Builds the wrapping selector, where `build_inner` builds the frontier selector for a given policy
+    pub(crate) fn build(
+        self,
+        build_inner: impl FnOnce(&Policy) -> Box<dyn TemplateTransactionSelector>,
+    ) -> Box<dyn TemplateTransactionSelector> {
+        let remaining_policy = Policy { max_block_mass: self.policy.max_block_mass - self.prioritized_mass, ..self.policy.clone() };
+        let inner = if remaining_policy.max_block_mass == 0 {
+            Box::new(TakeAllSelector::new(Vec::new(), remaining_policy))
+        } else {
+            build_inner(&remaining_policy)
+        };
+        let prioritized_ids = self.prioritized.iter().map(|tx| tx.id()).collect();
+        Box::new(PolicySelector {
+            policy: self.policy,
+            quota_limits: self.quota_limits,
+            usage: self.usage,
+            prioritized: self.prioritized,
+            prioritized_ids,
+            selections: self.selections,
+            excluded: self.excluded,
+            masses: self.masses,
+            inner,
+            consensus_rejections: 0,
+        })
+    }
+}
+
+/// A selector leading its first round with the prioritized transactions, followed by the transactions
+/// of the inner frontier selector which are neither excluded nor over their lane quota
+struct PolicySelector {
+    policy: Policy,
+    quota_limits: HashMap<SubnetworkId, u64>,
+    usage: TemplateUsage,
+    prioritized: Vec<Arc<Transaction>>,
+    prioritized_ids: HashSet<TransactionId>,
+    selections: HashMap<TransactionId, PolicySelection>,
+    excluded: HashSet<TransactionId>,
+    masses: HashMap<TransactionId, u64>,
+    inner: Box<dyn TemplateTransactionSelector>,
+    consensus_rejections: usize,
+}
+
+impl TemplateTransactionSelector for PolicySelector {
+    fn select_transactions(&mut self) -> Vec<Transaction> {
+        // Consensus only asks for further rounds on rejections, so the first round must hold the full template
+        let mut transactions: Vec<Transaction> = self.prioritized.drain(..).map(|tx| tx.as_ref().clone()).collect();
+        loop {
+            let candidates = self.inner.select_transactions();
+            if candidates.is_empty() {
+                return transactions;
+            }
+            let mut skipped = false;
+            for tx in candidates {
+                let id = tx.id();
+                let mass = self.masses.get(&id).copied().unwrap_or_default();
+                if self.prioritized_ids.contains(&id)
+                    || self.excluded.contains(&id)
+                    || !self.usage.try_select(&self.policy, &self.quota_limits, tx.subnetwork_id, tx.gas, mass)
+                {
+                    // Hand the skipped mass back to the inner selector so it can fill it on its next round
+                    self.inner.reject_selection(id);
+                    skipped = true;
+                    continue;
+                }
+                self.selections.insert(id, PolicySelection { lane: tx.subnetwork_id, gas: tx.gas, mass, from_inner: true });
+                transactions.push(tx);
+            }
+            // The inner selector drops all candidates of a round, so refilling the skipped mass always terminates
+            if !skipped {
+                return transactions;
+            }
+        }
+    }
+
+    fn reject_selection(&mut self, tx_id: TransactionId) {
+        let selection = self.selections.remove(&tx_id).expect("only previously selected txs can be rejected (and only once)");
+        self.usage.release(selection.lane, selection.gas, selection.mass);
+        if selection.from_inner {
+            self.inner.reject_selection(tx_id);
+        }
+        self.consensus_rejections += 1;
+    }
+
+    fn is_successful(&self) -> bool {
+        // Policy skips count as rejections of the inner selector, so only consensus rejections may fail the operation
+        self.consensus_rejections == 0 || self.inner.is_successful()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lane(id: u8) -> SubnetworkId {
+        SubnetworkId::from_byte(id)
+    }
+
+    #[test]
+    fn test_parse_template_selection_policy() {
+        let policy = build_template_selection_policy(&[]).unwrap();
+        assert_eq!(policy.name(), "feerate");
+        assert!(!policy.classifies_candidates());
+
+        let specs = ["feerate".to_string(), format!("subnetwork-quota={}:10:40", lane(3))];
+        let policy = build_template_selection_policy(&specs).unwrap();
+        assert!(!policy.classifies_candidates());
+        assert_eq!(policy.subnetwork_quotas(), &[SubnetworkQuota::new(lane(3), 10, 40).unwrap()]);
+
+        let specs = [
+            "address-priority=kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j".to_string(),
+            format!("subnetwork-quota={}:0:20", lane(3)),
+        ];
+        let policy = build_template_selection_policy(&specs).unwrap();
+        assert!(policy.classifies_candidates());
+        assert_eq!(policy.subnetwork_quotas().len(), 1);
+
+        for (spec, expected) in [
+            ("fifo", TemplateSelectionPolicyError::UnknownPolicy("fifo".to_string())),
+            ("address-priority", TemplateSelectionPolicyError::MissingValue("address-priority".to_string())),
+            ("address-exclude=", TemplateSelectionPolicyError::MissingValue("address-exclude".to_string())),
+        ] {
+            assert_eq!(build_template_selection_policy(&[spec.to_string()]).unwrap_err(), expected, "spec `{spec}`");
+        }
+        for spec in [
+            "address-exclude=kaspa:invalid".to_string(),
+            "subnetwork-quota=00:10:20".to_string(),
+            format!("subnetwork-quota={}:30:20", lane(3)),
+            format!("subnetwork-quota={}:10:101", lane(3)),
+            format!("subnetwork-quota={}:10", lane(3)),
+        ] {
+            assert!(build_template_selection_policy(&[spec.clone()]).is_err(), "spec `{spec}` should be rejected");
+        }
+
+        let duplicate = [format!("subnetwork-quota={}:0:20", lane(3)), format!("subnetwork-quota={}:0:30", lane(3))];
+        assert_eq!(build_template_selection_policy(&duplicate).unwrap_err(), TemplateSelectionPolicyError::DuplicateQuota(lane(3)));
+    }
+}
@@ -0,0 +1,280 @@
+//! A fee estimator learning from the actual inclusion of mempool transactions in accepted blocks.
+//!
+//! Unlike [`super::FeerateEstimator`], which models the current mempool weight, this estimator only
+//! looks at what recently happened: the feerates of the mempool transactions which were included in
+//! blocks and the time each of them waited in the mempool. It therefore adapts to sudden changes of
+//! the mempool (a spike or a drain) as fast as blocks reflect them.
+//!
+//! Transactions which left the mempool without being included (expired or evicted) count as failures
+//! for every target, and so do the transactions still waiting in the mempool for longer than a target.
+
+use super::Feerate;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Inclusion time targets (in seconds) reported by default
+pub const DEFAULT_INCLUSION_TARGETS_SECONDS: [f64; 5] = [1.0, 10.0, 60.0, 600.0, 3600.0];
+
+/// Inclusion confidences reported by default
+pub const DEFAULT_INCLUSION_CONFIDENCES: [f64; 3] = [0.5, 0.8, 0.95];
+
+/// Observations older than this window (expressed in seconds of DAA score) are discarded
+const OBSERVATION_WINDOW_SECONDS: u64 = 2 * 3600;
+
+/// Upper bound of the number of kept observations
+const MAX_OBSERVATIONS: usize = 100_000;
+
+/// Feerates are grouped in buckets growing exponentially by this factor
+const BUCKET_SPACING: f64 = 1.1;
+
+/// Minimum number of observations required to evaluate a group of buckets
+const MIN_GROUP_SAMPLES: usize = 16;
+
+#[derive(Clone, Copy, Debug)]
+struct InclusionObservation {
+    /// DAA score at which the transaction was included or dropped
+    daa_score: u64,
+    feerate: Feerate,
+    /// Time the transaction spent in the mempool before inclusion or removal
+    waited_seconds: f64,
+    /// Whether the transaction was included, rather than expired or evicted
+    included: bool,
+}
+
+/// A transaction still waiting in the mempool
+#[derive(Clone, Copy, Debug)]
+pub struct PendingInclusion {
+    pub added_at_daa_score: u64,
+    pub feerate: Feerate,
+}
+
+/// The feerate required for inclusion within `target_seconds` at a given confidence
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InclusionFeerateEstimate {
+    pub target_seconds: f64,
+    /// The probability, in the `[0, 1]` range, of an inclusion within `target_seconds`
+    pub confidence: f64,
+    /// The lowest observed feerate reaching the target at the requested confidence, or `None` if
+    /// there are not enough observations
+    pub feerate: Option<Feerate>,
+}
+
+#[derive(Default)]
+struct BucketStats {
+    count: usize,
+    in_time: usize,
+    min_feerate: Feerate,
+}
+
+#[derive(Clone, Debug)]
+pub struct InclusionFeerateEstimator {
+    observations: VecDeque<InclusionObservation>,
+    network_blocks_per_second: u64,
+}
+
+impl InclusionFeerateEstimator {
+    pub fn new(network_blocks_per_second: u64) -> Self {
+        Self { observations: VecDeque::new(), network_blocks_per_second: network_blocks_per_second.max(1) }
+    }
+
+    /// Records the inclusion of a transaction which entered the mempool at `added_at_daa_score`
+    pub fn record(&mut self, block_daa_score: u64, added_at_daa_score: u64, feerate: Feerate) {
+        self.observe(block_daa_score, added_at_daa_score, feerate, true);
+    }
+
+    /// Records a transaction which entered the mempool at `added_at_daa_score` and left it at `daa_score`
+    /// without being included
+    pub fn record_failure(&mut self, daa_score: u64, added_at_daa_score: u64, feerate: Feerate) {
+        self.observe(daa_score, added_at_daa_score, feerate, false);
+    }
+
+    fn observe(&mut self, daa_score: u64, added_at_daa_score: u64, feerate: Feerate, included: bool) {
+        if !feerate.is_finite() || feerate <= 0.0 {
+            return;
+        }
+        let waited_seconds = self.waited_seconds(added_at_daa_score, daa_score);
+        self.observations.push_back(InclusionObservation { daa_score, feerate, waited_seconds, included });
+
+        let window = OBSERVATION_WINDOW_SECONDS * self.network_blocks_per_second;
+        while self.observations.len() > MAX_OBSERVATIONS
+            || self.observations.front().is_some_and(|oldest| oldest.daa_score.saturating_add(window) < daa_score)
+        {
+            self.observations.pop_front();
+        }
+    }
+
+    fn waited_seconds(&self, from_daa_score: u64, to_daa_score: u64) -> f64 {
+        to_daa_score.saturating_sub(from_daa_score) as f64 / self.network_blocks_per_second as f64
+    }
+
+    pub fn observation_count(&self) -> usize {
+        self.observations.len()
+    }
+
+    /// Returns the feerate needed for an inclusion within `target_seconds` with probability `confidence`,
+    /// given the transactions still `pending` in the mempool at `virtual_daa_score`
+    pub fn estimate(
+        &self,
+        target_seconds: f64,
+        confidence: f64,
+        virtual_daa_score: u64,
+        pending: &[PendingInclusion],
+    ) -> Option<Feerate> {
+        Self::estimate_from_buckets(&self.bucket_stats(target_seconds, virtual_daa_score, pending), confidence)
+    }
+
+    /// Returns an estimate for every combination of `targets_seconds` and `confidences`
+    pub fn estimations(
+        &self,
+        targets_seconds: &[f64],
+        confidences: &[f64],
+        virtual_daa_score: u64,
+        pending: &[PendingInclusion],
+    ) -> Vec<InclusionFeerateEstimate> {
+        targets_seconds
+            .iter()
+            .flat_map(|&target_seconds| {
+                let buckets = self.bucket_stats(target_seconds, virtual_daa_score, pending);
+                confidences.iter().map(move |&confidence| InclusionFeerateEstimate {
+                    target_seconds,
+                    confidence,
+                    feerate: Self::estimate_from_buckets(&buckets, confidence),
+                })
+            })
+            .collect()
+    }
+
+    fn bucket_stats(&self, target_seconds: f64, virtual_daa_score: u64, pending: &[PendingInclusion]) -> BTreeMap<i64, BucketStats> {
+        let mut buckets = BTreeMap::<i64, BucketStats>::new();
+        let mut add = |feerate: Feerate, in_time: bool| {
+            let index = (feerate.ln() / BUCKET_SPACING.ln()).floor() as i64;
+            let stats = buckets.entry(index).or_insert_with(|| BucketStats { min_feerate: Feerate::INFINITY, ..Default::default() });
+            stats.count += 1;
+            if in_time {
+                stats.in_time += 1;
+            }
+            stats.min_feerate = stats.min_feerate.min(feerate);
+        };
+        for observation in self.observations.iter() {
+            add(observation.feerate, observation.included && observation.waited_seconds <= target_seconds);
+        }
+        // Transactions still waiting past the target already missed it, the others are yet undecided
+        for transaction in pending.iter().filter(|transaction| transaction.feerate.is_finite() && transaction.feerate > 0.0) {
+            if self.waited_seconds(transaction.added_at_daa_score, virtual_daa_score) > target_seconds {
+                add(transaction.feerate, false);
+            }
+        }
+        buckets
+    }
+
+    /// Walks the buckets from the highest feerate down, merging them into groups of at least
+    /// [`MIN_GROUP_SAMPLES`] observations, and stops at the first group failing the confidence.
+    /// The lowest feerate of the last successful group is the estimate.
+    fn estimate_from_buckets(buckets: &BTreeMap<i64, BucketStats>, confidence: f64) -> Option<Feerate> {
+        let mut estimate = None;
+        let (mut count, mut in_time, mut min_feerate) = (0, 0, Feerate::INFINITY);
+        for stats in buckets.values().rev() {
+            count += stats.count;
+            in_time += stats.in_time;
+            min_feerate = min_feerate.min(stats.min_feerate);
+            if count < MIN_GROUP_SAMPLES {
+                continue;
+            }
+            if (in_time as f64) < confidence * count as f64 {
+                break;
+            }
+            estimate = Some(min_feerate);
+            (count, in_time, min_feerate) = (0, 0, Feerate::INFINITY);
+        }
+        estimate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BPS: u64 = 10;
+
+    /// Records `count` inclusions of transactions paying `feerate` which waited `waited_seconds`
+    fn record(estimator: &mut InclusionFeerateEstimator, block_daa_score: u64, count: usize, feerate: f64, waited_seconds: u64) {
+        for _ in 0..count {
+            estimator.record(block_daa_score, block_daa_score - waited_seconds * BPS, feerate);
+        }
+    }
+
+    #[test]
+    fn test_inclusion_estimations() {
+        let mut estimator = InclusionFeerateEstimator::new(BPS);
+        assert_eq!(estimator.estimate(60.0, 0.5, 0, &[]), None, "no estimate without observations");
+
+        let daa_score = 1_000_000;
+        // High payers get in within a second, medium ones within a minute and low ones within an hour
+        record(&mut estimator, daa_score, 50, 10.0, 0);
+        record(&mut estimator, daa_score, 50, 3.0, 30);
+        record(&mut estimator, daa_score, 50, 1.0, 1800);
+        assert_eq!(estimator.observation_count(), 150);
+
+        assert_eq!(estimator.estimate(1.0, 0.95, 0, &[]), Some(10.0));
+        assert_eq!(estimator.estimate(60.0, 0.95, 0, &[]), Some(3.0));
+        assert_eq!(estimator.estimate(3600.0, 0.95, 0, &[]), Some(1.0));
+
+        // Groups are evaluated on their own, so late medium payers do not benefit from the high payers' success
+        assert_eq!(estimator.estimate(1.0, 0.5, 0, &[]), Some(10.0));
+        let estimations = estimator.estimations(&DEFAULT_INCLUSION_TARGETS_SECONDS, &DEFAULT_INCLUSION_CONFIDENCES, daa_score, &[]);
+        assert_eq!(estimations.len(), DEFAULT_INCLUSION_TARGETS_SECONDS.len() * DEFAULT_INCLUSION_CONFIDENCES.len());
+        for estimation in estimations {
+            assert!(estimation.feerate.is_some(), "{estimation:?}");
+        }
+    }
+
+    #[test]
+    fn test_inclusion_estimations_require_enough_samples() {
+        let mut estimator = InclusionFeerateEstimator::new(BPS);
+        record(&mut estimator, 1_000, MIN_GROUP_SAMPLES - 1, 5.0, 0);
+        assert_eq!(estimator.estimate(1.0, 0.5, 0, &[]), None);
+        record(&mut estimator, 1_000, 1, 5.0, 0);
+        assert_eq!(estimator.estimate(1.0, 0.5, 0, &[]), Some(5.0));
+    }
+
+    #[test]
+    fn test_inclusion_observations_expire() {
+        let mut estimator = InclusionFeerateEstimator::new(BPS);
+        let daa_score = 1_000_000;
+        record(&mut estimator, daa_score, 20, 5.0, 0);
+        // The mempool drained: cheap transactions now get in immediately
+        let later = daa_score + OBSERVATION_WINDOW_SECONDS * BPS + 1;
+        record(&mut estimator, later, 20, 1.0, 0);
+        assert_eq!(estimator.observation_count(), 20, "observations out of the window should be discarded");
+        assert_eq!(estimator.estimate(1.0, 0.95, 0, &[]), Some(1.0));
+    }
+
+    #[test]
+    fn test_inclusion_failures() {
+        let mut estimator = InclusionFeerateEstimator::new(BPS);
+        let daa_score = 1_000_000;
+        record(&mut estimator, daa_score, 20, 5.0, 0);
+        record(&mut estimator, daa_score, 20, 2.0, 0);
+        assert_eq!(estimator.estimate(1.0, 0.8, daa_score, &[]), Some(2.0));
+
+        // Most of the cheap transactions were evicted or expired rather than included
+        for _ in 0..40 {
+            estimator.record_failure(daa_score, daa_score - 60 * BPS, 2.0);
+        }
+        assert_eq!(estimator.observation_count(), 80);
+        assert_eq!(estimator.estimate(1.0, 0.8, daa_score, &[]), Some(5.0));
+        assert_eq!(estimator.estimate(3600.0, 0.8, daa_score, &[]), Some(5.0), "failures miss every target");
+    }
+
+    #[test]
+    fn test_inclusion_pending_transactions() {
+        let mut estimator = InclusionFeerateEstimator::new(BPS);
+        let daa_score = 1_000_000;
+        record(&mut estimator, daa_score, 20, 5.0, 0);
+        record(&mut estimator, daa_score, 20, 2.0, 0);
+
+        // Cheap transactions stuck for a minute count against the shorter targets only
+        let pending = vec![PendingInclusion { added_at_daa_score: daa_score - 60 * BPS, feerate: 2.0 }; 40];
+        assert_eq!(estimator.estimate(10.0, 0.8, daa_score, &pending), Some(5.0));
+        assert_eq!(estimator.estimate(600.0, 0.8, daa_score, &pending), Some(2.0));
+    }
+}
@@ -2,9 +2,12 @@
 //! behind this fee estimator.
 
 use crate::mempool::model::frontier::selectors::ALPHA;
+use inclusion::InclusionFeerateEstimate;
 use itertools::Itertools;
 use std::fmt::Display;
 
+pub mod inclusion;
+
 /// A type representing fee/mass of a transaction in `sompi/gram` units.
 /// Given a feerate value recommendation, calculate the required fee by
 /// taking the transaction mass and multiplying it by feerate: `fee = feerate * mass(tx)`
@@ -173,6 +176,11 @@ pub struct FeeEstimateVerbose {
     pub next_block_template_feerate_min: f64,
     pub next_block_template_feerate_median: f64,
     pub next_block_template_feerate_max: f64,
+
+    /// Number of recent block inclusions of mempool transactions backing `inclusion_estimations`
+    pub inclusion_observation_count: u64,
+    /// Feerates needed for inclusion within given times at given confidences, learned from recent blocks
+    pub inclusion_estimations: Vec<InclusionFeerateEstimate>,
 }
 
 #[cfg(test)]
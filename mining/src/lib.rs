@@ -14,11 +14,18 @@ mod manager_tests;
 pub mod mempool;
 pub mod model;
 pub mod monitor;
+pub mod notification;
+pub mod persistence;
 #[cfg(test)]
 mod toccata_transient_mass_activation_tests;
 
 // Exposed for benchmarks
 pub use block_template::policy::Policy;
+pub use block_template::selection::{
+    AddressPriorityPolicy, CandidateVerdict, CompositeSelectionPolicy, FeeratePolicy, SelectionCandidate, SubnetworkQuota,
+    SubnetworkQuotaPolicy, TemplateSelectionPolicy, TemplateSelectionPolicyError, TemplateSelectionPolicyResult,
+    build_template_selection_policy,
+};
 pub use mempool::model::frontier::{Frontier, feerate_key::FeerateTransactionKey, search_tree::SearchTree};
 
 #[cfg(test)]
@@ -1,9 +1,12 @@
 use crate::{
-    MempoolCountersSnapshot, MiningCounters, P2pTxCountSample,
+    FeeratePolicy, MempoolCountersSnapshot, MiningCounters, P2pTxCountSample, TemplateSelectionPolicy,
     block_template::{builder::BlockTemplateBuilder, errors::BuilderError},
     cache::BlockTemplateCache,
     errors::MiningManagerResult,
-    feerate::{FeeEstimateVerbose, FeerateEstimations, FeerateEstimatorArgs},
+    feerate::{
+        FeeEstimateVerbose, FeerateEstimations, FeerateEstimatorArgs,
+        inclusion::{DEFAULT_INCLUSION_CONFIDENCES, DEFAULT_INCLUSION_TARGETS_SECONDS},
+    },
     mempool::{
         Mempool,
         config::Config,
@@ -20,6 +23,8 @@ use crate::{
         tx_insert::TransactionInsertion,
         tx_query::TransactionQuery,
     },
+    notification::{MempoolNotification, MempoolNotificationSender, MempoolRemovalReason},
+    persistence::MempoolSnapshot,
 };
 use itertools::Itertools;
 use kaspa_consensus_core::{
@@ -37,8 +42,12 @@ use kaspa_consensus_core::{
 use kaspa_consensusmanager::{ConsensusProxy, spawn_blocking};
 use kaspa_core::{debug, error, info, time::Stopwatch, warn};
 use kaspa_mining_errors::{manager::MiningManagerError, mempool::RuleError};
-use parking_lot::RwLock;
-use std::sync::Arc;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::{HashMap, VecDeque},
+    iter::once,
+    sync::Arc,
+};
 use tokio::sync::mpsc::UnboundedSender;
 
 pub struct MiningManager {
@@ -46,6 +55,10 @@ pub struct MiningManager {
     block_template_cache: BlockTemplateCache,
     mempool: RwLock<Mempool>,
     counters: Arc<MiningCounters>,
+    notification_sender: Option<MempoolNotificationSender>,
+    /// Notifications drained from the mempool in the order of its changes, waiting for the lock to be released
+    notification_queue: Mutex<VecDeque<MempoolNotification>>,
+    selection_policy: Arc<dyn TemplateSelectionPolicy>,
 }
 
 impl MiningManager {
@@ -88,7 +101,65 @@ impl MiningManager {
         let config = Arc::new(config);
         let mempool = RwLock::new(Mempool::new(config.clone(), toccata_activation, counters.clone()));
         let block_template_cache = BlockTemplateCache::new(cache_lifetime);
-        Self { config, block_template_cache, mempool, counters }
+        Self {
+            config,
+            block_template_cache,
+            mempool,
+            counters,
+            notification_sender: None,
+            notification_queue: Default::default(),
+            selection_policy: Arc::new(FeeratePolicy),
+        }
+    }
+
+    /// Sets the channel receiving the mempool notifications
+    ///
+    /// Notifications are only emitted while activated by [`Self::set_notifications_active`].
+    pub fn with_notification_sender(mut self, notification_sender: MempoolNotificationSender) -> Self {
+        self.notification_sender = Some(notification_sender);
+        self
+    }
+
+    /// Starts or stops emitting mempool notifications, typically as listeners subscribe and unsubscribe
+    ///
+    /// While inactive, the mempool changes are not recorded at all.
+    pub fn set_notifications_active(&self, active: bool) {
+        if self.notification_sender.is_some() {
+            self.mempool.write().set_events_enabled(active);
+        }
+    }
+
+    /// Sets the policy consulted when selecting the transactions of block templates
+    pub fn with_selection_policy(mut self, selection_policy: Arc<dyn TemplateSelectionPolicy>) -> Self {
+        self.selection_policy = selection_policy;
+        self
+    }
+
+    /// Queues the notifications of the transaction pool changes recorded while holding the mempool write lock
+    ///
+    /// Must be called before releasing the write lock so notifications are queued in the order of the changes.
+    /// The queue is then flushed by [`Self::send_mempool_notifications`] once the lock is released.
+    fn queue_mempool_events(&self, mempool: &mut Mempool, muted_reason: MempoolRemovalReason) {
+        if self.notification_sender.is_some() {
+            let notifications = mempool.drain_events(muted_reason);
+            if !notifications.is_empty() {
+                self.notification_queue.lock().extend(notifications);
+            }
+        }
+    }
+
+    /// Sends the queued mempool notifications
+    ///
+    /// Must be called after releasing the mempool write lock.
+    fn send_mempool_notifications(&self) {
+        if let Some(ref sender) = self.notification_sender {
+            let mut queue = self.notification_queue.lock();
+            while let Some(notification) = queue.pop_front() {
+                if let Err(err) = sender.try_send(notification) {
+                    warn!("Failed to send a mempool notification: {}", err);
+                }
+            }
+        }
     }
 
     pub fn get_block_template(&self, consensus: &dyn ConsensusApi, miner_data: &MinerData) -> MiningManagerResult<BlockTemplate> {
@@ -196,7 +267,9 @@ impl MiningManager {
                             error!("Error from mempool.remove_transactions: {:?}", err);
                         }
                     });
+                    self.queue_mempool_events(&mut mempool_write, MempoolRemovalReason::Evicted);
                     drop(mempool_write);
+                    self.send_mempool_notifications();
 
                     debug!(
                         "Building a new block template failed for {} txs missing outpoint and {} invalid txs",
@@ -213,7 +286,7 @@ impl MiningManager {
 
     /// Dynamically builds a transaction selector based on the specific state of the ready transactions frontier
     pub(crate) fn build_selector(&self) -> Box<dyn TemplateTransactionSelector> {
-        self.mempool.read().build_selector()
+        self.mempool.read().build_selector(self.selection_policy.as_ref())
     }
 
     /// Returns realtime feerate estimations based on internal mempool state
@@ -237,6 +310,12 @@ impl MiningManager {
         let estimator = mempool_read.build_feerate_estimator(args);
         let ready_transactions_count = mempool_read.ready_transaction_count();
         let ready_transaction_total_mass = mempool_read.ready_transaction_total_mass();
+        let inclusion_observation_count = mempool_read.inclusion_observation_count();
+        let inclusion_estimations = mempool_read.inclusion_feerate_estimations(
+            &DEFAULT_INCLUSION_TARGETS_SECONDS,
+            &DEFAULT_INCLUSION_CONFIDENCES,
+            consensus.get_virtual_daa_score(),
+        );
         drop(mempool_read);
         let mut resp = FeeEstimateVerbose {
             estimations: estimator.calc_estimations(self.config.minimum_feerate()),
@@ -247,6 +326,9 @@ impl MiningManager {
             next_block_template_feerate_min: -1.0,
             next_block_template_feerate_median: -1.0,
             next_block_template_feerate_max: -1.0,
+
+            inclusion_observation_count: inclusion_observation_count as u64,
+            inclusion_estimations,
         };
         // calculate next_block_template_feerate_xxx
         {
@@ -323,11 +405,28 @@ impl MiningManager {
         let validation_result = validate_mempool_transaction(consensus, &mut transaction, &args);
         // write lock on mempool
         let mut mempool = self.mempool.write();
-        match mempool.post_validate_and_insert_transaction(consensus, validation_result, transaction, priority, orphan, rbf_policy)? {
-            TransactionPostValidation { removed, accepted: Some(accepted_transaction) } => {
-                let unorphaned_transactions = mempool.get_unorphaned_transactions_after_accepted_transaction(&accepted_transaction);
-                drop(mempool);
-
+        let post_validation = mempool.post_validate_and_insert_transaction(
+            consensus,
+            validation_result,
+            transaction,
+            priority,
+            orphan,
+            rbf_policy,
+            None,
+        );
+        self.queue_mempool_events(&mut mempool, MempoolRemovalReason::Evicted);
+        let post_validation = post_validation.map(|TransactionPostValidation { removed, accepted }| {
+            let unorphaned_transactions = accepted
+                .as_ref()
+                .map(|accepted_transaction| mempool.get_unorphaned_transactions_after_accepted_transaction(accepted_transaction))
+                .unwrap_or_default();
+            (removed, accepted, unorphaned_transactions)
+        });
+        drop(mempool);
+        self.send_mempool_notifications();
+        let (removed, accepted, unorphaned_transactions) = post_validation?;
+        match accepted {
+            Some(accepted_transaction) => {
                 // The capacity used here may be exceeded since accepted unorphaned transaction may themselves unorphan other transactions.
                 let mut accepted_transactions = Vec::with_capacity(unorphaned_transactions.len() + 1);
                 // We include the original accepted transaction as well
@@ -337,7 +436,7 @@ impl MiningManager {
 
                 Ok(TransactionInsertion::new(removed, accepted_transactions))
             }
-            TransactionPostValidation { removed, accepted: None } => Ok(TransactionInsertion::new(removed, vec![])),
+            None => Ok(TransactionInsertion::new(removed, vec![])),
         }
     }
 
@@ -391,6 +490,7 @@ impl MiningManager {
                         priority,
                         Orphan::Forbidden,
                         rbf_policy,
+                        None,
                     ) {
                         Ok(TransactionPostValidation { removed: _, accepted: Some(accepted_transaction) }) => {
                             accepted_transactions.push(accepted_transaction.clone());
@@ -405,7 +505,9 @@ impl MiningManager {
                     }
                 })
                 .collect::<Vec<_>>();
+            self.queue_mempool_events(&mut mempool, MempoolRemovalReason::Evicted);
             drop(mempool);
+            self.send_mempool_notifications();
         }
         accepted_transactions
     }
@@ -425,6 +527,22 @@ impl MiningManager {
         priority: Priority,
         orphan: Orphan,
         rbf_policy: RbfPolicy,
+    ) -> Vec<MiningManagerResult<Arc<Transaction>>> {
+        self.validate_and_insert_transaction_batch_impl(consensus, transactions, priority, orphan, rbf_policy, None)
+    }
+
+    /// See `validate_and_insert_transaction_batch`
+    ///
+    /// When provided, `restored` maps the transactions restored from a mempool snapshot to their original priority
+    /// and insertion DAA score, overriding `priority`.
+    fn validate_and_insert_transaction_batch_impl(
+        &self,
+        consensus: &dyn ConsensusApi,
+        transactions: Vec<Transaction>,
+        priority: Priority,
+        orphan: Orphan,
+        rbf_policy: RbfPolicy,
+        restored: Option<&HashMap<TransactionId, (Priority, u64)>>,
     ) -> Vec<MiningManagerResult<Arc<Transaction>>> {
         const TRANSACTION_CHUNK_SIZE: usize = 250;
 
@@ -494,6 +612,10 @@ impl MiningManager {
             let mut mempool = self.mempool.write();
             let txs = chunk.flat_map(|(transaction, validation_result)| {
                 let transaction_id = transaction.id();
+                let (priority, restored_daa_score) = match restored.and_then(|restored| restored.get(&transaction_id)) {
+                    Some(&(priority, added_at_daa_score)) => (priority, Some(added_at_daa_score)),
+                    None => (priority, None),
+                };
                 match mempool.post_validate_and_insert_transaction(
                     consensus,
                     validation_result,
@@ -501,6 +623,7 @@ impl MiningManager {
                     priority,
                     orphan,
                     rbf_policy,
+                    restored_daa_score,
                 ) {
                     Ok(TransactionPostValidation { removed: _, accepted: Some(accepted_transaction) }) => {
                         insert_results.push(Ok(accepted_transaction.clone()));
@@ -519,6 +642,9 @@ impl MiningManager {
                 }
             });
             unorphaned_transactions.extend(txs);
+            self.queue_mempool_events(&mut mempool, MempoolRemovalReason::Evicted);
+            drop(mempool);
+            self.send_mempool_notifications();
         }
 
         insert_results
@@ -526,6 +652,57 @@ impl MiningManager {
         insert_results
     }
 
+    /// Validates a package of transactions as a unit and adds them to the set of known transactions
+    /// that have not yet been added to any block.
+    ///
+    /// The package transactions may spend the outputs of one another. The package is rejected as a whole
+    /// if any of its transactions is invalid, is an orphan or double spends a mempool transaction. The
+    /// minimum relay fee applies to the combined fee and mass of the package, so a child can pay for a
+    /// parent not meeting the minimum relay fee on its own.
+    ///
+    /// On success, returns the accepted package transactions along with the transactions unorphaned following
+    /// their insertion. The returned transactions are references of objects owned by the mempool.
+    pub fn validate_and_insert_transaction_package(
+        &self,
+        consensus: &dyn ConsensusApi,
+        transactions: Vec<Transaction>,
+        priority: Priority,
+    ) -> MiningManagerResult<TransactionInsertion> {
+        let transactions = transactions.into_iter().map(MutableTransaction::from_tx).topological_into_iter().collect_vec();
+
+        // read lock on mempool
+        let mut transactions = {
+            let mempool = self.mempool.read();
+            mempool.validate_transaction_package_in_isolation(&transactions)?;
+            mempool.pre_validate_and_populate_transaction_package(consensus, transactions)?
+        };
+
+        // no lock on mempool
+        // The package size is bounded by MAXIMUM_PACKAGE_TRANSACTION_COUNT so it is validated in a single chunk
+        let args = TransactionValidationBatchArgs::new();
+        let validation_results = validate_mempool_transactions_in_parallel(consensus, &mut transactions, &args);
+
+        // write lock on mempool
+        let mut mempool = self.mempool.write();
+        let post_validation =
+            mempool.post_validate_and_insert_transaction_package(consensus, validation_results, transactions, priority);
+        self.queue_mempool_events(&mut mempool, MempoolRemovalReason::Evicted);
+        let post_validation = post_validation.map(|accepted_transactions| {
+            let unorphaned_transactions = accepted_transactions
+                .iter()
+                .flat_map(|transaction| mempool.get_unorphaned_transactions_after_accepted_transaction(transaction))
+                .collect_vec();
+            (accepted_transactions, unorphaned_transactions)
+        });
+        drop(mempool);
+        self.send_mempool_notifications();
+        let (mut accepted_transactions, unorphaned_transactions) = post_validation?;
+
+        self.counters.increase_tx_counts(accepted_transactions.len() as u64, priority);
+        accepted_transactions.extend(self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions));
+        Ok(TransactionInsertion::new(None, accepted_transactions))
+    }
+
     fn next_transaction_chunk_upper_bound(
         &self,
         transactions: &[MutableTransaction],
@@ -562,6 +739,27 @@ impl MiningManager {
         self.mempool.read().has_transaction(transaction_id, query)
     }
 
+    /// Try to return a non-orphan mempool transaction along with its mempool ancestors, in topological order.
+    ///
+    /// Returns `None` if the transaction is missing or if the package would exceed the maximum package size.
+    pub fn get_transaction_package(&self, transaction_id: &TransactionId) -> Option<Vec<Arc<Transaction>>> {
+        self.mempool.read().get_transaction_package(transaction_id)
+    }
+
+    /// Returns the mempool transactions selected by `query` whose id satisfies `filter`.
+    ///
+    /// The ids are visited in place under the mempool read lock, so `filter` is meant for cheap lookups by a key
+    /// derived from the id, such as the short ids of compact blocks.
+    ///
+    /// Note: orphan transactions are returned as is, their storage mass commitment may thus be unset.
+    pub fn get_transactions_by_id_filter(
+        &self,
+        query: TransactionQuery,
+        filter: impl FnMut(&TransactionId) -> bool,
+    ) -> Vec<Arc<Transaction>> {
+        self.mempool.read().get_transactions_by_id_filter(query, filter)
+    }
+
     pub fn get_all_transactions(&self, query: TransactionQuery) -> (Vec<MutableTransaction>, Vec<MutableTransaction>) {
         const TRANSACTION_CHUNK_SIZE: usize = 1000;
         // read lock on mempool by transaction chunks
@@ -602,6 +800,67 @@ impl MiningManager {
         self.mempool.read().transaction_count(query)
     }
 
+    /// Returns a snapshot of the transaction pool, and of the orphan pool if `include_orphans` is set,
+    /// suitable for being persisted and restored on a later run.
+    pub fn mempool_snapshot(&self, include_orphans: bool) -> MempoolSnapshot {
+        let query = if include_orphans { TransactionQuery::All } else { TransactionQuery::TransactionsOnly };
+        let (transactions, orphans) = self.mempool.read().get_snapshot_entries(query);
+        MempoolSnapshot::new(transactions, orphans)
+    }
+
+    /// Revalidates the transactions of a mempool snapshot against the current virtual UTXO set and inserts
+    /// the valid ones, keeping their original priority and insertion DAA score.
+    ///
+    /// Returns the number of transactions inserted in the transaction pool.
+    pub fn restore_mempool_snapshot(&self, consensus: &dyn ConsensusApi, snapshot: MempoolSnapshot) -> usize {
+        let metadata = snapshot
+            .transactions
+            .iter()
+            .chain(snapshot.orphans.iter())
+            .map(|entry| (entry.transaction.id(), (entry.priority, entry.added_at_daa_score)))
+            .collect::<HashMap<_, _>>();
+
+        // A batch validates all its transactions before inserting any, so a transaction chained to another one of
+        // the same batch would be seen as an orphan. The transaction pool entries are hence grouped by their depth
+        // in the snapshot, each batch only spending outputs of transactions already inserted by the previous ones.
+        let mut depths: HashMap<TransactionId, usize> = HashMap::with_capacity(snapshot.transactions.len());
+        let mut batches: Vec<Vec<Transaction>> = vec![];
+        for transaction in snapshot.transactions.into_iter().map(|entry| entry.transaction).topological_into_iter() {
+            let depth = transaction
+                .inputs
+                .iter()
+                .filter_map(|input| depths.get(&input.previous_outpoint.transaction_id).map(|depth| depth + 1))
+                .max()
+                .unwrap_or_default();
+            depths.insert(transaction.id(), depth);
+            if depth == batches.len() {
+                batches.push(vec![]);
+            }
+            batches[depth].push(transaction);
+        }
+
+        let orphans = snapshot.orphans.into_iter().map(|entry| entry.transaction).collect_vec();
+        batches
+            .into_iter()
+            .map(|transactions| (transactions, Orphan::Forbidden))
+            .chain(once((orphans, Orphan::Allowed)))
+            .filter(|(transactions, _)| !transactions.is_empty())
+            .map(|(transactions, orphan)| {
+                self.validate_and_insert_transaction_batch_impl(
+                    consensus,
+                    transactions,
+                    Priority::Low,
+                    orphan,
+                    RbfPolicy::Forbidden,
+                    Some(&metadata),
+                )
+                .iter()
+                .filter(|result| result.is_ok())
+                .count()
+            })
+            .sum()
+    }
+
     pub fn handle_new_block_transactions(
         &self,
         consensus: &dyn ConsensusApi,
@@ -613,7 +872,12 @@ impl MiningManager {
         // problem of the internal implementation and unrelated to the caller
 
         // write lock on mempool
-        let unorphaned_transactions = self.mempool.write().handle_new_block_transactions(block_daa_score, block_transactions)?;
+        let mut mempool = self.mempool.write();
+        let unorphaned_transactions = mempool.handle_new_block_transactions(block_daa_score, block_transactions);
+        self.queue_mempool_events(&mut mempool, MempoolRemovalReason::Evicted);
+        drop(mempool);
+        self.send_mempool_notifications();
+        let unorphaned_transactions = unorphaned_transactions?;
 
         // alternate no & write lock on mempool
         let accepted_transactions = self.validate_and_insert_unorphaned_transactions(consensus, unorphaned_transactions);
@@ -635,13 +899,18 @@ impl MiningManager {
 
         // mempool
         let expired_low_priority_transactions = self.mempool.write().collect_expired_low_priority_transactions(consensus);
+        let virtual_daa_score = consensus.get_virtual_daa_score();
         for chunk in &expired_low_priority_transactions.iter().chunks(24) {
             let mut mempool = self.mempool.write();
             chunk.into_iter().for_each(|tx| {
+                mempool.record_inclusion_failure(tx, virtual_daa_score);
                 if let Err(err) = mempool.remove_transaction(tx, true, TxRemovalReason::Muted, "") {
                     warn!("Failed to remove transaction {} from mempool: {}", tx, err);
                 }
             });
+            self.queue_mempool_events(&mut mempool, MempoolRemovalReason::Expired);
+            drop(mempool);
+            self.send_mempool_notifications();
         }
         match expired_low_priority_transactions.len() {
             0 => {}
@@ -816,8 +1085,10 @@ impl MiningManager {
             if !valid_ids.is_empty() {
                 let _ = transaction_ids_sender.send(valid_ids);
             }
+            self.queue_mempool_events(&mut mempool, MempoolRemovalReason::Evicted);
             drop(_swo);
             drop(mempool);
+            self.send_mempool_notifications();
         }
         match accepted + missing_outpoint + invalid {
             0 => {
@@ -858,6 +1129,11 @@ impl MiningManager {
     pub(crate) fn get_estimated_size(&self) -> usize {
         self.mempool.read().get_estimated_size()
     }
+
+    #[cfg(test)]
+    pub(crate) fn assert_ancestor_packages_consistency(&self) {
+        self.mempool.read().assert_ancestor_packages_consistency()
+    }
 }
 
 /// Async proxy for the mining manager
@@ -875,6 +1151,11 @@ impl MiningManagerProxy {
         consensus.clone().spawn_blocking(move |c| self.inner.get_block_template(c, &miner_data)).await
     }
 
+    /// Starts or stops emitting mempool notifications, see [`MiningManager::set_notifications_active`]
+    pub async fn set_notifications_active(self, active: bool) {
+        spawn_blocking(move || self.inner.set_notifications_active(active)).await.unwrap()
+    }
+
     /// Returns realtime feerate estimations based on internal mempool state
     pub async fn get_realtime_feerate_estimations(self) -> FeerateEstimations {
         spawn_blocking(move || self.inner.get_realtime_feerate_estimations()).await.unwrap()
@@ -932,6 +1213,19 @@ impl MiningManagerProxy {
             .await
     }
 
+    /// Validates a package of transactions as a unit and adds them to the set of known transactions
+    /// that have not yet been added to any block.
+    ///
+    /// See [`MiningManager::validate_and_insert_transaction_package`]
+    pub async fn validate_and_insert_transaction_package(
+        self,
+        consensus: &ConsensusProxy,
+        transactions: Vec<Transaction>,
+        priority: Priority,
+    ) -> MiningManagerResult<TransactionInsertion> {
+        consensus.clone().spawn_blocking(move |c| self.inner.validate_and_insert_transaction_package(c, transactions, priority)).await
+    }
+
     pub async fn handle_new_block_transactions(
         self,
         consensus: &ConsensusProxy,
@@ -968,14 +1262,37 @@ impl MiningManagerProxy {
         spawn_blocking(move || self.inner.has_transaction(&transaction_id, query)).await.unwrap()
     }
 
+    /// Try to return a non-orphan mempool transaction along with its mempool ancestors, in topological order.
+    pub async fn get_transaction_package(self, transaction_id: TransactionId) -> Option<Vec<Arc<Transaction>>> {
+        spawn_blocking(move || self.inner.get_transaction_package(&transaction_id)).await.unwrap()
+    }
+
     pub async fn transaction_count(self, query: TransactionQuery) -> usize {
         spawn_blocking(move || self.inner.transaction_count(query)).await.unwrap()
     }
 
+    /// Returns the mempool transactions selected by `query` whose id satisfies `filter`, visiting the ids in place
+    pub async fn get_transactions_by_id_filter<F>(self, query: TransactionQuery, filter: F) -> Vec<Arc<Transaction>>
+    where
+        F: FnMut(&TransactionId) -> bool + Send + 'static,
+    {
+        spawn_blocking(move || self.inner.get_transactions_by_id_filter(query, filter)).await.unwrap()
+    }
+
     pub async fn get_all_transactions(self, query: TransactionQuery) -> (Vec<MutableTransaction>, Vec<MutableTransaction>) {
         spawn_blocking(move || self.inner.get_all_transactions(query)).await.unwrap()
     }
 
+    /// Returns a snapshot of the transaction pool, and of the orphan pool if `include_orphans` is set.
+    pub async fn mempool_snapshot(self, include_orphans: bool) -> MempoolSnapshot {
+        spawn_blocking(move || self.inner.mempool_snapshot(include_orphans)).await.unwrap()
+    }
+
+    /// Revalidates and inserts the transactions of a mempool snapshot, returning the number of restored transactions.
+    pub async fn restore_mempool_snapshot(self, consensus: &ConsensusProxy, snapshot: MempoolSnapshot) -> usize {
+        consensus.clone().spawn_blocking(move |c| self.inner.restore_mempool_snapshot(c, snapshot)).await
+    }
+
     /// get_transactions_by_addresses returns the sending and receiving transactions for
     /// a set of addresses.
     ///
@@ -1,17 +1,19 @@
 #[cfg(test)]
 mod tests {
     use crate::{
-        MiningCounters,
+        AddressPriorityPolicy, MiningCounters, SubnetworkQuota, SubnetworkQuotaPolicy,
         block_template::{builder::BlockTemplateBuilder, policy::Policy},
         errors::{MiningManagerError, MiningManagerResult},
         manager::MiningManager,
         mempool::{
             config::{Config, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE},
             errors::RuleError,
-            model::frontier::selectors::TakeAllSelector,
+            model::{frontier::selectors::TakeAllSelector, transactions_pool::MAXIMUM_PACKAGE_ANCESTORS},
             tx::{Orphan, Priority, RbfPolicy},
         },
         model::{tx_insert::TransactionInsertion, tx_query::TransactionQuery},
+        notification::MempoolNotification,
+        persistence::MempoolSnapshot,
         testutils::consensus_mock::ConsensusMock,
     };
     use itertools::Itertools;
@@ -27,7 +29,7 @@ mod tests {
         constants::{MAX_TX_IN_SEQUENCE_NUM, SOMPI_PER_KASPA, TX_VERSION},
         errors::tx::TxRuleError,
         mass::{BlockLaneLimits, BlockMassLimits, NonContextualMasses, transaction_estimated_serialized_size},
-        subnets::SUBNETWORK_ID_NATIVE,
+        subnets::{SUBNETWORK_ID_NATIVE, SubnetworkId},
         tx::{
             MutableTransaction, ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput,
             UtxoEntry, scriptvec,
@@ -40,7 +42,7 @@ mod tests {
         test_helpers::{create_transaction, create_transaction_with_change, op_true_script},
     };
     use kaspa_utils::mem_size::MemSizeEstimator;
-    use std::{iter::once, sync::Arc};
+    use std::{collections::HashSet, iter::once, sync::Arc};
     use tokio::sync::mpsc::{error::TryRecvError, unbounded_channel};
 
     const TARGET_TIME_PER_BLOCK: u64 = 1_000;
@@ -627,6 +629,129 @@ mod tests {
         }
     }
 
+    /// test_mempool_notifications verifies that transactions added to the mempool and then included in a block are notified,
+    /// but only while notifications are active.
+    #[test]
+    fn test_mempool_notifications() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let (sender, receiver) = async_channel::unbounded();
+        let mining_manager = default_mining_manager().with_notification_sender(sender);
+
+        // Notifications are inactive until some listener subscribes
+        let inactive_transaction = create_transaction_with_utxo_entry(1, 0);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            inactive_transaction.tx.as_ref().clone(),
+            Priority::Low,
+            Orphan::Allowed,
+            RbfPolicy::Forbidden,
+        );
+        assert!(result.is_ok(), "the insertion of a new valid transaction in the mempool failed");
+        assert!(receiver.is_empty(), "no notification was expected while inactive");
+
+        mining_manager.set_notifications_active(true);
+        let transaction = create_transaction_with_utxo_entry(0, 0);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            transaction.tx.as_ref().clone(),
+            Priority::Low,
+            Orphan::Allowed,
+            RbfPolicy::Forbidden,
+        );
+        assert!(result.is_ok(), "the insertion of a new valid transaction in the mempool failed");
+        match receiver.try_recv() {
+            Ok(MempoolNotification::TransactionAdded(notification)) => {
+                assert_eq!(vec![transaction.id()], notification.transactions.iter().map(|x| x.id()).collect_vec());
+            }
+            notification => panic!("expected a transaction added notification, got {notification:?}"),
+        }
+
+        let block_transactions = build_block_transactions(once(transaction.tx.as_ref()));
+        let result = mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &block_transactions);
+        assert!(result.is_ok());
+        match receiver.try_recv() {
+            Ok(MempoolNotification::TransactionIncluded(notification)) => {
+                assert_eq!(vec![transaction.id()], notification.transactions.iter().map(|x| x.id()).collect_vec());
+            }
+            notification => panic!("expected a transaction included notification, got {notification:?}"),
+        }
+        assert!(receiver.is_empty(), "no other notification was expected");
+    }
+
+    /// test_restore_mempool_snapshot verifies that a persisted mempool is restored with its transaction priorities and orphans,
+    /// dropping the transactions which are no longer valid.
+    #[test]
+    fn test_restore_mempool_snapshot() {
+        const TX_COUNT: usize = 5;
+        let consensus = Arc::new(ConsensusMock::new());
+        let mining_manager = default_mining_manager();
+
+        let (parent_txs, child_txs) = create_arrays_of_parent_and_children_transactions(&consensus, TX_COUNT);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            parent_txs.iter(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            child_txs.iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        let orphan_tx = create_child_and_parent_txs_and_add_parent_to_consensus(&Arc::new(ConsensusMock::new()));
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            once(&orphan_tx),
+            Priority::Low,
+            Orphan::Allowed,
+            RbfPolicy::Forbidden,
+        );
+        assert_transaction_count(&mining_manager, 2 * TX_COUNT + 1, "all transactions should be in the mempool");
+
+        // Roundtrip the snapshot through a file
+        let snapshot = mining_manager.mempool_snapshot(true);
+        assert_eq!(2 * TX_COUNT, snapshot.transactions.len());
+        assert_eq!(1, snapshot.orphans.len());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mempool.bin");
+        snapshot.write_to_file(&path).unwrap();
+        let mut snapshot = MempoolSnapshot::read_from_file(&path).unwrap();
+        assert!(snapshot.orphans.iter().all(|entry| entry.transaction.id() == orphan_tx.id()));
+
+        // Give each transaction a distinct insertion DAA score, differing from the current virtual one
+        let added_at_daa_score = |transaction_id: TransactionId| {
+            100 + parent_txs.iter().chain(child_txs.iter()).position(|tx| tx.id() == transaction_id).unwrap() as u64
+        };
+        snapshot.transactions.iter_mut().for_each(|entry| entry.added_at_daa_score = added_at_daa_score(entry.transaction.id()));
+
+        // Have the first parent mined meanwhile so it gets rejected on restore while its child remains valid
+        consensus.add_transaction(parent_txs[0].clone(), 2);
+
+        let restored_manager = default_mining_manager();
+        let restored = restored_manager.restore_mempool_snapshot(consensus.as_ref(), snapshot);
+        assert_eq!(2 * TX_COUNT - 1, restored);
+        let restored_snapshot = restored_manager.mempool_snapshot(false);
+        for parent_tx in parent_txs.iter().skip(1) {
+            let entry = restored_snapshot.transactions.iter().find(|entry| entry.transaction.id() == parent_tx.id());
+            assert_eq!(Some(Priority::High), entry.map(|entry| entry.priority), "a restored transaction should keep its priority");
+        }
+        assert_eq!(2 * TX_COUNT - 1, restored_snapshot.transactions.len());
+        for entry in restored_snapshot.transactions.iter() {
+            assert_eq!(
+                added_at_daa_score(entry.transaction.id()),
+                entry.added_at_daa_score,
+                "a restored transaction should keep its insertion DAA score"
+            );
+        }
+        assert!(restored_manager.has_transaction(&orphan_tx.id(), TransactionQuery::OrphansOnly), "the orphan should be restored");
+    }
+
     #[test]
     /// test_double_spend_with_block verifies that any transactions which are now double spends as a result of the block's new transactions
     /// will be removed from the mempool.
@@ -1205,6 +1330,394 @@ mod tests {
         assert!(validate_and_insert_mutable_transaction(&mining_manager, consensus.as_ref(), too_big_tx.clone()).is_err());
     }
 
+    // This test verifies that a high paying child prioritizes its low paying parent (CPFP) against eviction,
+    // and that the child is prioritized by its own feerate once the parent is mined.
+    #[test]
+    fn test_child_pays_for_parent() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mut config =
+            Config::build_default(TARGET_TIME_PER_BLOCK, false, BlockMassLimits::with_shared_limit(MAX_BLOCK_MASS), BLOCK_LANE_LIMITS);
+        config.maximum_transaction_count = 3;
+        let mining_manager = MiningManager::with_config(config, ForkActivation::never(), None, counters);
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 3);
+        let parent_tx = create_transaction(&funding_txs[0], DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let child_tx = create_transaction(&parent_tx, 100 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        // Pays more than the parent alone but less than the parent/child package
+        let unrelated_tx = create_transaction(&funding_txs[1], 2 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            [&parent_tx, &child_tx, &unrelated_tx].into_iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        assert_transaction_count(&mining_manager, 3, "all transactions should be in the mempool");
+
+        // The mempool is full so a new transaction must evict the ready transaction having the lowest effective feerate
+        let new_tx = create_transaction(&funding_txs[2], 10 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            once(&new_tx),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        assert!(
+            mining_manager.get_transaction(&unrelated_tx.id(), TransactionQuery::All).is_none(),
+            "the unrelated transaction should be evicted"
+        );
+        for tx in [&parent_tx, &child_tx, &new_tx] {
+            assert!(
+                mining_manager.get_transaction(&tx.id(), TransactionQuery::All).is_some(),
+                "the transaction {} should be in the mempool",
+                tx.id()
+            );
+        }
+
+        // Chained transactions cannot share a block so only the parent is selected
+        let transactions = mining_manager.build_selector().select_transactions();
+        assert!(contained_by(parent_tx.id(), &transactions));
+        assert!(!contained_by(child_tx.id(), &transactions));
+
+        // Once the parent is mined the child becomes ready
+        let block_transactions = build_block_transactions(once(&parent_tx));
+        mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &block_transactions).unwrap();
+        let transactions = mining_manager.build_selector().select_transactions();
+        assert!(contained_by(child_tx.id(), &transactions));
+        assert!(contained_by(new_tx.id(), &transactions));
+    }
+
+    // This test verifies that the ancestor packages updated in place on every removal match the packages
+    // built from scratch, including for a redeemer getting few enough ancestors to pay for them.
+    #[test]
+    fn test_ancestor_packages_after_removals() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let mining_manager = default_mining_manager();
+
+        // A chain whose last transaction has one ancestor too many for paying for them, and a tree of redeemers
+        let funding_txs = create_and_add_funding_transactions(&consensus, 1);
+        let mut chain = vec![create_transaction(&funding_txs[0], DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE)];
+        for i in 0..=MAXIMUM_PACKAGE_ANCESTORS {
+            let fee = if i == 0 { 100 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE } else { DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE };
+            chain.push(create_transaction(chain.last().unwrap(), fee));
+        }
+        let root_tx =
+            create_funded_transaction(once(&funding_txs[0]), vec![1], Some(SOMPI_PER_KASPA), DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let tree = create_children_tree(&root_tx, 3);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            chain.iter().chain(once(&root_tx)).chain(tree.iter()),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        mining_manager.assert_ancestor_packages_consistency();
+
+        // Mining the chain and tree roots makes their children ready
+        let block_transactions = build_block_transactions([&chain[0], &root_tx].into_iter());
+        mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &block_transactions).unwrap();
+        mining_manager.assert_ancestor_packages_consistency();
+
+        // Double spends remove the chain and a subtree along with their redeemers
+        let double_spends = [
+            create_transaction(&chain[0], 2 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE),
+            create_transaction(&root_tx, 2 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE),
+        ];
+        let block_transactions = build_block_transactions(double_spends.iter());
+        mining_manager.handle_new_block_transactions(consensus.as_ref(), 3, &block_transactions).unwrap();
+        mining_manager.assert_ancestor_packages_consistency();
+        assert!(chain.iter().all(|tx| mining_manager.get_transaction(&tx.id(), TransactionQuery::All).is_none()));
+        assert!(mining_manager.get_transaction(&tree[0].id(), TransactionQuery::All).is_none());
+        assert!(mining_manager.get_transaction(&tree[1].id(), TransactionQuery::All).is_some());
+    }
+
+    // This test verifies that the inclusion of mempool transactions in blocks feeds the
+    // confidence-target feerate estimations of the verbose fee estimate.
+    #[test]
+    fn test_inclusion_feerate_estimations() {
+        const TX_COUNT: usize = 20;
+
+        let consensus = Arc::new(ConsensusMock::new());
+        let mining_manager = default_mining_manager();
+        let funding_txs = create_and_add_funding_transactions(&consensus, TX_COUNT);
+        let transactions = funding_txs.iter().map(|tx| create_transaction(tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE)).collect_vec();
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            transactions.iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+
+        let estimate = mining_manager.get_realtime_feerate_estimations_verbose(consensus.as_ref(), Prefix::Testnet).unwrap();
+        assert_eq!(estimate.inclusion_observation_count, 0);
+        assert!(estimate.inclusion_estimations.iter().all(|estimation| estimation.feerate.is_none()));
+
+        // The transactions entered the mempool at DAA score 0 and get included 2 seconds later at 1 BPS
+        let block_transactions = build_block_transactions(transactions.iter());
+        mining_manager.handle_new_block_transactions(consensus.as_ref(), 2, &block_transactions).unwrap();
+        assert_transaction_count(&mining_manager, 0, "all transactions should be removed from the mempool");
+
+        let estimate = mining_manager.get_realtime_feerate_estimations_verbose(consensus.as_ref(), Prefix::Testnet).unwrap();
+        assert_eq!(estimate.inclusion_observation_count, TX_COUNT as u64);
+        for estimation in estimate.inclusion_estimations {
+            assert_eq!(
+                estimation.feerate.is_some(),
+                estimation.target_seconds >= 2.0,
+                "only targets of at least 2 seconds should be met: {estimation:?}"
+            );
+        }
+    }
+
+    // This test verifies that an address priority policy places the transactions paying to its
+    // prioritized addresses ahead of better paying ones and drops those involving excluded addresses.
+    #[test]
+    fn test_address_priority_selection_policy() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let payout_script = get_miner_data(Prefix::Testnet).script_public_key;
+        let excluded_script = get_miner_data(Prefix::Testnet).script_public_key;
+        let mining_manager = default_mining_manager().with_selection_policy(Arc::new(AddressPriorityPolicy::from_scripts(
+            vec![payout_script.clone()],
+            vec![excluded_script.clone()],
+        )));
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 3);
+        let regular_tx = create_transaction(&funding_txs[0], 100 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let payout_tx = create_transaction_paying_to(&funding_txs[1], DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE, payout_script);
+        let excluded_tx = create_transaction_paying_to(&funding_txs[2], 100 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE, excluded_script);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            [&regular_tx, &payout_tx, &excluded_tx].into_iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        assert_transaction_count(&mining_manager, 3, "all transactions should be in the mempool");
+
+        // The prioritized transaction leads the first selection round and the excluded one is never selected
+        let mut selector = mining_manager.build_selector();
+        let transactions = selector.select_transactions();
+        assert_eq!(transactions.iter().map(|tx| tx.id()).collect_vec(), vec![payout_tx.id(), regular_tx.id()]);
+        assert!(selector.select_transactions().is_empty());
+
+        let block_template = mining_manager.get_block_template(consensus.as_ref(), &get_miner_data(Prefix::Testnet)).unwrap();
+        let template_ids = block_template.block.transactions.iter().skip(1).map(|tx| tx.id()).collect_vec();
+        assert_eq!(template_ids, vec![payout_tx.id(), regular_tx.id()]);
+    }
+
+    // This test verifies that a subnetwork quota policy reserves block mass for the best transactions
+    // of a lane and caps the mass the lane occupies.
+    #[test]
+    fn test_subnetwork_quota_selection_policy() {
+        const NATIVE_TX_COUNT: usize = 4;
+        const LANE_TX_COUNT: usize = 3;
+
+        let consensus = Arc::new(ConsensusMock::new());
+        let lane = SubnetworkId::from_byte(3);
+        let funding_txs = create_and_add_funding_transactions(&consensus, NATIVE_TX_COUNT + LANE_TX_COUNT);
+        let native_txs = funding_txs[..NATIVE_TX_COUNT]
+            .iter()
+            .map(|funding_tx| create_transaction(funding_tx, 100 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE))
+            .collect_vec();
+        let lane_txs = funding_txs[NATIVE_TX_COUNT..]
+            .iter()
+            .map(|funding_tx| {
+                let mut tx = create_transaction(funding_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+                tx.subnetwork_id = lane;
+                tx.finalize();
+                tx
+            })
+            .collect_vec();
+
+        // All transactions have the same mass, so the block fits exactly 10 of them
+        let tx_mass = transaction_estimated_serialized_size(&native_txs[0]);
+        let mut config =
+            Config::build_default(TARGET_TIME_PER_BLOCK, false, BlockMassLimits::with_shared_limit(10 * tx_mass), BLOCK_LANE_LIMITS);
+        let quota = SubnetworkQuota::new(lane, 10, 20).unwrap();
+        let mining_manager = MiningManager::with_config(config, ForkActivation::never(), None, Arc::new(MiningCounters::default()))
+            .with_selection_policy(Arc::new(SubnetworkQuotaPolicy::new(vec![quota]).unwrap()));
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            native_txs.iter().chain(lane_txs.iter()),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        assert_transaction_count(&mining_manager, NATIVE_TX_COUNT + LANE_TX_COUNT, "all transactions should be in the mempool");
+
+        let block_template = mining_manager.get_block_template(consensus.as_ref(), &get_miner_data(Prefix::Testnet)).unwrap();
+        let transactions = &block_template.block.transactions[1..];
+        // The reserved 10% of the block goes to a lane transaction despite its lower feerate
+        assert_eq!(transactions[0].subnetwork_id, lane);
+        // The lane may not exceed 20% of the block
+        assert_eq!(transactions.iter().filter(|tx| tx.subnetwork_id == lane).count(), 2);
+        assert!(native_txs.iter().all(|tx| contained_by(tx.id(), transactions)));
+    }
+
+    // This test verifies that a transaction package is validated as a unit, its combined fee paying
+    // for a parent below the minimum relay fee.
+    #[test]
+    fn test_validate_and_insert_transaction_package() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let mining_manager = default_mining_manager();
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 1);
+        let parent_tx = create_transaction(&funding_txs[0], 0);
+        let result = mining_manager.validate_and_insert_transaction(
+            consensus.as_ref(),
+            parent_tx.clone(),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        assert!(
+            matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectNonStandard(..)))),
+            "a transaction without fee should be rejected on its own but got {result:?}"
+        );
+
+        // The combined fee of the package is still too low
+        let cheap_child_tx = create_transaction(&parent_tx, 1);
+        let result = mining_manager.validate_and_insert_transaction_package(
+            consensus.as_ref(),
+            vec![parent_tx.clone(), cheap_child_tx],
+            Priority::High,
+        );
+        assert!(
+            matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectInsufficientPackageFee(..)))),
+            "a package with insufficient fee should be rejected but got {result:?}"
+        );
+        assert_transaction_count(&mining_manager, 0, "no transaction of a rejected package should be in the mempool");
+
+        let child_tx = create_transaction(&parent_tx, 10 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let result = mining_manager.validate_and_insert_transaction_package(
+            consensus.as_ref(),
+            vec![child_tx.clone(), child_tx.clone()],
+            Priority::High,
+        );
+        assert!(
+            matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectDuplicateInPackage(_)))),
+            "a package with duplicate transactions should be rejected but got {result:?}"
+        );
+
+        // The package is provided in reverse topological order on purpose
+        let result = mining_manager.validate_and_insert_transaction_package(
+            consensus.as_ref(),
+            vec![child_tx.clone(), parent_tx.clone()],
+            Priority::High,
+        );
+        let accepted = result.expect("the package should be accepted").accepted;
+        assert_eq!(vec![parent_tx.id(), child_tx.id()], accepted.iter().map(|tx| tx.id()).collect_vec());
+        assert_transaction_count(&mining_manager, 2, "both package transactions should be in the mempool");
+
+        // The package served to peers is made of the child and its mempool ancestor
+        let package = mining_manager.get_transaction_package(&child_tx.id()).expect("the child should be in the mempool");
+        assert_eq!(vec![parent_tx.id(), child_tx.id()], package.iter().map(|tx| tx.id()).collect_vec());
+    }
+
+    // This test verifies that the transactions evicted by a package failing partway are restored
+    // along with their relations once the package is rolled back, without being recorded as inclusion
+    // failures, and that the evictions of a committed package are.
+    #[test]
+    fn test_transaction_package_rollback_restores_evicted() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let counters = Arc::new(MiningCounters::default());
+        let mut config =
+            Config::build_default(TARGET_TIME_PER_BLOCK, false, BlockMassLimits::with_shared_limit(MAX_BLOCK_MASS), BLOCK_LANE_LIMITS);
+        config.maximum_transaction_count = 3;
+        let mining_manager = MiningManager::with_config(config, ForkActivation::never(), None, counters);
+
+        let funding_txs = create_and_add_funding_transactions(&consensus, 3);
+        let high_priority_tx = create_transaction(&funding_txs[0], DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            once(&high_priority_tx),
+            Priority::High,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        let low_parent_tx = create_transaction(&funding_txs[1], DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let low_child_tx = create_transaction(&low_parent_tx, DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        validate_and_insert_transactions(
+            &mining_manager,
+            consensus.as_ref(),
+            [&low_parent_tx, &low_child_tx].into_iter(),
+            Priority::Low,
+            Orphan::Forbidden,
+            RbfPolicy::Forbidden,
+        );
+        assert_transaction_count(&mining_manager, 3, "the mempool should be full");
+        let inclusion_observation_count = || {
+            mining_manager
+                .get_realtime_feerate_estimations_verbose(consensus.as_ref(), Prefix::Testnet)
+                .unwrap()
+                .inclusion_observation_count
+        };
+        assert_eq!(inclusion_observation_count(), 0);
+
+        // The first package transaction evicts the low paying chain, freeing two slots, but the third one finds
+        // no room left since the remaining transactions are either high priority or its own ancestors
+        let package_tx_1 = create_transaction(&funding_txs[2], 100 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let package_tx_2 = create_transaction(&package_tx_1, 100 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let package_tx_3 = create_transaction(&package_tx_2, 100 * DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE);
+        let result = mining_manager.validate_and_insert_transaction_package(
+            consensus.as_ref(),
+            vec![package_tx_1.clone(), package_tx_2.clone(), package_tx_3.clone()],
+            Priority::Low,
+        );
+        assert!(
+            matches!(result, Err(MiningManagerError::MempoolError(RuleError::RejectMempoolIsFull))),
+            "a package not fitting in the mempool should be rejected but got {result:?}"
+        );
+
+        assert_transaction_count(&mining_manager, 3, "the evicted transactions should be restored");
+        for tx in [&package_tx_1, &package_tx_2, &package_tx_3] {
+            assert!(
+                mining_manager.get_transaction(&tx.id(), TransactionQuery::All).is_none(),
+                "the package transaction {} should not be in the mempool",
+                tx.id()
+            );
+        }
+        let package = mining_manager.get_transaction_package(&low_child_tx.id()).expect("the low child should be in the mempool");
+        assert_eq!(vec![low_parent_tx.id(), low_child_tx.id()], package.iter().map(|tx| tx.id()).collect_vec());
+        let transactions = mining_manager.build_selector().select_transactions();
+        assert!(contained_by(low_parent_tx.id(), &transactions));
+        assert!(!contained_by(low_child_tx.id(), &transactions), "the restored child should still be chained to its parent");
+        assert_eq!(inclusion_observation_count(), 0, "a rolled back package should leave the inclusion estimator unchanged");
+
+        // Once the package commits, only the evicted parent failed on its feerate, its child going along with it
+        mining_manager
+            .validate_and_insert_transaction_package(consensus.as_ref(), vec![package_tx_1, package_tx_2], Priority::Low)
+            .unwrap();
+        assert!(mining_manager.get_transaction(&low_parent_tx.id(), TransactionQuery::All).is_none());
+        assert_eq!(inclusion_observation_count(), 1);
+    }
+
+    // This test verifies that the mempool transactions can be looked up by a predicate on their id.
+    #[test]
+    fn test_get_transactions_by_id_filter() {
+        let consensus = Arc::new(ConsensusMock::new());
+        let mining_manager = default_mining_manager();
+
+        let transactions = (0..4).map(|i| create_transaction_with_utxo_entry(i, 0)).collect_vec();
+        for tx in transactions.iter() {
+            validate_and_insert_mutable_transaction(&mining_manager, consensus.as_ref(), tx.clone()).unwrap();
+        }
+
+        let wanted: HashSet<TransactionId> = [transactions[1].id(), transactions[3].id()].into_iter().collect();
+        let found = mining_manager.get_transactions_by_id_filter(TransactionQuery::All, |id| wanted.contains(id));
+        assert_eq!(found.iter().map(|tx| tx.id()).collect::<HashSet<_>>(), wanted);
+        assert!(mining_manager.get_transactions_by_id_filter(TransactionQuery::OrphansOnly, |id| wanted.contains(id)).is_empty());
+    }
+
     #[test]
     fn test_realtime_feerate_estimations_respect_minimum_standard_feerate() {
         let minimum_feerate = DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE as f64 / 1000.0;
@@ -1511,6 +2024,13 @@ mod tests {
         Transaction::new(TX_VERSION, vec![], outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![])
     }
 
+    fn create_transaction_paying_to(tx_to_spend: &Transaction, fee: u64, script_public_key: ScriptPublicKey) -> Transaction {
+        let mut tx = create_transaction(tx_to_spend, fee);
+        tx.outputs[0].script_public_key = script_public_key;
+        tx.finalize();
+        tx
+    }
+
     fn contained_by<T: AsRef<Transaction>>(transaction_id: TransactionId, transactions: &[T]) -> bool {
         transactions.iter().any(|x| x.as_ref().id() == transaction_id)
     }
@@ -1,7 +1,7 @@
 use crate::mempool::{
     Mempool,
     config::LEGACY_MINIMUM_RELAY_TRANSACTION_FEE,
-    errors::{NonStandardError, NonStandardResult},
+    errors::{NonStandardError, NonStandardResult, RuleError, RuleResult},
     tx::Priority,
 };
 use kaspa_consensus_core::{
@@ -30,6 +30,15 @@ pub(crate) const MAXIMUM_STANDARD_TRANSACTION_MASS_PRE_TOCCATA: u64 = 100_000;
 /// primed in mempools ahead of activation.
 const STANDARD_MASS_RELAXATION_WINDOW_SECONDS: u64 = 30 * 60;
 
+/// The mass a transaction pays the minimum relay fee for, along with the applicable relay fee rate
+struct RelayFeeMass {
+    fee_mass: u64,
+    relay_fee: u64,
+    use_prior_p2p_fee_rules: bool,
+    compute_mass: u64,
+    normalized_transient_mass: u64,
+}
+
 impl Mempool {
     /// Returns the per-dimension standard mass cap currently in effect, or `None` once the cap has
     /// been relaxed (within [`STANDARD_MASS_RELAXATION_WINDOW_SECONDS`] before Toccata activation,
@@ -91,6 +100,17 @@ impl Mempool {
         transaction: &MutableTransaction,
         priority: Priority,
         virtual_daa_score: u64,
+    ) -> NonStandardResult<()> {
+        self.check_transaction_inputs_standard_in_context(transaction, virtual_daa_score)?;
+        self.check_transaction_relay_fee(transaction, priority, virtual_daa_score)
+    }
+
+    /// Performs the checks of [`Self::check_transaction_standard_in_context`] not involving the transaction
+    /// fee, which a transaction package verifies globally instead.
+    pub(crate) fn check_transaction_inputs_standard_in_context(
+        &self,
+        transaction: &MutableTransaction,
+        virtual_daa_score: u64,
     ) -> NonStandardResult<()> {
         let transaction_id = transaction.id();
 
@@ -124,6 +144,60 @@ impl Mempool {
             }
         }
 
+        Ok(())
+    }
+
+    /// Makes sure that the transaction's fee is above the minimum for acceptance into the mempool and relay.
+    fn check_transaction_relay_fee(
+        &self,
+        transaction: &MutableTransaction,
+        priority: Priority,
+        virtual_daa_score: u64,
+    ) -> NonStandardResult<()> {
+        let RelayFeeMass { fee_mass, relay_fee, use_prior_p2p_fee_rules, compute_mass, normalized_transient_mass } =
+            self.relay_fee_mass(transaction, priority, virtual_daa_score);
+        let minimum_fee = self.minimum_required_transaction_relay_fee(fee_mass, relay_fee);
+
+        let fee = transaction.calculated_fee.unwrap();
+        if fee < minimum_fee {
+            let transaction_id = transaction.id();
+            return if use_prior_p2p_fee_rules || compute_mass >= normalized_transient_mass {
+                Err(NonStandardError::RejectInsufficientComputeFee(transaction_id, fee, minimum_fee, compute_mass))
+            } else {
+                Err(NonStandardError::RejectInsufficientTransientFee(transaction_id, fee, minimum_fee, normalized_transient_mass))
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Makes sure that the combined fee of a transaction package is above the minimum for acceptance
+    /// of the combined mass into the mempool and relay, letting children pay for their parents.
+    pub(crate) fn check_transaction_package_relay_fee(
+        &self,
+        transactions: &[MutableTransaction],
+        priority: Priority,
+        virtual_daa_score: u64,
+    ) -> RuleResult<()> {
+        if self.config.accept_non_standard {
+            return Ok(());
+        }
+        let (mut fee, mut fee_mass, mut relay_fee) = (0u64, 0u64, self.config.minimum_relay_transaction_fee);
+        for transaction in transactions {
+            let relay_fee_mass = self.relay_fee_mass(transaction, priority, virtual_daa_score);
+            fee = fee.saturating_add(transaction.calculated_fee.unwrap());
+            fee_mass = fee_mass.saturating_add(relay_fee_mass.fee_mass);
+            // The relay fee only depends on the priority and the virtual DAA score so it is shared by all the transactions
+            relay_fee = relay_fee_mass.relay_fee;
+        }
+        let minimum_fee = self.minimum_required_transaction_relay_fee(fee_mass, relay_fee);
+        if fee < minimum_fee {
+            return Err(RuleError::RejectInsufficientPackageFee(fee, minimum_fee, fee_mass));
+        }
+        Ok(())
+    }
+
+    fn relay_fee_mass(&self, transaction: &MutableTransaction, priority: Priority, virtual_daa_score: u64) -> RelayFeeMass {
         // Minimum relay fee applies to normalized non-contextual mass so block-space usage has a
         // minimum cost, whether dominated by compute or by transient byte footprint.
         // Storage mass does not require an additional relay-fee floor here since storage growth is
@@ -145,19 +219,9 @@ impl Mempool {
         } else {
             (masses.compute_mass.max(normalized_transient_mass), self.config.minimum_relay_transaction_fee)
         };
-        let minimum_fee = self.minimum_required_transaction_relay_fee(fee_mass, relay_fee);
-
-        let fee = transaction.calculated_fee.unwrap();
-        if fee < minimum_fee {
-            return if use_prior_p2p_fee_rules || masses.compute_mass >= normalized_transient_mass {
-                Err(NonStandardError::RejectInsufficientComputeFee(transaction_id, fee, minimum_fee, masses.compute_mass))
-            } else {
-                Err(NonStandardError::RejectInsufficientTransientFee(transaction_id, fee, minimum_fee, normalized_transient_mass))
-            };
-        }
         // end-TODO
 
-        Ok(())
+        RelayFeeMass { fee_mass, relay_fee, use_prior_p2p_fee_rules, compute_mass: masses.compute_mass, normalized_transient_mass }
     }
 
     /// minimum_required_transaction_relay_fee returns the minimum transaction fee required
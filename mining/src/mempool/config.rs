@@ -17,6 +17,9 @@ pub(crate) const DEFAULT_ORPHAN_EXPIRE_SCAN_INTERVAL_SECONDS: u64 = 10;
 pub(crate) const DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_NORMALIZED_MASS: u64 = 500_000;
 pub(crate) const DEFAULT_MAXIMUM_ORPHAN_TRANSACTION_COUNT: u64 = 500;
 
+/// MAXIMUM_PACKAGE_TRANSACTION_COUNT is the maximum number of transactions of a package validated as a unit
+pub const MAXIMUM_PACKAGE_TRANSACTION_COUNT: usize = 25;
+
 /// DEFAULT_MINIMUM_RELAY_TRANSACTION_FEE specifies the minimum transaction fee for a transaction to be accepted to
 /// the mempool and relayed. It is specified in sompi per 1kg (or 1000 grams) of transaction mass.
 /// The default is 100 sompi per gram.
@@ -24,6 +24,7 @@ impl Mempool {
         let mut tx_accepted_counts = 0;
         let mut input_counts = 0;
         let mut output_counts = 0;
+        let cofactors = self.config.mempool_mass_cofactors.get(block_daa_score);
         for transaction in block_transactions[1..].iter() {
             let transaction_id = transaction.id();
             // Rust rewrite: This behavior does differ from golang implementation.
@@ -31,6 +32,11 @@ impl Mempool {
             // its redeemers in the orphan pool. We give those a chance to be unorphaned and included
             // in the next block template.
             if !self.orphan_pool.has(&transaction_id) {
+                if let Some(mempool_transaction) = self.transaction_pool.get(&transaction_id)
+                    && let Some(feerate) = mempool_transaction.mtx.calculated_feerate(&cofactors)
+                {
+                    self.inclusion_estimator.record(block_daa_score, mempool_transaction.added_at_daa_score, feerate);
+                }
                 self.remove_transaction(&transaction_id, false, TxRemovalReason::Accepted, "")?;
             }
             self.remove_double_spends(transaction)?;
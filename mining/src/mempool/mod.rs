@@ -1,21 +1,30 @@
 use crate::{
-    MiningCounters,
-    feerate::{FeerateEstimator, FeerateEstimatorArgs},
+    MiningCounters, TemplateSelectionPolicy,
+    feerate::{
+        FeerateEstimator, FeerateEstimatorArgs,
+        inclusion::{InclusionFeerateEstimate, InclusionFeerateEstimator},
+    },
     model::{
         owner_txs::{GroupedOwnerTransactions, ScriptPublicKeySet},
         tx_query::TransactionQuery,
     },
+    notification::{MempoolNotification, MempoolRemovalReason},
+    persistence::MempoolSnapshotEntry,
 };
 
 use self::{
-    config::Config,
-    model::{accepted_transactions::AcceptedTransactions, orphan_pool::OrphanPool, pool::Pool, transactions_pool::TransactionsPool},
+    config::{Config, MAXIMUM_PACKAGE_TRANSACTION_COUNT},
+    model::{
+        accepted_transactions::AcceptedTransactions, events::MempoolEvents, orphan_pool::OrphanPool, pool::Pool,
+        transactions_pool::TransactionsPool,
+    },
     tx::Priority,
+    validate_and_insert_package::PackageEvictions,
 };
 use kaspa_consensus_core::{
     block::TemplateTransactionSelector,
     config::params::ForkActivation,
-    tx::{MutableTransaction, TransactionId},
+    tx::{MutableTransaction, Transaction, TransactionId},
 };
 use kaspa_core::time::Stopwatch;
 use std::sync::Arc;
@@ -29,6 +38,7 @@ pub(crate) mod model;
 pub(crate) mod populate_entries_and_try_validate;
 pub(crate) mod remove_transaction;
 pub(crate) mod replace_by_fee;
+pub(crate) mod validate_and_insert_package;
 pub(crate) mod validate_and_insert_transaction;
 
 /// Mempool contains transactions intended to be inserted into a block and mined.
@@ -54,7 +64,11 @@ pub(crate) struct Mempool {
     transaction_pool: TransactionsPool,
     orphan_pool: OrphanPool,
     accepted_transactions: AcceptedTransactions,
+    events: MempoolEvents,
+    /// Transactions removed while a package is being inserted, restored if the package is rolled back
+    package_evictions: Option<PackageEvictions>,
     counters: Arc<MiningCounters>,
+    inclusion_estimator: InclusionFeerateEstimator,
 }
 
 impl Mempool {
@@ -62,7 +76,29 @@ impl Mempool {
         let transaction_pool = TransactionsPool::new(config.clone());
         let orphan_pool = OrphanPool::new(config.clone());
         let accepted_transactions = AcceptedTransactions::new(config.clone());
-        Self { config, toccata_activation, transaction_pool, orphan_pool, accepted_transactions, counters }
+        let events = MempoolEvents::default();
+        let inclusion_estimator = InclusionFeerateEstimator::new(config.network_blocks_per_second);
+        Self {
+            config,
+            toccata_activation,
+            transaction_pool,
+            orphan_pool,
+            accepted_transactions,
+            events,
+            package_evictions: None,
+            counters,
+            inclusion_estimator,
+        }
+    }
+
+    /// Starts or stops recording the transaction pool changes reported by [`Self::drain_events`]
+    pub(crate) fn set_events_enabled(&mut self, enabled: bool) {
+        self.events.set_enabled(enabled);
+    }
+
+    /// Drains the transaction pool changes recorded since the previous call
+    pub(crate) fn drain_events(&mut self, muted_reason: MempoolRemovalReason) -> Vec<MempoolNotification> {
+        self.events.drain(muted_reason)
     }
 
     pub(crate) fn get_transaction(&self, transaction_id: &TransactionId, query: TransactionQuery) -> Option<MutableTransaction> {
@@ -81,6 +117,10 @@ impl Mempool {
             || (query.include_orphan_pool() && self.orphan_pool.has(transaction_id))
     }
 
+    pub(crate) fn get_transaction_package(&self, transaction_id: &TransactionId) -> Option<Vec<Arc<Transaction>>> {
+        self.transaction_pool.get_transaction_package(transaction_id, MAXIMUM_PACKAGE_TRANSACTION_COUNT)
+    }
+
     pub(crate) fn get_all_transactions(&self, query: TransactionQuery) -> (Vec<MutableTransaction>, Vec<MutableTransaction>) {
         let transactions = if query.include_transaction_pool() { self.transaction_pool.get_all_transactions() } else { vec![] };
         let orphans = if query.include_orphan_pool() { self.orphan_pool.get_all_transactions() } else { vec![] };
@@ -93,6 +133,37 @@ impl Mempool {
         (transactions, orphans)
     }
 
+    /// Returns the transactions of the pools selected by `query` whose id satisfies `filter`, visiting the ids in place
+    pub(crate) fn get_transactions_by_id_filter(
+        &self,
+        query: TransactionQuery,
+        mut filter: impl FnMut(&TransactionId) -> bool,
+    ) -> Vec<Arc<Transaction>> {
+        let mut transactions = vec![];
+        if query.include_transaction_pool() {
+            transactions.extend(self.transaction_pool.all().iter().filter(|(id, _)| filter(id)).map(|(_, tx)| tx.mtx.tx.clone()));
+        }
+        if query.include_orphan_pool() {
+            transactions.extend(self.orphan_pool.all().iter().filter(|(id, _)| filter(id)).map(|(_, tx)| tx.mtx.tx.clone()));
+        }
+        transactions
+    }
+
+    /// Returns the transactions of the pools selected by `query`, along with their priority and insertion DAA score
+    pub(crate) fn get_snapshot_entries(&self, query: TransactionQuery) -> (Vec<MempoolSnapshotEntry>, Vec<MempoolSnapshotEntry>) {
+        let transactions = if query.include_transaction_pool() {
+            self.transaction_pool.all().values().map(MempoolSnapshotEntry::from).collect()
+        } else {
+            vec![]
+        };
+        let orphans = if query.include_orphan_pool() {
+            self.orphan_pool.all().values().map(MempoolSnapshotEntry::from).collect()
+        } else {
+            vec![]
+        };
+        (transactions, orphans)
+    }
+
     pub(crate) fn get_transactions_by_addresses(
         &self,
         script_public_keys: &ScriptPublicKeySet,
@@ -128,9 +199,9 @@ impl Mempool {
     }
 
     /// Dynamically builds a transaction selector based on the specific state of the ready transactions frontier
-    pub(crate) fn build_selector(&self) -> Box<dyn TemplateTransactionSelector> {
+    pub(crate) fn build_selector(&self, selection_policy: &dyn TemplateSelectionPolicy) -> Box<dyn TemplateTransactionSelector> {
         let _sw = Stopwatch::<10>::with_threshold("build_selector op");
-        self.transaction_pool.build_selector()
+        self.transaction_pool.build_selector(selection_policy)
     }
 
     /// Builds a feerate estimator based on internal state of the ready transactions frontier
@@ -138,6 +209,37 @@ impl Mempool {
         self.transaction_pool.build_feerate_estimator(args)
     }
 
+    /// Returns the feerates needed for inclusion within `targets_seconds` at `confidences`, learned from recent blocks
+    /// and from the ready transactions still waiting at `virtual_daa_score`
+    pub(crate) fn inclusion_feerate_estimations(
+        &self,
+        targets_seconds: &[f64],
+        confidences: &[f64],
+        virtual_daa_score: u64,
+    ) -> Vec<InclusionFeerateEstimate> {
+        let pending = self.transaction_pool.pending_inclusions();
+        self.inclusion_estimator.estimations(targets_seconds, confidences, virtual_daa_score, &pending)
+    }
+
+    /// Records a transaction about to be expired or evicted from the pool as an inclusion failure
+    ///
+    /// While a package is being inserted, the failure is held back until the package is committed.
+    pub(crate) fn record_inclusion_failure(&mut self, transaction_id: &TransactionId, virtual_daa_score: u64) {
+        if let Some(transaction) = self.transaction_pool.get(transaction_id) {
+            let cofactors = self.config.mempool_mass_cofactors.get(virtual_daa_score);
+            if let Some(feerate) = transaction.mtx.calculated_feerate(&cofactors) {
+                match self.package_evictions.as_mut() {
+                    Some(evictions) => evictions.inclusion_failures.push((*transaction_id, transaction.added_at_daa_score, feerate)),
+                    None => self.inclusion_estimator.record_failure(virtual_daa_score, transaction.added_at_daa_score, feerate),
+                }
+            }
+        }
+    }
+
+    pub(crate) fn inclusion_observation_count(&self) -> usize {
+        self.inclusion_estimator.observation_count()
+    }
+
     pub(crate) fn all_transaction_ids_with_priority(&self, priority: Priority) -> Vec<TransactionId> {
         let _sw = Stopwatch::<15>::with_threshold("all_transaction_ids_with_priority op");
         self.transaction_pool.all_transaction_ids_with_priority(priority)
@@ -166,10 +268,17 @@ impl Mempool {
     pub(crate) fn get_estimated_size(&self) -> usize {
         self.transaction_pool.get_estimated_size()
     }
+
+    #[cfg(test)]
+    pub(crate) fn assert_ancestor_packages_consistency(&self) {
+        self.transaction_pool.assert_ancestor_packages_consistency()
+    }
 }
 
 pub mod tx {
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
     pub enum Priority {
         /// Provenance is P2P
         Low,
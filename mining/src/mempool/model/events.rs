@@ -0,0 +1,257 @@
+use crate::{
+    mempool::model::tx::TxRemovalReason,
+    notification::{
+        MempoolNotification, MempoolRemovalReason, TransactionAddedNotification, TransactionIncludedNotification,
+        TransactionRemovedNotification, TransactionReplacedNotification,
+    },
+};
+use kaspa_consensus_core::tx::{MutableTransaction, TransactionId};
+use std::sync::Arc;
+
+/// Buffer of the transaction pool changes occurring while the mempool is write-locked
+///
+/// The buffer is drained into [`MempoolNotification`]s by the mining manager right before
+/// releasing the lock, so the notifications follow the order of the pool mutations.
+///
+/// Nothing is recorded unless enabled, which the mining manager does only while some
+/// listener is subscribed to mempool notifications.
+#[derive(Default)]
+pub(crate) struct MempoolEvents {
+    enabled: bool,
+    events: Vec<MempoolEvent>,
+}
+
+enum MempoolEvent {
+    Added(MutableTransaction),
+    Included(MutableTransaction),
+    /// Removal whose reason is `None` when made with [`TxRemovalReason::Muted`]
+    Removed(Option<MempoolRemovalReason>, MutableTransaction),
+    Replaced(MutableTransaction, Vec<MutableTransaction>),
+}
+
+impl MempoolEvents {
+    /// Starts or stops recording, discarding any event recorded so far when stopping
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.events.clear();
+        }
+    }
+
+    pub(crate) fn record_added(&mut self, transaction: &MutableTransaction) {
+        if !self.enabled {
+            return;
+        }
+        self.events.push(MempoolEvent::Added(transaction.clone()));
+    }
+
+    /// Records the replacement of some mempool transactions by `replacing`
+    ///
+    /// The replaced transactions are the ones removed with [`TxRemovalReason::ReplacedByFee`] right after.
+    pub(crate) fn record_replacing(&mut self, replacing: &MutableTransaction) {
+        if !self.enabled {
+            return;
+        }
+        self.events.push(MempoolEvent::Replaced(replacing.clone(), vec![]));
+    }
+
+    pub(crate) fn record_removed(&mut self, reason: TxRemovalReason, transaction: &MutableTransaction) {
+        if !self.enabled {
+            return;
+        }
+        let transaction = transaction.clone();
+        match reason {
+            TxRemovalReason::Accepted => self.events.push(MempoolEvent::Included(transaction)),
+            TxRemovalReason::ReplacedByFee => match self.events.last_mut() {
+                Some(MempoolEvent::Replaced(_, replaced)) => replaced.push(transaction),
+                // A replacement is always recorded first, so this is not expected
+                _ => self.events.push(MempoolEvent::Removed(Some(MempoolRemovalReason::Evicted), transaction)),
+            },
+            TxRemovalReason::Muted => self.events.push(MempoolEvent::Removed(None, transaction)),
+            _ => self.events.push(MempoolEvent::Removed(Some(MempoolRemovalReason::Evicted), transaction)),
+        }
+    }
+
+    /// Forgets the last addition of a transaction which was rolled back before the lock got released
+    pub(crate) fn discard_added(&mut self, transaction_id: TransactionId) {
+        if let Some(position) = self.events.iter().rposition(|x| matches!(x, MempoolEvent::Added(tx) if tx.id() == transaction_id)) {
+            self.events.remove(position);
+        }
+    }
+
+    /// Forgets the last removal of a transaction which was restored before the lock got released
+    pub(crate) fn discard_removed(&mut self, transaction_id: TransactionId) {
+        if let Some(position) =
+            self.events.iter().rposition(|x| matches!(x, MempoolEvent::Removed(_, tx) if tx.id() == transaction_id))
+        {
+            self.events.remove(position);
+        }
+    }
+
+    /// Drains the recorded events into notifications
+    ///
+    /// Consecutive events of a same kind are batched into a single notification.
+    ///
+    /// `muted_reason` qualifies the removals made with [`TxRemovalReason::Muted`], whose actual
+    /// reason is only known by the caller.
+    pub(crate) fn drain(&mut self, muted_reason: MempoolRemovalReason) -> Vec<MempoolNotification> {
+        if !self.enabled {
+            return vec![];
+        }
+
+        let mut notifications = vec![];
+        for event in self.events.drain(..) {
+            match (event, notifications.last_mut()) {
+                (MempoolEvent::Added(tx), Some(MempoolNotification::TransactionAdded(last))) => {
+                    Self::batch(&mut last.transactions, tx)
+                }
+                (MempoolEvent::Added(tx), _) => notifications
+                    .push(MempoolNotification::TransactionAdded(TransactionAddedNotification { transactions: Arc::new(vec![tx]) })),
+                (MempoolEvent::Included(tx), Some(MempoolNotification::TransactionIncluded(last))) => {
+                    Self::batch(&mut last.transactions, tx)
+                }
+                (MempoolEvent::Included(tx), _) => {
+                    notifications.push(MempoolNotification::TransactionIncluded(TransactionIncludedNotification {
+                        transactions: Arc::new(vec![tx]),
+                    }))
+                }
+                (MempoolEvent::Removed(reason, tx), last) => {
+                    let reason = reason.unwrap_or(muted_reason);
+                    match last {
+                        Some(MempoolNotification::TransactionRemoved(last)) if last.reason == reason => {
+                            Self::batch(&mut last.transactions, tx)
+                        }
+                        _ => notifications.push(MempoolNotification::TransactionRemoved(TransactionRemovedNotification {
+                            reason,
+                            transactions: Arc::new(vec![tx]),
+                        })),
+                    }
+                }
+                (MempoolEvent::Replaced(_, replaced), _) if replaced.is_empty() => {}
+                (MempoolEvent::Replaced(replacing, replaced), _) => {
+                    notifications.push(MempoolNotification::TransactionReplaced(TransactionReplacedNotification {
+                        replacing,
+                        replaced: Arc::new(replaced),
+                    }))
+                }
+            }
+        }
+        notifications
+    }
+
+    fn batch(transactions: &mut Arc<Vec<MutableTransaction>>, transaction: MutableTransaction) {
+        Arc::make_mut(transactions).push(transaction);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{subnets::SUBNETWORK_ID_NATIVE, tx::Transaction};
+
+    fn tx(lock_time: u64) -> MutableTransaction {
+        MutableTransaction::from_tx(Transaction::new(0, vec![], vec![], lock_time, SUBNETWORK_ID_NATIVE, 0, vec![]))
+    }
+
+    fn ids(transactions: &[MutableTransaction]) -> Vec<TransactionId> {
+        transactions.iter().map(|x| x.id()).collect()
+    }
+
+    #[test]
+    fn test_mempool_events_drain() {
+        let mut events = MempoolEvents::default();
+        events.record_added(&tx(0));
+        assert!(events.drain(MempoolRemovalReason::Evicted).is_empty(), "a disabled buffer must record nothing");
+
+        events.set_enabled(true);
+        let (a, b, c, d, e) = (tx(1), tx(2), tx(3), tx(4), tx(5));
+        events.record_removed(TxRemovalReason::Accepted, &a);
+        events.record_replacing(&c);
+        events.record_removed(TxRemovalReason::ReplacedByFee, &b);
+        events.record_added(&c);
+        events.record_removed(TxRemovalReason::Muted, &d);
+        events.record_removed(TxRemovalReason::DoubleSpend, &e);
+
+        let notifications = events.drain(MempoolRemovalReason::Expired);
+        assert_eq!(notifications.len(), 5);
+        match &notifications[0] {
+            MempoolNotification::TransactionIncluded(n) => assert_eq!(ids(&n.transactions), ids(&[a])),
+            n => panic!("unexpected notification {n:?}"),
+        }
+        match &notifications[1] {
+            MempoolNotification::TransactionReplaced(n) => {
+                assert_eq!(n.replacing.id(), c.id());
+                assert_eq!(ids(&n.replaced), ids(&[b]));
+            }
+            n => panic!("unexpected notification {n:?}"),
+        }
+        match &notifications[2] {
+            MempoolNotification::TransactionAdded(n) => assert_eq!(ids(&n.transactions), ids(&[c])),
+            n => panic!("unexpected notification {n:?}"),
+        }
+        match &notifications[3] {
+            MempoolNotification::TransactionRemoved(n) => {
+                assert_eq!(n.reason, MempoolRemovalReason::Expired);
+                assert_eq!(ids(&n.transactions), ids(&[d]));
+            }
+            n => panic!("unexpected notification {n:?}"),
+        }
+        match &notifications[4] {
+            MempoolNotification::TransactionRemoved(n) => {
+                assert_eq!(n.reason, MempoolRemovalReason::Evicted);
+                assert_eq!(ids(&n.transactions), ids(&[e]));
+            }
+            n => panic!("unexpected notification {n:?}"),
+        }
+        assert!(events.drain(MempoolRemovalReason::Evicted).is_empty(), "draining must empty the buffer");
+
+        events.record_added(&tx(6));
+        events.set_enabled(false);
+        assert!(events.drain(MempoolRemovalReason::Evicted).is_empty(), "disabling must discard the recorded events");
+    }
+
+    #[test]
+    fn test_mempool_events_add_then_remove() {
+        let mut events = MempoolEvents::default();
+        events.set_enabled(true);
+        let (a, b, c) = (tx(1), tx(2), tx(3));
+        events.record_added(&a);
+        events.record_added(&b);
+        events.record_removed(TxRemovalReason::DoubleSpend, &a);
+        events.record_removed(TxRemovalReason::Muted, &b);
+        events.record_added(&c);
+
+        let notifications = events.drain(MempoolRemovalReason::Evicted);
+        assert_eq!(notifications.len(), 3, "consecutive events of a same kind must be batched");
+        match &notifications[0] {
+            MempoolNotification::TransactionAdded(n) => assert_eq!(ids(&n.transactions), ids(&[a.clone(), b.clone()])),
+            n => panic!("unexpected notification {n:?}"),
+        }
+        match &notifications[1] {
+            MempoolNotification::TransactionRemoved(n) => {
+                assert_eq!(n.reason, MempoolRemovalReason::Evicted);
+                assert_eq!(ids(&n.transactions), ids(&[a, b]));
+            }
+            n => panic!("unexpected notification {n:?}"),
+        }
+        match &notifications[2] {
+            MempoolNotification::TransactionAdded(n) => assert_eq!(ids(&n.transactions), ids(&[c])),
+            n => panic!("unexpected notification {n:?}"),
+        }
+    }
+
+    #[test]
+    fn test_mempool_events_discard() {
+        let mut events = MempoolEvents::default();
+        events.set_enabled(true);
+        let (a, b, c) = (tx(1), tx(2), tx(3));
+        events.record_added(&a);
+        events.record_added(&b);
+        events.record_removed(TxRemovalReason::MakingRoom, &c);
+        events.discard_added(b.id());
+        events.discard_added(a.id());
+        events.discard_removed(c.id());
+
+        assert!(events.drain(MempoolRemovalReason::Evicted).is_empty(), "rolled back changes must not be notified");
+    }
+}
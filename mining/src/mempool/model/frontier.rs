@@ -430,6 +430,18 @@ impl Frontier {
     pub fn ascending_iter(&self) -> impl DoubleEndedIterator<Item = &Arc<Transaction>> + ExactSizeIterator + FusedIterator {
         self.search_tree.ascending_iter().map(|key| &key.tx)
     }
+
+    /// Returns an iterator to the keys of the frontier in increasing feerate order
+    pub(crate) fn ascending_key_iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &FeerateTransactionKey> + ExactSizeIterator + FusedIterator {
+        self.search_tree.ascending_iter()
+    }
+
+    /// Returns an iterator to the keys of a single lane of the frontier in decreasing feerate order
+    pub(crate) fn lane_descending_key_iter(&self, lane: &SubnetworkId) -> impl Iterator<Item = &FeerateTransactionKey> {
+        self.by_lane.get(lane).into_iter().flat_map(|keys| keys.iter().rev())
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,4 @@
-use crate::mempool::model::{frontier::selectors::ALPHA, tx::MempoolTransaction};
-use kaspa_consensus_core::mass::{ContextualMasses, Mass, MassCofactors};
+use crate::mempool::model::{frontier::selectors::ALPHA, package::Package, tx::MempoolTransaction};
 use kaspa_consensus_core::subnets::SubnetworkId;
 use kaspa_consensus_core::tx::Transaction;
 use std::sync::Arc;
@@ -8,6 +7,10 @@ use std::sync::Arc;
 pub struct FeerateTransactionKey {
     pub fee: u64,
     pub mass: u64,
+    /// Fee of the package prioritizing the transaction, which includes descendants paying for it (CPFP)
+    package_fee: u64,
+    /// Mass of the package prioritizing the transaction
+    package_mass: u64,
     weight: f64,
     pub tx: Arc<Transaction>,
 }
@@ -22,17 +25,31 @@ impl PartialEq for FeerateTransactionKey {
 
 impl FeerateTransactionKey {
     pub fn new(fee: u64, mass: u64, tx: Arc<Transaction>) -> Self {
+        Self::with_package(fee, mass, fee, mass, tx)
+    }
+
+    /// Builds a key prioritized by the feerate of a package (`package_fee`, `package_mass`) the transaction
+    /// is part of, typically the transaction and a descendant paying for it. The `fee` and `mass` of the
+    /// transaction itself remain the ones accounted for in block templates.
+    pub fn with_package(fee: u64, mass: u64, package_fee: u64, package_mass: u64, tx: Arc<Transaction>) -> Self {
         // NOTE: any change to the way this weight is calculated (such as scaling by some factor)
         // requires a reversed update to total_weight in `Frontier::build_feerate_estimator`. This
         // is because the math methods in FeeEstimator assume this specific weight function.
         //
         // Gas is intentionally not folded into this global weight: gas capacity is lane-local and
         // is enforced by selectors during block template construction.
-        Self { fee, mass, weight: (fee as f64 / mass as f64).powi(ALPHA), tx }
+        let weight = (package_fee as f64 / package_mass as f64).powi(ALPHA);
+        Self { fee, mass, package_fee, package_mass, weight, tx }
     }
 
+    /// The effective feerate of the transaction, i.e., the feerate of its prioritizing package
     pub fn feerate(&self) -> f64 {
-        self.fee as f64 / self.mass as f64
+        self.package_fee as f64 / self.package_mass as f64
+    }
+
+    /// The package prioritizing the transaction
+    pub(crate) fn package(&self) -> Package {
+        Package::new(self.package_fee, self.package_mass)
     }
 
     pub fn weight(&self) -> f64 {
@@ -68,6 +85,10 @@ impl Ord for FeerateTransactionKey {
         }
 
         // If feerates (and thus weights) are equal, prefer the higher fee in absolute value
+        match self.package_fee.cmp(&other.package_fee) {
+            core::cmp::Ordering::Equal => {}
+            ord => return ord,
+        }
         match self.fee.cmp(&other.fee) {
             core::cmp::Ordering::Equal => {}
             ord => return ord,
@@ -75,7 +96,8 @@ impl Ord for FeerateTransactionKey {
 
         //
         // At this point we don't compare the mass fields since if both feerate
-        // and fee are equal, mass must be equal as well
+        // and fee are equal, mass must be equal as well (for the package and the
+        // transaction alike)
         //
 
         // Finally, we compare transaction ids in order to allow multiple transactions with
@@ -85,18 +107,9 @@ impl Ord for FeerateTransactionKey {
 }
 
 impl FeerateTransactionKey {
-    pub(crate) fn from_tx(tx: &MempoolTransaction, cofactors: &MassCofactors) -> Self {
-        // NOTE: The code below is a mempool simplification reducing the various block mass units to a
-        //       single one-dimension value (making it easier to select transactions for block templates).
-        // Future mempool improvements are expected to refine this behavior and use the multi-dimension values
-        // in order to optimize and increase block space usage.
-        let mass = Mass::new(
-            tx.mtx.calculated_non_contextual_masses.expect("masses are expected to be calculated"),
-            ContextualMasses::new(tx.mtx.tx.storage_mass()),
-        )
-        .normalized_max(cofactors);
-        let fee = tx.mtx.calculated_fee.expect("fee is expected to be populated");
-        Self::new(fee, mass, tx.mtx.tx.clone())
+    /// Builds the key of `tx`, whose own fee and mass are `own`, prioritized by `package`
+    pub(crate) fn from_package(tx: &MempoolTransaction, own: Package, package: Package) -> Self {
+        Self::with_package(own.fee, own.mass, package.fee, package.mass, tx.mtx.tx.clone())
     }
 }
 
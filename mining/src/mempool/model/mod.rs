@@ -1,7 +1,9 @@
 pub(crate) mod accepted_transactions;
+pub(crate) mod events;
 pub(crate) mod frontier;
 pub(crate) mod map;
 pub(crate) mod orphan_pool;
+pub(crate) mod package;
 pub(crate) mod pool;
 pub(crate) mod transactions_pool;
 pub(crate) mod tx;
@@ -62,6 +62,7 @@ impl OrphanPool {
     pub(crate) fn try_add_orphan(
         &mut self,
         virtual_daa_score: u64,
+        added_at_daa_score: u64,
         transaction: MutableTransaction,
         priority: Priority,
     ) -> RuleResult<()> {
@@ -75,7 +76,7 @@ impl OrphanPool {
         self.check_orphan_double_spend(&transaction)?;
         // Make sure there is room in the pool for the new transaction
         self.limit_orphan_pool_size(1)?;
-        self.add_orphan(virtual_daa_score, transaction, priority)?;
+        self.add_orphan(added_at_daa_score, transaction, priority)?;
         Ok(())
     }
 
@@ -125,9 +126,14 @@ impl OrphanPool {
         Ok(())
     }
 
-    fn add_orphan(&mut self, virtual_daa_score: u64, transaction: MutableTransaction, priority: Priority) -> RuleResult<()> {
+    /// Puts back an orphan removed from the pool, bypassing the orphan checks it already passed
+    pub(crate) fn restore_orphan(&mut self, transaction: MempoolTransaction) -> RuleResult<()> {
+        self.add_orphan(transaction.added_at_daa_score, transaction.mtx, transaction.priority)
+    }
+
+    fn add_orphan(&mut self, added_at_daa_score: u64, transaction: MutableTransaction, priority: Priority) -> RuleResult<()> {
         let id = transaction.id();
-        let transaction = MempoolTransaction::new(transaction, priority, virtual_daa_score);
+        let transaction = MempoolTransaction::new(transaction, priority, added_at_daa_score);
         // Add all entries in outpoint_owner_id
         for input in transaction.mtx.tx.inputs.iter() {
             self.outpoint_owner_id.insert(input.previous_outpoint, id);
@@ -0,0 +1,65 @@
+use crate::mempool::model::tx::MempoolTransaction;
+use kaspa_consensus_core::mass::{ContextualMasses, Mass, MassCofactors};
+use std::ops::{AddAssign, SubAssign};
+
+/// Aggregated fee and normalized mass of a set of mempool transactions.
+///
+/// The transaction pool tracks, for every transaction, the package made of the transaction and all its
+/// unconfirmed ancestors. The feerate of this ancestor package is what a child paying for its parents
+/// (CPFP) brings to the table, so frontier transactions are prioritized by the best ancestor package
+/// they are part of.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Package {
+    pub fee: u64,
+    pub mass: u64,
+}
+
+impl Package {
+    pub(crate) fn new(fee: u64, mass: u64) -> Self {
+        Self { fee, mass }
+    }
+
+    /// Builds the single transaction package of `tx`
+    pub(crate) fn from_tx(tx: &MempoolTransaction, cofactors: &MassCofactors) -> Self {
+        // NOTE: The code below is a mempool simplification reducing the various block mass units to a
+        //       single one-dimension value (making it easier to select transactions for block templates).
+        // Future mempool improvements are expected to refine this behavior and use the multi-dimension values
+        // in order to optimize and increase block space usage.
+        let mass = Mass::new(
+            tx.mtx.calculated_non_contextual_masses.expect("masses are expected to be calculated"),
+            ContextualMasses::new(tx.mtx.tx.storage_mass()),
+        )
+        .normalized_max(cofactors);
+        let fee = tx.mtx.calculated_fee.expect("fee is expected to be populated");
+        Self::new(fee, mass)
+    }
+}
+
+impl AddAssign for Package {
+    fn add_assign(&mut self, rhs: Self) {
+        self.fee += rhs.fee;
+        self.mass += rhs.mass;
+    }
+}
+
+impl SubAssign for Package {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.fee -= rhs.fee;
+        self.mass -= rhs.mass;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_aggregation() {
+        let mut package = Package::default();
+        package += Package::new(1_000, 2_000);
+        package += Package::new(100_000, 1_000);
+        assert_eq!(Package::new(101_000, 3_000), package);
+        package -= Package::new(1_000, 2_000);
+        assert_eq!(Package::new(100_000, 1_000), package);
+    }
+}
@@ -1,26 +1,28 @@
 use crate::{
-    Policy,
-    feerate::{FeerateEstimator, FeerateEstimatorArgs},
+    CandidateVerdict, Policy, SelectionCandidate, TemplateSelectionPolicy,
+    block_template::selection::PolicySelectorBuilder,
+    feerate::{FeerateEstimator, FeerateEstimatorArgs, inclusion::PendingInclusion},
     mempool::{
         config::Config,
         errors::{RuleError, RuleResult},
         model::{
             map::MempoolTransactionCollection,
+            package::Package,
             pool::{Pool, TransactionsEdges},
             tx::{DoubleSpend, MempoolTransaction},
             utxo_set::MempoolUtxoSet,
         },
         tx::Priority,
     },
-    model::{TransactionIdSet, topological_index::TopologicalIndex},
+    model::{TransactionIdSet, topological_index::TopologicalIndex, topological_sort::TopologicalSort},
 };
 use kaspa_consensus_core::{
     block::TemplateTransactionSelector,
-    tx::{MutableTransaction, TransactionId, TransactionOutpoint},
+    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint},
 };
 use kaspa_core::{debug, time::unix_now, trace};
 use std::{
-    collections::{hash_map::Keys, hash_set::Iter},
+    collections::{BTreeSet, HashMap, VecDeque, hash_map::Keys, hash_set::Iter},
     iter::once,
     sync::Arc,
 };
@@ -28,6 +30,23 @@ use std::{
 use super::frontier::Frontier;
 use super::frontier::feerate_key::FeerateTransactionKey;
 
+/// Maximum number of mempool ancestors a transaction may have for paying for them (CPFP).
+///
+/// Bounds the cost of maintaining the packages prioritizing the ready transactions.
+pub(crate) const MAXIMUM_PACKAGE_ANCESTORS: usize = 25;
+
+/// The ancestor package of a transaction eligible for paying for its ancestors
+struct AncestorPackage {
+    /// Key of the transaction prioritized by the package made of the transaction and all its mempool ancestors
+    key: FeerateTransactionKey,
+
+    /// Mempool ancestors of the transaction
+    ancestors: TransactionIdSet,
+
+    /// Ready transactions of the package, which the package is competing to prioritize
+    ready_ancestors: Vec<TransactionId>,
+}
+
 /// Pool of transactions to be included in a block template
 ///
 /// ### Rust rewrite notes
@@ -68,6 +87,15 @@ pub(crate) struct TransactionsPool {
     /// Transactions with no parents in the mempool -- ready to be inserted into a block template
     ready_transactions: Frontier,
 
+    /// Ancestor packages of the transactions having at most [`MAXIMUM_PACKAGE_ANCESTORS`] ancestors
+    ancestor_packages: HashMap<TransactionId, AncestorPackage>,
+
+    /// Ancestor packages competing to prioritize each ready transaction, keyed by the package feerate.
+    ///
+    /// A ready transaction always competes with its own single transaction package. The frontier key of
+    /// a ready transaction is prioritized by the best package of its set.
+    ready_packages: HashMap<TransactionId, BTreeSet<FeerateTransactionKey>>,
+
     last_expire_scan_daa_score: u64,
 
     /// last expire scan time in milliseconds
@@ -89,6 +117,8 @@ impl TransactionsPool {
             parent_transactions: TransactionsEdges::default(),
             chained_transactions: TransactionsEdges::default(),
             ready_transactions: Frontier::new(target_time_per_block),
+            ancestor_packages: HashMap::default(),
+            ready_packages: HashMap::default(),
             last_expire_scan_daa_score: 0,
             last_expire_scan_time: unix_now(),
             utxo_set: MempoolUtxoSet::new(),
@@ -122,25 +152,172 @@ impl TransactionsPool {
         // The transactions chained to the added transaction cannot be stored
         // here yet since, by definition, they would have been orphans.
         let parents = self.get_parent_transaction_ids_in_pool(&transaction.mtx);
-        self.parent_transactions.insert(id, parents.clone());
-        if parents.is_empty() {
-            let cofactors = self.config.mempool_mass_cofactors.get(transaction.added_at_daa_score);
-            self.ready_transactions.insert(FeerateTransactionKey::from_tx(&transaction, &cofactors));
-        }
-        for parent_id in parents {
-            let entry = self.chained_transactions.entry(parent_id).or_default();
+        for parent_id in parents.iter() {
+            let entry = self.chained_transactions.entry(*parent_id).or_default();
             entry.insert(id);
         }
+        self.parent_transactions.insert(id, parents);
 
         self.utxo_set.add_transaction(&transaction.mtx);
         self.estimated_size += transaction_size;
         self.all_transactions.insert(id, transaction);
+
+        // Let the transaction compete to prioritize its ready ancestors, or itself if it has no parents.
+        // The transactions chained to the added transaction are orphans so no other package is affected.
+        self.register_ancestor_package(id);
         trace!("Added transaction {}", id);
         Ok(())
     }
 
+    fn is_ready(&self, transaction_id: &TransactionId) -> bool {
+        self.parent_transactions.get(transaction_id).is_some_and(|parents| parents.is_empty())
+    }
+
+    /// Returns the ids of all the ancestors of `transaction_id` existing in the pool, or `None`
+    /// if there are more than `limit` of them.
+    fn get_ancestor_ids_in_pool(&self, transaction_id: &TransactionId, limit: usize) -> Option<TransactionIdSet> {
+        let mut ancestors = TransactionIdSet::new();
+        let mut stack = vec![*transaction_id];
+        while let Some(id) = stack.pop() {
+            for parent_id in self.parent_transactions.get(&id).into_iter().flatten() {
+                if ancestors.insert(*parent_id) {
+                    if ancestors.len() > limit {
+                        return None;
+                    }
+                    stack.push(*parent_id);
+                }
+            }
+        }
+        Some(ancestors)
+    }
+
+    /// Returns the transaction along with all its ancestors existing in the pool, in topological order,
+    /// or `None` if the transaction is missing or if the package would exceed `limit` transactions.
+    pub(crate) fn get_transaction_package(&self, transaction_id: &TransactionId, limit: usize) -> Option<Vec<Arc<Transaction>>> {
+        let transaction = self.get(transaction_id)?;
+        let ancestors = self.get_ancestor_ids_in_pool(transaction_id, limit.checked_sub(1)?)?;
+        let package = ancestors
+            .iter()
+            .filter_map(|id| self.get(id))
+            .chain(once(transaction))
+            .map(|x| x.mtx.tx.clone())
+            .collect::<Vec<_>>()
+            .topological_sort();
+        Some(package)
+    }
+
+    /// Returns the ids of the transactions chained to `transaction_id` at most `depth` generations apart
+    fn get_redeemer_ids_within_depth(&self, transaction_id: &TransactionId, depth: usize) -> Vec<TransactionId> {
+        let mut visited = TransactionIdSet::new();
+        let mut redeemers = vec![];
+        let mut queue = VecDeque::from([(*transaction_id, 0)]);
+        while let Some((id, generation)) = queue.pop_front() {
+            if generation == depth {
+                continue;
+            }
+            for redeemer_id in self.chained_transactions.get(&id).into_iter().flatten() {
+                if visited.insert(*redeemer_id) {
+                    redeemers.push(*redeemer_id);
+                    queue.push_back((*redeemer_id, generation + 1));
+                }
+            }
+        }
+        redeemers
+    }
+
+    fn own_package(&self, transaction: &MempoolTransaction) -> Package {
+        let cofactors = self.config.mempool_mass_cofactors.get(transaction.added_at_daa_score);
+        Package::from_tx(transaction, &cofactors)
+    }
+
+    /// Builds the frontier key of a ready transaction, prioritized by the best package competing for it
+    fn ready_key(&self, transaction_id: &TransactionId) -> Option<FeerateTransactionKey> {
+        let best = self.ready_packages.get(transaction_id)?.last()?;
+        let transaction = self.all_transactions.get(transaction_id)?;
+        Some(FeerateTransactionKey::from_package(transaction, self.own_package(transaction), best.package()))
+    }
+
+    /// Applies `op` to the packages competing for the ready transaction `transaction_id`, keeping
+    /// its frontier key in sync with the best package.
+    fn update_ready_packages(&mut self, transaction_id: &TransactionId, op: impl FnOnce(&mut BTreeSet<FeerateTransactionKey>)) {
+        if let Some(key) = self.ready_key(transaction_id) {
+            self.ready_transactions.remove(&key);
+        }
+        let packages = self.ready_packages.entry(*transaction_id).or_default();
+        op(packages);
+        if packages.is_empty() {
+            self.ready_packages.remove(transaction_id);
+        } else if let Some(key) = self.ready_key(transaction_id) {
+            self.ready_transactions.insert(key);
+        }
+    }
+
+    /// Registers the ancestor package of `transaction_id` as a competitor for prioritizing its ready ancestors.
+    ///
+    /// A ready transaction has no ancestors so its package is registered for prioritizing itself, which
+    /// also inserts it in the frontier.
+    fn register_ancestor_package(&mut self, transaction_id: TransactionId) {
+        let Some(ancestors) = self.get_ancestor_ids_in_pool(&transaction_id, MAXIMUM_PACKAGE_ANCESTORS) else {
+            return;
+        };
+        let transaction = self.all_transactions.get(&transaction_id).unwrap();
+        let own = self.own_package(transaction);
+        let mut package = own;
+        for ancestor_id in ancestors.iter() {
+            package += self.own_package(self.all_transactions.get(ancestor_id).unwrap());
+        }
+        let key = FeerateTransactionKey::from_package(transaction, own, package);
+        let ready_ancestors = ancestors.iter().copied().chain(once(transaction_id)).filter(|id| self.is_ready(id)).collect();
+        self.insert_ancestor_package(transaction_id, AncestorPackage { key, ancestors, ready_ancestors });
+    }
+
+    fn insert_ancestor_package(&mut self, transaction_id: TransactionId, package: AncestorPackage) {
+        for ready_id in package.ready_ancestors.iter() {
+            self.update_ready_packages(ready_id, |packages| {
+                packages.insert(package.key.clone());
+            });
+        }
+        self.ancestor_packages.insert(transaction_id, package);
+    }
+
+    /// Withdraws the ancestor package of `transaction_id` from the competition for its ready ancestors
+    fn take_ancestor_package(&mut self, transaction_id: &TransactionId) -> Option<AncestorPackage> {
+        let package = self.ancestor_packages.remove(transaction_id)?;
+        for ready_id in package.ready_ancestors.iter() {
+            self.update_ready_packages(ready_id, |packages| {
+                packages.remove(&package.key);
+            });
+        }
+        Some(package)
+    }
+
     /// Fully removes the transaction from all relational sets, as well as from the UTXO set
+    ///
+    /// Only the packages of the redeemers spending the transaction are updated, by removing the transaction
+    /// from them rather than by rebuilding them. The cost of a removal is thus linear in the number of
+    /// redeemers within [`MAXIMUM_PACKAGE_ANCESTORS`] generations, so removing a transaction along with its
+    /// redeemers is best done in reversed topological order.
     pub(crate) fn remove_transaction(&mut self, transaction_id: &TransactionId) -> RuleResult<MempoolTransaction> {
+        let removed_package = match self.all_transactions.get(transaction_id) {
+            Some(transaction) => self.own_package(transaction),
+            None => return Err(RuleError::RejectMissingTransaction(*transaction_id)),
+        };
+
+        // Withdraw the packages about to change while the transactions they compete for are all in the pool.
+        // A redeemer having at most MAXIMUM_PACKAGE_ANCESTORS ancestors is at most as many generations apart,
+        // and one generation more is enough for a redeemer to get few enough ancestors once the transaction
+        // is removed.
+        self.take_ancestor_package(transaction_id);
+        let mut redeemer_packages = vec![];
+        let mut ineligible_redeemers = vec![];
+        for id in self.get_redeemer_ids_within_depth(transaction_id, MAXIMUM_PACKAGE_ANCESTORS + 1) {
+            match self.take_ancestor_package(&id) {
+                Some(package) => redeemer_packages.push((id, package)),
+                None => ineligible_redeemers.push(id),
+            }
+        }
+        debug_assert!(!self.ready_packages.contains_key(transaction_id), "the transaction only competes for itself");
+
         // Remove all bijective parent/chained relations
         if let Some(parents) = self.parent_transactions.get(transaction_id) {
             for parent in parents.iter() {
@@ -149,26 +326,35 @@ impl TransactionsPool {
                 }
             }
         }
-        if let Some(chains) = self.chained_transactions.get(transaction_id) {
-            for chain in chains.iter() {
-                if let Some(parents) = self.parent_transactions.get_mut(chain) {
-                    parents.remove(transaction_id);
-                    if parents.is_empty() {
-                        let tx = self.all_transactions.get(chain).unwrap();
-                        let cofactors = self.config.mempool_mass_cofactors.get(tx.added_at_daa_score);
-                        self.ready_transactions.insert(FeerateTransactionKey::from_tx(tx, &cofactors));
-                    }
-                }
+        let chains = self.chained_transactions.remove(transaction_id).unwrap_or_default();
+        for chain in chains.iter() {
+            if let Some(parents) = self.parent_transactions.get_mut(chain) {
+                parents.remove(transaction_id);
             }
         }
         self.parent_transactions.remove(transaction_id);
-        self.chained_transactions.remove(transaction_id);
 
         // Remove the transaction itself
-        let removed_tx = self.all_transactions.remove(transaction_id).ok_or(RuleError::RejectMissingTransaction(*transaction_id))?;
-
-        let cofactors = self.config.mempool_mass_cofactors.get(removed_tx.added_at_daa_score);
-        self.ready_transactions.remove(&FeerateTransactionKey::from_tx(&removed_tx, &cofactors));
+        let removed_tx = self.all_transactions.remove(transaction_id).unwrap();
+
+        // Register the updated packages, letting them compete for the redeemers becoming ready, which also
+        // inserts these in the frontier
+        let unblocked = chains.into_iter().filter(|id| self.is_ready(id)).collect::<Vec<_>>();
+        for (id, mut package) in redeemer_packages {
+            let mut aggregate = package.key.package();
+            aggregate -= removed_package;
+            let transaction = self.all_transactions.get(&id).unwrap();
+            package.key = FeerateTransactionKey::from_package(transaction, self.own_package(transaction), aggregate);
+            package.ancestors.remove(transaction_id);
+            package.ready_ancestors.retain(|ready_id| ready_id != transaction_id);
+            package
+                .ready_ancestors
+                .extend(unblocked.iter().copied().filter(|ready_id| *ready_id == id || package.ancestors.contains(ready_id)));
+            self.insert_ancestor_package(id, package);
+        }
+        for id in ineligible_redeemers {
+            self.register_ancestor_package(id);
+        }
 
         // TODO: consider using `self.parent_transactions.get(transaction_id)`
         // The tradeoff to consider is whether it might be possible that a parent tx exists in the pool
@@ -199,6 +385,19 @@ impl TransactionsPool {
         }
     }
 
+    /// Returns the ready transactions, which could be included right away, with their own feerate
+    pub(crate) fn pending_inclusions(&self) -> Vec<PendingInclusion> {
+        self.all_transactions
+            .values()
+            .filter(|transaction| self.is_ready(&transaction.id()))
+            .filter_map(|transaction| {
+                let cofactors = self.config.mempool_mass_cofactors.get(transaction.added_at_daa_score);
+                let feerate = transaction.mtx.calculated_feerate(&cofactors)?;
+                Some(PendingInclusion { added_at_daa_score: transaction.added_at_daa_score, feerate })
+            })
+            .collect()
+    }
+
     pub(crate) fn ready_transaction_count(&self) -> usize {
         self.ready_transactions.len()
     }
@@ -207,11 +406,40 @@ impl TransactionsPool {
         self.ready_transactions.total_mass()
     }
 
-    /// Dynamically builds a transaction selector based on the specific state of the ready transactions frontier
-    pub(crate) fn build_selector(&self) -> Box<dyn TemplateTransactionSelector> {
-        self.ready_transactions
-            // Params::mempool_block_mass_cofactors asserts that the reference mass is stable across activation.
-            .build_selector(&Policy::new(self.config.mempool_mass_cofactors.after().reference, self.config.block_lane_limits))
+    /// Dynamically builds a transaction selector based on the specific state of the ready transactions frontier,
+    /// applying the prioritizations, exclusions and lane quotas of `selection_policy`
+    ///
+    /// Ancestor packages are not selected as a whole: consensus forbids a block from including a transaction
+    /// along with one it spends, so only the ready transactions of the frontier are selectable. A package rather
+    /// boosts its ready ancestors to the package feerate, and once these are mined the next generation becomes
+    /// ready and competes with its own package.
+    pub(crate) fn build_selector(&self, selection_policy: &dyn TemplateSelectionPolicy) -> Box<dyn TemplateTransactionSelector> {
+        // Params::mempool_block_mass_cofactors asserts that the reference mass is stable across activation.
+        let policy = Policy::new(self.config.mempool_mass_cofactors.after().reference, self.config.block_lane_limits);
+        let quotas = selection_policy.subnetwork_quotas();
+        if !selection_policy.classifies_candidates() && quotas.is_empty() {
+            return self.ready_transactions.build_selector(&policy);
+        }
+
+        let mut builder = PolicySelectorBuilder::new(&policy, quotas);
+        if selection_policy.classifies_candidates() {
+            // Visit the frontier best-first so that prioritized transactions compete by feerate among themselves
+            for key in self.ready_transactions.ascending_key_iter().rev() {
+                let Some(transaction) = self.all_transactions.get(&key.tx.id()) else { continue };
+                let candidate = SelectionCandidate { transaction: &transaction.mtx, mass: key.mass, fee: key.fee };
+                match selection_policy.classify(&candidate) {
+                    CandidateVerdict::Feerate => {}
+                    CandidateVerdict::Prioritize => {
+                        builder.prioritize(key);
+                    }
+                    CandidateVerdict::Exclude => builder.exclude(key.tx.id()),
+                }
+            }
+        }
+        for quota in quotas {
+            builder.reserve(quota, self.ready_transactions.lane_descending_key_iter(&quota.subnetwork_id));
+        }
+        builder.build(|remaining_policy| self.ready_transactions.build_selector(remaining_policy))
     }
 
     /// Builds a feerate estimator based on internal state of the ready transactions frontier
@@ -220,9 +448,13 @@ impl TransactionsPool {
     }
 
     /// Returns the exceeding low-priority transactions having the lowest fee rates in order
-    /// to make room for `transaction`. The returned transactions
-    /// are guaranteed to be unchained (no successor in mempool) and to not be parent of
-    /// `transaction`.
+    /// to make room for `transaction`. The returned transactions are ready transactions which
+    /// are guaranteed to not be ancestors of `transaction`.
+    ///
+    /// Ready transactions are ranked by their effective feerate, i.e., including the best package of
+    /// redeemers paying for them, so a low fee parent is protected by a high fee child. Since the caller
+    /// removes the returned transactions along with all their redeemers, the room freed by the redeemers
+    /// is accounted for as well.
     ///
     /// An error is returned if the mempool is filled with high priority transactions, or
     /// there are not enough lower feerate transactions that can be removed to accommodate `transaction`
@@ -243,31 +475,36 @@ impl TransactionsPool {
         let pending_cofactors = self.config.mempool_mass_cofactors.get(virtual_daa_score);
         let feerate_threshold = transaction.calculated_feerate(&pending_cofactors).unwrap();
         let mut txs_to_remove = Vec::with_capacity(1); // Normally we expect a single removal
+        let mut selected = TransactionIdSet::new();
         let mut selection_overall_size = 0;
-        for tx in self
+        for key in self
             .ready_transactions
-            .ascending_iter()
-            .map(|tx| self.all_transactions.get(&tx.id()).unwrap())
-            .filter(|mtx| mtx.priority == Priority::Low)
+            .ascending_key_iter()
+            .filter(|key| self.all_transactions.get(&key.tx.id()).unwrap().priority == Priority::Low)
         {
             // TODO (optimization): inline the `has_parent_in_set` check within the redeemer traversal and exit early if possible
-            let redeemers = self.get_redeemer_ids_in_pool(&tx.id()).into_iter().chain(once(tx.id())).collect::<TransactionIdSet>();
+            let redeemers =
+                self.get_redeemer_ids_in_pool(&key.tx.id()).into_iter().chain(once(key.tx.id())).collect::<TransactionIdSet>();
             if transaction.has_parent_in_set(&redeemers) {
                 continue;
             }
 
-            // We are iterating ready txs by ascending feerate so the pending tx has lower feerate than all remaining txs
-            let tx_cofactors = self.config.mempool_mass_cofactors.get(tx.added_at_daa_score);
-            if tx.feerate(&tx_cofactors) > feerate_threshold {
+            // We are iterating ready txs by ascending effective feerate so the pending tx has lower feerate than all remaining txs
+            if key.feerate() > feerate_threshold {
                 let err = RuleError::RejectMempoolIsFull;
                 debug!("Transaction {} with feerate {} has been rejected: {}", transaction.id(), feerate_threshold, err);
                 return Err(err);
             }
 
-            txs_to_remove.push(tx.id());
-            selection_overall_size += tx.mtx.mempool_estimated_bytes();
+            txs_to_remove.push(key.tx.id());
+            for id in redeemers {
+                // A redeemer with several ready ancestors might already be accounted for
+                if selected.insert(id) {
+                    selection_overall_size += self.all_transactions.get(&id).unwrap().mtx.mempool_estimated_bytes();
+                }
+            }
 
-            if self.len() + 1 - txs_to_remove.len() <= self.config.maximum_transaction_count
+            if self.len() + 1 - selected.len() <= self.config.maximum_transaction_count
                 && self.estimated_size + transaction_size - selection_overall_size <= self.config.mempool_size_limit
             {
                 return Ok(txs_to_remove);
@@ -291,6 +528,48 @@ impl TransactionsPool {
         self.estimated_size
     }
 
+    /// Asserts that the ancestor packages and the frontier match the ones built from scratch out of the current pool
+    #[cfg(test)]
+    pub(crate) fn assert_ancestor_packages_consistency(&self) {
+        let mut expected_ready_packages: HashMap<TransactionId, Vec<(TransactionId, Package)>> = HashMap::new();
+        for (id, transaction) in self.all_transactions.iter() {
+            let ancestors = self.get_ancestor_ids_in_pool(id, MAXIMUM_PACKAGE_ANCESTORS);
+            let Some(package) = self.ancestor_packages.get(id) else {
+                assert!(ancestors.is_none(), "transaction {id} should have an ancestor package");
+                continue;
+            };
+            let ancestors = ancestors.unwrap_or_else(|| panic!("transaction {id} should have no ancestor package"));
+            let mut aggregate = self.own_package(transaction);
+            for ancestor_id in ancestors.iter() {
+                aggregate += self.own_package(self.all_transactions.get(ancestor_id).unwrap());
+            }
+            assert_eq!(package.ancestors, ancestors, "ancestors of transaction {id}");
+            assert_eq!(package.key.package(), aggregate, "package of transaction {id}");
+            let ready_ancestors =
+                ancestors.iter().copied().chain(once(*id)).filter(|x| self.is_ready(x)).collect::<TransactionIdSet>();
+            assert_eq!(
+                package.ready_ancestors.iter().copied().collect::<TransactionIdSet>(),
+                ready_ancestors,
+                "ready ancestors of {id}"
+            );
+            for ready_id in ready_ancestors {
+                expected_ready_packages.entry(ready_id).or_default().push((*id, aggregate));
+            }
+        }
+
+        assert_eq!(self.ready_packages.len(), expected_ready_packages.len());
+        for (ready_id, mut expected) in expected_ready_packages {
+            let mut packages = self.ready_packages[&ready_id].iter().map(|key| (key.tx.id(), key.package())).collect::<Vec<_>>();
+            packages.sort_by_key(|(id, _)| *id);
+            expected.sort_by_key(|(id, _)| *id);
+            assert_eq!(packages, expected, "packages competing for transaction {ready_id}");
+        }
+        assert_eq!(self.ready_transactions.len(), self.ready_packages.len());
+        for key in self.ready_transactions.ascending_key_iter() {
+            assert_eq!(key.package(), self.ready_key(&key.tx.id()).unwrap().package(), "frontier key of {}", key.tx.id());
+        }
+    }
+
     pub(crate) fn all_transaction_ids_with_priority(&self, priority: Priority) -> Vec<TransactionId> {
         self.all().values().filter_map(|x| if x.priority == priority { Some(x.id()) } else { None }).collect()
     }
@@ -1,8 +1,12 @@
-use crate::mempool::{
-    Mempool,
-    errors::RuleResult,
-    model::{pool::Pool, tx::TxRemovalReason},
+use crate::{
+    mempool::{
+        Mempool,
+        errors::RuleResult,
+        model::{pool::Pool, tx::TxRemovalReason},
+    },
+    model::topological_sort::TopologicalSort,
 };
+use itertools::Itertools;
 use kaspa_consensus_core::tx::TransactionId;
 use kaspa_core::debug;
 use kaspa_utils::iter::IterExtensions;
@@ -29,13 +33,28 @@ impl Mempool {
             removed_transactions.extend(self.transaction_pool.get_redeemer_ids_in_pool(transaction_id));
         }
 
+        // Remove the redeemers from the transaction pool and the UTXO set (handled within the pool) before the
+        // transactions they spend, so that no removal updates the packages of transactions about to be removed
+        let mut removed_pool_transactions = Vec::with_capacity(removed_transactions.len());
+        if removed_transactions.len() > 1 {
+            let transactions =
+                removed_transactions.iter().map(|id| self.transaction_pool.get(id).unwrap().mtx.tx.clone()).collect_vec();
+            removed_transactions = transactions.topological_sort().into_iter().map(|tx| tx.id()).collect();
+        }
+        for tx_id in removed_transactions.iter().rev() {
+            removed_pool_transactions.push(self.transaction_pool.remove_transaction(tx_id)?);
+        }
+
         let mut removed_orphans: Vec<TransactionId> = vec![];
-        for tx_id in removed_transactions.iter() {
-            // Remove the tx from the transaction pool and the UTXO set (handled within the pool)
-            let tx = self.transaction_pool.remove_transaction(tx_id)?;
+        for tx in removed_pool_transactions.into_iter().rev() {
             // Update/remove descendent orphan txs (depending on `remove_redeemers`)
             let txs = self.orphan_pool.update_orphans_after_transaction_removed(&tx, remove_redeemers)?;
-            removed_orphans.extend(txs.into_iter().map(|x| x.id()));
+            removed_orphans.extend(txs.iter().map(|x| x.id()));
+            self.events.record_removed(reason, &tx.mtx);
+            if let Some(evictions) = self.package_evictions.as_mut() {
+                evictions.transactions.push(tx);
+                evictions.orphans.extend(txs);
+            }
         }
         removed_transactions.extend(removed_orphans);
 
@@ -108,6 +108,7 @@ impl Mempool {
                             self.validate_double_spending_transaction(transaction, double_spend, virtual_daa_score)?;
                         }
                         // We apply consequences such as removal only after we fully validate against all double spends
+                        self.events.record_replacing(transaction);
                         for double_spend in double_spends {
                             self.remove_transaction(
                                 &double_spend.owner_id,
@@ -131,6 +132,7 @@ impl Mempool {
                             .mtx
                             .tx
                             .clone();
+                        self.events.record_replacing(transaction);
                         self.remove_transaction(
                             &double_spends[0].owner_id,
                             true,
@@ -0,0 +1,210 @@
+use crate::{
+    mempool::{
+        Mempool,
+        config::MAXIMUM_PACKAGE_TRANSACTION_COUNT,
+        errors::{RuleError, RuleResult},
+        model::tx::{MempoolTransaction, TransactionPreValidation, TxRemovalReason},
+        tx::{Priority, RbfPolicy},
+    },
+    model::topological_sort::TopologicalSort,
+};
+use itertools::Itertools;
+use kaspa_consensus_core::{
+    api::ConsensusApi,
+    constants::UNACCEPTED_DAA_SCORE,
+    tx::{MutableTransaction, Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
+};
+use kaspa_core::debug;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+impl Mempool {
+    /// Validates that a package is made of at most [`MAXIMUM_PACKAGE_TRANSACTION_COUNT`] distinct transactions
+    /// not spending the same outputs.
+    pub(crate) fn validate_transaction_package_in_isolation(&self, transactions: &[MutableTransaction]) -> RuleResult<()> {
+        if transactions.is_empty() {
+            return Err(RuleError::RejectEmptyPackage);
+        }
+        if transactions.len() > MAXIMUM_PACKAGE_TRANSACTION_COUNT {
+            return Err(RuleError::RejectPackageSize(transactions.len(), MAXIMUM_PACKAGE_TRANSACTION_COUNT));
+        }
+        let mut transaction_ids = HashSet::with_capacity(transactions.len());
+        let mut spent_outpoints = HashSet::new();
+        for transaction in transactions {
+            let transaction_id = transaction.id();
+            if !transaction_ids.insert(transaction_id) {
+                return Err(RuleError::RejectDuplicateInPackage(transaction_id));
+            }
+            for input in transaction.tx.inputs.iter() {
+                if !spent_outpoints.insert(input.previous_outpoint) {
+                    return Err(RuleError::RejectDoubleSpendInPackage(input.previous_outpoint, transaction_id));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Pre-validates the transactions of a topologically sorted package and populates their UTXO entries
+    /// from the mempool and from the outputs of the package transactions they spend.
+    ///
+    /// Transactions already in the mempool or already accepted by the consensus are dropped from the package.
+    pub(crate) fn pre_validate_and_populate_transaction_package(
+        &self,
+        consensus: &dyn ConsensusApi,
+        transactions: Vec<MutableTransaction>,
+    ) -> RuleResult<Vec<MutableTransaction>> {
+        let mut package_entries = HashMap::new();
+        let mut populated_transactions = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            let transaction_id = transaction.id();
+            let mut transaction = match self.pre_validate_and_populate_transaction(consensus, transaction, RbfPolicy::Forbidden) {
+                Ok(TransactionPreValidation { transaction, feerate_threshold: _ }) => transaction,
+                Err(RuleError::RejectAlreadyAccepted(_)) | Err(RuleError::RejectDuplicate(_)) => {
+                    debug!("Ignoring package transaction {} already known", transaction_id);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            for (entry, input) in transaction.entries.iter_mut().zip(transaction.tx.inputs.iter()) {
+                if entry.is_none() {
+                    *entry = package_entries.get(&input.previous_outpoint).cloned();
+                }
+            }
+            for (index, output) in transaction.tx.outputs.iter().enumerate() {
+                package_entries.insert(
+                    TransactionOutpoint::new(transaction_id, index as u32),
+                    UtxoEntry::new(
+                        output.value,
+                        output.script_public_key.clone(),
+                        UNACCEPTED_DAA_SCORE,
+                        false,
+                        output.covenant.map(|x| x.covenant_id),
+                    ),
+                );
+            }
+            populated_transactions.push(transaction);
+        }
+        Ok(populated_transactions)
+    }
+
+    /// Inserts a validated package in the transaction pool, as a unit.
+    ///
+    /// Every transaction must meet all the mempool rules but the minimum relay fee, which applies to the
+    /// combined fee and mass of the package instead. The package transactions are inserted in topological
+    /// order and the ones already inserted are removed if a later one cannot be. Package transactions waiting
+    /// in the orphan pool are only unorphaned once the whole package is inserted.
+    pub(crate) fn post_validate_and_insert_transaction_package(
+        &mut self,
+        consensus: &dyn ConsensusApi,
+        validation_results: Vec<RuleResult<()>>,
+        transactions: Vec<MutableTransaction>,
+        priority: Priority,
+    ) -> RuleResult<Vec<Arc<Transaction>>> {
+        let virtual_daa_score = consensus.get_virtual_daa_score();
+        for (transaction, validation_result) in transactions.iter().zip(validation_results) {
+            let transaction_id = transaction.id();
+
+            // The package may have been concurrently accepted or inserted, at least partially
+            self.validate_transaction_unacceptance(transaction_id)?;
+            self.validate_transaction_not_duplicate(transaction_id)?;
+
+            match validation_result {
+                Ok(_) => {}
+                // A package is complete by definition so it never contains orphans
+                Err(RuleError::RejectMissingOutpoint) => return Err(RuleError::RejectDisallowedOrphan(transaction_id)),
+                Err(err) => return Err(err),
+            }
+
+            self.validate_transaction_limits_in_context(transaction, virtual_daa_score)?;
+            if !self.config.accept_non_standard {
+                self.check_transaction_inputs_standard_in_context(transaction, virtual_daa_score)?;
+            }
+            self.transaction_pool.check_double_spends(transaction)?;
+        }
+        self.check_transaction_package_relay_fee(&transactions, priority, virtual_daa_score)?;
+
+        // Keep track of the transactions evicted to make room for the package so they can be restored
+        self.package_evictions = Some(PackageEvictions::default());
+        let mut accepted_transactions: Vec<Arc<Transaction>> = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            let transaction_id = transaction.id();
+            match self.insert_validated_transaction(transaction, priority, virtual_daa_score, virtual_daa_score) {
+                Ok(accepted_transaction) => accepted_transactions.push(accepted_transaction),
+                Err(err) => {
+                    let evictions = self.package_evictions.take().unwrap_or_default();
+                    self.rollback_transaction_package(&accepted_transactions, evictions)?;
+                    debug!("Failed to insert package transaction {}: {}", transaction_id, err);
+                    return Err(err);
+                }
+            }
+        }
+        let evictions = self.package_evictions.take().unwrap_or_default();
+
+        // Package transactions evicted by a later one of the same package did not fail against the rest of the pool
+        let package_ids: HashSet<_> = accepted_transactions.iter().map(|x| x.id()).collect();
+        for (transaction_id, added_at_daa_score, feerate) in evictions.inclusion_failures {
+            if !package_ids.contains(&transaction_id) {
+                self.inclusion_estimator.record_failure(virtual_daa_score, added_at_daa_score, feerate);
+            }
+        }
+
+        // A package transaction previously received alone may be waiting in the orphan pool
+        for accepted_transaction in accepted_transactions.iter() {
+            let transaction_id = accepted_transaction.id();
+            if self.orphan_pool.has(&transaction_id) {
+                self.orphan_pool.remove_orphan(&transaction_id, false, TxRemovalReason::Unorphaned, "")?;
+            }
+        }
+        Ok(accepted_transactions)
+    }
+
+    /// Removes the partially inserted transactions of a package and restores the transactions evicted
+    /// meanwhile, leaving no trace in the mempool events nor in the inclusion feerate estimator
+    fn rollback_transaction_package(
+        &mut self,
+        accepted_transactions: &[Arc<Transaction>],
+        evictions: PackageEvictions,
+    ) -> RuleResult<()> {
+        for accepted_transaction in accepted_transactions.iter().rev() {
+            let transaction_id = accepted_transaction.id();
+            self.events.discard_added(transaction_id);
+            // The transaction may have been evicted to make room for a later one of the package
+            if !self.transaction_pool.has(&transaction_id) {
+                continue;
+            }
+            let removed_transaction = self.transaction_pool.remove_transaction(&transaction_id)?;
+            self.orphan_pool.update_orphans_after_transaction_removed(&removed_transaction, false)?;
+        }
+
+        // The evicted transactions are removed along with their redeemers in no particular order,
+        // so they are restored in topological order, before the orphans spending them
+        let package_ids: HashSet<_> = accepted_transactions.iter().map(|x| x.id()).collect();
+        let mut evicted: HashMap<_, _> =
+            evictions.transactions.into_iter().filter(|x| !package_ids.contains(&x.id())).map(|x| (x.id(), x)).collect();
+        let sorted = evicted.values().map(|x| x.mtx.tx.clone()).collect_vec().topological_sort();
+        for transaction in sorted {
+            let transaction = evicted.remove(&transaction.id()).unwrap();
+            let transaction_id = transaction.id();
+            let transaction_size = transaction.mtx.mempool_estimated_bytes();
+            self.transaction_pool.add_mempool_transaction(transaction, transaction_size)?;
+            self.events.discard_removed(transaction_id);
+        }
+        for orphan in evictions.orphans {
+            self.orphan_pool.restore_orphan(orphan)?;
+        }
+        Ok(())
+    }
+}
+
+/// Transactions removed from the mempool while inserting a package
+#[derive(Default)]
+pub(crate) struct PackageEvictions {
+    pub(crate) transactions: Vec<MempoolTransaction>,
+    /// Orphans removed as redeemers of the evicted transactions
+    pub(crate) orphans: Vec<MempoolTransaction>,
+    /// Id, DAA score at insertion and feerate of the transactions evicted on their feerate, only recorded as
+    /// inclusion failures once the package is committed
+    pub(crate) inclusion_failures: Vec<(TransactionId, u64, f64)>,
+}
@@ -1,4 +1,4 @@
-use std::sync::atomic::Ordering;
+use std::sync::{Arc, atomic::Ordering};
 
 use crate::mempool::{
     Mempool,
@@ -36,6 +36,11 @@ impl Mempool {
         Ok(TransactionPreValidation { transaction, feerate_threshold })
     }
 
+    /// Inserts a validated transaction in the transaction pool, or in the orphan pool if it misses outpoints.
+    ///
+    /// `restored_daa_score` is the DAA score at which a transaction restored from a mempool snapshot was originally
+    /// inserted. It is used in place of the virtual DAA score so the expiration of the transaction is not delayed.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn post_validate_and_insert_transaction(
         &mut self,
         consensus: &dyn ConsensusApi,
@@ -44,6 +49,7 @@ impl Mempool {
         priority: Priority,
         orphan: Orphan,
         rbf_policy: RbfPolicy,
+        restored_daa_score: Option<u64>,
     ) -> RuleResult<TransactionPostValidation> {
         let transaction_id = transaction.id();
 
@@ -62,7 +68,8 @@ impl Mempool {
                 }
                 self.validate_replace_by_fee_policy_constraints(&transaction, rbf_policy)?;
                 let virtual_daa_score = consensus.get_virtual_daa_score();
-                self.orphan_pool.try_add_orphan(virtual_daa_score, transaction, priority)?;
+                let added_at_daa_score = restored_daa_score.map_or(virtual_daa_score, |score| score.min(virtual_daa_score));
+                self.orphan_pool.try_add_orphan(virtual_daa_score, added_at_daa_score, transaction, priority)?;
                 return Ok(TransactionPostValidation::default());
             }
             Err(err) => {
@@ -86,6 +93,20 @@ impl Mempool {
         //       above. We choose to ignore this rare case for now, as it essentially means that even the increased
         //       feerate of the replacement tx is very low relative to the mempool overall.
         //
+        let added_at_daa_score = restored_daa_score.map_or(virtual_daa_score, |score| score.min(virtual_daa_score));
+        let accepted_transaction = self.insert_validated_transaction(transaction, priority, virtual_daa_score, added_at_daa_score)?;
+        Ok(TransactionPostValidation { removed: removed_transaction, accepted: Some(accepted_transaction) })
+    }
+
+    /// Adds a fully validated transaction to the transaction pool, evicting lower feerate transactions if the pool is full
+    pub(super) fn insert_validated_transaction(
+        &mut self,
+        transaction: MutableTransaction,
+        priority: Priority,
+        virtual_daa_score: u64,
+        added_at_daa_score: u64,
+    ) -> RuleResult<Arc<Transaction>> {
+        let transaction_id = transaction.id();
 
         // Before adding the transaction, check if there is room in the pool
         let transaction_size = transaction.mempool_estimated_bytes();
@@ -93,6 +114,8 @@ impl Mempool {
         if !txs_to_remove.is_empty() {
             let transaction_pool_len_before = self.transaction_pool.len();
             for x in txs_to_remove.iter() {
+                // Only the evicted transaction itself failed on its feerate, its redeemers go along with it
+                self.record_inclusion_failure(x, virtual_daa_score);
                 self.remove_transaction(x, true, TxRemovalReason::MakingRoom, format!(" for {}", transaction_id).as_str())?;
                 // self.transaction_pool.limit_transaction_count(&transaction) returns the
                 // smallest prefix of `ready_transactions` (sorted by ascending fee-rate)
@@ -125,12 +148,13 @@ impl Mempool {
 
         // Add the transaction to the mempool as a MempoolTransaction and return a clone of the embedded Arc<Transaction>
         let accepted_transaction =
-            self.transaction_pool.add_transaction(transaction, virtual_daa_score, priority, transaction_size)?.mtx.tx.clone();
-        Ok(TransactionPostValidation { removed: removed_transaction, accepted: Some(accepted_transaction) })
+            self.transaction_pool.add_transaction(transaction, added_at_daa_score, priority, transaction_size)?;
+        self.events.record_added(&accepted_transaction.mtx);
+        Ok(accepted_transaction.mtx.tx.clone())
     }
 
     /// Validates that the transaction wasn't already accepted into the DAG
-    fn validate_transaction_unacceptance(&self, transaction_id: TransactionId) -> RuleResult<()> {
+    pub(super) fn validate_transaction_unacceptance(&self, transaction_id: TransactionId) -> RuleResult<()> {
         // Reject if the transaction is registered as an accepted transaction
         match self.accepted_transactions.has(&transaction_id) {
             true => Err(RuleError::RejectAlreadyAccepted(transaction_id)),
@@ -138,7 +162,7 @@ impl Mempool {
         }
     }
 
-    fn validate_transaction_not_duplicate(&self, transaction_id: TransactionId) -> RuleResult<()> {
+    pub(super) fn validate_transaction_not_duplicate(&self, transaction_id: TransactionId) -> RuleResult<()> {
         if self.transaction_pool.has(&transaction_id) {
             return Err(RuleError::RejectDuplicate(transaction_id));
         }
@@ -0,0 +1,50 @@
+//! Notifications emitted by the mempool about changes in its transaction pool
+//!
+//! Only the ordinary transaction pool is covered: orphans entering or leaving the
+//! orphan pool are not reported.
+
+use kaspa_consensus_core::tx::MutableTransaction;
+use std::sync::Arc;
+
+pub type MempoolNotificationSender = async_channel::Sender<MempoolNotification>;
+pub type MempoolNotificationReceiver = async_channel::Receiver<MempoolNotification>;
+
+/// Reason of a transaction removal reported by [`MempoolNotification::TransactionRemoved`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MempoolRemovalReason {
+    /// The transaction was evicted, either making room for others, because of a double spend
+    /// or because it was found invalid
+    Evicted,
+    /// The transaction expired without being included in a block
+    Expired,
+}
+
+#[derive(Clone, Debug)]
+pub struct TransactionAddedNotification {
+    pub transactions: Arc<Vec<MutableTransaction>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TransactionRemovedNotification {
+    pub reason: MempoolRemovalReason,
+    pub transactions: Arc<Vec<MutableTransaction>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TransactionReplacedNotification {
+    pub replacing: MutableTransaction,
+    pub replaced: Arc<Vec<MutableTransaction>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TransactionIncludedNotification {
+    pub transactions: Arc<Vec<MutableTransaction>>,
+}
+
+#[derive(Clone, Debug)]
+pub enum MempoolNotification {
+    TransactionAdded(TransactionAddedNotification),
+    TransactionRemoved(TransactionRemovedNotification),
+    TransactionReplaced(TransactionReplacedNotification),
+    TransactionIncluded(TransactionIncludedNotification),
+}
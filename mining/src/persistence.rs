@@ -0,0 +1,177 @@
+use crate::{
+    manager::MiningManagerProxy,
+    mempool::{model::tx::MempoolTransaction, tx::Priority},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use kaspa_consensus_core::tx::Transaction;
+use kaspa_consensusmanager::ConsensusManager;
+use kaspa_core::{
+    info,
+    task::service::{AsyncService, AsyncServiceFuture},
+    trace, warn,
+};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
+
+const PERSISTENCE: &str = "mempool-persistence";
+
+/// Version of the mempool snapshot file format, bumped on every incompatible change
+pub const MEMPOOL_SNAPSHOT_VERSION: u16 = 1;
+
+/// Default name of the mempool snapshot file, relative to the node data directory
+pub const MEMPOOL_SNAPSHOT_FILE_NAME: &str = "mempool.bin";
+
+#[derive(Error, Debug)]
+pub enum MempoolSnapshotError {
+    #[error("mempool snapshot i/o error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("unsupported mempool snapshot version {0}, expected {MEMPOOL_SNAPSHOT_VERSION}")]
+    UnsupportedVersion(u16),
+}
+
+pub type MempoolSnapshotResult<T> = std::result::Result<T, MempoolSnapshotError>;
+
+/// A mempool transaction along with the metadata required to restore it as it was
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct MempoolSnapshotEntry {
+    pub transaction: Transaction,
+    pub priority: Priority,
+    /// DAA score of the virtual at the time the transaction was first inserted, which drives its expiration
+    pub added_at_daa_score: u64,
+}
+
+impl From<&MempoolTransaction> for MempoolSnapshotEntry {
+    fn from(tx: &MempoolTransaction) -> Self {
+        Self { transaction: (*tx.mtx.tx).clone(), priority: tx.priority, added_at_daa_score: tx.added_at_daa_score }
+    }
+}
+
+/// Content of the mempool persisted on shutdown
+#[derive(Debug, Clone, Default, BorshSerialize, BorshDeserialize)]
+pub struct MempoolSnapshot {
+    pub transactions: Vec<MempoolSnapshotEntry>,
+    pub orphans: Vec<MempoolSnapshotEntry>,
+}
+
+impl MempoolSnapshot {
+    pub fn new(transactions: Vec<MempoolSnapshotEntry>, orphans: Vec<MempoolSnapshotEntry>) -> Self {
+        Self { transactions, orphans }
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len() + self.orphans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty() && self.orphans.is_empty()
+    }
+
+    /// Writes the snapshot to `path`, preceded by the format version.
+    ///
+    /// The content is first written to a temporary file which is then renamed, so an interrupted
+    /// write never leaves a truncated snapshot behind.
+    pub fn write_to_file(&self, path: &Path) -> MempoolSnapshotResult<()> {
+        let tmp_path = path.with_extension("tmp");
+        let file = File::create(&tmp_path)?;
+        let mut writer = BufWriter::new(file);
+        MEMPOOL_SNAPSHOT_VERSION.serialize(&mut writer)?;
+        self.serialize(&mut writer)?;
+        writer.flush()?;
+        writer.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn read_from_file(path: &Path) -> MempoolSnapshotResult<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let version = u16::deserialize_reader(&mut reader)?;
+        if version != MEMPOOL_SNAPSHOT_VERSION {
+            return Err(MempoolSnapshotError::UnsupportedVersion(version));
+        }
+        let mut snapshot = Self::deserialize_reader(&mut reader)?;
+        // Never trust the persisted transaction ids
+        snapshot.transactions.iter_mut().chain(snapshot.orphans.iter_mut()).for_each(|entry| entry.transaction.finalize());
+        Ok(snapshot)
+    }
+}
+
+/// Service restoring the mempool from its snapshot file on startup and persisting it on shutdown
+pub struct MempoolPersistence {
+    consensus_manager: Arc<ConsensusManager>,
+    mining_manager: MiningManagerProxy,
+    path: PathBuf,
+    include_orphans: bool,
+}
+
+impl MempoolPersistence {
+    pub fn new(
+        consensus_manager: Arc<ConsensusManager>,
+        mining_manager: MiningManagerProxy,
+        path: PathBuf,
+        include_orphans: bool,
+    ) -> Self {
+        Self { consensus_manager, mining_manager, path, include_orphans }
+    }
+
+    async fn restore(&self) {
+        if !self.path.exists() {
+            return;
+        }
+        let path = self.path.clone();
+        let snapshot = match tokio::task::spawn_blocking(move || MempoolSnapshot::read_from_file(&path)).await.unwrap() {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                warn!("Discarding the mempool snapshot {}: {}", self.path.display(), err);
+                let _ = fs::remove_file(&self.path);
+                return;
+            }
+        };
+        let total = snapshot.len();
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let restored = self.mining_manager.clone().restore_mempool_snapshot(&session, snapshot).await;
+        info!("Restored {} of {} persisted mempool transactions", restored, total);
+        // The snapshot is kept until overwritten on the next shutdown, so a crash meanwhile does not lose the mempool.
+        // Restoring it again is safe since every transaction gets revalidated.
+    }
+
+    async fn persist(&self) {
+        let snapshot = self.mining_manager.clone().mempool_snapshot(self.include_orphans).await;
+        let total = snapshot.len();
+        let path = self.path.clone();
+        match tokio::task::spawn_blocking(move || snapshot.write_to_file(&path)).await.unwrap() {
+            Ok(()) => info!("Persisted {} mempool transactions to {}", total, self.path.display()),
+            Err(err) => warn!("Failed to persist the mempool to {}: {}", self.path.display(), err),
+        }
+    }
+}
+
+impl AsyncService for MempoolPersistence {
+    fn ident(self: Arc<Self>) -> &'static str {
+        PERSISTENCE
+    }
+
+    fn start(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            self.restore().await;
+            Ok(())
+        })
+    }
+
+    fn signal_exit(self: Arc<Self>) {
+        trace!("sending an exit signal to {}", PERSISTENCE);
+    }
+
+    fn stop(self: Arc<Self>) -> AsyncServiceFuture {
+        Box::pin(async move {
+            self.persist().await;
+            trace!("{} stopped", PERSISTENCE);
+            Ok(())
+        })
+    }
+}
@@ -51,10 +51,29 @@ event_type_enum! {
         VirtualDaaScoreChanged,
         PruningPointUtxoSetOverride,
         NewBlockTemplate,
+        MempoolTransactionAdded,
+        MempoolTransactionRemoved,
+        MempoolTransactionReplaced,
+        MempoolTransactionIncluded,
     }
 }
 
-pub const EVENT_COUNT: usize = 9;
+pub const EVENT_COUNT: usize = 13;
+
+impl EventType {
+    /// Returns true if subscriptions to this event type are scoped by a set of addresses
+    /// (see [`UtxosChangedScope`](crate::scope::UtxosChangedScope)).
+    pub fn is_address_scoped(&self) -> bool {
+        matches!(
+            self,
+            EventType::UtxosChanged
+                | EventType::MempoolTransactionAdded
+                | EventType::MempoolTransactionRemoved
+                | EventType::MempoolTransactionReplaced
+                | EventType::MempoolTransactionIncluded
+        )
+    }
+}
 
 impl FromStr for EventType {
     type Err = Error;
@@ -70,6 +89,10 @@ impl FromStr for EventType {
             "virtual-daa-score-changed" => Ok(EventType::VirtualDaaScoreChanged),
             "pruning-point-utxo-set-override" => Ok(EventType::PruningPointUtxoSetOverride),
             "new-block-template" => Ok(EventType::NewBlockTemplate),
+            "mempool-transaction-added" => Ok(EventType::MempoolTransactionAdded),
+            "mempool-transaction-removed" => Ok(EventType::MempoolTransactionRemoved),
+            "mempool-transaction-replaced" => Ok(EventType::MempoolTransactionReplaced),
+            "mempool-transaction-included" => Ok(EventType::MempoolTransactionIncluded),
             _ => Err(Error::InvalidEventType(s.to_string())),
         }
     }
@@ -28,7 +28,8 @@ pub trait Notification: Clone + Debug + Display + Send + Sync + 'static {
                 subscription.as_any().downcast_ref::<VirtualChainChangedSubscription>().unwrap(),
                 context,
             ),
-            EventType::UtxosChanged => self
+            // All address scoped event types share the same subscription implementation and filtering entry point
+            event_type if event_type.is_address_scoped() => self
                 .apply_utxos_changed_subscription(subscription.as_any().downcast_ref::<UtxosChangedSubscription>().unwrap(), context),
             _ => self.apply_overall_subscription(subscription.as_any().downcast_ref::<OverallSubscription>().unwrap(), context),
         }
@@ -45,6 +45,10 @@ pub enum Scope {
     VirtualDaaScoreChanged,
     PruningPointUtxoSetOverride,
     NewBlockTemplate,
+    MempoolTransactionAdded,
+    MempoolTransactionRemoved,
+    MempoolTransactionReplaced,
+    MempoolTransactionIncluded,
 }
 }
 
@@ -52,6 +56,46 @@ impl Scope {
     pub fn event_type(&self) -> EventType {
         self.into()
     }
+
+    /// Builds the scope of an address scoped event type (see [`EventType::is_address_scoped`]).
+    ///
+    /// An empty `addresses` vector means all addresses.
+    ///
+    /// Panics if `event_type` is not address scoped.
+    pub fn with_addresses(event_type: EventType, addresses: Vec<Address>) -> Self {
+        match event_type {
+            EventType::UtxosChanged => UtxosChangedScope::new(addresses).into(),
+            EventType::MempoolTransactionAdded => MempoolTransactionAddedScope::new(addresses).into(),
+            EventType::MempoolTransactionRemoved => MempoolTransactionRemovedScope::new(addresses).into(),
+            EventType::MempoolTransactionReplaced => MempoolTransactionReplacedScope::new(addresses).into(),
+            EventType::MempoolTransactionIncluded => MempoolTransactionIncludedScope::new(addresses).into(),
+            _ => panic!("{event_type} is not an address scoped event type"),
+        }
+    }
+
+    /// Returns the addresses of an address scoped scope, `None` for any other scope
+    pub fn addresses(&self) -> Option<&Vec<Address>> {
+        match self {
+            Scope::UtxosChanged(scope) => Some(&scope.addresses),
+            Scope::MempoolTransactionAdded(scope) => Some(&scope.addresses),
+            Scope::MempoolTransactionRemoved(scope) => Some(&scope.addresses),
+            Scope::MempoolTransactionReplaced(scope) => Some(&scope.addresses),
+            Scope::MempoolTransactionIncluded(scope) => Some(&scope.addresses),
+            _ => None,
+        }
+    }
+
+    /// Consumes the scope and returns its addresses if address scoped, `None` otherwise
+    pub fn into_addresses(self) -> Option<Vec<Address>> {
+        match self {
+            Scope::UtxosChanged(scope) => Some(scope.addresses),
+            Scope::MempoolTransactionAdded(scope) => Some(scope.addresses),
+            Scope::MempoolTransactionRemoved(scope) => Some(scope.addresses),
+            Scope::MempoolTransactionReplaced(scope) => Some(scope.addresses),
+            Scope::MempoolTransactionIncluded(scope) => Some(scope.addresses),
+            _ => None,
+        }
+    }
 }
 
 impl Serializer for Scope {
@@ -266,3 +310,60 @@ impl Deserializer for NewBlockTemplateScope {
         Ok(Self {})
     }
 }
+
+/// Defines a scope selecting mempool transactions by the addresses they involve, either as
+/// spent UTXO or as output. An empty address set selects all transactions.
+macro_rules! mempool_transaction_scope {
+    ($name:ident) => {
+        #[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+        pub struct $name {
+            pub addresses: Vec<Address>,
+        }
+
+        impl $name {
+            pub fn new(addresses: Vec<Address>) -> Self {
+                Self { addresses }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let addresses = match self.addresses.len() {
+                    0 => "all".to_string(),
+                    1 => format!("{}", self.addresses[0]),
+                    n => format!("{} addresses", n),
+                };
+                write!(f, "{} ({})", stringify!($name), addresses)
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.addresses.len() == other.addresses.len() && self.addresses.iter().all(|x| other.addresses.contains(x))
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Serializer for $name {
+            fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+                store!(u16, &1, writer)?;
+                store!(Vec<Address>, &self.addresses, writer)?;
+                Ok(())
+            }
+        }
+
+        impl Deserializer for $name {
+            fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+                let _version = load!(u16, reader)?;
+                let addresses = load!(Vec<Address>, reader)?;
+                Ok(Self { addresses })
+            }
+        }
+    };
+}
+
+mempool_transaction_scope!(MempoolTransactionAddedScope);
+mempool_transaction_scope!(MempoolTransactionRemovedScope);
+mempool_transaction_scope!(MempoolTransactionReplacedScope);
+mempool_transaction_scope!(MempoolTransactionIncludedScope);
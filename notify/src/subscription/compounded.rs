@@ -1,7 +1,7 @@
 use crate::{
     address::{error::Result, tracker::Counters},
     events::EventType,
-    scope::{Scope, UtxosChangedScope, VirtualChainChangedScope},
+    scope::{Scope, VirtualChainChangedScope},
     subscription::{Command, Compounded, Mutation, Subscription, context::SubscriptionContext},
 };
 use itertools::Itertools;
@@ -149,19 +149,28 @@ impl Subscription for VirtualChainChangedSubscription {
     }
 }
 
-#[derive(Clone, Default, Debug, PartialEq, Eq)]
+/// Compounded subscription to notifications filtered by a set of addresses
+///
+/// Serves UtxosChanged as well as all other address scoped event types (see [`EventType::is_address_scoped`]).
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UtxosChangedSubscription {
+    event_type: EventType,
     all: usize,
     indexes: Counters,
 }
 
 impl UtxosChangedSubscription {
     pub fn new() -> Self {
-        Self { all: 0, indexes: Counters::new() }
+        Self::with_capacity(0)
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
-        Self { all: 0, indexes: Counters::with_capacity(capacity) }
+        Self::with_event_type(EventType::UtxosChanged, capacity)
+    }
+
+    pub fn with_event_type(event_type: EventType, capacity: usize) -> Self {
+        assert!(event_type.is_address_scoped(), "{event_type} is not an address scoped event type");
+        Self { event_type, all: 0, indexes: Counters::with_capacity(capacity) }
     }
 
     pub fn to_addresses(&self, prefix: Prefix, context: &SubscriptionContext) -> Vec<Address> {
@@ -180,34 +189,45 @@ impl UtxosChangedSubscription {
     pub fn unregister(&mut self, addresses: Vec<Address>, context: &SubscriptionContext) -> Vec<Address> {
         context.address_tracker.unregister(&mut self.indexes, addresses)
     }
+
+    #[inline(always)]
+    fn scope_with(&self, addresses: Vec<Address>) -> Scope {
+        Scope::with_addresses(self.event_type, addresses)
+    }
+}
+
+impl Default for UtxosChangedSubscription {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Compounded for UtxosChangedSubscription {
     fn compound(&mut self, mutation: Mutation, context: &SubscriptionContext) -> Option<Mutation> {
         assert_eq!(self.event_type(), mutation.event_type());
-        if let Scope::UtxosChanged(scope) = mutation.scope {
+        if let Some(scope_addresses) = mutation.scope.into_addresses() {
             match mutation.command {
                 Command::Start => {
-                    if scope.addresses.is_empty() {
+                    if scope_addresses.is_empty() {
                         // Add All
                         self.all += 1;
                         if self.all == 1 {
-                            return Some(Mutation::new(Command::Start, UtxosChangedScope::default().into()));
+                            return Some(Mutation::new(Command::Start, self.scope_with(vec![])));
                         }
                     } else {
                         // Add(A)
-                        let added = self.register(scope.addresses, context).expect("compounded always registers");
+                        let added = self.register(scope_addresses, context).expect("compounded always registers");
                         if !added.is_empty() && self.all == 0 {
-                            return Some(Mutation::new(Command::Start, UtxosChangedScope::new(added).into()));
+                            return Some(Mutation::new(Command::Start, self.scope_with(added)));
                         }
                     }
                 }
                 Command::Stop => {
-                    if !scope.addresses.is_empty() {
+                    if !scope_addresses.is_empty() {
                         // Remove(R)
-                        let removed = self.unregister(scope.addresses, context);
+                        let removed = self.unregister(scope_addresses, context);
                         if !removed.is_empty() && self.all == 0 {
-                            return Some(Mutation::new(Command::Stop, UtxosChangedScope::new(removed).into()));
+                            return Some(Mutation::new(Command::Stop, self.scope_with(removed)));
                         }
                     } else {
                         // Remove All
@@ -216,9 +236,9 @@ impl Compounded for UtxosChangedSubscription {
                         if self.all == 0 {
                             let addresses = self.to_addresses(Prefix::Mainnet, context);
                             if !addresses.is_empty() {
-                                return Some(Mutation::new(Command::Start, UtxosChangedScope::new(addresses).into()));
+                                return Some(Mutation::new(Command::Start, self.scope_with(addresses)));
                             } else {
-                                return Some(Mutation::new(Command::Stop, UtxosChangedScope::default().into()));
+                                return Some(Mutation::new(Command::Stop, self.scope_with(vec![])));
                             }
                         }
                     }
@@ -232,7 +252,7 @@ impl Compounded for UtxosChangedSubscription {
 impl Subscription for UtxosChangedSubscription {
     #[inline(always)]
     fn event_type(&self) -> EventType {
-        EventType::UtxosChanged
+        self.event_type
     }
 
     fn active(&self) -> bool {
@@ -241,7 +261,7 @@ impl Subscription for UtxosChangedSubscription {
 
     fn scope(&self, context: &SubscriptionContext) -> Scope {
         let addresses = if self.all > 0 { vec![] } else { self.to_addresses(Prefix::Mainnet, context) };
-        Scope::UtxosChanged(UtxosChangedScope::new(addresses))
+        self.scope_with(addresses)
     }
 }
 
@@ -253,7 +273,7 @@ mod tests {
     use super::*;
     use crate::{
         address::{test_helpers::get_3_addresses, tracker::Counter},
-        scope::BlockAddedScope,
+        scope::{BlockAddedScope, UtxosChangedScope},
     };
     use std::panic::AssertUnwindSafe;
 
@@ -398,6 +418,7 @@ mod tests {
                 Step { name: "remove a0", mutation: remove_0(), result: Some(remove_0()) },
             ],
             final_state: Box::new(UtxosChangedSubscription {
+                event_type: EventType::UtxosChanged,
                 all: 0,
                 indexes: Counters::with_counters(vec![
                     Counter { index: 0, count: 0, locked: true },
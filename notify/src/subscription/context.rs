@@ -1,5 +1,6 @@
 use crate::{
     address::tracker::Tracker,
+    events::{EVENT_TYPE_ARRAY, EventArray, EventType},
     listener::ListenerId,
     subscription::{
         DynSubscription,
@@ -15,6 +16,8 @@ use kaspa_addresses::Address;
 pub struct SubscriptionContextInner {
     pub address_tracker: Tracker,
     pub utxos_changed_subscription_to_all: DynSubscription,
+    /// Wildcard subscriptions of the mempool event types, indexed by event type
+    pub mempool_subscriptions_to_all: EventArray<Option<DynSubscription>>,
 }
 
 impl SubscriptionContextInner {
@@ -28,7 +31,21 @@ impl SubscriptionContextInner {
         let address_tracker = Tracker::new(max_addresses);
         let utxos_changed_subscription_all =
             Arc::new(UtxosChangedSubscription::new(UtxosChangedState::All, Self::CONTEXT_LISTENER_ID));
-        Self { address_tracker, utxos_changed_subscription_to_all: utxos_changed_subscription_all }
+        Self {
+            address_tracker,
+            utxos_changed_subscription_to_all: utxos_changed_subscription_all,
+            mempool_subscriptions_to_all: Self::mempool_subscriptions_to_all(),
+        }
+    }
+
+    fn mempool_subscriptions_to_all() -> EventArray<Option<DynSubscription>> {
+        EventArray::from_fn(|i| {
+            let event_type = EVENT_TYPE_ARRAY[i];
+            (event_type.is_address_scoped() && event_type != EventType::UtxosChanged).then(|| {
+                Arc::new(UtxosChangedSubscription::with_event_type(event_type, UtxosChangedState::All, Self::CONTEXT_LISTENER_ID, 0))
+                    as DynSubscription
+            })
+        })
     }
 
     #[cfg(test)]
@@ -36,7 +53,21 @@ impl SubscriptionContextInner {
         let address_tracker = Tracker::with_addresses(addresses);
         let utxos_changed_subscription_all =
             Arc::new(UtxosChangedSubscription::new(UtxosChangedState::All, Self::CONTEXT_LISTENER_ID));
-        Self { address_tracker, utxos_changed_subscription_to_all: utxos_changed_subscription_all }
+        Self {
+            address_tracker,
+            utxos_changed_subscription_to_all: utxos_changed_subscription_all,
+            mempool_subscriptions_to_all: Self::mempool_subscriptions_to_all(),
+        }
+    }
+
+    fn mempool_subscriptions_to_all() -> EventArray<Option<DynSubscription>> {
+        EventArray::from_fn(|i| {
+            let event_type = EVENT_TYPE_ARRAY[i];
+            (event_type.is_address_scoped() && event_type != EventType::UtxosChanged).then(|| {
+                Arc::new(UtxosChangedSubscription::with_event_type(event_type, UtxosChangedState::All, Self::CONTEXT_LISTENER_ID, 0))
+                    as DynSubscription
+            })
+        })
     }
 }
 
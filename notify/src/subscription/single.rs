@@ -3,7 +3,7 @@ use crate::{
     error::Result,
     events::EventType,
     listener::ListenerId,
-    scope::{Scope, UtxosChangedScope, VirtualChainChangedScope},
+    scope::{Scope, VirtualChainChangedScope},
     subscription::{
         BroadcastingSingle, Command, DynSubscription, Mutation, MutationOutcome, MutationPolicies, Single, Subscription,
         UtxosChangedMutationPolicy, context::SubscriptionContext,
@@ -182,9 +182,9 @@ enum UtxosChangedMutation {
     All,
 }
 
-impl From<(Command, &UtxosChangedScope)> for UtxosChangedMutation {
-    fn from((command, scope): (Command, &UtxosChangedScope)) -> Self {
-        match (command, scope.addresses.is_empty()) {
+impl From<(Command, &Vec<Address>)> for UtxosChangedMutation {
+    fn from((command, addresses): (Command, &Vec<Address>)) -> Self {
+        match (command, addresses.is_empty()) {
             (Command::Stop, true) => Self::None,
             (Command::Stop, false) => Self::Remove,
             (Command::Start, false) => Self::Add,
@@ -306,8 +306,14 @@ impl Display for UtxosChangedSubscriptionData {
     }
 }
 
+/// Subscription to notifications filtered by a set of addresses
+///
+/// Serves UtxosChanged as well as all other address scoped event types (see [`EventType::is_address_scoped`]).
 #[derive(Debug)]
 pub struct UtxosChangedSubscription {
+    /// Address scoped event type of the subscription
+    event_type: EventType,
+
     /// Mutable inner data
     data: RwLock<UtxosChangedSubscriptionData>,
 
@@ -323,8 +329,13 @@ impl UtxosChangedSubscription {
     }
 
     pub fn with_capacity(state: UtxosChangedState, listener_id: ListenerId, capacity: usize) -> Self {
+        Self::with_event_type(EventType::UtxosChanged, state, listener_id, capacity)
+    }
+
+    pub fn with_event_type(event_type: EventType, state: UtxosChangedState, listener_id: ListenerId, capacity: usize) -> Self {
+        assert!(event_type.is_address_scoped(), "{event_type} is not an address scoped event type");
         let data = RwLock::new(UtxosChangedSubscriptionData::with_capacity(state, capacity));
-        let subscription = Self { data, listener_id };
+        let subscription = Self { event_type, data, listener_id };
         trace!(
             "UtxosChangedSubscription: {} in total (new {})",
             UTXOS_CHANGED_SUBSCRIPTIONS.fetch_add(1, Ordering::SeqCst) + 1,
@@ -361,11 +372,16 @@ impl UtxosChangedSubscription {
     pub fn to_all(&self) -> bool {
         matches!(self.data().state, UtxosChangedState::All)
     }
+
+    #[inline(always)]
+    fn scope_with(&self, addresses: Vec<Address>) -> Scope {
+        Scope::with_addresses(self.event_type, addresses)
+    }
 }
 
 impl Clone for UtxosChangedSubscription {
     fn clone(&self) -> Self {
-        let subscription = Self { data: RwLock::new(self.data().clone()), listener_id: self.listener_id };
+        let subscription = Self { event_type: self.event_type, data: RwLock::new(self.data().clone()), listener_id: self.listener_id };
         trace!(
             "UtxosChangedSubscription: {} in total (clone {})",
             UTXOS_CHANGED_SUBSCRIPTIONS.fetch_add(1, Ordering::SeqCst) + 1,
@@ -421,10 +437,10 @@ impl Single for UtxosChangedSubscription {
         context: &SubscriptionContext,
     ) -> Result<MutationOutcome> {
         assert_eq!(self.event_type(), mutation.event_type());
-        let outcome = if let Scope::UtxosChanged(scope) = mutation.scope {
+        let outcome = if let Some(scope_addresses) = mutation.scope.into_addresses() {
             let mut data = self.data_mut();
             let state = data.state;
-            let mutation_type = UtxosChangedMutation::from((mutation.command, &scope));
+            let mutation_type = UtxosChangedMutation::from((mutation.command, &scope_addresses));
             match (state, mutation_type) {
                 (UtxosChangedState::None, UtxosChangedMutation::None | UtxosChangedMutation::Remove) => {
                     // State None + Mutations None or Remove(R) => No change
@@ -432,14 +448,14 @@ impl Single for UtxosChangedSubscription {
                 }
                 (UtxosChangedState::None, UtxosChangedMutation::Add) => {
                     // State None + Mutation Add(A) => Mutated new state Selected(A)
-                    let addresses = data.register(scope.addresses, context)?;
+                    let addresses = data.register(scope_addresses, context)?;
                     data.update_state(UtxosChangedState::Selected);
                     let mutations = match policies.utxo_changed {
                         UtxosChangedMutationPolicy::AddressSet => {
-                            vec![Mutation::new(mutation.command, UtxosChangedScope::new(addresses).into())]
+                            vec![Mutation::new(mutation.command, self.scope_with(addresses))]
                         }
                         UtxosChangedMutationPolicy::Wildcard => {
-                            vec![Mutation::new(mutation.command, UtxosChangedScope::default().into())]
+                            vec![Mutation::new(mutation.command, self.scope_with(vec![]))]
                         }
                     };
                     MutationOutcome::with_mutated(current.clone(), mutations)
@@ -447,7 +463,7 @@ impl Single for UtxosChangedSubscription {
                 (UtxosChangedState::None, UtxosChangedMutation::All) => {
                     // State None + Mutation All => Mutated new state All
                     data.update_state(UtxosChangedState::All);
-                    let mutations = vec![Mutation::new(mutation.command, UtxosChangedScope::default().into())];
+                    let mutations = vec![Mutation::new(mutation.command, self.scope_with(vec![]))];
                     MutationOutcome::with_mutated(current.clone(), mutations)
                 }
                 (UtxosChangedState::Selected, UtxosChangedMutation::None) => {
@@ -457,22 +473,22 @@ impl Single for UtxosChangedSubscription {
                     assert!(!removed.is_empty(), "state Selected implies a non empty address set");
                     let mutations = match policies.utxo_changed {
                         UtxosChangedMutationPolicy::AddressSet => {
-                            vec![Mutation::new(Command::Stop, UtxosChangedScope::new(removed).into())]
+                            vec![Mutation::new(Command::Stop, self.scope_with(removed))]
                         }
                         UtxosChangedMutationPolicy::Wildcard => {
-                            vec![Mutation::new(Command::Stop, UtxosChangedScope::default().into())]
+                            vec![Mutation::new(Command::Stop, self.scope_with(vec![]))]
                         }
                     };
                     MutationOutcome::with_mutated(current.clone(), mutations)
                 }
                 (UtxosChangedState::Selected, UtxosChangedMutation::Remove) => {
                     // State Selected(S) + Mutation Remove(R) => Mutated state Selected(S – R) or mutated new state None or no change
-                    let removed = data.unregister(scope.addresses, context);
+                    let removed = data.unregister(scope_addresses, context);
                     match (removed.is_empty(), data.indexes.is_empty()) {
                         (false, false) => {
                             let mutations = match policies.utxo_changed {
                                 UtxosChangedMutationPolicy::AddressSet => {
-                                    vec![Mutation::new(Command::Stop, UtxosChangedScope::new(removed).into())]
+                                    vec![Mutation::new(Command::Stop, self.scope_with(removed))]
                                 }
                                 UtxosChangedMutationPolicy::Wildcard => vec![],
                             };
@@ -482,10 +498,10 @@ impl Single for UtxosChangedSubscription {
                             data.update_state(UtxosChangedState::None);
                             let mutations = match policies.utxo_changed {
                                 UtxosChangedMutationPolicy::AddressSet => {
-                                    vec![Mutation::new(Command::Stop, UtxosChangedScope::new(removed).into())]
+                                    vec![Mutation::new(Command::Stop, self.scope_with(removed))]
                                 }
                                 UtxosChangedMutationPolicy::Wildcard => {
-                                    vec![Mutation::new(Command::Stop, UtxosChangedScope::default().into())]
+                                    vec![Mutation::new(Command::Stop, self.scope_with(vec![]))]
                                 }
                             };
                             MutationOutcome::with_mutated(current.clone(), mutations)
@@ -495,12 +511,12 @@ impl Single for UtxosChangedSubscription {
                 }
                 (UtxosChangedState::Selected, UtxosChangedMutation::Add) => {
                     // State Selected(S) + Mutation Add(A) => Mutated state Selected(A ∪ S)
-                    let added = data.register(scope.addresses, context)?;
+                    let added = data.register(scope_addresses, context)?;
                     match added.is_empty() {
                         false => {
                             let mutations = match policies.utxo_changed {
                                 UtxosChangedMutationPolicy::AddressSet => {
-                                    vec![Mutation::new(Command::Start, UtxosChangedScope::new(added).into())]
+                                    vec![Mutation::new(Command::Start, self.scope_with(added))]
                                 }
                                 UtxosChangedMutationPolicy::Wildcard => vec![],
                             };
@@ -516,8 +532,8 @@ impl Single for UtxosChangedSubscription {
                     data.update_state(UtxosChangedState::All);
                     let mutations = match policies.utxo_changed {
                         UtxosChangedMutationPolicy::AddressSet => vec![
-                            Mutation::new(Command::Stop, UtxosChangedScope::new(removed).into()),
-                            Mutation::new(Command::Start, UtxosChangedScope::default().into()),
+                            Mutation::new(Command::Stop, self.scope_with(removed)),
+                            Mutation::new(Command::Start, self.scope_with(vec![])),
                         ],
                         UtxosChangedMutationPolicy::Wildcard => vec![],
                     };
@@ -526,7 +542,7 @@ impl Single for UtxosChangedSubscription {
                 (UtxosChangedState::All, UtxosChangedMutation::None) => {
                     // State All + Mutation None => Mutated new state None
                     data.update_state(UtxosChangedState::None);
-                    let mutations = vec![Mutation::new(Command::Stop, UtxosChangedScope::default().into())];
+                    let mutations = vec![Mutation::new(Command::Stop, self.scope_with(vec![]))];
                     MutationOutcome::with_mutated(current.clone(), mutations)
                 }
                 (UtxosChangedState::All, UtxosChangedMutation::Remove) => {
@@ -535,12 +551,12 @@ impl Single for UtxosChangedSubscription {
                 }
                 (UtxosChangedState::All, UtxosChangedMutation::Add) => {
                     // State All + Mutation Add(A) => Mutated new state Selectee(A)
-                    let added = data.register(scope.addresses, context)?;
+                    let added = data.register(scope_addresses, context)?;
                     data.update_state(UtxosChangedState::Selected);
                     let mutations = match policies.utxo_changed {
                         UtxosChangedMutationPolicy::AddressSet => vec![
-                            Mutation::new(Command::Start, UtxosChangedScope::new(added).into()),
-                            Mutation::new(Command::Stop, UtxosChangedScope::default().into()),
+                            Mutation::new(Command::Start, self.scope_with(added)),
+                            Mutation::new(Command::Stop, self.scope_with(vec![])),
                         ],
                         UtxosChangedMutationPolicy::Wildcard => vec![],
                     };
@@ -560,7 +576,7 @@ impl Single for UtxosChangedSubscription {
 
 impl Subscription for UtxosChangedSubscription {
     fn event_type(&self) -> EventType {
-        EventType::UtxosChanged
+        self.event_type
     }
 
     fn active(&self) -> bool {
@@ -569,7 +585,7 @@ impl Subscription for UtxosChangedSubscription {
 
     fn scope(&self, context: &SubscriptionContext) -> Scope {
         // TODO: consider using a provided prefix
-        UtxosChangedScope::new(self.data().to_addresses(Prefix::Mainnet, context)).into()
+        self.scope_with(self.data().to_addresses(Prefix::Mainnet, context))
     }
 }
 
@@ -583,6 +599,13 @@ impl BroadcastingSingle for DynSubscription {
                     false => self,
                 }
             }
+            event_type if event_type.is_address_scoped() => {
+                let mempool_subscription = self.as_any().downcast_ref::<UtxosChangedSubscription>().unwrap();
+                match (mempool_subscription.to_all(), &context.mempool_subscriptions_to_all[event_type]) {
+                    (true, Some(subscription_to_all)) => subscription_to_all.clone(),
+                    _ => self,
+                }
+            }
             _ => self,
         }
     }
@@ -592,7 +615,10 @@ impl BroadcastingSingle for DynSubscription {
 mod tests {
     use super::super::*;
     use super::*;
-    use crate::{address::test_helpers::get_3_addresses, scope::BlockAddedScope};
+    use crate::{
+        address::test_helpers::get_3_addresses,
+        scope::{BlockAddedScope, UtxosChangedScope},
+    };
     use std::collections::hash_map::DefaultHasher;
 
     #[test]
@@ -1082,4 +1108,24 @@ mod tests {
         ]);
         tests.run(&context)
     }
+
+    #[test]
+    fn test_mempool_broadcasting() {
+        let context = SubscriptionContext::new();
+        let event_type = EventType::MempoolTransactionAdded;
+        let subscription = |state: UtxosChangedState, listener_id: ListenerId| {
+            Arc::new(UtxosChangedSubscription::with_event_type(event_type, state, listener_id, 0)) as DynSubscription
+        };
+
+        // Blanket subscriptions of all listeners are collapsed into the single context instance of their event type
+        let to_all = context.mempool_subscriptions_to_all[event_type].clone().unwrap();
+        let broadcasting = subscription(UtxosChangedState::All, 1).broadcasting(&context);
+        assert!(Arc::ptr_eq(&broadcasting, &to_all));
+        assert!(Arc::ptr_eq(&subscription(UtxosChangedState::All, 2).broadcasting(&context), &to_all));
+        assert_eq!(broadcasting.event_type(), event_type);
+
+        // Subscriptions to selected addresses are kept as is
+        let selected = subscription(UtxosChangedState::Selected, 3);
+        assert!(Arc::ptr_eq(&selected.clone().broadcasting(&context), &selected));
+    }
 }
@@ -1,9 +1,11 @@
 use crate::flowcontext::{
+    misbehavior::{MisbehaviorScore, MisbehaviorTracker},
     orphans::{OrphanBlocksPool, OrphanOutput},
     process_queue::ProcessQueue,
     transactions::TransactionsSpread,
 };
 use crate::user_agent_rule::{UserAgentRuleRejectReason, UserAgentRuleSet};
+use crate::v7::blockrelay::compact::CompactBlockRelayCounters;
 use crate::{v7, v8, v10};
 use async_trait::async_trait;
 use futures::future::join_all;
@@ -32,13 +34,13 @@ use kaspa_notify::notifier::Notify;
 use kaspa_p2p_lib::{
     ConnectionInitializer, Hub, KaspadHandshake, PeerKey, PeerProperties, Router,
     common::ProtocolError,
-    convert::model::version::Version,
+    convert::model::version::{ENCRYPTED_TRANSPORT_SERVICE, ONION_ADDRESSES_SERVICE, PACKAGE_RELAY_SERVICE, Version},
     make_message,
     pb::{InvRelayBlockMessage, kaspad_message::Payload},
 };
 use kaspa_p2p_mining::rule_engine::MiningRuleEngine;
 use kaspa_utils::iter::IterExtensions;
-use kaspa_utils::networking::PeerId;
+use kaspa_utils::networking::{IpAddress, PeerId};
 use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
 use std::time::Instant;
@@ -60,7 +62,13 @@ use tokio_stream::{StreamExt, wrappers::UnboundedReceiverStream};
 use uuid::Uuid;
 
 /// The P2P protocol version.
-const PROTOCOL_VERSION: u32 = 10;
+const PROTOCOL_VERSION: u32 = 11;
+
+/// The protocol version required from peers once Toccata is about to activate
+const TOCCATA_PROTOCOL_VERSION: u32 = 10;
+
+/// The protocol version introducing compact block relay
+pub const COMPACT_BLOCK_RELAY_PROTOCOL_VERSION: u32 = 11;
 
 /// See `check_orphan_resolution_range`
 const BASELINE_ORPHAN_RESOLUTION_RANGE: u32 = 5;
@@ -289,6 +297,10 @@ pub struct FlowContextInner {
 
     // Mining rule engine
     mining_rule_engine: Arc<MiningRuleEngine>,
+
+    compact_block_relay_counters: Arc<CompactBlockRelayCounters>,
+
+    misbehavior_tracker: MisbehaviorTracker,
 }
 
 #[derive(Clone)]
@@ -394,6 +406,8 @@ impl FlowContext {
                 max_orphans,
                 config,
                 mining_rule_engine,
+                compact_block_relay_counters: Default::default(),
+                misbehavior_tracker: Default::default(),
             }),
         }
     }
@@ -440,6 +454,39 @@ impl FlowContext {
         &self.mining_manager
     }
 
+    pub fn compact_block_relay_counters(&self) -> &Arc<CompactBlockRelayCounters> {
+        &self.compact_block_relay_counters
+    }
+
+    /// Returns the current misbehavior score of the peers with the given IP, if any
+    pub fn misbehavior_score(&self, ip: IpAddress) -> Option<MisbehaviorScore> {
+        self.misbehavior_tracker.get(ip, unix_now())
+    }
+
+    /// Adds the misbehavior indicated by `err`, if any, to the score of the peer. Peers reaching the
+    /// configured ban threshold are banned for the configured ban duration.
+    pub async fn report_misbehavior(&self, router: &Router, err: &ProtocolError) {
+        let Some(misbehavior) = err.misbehavior() else {
+            return;
+        };
+        let ip: IpAddress = router.net_address().ip().into();
+        let reason = format!("{misbehavior}: {err}");
+        let score = self.misbehavior_tracker.add(ip, misbehavior, reason.clone(), unix_now());
+        debug!("P2P, misbehavior score of peer {} increased to {} ({})", router, score, reason);
+        if self.config.ban_threshold == 0 || score < self.config.ban_threshold {
+            return;
+        }
+        let Some(connection_manager) = self.connection_manager() else {
+            return;
+        };
+        let duration = Duration::from_secs(self.config.ban_duration);
+        let reason = format!("misbehavior score {score} reached the ban threshold, latest {reason}");
+        if connection_manager.ban_for(ip.into(), duration, reason).await {
+            warn!("Banned peer {} for {:?} after reaching misbehavior score {} ({})", router, duration, score, misbehavior);
+            self.misbehavior_tracker.remove(ip);
+        }
+    }
+
     pub fn try_set_ibd_running(&self, peer: PeerKey, relay_daa_score: u64) -> Option<IbdRunningGuard> {
         if self.is_ibd_running.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
             self.ibd_metadata.write().replace(IbdMetadata { peer, daa_score: relay_daa_score });
@@ -726,6 +773,25 @@ impl FlowContext {
         ))
     }
 
+    /// Validates the rpc-submitted transaction package as a unit, inserts it into the mempool and propagates it to peers.
+    ///
+    /// Returns the accepted transactions in topological order. Transactions of the package which are already in the mempool
+    /// are not part of the result.
+    pub async fn submit_rpc_transaction_package(
+        &self,
+        consensus: &ConsensusProxy,
+        transactions: Vec<Transaction>,
+    ) -> Result<Vec<Arc<Transaction>>, ProtocolError> {
+        let transaction_insertion =
+            self.mining_manager().clone().validate_and_insert_transaction_package(consensus, transactions, Priority::High).await?;
+        self.broadcast_transactions(
+            transaction_insertion.accepted.iter().map(|x| x.id()),
+            false, // RPC transactions are considered high priority, so we don't want to throttle them
+        )
+        .await;
+        Ok(transaction_insertion.accepted)
+    }
+
     /// Returns true if the time has come for running the task cleaning mempool transactions.
     async fn should_run_mempool_scanning_task(&self) -> bool {
         self.transactions_spread.write().await.should_run_mempool_scanning_task()
@@ -765,10 +831,15 @@ impl ConnectionInitializer for FlowContext {
 
         let network_name = self.config.network_name();
 
-        let local_address = self.address_manager.lock().best_local_address();
+        // Behind a proxy, our address is only advertised when explicitly configured
+        let local_address = if self.config.proxy.is_some() && self.config.externalip.is_none() {
+            None
+        } else {
+            self.address_manager.lock().best_local_address()
+        };
 
         // Networks with a scheduled Toccata activation advertise the current protocol version.
-        // Other networks still support v10 locally, but advertise v9 so future Toccata-activated peers reject them.
+        // Other networks still support v10 and above locally, but advertise v9 so future Toccata-activated peers reject them.
         let advertise_toccata_p2p = self.config.toccata_activation != ForkActivation::never();
         let advertised_protocol_version = if advertise_toccata_p2p { PROTOCOL_VERSION } else { 9 };
 
@@ -777,6 +848,7 @@ impl ConnectionInitializer for FlowContext {
         let mut self_version_message =
             Version::new(local_address, self.node_id, network_name.clone(), None, advertised_protocol_version);
         self_version_message.add_user_agent(name(), version(), &self.config.user_agent_comments);
+        self_version_message.services |= PACKAGE_RELAY_SERVICE | ONION_ADDRESSES_SERVICE | ENCRYPTED_TRANSPORT_SERVICE;
         // TODO: disable_relay_tx from config/cmd
 
         // Perform the handshake
@@ -819,6 +891,15 @@ impl ConnectionInitializer for FlowContext {
             return Err(ProtocolError::OtherOwned(format!("peer user agent rejected: {}", peer_version.user_agent)));
         }
 
+        // Encrypt the transport if the peer supports it, otherwise fall back to plaintext
+        let transport_session_id = if peer_version.services & ENCRYPTED_TRANSPORT_SERVICE != 0 {
+            let session_id = handshake.negotiate_encryption().await?;
+            debug!("P2P, encrypted transport established with peer {}, session id: {}", router, session_id);
+            Some(session_id)
+        } else {
+            None
+        };
+
         debug!("protocol versions - self: {}, peer: {}", PROTOCOL_VERSION, peer_version.protocol_version);
 
         let peer_protocol_version = peer_version.protocol_version;
@@ -834,16 +915,28 @@ impl ConnectionInitializer for FlowContext {
         //
         // Note: post-activation fresh nodes with virtual DAA score near genesis are not covered here and
         // are guarded later during IBD by `validate_pruning_point_freshness_for_toccata`.
+        //
+        // Compact block relay changes the responses to relay block requests, so it is only applied if
+        // both sides advertise it.
+        let toccata_flows_version = if peer_protocol_version.min(advertised_protocol_version) >= COMPACT_BLOCK_RELAY_PROTOCOL_VERSION {
+            COMPACT_BLOCK_RELAY_PROTOCOL_VERSION
+        } else {
+            TOCCATA_PROTOCOL_VERSION
+        };
         let (flows, applied_protocol_version) = if connect_only_new_versions {
             // Register all flows according to version
             match peer_protocol_version {
-                v if v >= PROTOCOL_VERSION => (v10::register(self.clone(), router.clone(), PROTOCOL_VERSION), PROTOCOL_VERSION),
-                v => return Err(ProtocolError::VersionMismatch(PROTOCOL_VERSION, v)),
+                v if v >= TOCCATA_PROTOCOL_VERSION => {
+                    (v10::register(self.clone(), router.clone(), toccata_flows_version), toccata_flows_version)
+                }
+                v => return Err(ProtocolError::VersionMismatch(TOCCATA_PROTOCOL_VERSION, v)),
             }
         } else {
             // Register all flows according to version
             match peer_protocol_version {
-                v if v >= PROTOCOL_VERSION => (v10::register(self.clone(), router.clone(), PROTOCOL_VERSION), PROTOCOL_VERSION),
+                v if v >= TOCCATA_PROTOCOL_VERSION => {
+                    (v10::register(self.clone(), router.clone(), toccata_flows_version), toccata_flows_version)
+                }
                 9 => (v8::register(self.clone(), router.clone(), 9), 9),
                 8 => (v8::register(self.clone(), router.clone(), 8), 8),
                 7 => (v7::register(self.clone(), router.clone()), 7),
@@ -854,11 +947,13 @@ impl ConnectionInitializer for FlowContext {
         // Build and register the peer properties
         let peer_properties = Arc::new(PeerProperties {
             user_agent: peer_version.user_agent,
+            services: peer_version.services,
             advertised_protocol_version: peer_version.protocol_version,
             protocol_version: applied_protocol_version,
             disable_relay_tx: peer_version.disable_relay_tx,
             subnetwork_id: peer_version.subnetwork_id,
             time_offset,
+            transport_session_id,
         });
         router.set_properties(peer_properties);
 
@@ -869,7 +964,7 @@ impl ConnectionInitializer for FlowContext {
 
         // Launch all flows. Note we launch only after the ready signal was exchanged
         for flow in flows {
-            flow.launch();
+            flow.launch(self.clone());
         }
 
         if router.is_outbound() || peer_version.address.is_some() {
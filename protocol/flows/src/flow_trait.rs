@@ -1,3 +1,4 @@
+use crate::flow_context::FlowContext;
 use kaspa_core::warn;
 use kaspa_p2p_lib::{Router, common::ProtocolError};
 use kaspa_utils::any::type_name_short;
@@ -16,7 +17,8 @@ where
 
     async fn start(&mut self) -> Result<(), ProtocolError>;
 
-    fn launch(mut self: Box<Self>) {
+    /// Spawns the flow. On error, the peer is disconnected and charged with the misbehavior indicated by the error
+    fn launch(mut self: Box<Self>, ctx: FlowContext) {
         tokio::spawn(async move {
             let res = self.start().await;
             if let Err(err) = res
@@ -26,6 +28,7 @@ where
                 if router.close().await || !err.is_connection_closed_error() {
                     warn!("{} flow error: {}, disconnecting from peer {}.", self.name(), err, router);
                 }
+                ctx.report_misbehavior(&router, &err).await;
             }
         });
     }
@@ -0,0 +1,94 @@
+use kaspa_p2p_lib::common::Misbehavior;
+use kaspa_utils::networking::IpAddress;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// The amount by which misbehavior scores decay every minute
+const SCORE_DECAY_PER_MINUTE: u64 = 1;
+const MINUTE_MILLIS: u64 = 60 * 1000;
+
+/// The misbehavior score of a peer along with its latest misbehavior
+#[derive(Clone, Debug)]
+pub struct MisbehaviorScore {
+    pub score: u32,
+    /// A description of the latest misbehavior of the peer
+    pub reason: String,
+    /// Unix timestamp (in milliseconds) up to which the decay was applied
+    decayed_until: u64,
+}
+
+impl MisbehaviorScore {
+    /// Applies the decay accumulated since the last update, in whole minutes
+    fn decay(&mut self, now: u64) {
+        let minutes = now.saturating_sub(self.decayed_until) / MINUTE_MILLIS;
+        self.score = (self.score as u64).saturating_sub(minutes * SCORE_DECAY_PER_MINUTE) as u32;
+        self.decayed_until += minutes * MINUTE_MILLIS;
+    }
+}
+
+/// Tracks the misbehavior scores of peers by IP, so that scores survive reconnections. Scores decay
+/// linearly over time, so only misbehavior repeated in short succession accumulates to a ban.
+#[derive(Default)]
+pub struct MisbehaviorTracker {
+    scores: Mutex<HashMap<IpAddress, MisbehaviorScore>>,
+}
+
+impl MisbehaviorTracker {
+    /// Adds the penalty of `misbehavior` to the score of `ip` and returns the updated score
+    pub fn add(&self, ip: IpAddress, misbehavior: Misbehavior, reason: String, now: u64) -> u32 {
+        let mut scores = self.scores.lock();
+        scores.retain(|_, entry| {
+            entry.decay(now);
+            entry.score > 0
+        });
+        let entry = scores.entry(ip).or_insert_with(|| MisbehaviorScore { score: 0, reason: Default::default(), decayed_until: now });
+        entry.score = entry.score.saturating_add(misbehavior.penalty());
+        entry.reason = reason;
+        entry.score
+    }
+
+    /// Returns the current score of `ip`, if positive
+    pub fn get(&self, ip: IpAddress, now: u64) -> Option<MisbehaviorScore> {
+        let mut scores = self.scores.lock();
+        let entry = scores.get_mut(&ip)?;
+        entry.decay(now);
+        (entry.score > 0).then(|| entry.clone())
+    }
+
+    pub fn remove(&self, ip: IpAddress) {
+        self.scores.lock().remove(&ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn test_misbehavior_score_accumulation_and_decay() {
+        let tracker = MisbehaviorTracker::default();
+        let ip: IpAddress = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)).into();
+        let other: IpAddress = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)).into();
+        let now = 1_000_000;
+
+        assert!(tracker.get(ip, now).is_none());
+        assert_eq!(tracker.add(ip, Misbehavior::Timeout, "first".to_owned(), now), 10);
+        assert_eq!(tracker.add(ip, Misbehavior::UnrequestedData, "second".to_owned(), now), 30);
+        assert_eq!(tracker.add(other, Misbehavior::InvalidTransaction, "other".to_owned(), now), 50);
+
+        // Partial minutes do not decay the score, but are accounted for by later queries
+        let score = tracker.get(ip, now + MINUTE_MILLIS * 3 / 2).unwrap();
+        assert_eq!(score.score, 29);
+        assert_eq!(score.reason, "second");
+        assert_eq!(tracker.get(ip, now + MINUTE_MILLIS * 2).unwrap().score, 28);
+
+        // Fully decayed scores are dropped
+        assert!(tracker.get(ip, now + MINUTE_MILLIS * 30).is_none());
+        assert_eq!(tracker.add(ip, Misbehavior::Timeout, "third".to_owned(), now + MINUTE_MILLIS * 30), 10);
+        assert_eq!(tracker.get(other, now + MINUTE_MILLIS * 30).unwrap().score, 20);
+
+        tracker.remove(other);
+        assert!(tracker.get(other, now + MINUTE_MILLIS * 30).is_none());
+    }
+}
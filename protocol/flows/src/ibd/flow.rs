@@ -7,7 +7,7 @@ use futures::future::{Either, join_all, select, try_join_all};
 use itertools::Itertools;
 use kaspa_consensus_core::{
     BlockHashSet,
-    api::BlockValidationFuture,
+    api::{BlockValidationFuture, SmtExportMetadata},
     block::Block,
     config::params::{ForkActivation, Params},
     header::Header,
@@ -24,7 +24,7 @@ use kaspa_p2p_lib::{
     common::ProtocolError,
     convert::{
         header::{HeaderFormat, Versioned},
-        model::trusted::TrustedDataPackage,
+        model::trusted::{TrustedDataEntry, TrustedDataPackage},
     },
     dequeue_with_timeout, make_message, make_request,
     pb::{
@@ -33,6 +33,7 @@ use kaspa_p2p_lib::{
         kaspad_message::Payload,
     },
 };
+use kaspa_seq_commit::verify::{SmtMetadata, verify_smt_metadata};
 use kaspa_utils::channel::JobReceiver;
 use std::{
     sync::Arc,
@@ -392,7 +393,7 @@ impl IbdFlow {
         let pruning_point = self.sync_and_validate_pruning_proof(&staging_session, relay_block).await?;
         self.sync_headers(&staging_session, syncer_virtual_selected_parent, pruning_point, relay_block).await?;
         staging_session.async_validate_pruning_points(syncer_virtual_selected_parent).await?;
-        self.validate_staging_timestamps(&self.ctx.consensus().session().await, &staging_session).await?;
+        validate_staging_timestamps(&self.ctx.consensus().session().await, &staging_session).await?;
         Ok(())
     }
 
@@ -424,34 +425,8 @@ impl IbdFlow {
         );
 
         let proof_metadata = PruningProofMetadata::new(relay_block.header.blue_work);
-
-        // Get a new session for current consensus (non staging)
-        let consensus = self.ctx.consensus().session().await;
-
-        // The proof is validated in the context of current consensus
-        let proof =
-            consensus.clone().spawn_blocking(move |c| c.validate_pruning_proof(&proof, &proof_metadata).map(|()| proof)).await?;
-
-        let proof_pruning_point_header = proof[0].last().expect("was just ensured by validation");
-        let proof_pruning_point = proof_pruning_point_header.hash;
-
-        if proof_pruning_point == self.ctx.config.genesis.hash {
-            return Err(ProtocolError::Other("the proof pruning point is the genesis block"));
-        }
-
-        if proof_pruning_point == consensus.async_pruning_point().await {
-            return Err(ProtocolError::Other("the proof pruning point is the same as the current pruning point"));
-        }
-        drop(consensus);
-
-        // [Toccata] Reject IBD from outdated peers
-        validate_pruning_point_freshness_for_toccata(
-            self.ctx.config.as_ref(),
-            proof_pruning_point_header.hash,
-            proof_pruning_point_header.timestamp,
-            proof_pruning_point_header.daa_score,
-            unix_now(),
-        )?;
+        let proof = validate_pruning_proof(&self.ctx, proof, proof_metadata).await?;
+        let proof_pruning_point = proof[0].last().expect("was just ensured by validation").hash;
 
         self.router
             .enqueue(make_message!(Payload::RequestPruningPointAndItsAnticone, RequestPruningPointAndItsAnticoneMessage {}))
@@ -459,33 +434,7 @@ impl IbdFlow {
         // First, all pruning points up to the last are sent
         let msg = dequeue_with_timeout!(self.incoming_route, Payload::PruningPoints)?;
         let pruning_points: PruningPointsList = Versioned(self.header_format, msg).try_into()?;
-
-        if pruning_points.is_empty() || pruning_points.last().unwrap().hash != proof_pruning_point {
-            return Err(ProtocolError::Other("the proof pruning point is not equal to the last pruning point in the list"));
-        }
-
-        if pruning_points.first().unwrap().hash != self.ctx.config.genesis.hash {
-            return Err(ProtocolError::Other("the first pruning point in the list is expected to be genesis"));
-        }
-
-        // Check if past pruning points violate finality of current consensus
-        if self.ctx.consensus().session().await.async_are_pruning_points_violating_finality(pruning_points.clone()).await {
-            // TODO (relaxed): consider performing additional actions on finality conflicts in addition to disconnecting from the peer (e.g., banning, rpc notification)
-            return Err(ProtocolError::Other("pruning points are violating finality"));
-        }
-
-        {
-            // Sanity check for consistency between past pruning points and the headers proof
-            let pruning_points_set: BlockHashSet = pruning_points.iter().map(|h| h.hash).collect();
-            for level in proof.iter() {
-                if let Some(root) = level.first()
-                    && root.hash != self.ctx.config.genesis.hash
-                    && !pruning_points_set.contains(&root.pruning_point)
-                {
-                    return Err(ProtocolError::Other("proof and past pruning points are inconsistent with each other"));
-                }
-            }
-        }
+        validate_past_pruning_points(&self.ctx, &proof, &pruning_points).await?;
 
         // Trusted data is sent in two stages:
         // The first, TrustedDataPackage, contains meta data about daa_window
@@ -502,129 +451,12 @@ impl IbdFlow {
         let Some(pruning_point_entry) = entry_stream.next().await? else {
             return Err(ProtocolError::Other("got `done` message before receiving the pruning point"));
         };
-
-        if pruning_point_entry.block.is_header_only() {
-            return Err(ProtocolError::Other("pruning point entry is header-only"));
-        }
-
-        if pruning_point_entry.block.hash() != proof_pruning_point {
-            return Err(ProtocolError::Other("the proof pruning point is not equal to the expected trusted entry"));
-        }
-
-        // TODO(optimization): this buffering can be heavy on RAM for large chain segments, but is acceptable
-        // since syncee memory usage is still low at this phase.
-        let mut entries = vec![pruning_point_entry];
-        let mut header_only_chain_segment = Vec::new();
-        // Each selected-chain block contributes at least one blue score, so F blue-depth back is bounded
-        // by F chain blocks (plus 2K for noise/robustness).
-        let max_header_only_chain_segment_len =
-            self.ctx.config.finality_depth().saturating_add(2 * self.ctx.config.ghostdag_k() as u64 + 1);
+        let mut trusted_entries = TrustedEntriesCollector::new(&self.ctx, pruning_point_entry, proof_pruning_point)?;
         while let Some(entry) = entry_stream.next().await? {
-            match entry.block.is_header_only() {
-                true => {
-                    if header_only_chain_segment.is_empty() {
-                        info!("Finished downloading {} blocks from the pruning point anticone", entries.len() - 1);
-                        info!("Starting to download the pruning point chain segment");
-                    }
-                    header_only_chain_segment.push(entry.block.header.clone());
-                    if header_only_chain_segment.len().is_multiple_of(1000) {
-                        info!("Downloaded {} headers from the pruning point chain segment", header_only_chain_segment.len());
-
-                        if header_only_chain_segment.len() as u64 > max_header_only_chain_segment_len {
-                            return Err(ProtocolError::OtherOwned(format!(
-                                "pruning point chain segment length {} exceeds maximum {}",
-                                header_only_chain_segment.len(),
-                                max_header_only_chain_segment_len
-                            )));
-                        }
-                    }
-                }
-                // We expect all header-only entries to be sent after all non-header-only entries
-                false if header_only_chain_segment.is_empty() => {
-                    entries.push(entry);
-                    if (entries.len() - 1).is_multiple_of(1000) {
-                        info!("Downloaded {} blocks from the pruning point anticone", entries.len() - 1);
-                    }
-                }
-                false => {
-                    return Err(ProtocolError::Other("trusted body entries arrived after header-only trusted entries"));
-                }
-            }
-        }
-
-        if header_only_chain_segment.is_empty() {
-            // No chain segment means the anticone was not logged yet.
-            info!("Finished downloading {} blocks from the pruning point anticone", entries.len() - 1);
-        } else {
-            info!("Finished downloading {} headers from the pruning point chain segment", header_only_chain_segment.len());
-        }
-
-        // Create a topologically ordered vector of trusted blocks - the pruning point and its anticone,
-        // and their daa windows headers
-        let mut trusted_set = pkg.build_trusted_subdag(entries)?;
-
-        if self.ctx.config.enable_sanity_checks {
-            let con = self.ctx.consensus().unguarded_session_blocking();
-            trusted_set = staging
-                .clone()
-                .spawn_blocking(move |c| {
-                    let ref_proof = proof.clone();
-                    c.apply_pruning_proof(proof, &trusted_set, &header_only_chain_segment)?;
-                    c.import_pruning_points(pruning_points)?;
-
-                    info!("Building the proof which was just applied (sanity test)");
-                    let built_proof = c.get_pruning_point_proof();
-                    let mut mismatch_detected = false;
-                    for (i, (ref_level, built_level)) in ref_proof.iter().zip(built_proof.iter()).enumerate() {
-                        if ref_level.iter().map(|h| h.hash).collect::<BlockHashSet>()
-                            != built_level.iter().map(|h| h.hash).collect::<BlockHashSet>()
-                        {
-                            mismatch_detected = true;
-                            warn!("Locally built proof for level {} does not match the applied one", i);
-                        }
-                    }
-                    if mismatch_detected {
-                        info!("Validating the locally built proof (sanity test fallback #2)");
-                        // Note: the proof is validated in the context of *current* consensus
-                        if let Err(err) = con.validate_pruning_proof(&built_proof, &proof_metadata) {
-                            panic!("Locally built proof failed validation: {}", err);
-                        }
-                        info!("Locally built proof was validated successfully");
-                    } else {
-                        info!("Proof was locally built successfully");
-                    }
-                    Result::<_, ProtocolError>::Ok(trusted_set)
-                })
-                .await?;
-        } else {
-            trusted_set = staging
-                .clone()
-                .spawn_blocking(move |c| {
-                    c.apply_pruning_proof(proof, &trusted_set, &header_only_chain_segment)?;
-                    c.import_pruning_points(pruning_points)?;
-                    Result::<_, ProtocolError>::Ok(trusted_set)
-                })
-                .await?;
+            trusted_entries.push(entry)?;
         }
 
-        // TODO (relaxed): add logs to staging commit process
-
-        info!("Starting to process {} trusted blocks", trusted_set.len());
-        let mut last_time = Instant::now();
-        let mut last_index: usize = 0;
-        for (i, tb) in trusted_set.into_iter().enumerate() {
-            let now = Instant::now();
-            let passed = now.duration_since(last_time);
-            if passed > Duration::from_secs(1) {
-                info!("Processed {} trusted blocks in the last {:.2}s (total {})", i - last_index, passed.as_secs_f64(), i);
-                last_time = now;
-                last_index = i;
-            }
-            // TODO (relaxed): queue and join in batches
-            staging.validate_and_insert_trusted_block(tb).virtual_state_task.await?;
-        }
-        staging.async_clear_body_missing_anticone_set().await;
-        info!("Done processing trusted blocks");
+        apply_pruning_proof_and_trusted_data(&self.ctx, staging, proof, proof_metadata, pruning_points, pkg, trusted_entries).await?;
         Ok(proof_pruning_point)
     }
 
@@ -697,7 +529,6 @@ impl IbdFlow {
     async fn sync_new_smt_state(&mut self, consensus: &ConsensusProxy, pruning_point: Hash) -> Result<(), ProtocolError> {
         use super::streams::SmtStream;
         use kaspa_p2p_lib::pb::RequestPruningPointSmtStateMessage;
-        use kaspa_seq_commit::verify::{SmtMetadata, verify_smt_metadata};
 
         let pp_header = consensus.async_get_header(pruning_point).await.unwrap();
         if !self.ctx.config.toccata_activation.is_active(pp_header.daa_score) {
@@ -720,36 +551,7 @@ impl IbdFlow {
 
         // Phase 0: receive and verify metadata. Single 96-byte wire.
         let md = stream.recv_metadata().await?;
-        let parent_header = consensus.async_get_header(pp_header.direct_parents()[0]).await.unwrap();
-
-        // Derive the shortcut block via consensus (uses reachability + headers only; safe at the PP
-        // boundary before the SMT is imported). Then resolve to the seqcommit hash with the same
-        // fold-to-zero rule used by `inactivity_shortcut(block)`.
-        let shortcut_block = consensus
-            .async_inactivity_shortcut_block_for_pov(pruning_point)
-            .await
-            .map_err(|e| ProtocolError::OtherOwned(format!("inactivity_shortcut_block resolution failed: {e}")))?;
-        let shortcut_header = consensus
-            .async_get_header(shortcut_block)
-            .await
-            .map_err(|_| ProtocolError::Other("inactivity_shortcut_block header not found"))?;
-        let inactivity_shortcut = if !self.ctx.config.toccata_activation.is_active(shortcut_header.daa_score) {
-            kaspa_hashes::ZERO_HASH
-        } else {
-            shortcut_header.accepted_id_merkle_root
-        };
-
-        verify_smt_metadata(
-            &SmtMetadata {
-                lanes_root: &md.lanes_root,
-                payload_and_ctx_digest: &md.payload_and_ctx_digest,
-                parent_seq_commit: &md.parent_seq_commit,
-            },
-            inactivity_shortcut,
-            pp_header.accepted_id_merkle_root,
-            parent_header.accepted_id_merkle_root,
-        )
-        .map_err(|e| ProtocolError::OtherOwned(format!("SMT metadata verification failed: {e}")))?;
+        let shortcut_block = verify_pruning_point_smt_metadata(&self.ctx, consensus, pruning_point, &md).await?;
 
         // Small queue of already-chunked batches: one in flight + one being processed
         // by the importer is enough headroom; each chunk holds up to SMT_CHUNK_SIZE lanes.
@@ -776,11 +578,7 @@ impl IbdFlow {
         consensus.async_clear_pruning_utxo_set().await; // this deletes the old pruning utxoset and also sets the pruning utxo as invalidated
         self.sync_pruning_point_utxoset(consensus, pruning_point).await?;
         // Only if the function has reached here, will the utxo be considered "final"
-        consensus.async_set_pruning_utxoset_stable().await;
-        // Once a new utxoset is stored, the utxoindex needs to be resynced as well. This happens through the reset handler mechanism.
-        let consensus_manager = self.ctx.consensus_manager.clone();
-        spawn_blocking(move || consensus_manager.invoke_consensus_reset_handlers()).await.unwrap();
-        self.ctx.on_pruning_point_utxoset_override();
+        finalize_new_utxo_set(&self.ctx, consensus).await;
         Ok(())
     }
 
@@ -827,27 +625,6 @@ impl IbdFlow {
         }
     }
 
-    async fn validate_staging_timestamps(
-        &self,
-        consensus: &ConsensusProxy,
-        staging_consensus: &ConsensusProxy,
-    ) -> Result<(), ProtocolError> {
-        // The purpose of this check is to prevent the potential abuse explained here:
-        // https://github.com/kaspanet/research/issues/3#issuecomment-895243792
-        let staging_hst = staging_consensus.async_get_header(staging_consensus.async_get_headers_selected_tip().await).await.unwrap();
-        let current_hst = consensus.async_get_header(consensus.async_get_headers_selected_tip().await).await.unwrap();
-        // If staging is behind current or within 10 minutes ahead of it, then something is wrong and we reject the IBD
-        if staging_hst.timestamp < current_hst.timestamp || staging_hst.timestamp - current_hst.timestamp < 600_000 {
-            Err(ProtocolError::OtherOwned(format!(
-                "The difference between the timestamp of the current selected tip ({}) and the 
-staging selected tip ({}) is too small or negative. Aborting IBD...",
-                current_hst.timestamp, staging_hst.timestamp
-            )))
-        } else {
-            Ok(())
-        }
-    }
-
     async fn sync_pruning_point_utxoset(&mut self, consensus: &ConsensusProxy, pruning_point: Hash) -> Result<(), ProtocolError> {
         info!("downloading the pruning point utxoset, this can take a little while.");
         self.router
@@ -942,7 +719,7 @@ staging selected tip ({}) is too small or negative. Aborting IBD...",
                 let msg = dequeue_with_timeout!(self.incoming_route, Payload::IbdBlock)?;
                 let block: Block = Versioned(self.header_format, msg).try_into()?;
                 if block.hash() != hash {
-                    return Err(ProtocolError::OtherOwned(format!("expected block {} but got {}", hash, block.hash())));
+                    return Err(ProtocolError::UnrequestedData(format!("expected block {} but got {}", hash, block.hash())));
                 }
                 if block.is_header_only() {
                     return Err(ProtocolError::OtherOwned(format!("sent header of {} where expected block with body", block.hash())));
@@ -1041,7 +818,7 @@ staging selected tip ({}) is too small or negative. Aborting IBD...",
             let msg = dequeue_with_timeout!(self.incoming_route, Payload::IbdBlock)?;
             let block: Block = Versioned(self.header_format, msg).try_into()?;
             if block.hash() != expected_hash {
-                return Err(ProtocolError::OtherOwned(format!("expected block {} but got {}", expected_hash, block.hash())));
+                return Err(ProtocolError::UnrequestedData(format!("expected block {} but got {}", expected_hash, block.hash())));
             }
             if block.is_header_only() {
                 return Err(ProtocolError::OtherOwned(format!("sent header of {} where expected block with body", block.hash())));
@@ -1089,6 +866,308 @@ staging selected tip ({}) is too small or negative. Aborting IBD...",
     }
 }
 
+/// Validates a pruning proof in the context of current consensus, and verifies that its pruning point is
+/// an eligible sync target. Shared by IBD with headers proof and by snapshot import.
+pub(super) async fn validate_pruning_proof(
+    ctx: &FlowContext,
+    proof: PruningPointProof,
+    proof_metadata: PruningProofMetadata,
+) -> Result<PruningPointProof, ProtocolError> {
+    // Get a new session for current consensus (non staging)
+    let consensus = ctx.consensus().session().await;
+
+    // The proof is validated in the context of current consensus
+    let proof = consensus.clone().spawn_blocking(move |c| c.validate_pruning_proof(&proof, &proof_metadata).map(|()| proof)).await?;
+
+    let proof_pruning_point_header = proof[0].last().expect("was just ensured by validation");
+    let proof_pruning_point = proof_pruning_point_header.hash;
+
+    if proof_pruning_point == ctx.config.genesis.hash {
+        return Err(ProtocolError::Other("the proof pruning point is the genesis block"));
+    }
+
+    if proof_pruning_point == consensus.async_pruning_point().await {
+        return Err(ProtocolError::Other("the proof pruning point is the same as the current pruning point"));
+    }
+    drop(consensus);
+
+    // [Toccata] Reject IBD from outdated peers
+    validate_pruning_point_freshness_for_toccata(
+        ctx.config.as_ref(),
+        proof_pruning_point_header.hash,
+        proof_pruning_point_header.timestamp,
+        proof_pruning_point_header.daa_score,
+        unix_now(),
+    )?;
+
+    Ok(proof)
+}
+
+/// Validates the list of past pruning points accompanying an already validated pruning proof
+pub(super) async fn validate_past_pruning_points(
+    ctx: &FlowContext,
+    proof: &PruningPointProof,
+    pruning_points: &PruningPointsList,
+) -> Result<(), ProtocolError> {
+    let proof_pruning_point = proof[0].last().expect("was ensured by proof validation").hash;
+
+    if pruning_points.is_empty() || pruning_points.last().unwrap().hash != proof_pruning_point {
+        return Err(ProtocolError::Other("the proof pruning point is not equal to the last pruning point in the list"));
+    }
+
+    if pruning_points.first().unwrap().hash != ctx.config.genesis.hash {
+        return Err(ProtocolError::Other("the first pruning point in the list is expected to be genesis"));
+    }
+
+    // Check if past pruning points violate finality of current consensus
+    if ctx.consensus().session().await.async_are_pruning_points_violating_finality(pruning_points.clone()).await {
+        // TODO (relaxed): consider performing additional actions on finality conflicts in addition to disconnecting from the peer (e.g., banning, rpc notification)
+        return Err(ProtocolError::Other("pruning points are violating finality"));
+    }
+
+    // Sanity check for consistency between past pruning points and the headers proof
+    let pruning_points_set: BlockHashSet = pruning_points.iter().map(|h| h.hash).collect();
+    for level in proof.iter() {
+        if let Some(root) = level.first()
+            && root.hash != ctx.config.genesis.hash
+            && !pruning_points_set.contains(&root.pruning_point)
+        {
+            return Err(ProtocolError::Other("proof and past pruning points are inconsistent with each other"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the trusted entries following the pruning point entry: first the blocks of the pruning point
+/// anticone, followed by the header-only pruning point chain segment
+pub(super) struct TrustedEntriesCollector {
+    entries: Vec<TrustedDataEntry>,
+    header_only_chain_segment: Vec<Arc<Header>>,
+    max_header_only_chain_segment_len: u64,
+}
+
+impl TrustedEntriesCollector {
+    pub(super) fn new(
+        ctx: &FlowContext,
+        pruning_point_entry: TrustedDataEntry,
+        proof_pruning_point: Hash,
+    ) -> Result<Self, ProtocolError> {
+        if pruning_point_entry.block.is_header_only() {
+            return Err(ProtocolError::Other("pruning point entry is header-only"));
+        }
+
+        if pruning_point_entry.block.hash() != proof_pruning_point {
+            return Err(ProtocolError::Other("the proof pruning point is not equal to the expected trusted entry"));
+        }
+
+        // Each selected-chain block contributes at least one blue score, so F blue-depth back is bounded
+        // by F chain blocks (plus 2K for noise/robustness).
+        let max_header_only_chain_segment_len = ctx.config.finality_depth().saturating_add(2 * ctx.config.ghostdag_k() as u64 + 1);
+
+        // TODO(optimization): this buffering can be heavy on RAM for large chain segments, but is acceptable
+        // since syncee memory usage is still low at this phase.
+        Ok(Self { entries: vec![pruning_point_entry], header_only_chain_segment: Vec::new(), max_header_only_chain_segment_len })
+    }
+
+    pub(super) fn push(&mut self, entry: TrustedDataEntry) -> Result<(), ProtocolError> {
+        match entry.block.is_header_only() {
+            true => {
+                if self.header_only_chain_segment.is_empty() {
+                    info!("Finished downloading {} blocks from the pruning point anticone", self.entries.len() - 1);
+                    info!("Starting to download the pruning point chain segment");
+                }
+                self.header_only_chain_segment.push(entry.block.header.clone());
+                if self.header_only_chain_segment.len().is_multiple_of(1000) {
+                    info!("Downloaded {} headers from the pruning point chain segment", self.header_only_chain_segment.len());
+
+                    if self.header_only_chain_segment.len() as u64 > self.max_header_only_chain_segment_len {
+                        return Err(ProtocolError::OtherOwned(format!(
+                            "pruning point chain segment length {} exceeds maximum {}",
+                            self.header_only_chain_segment.len(),
+                            self.max_header_only_chain_segment_len
+                        )));
+                    }
+                }
+            }
+            // We expect all header-only entries to be sent after all non-header-only entries
+            false if self.header_only_chain_segment.is_empty() => {
+                self.entries.push(entry);
+                if (self.entries.len() - 1).is_multiple_of(1000) {
+                    info!("Downloaded {} blocks from the pruning point anticone", self.entries.len() - 1);
+                }
+            }
+            false => {
+                return Err(ProtocolError::Other("trusted body entries arrived after header-only trusted entries"));
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> (Vec<TrustedDataEntry>, Vec<Arc<Header>>) {
+        if self.header_only_chain_segment.is_empty() {
+            // No chain segment means the anticone was not logged yet.
+            info!("Finished downloading {} blocks from the pruning point anticone", self.entries.len() - 1);
+        } else {
+            info!("Finished downloading {} headers from the pruning point chain segment", self.header_only_chain_segment.len());
+        }
+        (self.entries, self.header_only_chain_segment)
+    }
+}
+
+/// Applies a validated pruning proof, the past pruning points and the trusted sub-DAG of the pruning point to the staging consensus
+pub(super) async fn apply_pruning_proof_and_trusted_data(
+    ctx: &FlowContext,
+    staging: &ConsensusProxy,
+    proof: PruningPointProof,
+    proof_metadata: PruningProofMetadata,
+    pruning_points: PruningPointsList,
+    pkg: TrustedDataPackage,
+    trusted_entries: TrustedEntriesCollector,
+) -> Result<(), ProtocolError> {
+    let (entries, header_only_chain_segment) = trusted_entries.finish();
+
+    // Create a topologically ordered vector of trusted blocks - the pruning point and its anticone,
+    // and their daa windows headers
+    let mut trusted_set = pkg.build_trusted_subdag(entries)?;
+
+    if ctx.config.enable_sanity_checks {
+        let con = ctx.consensus().unguarded_session_blocking();
+        trusted_set = staging
+            .clone()
+            .spawn_blocking(move |c| {
+                let ref_proof = proof.clone();
+                c.apply_pruning_proof(proof, &trusted_set, &header_only_chain_segment)?;
+                c.import_pruning_points(pruning_points)?;
+
+                info!("Building the proof which was just applied (sanity test)");
+                let built_proof = c.get_pruning_point_proof();
+                let mut mismatch_detected = false;
+                for (i, (ref_level, built_level)) in ref_proof.iter().zip(built_proof.iter()).enumerate() {
+                    if ref_level.iter().map(|h| h.hash).collect::<BlockHashSet>()
+                        != built_level.iter().map(|h| h.hash).collect::<BlockHashSet>()
+                    {
+                        mismatch_detected = true;
+                        warn!("Locally built proof for level {} does not match the applied one", i);
+                    }
+                }
+                if mismatch_detected {
+                    info!("Validating the locally built proof (sanity test fallback #2)");
+                    // Note: the proof is validated in the context of *current* consensus
+                    if let Err(err) = con.validate_pruning_proof(&built_proof, &proof_metadata) {
+                        panic!("Locally built proof failed validation: {}", err);
+                    }
+                    info!("Locally built proof was validated successfully");
+                } else {
+                    info!("Proof was locally built successfully");
+                }
+                Result::<_, ProtocolError>::Ok(trusted_set)
+            })
+            .await?;
+    } else {
+        trusted_set = staging
+            .clone()
+            .spawn_blocking(move |c| {
+                c.apply_pruning_proof(proof, &trusted_set, &header_only_chain_segment)?;
+                c.import_pruning_points(pruning_points)?;
+                Result::<_, ProtocolError>::Ok(trusted_set)
+            })
+            .await?;
+    }
+
+    // TODO (relaxed): add logs to staging commit process
+
+    info!("Starting to process {} trusted blocks", trusted_set.len());
+    let mut last_time = Instant::now();
+    let mut last_index: usize = 0;
+    for (i, tb) in trusted_set.into_iter().enumerate() {
+        let now = Instant::now();
+        let passed = now.duration_since(last_time);
+        if passed > Duration::from_secs(1) {
+            info!("Processed {} trusted blocks in the last {:.2}s (total {})", i - last_index, passed.as_secs_f64(), i);
+            last_time = now;
+            last_index = i;
+        }
+        // TODO (relaxed): queue and join in batches
+        staging.validate_and_insert_trusted_block(tb).virtual_state_task.await?;
+    }
+    staging.async_clear_body_missing_anticone_set().await;
+    info!("Done processing trusted blocks");
+    Ok(())
+}
+
+pub(super) async fn validate_staging_timestamps(
+    consensus: &ConsensusProxy,
+    staging_consensus: &ConsensusProxy,
+) -> Result<(), ProtocolError> {
+    // The purpose of this check is to prevent the potential abuse explained here:
+    // https://github.com/kaspanet/research/issues/3#issuecomment-895243792
+    let staging_hst = staging_consensus.async_get_header(staging_consensus.async_get_headers_selected_tip().await).await.unwrap();
+    let current_hst = consensus.async_get_header(consensus.async_get_headers_selected_tip().await).await.unwrap();
+    // If staging is behind current or within 10 minutes ahead of it, then something is wrong and we reject the IBD
+    if staging_hst.timestamp < current_hst.timestamp || staging_hst.timestamp - current_hst.timestamp < 600_000 {
+        Err(ProtocolError::OtherOwned(format!(
+            "The difference between the timestamp of the current selected tip ({}) and the 
+staging selected tip ({}) is too small or negative. Aborting IBD...",
+            current_hst.timestamp, staging_hst.timestamp
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Verifies the SMT metadata of `pruning_point` against its header commitments, and returns
+/// the resolved inactivity shortcut block required for importing the SMT lanes
+pub(super) async fn verify_pruning_point_smt_metadata(
+    ctx: &FlowContext,
+    consensus: &ConsensusProxy,
+    pruning_point: Hash,
+    md: &SmtExportMetadata,
+) -> Result<Hash, ProtocolError> {
+    let pp_header = consensus.async_get_header(pruning_point).await.unwrap();
+    let parent_header = consensus.async_get_header(pp_header.direct_parents()[0]).await.unwrap();
+
+    // Derive the shortcut block via consensus (uses reachability + headers only; safe at the PP
+    // boundary before the SMT is imported). Then resolve to the seqcommit hash with the same
+    // fold-to-zero rule used by `inactivity_shortcut(block)`.
+    let shortcut_block = consensus
+        .async_inactivity_shortcut_block_for_pov(pruning_point)
+        .await
+        .map_err(|e| ProtocolError::OtherOwned(format!("inactivity_shortcut_block resolution failed: {e}")))?;
+    let shortcut_header = consensus
+        .async_get_header(shortcut_block)
+        .await
+        .map_err(|_| ProtocolError::Other("inactivity_shortcut_block header not found"))?;
+    let inactivity_shortcut = if !ctx.config.toccata_activation.is_active(shortcut_header.daa_score) {
+        kaspa_hashes::ZERO_HASH
+    } else {
+        shortcut_header.accepted_id_merkle_root
+    };
+
+    verify_smt_metadata(
+        &SmtMetadata {
+            lanes_root: &md.lanes_root,
+            payload_and_ctx_digest: &md.payload_and_ctx_digest,
+            parent_seq_commit: &md.parent_seq_commit,
+        },
+        inactivity_shortcut,
+        pp_header.accepted_id_merkle_root,
+        parent_header.accepted_id_merkle_root,
+    )
+    .map_err(|e| ProtocolError::OtherOwned(format!("SMT metadata verification failed: {e}")))?;
+
+    Ok(shortcut_block)
+}
+
+/// Marks a newly imported pruning point UTXO set as stable and notifies the components which depend on it
+pub(super) async fn finalize_new_utxo_set(ctx: &FlowContext, consensus: &ConsensusProxy) {
+    consensus.async_set_pruning_utxoset_stable().await;
+    // Once a new utxoset is stored, the utxoindex needs to be resynced as well. This happens through the reset handler mechanism.
+    let consensus_manager = ctx.consensus_manager.clone();
+    spawn_blocking(move || consensus_manager.invoke_consensus_reset_handlers()).await.unwrap();
+    ctx.on_pruning_point_utxoset_override();
+}
+
 /// [Toccata] Fresh nodes cannot easily identify outdated peers after activation, so we guard
 /// against syncers advertising pruning points that are clearly stale.
 ///
@@ -1,7 +1,9 @@
 mod flow;
 mod negotiate;
 mod progress;
+mod snapshot;
 mod streams;
 
 pub use flow::*;
+pub use snapshot::{SNAPSHOT_VERSION, SnapshotError, SnapshotResult, export_snapshot, import_snapshot};
 pub use streams::*;
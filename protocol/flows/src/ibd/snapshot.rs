@@ -0,0 +1,691 @@
+//! Pruning point snapshots: a single checksummed file holding everything a syncee otherwise downloads
+//! from a syncer during IBD with headers proof, so that new nodes can be bootstrapped locally.
+//!
+//! The file consists of a fixed header followed by length-prefixed P2P messages (recorded in the exact
+//! wire format used by IBD), and is terminated by a checksum over all preceding bytes:
+//!
+//! | Field          | Size      | Description                                                  |
+//! |----------------|-----------|--------------------------------------------------------------|
+//! | magic          | 8         | `KASSNAP\0`                                                  |
+//! | version        | 4 (LE)    | [`SNAPSHOT_VERSION`]                                         |
+//! | genesis        | 32        | genesis hash of the exporting network                        |
+//! | pruning point  | 32        | the pruning point the snapshot was taken at                  |
+//! | records        | variable  | each a 4-byte (LE) length followed by an encoded message     |
+//! | checksum       | 32        | [`PruningPointSnapshotChecksum`] of all preceding bytes      |
+//!
+//! Records appear in the following order:
+//! 1. `BlockHeaders` with the single header of the exporter's sink (playing the role of the IBD relay block)
+//! 2. `PruningPointProof`
+//! 3. `PruningPoints`, `TrustedData`, `BlockWithTrustedDataV4`*, `DoneBlocksWithTrustedData`
+//! 4. `BlockHeaders`*, `DoneHeaders` -- the headers between the pruning point and the sink
+//! 5. `SmtMetadata`, `SmtLaneChunk`* -- only if Toccata is active at the pruning point
+//! 6. `PruningPointUtxoSetChunk`*, `DonePruningPointUtxoSetChunks`
+
+use super::{
+    HeadersChunk, SMT_CHUNK_SIZE, SmtLaneDecoder, TrustedEntriesCollector, UtxosetChunk, apply_pruning_proof_and_trusted_data,
+    finalize_new_utxo_set, smt_lane_to_entry, smt_metadata_from_message, smt_metadata_to_message, validate_past_pruning_points,
+    validate_pruning_proof, validate_staging_timestamps, verify_pruning_point_smt_metadata,
+};
+use crate::flow_context::FlowContext;
+use futures::future::try_join_all;
+use itertools::Itertools;
+use kaspa_consensus_core::{
+    api::{ConsensusApi, ImportLane},
+    block::Block,
+    config::Config,
+    errors::{block::RuleError, consensus::ConsensusError, pruning::PruningImportError},
+    pruning::{PruningPointProof, PruningPointsList, PruningProofMetadata},
+};
+use kaspa_consensusmanager::{ConsensusProxy, StagingConsensus, spawn_blocking};
+use kaspa_core::{info, warn};
+use kaspa_hashes::{Hash, Hasher, HasherBase, PruningPointSnapshotChecksum};
+use kaspa_muhash::MuHash;
+use kaspa_p2p_lib::{
+    common::ProtocolError,
+    convert::{
+        error::ConversionError,
+        header::{HeaderFormat, Versioned},
+        model::trusted::{TrustedDataEntry, TrustedDataPackage},
+    },
+    make_message,
+    pb::{
+        BlockHeadersMessage, BlockWithTrustedDataV4Message, DoneBlocksWithTrustedDataMessage, DoneHeadersMessage,
+        DonePruningPointUtxoSetChunksMessage, KaspadMessage, PruningPointProofMessage, PruningPointUtxoSetChunkMessage,
+        PruningPointsMessage, SmtLaneChunkMessage, TrustedDataMessage, kaspad_message::Payload,
+    },
+};
+use prost::Message;
+use std::{
+    cmp::max,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, BufReader};
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"KASSNAP\0";
+pub const SNAPSHOT_VERSION: u32 = 1;
+const SNAPSHOT_HEADER_LEN: u64 = 8 + 4 + 32 + 32;
+const SNAPSHOT_CHECKSUM_LEN: u64 = 32;
+
+/// Records are bounded in order to fail fast on malformed files rather than attempting huge allocations
+const MAX_RECORD_LEN: usize = 1 << 30;
+
+/// Snapshots are always recorded in the current header wire format
+const SNAPSHOT_HEADER_FORMAT: HeaderFormat = HeaderFormat::Compressed;
+
+const UTXO_CHUNK_SIZE: usize = 1000;
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("not a pruning point snapshot file")]
+    InvalidMagic,
+
+    #[error("unsupported snapshot version {0} (expected {SNAPSHOT_VERSION})")]
+    UnsupportedVersion(u32),
+
+    #[error("the snapshot was exported on a different network (genesis {0})")]
+    WrongNetwork(Hash),
+
+    #[error("snapshot checksum mismatch, the file is corrupted")]
+    ChecksumMismatch,
+
+    #[error("snapshot is truncated")]
+    Truncated,
+
+    #[error("snapshot record of length {0} exceeds the maximum allowed length")]
+    RecordTooLong(usize),
+
+    #[error("snapshot contains unexpected records after the UTXO set")]
+    TrailingRecords,
+
+    #[error("a snapshot cannot be exported: {0}")]
+    NotReady(&'static str),
+
+    #[error("{0}")]
+    DecodeError(#[from] prost::DecodeError),
+
+    #[error("{0}")]
+    ConversionError(#[from] ConversionError),
+
+    #[error("{0}")]
+    ConsensusError(#[from] ConsensusError),
+
+    #[error("{0}")]
+    RuleError(#[from] RuleError),
+
+    #[error("{0}")]
+    PruningImportError(#[from] PruningImportError),
+
+    #[error("{0}")]
+    ProtocolError(#[from] ProtocolError),
+}
+
+pub type SnapshotResult<T> = std::result::Result<T, SnapshotError>;
+
+/// Reads the next record and extracts the expected payload out of it
+macro_rules! read_record {
+    ($reader:expr, $pattern:path) => {{
+        match $reader.next().await? {
+            Some($pattern(msg)) => Ok::<_, SnapshotError>(msg),
+            Some(payload) => Err(ProtocolError::UnexpectedMessage(stringify!($pattern), Some((&payload).into())).into()),
+            None => Err(SnapshotError::Truncated),
+        }
+    }};
+}
+
+struct SnapshotWriter<W: Write> {
+    writer: W,
+    hasher: PruningPointSnapshotChecksum,
+    records: u64,
+}
+
+impl<W: Write> SnapshotWriter<W> {
+    fn new(writer: W, genesis: Hash, pruning_point: Hash) -> std::io::Result<Self> {
+        let mut snapshot_writer = Self { writer, hasher: PruningPointSnapshotChecksum::new(), records: 0 };
+        snapshot_writer.write_bytes(SNAPSHOT_MAGIC)?;
+        snapshot_writer.write_bytes(&SNAPSHOT_VERSION.to_le_bytes())?;
+        snapshot_writer.write_bytes(&genesis.as_bytes())?;
+        snapshot_writer.write_bytes(&pruning_point.as_bytes())?;
+        Ok(snapshot_writer)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.hasher.update(bytes);
+        self.writer.write_all(bytes)
+    }
+
+    fn write(&mut self, msg: KaspadMessage) -> std::io::Result<()> {
+        let bytes = msg.encode_to_vec();
+        self.write_bytes(&(bytes.len() as u32).to_le_bytes())?;
+        self.write_bytes(&bytes)?;
+        self.records += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> std::io::Result<W> {
+        let checksum = self.hasher.finalize();
+        self.writer.write_all(&checksum.as_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+struct SnapshotReader<R> {
+    reader: R,
+    /// The number of record bytes not read yet
+    remaining: u64,
+    pruning_point: Hash,
+}
+
+impl SnapshotReader<BufReader<tokio::fs::File>> {
+    /// Opens a snapshot file, verifying its checksum and that it belongs to the network of `genesis`
+    async fn open(path: &Path, genesis: Hash) -> SnapshotResult<Self> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let len = file.metadata().await?.len();
+        if len < SNAPSHOT_HEADER_LEN + SNAPSHOT_CHECKSUM_LEN {
+            return Err(SnapshotError::Truncated);
+        }
+
+        // The whole file is verified before any of it is processed
+        let content_len = len - SNAPSHOT_CHECKSUM_LEN;
+        let mut reader = BufReader::new(file);
+        let mut hasher = PruningPointSnapshotChecksum::new();
+        let mut buf = vec![0u8; 1 << 20];
+        let mut left = content_len;
+        while left > 0 {
+            let n = reader.read(&mut buf[..left.min(buf.len() as u64) as usize]).await?;
+            if n == 0 {
+                return Err(SnapshotError::Truncated);
+            }
+            hasher.update(&buf[..n]);
+            left -= n as u64;
+        }
+        let mut checksum = [0u8; 32];
+        reader.read_exact(&mut checksum).await?;
+        if hasher.finalize() != Hash::from_bytes(checksum) {
+            return Err(SnapshotError::ChecksumMismatch);
+        }
+
+        file = reader.into_inner();
+        file.rewind().await?;
+        let mut reader = BufReader::new(file);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).await?;
+        if &magic != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidMagic);
+        }
+        let version = reader.read_u32_le().await?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        let snapshot_genesis = read_hash(&mut reader).await?;
+        if snapshot_genesis != genesis {
+            return Err(SnapshotError::WrongNetwork(snapshot_genesis));
+        }
+        let pruning_point = read_hash(&mut reader).await?;
+
+        Ok(Self { reader, remaining: content_len - SNAPSHOT_HEADER_LEN, pruning_point })
+    }
+}
+
+impl<R: AsyncRead + Unpin> SnapshotReader<R> {
+    /// Returns the payload of the next record, or `None` once all records were read
+    async fn next(&mut self) -> SnapshotResult<Option<Payload>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        if self.remaining < 4 {
+            return Err(SnapshotError::Truncated);
+        }
+        let len = self.reader.read_u32_le().await? as usize;
+        if len > MAX_RECORD_LEN {
+            return Err(SnapshotError::RecordTooLong(len));
+        }
+        if self.remaining - 4 < len as u64 {
+            return Err(SnapshotError::Truncated);
+        }
+        let mut bytes = vec![0u8; len];
+        self.reader.read_exact(&mut bytes).await?;
+        self.remaining -= 4 + len as u64;
+        match KaspadMessage::decode(bytes.as_slice())?.payload {
+            Some(payload) => Ok(Some(payload)),
+            None => Err(ProtocolError::Other("snapshot record has an empty payload").into()),
+        }
+    }
+}
+
+async fn read_hash<R: AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<Hash> {
+    let mut bytes = [0u8; 32];
+    reader.read_exact(&mut bytes).await?;
+    Ok(Hash::from_bytes(bytes))
+}
+
+/// Exports a snapshot of the current pruning point state to `path`, returning the snapshot pruning point.
+///
+/// The file is first written to a temporary sibling path and renamed into place once complete.
+pub fn export_snapshot(consensus: &dyn ConsensusApi, config: &Config, path: &Path) -> SnapshotResult<Hash> {
+    let pruning_point = consensus.pruning_point();
+    if pruning_point == config.genesis.hash {
+        return Err(SnapshotError::NotReady("the pruning point is still genesis"));
+    }
+    if !consensus.is_pruning_utxoset_stable() {
+        return Err(SnapshotError::NotReady("the pruning point UTXO set is incomplete"));
+    }
+    if !consensus.is_pruning_point_anticone_fully_synced() {
+        return Err(SnapshotError::NotReady("the pruning point anticone is missing block data"));
+    }
+    let pp_header = consensus.get_header(pruning_point)?;
+    let export_smt = config.toccata_activation.is_active(pp_header.daa_score);
+    if export_smt && !consensus.is_pruning_smt_stable() {
+        return Err(SnapshotError::NotReady("the pruning point SMT state is incomplete"));
+    }
+
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    let mut writer = SnapshotWriter::new(BufWriter::new(File::create(&tmp_path)?), config.genesis.hash, pruning_point)?;
+    let result = write_snapshot_records(consensus, config, &mut writer, pruning_point, export_smt);
+    let records = writer.records;
+    let result = result.and_then(|()| {
+        let file = writer.finish()?.into_inner().map_err(|err| err.into_error())?;
+        file.sync_all()?;
+        Ok(())
+    });
+    if let Err(err) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+    std::fs::rename(&tmp_path, path)?;
+
+    info!("Exported a snapshot of pruning point {} with {} records to {}", pruning_point, records, path.display());
+    Ok(pruning_point)
+}
+
+fn write_snapshot_records<W: Write>(
+    consensus: &dyn ConsensusApi,
+    config: &Config,
+    writer: &mut SnapshotWriter<W>,
+    pruning_point: Hash,
+    export_smt: bool,
+) -> SnapshotResult<()> {
+    let format = SNAPSHOT_HEADER_FORMAT;
+
+    let sink = consensus.get_sink();
+    let sink_header = consensus.get_header(sink)?;
+    writer.write(make_message!(Payload::BlockHeaders, BlockHeadersMessage { block_headers: vec![(format, &*sink_header).into()] }))?;
+
+    let proof = consensus.get_pruning_point_proof();
+    writer.write(make_message!(
+        Payload::PruningPointProof,
+        PruningPointProofMessage { headers: proof.iter().map(|headers| (format, headers).into()).collect() }
+    ))?;
+
+    // Pruning points and the trusted data of the pruning point anticone
+    let pp_headers = consensus.pruning_point_headers();
+    writer.write(make_message!(
+        Payload::PruningPoints,
+        PruningPointsMessage { headers: pp_headers.into_iter().map(|header| (format, &*header).into()).collect() }
+    ))?;
+    let trusted_data = consensus.get_pruning_point_anticone_and_trusted_data()?;
+    writer.write(make_message!(
+        Payload::TrustedData,
+        TrustedDataMessage {
+            daa_window: trusted_data.daa_window_blocks.iter().map(|daa_block| (format, daa_block).into()).collect_vec(),
+            ghostdag_data: trusted_data.ghostdag_blocks.iter().map(|gd| gd.into()).collect_vec()
+        }
+    ))?;
+    let blocks_iter = trusted_data
+        .anticone
+        .iter()
+        .copied()
+        .map(|hash| (hash, false))
+        .chain(trusted_data.header_only_chain_segment.iter().copied().map(|hash| (hash, true)));
+    for (hash, header_only) in blocks_iter {
+        let block = if header_only { Block::from_header_arc(consensus.get_header(hash)?) } else { consensus.get_block(hash)? };
+        writer.write(make_message!(
+            Payload::BlockWithTrustedDataV4,
+            BlockWithTrustedDataV4Message { block: Some((format, &block).into()), ..Default::default() }
+        ))?;
+    }
+    writer.write(make_message!(Payload::DoneBlocksWithTrustedData, DoneBlocksWithTrustedDataMessage {}))?;
+
+    // Headers in the future of the pruning point, up to the sink
+    // Internal consensus logic requires that `max_blocks > mergeset_size_limit`
+    let max_blocks = max(1 << 10, config.mergeset_size_limit() as usize + 1);
+    let mut low = pruning_point;
+    let mut header_count = 0;
+    while low != sink {
+        let hashes = consensus.get_hashes_between(low, sink, max_blocks)?.0;
+        low = *hashes.last().expect("low and sink are different");
+        header_count += hashes.len();
+        let block_headers = hashes
+            .into_iter()
+            .map(|hash| Ok((format, &*consensus.get_header(hash)?).into()))
+            .collect::<Result<Vec<_>, ConsensusError>>()?;
+        writer.write(make_message!(Payload::BlockHeaders, BlockHeadersMessage { block_headers }))?;
+    }
+    writer.write(make_message!(Payload::DoneHeaders, DoneHeadersMessage {}))?;
+
+    // SMT state
+    let mut lane_count = 0;
+    if export_smt {
+        let metadata = consensus.get_pruning_point_smt_metadata(pruning_point)?;
+        writer.write(make_message!(Payload::SmtMetadata, smt_metadata_to_message(&metadata)))?;
+        for chunk in &consensus.open_pruning_point_smt_lane_stream(pruning_point)?.chunks(SMT_CHUNK_SIZE) {
+            let entries = chunk.map_ok(smt_lane_to_entry).collect::<Result<Vec<_>, _>>()?;
+            lane_count += entries.len();
+            writer.write(make_message!(Payload::SmtLaneChunk, SmtLaneChunkMessage { entries }))?;
+        }
+        if lane_count as u64 != metadata.active_lanes_count {
+            return Err(ConsensusError::General("SMT lane stream does not match the SMT metadata").into());
+        }
+    }
+
+    // UTXO set
+    let mut from_outpoint = None;
+    let mut utxo_count = 0;
+    loop {
+        let utxos = consensus.get_pruning_point_utxos(pruning_point, from_outpoint, UTXO_CHUNK_SIZE, from_outpoint.is_some())?;
+        utxo_count += utxos.len();
+        writer.write(make_message!(
+            Payload::PruningPointUtxoSetChunk,
+            PruningPointUtxoSetChunkMessage {
+                outpoint_and_utxo_entry_pairs: utxos.iter().map(|(outpoint, entry)| (outpoint, entry).into()).collect_vec()
+            }
+        ))?;
+        // This indicates that there are no more entries to query
+        if utxos.len() < UTXO_CHUNK_SIZE {
+            break;
+        }
+        from_outpoint = Some(utxos.last().expect("not empty by prev condition").0);
+    }
+    writer.write(make_message!(Payload::DonePruningPointUtxoSetChunks, DonePruningPointUtxoSetChunksMessage {}))?;
+
+    info!("Snapshot contents: {} headers above the pruning point, {} SMT lanes and {} UTXOs", header_count, lane_count, utxo_count);
+    Ok(())
+}
+
+/// Bootstraps the node from the snapshot at `path`, returning the imported pruning point.
+///
+/// The import is skipped, returning `None`, if the node already synced past genesis or the snapshot holds no
+/// pruning point beyond it, so that restarting with the same arguments does not fail.
+///
+/// The snapshot goes through the same validation as data received from a syncer during IBD with headers proof:
+/// the pruning proof, past pruning points and trusted data are applied to a staging consensus which is committed
+/// only once the headers up to the snapshot sink are validated. The SMT metadata is then verified against the
+/// pruning point header commitments, and the UTXO set MuHash against the pruning point UTXO commitment.
+pub async fn import_snapshot(ctx: &FlowContext, path: &Path) -> SnapshotResult<Option<Hash>> {
+    let mut reader = SnapshotReader::open(path, ctx.config.genesis.hash).await?;
+    let local_pruning_point = ctx.consensus().session().await.async_pruning_point().await;
+    if local_pruning_point != ctx.config.genesis.hash {
+        info!(
+            "Skipping the snapshot import from {}: the node is already synced up to pruning point {}",
+            path.display(),
+            local_pruning_point
+        );
+        return Ok(None);
+    }
+    if reader.pruning_point == ctx.config.genesis.hash {
+        info!("Skipping the snapshot import from {}: the snapshot pruning point is genesis", path.display());
+        return Ok(None);
+    }
+    info!("Importing a snapshot of pruning point {} from {}", reader.pruning_point, path.display());
+
+    let sink_header =
+        match HeadersChunk::try_from(Versioned(SNAPSHOT_HEADER_FORMAT, read_record!(reader, Payload::BlockHeaders)?))?.as_slice() {
+            [sink_header] => sink_header.clone(),
+            _ => return Err(ProtocolError::Other("expected the snapshot sink header").into()),
+        };
+
+    let proof: PruningPointProof = Versioned(SNAPSHOT_HEADER_FORMAT, read_record!(reader, Payload::PruningPointProof)?).try_into()?;
+    let proof_metadata = PruningProofMetadata::new(sink_header.blue_work);
+    let proof = validate_pruning_proof(ctx, proof, proof_metadata).await?;
+    let pruning_point = proof[0].last().expect("was just ensured by validation").hash;
+    if pruning_point != reader.pruning_point {
+        return Err(ProtocolError::Other("the proof pruning point is not the snapshot pruning point").into());
+    }
+
+    let pruning_points: PruningPointsList =
+        Versioned(SNAPSHOT_HEADER_FORMAT, read_record!(reader, Payload::PruningPoints)?).try_into()?;
+    validate_past_pruning_points(ctx, &proof, &pruning_points).await?;
+
+    let pkg: TrustedDataPackage = Versioned(SNAPSHOT_HEADER_FORMAT, read_record!(reader, Payload::TrustedData)?).try_into()?;
+    let pruning_point_entry: TrustedDataEntry =
+        Versioned(SNAPSHOT_HEADER_FORMAT, read_record!(reader, Payload::BlockWithTrustedDataV4)?).try_into()?;
+    let mut trusted_entries = TrustedEntriesCollector::new(ctx, pruning_point_entry, pruning_point)?;
+    loop {
+        match reader.next().await? {
+            Some(Payload::BlockWithTrustedDataV4(msg)) => trusted_entries.push(Versioned(SNAPSHOT_HEADER_FORMAT, msg).try_into()?)?,
+            Some(Payload::DoneBlocksWithTrustedData(_)) => break,
+            Some(payload) => {
+                return Err(ProtocolError::UnexpectedMessage(
+                    stringify!(Payload::BlockWithTrustedDataV4 | Payload::DoneBlocksWithTrustedData),
+                    Some((&payload).into()),
+                )
+                .into());
+            }
+            None => return Err(SnapshotError::Truncated),
+        }
+    }
+
+    let staging = ctx.consensus_manager.new_staging_consensus();
+    match import_staging_data(
+        ctx,
+        &staging,
+        &mut reader,
+        proof,
+        proof_metadata,
+        pruning_points,
+        pkg,
+        trusted_entries,
+        sink_header.hash,
+    )
+    .await
+    {
+        Ok(()) => {
+            spawn_blocking(|| staging.commit()).await.unwrap();
+            info!("Header stage of the snapshot import completed successfully. Committed staging consensus.");
+        }
+        Err(err) => {
+            warn!("Snapshot import was unsuccessful ({})", err);
+            staging.cancel();
+            return Err(err);
+        }
+    }
+
+    // This obtains the freshly committed staging consensus
+    let session = ctx.consensus().session().await;
+    import_smt_state(ctx, &session, &mut reader, pruning_point).await?;
+    import_utxo_set(ctx, &session, &mut reader, pruning_point).await?;
+
+    if reader.next().await?.is_some() {
+        return Err(SnapshotError::TrailingRecords);
+    }
+    info!("Snapshot of pruning point {} was imported successfully", pruning_point);
+    Ok(Some(pruning_point))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn import_staging_data<R: AsyncRead + Unpin>(
+    ctx: &FlowContext,
+    staging: &StagingConsensus,
+    reader: &mut SnapshotReader<R>,
+    proof: PruningPointProof,
+    proof_metadata: PruningProofMetadata,
+    pruning_points: PruningPointsList,
+    pkg: TrustedDataPackage,
+    trusted_entries: TrustedEntriesCollector,
+    sink: Hash,
+) -> SnapshotResult<()> {
+    let staging_session = staging.session().await;
+    apply_pruning_proof_and_trusted_data(ctx, &staging_session, proof, proof_metadata, pruning_points, pkg, trusted_entries).await?;
+
+    info!("Processing the snapshot headers above the pruning point");
+    let mut header_count = 0;
+    loop {
+        match reader.next().await? {
+            Some(Payload::BlockHeaders(msg)) => {
+                let chunk: HeadersChunk = Versioned(SNAPSHOT_HEADER_FORMAT, msg).try_into()?;
+                header_count += chunk.len();
+                try_join_all(
+                    chunk.into_iter().map(|h| staging_session.validate_and_insert_block(Block::from_header_arc(h)).virtual_state_task),
+                )
+                .await?;
+            }
+            Some(Payload::DoneHeaders(_)) => break,
+            Some(payload) => {
+                return Err(ProtocolError::UnexpectedMessage(
+                    stringify!(Payload::BlockHeaders | Payload::DoneHeaders),
+                    Some((&payload).into()),
+                )
+                .into());
+            }
+            None => return Err(SnapshotError::Truncated),
+        }
+    }
+    info!("Processed {} snapshot headers", header_count);
+
+    if staging_session.async_get_block_status(sink).await.is_none() {
+        return Err(ProtocolError::Other("the snapshot headers do not include its sink").into());
+    }
+    staging_session.async_validate_pruning_points(sink).await?;
+    validate_staging_timestamps(&ctx.consensus().session().await, &staging_session).await?;
+    Ok(())
+}
+
+async fn import_smt_state<R: AsyncRead + Unpin>(
+    ctx: &FlowContext,
+    consensus: &ConsensusProxy,
+    reader: &mut SnapshotReader<R>,
+    pruning_point: Hash,
+) -> SnapshotResult<()> {
+    let pp_header = consensus.async_get_header(pruning_point).await?;
+    if !ctx.config.toccata_activation.is_active(pp_header.daa_score) {
+        consensus.async_set_pruning_smt_stable().await;
+        return Ok(());
+    }
+
+    consensus.async_clear_pruning_smt_stores().await;
+
+    let md = smt_metadata_from_message(read_record!(reader, Payload::SmtMetadata)?)?;
+    let shortcut_block = verify_pruning_point_smt_metadata(ctx, consensus, pruning_point, &md).await?;
+    let mut decoder = SmtLaneDecoder::new(md.active_lanes_count);
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<Vec<ImportLane>>(2);
+    let consensus_for_import = consensus.clone();
+    let builder_handle =
+        tokio::task::spawn_blocking(move || consensus_for_import.import_pruning_point_smt(pruning_point, md, shortcut_block, rx));
+
+    while !decoder.is_complete() {
+        let chunk = decoder.decode(read_record!(reader, Payload::SmtLaneChunk)?)?;
+        tx.send(chunk).await.map_err(|_| ProtocolError::Other("streaming SMT builder stopped unexpectedly"))?;
+    }
+    drop(tx);
+
+    builder_handle.await.map_err(|e| ProtocolError::OtherOwned(format!("SMT builder task panicked: {e}")))??;
+    consensus.async_set_pruning_smt_stable().await;
+
+    info!("Imported the SMT state: {} lanes", decoder.lane_count());
+    Ok(())
+}
+
+async fn import_utxo_set<R: AsyncRead + Unpin>(
+    ctx: &FlowContext,
+    consensus: &ConsensusProxy,
+    reader: &mut SnapshotReader<R>,
+    pruning_point: Hash,
+) -> SnapshotResult<()> {
+    consensus.async_clear_pruning_utxo_set().await;
+
+    let mut multiset = MuHash::new();
+    let mut utxo_count = 0;
+    loop {
+        let chunk: UtxosetChunk = match reader.next().await? {
+            Some(Payload::PruningPointUtxoSetChunk(msg)) => msg.try_into()?,
+            Some(Payload::DonePruningPointUtxoSetChunks(_)) => break,
+            Some(payload) => {
+                return Err(ProtocolError::UnexpectedMessage(
+                    stringify!(Payload::PruningPointUtxoSetChunk | Payload::DonePruningPointUtxoSetChunks),
+                    Some((&payload).into()),
+                )
+                .into());
+            }
+            None => return Err(SnapshotError::Truncated),
+        };
+        utxo_count += chunk.len();
+        multiset = consensus
+            .clone()
+            .spawn_blocking(move |c| {
+                c.append_imported_pruning_point_utxos(&chunk, &mut multiset);
+                multiset
+            })
+            .await;
+    }
+
+    // Verifies the MuHash of the imported set against the UTXO commitment of the pruning point
+    consensus.clone().spawn_blocking(move |c| c.import_pruning_point_utxo_set(pruning_point, multiset)).await?;
+    finalize_new_utxo_set(ctx, consensus).await;
+
+    info!("Imported the pruning point UTXO set: {} UTXOs", utxo_count);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_p2p_lib::pb::RequestNextHeadersMessage;
+
+    fn write_test_snapshot(path: &Path, genesis: Hash, records: Vec<KaspadMessage>) {
+        let mut writer = SnapshotWriter::new(BufWriter::new(File::create(path).unwrap()), genesis, 7.into()).unwrap();
+        for record in records {
+            writer.write(record).unwrap();
+        }
+        writer.finish().unwrap().flush().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_file_format() {
+        let dir = std::env::temp_dir().join(format!("kaspa-snapshot-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("snapshot.bin");
+        let genesis = Hash::from_u64_word(1);
+
+        write_test_snapshot(
+            &path,
+            genesis,
+            vec![
+                make_message!(Payload::DoneHeaders, DoneHeadersMessage {}),
+                make_message!(Payload::RequestNextHeaders, RequestNextHeadersMessage {}),
+            ],
+        );
+
+        // Records are read back in order
+        let mut reader = SnapshotReader::open(&path, genesis).await.unwrap();
+        assert_eq!(reader.pruning_point, 7.into());
+        assert!(matches!(reader.next().await.unwrap(), Some(Payload::DoneHeaders(_))));
+        assert!(matches!(reader.next().await.unwrap(), Some(Payload::RequestNextHeaders(_))));
+        assert!(reader.next().await.unwrap().is_none());
+
+        // Snapshots of other networks are rejected
+        assert!(
+            matches!(SnapshotReader::open(&path, Hash::from_u64_word(2)).await, Err(SnapshotError::WrongNetwork(g)) if g == genesis)
+        );
+
+        // Any corruption is detected by the checksum
+        let mut bytes = std::fs::read(&path).unwrap();
+        let last_record_byte = bytes.len() - SNAPSHOT_CHECKSUM_LEN as usize - 1;
+        bytes[last_record_byte] ^= 1;
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(matches!(SnapshotReader::open(&path, genesis).await, Err(SnapshotError::ChecksumMismatch)));
+
+        std::fs::write(&path, &bytes[..40]).unwrap();
+        assert!(matches!(SnapshotReader::open(&path, genesis).await, Err(SnapshotError::Truncated)));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
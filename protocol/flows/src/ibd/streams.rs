@@ -3,6 +3,7 @@
 //!
 
 use kaspa_consensus_core::{
+    api::{ImportLane, SmtExportMetadata},
     errors::consensus::ConsensusError,
     header::Header,
     tx::{TransactionOutpoint, UtxoEntry},
@@ -16,7 +17,7 @@ use kaspa_p2p_lib::{
     make_message,
     pb::{
         RequestNextHeadersMessage, RequestNextPruningPointAndItsAnticoneBlocksMessage, RequestNextPruningPointSmtChunkMessage,
-        RequestNextPruningPointUtxoSetChunkMessage, kaspad_message::Payload,
+        RequestNextPruningPointUtxoSetChunkMessage, SmtLaneChunkMessage, SmtLaneEntry, SmtMetadataMessage, kaspad_message::Payload,
     },
 };
 use std::sync::Arc;
@@ -215,50 +216,138 @@ impl<'a, 'b> PruningPointUtxosetChunkStream<'a, 'b> {
 
 const SMT_PROOF_INTERVAL: usize = kaspa_consensus_core::api::SMT_PROOF_INTERVAL;
 
+/// Encodes SMT metadata as an [`SmtMetadataMessage`], see [`smt_metadata_from_message`]
+pub fn smt_metadata_to_message(metadata: &SmtExportMetadata) -> SmtMetadataMessage {
+    let mut data = Vec::with_capacity(96);
+    data.extend_from_slice(&metadata.lanes_root.as_bytes());
+    data.extend_from_slice(&metadata.payload_and_ctx_digest.as_bytes());
+    data.extend_from_slice(&metadata.parent_seq_commit.as_bytes());
+    SmtMetadataMessage { data, active_lanes_count: metadata.active_lanes_count }
+}
+
+/// Encodes an SMT lane as an [`SmtLaneEntry`], whose data is `lane_key || lane_tip`
+pub fn smt_lane_to_entry(lane: ImportLane) -> SmtLaneEntry {
+    let mut data = Vec::with_capacity(64);
+    data.extend_from_slice(&lane.lane_key.as_bytes());
+    data.extend_from_slice(&lane.lane_tip.as_bytes());
+    let proof = lane.proof.as_ref().map(|p| p.to_bytes()).unwrap_or_default();
+    SmtLaneEntry { data, blue_score: lane.blue_score, proof }
+}
+
+/// Parses an [`SmtMetadataMessage`], whose data is the 96-byte wire `lanes_root || payload_and_ctx_digest || parent_seq_commit`
+pub fn smt_metadata_from_message(payload: SmtMetadataMessage) -> Result<SmtExportMetadata, ProtocolError> {
+    let (chunks, rem) = payload.data.as_chunks::<32>();
+    if !rem.is_empty() {
+        return Err(ProtocolError::Other("SmtMetadata data length not a multiple of 32 bytes"));
+    }
+    match *chunks {
+        [lanes_root, payload_and_ctx_digest, parent_seq_commit] => {
+            let [lanes_root, payload_and_ctx_digest, parent_seq_commit] =
+                [lanes_root, payload_and_ctx_digest, parent_seq_commit].map(Hash::from_bytes);
+            Ok(SmtExportMetadata {
+                lanes_root,
+                payload_and_ctx_digest,
+                parent_seq_commit,
+                active_lanes_count: payload.active_lanes_count,
+            })
+        }
+        _ => Err(ProtocolError::Other("SmtMetadata data must be 96 bytes")),
+    }
+}
+
+/// Decodes consecutive [`SmtLaneChunkMessage`]s of a single SMT state transfer.
+///
+/// Enforces the chunk size limit, the `active_lanes_count` bound announced by the metadata,
+/// and that the first and every [`SMT_PROOF_INTERVAL`]-th entry carries proof bytes.
+pub struct SmtLaneDecoder {
+    expected_count: u64,
+    lane_count: u64,
+}
+
+impl SmtLaneDecoder {
+    pub fn new(expected_count: u64) -> Self {
+        Self { expected_count, lane_count: 0 }
+    }
+
+    /// Whether all `active_lanes_count` lanes were decoded
+    pub fn is_complete(&self) -> bool {
+        self.lane_count >= self.expected_count
+    }
+
+    pub fn lane_count(&self) -> u64 {
+        self.lane_count
+    }
+
+    pub fn decode(&mut self, payload: SmtLaneChunkMessage) -> Result<Vec<ImportLane>, ProtocolError> {
+        if payload.entries.is_empty() {
+            return Err(ProtocolError::Other("received an empty SmtLaneChunk"));
+        }
+
+        if payload.entries.len() > SMT_CHUNK_SIZE {
+            return Err(ProtocolError::Other("SmtLaneChunk exceeds SMT_CHUNK_SIZE"));
+        }
+
+        let remaining = self.expected_count - self.lane_count;
+        if payload.entries.len() as u64 > remaining {
+            return Err(ProtocolError::Other("received more SMT lane entries than active_lanes_count"));
+        }
+
+        let mut lanes = Vec::with_capacity(payload.entries.len());
+        for entry in payload.entries {
+            let Some((&key_bytes, rem)) = entry.data.split_first_chunk::<32>() else {
+                return Err(ProtocolError::Other("SmtLaneEntry data too short for lane_key"));
+            };
+            let Some(&tip_bytes) = rem.first_chunk::<32>() else {
+                return Err(ProtocolError::Other("SmtLaneEntry data too short for lane_tip"));
+            };
+            if rem.len() != 32 {
+                return Err(ProtocolError::Other("SmtLaneEntry data must be exactly 64 bytes"));
+            }
+            let lane_key = Hash::from_bytes(key_bytes);
+            let lane_tip = Hash::from_bytes(tip_bytes);
+
+            let proof = if (self.lane_count as usize).is_multiple_of(SMT_PROOF_INTERVAL) {
+                Some(
+                    kaspa_smt::proof::OwnedSmtProof::from_bytes(&entry.proof)
+                        .map_err(|e| ProtocolError::OtherOwned(format!("invalid SMT proof: {e}")))?,
+                )
+            } else {
+                None
+            };
+
+            lanes.push(ImportLane { lane_key, lane_tip, blue_score: entry.blue_score, proof });
+            self.lane_count += 1;
+        }
+
+        Ok(lanes)
+    }
+}
+
 /// Stream of SMT lane chunks. Flow-controlled: after every [`SMT_FLOW_CONTROL_WINDOW`]
 /// chunks received the stream enqueues a [`RequestNextPruningPointSmtChunkMessage`]
 /// back to the peer. The total number of lanes is conveyed via the metadata header
 /// (`active_lanes_count`), so no explicit `Done` sentinel is required — both sides
 /// terminate naturally once that many lanes have been transferred.
 ///
-/// Enforces that the first and every [`SMT_PROOF_INTERVAL`]-th entry carries proof bytes.
+/// Chunks are validated by an [`SmtLaneDecoder`].
 pub struct SmtStream<'a, 'b> {
     router: &'a Router,
     incoming_route: &'b mut IncomingRoute,
-    expected_count: u64,
-    lane_count: u64,
+    decoder: SmtLaneDecoder,
     chunks_received: usize,
 }
 
 impl<'a, 'b> SmtStream<'a, 'b> {
     pub fn new(router: &'a Router, incoming_route: &'b mut IncomingRoute) -> Self {
-        Self { router, incoming_route, expected_count: 0, lane_count: 0, chunks_received: 0 }
+        Self { router, incoming_route, decoder: SmtLaneDecoder::new(0), chunks_received: 0 }
     }
 
-    pub async fn recv_metadata(&mut self) -> Result<kaspa_consensus_core::api::SmtExportMetadata, ProtocolError> {
+    pub async fn recv_metadata(&mut self) -> Result<SmtExportMetadata, ProtocolError> {
         match timeout(DEFAULT_TIMEOUT, self.incoming_route.recv()).await {
             Ok(Some(msg)) => match msg.payload {
                 Some(Payload::SmtMetadata(payload)) => {
-                    use kaspa_consensus_core::api::SmtExportMetadata;
-                    // Wire: 96 bytes = lanes_root || payload_and_ctx_digest || parent_seq_commit.
-                    let (chunks, rem) = payload.data.as_chunks::<32>();
-                    if !rem.is_empty() {
-                        return Err(ProtocolError::Other("SmtMetadata data length not a multiple of 32 bytes"));
-                    }
-                    self.expected_count = payload.active_lanes_count;
-                    let md = match *chunks {
-                        [lanes_root, payload_and_ctx_digest, parent_seq_commit] => {
-                            let [lanes_root, payload_and_ctx_digest, parent_seq_commit] =
-                                [lanes_root, payload_and_ctx_digest, parent_seq_commit].map(Hash::from_bytes);
-                            SmtExportMetadata {
-                                lanes_root,
-                                payload_and_ctx_digest,
-                                parent_seq_commit,
-                                active_lanes_count: payload.active_lanes_count,
-                            }
-                        }
-                        _ => return Err(ProtocolError::Other("SmtMetadata data must be 96 bytes")),
-                    };
+                    let md = smt_metadata_from_message(payload)?;
+                    self.decoder = SmtLaneDecoder::new(md.active_lanes_count);
                     Ok(md)
                 }
                 Some(Payload::UnexpectedPruningPoint(_)) => Err(ProtocolError::ConsensusError(ConsensusError::UnexpectedPruningPoint)),
@@ -271,8 +360,8 @@ impl<'a, 'b> SmtStream<'a, 'b> {
 
     /// Receives the next chunk of lanes from the peer. Returns `Ok(None)` once
     /// `active_lanes_count` lanes have been consumed.
-    pub async fn next_chunk(&mut self) -> Result<Option<Vec<kaspa_consensus_core::api::ImportLane>>, ProtocolError> {
-        if self.lane_count >= self.expected_count {
+    pub async fn next_chunk(&mut self) -> Result<Option<Vec<ImportLane>>, ProtocolError> {
+        if self.decoder.is_complete() {
             return Ok(None);
         }
 
@@ -293,53 +382,14 @@ impl<'a, 'b> SmtStream<'a, 'b> {
             Err(_) => return Err(ProtocolError::Timeout(DEFAULT_TIMEOUT)),
         };
 
-        if payload.entries.is_empty() {
-            return Err(ProtocolError::Other("received an empty SmtLaneChunk"));
-        }
-
-        if payload.entries.len() > SMT_CHUNK_SIZE {
-            return Err(ProtocolError::Other("SmtLaneChunk exceeds SMT_CHUNK_SIZE"));
-        }
-
-        let remaining = self.expected_count - self.lane_count;
-        if payload.entries.len() as u64 > remaining {
-            return Err(ProtocolError::Other("received more SMT lane entries than active_lanes_count"));
-        }
-
-        let mut lanes = Vec::with_capacity(payload.entries.len());
-        for entry in payload.entries {
-            let Some((&key_bytes, rem)) = entry.data.split_first_chunk::<32>() else {
-                return Err(ProtocolError::Other("SmtLaneEntry data too short for lane_key"));
-            };
-            let Some(&tip_bytes) = rem.first_chunk::<32>() else {
-                return Err(ProtocolError::Other("SmtLaneEntry data too short for lane_tip"));
-            };
-            if rem.len() != 32 {
-                return Err(ProtocolError::Other("SmtLaneEntry data must be exactly 64 bytes"));
-            }
-            let lane_key = Hash::from_bytes(key_bytes);
-            let lane_tip = Hash::from_bytes(tip_bytes);
-
-            let proof = if (self.lane_count as usize).is_multiple_of(SMT_PROOF_INTERVAL) {
-                Some(
-                    kaspa_smt::proof::OwnedSmtProof::from_bytes(&entry.proof)
-                        .map_err(|e| ProtocolError::OtherOwned(format!("invalid SMT proof: {e}")))?,
-                )
-            } else {
-                None
-            };
-
-            lanes.push(kaspa_consensus_core::api::ImportLane { lane_key, lane_tip, blue_score: entry.blue_score, proof });
-            self.lane_count += 1;
-        }
-
+        let lanes = self.decoder.decode(payload)?;
         self.chunks_received += 1;
 
         // Enqueue RequestNext for the next window — but only if more lanes remain.
         // When `lane_count == expected_count` the caller will stop iterating and the
         // sender's loop has already exhausted its DB iteration, so no further signal
         // is needed (and would dead-lock the sender past its last chunk).
-        if self.lane_count < self.expected_count && self.chunks_received.is_multiple_of(SMT_FLOW_CONTROL_WINDOW) {
+        if !self.decoder.is_complete() && self.chunks_received.is_multiple_of(SMT_FLOW_CONTROL_WINDOW) {
             self.router
                 .enqueue(make_message!(Payload::RequestNextPruningPointSmtChunk, RequestNextPruningPointSmtChunkMessage {}))
                 .await?;
@@ -349,6 +399,6 @@ impl<'a, 'b> SmtStream<'a, 'b> {
     }
 
     pub fn lane_count(&self) -> u64 {
-        self.lane_count
+        self.decoder.lane_count()
     }
 }
@@ -1,16 +1,17 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use kaspa_addressmanager::NetAddress;
 use kaspa_connectionmanager::ConnectionManager;
 use kaspa_core::{
-    task::service::{AsyncService, AsyncServiceFuture},
+    error,
+    task::service::{AsyncService, AsyncServiceError, AsyncServiceFuture},
     trace,
 };
-use kaspa_p2p_lib::Adaptor;
+use kaspa_p2p_lib::{Adaptor, Socks5Proxy};
 use kaspa_utils::triggers::SingleTrigger;
 use kaspa_utils_tower::counters::TowerConnectionCounters;
 
-use crate::flow_context::FlowContext;
+use crate::{flow_context::FlowContext, ibd::import_snapshot};
 
 const P2P_CORE_SERVICE: &str = "p2p-service";
 
@@ -25,6 +26,9 @@ pub struct P2pService {
     default_port: u16,
     shutdown: SingleTrigger,
     counters: Arc<TowerConnectionCounters>,
+    proxy: Option<Socks5Proxy>,
+    /// A pruning point snapshot to bootstrap from before connecting to peers
+    snapshot: Option<PathBuf>,
 }
 
 impl P2pService {
@@ -38,6 +42,8 @@ impl P2pService {
         dns_seeders: &'static [&'static str],
         default_port: u16,
         counters: Arc<TowerConnectionCounters>,
+        proxy: Option<Socks5Proxy>,
+        snapshot: Option<PathBuf>,
     ) -> Self {
         Self {
             flow_context,
@@ -50,6 +56,8 @@ impl P2pService {
             dns_seeders,
             default_port,
             counters,
+            proxy,
+            snapshot,
         }
     }
 }
@@ -65,26 +73,50 @@ impl AsyncService for P2pService {
         // Prepare a shutdown signal receiver
         let shutdown_signal = self.shutdown.listener.clone();
 
-        let p2p_adaptor = if self.inbound_limit == 0 {
-            Adaptor::client_only(self.flow_context.hub().clone(), self.flow_context.clone(), self.counters.clone())
-        } else {
-            Adaptor::bidirectional(self.listen, self.flow_context.hub().clone(), self.flow_context.clone(), self.counters.clone())
+        // Launch the service and wait for a shutdown signal
+        Box::pin(async move {
+            // The snapshot is imported before any peer connection, so that IBD continues from its pruning point
+            if let Some(snapshot) = self.snapshot.as_ref() {
+                tokio::select! {
+                    result = import_snapshot(&self.flow_context, snapshot) => {
+                        if let Err(err) = result {
+                            error!("Failed importing the snapshot {}: {}", snapshot.display(), err);
+                            return Err(AsyncServiceError::Service(format!("failed importing the snapshot: {err}")));
+                        }
+                    }
+                    _ = shutdown_signal.clone() => return Ok(()),
+                }
+            }
+
+            let p2p_adaptor = if self.inbound_limit == 0 {
+                Adaptor::client_only(
+                    self.flow_context.hub().clone(),
+                    self.flow_context.clone(),
+                    self.counters.clone(),
+                    self.proxy.clone(),
+                )
+            } else {
+                Adaptor::bidirectional(
+                    self.listen,
+                    self.flow_context.hub().clone(),
+                    self.flow_context.clone(),
+                    self.counters.clone(),
+                    self.proxy.clone(),
+                )
                 .unwrap()
-        };
-        let connection_manager = ConnectionManager::new(
-            p2p_adaptor.clone(),
-            self.outbound_target,
-            self.inbound_limit,
-            self.dns_seeders,
-            self.default_port,
-            self.flow_context.address_manager.clone(),
-        );
+            };
+            let connection_manager = ConnectionManager::new(
+                p2p_adaptor.clone(),
+                self.outbound_target,
+                self.inbound_limit,
+                self.dns_seeders,
+                self.default_port,
+                self.flow_context.address_manager.clone(),
+            );
 
-        self.flow_context.set_connection_manager(connection_manager.clone());
-        self.flow_context.start_async_services();
+            self.flow_context.set_connection_manager(connection_manager.clone());
+            self.flow_context.start_async_services();
 
-        // Launch the service and wait for a shutdown signal
-        Box::pin(async move {
             for peer_address in self.connect_peers.iter().cloned().chain(self.add_peers.iter().cloned()) {
                 connection_manager.add_connection_request(peer_address.into(), true).await;
             }
@@ -1,6 +1,9 @@
 use crate::v7::{
     address::{ReceiveAddressesFlow, SendAddressesFlow},
-    blockrelay::{flow::HandleRelayInvsFlow, handle_requests::HandleRelayBlockRequests},
+    blockrelay::{
+        flow::HandleRelayInvsFlow,
+        handle_requests::{HandleBlockTxnsRequests, HandleRelayBlockRequests},
+    },
     ping::{ReceivePingsFlow, SendPingsFlow},
     request_antipast::HandleAntipastRequests,
     request_block_locator::RequestBlockLocatorFlow,
@@ -10,13 +13,17 @@ use crate::v7::{
     request_pp_proof::RequestPruningPointProofFlow,
     request_pruning_point_and_anticone::PruningPointAndItsAnticoneRequestsFlow,
     request_pruning_point_utxo_set::RequestPruningPointUtxoSetFlow,
-    txrelay::flow::{RelayTransactionsFlow, RequestTransactionsFlow},
+    txrelay::flow::{RelayTransactionsFlow, RequestTransactionPackagesFlow, RequestTransactionsFlow},
 };
 use crate::v8::request_block_bodies::HandleBlockBodyRequests;
 pub(crate) mod request_pruning_point_smt_state;
 use request_pruning_point_smt_state::RequestPruningPointSmtStateFlow;
 
-use crate::{flow_context::FlowContext, flow_trait::Flow, ibd::IbdFlow};
+use crate::{
+    flow_context::{COMPACT_BLOCK_RELAY_PROTOCOL_VERSION, FlowContext},
+    flow_trait::Flow,
+    ibd::IbdFlow,
+};
 use kaspa_p2p_lib::{KaspadMessagePayloadType, Router, SharedIncomingRoute, convert::header::HeaderFormat};
 use kaspa_utils::channel;
 use std::sync::Arc;
@@ -25,6 +32,7 @@ pub fn register(ctx: FlowContext, router: Arc<Router>, protocol_version: u32) ->
     let (ibd_sender, relay_receiver) = channel::job();
     let body_only_ibd_permitted = true;
     let header_format = HeaderFormat::from(protocol_version);
+    let compact_block_relay = protocol_version >= COMPACT_BLOCK_RELAY_PROTOCOL_VERSION;
     let mut flows: Vec<Box<dyn Flow>> = vec![
         Box::new(IbdFlow::new(
             ctx.clone(),
@@ -52,12 +60,15 @@ pub fn register(ctx: FlowContext, router: Arc<Router>, protocol_version: u32) ->
             body_only_ibd_permitted,
             header_format,
         )),
-        Box::new(HandleRelayBlockRequests::new(
-            ctx.clone(),
-            router.clone(),
-            router.subscribe(vec![KaspadMessagePayloadType::RequestRelayBlocks]),
-            header_format,
-        )),
+        Box::new({
+            let flow = HandleRelayBlockRequests::new(
+                ctx.clone(),
+                router.clone(),
+                router.subscribe(vec![KaspadMessagePayloadType::RequestRelayBlocks]),
+                header_format,
+            );
+            if compact_block_relay { flow.with_compact_block_relay() } else { flow }
+        }),
         Box::new(ReceivePingsFlow::new(ctx.clone(), router.clone(), router.subscribe(vec![KaspadMessagePayloadType::Ping]))),
         Box::new(SendPingsFlow::new(ctx.clone(), router.clone(), router.subscribe(vec![KaspadMessagePayloadType::Pong]))),
         Box::new(RequestHeadersFlow::new(
@@ -119,20 +130,34 @@ pub fn register(ctx: FlowContext, router: Arc<Router>, protocol_version: u32) ->
             router.subscribe(vec![KaspadMessagePayloadType::RequestAntipast]),
             header_format,
         )),
-        Box::new(RelayTransactionsFlow::new(
+        Box::new(
+            RelayTransactionsFlow::new(
+                ctx.clone(),
+                router.clone(),
+                router.subscribe_with_capacity(
+                    vec![KaspadMessagePayloadType::InvTransactions],
+                    RelayTransactionsFlow::invs_channel_size(),
+                ),
+                router.subscribe_with_capacity(
+                    vec![
+                        KaspadMessagePayloadType::Transaction,
+                        KaspadMessagePayloadType::TransactionNotFound,
+                        KaspadMessagePayloadType::TransactionPackage,
+                    ],
+                    RelayTransactionsFlow::txs_channel_size(),
+                ),
+            )
+            .with_package_relay(),
+        ),
+        Box::new(RequestTransactionsFlow::new(
             ctx.clone(),
             router.clone(),
-            router
-                .subscribe_with_capacity(vec![KaspadMessagePayloadType::InvTransactions], RelayTransactionsFlow::invs_channel_size()),
-            router.subscribe_with_capacity(
-                vec![KaspadMessagePayloadType::Transaction, KaspadMessagePayloadType::TransactionNotFound],
-                RelayTransactionsFlow::txs_channel_size(),
-            ),
+            router.subscribe(vec![KaspadMessagePayloadType::RequestTransactions]),
         )),
-        Box::new(RequestTransactionsFlow::new(
+        Box::new(RequestTransactionPackagesFlow::new(
             ctx.clone(),
             router.clone(),
-            router.subscribe(vec![KaspadMessagePayloadType::RequestTransactions]),
+            router.subscribe(vec![KaspadMessagePayloadType::RequestTransactionPackage]),
         )),
         Box::new(ReceiveAddressesFlow::new(ctx.clone(), router.clone(), router.subscribe(vec![KaspadMessagePayloadType::Addresses]))),
         Box::new(SendAddressesFlow::new(
@@ -147,19 +172,28 @@ pub fn register(ctx: FlowContext, router: Arc<Router>, protocol_version: u32) ->
         )),
     ];
 
+    if compact_block_relay {
+        flows.push(Box::new(HandleBlockTxnsRequests::new(
+            ctx.clone(),
+            router.clone(),
+            router.subscribe(vec![KaspadMessagePayloadType::RequestBlockTxns]),
+        )));
+    }
+
     let invs_route = router.subscribe_with_capacity(vec![KaspadMessagePayloadType::InvRelayBlock], ctx.block_invs_channel_size());
     let shared_invs_route = SharedIncomingRoute::new(invs_route);
 
     let num_relay_flows = (ctx.config.bps() as usize / 2).max(1);
     flows.extend((0..num_relay_flows).map(|_| {
-        Box::new(HandleRelayInvsFlow::new(
+        let flow = HandleRelayInvsFlow::new(
             ctx.clone(),
             router.clone(),
             shared_invs_route.clone(),
             router.subscribe(vec![]),
             ibd_sender.clone(),
             header_format,
-        )) as Box<dyn Flow>
+        );
+        Box::new(if compact_block_relay { flow.with_compact_block_relay() } else { flow }) as Box<dyn Flow>
     }));
 
     flows
@@ -1,13 +1,13 @@
 use crate::{flow_context::FlowContext, flow_trait::Flow};
-use itertools::Itertools;
 use kaspa_addressmanager::NetAddress;
 use kaspa_p2p_lib::{
     IncomingRoute, Router,
     common::ProtocolError,
+    convert::model::version::ONION_ADDRESSES_SERVICE,
     dequeue, dequeue_with_timeout, make_message,
-    pb::{AddressesMessage, RequestAddressesMessage, kaspad_message::Payload},
+    pb::{self, AddressesMessage, RequestAddressesMessage, kaspad_message::Payload},
 };
-use kaspa_utils::networking::IpAddress;
+use kaspa_utils::networking::{IpAddress, OnionNetAddress};
 use rand::seq::SliceRandom;
 use std::sync::Arc;
 
@@ -49,14 +49,18 @@ impl ReceiveAddressesFlow {
             .await?;
 
         let msg = dequeue_with_timeout!(self.incoming_route, Payload::Addresses)?;
-        let address_list: Vec<(IpAddress, u16)> = msg.try_into()?;
-        if address_list.len() > MAX_ADDRESSES_RECEIVE {
-            return Err(ProtocolError::OtherOwned(format!("address count {} exceeded {}", address_list.len(), MAX_ADDRESSES_RECEIVE)));
+        let (address_list, onion_address_list): (Vec<(IpAddress, u16)>, Vec<OnionNetAddress>) = msg.try_into()?;
+        let address_count = address_list.len() + onion_address_list.len();
+        if address_count > MAX_ADDRESSES_RECEIVE {
+            return Err(ProtocolError::OtherOwned(format!("address count {} exceeded {}", address_count, MAX_ADDRESSES_RECEIVE)));
         }
         let mut amgr_lock = self.ctx.address_manager.lock();
         for (ip, port) in address_list {
             amgr_lock.add_address(NetAddress::new(ip, port))
         }
+        for address in onion_address_list {
+            amgr_lock.add_onion_address(address)
+        }
 
         Ok(())
     }
@@ -87,11 +91,14 @@ impl SendAddressesFlow {
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
         loop {
             dequeue!(self.incoming_route, Payload::RequestAddresses)?;
-            let addresses = self.ctx.address_manager.lock().iterate_addresses().collect_vec();
-            let address_list = addresses
-                .choose_multiple(&mut rand::thread_rng(), MAX_ADDRESSES_SEND)
-                .map(|addr| (addr.ip, addr.port).into())
-                .collect();
+            // Onion addresses are only sent to peers which can decode them
+            let include_onion = self.router.properties().services & ONION_ADDRESSES_SERVICE != 0;
+            let addresses: Vec<pb::NetAddress> = {
+                let amgr_lock = self.ctx.address_manager.lock();
+                let onion_addresses = include_onion.then(|| amgr_lock.iterate_onion_addresses()).into_iter().flatten();
+                amgr_lock.iterate_addresses().map(|addr| (addr.ip, addr.port).into()).chain(onion_addresses.map(Into::into)).collect()
+            };
+            let address_list = addresses.choose_multiple(&mut rand::thread_rng(), MAX_ADDRESSES_SEND).cloned().collect();
             self.router.enqueue(make_message!(Payload::Addresses, AddressesMessage { address_list })).await?;
         }
     }
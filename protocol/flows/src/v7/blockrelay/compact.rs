@@ -0,0 +1,251 @@
+use kaspa_consensus_core::{
+    block::Block,
+    header::Header,
+    merkle::calc_hash_merkle_root,
+    tx::{Transaction, TransactionId},
+};
+use kaspa_hashes::Hash;
+use kaspa_mining::{manager::MiningManagerProxy, model::tx_query::TransactionQuery};
+use kaspa_p2p_lib::{
+    common::ProtocolError,
+    convert::model::compact_block::{CompactBlock, short_transaction_id},
+};
+use std::{
+    collections::{HashMap, HashSet, hash_map::Entry},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// Counters of the compact block relay, reported by the bandwidth metrics
+#[derive(Default, Debug)]
+pub struct CompactBlockRelayCounters {
+    /// The number of relay blocks received in compact form
+    pub blocks_received: AtomicU64,
+    /// The number of transactions which could not be resolved locally and were requested from peers
+    pub transactions_requested: AtomicU64,
+    /// The p2p bytes saved by receiving compact blocks rather than full blocks
+    pub bytes_saved: AtomicU64,
+}
+
+impl CompactBlockRelayCounters {
+    pub fn record(&self, transactions_requested: usize, full_block_bytes: usize, received_bytes: usize) {
+        self.blocks_received.fetch_add(1, Ordering::Relaxed);
+        self.transactions_requested.fetch_add(transactions_requested as u64, Ordering::Relaxed);
+        self.bytes_saved.fetch_add(full_block_bytes.saturating_sub(received_bytes) as u64, Ordering::Relaxed);
+    }
+}
+
+/// A relay block being reconstructed from its compact form
+pub struct PartialBlock {
+    header: Arc<Header>,
+    salt: u64,
+    transactions: Vec<Option<Transaction>>,
+    /// The block index of each short id of the compact block
+    short_id_indexes: Vec<(usize, u64)>,
+}
+
+impl PartialBlock {
+    pub fn new(compact_block: CompactBlock) -> Result<Self, ProtocolError> {
+        let transaction_count = compact_block.transaction_count();
+        let mut transactions = vec![None; transaction_count];
+        for prefilled in compact_block.prefilled_transactions {
+            match transactions.get_mut(prefilled.index as usize) {
+                Some(slot @ None) => *slot = Some(prefilled.transaction),
+                _ => {
+                    return Err(ProtocolError::OtherOwned(format!(
+                        "compact block {} has an invalid prefilled transaction index {}",
+                        compact_block.header.hash, prefilled.index
+                    )));
+                }
+            }
+        }
+        let short_id_indexes = transactions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, tx)| tx.is_none().then_some(index))
+            .zip(compact_block.short_ids)
+            .collect();
+        Ok(Self { header: compact_block.header, salt: compact_block.salt, transactions, short_id_indexes })
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.header.hash
+    }
+
+    /// Resolves the short ids from the transaction pool and the orphan pool of the mempool. Short ids matching
+    /// more than a single transaction are left unresolved.
+    pub async fn fill_from_mempool(&mut self, mining_manager: &MiningManagerProxy) {
+        if self.short_id_indexes.is_empty() {
+            return;
+        }
+        // The mempool ids are hashed in place, only the transactions matching a short id are returned
+        let salt = self.salt;
+        let short_ids: HashSet<u64> = self.short_id_indexes.iter().map(|&(_, short_id)| short_id).collect();
+        let candidates = mining_manager
+            .clone()
+            .get_transactions_by_id_filter(TransactionQuery::All, move |id| short_ids.contains(&short_transaction_id(salt, id)))
+            .await;
+
+        let resolved = self.resolve_short_ids(candidates.iter().map(|tx| tx.id()));
+        let mut candidates: HashMap<_, _> = candidates.into_iter().map(|tx| (tx.id(), tx)).collect();
+        for (index, id) in resolved {
+            self.transactions[index] = candidates.remove(&id).map(|tx| (*tx).clone());
+        }
+    }
+
+    /// Maps block indexes to the candidate transactions matching their short id unambiguously
+    fn resolve_short_ids(&self, candidates: impl Iterator<Item = TransactionId>) -> HashMap<usize, TransactionId> {
+        let mut indexes = HashMap::with_capacity(self.short_id_indexes.len());
+        let mut ambiguous = HashSet::new();
+        for &(index, short_id) in self.short_id_indexes.iter() {
+            if indexes.insert(short_id, index).is_some() {
+                ambiguous.insert(short_id);
+            }
+        }
+
+        let mut resolved = HashMap::new();
+        for id in candidates {
+            let short_id = short_transaction_id(self.salt, &id);
+            if ambiguous.contains(&short_id) {
+                continue;
+            }
+            if let Some(&index) = indexes.get(&short_id) {
+                match resolved.entry(index) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(id);
+                    }
+                    Entry::Occupied(entry) => {
+                        entry.remove();
+                        ambiguous.insert(short_id);
+                    }
+                }
+            }
+        }
+        resolved
+    }
+
+    /// Returns the indexes of the transactions which are still unknown
+    pub fn missing_indexes(&self) -> Vec<u32> {
+        self.transactions.iter().enumerate().filter_map(|(index, tx)| tx.is_none().then_some(index as u32)).collect()
+    }
+
+    /// Fills the transactions at `indexes` with `transactions` as received from the peer
+    pub fn fill(&mut self, indexes: &[u32], transactions: Vec<Transaction>) -> Result<(), ProtocolError> {
+        if indexes.len() != transactions.len() {
+            return Err(ProtocolError::OtherOwned(format!(
+                "requested {} transactions of block {} but got {}",
+                indexes.len(),
+                self.hash(),
+                transactions.len()
+            )));
+        }
+        for (&index, transaction) in indexes.iter().zip(transactions) {
+            // Indexes were validated when requested
+            self.transactions[index as usize] = Some(transaction);
+        }
+        Ok(())
+    }
+
+    /// Returns the reconstructed block if all transactions are known and match the header merkle root.
+    ///
+    /// A mismatch indicates that a short id resolved to an unrelated mempool transaction, or to a mempool
+    /// transaction with a different mass commitment. In that case all the transactions identified by
+    /// short ids are cleared, so that they get requested from the peer.
+    pub fn into_block(mut self) -> Result<Block, Self> {
+        if self.transactions.iter().all(Option::is_some)
+            && calc_hash_merkle_root(self.transactions.iter().map(|tx| tx.as_ref().unwrap())) == self.header.hash_merkle_root
+        {
+            return Ok(Block::from_arcs(self.header, Arc::new(self.transactions.into_iter().flatten().collect())));
+        }
+        for &(index, _) in self.short_id_indexes.iter() {
+            self.transactions[index] = None;
+        }
+        Err(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{
+        subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE, SubnetworkId},
+        tx::{ComputeCommit, TransactionInput, TransactionOutpoint},
+    };
+
+    fn transaction(subnetwork_id: SubnetworkId, seed: u64) -> Transaction {
+        let input = TransactionInput {
+            previous_outpoint: TransactionOutpoint::new(TransactionId::from_u64_word(seed), 0),
+            signature_script: vec![],
+            sequence: 0,
+            compute_commit: ComputeCommit::SigopCount(0.into()),
+        };
+        Transaction::new(0, vec![input], vec![], 0, subnetwork_id, 0, vec![])
+    }
+
+    fn block() -> Block {
+        let transactions = (0..4)
+            .map(|seed| transaction(if seed == 0 { SUBNETWORK_ID_COINBASE } else { SUBNETWORK_ID_NATIVE }, seed))
+            .collect::<Vec<_>>();
+        let mut header = Header::from_precomputed_hash(Hash::from_u64_word(1), vec![]);
+        header.hash_merkle_root = calc_hash_merkle_root(transactions.iter());
+        Block::new(header, transactions)
+    }
+
+    #[test]
+    fn test_partial_block_reconstruction() {
+        let block = block();
+        let mut partial_block = PartialBlock::new(CompactBlock::new(&block, 7)).unwrap();
+        assert_eq!(partial_block.missing_indexes(), vec![1, 2, 3], "only the coinbase should be prefilled");
+
+        // Simulates transactions resolved from the mempool, the last one being requested from the peer
+        partial_block.fill(&[1, 2], block.transactions[1..3].to_vec()).unwrap();
+        assert_eq!(partial_block.missing_indexes(), vec![3]);
+        assert!(partial_block.fill(&[3], vec![]).is_err());
+        partial_block.fill(&[3], vec![block.transactions[3].clone()]).unwrap();
+
+        let reconstructed = partial_block.into_block().ok().unwrap();
+        assert_eq!(reconstructed.hash(), block.hash());
+        assert!(reconstructed.transactions.iter().map(|tx| tx.id()).eq(block.transactions.iter().map(|tx| tx.id())));
+    }
+
+    #[test]
+    fn test_partial_block_merkle_root_mismatch() {
+        let block = block();
+        let mut partial_block = PartialBlock::new(CompactBlock::new(&block, 7)).unwrap();
+
+        // A short id collision resolves an unrelated mempool transaction
+        let transactions = vec![block.transactions[1].clone(), transaction(SUBNETWORK_ID_NATIVE, 100), block.transactions[3].clone()];
+        partial_block.fill(&[1, 2, 3], transactions).unwrap();
+
+        let partial_block = partial_block.into_block().err().unwrap();
+        assert_eq!(partial_block.missing_indexes(), vec![1, 2, 3], "all transactions identified by short ids should be requested");
+    }
+
+    #[test]
+    fn test_partial_block_short_id_resolution() {
+        let block = block();
+        let partial_block = PartialBlock::new(CompactBlock::new(&block, 7)).unwrap();
+        let ids = block.transactions.iter().map(|tx| tx.id()).collect::<Vec<_>>();
+
+        let resolved = partial_block.resolve_short_ids(ids[1..].iter().copied());
+        assert_eq!(resolved, HashMap::from([(1, ids[1]), (2, ids[2]), (3, ids[3])]));
+
+        // Unrelated candidates do not hide the matching ones, however many they are
+        let unrelated = (0..50_000).map(|seed| TransactionId::from_u64_word(1_000 + seed));
+        let resolved = partial_block.resolve_short_ids(unrelated.chain(ids[1..].iter().copied()));
+        assert_eq!(resolved, HashMap::from([(1, ids[1]), (2, ids[2]), (3, ids[3])]));
+
+        // A short id matched twice, as by colliding candidates, is left for the peer to send
+        let resolved = partial_block.resolve_short_ids([ids[1], ids[2], ids[2]].into_iter());
+        assert_eq!(resolved, HashMap::from([(1, ids[1])]));
+    }
+
+    #[test]
+    fn test_partial_block_rejects_invalid_prefilled_index() {
+        let mut compact_block = CompactBlock::new(&block(), 7);
+        compact_block.prefilled_transactions[0].index = 4;
+        assert!(PartialBlock::new(compact_block).is_err());
+    }
+}
@@ -1,20 +1,30 @@
+use super::compact::PartialBlock;
 use crate::{
     flow_context::{BlockLogEvent, FlowContext, RequestScope},
     flow_trait::Flow,
     flowcontext::orphans::OrphanOutput,
 };
-use kaspa_consensus_core::{api::BlockValidationFutures, block::Block, blockstatus::BlockStatus, errors::block::RuleError};
+use kaspa_consensus_core::{
+    api::BlockValidationFutures, block::Block, blockstatus::BlockStatus, errors::block::RuleError, tx::Transaction,
+};
 use kaspa_consensusmanager::{BlockProcessingBatch, ConsensusProxy};
 use kaspa_core::debug;
 use kaspa_hashes::Hash;
 use kaspa_p2p_lib::{
     IncomingRoute, Router, SharedIncomingRoute,
     common::ProtocolError,
-    convert::header::{HeaderFormat, Versioned},
+    convert::{
+        header::{HeaderFormat, Versioned},
+        model::compact_block::CompactBlock,
+    },
     dequeue, dequeue_with_timeout, make_message, make_request,
-    pb::{InvRelayBlockMessage, RequestBlockLocatorMessage, RequestRelayBlocksMessage, kaspad_message::Payload},
+    pb::{
+        BlockMessage, InvRelayBlockMessage, RequestBlockLocatorMessage, RequestBlockTxnsMessage, RequestRelayBlocksMessage,
+        kaspad_message::Payload,
+    },
 };
 use kaspa_utils::channel::{JobSender, JobTrySendError as TrySendError};
+use prost::Message;
 use std::{collections::VecDeque, sync::Arc};
 
 pub struct RelayInvMessage {
@@ -66,6 +76,8 @@ pub struct HandleRelayInvsFlow {
     ibd_sender: JobSender<Block>,
     /// Header format determined by protocol version
     header_format: HeaderFormat,
+    /// Indicates whether relay blocks are received in compact form (see [`CompactBlock`])
+    compact_block_relay: bool,
 }
 
 #[async_trait::async_trait]
@@ -88,7 +100,22 @@ impl HandleRelayInvsFlow {
         ibd_sender: JobSender<Block>,
         header_format: HeaderFormat,
     ) -> Self {
-        Self { ctx, router, invs_route: TwoWayIncomingRoute::new(invs_route), msg_route, ibd_sender, header_format }
+        Self {
+            ctx,
+            router,
+            invs_route: TwoWayIncomingRoute::new(invs_route),
+            msg_route,
+            ibd_sender,
+            header_format,
+            compact_block_relay: false,
+        }
+    }
+
+    /// Requests relay blocks in compact form, reconstructing them from the local mempool. Must
+    /// only be enabled if the peer responds to relay block requests with compact blocks.
+    pub fn with_compact_block_relay(mut self) -> Self {
+        self.compact_block_relay = true;
+        self
     }
 
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
@@ -252,15 +279,82 @@ impl HandleRelayInvsFlow {
                 request_id
             ))
             .await?;
-        let msg = dequeue_with_timeout!(self.msg_route, Payload::Block)?;
-        let block: Block = Versioned(header_format, msg).try_into()?;
+        let block: Block = if self.compact_block_relay {
+            let msg = dequeue_with_timeout!(self.msg_route, Payload::CompactBlock)?;
+            let exchanged_bytes = msg.encoded_len();
+            let compact_block: CompactBlock = Versioned(header_format, msg).try_into()?;
+            if compact_block.hash() != requested_hash {
+                return Err(ProtocolError::UnrequestedData(format!(
+                    "requested block hash {} but got compact block {}",
+                    requested_hash,
+                    compact_block.hash()
+                )));
+            }
+            self.reconstruct_compact_block(compact_block, exchanged_bytes, request_id, header_format).await?
+        } else {
+            let msg = dequeue_with_timeout!(self.msg_route, Payload::Block)?;
+            Versioned(header_format, msg).try_into()?
+        };
         if block.hash() != requested_hash {
-            Err(ProtocolError::OtherOwned(format!("requested block hash {} but got block {}", requested_hash, block.hash())))
+            Err(ProtocolError::UnrequestedData(format!("requested block hash {} but got block {}", requested_hash, block.hash())))
         } else {
             Ok(Some((block, request_scope)))
         }
     }
 
+    /// Reconstructs a relay block from its compact form using the local mempool, and requests the
+    /// transactions which could not be resolved locally from the peer
+    async fn reconstruct_compact_block(
+        &mut self,
+        compact_block: CompactBlock,
+        mut exchanged_bytes: usize,
+        request_id: u32,
+        header_format: HeaderFormat,
+    ) -> Result<Block, ProtocolError> {
+        let mut partial_block = PartialBlock::new(compact_block)?;
+        partial_block.fill_from_mempool(self.ctx.mining_manager()).await;
+        let mut transactions_requested = 0;
+
+        // A second round only follows a merkle root mismatch, in which case all the transactions
+        // identified by short ids are requested from the peer
+        for _ in 0..2 {
+            let missing_indexes = partial_block.missing_indexes();
+            if !missing_indexes.is_empty() {
+                transactions_requested += missing_indexes.len();
+                let request =
+                    RequestBlockTxnsMessage { block_hash: Some(partial_block.hash().into()), indexes: missing_indexes.clone() };
+                exchanged_bytes += request.encoded_len();
+                self.router.enqueue(make_request!(Payload::RequestBlockTxns, request, request_id)).await?;
+                let msg = dequeue_with_timeout!(self.msg_route, Payload::BlockTxns)?;
+                exchanged_bytes += msg.encoded_len();
+                let (hash, transactions): (Hash, Vec<Transaction>) = msg.try_into()?;
+                if hash != partial_block.hash() {
+                    return Err(ProtocolError::UnrequestedData(format!(
+                        "requested transactions of block {} but got transactions of block {}",
+                        partial_block.hash(),
+                        hash
+                    )));
+                }
+                partial_block.fill(&missing_indexes, transactions)?;
+            }
+            match partial_block.into_block() {
+                Ok(block) => {
+                    let full_block_bytes = BlockMessage::from((header_format, &block)).encoded_len();
+                    self.ctx.compact_block_relay_counters().record(transactions_requested, full_block_bytes, exchanged_bytes);
+                    debug!(
+                        "Reconstructed compact block {} with {} transactions, {} of which were requested",
+                        block.hash(),
+                        block.transactions.len(),
+                        transactions_requested
+                    );
+                    return Ok(block);
+                }
+                Err(partial) => partial_block = partial,
+            }
+        }
+        Err(ProtocolError::OtherOwned(format!("transactions of compact block {} do not match its merkle root", partial_block.hash())))
+    }
+
     /// Process the orphan block. Returns `Some(BlockProcessingBatch)` if the block has no missing roots, where
     /// the batch includes ancestor blocks and their consensus processing batch. This indicates a retry is recommended.
     async fn process_orphan(
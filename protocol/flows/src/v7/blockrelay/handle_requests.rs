@@ -1,11 +1,12 @@
 use crate::{flow_context::FlowContext, flow_trait::Flow};
 use kaspa_core::debug;
+use kaspa_hashes::Hash;
 use kaspa_p2p_lib::{
     IncomingRoute, Router,
     common::ProtocolError,
-    convert::header::HeaderFormat,
+    convert::{header::HeaderFormat, model::compact_block::CompactBlock},
     dequeue_with_request_id, make_message, make_response,
-    pb::{InvRelayBlockMessage, kaspad_message::Payload},
+    pb::{BlockTxnsMessage, InvRelayBlockMessage, TransactionMessage, kaspad_message::Payload},
 };
 use std::sync::Arc;
 
@@ -14,6 +15,8 @@ pub struct HandleRelayBlockRequests {
     router: Arc<Router>,
     incoming_route: IncomingRoute,
     header_format: HeaderFormat,
+    /// Indicates whether relay blocks are sent in compact form
+    compact_block_relay: bool,
 }
 
 #[async_trait::async_trait]
@@ -29,7 +32,13 @@ impl Flow for HandleRelayBlockRequests {
 
 impl HandleRelayBlockRequests {
     pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute, header_format: HeaderFormat) -> Self {
-        Self { ctx, router, incoming_route, header_format }
+        Self { ctx, router, incoming_route, header_format, compact_block_relay: false }
+    }
+
+    /// Responds to relay block requests with compact blocks (see [`CompactBlock`])
+    pub fn with_compact_block_relay(mut self) -> Self {
+        self.compact_block_relay = true;
+        self
     }
 
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
@@ -46,7 +55,14 @@ impl HandleRelayBlockRequests {
 
             for hash in hashes {
                 let block = session.async_get_block(hash).await?;
-                self.router.enqueue(make_response!(Payload::Block, (self.header_format, &block).into(), request_id)).await?;
+                if self.compact_block_relay {
+                    let compact_block = CompactBlock::new(&block, rand::random());
+                    self.router
+                        .enqueue(make_response!(Payload::CompactBlock, (self.header_format, &compact_block).into(), request_id))
+                        .await?;
+                } else {
+                    self.router.enqueue(make_response!(Payload::Block, (self.header_format, &block).into(), request_id)).await?;
+                }
                 debug!("relayed block with hash {} to peer {}", hash, self.router);
             }
         }
@@ -68,3 +84,55 @@ impl HandleRelayBlockRequests {
         Ok(())
     }
 }
+
+/// Serves the transactions of relay blocks which peers could not reconstruct from compact blocks
+pub struct HandleBlockTxnsRequests {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for HandleBlockTxnsRequests {
+    fn router(&self) -> Option<Arc<Router>> {
+        Some(self.router.clone())
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        self.start_impl().await
+    }
+}
+
+impl HandleBlockTxnsRequests {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+
+    async fn start_impl(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let (msg, request_id) = dequeue_with_request_id!(self.incoming_route, Payload::RequestBlockTxns)?;
+            let (hash, indexes): (Hash, Vec<u32>) = msg.try_into()?;
+
+            let block = self.ctx.consensus().unguarded_session().async_get_block(hash).await?;
+            let transactions = indexes
+                .into_iter()
+                .map(|index| {
+                    block.transactions.get(index as usize).map(|tx| tx.into()).ok_or_else(|| {
+                        ProtocolError::OtherOwned(format!(
+                            "requested transaction index {} is out of the range of block {}",
+                            index, hash
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<TransactionMessage>, _>>()?;
+            debug!("relayed {} transactions of block {} to peer {}", transactions.len(), hash, self.router);
+            self.router
+                .enqueue(make_response!(
+                    Payload::BlockTxns,
+                    BlockTxnsMessage { block_hash: Some(hash.into()), transactions },
+                    request_id
+                ))
+                .await?;
+        }
+    }
+}
@@ -1,2 +1,3 @@
+pub mod compact;
 pub mod flow;
 pub mod handle_requests;
@@ -5,11 +5,12 @@ use crate::{
 };
 use kaspa_consensus_core::tx::{Transaction, TransactionId};
 use kaspa_consensusmanager::ConsensusProxy;
-use kaspa_core::{time::unix_now, warn};
+use kaspa_core::{debug, time::unix_now, warn};
 use kaspa_mining::{
     P2pTxCountSample,
     errors::MiningManagerError,
     mempool::{
+        config::MAXIMUM_PACKAGE_TRANSACTION_COUNT,
         errors::RuleError,
         tx::{Orphan, Priority, RbfPolicy},
     },
@@ -18,17 +19,27 @@ use kaspa_mining::{
 use kaspa_p2p_lib::{
     IncomingRoute, Router,
     common::{DEFAULT_TIMEOUT, ProtocolError},
+    convert::model::version::PACKAGE_RELAY_SERVICE,
     dequeue, make_message,
-    pb::{RequestTransactionsMessage, TransactionNotFoundMessage, kaspad_message::Payload},
+    pb::{
+        RequestTransactionPackageMessage, RequestTransactionsMessage, TransactionNotFoundMessage, TransactionPackageMessage,
+        kaspad_message::Payload,
+    },
 };
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use tokio::time::timeout;
 
 pub(crate) const MAX_TPS_THRESHOLD: u64 = 3000;
 
+/// Maximum number of transaction ids rejected as non-standard tracked per peer in order to
+/// request the packages of their orphan children
+const MAX_TRACKED_REJECTED_TRANSACTIONS: usize = 1000;
+
 enum Response {
     Transaction(Transaction),
     NotFound(TransactionId),
+    /// A non-empty package of transactions, the id being the one of the last transaction
+    Package(TransactionId, Vec<Transaction>),
 }
 
 impl Response {
@@ -36,6 +47,7 @@ impl Response {
         match self {
             Response::Transaction(tx) => tx.id(),
             Response::NotFound(id) => id.to_owned(),
+            Response::Package(id, _) => id.to_owned(),
         }
     }
 }
@@ -52,6 +64,13 @@ pub struct RelayTransactionsFlow {
 
     /// Track the number of spam txs coming from this peer
     spam_counter: u64,
+
+    /// Whether transaction packages can be requested from this peer
+    package_relay: bool,
+
+    /// Ids of the txs of this peer recently rejected as non-standard, which may get accepted as part
+    /// of a package paying for them
+    rejected_transactions: HashSet<TransactionId>,
 }
 
 /// Holds the state information for whether we will throttle tx relay or not
@@ -74,7 +93,16 @@ impl Flow for RelayTransactionsFlow {
 
 impl RelayTransactionsFlow {
     pub fn new(ctx: FlowContext, router: Arc<Router>, invs_route: IncomingRoute, msg_route: IncomingRoute) -> Self {
-        Self { ctx, router, invs_route, msg_route, spam_counter: 0 }
+        Self { ctx, router, invs_route, msg_route, spam_counter: 0, package_relay: false, rejected_transactions: HashSet::new() }
+    }
+
+    /// Enables requesting the packages of orphan transactions whose parents were rejected as non-standard.
+    /// Requests are only sent if the peer advertises the package relay service.
+    ///
+    /// Note: the msg route must then also carry TransactionPackage messages
+    pub fn with_package_relay(mut self) -> Self {
+        self.package_relay = true;
+        self
     }
 
     pub fn invs_channel_size() -> usize {
@@ -91,6 +119,7 @@ impl RelayTransactionsFlow {
 
     async fn start_impl(&mut self) -> Result<(), ProtocolError> {
         // trace!("Starting relay transactions flow with {}", self.router.identity());
+        self.package_relay &= self.router.properties().services & PACKAGE_RELAY_SERVICE != 0;
         let mut throttling_state = ThrottlingState {
             should_throttle: false,
             last_checked_time: unix_now(),
@@ -170,7 +199,7 @@ impl RelayTransactionsFlow {
         Ok(requests)
     }
 
-    /// Returns the next Transaction or TransactionNotFound message in msg_route,
+    /// Returns the next Transaction, TransactionNotFound or TransactionPackage message in msg_route,
     /// returning only one of the message types at a time.
     async fn read_response(&mut self) -> Result<Response, ProtocolError> {
         match timeout(DEFAULT_TIMEOUT, self.msg_route.recv()).await {
@@ -179,8 +208,20 @@ impl RelayTransactionsFlow {
                     match msg.payload {
                         Some(Payload::Transaction(payload)) => Ok(Response::Transaction(payload.try_into()?)),
                         Some(Payload::TransactionNotFound(payload)) => Ok(Response::NotFound(payload.try_into()?)),
+                        Some(Payload::TransactionPackage(payload)) => {
+                            let transactions: Vec<Transaction> = payload.try_into()?;
+                            if transactions.len() > MAXIMUM_PACKAGE_TRANSACTION_COUNT {
+                                return Err(ProtocolError::Other(
+                                    "Number of transactions in transaction package message is over the limit",
+                                ));
+                            }
+                            let Some(transaction_id) = transactions.last().map(|tx| tx.id()) else {
+                                return Err(ProtocolError::Other("Transaction package message is empty"));
+                            };
+                            Ok(Response::Package(transaction_id, transactions))
+                        }
                         _ => Err(ProtocolError::UnexpectedMessage(
-                            stringify!(Payload::Transaction | Payload::TransactionNotFound),
+                            stringify!(Payload::Transaction | Payload::TransactionNotFound | Payload::TransactionPackage),
                             msg.payload.as_ref().map(|v| v.into()),
                         )),
                     }
@@ -207,15 +248,30 @@ impl RelayTransactionsFlow {
             let response = self.read_response().await?;
             let transaction_id = response.transaction_id();
             if transaction_id != request.req {
-                return Err(ProtocolError::OtherOwned(format!(
+                return Err(ProtocolError::UnrequestedData(format!(
                     "requested transaction id {} but got transaction {}",
                     request.req, transaction_id
                 )));
             }
-            if let Response::Transaction(transaction) = response {
-                transactions.push(transaction);
+            match response {
+                Response::Transaction(transaction) => transactions.push(transaction),
+                Response::NotFound(_) => {}
+                Response::Package(..) => {
+                    return Err(ProtocolError::UnrequestedData("received a transaction package instead of a transaction".to_owned()));
+                }
             }
         }
+
+        // Keep the parents of the received transactions so orphans spending rejected transactions can be identified
+        let transaction_parents: Vec<(TransactionId, Vec<TransactionId>)> = if self.package_relay {
+            transactions
+                .iter()
+                .map(|tx| (tx.id(), tx.inputs.iter().map(|input| input.previous_outpoint.transaction_id).collect()))
+                .collect()
+        } else {
+            vec![]
+        };
+
         let insert_results = self
             .ctx
             .mining_manager()
@@ -230,7 +286,13 @@ impl RelayTransactionsFlow {
                     // TODO: discuss a banning process
                     return Err(ProtocolError::MisbehavingPeer(format!("rejected invalid transaction {}", transaction_id)));
                 }
-                Err(MiningManagerError::MempoolError(RuleError::RejectNonStandard(..))) => {
+                Err(MiningManagerError::MempoolError(RuleError::RejectNonStandard(transaction_id, _))) => {
+                    if self.package_relay {
+                        if self.rejected_transactions.len() >= MAX_TRACKED_REJECTED_TRANSACTIONS {
+                            self.rejected_transactions.clear();
+                        }
+                        self.rejected_transactions.insert(*transaction_id);
+                    }
                     self.spam_counter += 1;
                     if self.spam_counter.is_multiple_of(100) {
                         kaspa_core::warn!("Peer {} has shared {} spam/non-standard txs ({:?})", self.router, self.spam_counter, res);
@@ -250,6 +312,69 @@ impl RelayTransactionsFlow {
             )
             .await;
 
+        // Received transactions spending outputs of transactions rejected as non-standard likely ended up in
+        // the orphan pool. Their packages are requested, letting them pay for their parents
+        let package_candidates = transaction_parents
+            .into_iter()
+            .filter(|(_, parents)| parents.iter().any(|parent_id| self.rejected_transactions.contains(parent_id)))
+            .map(|(transaction_id, _)| transaction_id)
+            .collect();
+        self.request_transaction_packages(&consensus, package_candidates, should_throttle).await
+    }
+
+    /// Requests the packages of the given transactions if they were orphaned, and inserts them into the mempool
+    async fn request_transaction_packages(
+        &mut self,
+        consensus: &ConsensusProxy,
+        transaction_ids: Vec<TransactionId>,
+        should_throttle: bool,
+    ) -> Result<(), ProtocolError> {
+        for transaction_id in transaction_ids {
+            if !self.ctx.mining_manager().clone().has_transaction(transaction_id, TransactionQuery::OrphansOnly).await {
+                continue;
+            }
+            self.router
+                .enqueue(make_message!(
+                    Payload::RequestTransactionPackage,
+                    RequestTransactionPackageMessage { id: Some(transaction_id.into()) }
+                ))
+                .await?;
+            let response = self.read_response().await?;
+            if response.transaction_id() != transaction_id {
+                return Err(ProtocolError::UnrequestedData(format!(
+                    "requested the package of transaction {} but got transaction {}",
+                    transaction_id,
+                    response.transaction_id()
+                )));
+            }
+            let transactions = match response {
+                Response::Package(_, transactions) => transactions,
+                Response::NotFound(_) => continue,
+                Response::Transaction(_) => {
+                    return Err(ProtocolError::UnrequestedData("received a transaction instead of a transaction package".to_owned()));
+                }
+            };
+            match self
+                .ctx
+                .mining_manager()
+                .clone()
+                .validate_and_insert_transaction_package(consensus, transactions, Priority::Low)
+                .await
+            {
+                Ok(insertion) => {
+                    self.ctx.broadcast_transactions(insertion.accepted.iter().map(|x| x.id()), should_throttle).await;
+                }
+                Err(MiningManagerError::MempoolError(RuleError::RejectInvalid(transaction_id))) => {
+                    return Err(ProtocolError::MisbehavingPeer(format!(
+                        "rejected invalid transaction {} of the package",
+                        transaction_id
+                    )));
+                }
+                Err(err) => {
+                    debug!("Failed to insert the package of transaction {} relayed by {}: {}", transaction_id, self.router, err);
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -306,6 +431,54 @@ impl RequestTransactionsFlow {
     }
 }
 
+// Flow listening to RequestTransactionPackage messages, responding with the requested
+// transaction along with its mempool ancestors if it is in the mempool, or with
+// TransactionNotFound otherwise.
+pub struct RequestTransactionPackagesFlow {
+    ctx: FlowContext,
+    router: Arc<Router>,
+    incoming_route: IncomingRoute,
+}
+
+#[async_trait::async_trait]
+impl Flow for RequestTransactionPackagesFlow {
+    fn router(&self) -> Option<Arc<Router>> {
+        Some(self.router.clone())
+    }
+
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        self.start_impl().await
+    }
+}
+
+impl RequestTransactionPackagesFlow {
+    pub fn new(ctx: FlowContext, router: Arc<Router>, incoming_route: IncomingRoute) -> Self {
+        Self { ctx, router, incoming_route }
+    }
+
+    async fn start_impl(&mut self) -> Result<(), ProtocolError> {
+        loop {
+            let msg = dequeue!(self.incoming_route, Payload::RequestTransactionPackage)?;
+            let transaction_id: TransactionId = msg.try_into()?;
+            if let Some(package) = self.ctx.mining_manager().clone().get_transaction_package(transaction_id).await {
+                self.router
+                    .enqueue(make_message!(
+                        Payload::TransactionPackage,
+                        TransactionPackageMessage { transactions: package.iter().map(|tx| (&**tx).into()).collect() }
+                    ))
+                    .await?;
+            } else {
+                self.router
+                    .enqueue(make_message!(
+                        Payload::TransactionNotFound,
+                        TransactionNotFoundMessage { id: Some(transaction_id.into()) }
+                    ))
+                    .await?;
+            }
+        }
+    }
+}
+
 /// If in the last 10 seconds we exceeded the TPS threshold, we will throttle tx relay
 fn check_tx_throttling(throttling_state: &mut ThrottlingState, next_snapshot: P2pTxCountSample) {
     let snapshot_delta = &next_snapshot - &throttling_state.curr_snapshot;
@@ -10,7 +10,8 @@ async fn main() {
     // [0] - init p2p-adaptor - server side
     let ip_port = NetAddress::from_str("[::1]:50051").unwrap();
     let initializer = Arc::new(EchoFlowInitializer::new());
-    let adaptor = kaspa_p2p_lib::Adaptor::bidirectional(ip_port, kaspa_p2p_lib::Hub::new(), initializer, Default::default()).unwrap();
+    let adaptor =
+        kaspa_p2p_lib::Adaptor::bidirectional(ip_port, kaspa_p2p_lib::Hub::new(), initializer, Default::default(), None).unwrap();
     // [1] - connect to a few peers
     let ip_port = String::from("[::1]:16111");
     for i in 0..1 {
@@ -1,7 +1,7 @@
 use crate::{KaspadMessagePayloadType, convert::error::ConversionError, core::peer::PeerKey};
 use kaspa_consensus_core::errors::{block::RuleError, consensus::ConsensusError, pruning::PruningImportError};
 use kaspa_mining_errors::manager::MiningManagerError;
-use std::time::Duration;
+use std::{fmt::Display, time::Duration};
 use thiserror::Error;
 
 /// Default P2P communication timeout
@@ -49,6 +49,9 @@ pub enum ProtocolError {
     #[error("misbehaving peer: {0}")]
     MisbehavingPeer(String),
 
+    #[error("unrequested data: {0}")]
+    UnrequestedData(String),
+
     #[error("peer connection is closed")]
     ConnectionClosed,
 
@@ -72,6 +75,99 @@ pub enum ProtocolError {
 
     #[error("got reject message: {0}")]
     IgnorableReject(String),
+
+    #[error("encrypted transport error: {0}")]
+    TransportError(&'static str),
+}
+
+/// Kinds of peer misbehavior, each adding a penalty to the misbehavior score of the peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Misbehavior {
+    InvalidBlock,
+    InvalidTransaction,
+    MalformedMessage,
+    UnrequestedData,
+    Timeout,
+}
+
+impl Misbehavior {
+    /// The score penalty of this misbehavior. With the default ban threshold of 100, a single invalid
+    /// block gets the peer banned whereas timeouts are only penalized when repeated in short succession
+    pub fn penalty(self) -> u32 {
+        match self {
+            Self::InvalidBlock => 100,
+            Self::InvalidTransaction => 50,
+            Self::MalformedMessage => 50,
+            Self::UnrequestedData => 20,
+            Self::Timeout => 10,
+        }
+    }
+}
+
+impl Display for Misbehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::InvalidBlock => "invalid block",
+            Self::InvalidTransaction => "invalid transaction",
+            Self::MalformedMessage => "malformed message",
+            Self::UnrequestedData => "unrequested data",
+            Self::Timeout => "timeout",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Returns the misbehavior of a peer which sent a block failing with `err`. Only errors proving the block
+/// itself invalid count; errors depending on the local clock, pruning state or missing data do not.
+fn rule_error_misbehavior(err: &RuleError) -> Option<Misbehavior> {
+    match err {
+        RuleError::TimeTooFarIntoTheFuture(..)
+        | RuleError::MissingParents(_)
+        | RuleError::PruningViolation(_)
+        | RuleError::PrunedBlock
+        | RuleError::InsufficientDaaWindowSize(_)
+        | RuleError::InvalidTransactionsInNewBlock(_) => None,
+
+        RuleError::WrongBlockVersion(..)
+        | RuleError::NoParents
+        | RuleError::TooManyParents(..)
+        | RuleError::OriginParent
+        | RuleError::InvalidParentsRelation(..)
+        | RuleError::InvalidParent(_)
+        | RuleError::UnexpectedHeaderDaaScore(..)
+        | RuleError::UnexpectedHeaderBlueScore(..)
+        | RuleError::UnexpectedHeaderBlueWork(..)
+        | RuleError::UnexpectedDifficulty(..)
+        | RuleError::TimeTooOld(..)
+        | RuleError::KnownInvalid
+        | RuleError::MergeSetTooBig(..)
+        | RuleError::ViolatingBoundedMergeDepth
+        | RuleError::BadMerkleRoot(..)
+        | RuleError::NoTransactions
+        | RuleError::FirstTxNotCoinbase
+        | RuleError::MultipleCoinbases(_)
+        | RuleError::BadCoinbasePayload(_)
+        | RuleError::BadCoinbasePayloadBlueScore(..)
+        | RuleError::TxInIsolationValidationFailed(..)
+        | RuleError::ExceedsComputeMassLimit(..)
+        | RuleError::ExceedsTransientMassLimit(..)
+        | RuleError::ExceedsStorageMassLimit(..)
+        | RuleError::ExceedsLanesPerBlockLimit(..)
+        | RuleError::ExceedsGasPerLaneLimit(..)
+        | RuleError::DoubleSpendInSameBlock(_)
+        | RuleError::ChainedTransaction(_)
+        | RuleError::TxInContextFailed(..)
+        | RuleError::WrongSubsidy(..)
+        | RuleError::DuplicateTransactions(_)
+        | RuleError::InvalidPoW
+        | RuleError::WrongHeaderPruningPoint(..)
+        | RuleError::WrongSelectedParentOrder(..)
+        | RuleError::UnexpectedIndirectParents(..)
+        | RuleError::BadUTXOCommitment(..)
+        | RuleError::BadAcceptedIDMerkleRoot(..)
+        | RuleError::BadCoinbaseTransaction
+        | RuleError::InvalidTransactionsInUtxoContext(..) => Some(Misbehavior::InvalidBlock),
+    }
 }
 
 /// String used as a P2P convention to signal connection is rejected because we are connecting to ourselves
@@ -92,6 +188,18 @@ impl ProtocolError {
         !matches!(self, Self::ConnectionClosed | Self::OutgoingRouteCapacityReached(_))
     }
 
+    /// Returns the misbehavior indicated by this error, if the error is attributable to the peer
+    pub fn misbehavior(&self) -> Option<Misbehavior> {
+        match self {
+            Self::RuleError(err) => rule_error_misbehavior(err),
+            Self::MisbehavingPeer(_) => Some(Misbehavior::InvalidTransaction),
+            Self::ConversionError(_) => Some(Misbehavior::MalformedMessage),
+            Self::UnexpectedMessage(..) | Self::UnrequestedData(_) => Some(Misbehavior::UnrequestedData),
+            Self::Timeout(_) => Some(Misbehavior::Timeout),
+            _ => None,
+        }
+    }
+
     pub fn to_reject_message(&self) -> String {
         match self {
             Self::LoopbackConnection(_) => LOOPBACK_CONNECTION_MESSAGE.to_owned(),
@@ -219,3 +327,23 @@ macro_rules! dequeue {
 macro_rules! dequeue_with_request_id {
     ($receiver:expr, $pattern:path) => {{ $crate::unwrap_message_with_request_id!($receiver.recv().await, $pattern) }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_hashes::Hash;
+
+    #[test]
+    fn test_rule_error_misbehavior() {
+        let invalid = [RuleError::BadMerkleRoot(Hash::default(), Hash::default()), RuleError::InvalidPoW, RuleError::KnownInvalid];
+        for err in invalid {
+            assert_eq!(ProtocolError::RuleError(err).misbehavior(), Some(Misbehavior::InvalidBlock));
+        }
+        // Errors depending on the local state are not the peer's fault
+        let contextual =
+            [RuleError::TimeTooFarIntoTheFuture(2, 1), RuleError::MissingParents(vec![Hash::default()]), RuleError::PrunedBlock];
+        for err in contextual {
+            assert_eq!(ProtocolError::RuleError(err).misbehavior(), None);
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use super::{
     error::ConversionError,
-    header::Versioned,
+    header::{HeaderFormat, Versioned},
     model::{
+        compact_block::{CompactBlock, PrefilledTransaction},
         trusted::{TrustedDataEntry, TrustedDataPackage},
         version::{MAX_USER_AGENT_LEN, Version},
     },
@@ -12,10 +13,10 @@ use kaspa_consensus_core::{
     block::Block,
     header::Header,
     pruning::{PruningPointProof, PruningPointsList},
-    tx::{TransactionId, TransactionOutpoint, UtxoEntry},
+    tx::{Transaction, TransactionId, TransactionOutpoint, UtxoEntry},
 };
 use kaspa_hashes::Hash;
-use kaspa_utils::networking::{IpAddress, PeerId};
+use kaspa_utils::networking::{IpAddress, OnionNetAddress, PeerId};
 
 use std::{collections::HashMap, sync::Arc};
 
@@ -39,6 +40,25 @@ impl From<Version> for protowire::VersionMessage {
     }
 }
 
+impl From<(HeaderFormat, &CompactBlock)> for protowire::CompactBlockMessage {
+    fn from(value: (HeaderFormat, &CompactBlock)) -> Self {
+        let (header_format, item) = value;
+        Self {
+            header: Some((header_format, item.header.as_ref()).into()),
+            salt: item.salt,
+            short_ids: item.short_ids.clone(),
+            prefilled_transactions: item
+                .prefilled_transactions
+                .iter()
+                .map(|prefilled| protowire::PrefilledTransaction {
+                    index: prefilled.index,
+                    transaction: Some((&prefilled.transaction).into()),
+                })
+                .collect(),
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // protowire to consensus_core
 // ----------------------------------------------------------------------------
@@ -194,6 +214,42 @@ impl TryFrom<protowire::RequestRelayBlocksMessage> for Vec<Hash> {
     }
 }
 
+impl TryFrom<Versioned<protowire::CompactBlockMessage>> for CompactBlock {
+    type Error = ConversionError;
+
+    fn try_from(value: Versioned<protowire::CompactBlockMessage>) -> Result<Self, Self::Error> {
+        let Versioned(header_format, msg) = value;
+        let header = msg.header.ok_or(ConversionError::NoneValue)?;
+        let prefilled_transactions = msg
+            .prefilled_transactions
+            .into_iter()
+            .map(|prefilled| Ok(PrefilledTransaction { index: prefilled.index, transaction: prefilled.transaction.try_into_ex()? }))
+            .collect::<Result<Vec<_>, Self::Error>>()?;
+        Ok(Self {
+            header: Arc::new(Versioned(header_format, header).try_into()?),
+            salt: msg.salt,
+            short_ids: msg.short_ids,
+            prefilled_transactions,
+        })
+    }
+}
+
+impl TryFrom<protowire::RequestBlockTxnsMessage> for (Hash, Vec<u32>) {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::RequestBlockTxnsMessage) -> Result<Self, Self::Error> {
+        Ok((msg.block_hash.try_into_ex()?, msg.indexes))
+    }
+}
+
+impl TryFrom<protowire::BlockTxnsMessage> for (Hash, Vec<Transaction>) {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::BlockTxnsMessage) -> Result<Self, Self::Error> {
+        Ok((msg.block_hash.try_into_ex()?, msg.transactions.into_iter().map(|v| v.try_into()).collect::<Result<_, _>>()?))
+    }
+}
+
 impl TryFrom<protowire::RequestIbdBlocksMessage> for Vec<Hash> {
     type Error = ConversionError;
 
@@ -217,11 +273,16 @@ impl TryFrom<protowire::BlockLocatorMessage> for Vec<Hash> {
     }
 }
 
-impl TryFrom<protowire::AddressesMessage> for Vec<(IpAddress, u16)> {
+/// Splits the address list into IP addresses and onion addresses
+impl TryFrom<protowire::AddressesMessage> for (Vec<(IpAddress, u16)>, Vec<OnionNetAddress>) {
     type Error = ConversionError;
 
     fn try_from(msg: protowire::AddressesMessage) -> Result<Self, Self::Error> {
-        msg.address_list.into_iter().map(|addr| addr.try_into()).collect::<Result<_, _>>()
+        let (onion_list, ip_list): (Vec<_>, Vec<_>) = msg.address_list.into_iter().partition(|addr| !addr.onion.is_empty());
+        Ok((
+            ip_list.into_iter().map(|addr| addr.try_into()).collect::<Result<_, _>>()?,
+            onion_list.into_iter().map(|addr| addr.try_into()).collect::<Result<_, _>>()?,
+        ))
     }
 }
 
@@ -249,6 +310,22 @@ impl TryFrom<protowire::TransactionNotFoundMessage> for TransactionId {
     }
 }
 
+impl TryFrom<protowire::RequestTransactionPackageMessage> for TransactionId {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::RequestTransactionPackageMessage) -> Result<Self, Self::Error> {
+        msg.id.try_into_ex()
+    }
+}
+
+impl TryFrom<protowire::TransactionPackageMessage> for Vec<Transaction> {
+    type Error = ConversionError;
+
+    fn try_from(msg: protowire::TransactionPackageMessage) -> Result<Self, Self::Error> {
+        msg.transactions.into_iter().map(|v| v.try_into()).collect()
+    }
+}
+
 impl TryFrom<protowire::RequestBlockLocatorMessage> for (Hash, u32) {
     type Error = ConversionError;
     fn try_from(msg: protowire::RequestBlockLocatorMessage) -> Result<Self, Self::Error> {
@@ -0,0 +1,107 @@
+//!
+//! Model structures of compact block relay. A compact block carries the header of a relay block along with
+//! salted short ids of its transactions, which the receiver is expected to resolve from its own mempool.
+//!
+
+use kaspa_consensus_core::{
+    block::Block,
+    header::Header,
+    tx::{Transaction, TransactionId},
+};
+use kaspa_hashes::{CompactBlockShortId, Hash, HasherBase};
+use std::sync::Arc;
+
+/// The number of transaction id hash bytes kept by a short id
+pub const SHORT_ID_BYTES: usize = 6;
+
+/// A transaction sent in full within a compact block
+pub struct PrefilledTransaction {
+    /// The index of the transaction within the block
+    pub index: u32,
+    pub transaction: Transaction,
+}
+
+pub struct CompactBlock {
+    pub header: Arc<Header>,
+    /// The salt keying the short ids of this compact block
+    pub salt: u64,
+    /// Short ids of the non-prefilled transactions, in block order
+    pub short_ids: Vec<u64>,
+    /// Transactions sent in full, in increasing index order
+    pub prefilled_transactions: Vec<PrefilledTransaction>,
+}
+
+impl CompactBlock {
+    /// Builds the compact form of `block`. The coinbase transaction is always prefilled
+    /// since it is never found in the mempool of the receiver.
+    pub fn new(block: &Block, salt: u64) -> Self {
+        let mut transactions = block.transactions.iter();
+        let prefilled_transactions =
+            transactions.next().map(|coinbase| PrefilledTransaction { index: 0, transaction: coinbase.clone() }).into_iter().collect();
+        let short_ids = transactions.map(|tx| short_transaction_id(salt, &tx.id())).collect();
+        Self { header: block.header.clone(), salt, short_ids, prefilled_transactions }
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.header.hash
+    }
+
+    pub fn transaction_count(&self) -> usize {
+        self.short_ids.len() + self.prefilled_transactions.len()
+    }
+}
+
+/// Returns the short id of `transaction_id` under `salt`, i.e., the first [`SHORT_ID_BYTES`] bytes of
+/// their keyed hash. Salting makes it impractical to craft transactions colliding with the short ids of
+/// other transactions, since the salt of a compact block is only known once the block is relayed.
+pub fn short_transaction_id(salt: u64, transaction_id: &TransactionId) -> u64 {
+    let mut hasher = CompactBlockShortId::new();
+    hasher.update(salt.to_le_bytes()).update(transaction_id);
+    let hash = hasher.finalize();
+    let mut bytes = [0u8; 8];
+    bytes[..SHORT_ID_BYTES].copy_from_slice(&hash.as_bytes()[..SHORT_ID_BYTES]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kaspa_consensus_core::{
+        subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE, SubnetworkId},
+        tx::{ComputeCommit, TransactionInput, TransactionOutpoint},
+    };
+
+    fn transaction(subnetwork_id: SubnetworkId, seed: u64) -> Transaction {
+        let input = TransactionInput {
+            previous_outpoint: TransactionOutpoint::new(TransactionId::from_u64_word(seed), 0),
+            signature_script: vec![],
+            sequence: 0,
+            compute_commit: ComputeCommit::SigopCount(0.into()),
+        };
+        Transaction::new(0, vec![input], vec![], 0, subnetwork_id, 0, vec![])
+    }
+
+    #[test]
+    fn test_short_transaction_ids() {
+        let id = TransactionId::from_u64_word(7);
+        assert_eq!(short_transaction_id(1, &id), short_transaction_id(1, &id));
+        assert_ne!(short_transaction_id(1, &id), short_transaction_id(2, &id), "short ids should depend on the salt");
+        assert!(short_transaction_id(1, &id) < 1 << (SHORT_ID_BYTES * 8));
+    }
+
+    #[test]
+    fn test_compact_block_prefills_coinbase() {
+        let transactions =
+            vec![transaction(SUBNETWORK_ID_COINBASE, 0), transaction(SUBNETWORK_ID_NATIVE, 1), transaction(SUBNETWORK_ID_NATIVE, 2)];
+        let ids = transactions.iter().map(|tx| tx.id()).collect::<Vec<_>>();
+        let block = Block::new(Header::from_precomputed_hash(Hash::from_u64_word(1), vec![]), transactions);
+
+        let compact = CompactBlock::new(&block, 42);
+        assert_eq!(compact.hash(), block.hash());
+        assert_eq!(compact.transaction_count(), 3);
+        assert_eq!(compact.prefilled_transactions.len(), 1);
+        assert_eq!(compact.prefilled_transactions[0].index, 0);
+        assert_eq!(compact.prefilled_transactions[0].transaction.id(), ids[0]);
+        assert_eq!(compact.short_ids, vec![short_transaction_id(42, &ids[1]), short_transaction_id(42, &ids[2])]);
+    }
+}
@@ -8,6 +8,15 @@ use kaspa_utils::networking::{NetAddress, PeerId};
 /// Maximum allowed length for the user agent field in a version message `VersionMessage`.
 pub const MAX_USER_AGENT_LEN: usize = 256;
 
+/// Service flag advertising support for transaction package relay (`RequestTransactionPackage` messages)
+pub const PACKAGE_RELAY_SERVICE: u64 = 1;
+
+/// Service flag advertising support for onion v3 entries (`NetAddress.onion`) in `Addresses` messages
+pub const ONION_ADDRESSES_SERVICE: u64 = 1 << 1;
+
+/// Service flag advertising support for the encrypted transport (`TransportKeyExchange` and `Encrypted` messages)
+pub const ENCRYPTED_TRANSPORT_SERVICE: u64 = 1 << 2;
+
 pub struct Version {
     pub protocol_version: u32,
     pub network: String,
@@ -4,7 +4,7 @@ use super::error::ConversionError;
 use crate::pb as protowire;
 
 use itertools::Itertools;
-use kaspa_utils::networking::{IpAddress, NetAddress};
+use kaspa_utils::networking::{IpAddress, NetAddress, ONION_V3_PUBKEY_LEN, OnionAddress, OnionNetAddress};
 
 // ----------------------------------------------------------------------------
 // consensus_core to protowire
@@ -20,6 +20,7 @@ impl From<(IpAddress, u16)> for protowire::NetAddress {
                 IpAddr::V6(ip) => ip.octets().to_vec(),
             },
             port: port as u32,
+            onion: vec![],
         }
     }
 }
@@ -30,6 +31,12 @@ impl From<NetAddress> for protowire::NetAddress {
     }
 }
 
+impl From<OnionNetAddress> for protowire::NetAddress {
+    fn from(item: OnionNetAddress) -> Self {
+        Self { timestamp: 0, ip: vec![], port: item.port as u32, onion: item.onion.pubkey().to_vec() }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // protowire to consensus_core
 // ----------------------------------------------------------------------------
@@ -65,9 +72,19 @@ impl TryFrom<protowire::NetAddress> for NetAddress {
     }
 }
 
+impl TryFrom<protowire::NetAddress> for OnionNetAddress {
+    type Error = ConversionError;
+
+    fn try_from(item: protowire::NetAddress) -> Result<Self, Self::Error> {
+        let pubkey: [u8; ONION_V3_PUBKEY_LEN] =
+            item.onion.as_slice().try_into().map_err(|_| ConversionError::IllegalOnionLength(item.onion.len()))?;
+        Ok(OnionNetAddress::new(OnionAddress::new(pubkey), item.port.try_into()?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use kaspa_utils::networking::IpAddress;
+    use kaspa_utils::networking::{IpAddress, OnionNetAddress};
 
     use crate::pb;
     use std::{
@@ -77,14 +94,30 @@ mod tests {
 
     #[test]
     fn test_netaddress() {
-        let net_addr_ipv4 = pb::NetAddress { timestamp: 0, ip: hex::decode("6a0a8af0").unwrap(), port: 123 };
+        let net_addr_ipv4 = pb::NetAddress { timestamp: 0, ip: hex::decode("6a0a8af0").unwrap(), port: 123, onion: vec![] };
         let ipv4 = Ipv4Addr::from_str("106.10.138.240").unwrap().into();
         assert_eq!(<(IpAddress, u16)>::try_from(net_addr_ipv4.clone()).unwrap(), (ipv4, 123u16));
         assert_eq!(pb::NetAddress::from((ipv4, 123u16)), net_addr_ipv4);
 
-        let net_addr_ipv6 = pb::NetAddress { timestamp: 0, ip: hex::decode("20010db885a3000000008a2e03707334").unwrap(), port: 456 };
+        let net_addr_ipv6 =
+            pb::NetAddress { timestamp: 0, ip: hex::decode("20010db885a3000000008a2e03707334").unwrap(), port: 456, onion: vec![] };
         let ipv6 = Ipv6Addr::from_str("2001:0db8:85a3:0000:0000:8a2e:0370:7334").unwrap().into();
         assert_eq!(<(IpAddress, u16)>::try_from(net_addr_ipv6.clone()).unwrap(), (ipv6, 456u16));
         assert_eq!(pb::NetAddress::from((ipv6, 456u16)), net_addr_ipv6);
     }
+
+    #[test]
+    fn test_onion_netaddress() {
+        let onion = OnionNetAddress::from_str("2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion:16111").unwrap();
+        let net_addr_onion = pb::NetAddress {
+            timestamp: 0,
+            ip: vec![],
+            port: 16111,
+            onion: hex::decode("d1b38b83a83b3ed918c5bb69dd444ad56bc8d5835a914de73447474e5f02591b").unwrap(),
+        };
+        assert_eq!(pb::NetAddress::from(onion), net_addr_onion);
+        assert_eq!(OnionNetAddress::try_from(net_addr_onion.clone()).unwrap(), onion);
+        assert!(<(IpAddress, u16)>::try_from(net_addr_onion).is_err());
+        assert!(OnionNetAddress::try_from(pb::NetAddress { onion: vec![1; 16], ..Default::default() }).is_err());
+    }
 }
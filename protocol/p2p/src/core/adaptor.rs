@@ -1,6 +1,7 @@
 use crate::ConnectionError;
 use crate::common::ProtocolError;
 use crate::core::hub::Hub;
+use crate::core::socks::Socks5Proxy;
 use crate::{Router, core::connection_handler::ConnectionHandler};
 use kaspa_utils::networking::NetAddress;
 use kaspa_utils_tower::counters::TowerConnectionCounters;
@@ -45,24 +46,32 @@ impl Adaptor {
         Self { _server_termination: server_termination, connection_handler, hub }
     }
 
-    /// Creates a P2P adaptor with only client-side support. Typical Kaspa nodes should use `Adaptor::bidirectional`
-    pub fn client_only(hub: Hub, initializer: Arc<dyn ConnectionInitializer>, counters: Arc<TowerConnectionCounters>) -> Arc<Self> {
+    /// Creates a P2P adaptor with only client-side support. Typical Kaspa nodes should use `Adaptor::bidirectional`.
+    /// Outbound connections are routed through `proxy` when one is provided.
+    pub fn client_only(
+        hub: Hub,
+        initializer: Arc<dyn ConnectionInitializer>,
+        counters: Arc<TowerConnectionCounters>,
+        proxy: Option<Socks5Proxy>,
+    ) -> Arc<Self> {
         let (hub_sender, hub_receiver) = mpsc_channel(Self::hub_channel_size());
-        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters);
+        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters, proxy);
         let adaptor = Arc::new(Adaptor::new(None, connection_handler, hub));
         adaptor.hub.clone().start_event_loop(hub_receiver, initializer);
         adaptor
     }
 
-    /// Creates a bidirectional P2P adaptor with a server serving at `serve_address` and with client support
+    /// Creates a bidirectional P2P adaptor with a server serving at `serve_address` and with client support.
+    /// Outbound connections are routed through `proxy` when one is provided.
     pub fn bidirectional(
         serve_address: NetAddress,
         hub: Hub,
         initializer: Arc<dyn ConnectionInitializer>,
         counters: Arc<TowerConnectionCounters>,
+        proxy: Option<Socks5Proxy>,
     ) -> Result<Arc<Self>, ConnectionError> {
         let (hub_sender, hub_receiver) = mpsc_channel(Self::hub_channel_size());
-        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters);
+        let connection_handler = ConnectionHandler::new(hub_sender, initializer.clone(), counters, proxy);
         let server_termination = connection_handler.serve(serve_address)?;
         let adaptor = Arc::new(Adaptor::new(Some(server_termination), connection_handler, hub));
         adaptor.hub.clone().start_event_loop(hub_receiver, initializer);
@@ -84,6 +93,11 @@ impl Adaptor {
         self.connection_handler.connect_with_retry(peer_address, retry_attempts, retry_interval).await.map(|r| r.key())
     }
 
+    /// Whether outbound connections are routed through a proxy, which is required for reaching onion peers
+    pub fn is_proxied(&self) -> bool {
+        self.connection_handler.is_proxied()
+    }
+
     /// Terminates all peers and cleans up any additional async resources
     pub async fn close(&self) {
         self.terminate_all_peers().await;
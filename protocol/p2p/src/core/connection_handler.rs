@@ -1,18 +1,21 @@
 use crate::common::ProtocolError;
 use crate::core::hub::HubEvent;
+use crate::core::socks::{ProxyTarget, Socks5Proxy};
 use crate::pb::{
     KaspadMessage, p2p_client::P2pClient as ProtoP2pClient, p2p_server::P2p as ProtoP2p, p2p_server::P2pServer as ProtoP2pServer,
 };
 use crate::{ConnectionInitializer, Router};
 use futures::FutureExt;
+use hyper_util::rt::TokioIo;
 use kaspa_core::{debug, info};
-use kaspa_utils::networking::NetAddress;
+use kaspa_utils::networking::{IpAddress, NetAddress, OnionNetAddress};
 use kaspa_utils_tower::{
     counters::TowerConnectionCounters,
     middleware::{CountBytesBody, MapRequestBodyLayer, MapResponseBodyLayer, ServiceBuilder},
 };
-use std::net::ToSocketAddrs;
+use std::net::{SocketAddr, ToSocketAddrs};
 use std::pin::Pin;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
@@ -20,14 +23,18 @@ use tokio::sync::mpsc::{Sender as MpscSender, channel as mpsc_channel};
 use tokio::sync::oneshot::{Sender as OneshotSender, channel as oneshot_channel};
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::transport::{Error as TonicError, Server as TonicServer};
+use tonic::transport::{Endpoint, Error as TonicError, Server as TonicServer, Uri};
 use tonic::{Request, Response, Status as TonicStatus, Streaming};
+use tower::service_fn;
 
 #[derive(Error, Debug)]
 pub enum ConnectionError {
     #[error("missing socket address")]
     NoAddress,
 
+    #[error("onion address {0} can only be reached through a proxy")]
+    ProxyRequired(String),
+
     #[error("{0}")]
     IoError(#[from] std::io::Error),
 
@@ -51,6 +58,8 @@ pub struct ConnectionHandler {
     hub_sender: MpscSender<HubEvent>,
     initializer: Arc<dyn ConnectionInitializer>,
     counters: Arc<TowerConnectionCounters>,
+    /// When set, outbound connections are made through this proxy
+    proxy: Option<Socks5Proxy>,
 }
 
 impl ConnectionHandler {
@@ -58,8 +67,13 @@ impl ConnectionHandler {
         hub_sender: MpscSender<HubEvent>,
         initializer: Arc<dyn ConnectionInitializer>,
         counters: Arc<TowerConnectionCounters>,
+        proxy: Option<Socks5Proxy>,
     ) -> Self {
-        Self { hub_sender, initializer, counters }
+        Self { hub_sender, initializer, counters, proxy }
+    }
+
+    pub(crate) fn is_proxied(&self) -> bool {
+        self.proxy.is_some()
     }
 
     /// Launches a P2P server listener loop
@@ -93,19 +107,28 @@ impl ConnectionHandler {
         Ok(termination_sender)
     }
 
-    /// Connect to a new peer
+    /// Connect to a new peer. `peer_address` is either a socket address, possibly with a hostname, or an onion
+    /// address (`<onion>.onion:<port>`)
     pub(crate) async fn connect(&self, peer_address: String) -> Result<Arc<Router>, ConnectionError> {
-        let Some(socket_address) = peer_address.to_socket_addrs()?.next() else {
-            return Err(ConnectionError::NoAddress);
-        };
-        let peer_address = format!("http://{}", peer_address); // Add scheme prefix as required by Tonic
+        let (socket_address, target) = Self::resolve_peer_address(&peer_address, self.is_proxied())?;
 
-        let channel = tonic::transport::Endpoint::new(peer_address)?
+        let endpoint = Endpoint::new(format!("http://{}", peer_address))? // Add scheme prefix as required by Tonic
             .timeout(Duration::from_millis(Self::communication_timeout()))
-            .connect_timeout(Duration::from_millis(Self::connect_timeout()))
-            .tcp_keepalive(Some(Duration::from_millis(Self::keep_alive())))
-            .connect()
-            .await?;
+            .tcp_keepalive(Some(Duration::from_millis(Self::keep_alive())));
+
+        let channel = match self.proxy.clone() {
+            Some(proxy) => {
+                endpoint
+                    .connect_timeout(Duration::from_millis(Self::proxy_connect_timeout()))
+                    .connect_with_connector(service_fn(move |_: Uri| {
+                        let (proxy, target) = (proxy.clone(), target.clone());
+                        async move { proxy.connect(&target).await.map(TokioIo::new) }
+                    }))
+                    .await?
+            }
+            None if matches!(target, ProxyTarget::Domain(..)) => return Err(ConnectionError::ProxyRequired(peer_address)),
+            None => endpoint.connect_timeout(Duration::from_millis(Self::connect_timeout())).connect().await?,
+        };
 
         let channel = ServiceBuilder::new()
             .layer(MapResponseBodyLayer::new(move |body| {
@@ -145,6 +168,30 @@ impl ConnectionHandler {
         Ok(router)
     }
 
+    /// Returns the socket address identifying the peer at `peer_address` along with the target to connect to.
+    ///
+    /// When proxied, hostnames are left for the proxy to resolve, since a local DNS query would leak the node
+    /// identity. The peer is then identified by a placeholder IP derived from its hostname.
+    fn resolve_peer_address(peer_address: &str, proxied: bool) -> Result<(SocketAddr, ProxyTarget), ConnectionError> {
+        // Onion peers are identified by a placeholder IP, while the onion address itself is resolved by the proxy
+        if let Ok(onion) = OnionNetAddress::from_str(peer_address) {
+            return Ok((SocketAddr::from(onion.placeholder_address()), ProxyTarget::Domain(onion.onion.to_string(), onion.port)));
+        }
+        if let Ok(socket_address) = SocketAddr::from_str(peer_address) {
+            return Ok((socket_address, ProxyTarget::Ip(socket_address)));
+        }
+        if proxied {
+            let (host, port) = peer_address.rsplit_once(':').ok_or(ConnectionError::NoAddress)?;
+            let port = port.parse().map_err(|_| ConnectionError::NoAddress)?;
+            let placeholder = NetAddress::new(IpAddress::hostname_placeholder(host), port);
+            return Ok((SocketAddr::from(placeholder), ProxyTarget::Domain(host.to_owned(), port)));
+        }
+        let Some(socket_address) = peer_address.to_socket_addrs()?.next() else {
+            return Err(ConnectionError::NoAddress);
+        };
+        Ok((socket_address, ProxyTarget::Ip(socket_address)))
+    }
+
     /// Connect to a new peer with `retry_attempts` retries and `retry_interval` duration between each attempt
     pub(crate) async fn connect_with_retry(
         &self,
@@ -196,6 +243,11 @@ impl ConnectionHandler {
     fn connect_timeout() -> u64 {
         1_000
     }
+
+    /// Establishing connections through a proxy (and over Tor circuits in particular) takes considerably longer
+    fn proxy_connect_timeout() -> u64 {
+        30_000
+    }
 }
 
 #[tonic::async_trait]
@@ -225,3 +277,37 @@ impl ProtoP2p for ConnectionHandler {
         Ok(Response::new(Box::pin(ReceiverStream::new(outgoing_receiver).map(Ok)) as Self::MessageStreamStream))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_peer_address() {
+        let socket_address: SocketAddr = "10.0.0.1:16111".parse().unwrap();
+        for proxied in [false, true] {
+            let resolved = ConnectionHandler::resolve_peer_address("10.0.0.1:16111", proxied).unwrap();
+            assert_eq!(resolved, (socket_address, ProxyTarget::Ip(socket_address)));
+        }
+
+        let onion = "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion";
+        let (_, target) = ConnectionHandler::resolve_peer_address(&format!("{onion}:16111"), false).unwrap();
+        assert_eq!(target, ProxyTarget::Domain(onion.to_owned(), 16111));
+
+        // The `.invalid` TLD never resolves, so any local DNS query would fail
+        let host = "seeder.kaspa.invalid";
+        let resolved = ConnectionHandler::resolve_peer_address(&format!("{host}:16111"), true).unwrap();
+        assert_eq!(resolved.1, ProxyTarget::Domain(host.to_owned(), 16111));
+        let placeholder = NetAddress::from(resolved.0);
+        assert!(placeholder.ip.is_hostname_placeholder() && !placeholder.ip.is_publicly_routable());
+        assert_eq!(placeholder.port, 16111);
+
+        // Peers are banned and disconnected by IP, so each hostname must be identified by its own placeholder
+        let other = ConnectionHandler::resolve_peer_address("other.kaspa.invalid:16111", true).unwrap();
+        assert_ne!(other.0.ip(), resolved.0.ip(), "distinct hostnames should not share an IP");
+        let same = ConnectionHandler::resolve_peer_address(&format!("{}:16112", host.to_uppercase()), true).unwrap();
+        assert_eq!(same.0.ip(), resolved.0.ip(), "a hostname should always be identified by the same IP");
+        assert!(ConnectionHandler::resolve_peer_address(host, true).is_err(), "a hostname without port cannot be proxied");
+        assert!(ConnectionHandler::resolve_peer_address(&format!("{host}:16111"), false).is_err());
+    }
+}
@@ -52,6 +52,13 @@ pub enum KaspadMessagePayloadType {
     SmtMetadata,
     SmtLaneChunk,
     RequestNextPruningPointSmtChunk,
+    RequestTransactionPackage,
+    TransactionPackage,
+    CompactBlock,
+    RequestBlockTxns,
+    BlockTxns,
+    TransportKeyExchange,
+    Encrypted,
 }
 
 impl From<&KaspadMessagePayload> for KaspadMessagePayloadType {
@@ -112,6 +119,13 @@ impl From<&KaspadMessagePayload> for KaspadMessagePayloadType {
             KaspadMessagePayload::SmtMetadata(_) => KaspadMessagePayloadType::SmtMetadata,
             KaspadMessagePayload::SmtLaneChunk(_) => KaspadMessagePayloadType::SmtLaneChunk,
             KaspadMessagePayload::RequestNextPruningPointSmtChunk(_) => KaspadMessagePayloadType::RequestNextPruningPointSmtChunk,
+            KaspadMessagePayload::RequestTransactionPackage(_) => KaspadMessagePayloadType::RequestTransactionPackage,
+            KaspadMessagePayload::TransactionPackage(_) => KaspadMessagePayloadType::TransactionPackage,
+            KaspadMessagePayload::CompactBlock(_) => KaspadMessagePayloadType::CompactBlock,
+            KaspadMessagePayload::RequestBlockTxns(_) => KaspadMessagePayloadType::RequestBlockTxns,
+            KaspadMessagePayload::BlockTxns(_) => KaspadMessagePayloadType::BlockTxns,
+            KaspadMessagePayload::TransportKeyExchange(_) => KaspadMessagePayloadType::TransportKeyExchange,
+            KaspadMessagePayload::Encrypted(_) => KaspadMessagePayloadType::Encrypted,
         }
     }
 }
@@ -1,16 +1,19 @@
 use kaspa_consensus_core::subnets::SubnetworkId;
+use kaspa_hashes::Hash;
 use kaspa_utils::networking::{IpAddress, PeerId};
 use std::{fmt::Display, net::SocketAddr, sync::Arc, time::Instant};
 
 #[derive(Debug, Clone, Default)]
 pub struct PeerProperties {
     pub user_agent: String,
-    // TODO: add services
+    pub services: u64,
     pub advertised_protocol_version: u32,
     pub protocol_version: u32,
     pub disable_relay_tx: bool,
     pub subnetwork_id: Option<SubnetworkId>,
     pub time_offset: i64,
+    /// The session id of the encrypted transport, or `None` if the connection fell back to plaintext
+    pub transport_session_id: Option<Hash>,
 }
 
 #[derive(Debug)]
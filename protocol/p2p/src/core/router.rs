@@ -4,6 +4,7 @@ use crate::pb::{KaspadMessage, kaspad_message::Payload as KaspadMessagePayload};
 use crate::{KaspadMessagePayloadType, common::ProtocolError};
 use crate::{Peer, make_message};
 use kaspa_core::{debug, error, info, trace, warn};
+use kaspa_hashes::Hash;
 use kaspa_utils::networking::PeerId;
 use parking_lot::{Mutex, RwLock};
 use seqlock::SeqLock;
@@ -20,6 +21,7 @@ use tokio::sync::oneshot::{Sender as OneshotSender, channel as oneshot_channel};
 use tonic::Streaming;
 
 use super::peer::{PeerKey, PeerProperties};
+use super::transport::TransportState;
 
 pub struct IncomingRoute {
     rx: MpscReceiver<KaspadMessage>,
@@ -139,6 +141,10 @@ pub struct Router {
 
     /// Used for managing router mutable state
     mutable_state: Mutex<RouterMutableState>,
+
+    /// Encryption state of the connection. Locked while sending outgoing messages so that they reach the
+    /// network in the order of their encryption
+    transport: Mutex<TransportState>,
 }
 
 impl Display for Router {
@@ -193,6 +199,7 @@ impl Router {
             outgoing_route,
             hub_sender,
             mutable_state: Mutex::new(RouterMutableState::new(Some(start_sender), Some(shutdown_sender))),
+            transport: Default::default(),
         });
 
         let router_clone = router.clone();
@@ -294,6 +301,19 @@ impl Router {
         self.mutable_state.lock().last_ping_duration
     }
 
+    /// The session id of the encrypted transport with this peer, or `None` if the connection is not encrypted
+    pub fn transport_session_id(&self) -> Option<Hash> {
+        self.transport.lock().session_id()
+    }
+
+    /// Sends the local ephemeral key of the transport key exchange, see [`crate::core::transport`].
+    /// No other message should be enqueued until the key of the peer is received
+    pub(crate) fn begin_transport_key_exchange(&self) -> Result<(), ProtocolError> {
+        let mut transport = self.transport.lock();
+        let msg = transport.begin_key_exchange(self.is_outbound)?;
+        self.send_outgoing(msg)
+    }
+
     pub fn incoming_flow_baseline_channel_size() -> usize {
         256
     }
@@ -363,6 +383,7 @@ impl Router {
 
     /// Routes a message coming from the network to the corresponding registered flow
     pub fn route_to_flow(&self, msg: KaspadMessage) -> Result<(), ProtocolError> {
+        let msg = self.transport.lock().incoming(msg, self.is_outbound)?;
         if msg.payload.is_none() {
             debug!("P2P, Route to flow got empty payload, peer: {}", self);
             return Err(ProtocolError::Other("received kaspad p2p message with empty payload"));
@@ -402,6 +423,12 @@ impl Router {
     /// Enqueues a locally-originated message to be sent to the network peer
     pub async fn enqueue(&self, msg: KaspadMessage) -> Result<(), ProtocolError> {
         assert!(msg.payload.is_some(), "Kaspad P2P message should always have a value");
+        let mut transport = self.transport.lock();
+        let msg = transport.outgoing(msg);
+        self.send_outgoing(msg)
+    }
+
+    fn send_outgoing(&self, msg: KaspadMessage) -> Result<(), ProtocolError> {
         match self.outgoing_route.try_send(msg) {
             Ok(_) => Ok(()),
             Err(TrySendError::Closed(_)) => Err(ProtocolError::ConnectionClosed),
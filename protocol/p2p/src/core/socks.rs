@@ -0,0 +1,266 @@
+//! A minimal SOCKS5 client (RFC 1928) used for routing outbound P2P connections through a proxy such as Tor
+
+use rand::{Rng, distributions::Alphanumeric};
+use std::net::{IpAddr, SocketAddr};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+
+const SOCKS_VERSION: u8 = 0x05;
+const AUTH_NONE: u8 = 0x00;
+const AUTH_USERNAME_PASSWORD: u8 = 0x02;
+const AUTH_NO_ACCEPTABLE_METHOD: u8 = 0xff;
+const USERNAME_PASSWORD_VERSION: u8 = 0x01;
+const CMD_CONNECT: u8 = 0x01;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+const REPLY_SUCCEEDED: u8 = 0x00;
+
+/// The length of the random credentials used for stream isolation
+const ISOLATION_CREDENTIALS_LEN: usize = 16;
+
+#[derive(Error, Debug)]
+pub enum Socks5Error {
+    #[error("{0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("unexpected SOCKS version {0}")]
+    UnexpectedVersion(u8),
+
+    #[error("the proxy accepts none of the offered authentication methods")]
+    NoAcceptableAuthMethod,
+
+    #[error("the proxy selected an unoffered authentication method {0}")]
+    UnexpectedAuthMethod(u8),
+
+    #[error("proxy authentication failed")]
+    AuthenticationFailed,
+
+    #[error("proxy failed to connect: {}", reply_description(*.0))]
+    ConnectFailed(u8),
+
+    #[error("unsupported address type {0} in proxy reply")]
+    UnsupportedAddressType(u8),
+
+    #[error("domain name of length {0} is too long")]
+    DomainTooLong(usize),
+}
+
+fn reply_description(reply: u8) -> &'static str {
+    match reply {
+        0x01 => "general SOCKS server failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+/// The destination of a proxied connection. Domains (such as onion services) are resolved by the proxy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProxyTarget {
+    Ip(SocketAddr),
+    Domain(String, u16),
+}
+
+/// A SOCKS5 proxy through which outbound connections are made
+#[derive(Clone, Debug)]
+pub struct Socks5Proxy {
+    pub address: SocketAddr,
+    /// When set, each connection authenticates with distinct random credentials. Tor isolates streams
+    /// by credentials (`IsolateSOCKSAuth`), so that peers are reached over different circuits.
+    pub stream_isolation: bool,
+}
+
+impl Socks5Proxy {
+    pub fn new(address: SocketAddr, stream_isolation: bool) -> Self {
+        Self { address, stream_isolation }
+    }
+
+    /// Connects to `target` through the proxy and returns the established stream
+    pub async fn connect(&self, target: &ProxyTarget) -> Result<TcpStream, Socks5Error> {
+        let mut stream = TcpStream::connect(self.address).await?;
+        self.handshake(&mut stream, target).await?;
+        Ok(stream)
+    }
+
+    async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(&self, stream: &mut S, target: &ProxyTarget) -> Result<(), Socks5Error> {
+        // Method negotiation. With stream isolation, credentials are offered in addition to no authentication,
+        // which leaves proxies without authentication support usable
+        if self.stream_isolation {
+            stream.write_all(&[SOCKS_VERSION, 2, AUTH_NONE, AUTH_USERNAME_PASSWORD]).await?;
+        } else {
+            stream.write_all(&[SOCKS_VERSION, 1, AUTH_NONE]).await?;
+        }
+        let mut response = [0u8; 2];
+        stream.read_exact(&mut response).await?;
+        if response[0] != SOCKS_VERSION {
+            return Err(Socks5Error::UnexpectedVersion(response[0]));
+        }
+        match response[1] {
+            AUTH_NONE => {}
+            AUTH_USERNAME_PASSWORD if self.stream_isolation => Self::authenticate(stream).await?,
+            AUTH_NO_ACCEPTABLE_METHOD => return Err(Socks5Error::NoAcceptableAuthMethod),
+            method => return Err(Socks5Error::UnexpectedAuthMethod(method)),
+        }
+
+        // Connect request
+        let mut request = vec![SOCKS_VERSION, CMD_CONNECT, 0x00];
+        let port = match target {
+            ProxyTarget::Ip(address) => {
+                match address.ip() {
+                    IpAddr::V4(ip) => {
+                        request.push(ATYP_IPV4);
+                        request.extend_from_slice(&ip.octets());
+                    }
+                    IpAddr::V6(ip) => {
+                        request.push(ATYP_IPV6);
+                        request.extend_from_slice(&ip.octets());
+                    }
+                }
+                address.port()
+            }
+            ProxyTarget::Domain(domain, port) => {
+                let len = u8::try_from(domain.len()).map_err(|_| Socks5Error::DomainTooLong(domain.len()))?;
+                request.push(ATYP_DOMAIN);
+                request.push(len);
+                request.extend_from_slice(domain.as_bytes());
+                *port
+            }
+        };
+        request.extend_from_slice(&port.to_be_bytes());
+        stream.write_all(&request).await?;
+
+        // Reply: VER | REP | RSV | ATYP | BND.ADDR | BND.PORT, where the bound address is of no interest to us
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != SOCKS_VERSION {
+            return Err(Socks5Error::UnexpectedVersion(reply[0]));
+        }
+        if reply[1] != REPLY_SUCCEEDED {
+            return Err(Socks5Error::ConnectFailed(reply[1]));
+        }
+        let address_len = match reply[3] {
+            ATYP_IPV4 => 4,
+            ATYP_IPV6 => 16,
+            ATYP_DOMAIN => stream.read_u8().await? as usize,
+            atyp => return Err(Socks5Error::UnsupportedAddressType(atyp)),
+        };
+        let mut bound_address = vec![0u8; address_len + 2];
+        stream.read_exact(&mut bound_address).await?;
+        Ok(())
+    }
+
+    /// Username/password authentication (RFC 1929) with random credentials
+    async fn authenticate<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S) -> Result<(), Socks5Error> {
+        let mut rng = rand::thread_rng();
+        let mut credential = || (&mut rng).sample_iter(Alphanumeric).take(ISOLATION_CREDENTIALS_LEN).collect::<Vec<u8>>();
+        let (username, password) = (credential(), credential());
+
+        let mut request = vec![USERNAME_PASSWORD_VERSION, username.len() as u8];
+        request.extend_from_slice(&username);
+        request.push(password.len() as u8);
+        request.extend_from_slice(&password);
+        stream.write_all(&request).await?;
+
+        let mut response = [0u8; 2];
+        stream.read_exact(&mut response).await?;
+        if response[1] != 0x00 {
+            return Err(Socks5Error::AuthenticationFailed);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::duplex;
+
+    /// Plays the proxy side of a handshake, returning the requested credentials (if any) and the connect request
+    async fn serve_handshake<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S, reply: u8) -> (Option<(Vec<u8>, Vec<u8>)>, Vec<u8>) {
+        let mut header = [0u8; 2];
+        stream.read_exact(&mut header).await.unwrap();
+        let mut methods = vec![0u8; header[1] as usize];
+        stream.read_exact(&mut methods).await.unwrap();
+
+        let credentials = if methods.contains(&AUTH_USERNAME_PASSWORD) {
+            stream.write_all(&[SOCKS_VERSION, AUTH_USERNAME_PASSWORD]).await.unwrap();
+            assert_eq!(stream.read_u8().await.unwrap(), USERNAME_PASSWORD_VERSION);
+            let mut username = vec![0u8; stream.read_u8().await.unwrap() as usize];
+            stream.read_exact(&mut username).await.unwrap();
+            let mut password = vec![0u8; stream.read_u8().await.unwrap() as usize];
+            stream.read_exact(&mut password).await.unwrap();
+            stream.write_all(&[USERNAME_PASSWORD_VERSION, 0x00]).await.unwrap();
+            Some((username, password))
+        } else {
+            stream.write_all(&[SOCKS_VERSION, AUTH_NONE]).await.unwrap();
+            None
+        };
+
+        let mut request = vec![0u8; 4];
+        stream.read_exact(&mut request).await.unwrap();
+        let address_len = match request[3] {
+            ATYP_IPV4 => 4,
+            ATYP_IPV6 => 16,
+            _ => {
+                let len = stream.read_u8().await.unwrap();
+                request.push(len);
+                len as usize
+            }
+        };
+        let mut rest = vec![0u8; address_len + 2];
+        stream.read_exact(&mut rest).await.unwrap();
+        request.extend(rest);
+
+        stream.write_all(&[SOCKS_VERSION, reply, 0x00, ATYP_IPV4, 0, 0, 0, 0, 0, 0]).await.unwrap();
+        (credentials, request)
+    }
+
+    #[tokio::test]
+    async fn test_socks5_handshake() {
+        let onion = "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion".to_owned();
+        let proxy = Socks5Proxy::new("127.0.0.1:9050".parse().unwrap(), true);
+
+        // Domains are sent unresolved, with distinct credentials per connection
+        let mut used_credentials = Vec::new();
+        for _ in 0..2 {
+            let (mut client, mut server) = duplex(1024);
+            let target = ProxyTarget::Domain(onion.clone(), 16111);
+            let (result, (credentials, request)) =
+                tokio::join!(proxy.handshake(&mut client, &target), serve_handshake(&mut server, REPLY_SUCCEEDED));
+            result.unwrap();
+            let mut expected = vec![SOCKS_VERSION, CMD_CONNECT, 0x00, ATYP_DOMAIN, onion.len() as u8];
+            expected.extend_from_slice(onion.as_bytes());
+            expected.extend_from_slice(&16111u16.to_be_bytes());
+            assert_eq!(request, expected);
+            let (username, password) = credentials.unwrap();
+            assert_eq!(username.len(), ISOLATION_CREDENTIALS_LEN);
+            assert_eq!(password.len(), ISOLATION_CREDENTIALS_LEN);
+            used_credentials.push(username);
+        }
+        assert_ne!(used_credentials[0], used_credentials[1]);
+
+        // Without isolation no credentials are offered
+        let proxy = Socks5Proxy::new("127.0.0.1:9050".parse().unwrap(), false);
+        let (mut client, mut server) = duplex(1024);
+        let target = ProxyTarget::Ip("[2001:db8::1]:16111".parse().unwrap());
+        let (result, (credentials, request)) =
+            tokio::join!(proxy.handshake(&mut client, &target), serve_handshake(&mut server, REPLY_SUCCEEDED));
+        result.unwrap();
+        assert!(credentials.is_none());
+        assert_eq!(request[3], ATYP_IPV6);
+
+        // Proxy failures are surfaced
+        let (mut client, mut server) = duplex(1024);
+        let (result, _) = tokio::join!(proxy.handshake(&mut client, &target), serve_handshake(&mut server, 0x05));
+        assert!(matches!(result, Err(Socks5Error::ConnectFailed(0x05))));
+    }
+}
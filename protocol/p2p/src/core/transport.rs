@@ -0,0 +1,293 @@
+//! Opportunistic encryption of the P2P message stream.
+//!
+//! Peers which both advertise [`ENCRYPTED_TRANSPORT_SERVICE`] exchange ephemeral secp256k1 public keys
+//! (`TransportKeyExchange` messages) right after the version handshake. Each side derives the ECDH shared
+//! secret, from which a ChaCha20-Poly1305 key is derived for each direction of the stream, along with a
+//! session id which both peers report and which operators can compare out-of-band in order to detect a
+//! man in the middle. From then on, every message is sent as an `Encrypted` message wrapping the encoded
+//! original message. Nonces are per-direction message counters, hence dropped, replayed or reordered
+//! messages fail authentication and terminate the connection.
+//!
+//! Since the key exchange is not authenticated by long-term identities, it protects against passive
+//! observers and message tampering, while active interception is only detectable via the session id.
+//! Peers not advertising the service keep exchanging plaintext messages.
+//!
+//! [`ENCRYPTED_TRANSPORT_SERVICE`]: crate::convert::model::version::ENCRYPTED_TRANSPORT_SERVICE
+
+use crate::{
+    common::ProtocolError,
+    make_message,
+    pb::{EncryptedMessage, KaspadMessage, TransportKeyExchangeMessage, kaspad_message::Payload},
+};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, Nonce,
+    aead::{Aead, KeyInit},
+};
+use kaspa_hashes::{Hash, Hasher, P2pTransportKey, P2pTransportSessionId};
+use prost::Message;
+use secp256k1::{PublicKey, SECP256K1, SecretKey, ecdh::SharedSecret};
+use std::fmt::Debug;
+
+/// Domain tags of the keys of each direction, initiator being the outbound side of the connection
+const INITIATOR_TO_RESPONDER: &[u8] = b"initiator";
+const RESPONDER_TO_INITIATOR: &[u8] = b"responder";
+
+/// The cipher of a single direction of the message stream
+struct DirectionalCipher {
+    cipher: ChaCha20Poly1305,
+    counter: u64,
+}
+
+impl DirectionalCipher {
+    fn new(key: Hash) -> Self {
+        Self { cipher: ChaCha20Poly1305::new(Key::from_slice(&key.as_bytes())), counter: 0 }
+    }
+
+    fn next_nonce(&mut self) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        nonce[..8].copy_from_slice(&self.counter.to_le_bytes());
+        self.counter += 1;
+        nonce
+    }
+}
+
+/// Session keys negotiated with a peer
+pub(crate) struct TransportSession {
+    session_id: Hash,
+    outgoing: DirectionalCipher,
+    incoming: DirectionalCipher,
+}
+
+impl TransportSession {
+    fn new(secret: &SecretKey, public: &PublicKey, peer_public: &PublicKey, is_initiator: bool) -> Self {
+        let shared_secret = SharedSecret::new(peer_public, secret);
+        let (initiator_public, responder_public) = if is_initiator { (public, peer_public) } else { (peer_public, public) };
+        let transcript = [
+            shared_secret.secret_bytes().as_slice(),
+            initiator_public.serialize().as_slice(),
+            responder_public.serialize().as_slice(),
+        ]
+        .concat();
+        let direction_key = |direction: &[u8]| P2pTransportKey::hash([transcript.as_slice(), direction].concat());
+        let (outgoing, incoming) = if is_initiator {
+            (INITIATOR_TO_RESPONDER, RESPONDER_TO_INITIATOR)
+        } else {
+            (RESPONDER_TO_INITIATOR, INITIATOR_TO_RESPONDER)
+        };
+        Self {
+            session_id: P2pTransportSessionId::hash(&transcript),
+            outgoing: DirectionalCipher::new(direction_key(outgoing)),
+            incoming: DirectionalCipher::new(direction_key(incoming)),
+        }
+    }
+
+    fn seal(&mut self, msg: KaspadMessage) -> KaspadMessage {
+        let nonce = self.outgoing.next_nonce();
+        let ciphertext = self
+            .outgoing
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), msg.encode_to_vec().as_slice())
+            .expect("encryption only fails for plaintexts far beyond the max message size");
+        make_message!(Payload::Encrypted, EncryptedMessage { ciphertext })
+    }
+
+    fn open(&mut self, msg: EncryptedMessage) -> Result<KaspadMessage, ProtocolError> {
+        let nonce = self.incoming.next_nonce();
+        let plaintext = self
+            .incoming
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), msg.ciphertext.as_slice())
+            .map_err(|_| ProtocolError::TransportError("message authentication failed"))?;
+        let msg =
+            KaspadMessage::decode(plaintext.as_slice()).map_err(|_| ProtocolError::TransportError("malformed encrypted message"))?;
+        match msg.payload {
+            Some(Payload::Encrypted(_) | Payload::TransportKeyExchange(_)) => {
+                Err(ProtocolError::TransportError("nested transport message"))
+            }
+            _ => Ok(msg),
+        }
+    }
+}
+
+/// Negotiation state of the transport encryption of a connection
+#[derive(Default)]
+pub(crate) enum TransportState {
+    #[default]
+    Plaintext,
+
+    /// The key of the peer arrived before the local key was sent
+    PeerKeyReceived(PublicKey),
+
+    /// The local key was sent and the key of the peer is awaited
+    LocalKeySent(SecretKey, PublicKey),
+
+    /// All messages are encrypted in both directions
+    Encrypted(TransportSession),
+}
+
+impl Debug for TransportState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Avoid leaking key material to logs
+        match self {
+            Self::Plaintext => write!(f, "Plaintext"),
+            Self::PeerKeyReceived(_) => write!(f, "PeerKeyReceived"),
+            Self::LocalKeySent(..) => write!(f, "LocalKeySent"),
+            Self::Encrypted(session) => write!(f, "Encrypted({})", session.session_id),
+        }
+    }
+}
+
+impl TransportState {
+    /// The session id of the encrypted transport, if negotiated
+    pub(crate) fn session_id(&self) -> Option<Hash> {
+        match self {
+            Self::Encrypted(session) => Some(session.session_id),
+            _ => None,
+        }
+    }
+
+    /// Starts the key exchange by generating the local ephemeral key. Returns the message carrying it, which
+    /// must be sent in plaintext and must not be followed by other messages until the key exchange completes.
+    pub(crate) fn begin_key_exchange(&mut self, is_initiator: bool) -> Result<KaspadMessage, ProtocolError> {
+        let secret = SecretKey::new(&mut rand::thread_rng());
+        let public = PublicKey::from_secret_key(SECP256K1, &secret);
+        let msg =
+            make_message!(Payload::TransportKeyExchange, TransportKeyExchangeMessage { public_key: public.serialize().to_vec() });
+        *self = match self {
+            Self::Plaintext => Self::LocalKeySent(secret, public),
+            Self::PeerKeyReceived(peer_public) => Self::Encrypted(TransportSession::new(&secret, &public, peer_public, is_initiator)),
+            _ => return Err(ProtocolError::TransportError("key exchange was already started")),
+        };
+        Ok(msg)
+    }
+
+    fn receive_key_exchange(&mut self, msg: &TransportKeyExchangeMessage, is_initiator: bool) -> Result<(), ProtocolError> {
+        let peer_public =
+            PublicKey::from_slice(&msg.public_key).map_err(|_| ProtocolError::TransportError("invalid ephemeral public key"))?;
+        *self = match self {
+            Self::Plaintext => Self::PeerKeyReceived(peer_public),
+            Self::LocalKeySent(secret, public) => Self::Encrypted(TransportSession::new(secret, public, &peer_public, is_initiator)),
+            _ => return Err(ProtocolError::TransportError("unexpected key exchange message")),
+        };
+        Ok(())
+    }
+
+    /// Encrypts an outgoing message if the transport is encrypted
+    pub(crate) fn outgoing(&mut self, msg: KaspadMessage) -> KaspadMessage {
+        match self {
+            Self::Encrypted(session) => session.seal(msg),
+            _ => msg,
+        }
+    }
+
+    /// Decrypts an incoming message if the transport is encrypted, and advances the key exchange on
+    /// key exchange messages (which are then returned as is so that the handshake can observe them)
+    pub(crate) fn incoming(&mut self, mut msg: KaspadMessage, is_initiator: bool) -> Result<KaspadMessage, ProtocolError> {
+        match msg.payload.take() {
+            Some(Payload::Encrypted(encrypted)) => match self {
+                Self::Encrypted(session) => session.open(encrypted),
+                _ => Err(ProtocolError::TransportError("unexpected encrypted message")),
+            },
+            Some(Payload::TransportKeyExchange(key_exchange)) => {
+                self.receive_key_exchange(&key_exchange, is_initiator)?;
+                msg.payload = Some(Payload::TransportKeyExchange(key_exchange));
+                Ok(msg)
+            }
+            payload => match self {
+                Self::Encrypted(_) => Err(ProtocolError::TransportError("plaintext message over an encrypted transport")),
+                _ => {
+                    msg.payload = payload;
+                    Ok(msg)
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::{PingMessage, ReadyMessage};
+
+    fn ping(nonce: u64) -> KaspadMessage {
+        make_message!(Payload::Ping, PingMessage { nonce })
+    }
+
+    fn assert_ping(msg: KaspadMessage, expected_nonce: u64) {
+        assert!(matches!(msg.payload, Some(Payload::Ping(PingMessage { nonce })) if nonce == expected_nonce));
+    }
+
+    /// Negotiates a session between an initiator and a responder, where the initiator key reaches the responder
+    /// before the responder starts its own key exchange
+    fn negotiate() -> (TransportState, TransportState) {
+        let (mut initiator, mut responder) = (TransportState::default(), TransportState::default());
+        let initiator_key = initiator.begin_key_exchange(true).unwrap();
+        responder.incoming(initiator_key, false).unwrap();
+        assert!(matches!(responder, TransportState::PeerKeyReceived(_)));
+        let responder_key = responder.begin_key_exchange(false).unwrap();
+        assert!(responder.session_id().is_some());
+        let responder_key = initiator.incoming(responder_key, true).unwrap();
+        assert!(matches!(responder_key.payload, Some(Payload::TransportKeyExchange(_))));
+        (initiator, responder)
+    }
+
+    #[test]
+    fn test_transport_session_round_trip() {
+        let (mut initiator, mut responder) = negotiate();
+        assert_eq!(initiator.session_id(), responder.session_id());
+        assert_ne!(negotiate().0.session_id(), initiator.session_id());
+
+        for nonce in 0..3 {
+            let sealed = initiator.outgoing(ping(nonce));
+            assert!(matches!(sealed.payload, Some(Payload::Encrypted(_))));
+            assert_ping(responder.incoming(sealed, false).unwrap(), nonce);
+
+            let sealed = responder.outgoing(ping(nonce + 100));
+            assert_ping(initiator.incoming(sealed, true).unwrap(), nonce + 100);
+        }
+
+        // Route ids are carried inside the encrypted message
+        let sealed = initiator.outgoing(make_message!(Payload::Ready, ReadyMessage {}, 5, 7));
+        assert_eq!((sealed.response_id, sealed.request_id), (0, 0));
+        let opened = responder.incoming(sealed, false).unwrap();
+        assert_eq!((opened.response_id, opened.request_id), (5, 7));
+    }
+
+    #[test]
+    fn test_transport_session_rejections() {
+        let (mut initiator, mut responder) = negotiate();
+
+        // Plaintext messages are rejected once the transport is encrypted
+        assert!(responder.incoming(ping(1), false).is_err());
+
+        // Tampered messages fail authentication
+        let mut sealed = initiator.outgoing(ping(1));
+        if let Some(Payload::Encrypted(encrypted)) = sealed.payload.as_mut() {
+            encrypted.ciphertext[0] ^= 1;
+        }
+        assert!(responder.incoming(sealed, false).is_err());
+
+        // Replayed or reordered messages fail authentication as well
+        let (mut initiator, mut responder) = negotiate();
+        initiator.outgoing(ping(1));
+        assert!(responder.incoming(initiator.outgoing(ping(2)), false).is_err());
+        let (mut initiator, mut responder) = negotiate();
+        let sealed = initiator.outgoing(ping(1));
+        assert_ping(responder.incoming(sealed.clone(), false).unwrap(), 1);
+        assert!(responder.incoming(sealed, false).is_err());
+
+        // Encrypted messages are rejected before the key exchange completes, as are repeated key exchanges
+        let mut plaintext = TransportState::default();
+        assert!(plaintext.incoming(initiator.outgoing(ping(3)), false).is_err());
+        let mut initiator = TransportState::default();
+        let key = initiator.begin_key_exchange(true).unwrap();
+        assert!(initiator.begin_key_exchange(true).is_err());
+        let mut responder = TransportState::default();
+        responder.incoming(key.clone(), false).unwrap();
+        assert!(responder.incoming(key, false).is_err());
+
+        // Invalid keys are rejected
+        let invalid_key = make_message!(Payload::TransportKeyExchange, TransportKeyExchangeMessage { public_key: vec![5; 33] });
+        assert!(TransportState::default().incoming(invalid_key, false).is_err());
+    }
+}
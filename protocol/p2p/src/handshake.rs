@@ -4,6 +4,7 @@ use crate::pb::{ReadyMessage, VerackMessage, VersionMessage, kaspad_message::Pay
 use crate::{IncomingRoute, KaspadMessagePayloadType, Router};
 use crate::{common::ProtocolError, dequeue_with_timeout, make_message};
 use kaspa_core::debug;
+use kaspa_hashes::Hash;
 
 /// Implements the Kaspa peer-to-peer handshake protocol
 pub struct KaspadHandshake<'a> {
@@ -11,6 +12,7 @@ pub struct KaspadHandshake<'a> {
     version_receiver: IncomingRoute,
     verack_receiver: IncomingRoute,
     ready_receiver: IncomingRoute,
+    key_exchange_receiver: IncomingRoute,
 }
 
 impl<'a> KaspadHandshake<'a> {
@@ -21,6 +23,7 @@ impl<'a> KaspadHandshake<'a> {
             version_receiver: router.subscribe(vec![KaspadMessagePayloadType::Version]),
             verack_receiver: router.subscribe(vec![KaspadMessagePayloadType::Verack]),
             ready_receiver: router.subscribe(vec![KaspadMessagePayloadType::Ready]),
+            key_exchange_receiver: router.subscribe(vec![KaspadMessagePayloadType::TransportKeyExchange]),
         }
     }
 
@@ -53,6 +56,22 @@ impl<'a> KaspadHandshake<'a> {
         Ok(())
     }
 
+    /// Negotiates the encrypted transport with a peer which advertised support for it, returning the session id.
+    /// Should be called right after the version exchange, and only if both sides advertised the encrypted transport
+    /// service, since all following messages are encrypted.
+    pub async fn negotiate_encryption(&mut self) -> Result<Hash, ProtocolError> {
+        debug!("starting transport key exchange");
+
+        self.router.begin_transport_key_exchange()?;
+
+        // The router completes the key exchange when routing the key of the peer
+        let key_exchange_message =
+            dequeue_with_timeout!(self.key_exchange_receiver, Payload::TransportKeyExchange, Duration::from_secs(4))?;
+        debug!("accepted transport key exchange message: {key_exchange_message:?}");
+
+        self.router.transport_session_id().ok_or(ProtocolError::TransportError("key exchange was not completed"))
+    }
+
     /// Exchange `Ready` messages with the peer. This is the final step of the handshake protocol and should
     /// only be called after all flows corresponding to the version exchange info are registered.
     pub async fn exchange_ready_messages(&mut self) -> Result<(), ProtocolError> {
@@ -78,3 +97,100 @@ impl<'a> KaspadHandshake<'a> {
         recv_res
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert::model::version::ENCRYPTED_TRANSPORT_SERVICE;
+    use crate::pb::PingMessage;
+    use crate::{Adaptor, ConnectionInitializer, Hub};
+    use kaspa_core::time::unix_now;
+    use kaspa_utils::networking::NetAddress;
+    use std::{str::FromStr, sync::Arc};
+    use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+    use tonic::async_trait;
+    use uuid::Uuid;
+
+    /// Performs the handshake advertising `services`, negotiating the encrypted transport only if both sides
+    /// advertise it, then hands the router over along with a route of the pings it receives
+    struct TransportInitializer {
+        services: u64,
+        sender: UnboundedSender<(Arc<Router>, IncomingRoute)>,
+    }
+
+    impl TransportInitializer {
+        fn new(services: u64) -> (Self, UnboundedReceiver<(Arc<Router>, IncomingRoute)>) {
+            let (sender, receiver) = unbounded_channel();
+            (Self { services, sender }, receiver)
+        }
+    }
+
+    #[async_trait]
+    impl ConnectionInitializer for TransportInitializer {
+        async fn initialize_connection(&self, router: Arc<Router>) -> Result<(), ProtocolError> {
+            let mut handshake = KaspadHandshake::new(&router);
+            router.start();
+            let peer_version = handshake
+                .handshake(VersionMessage {
+                    protocol_version: 5,
+                    services: self.services,
+                    timestamp: unix_now() as i64,
+                    address: None,
+                    id: Vec::from(Uuid::new_v4().as_bytes()),
+                    user_agent: String::new(),
+                    disable_relay_tx: false,
+                    subnetwork_id: None,
+                    network: "kaspa-mainnet".to_string(),
+                })
+                .await?;
+            if self.services & peer_version.services & ENCRYPTED_TRANSPORT_SERVICE != 0 {
+                handshake.negotiate_encryption().await?;
+            }
+            let ping_receiver = router.subscribe(vec![KaspadMessagePayloadType::Ping]);
+            handshake.exchange_ready_messages().await?;
+            self.sender.send((router, ping_receiver)).expect("the test should keep the receiver");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handshake_encryption_negotiation() {
+        kaspa_core::log::try_init_logger("debug");
+
+        // The encrypted transport is only used if both peers advertise it, whichever side initiates the connection
+        for (port, services1, services2) in [
+            (50055, ENCRYPTED_TRANSPORT_SERVICE, 0),
+            (50057, 0, ENCRYPTED_TRANSPORT_SERVICE),
+            (50059, ENCRYPTED_TRANSPORT_SERVICE, ENCRYPTED_TRANSPORT_SERVICE),
+        ] {
+            let (initializer1, mut routers1) = TransportInitializer::new(services1);
+            let address1 = NetAddress::from_str(&format!("[::1]:{port}")).unwrap();
+            let adaptor1 = Adaptor::bidirectional(address1, Hub::new(), Arc::new(initializer1), Default::default(), None).unwrap();
+            let (initializer2, mut routers2) = TransportInitializer::new(services2);
+            let address2 = NetAddress::from_str(&format!("[::1]:{}", port + 1)).unwrap();
+            let adaptor2 = Adaptor::bidirectional(address2, Hub::new(), Arc::new(initializer2), Default::default(), None).unwrap();
+
+            adaptor1
+                .connect_peer_with_retries(address2.to_string(), 16, Duration::from_secs(1))
+                .await
+                .expect("peer connection failed");
+            let timeout = Duration::from_secs(8);
+            let (router1, mut pings1) = tokio::time::timeout(timeout, routers1.recv()).await.unwrap().unwrap();
+            let (router2, mut pings2) = tokio::time::timeout(timeout, routers2.recv()).await.unwrap().unwrap();
+
+            // Messages flow both ways after the handshake, whether encrypted or not
+            router1.enqueue(make_message!(Payload::Ping, PingMessage { nonce: 1 })).await.unwrap();
+            assert_eq!(dequeue_with_timeout!(pings2, Payload::Ping, timeout).unwrap().nonce, 1);
+            router2.enqueue(make_message!(Payload::Ping, PingMessage { nonce: 2 })).await.unwrap();
+            assert_eq!(dequeue_with_timeout!(pings1, Payload::Ping, timeout).unwrap().nonce, 2);
+
+            let encrypted = services1 & services2 & ENCRYPTED_TRANSPORT_SERVICE != 0;
+            assert_eq!(router1.transport_session_id().is_some(), encrypted);
+            assert_eq!(router2.transport_session_id().is_some(), encrypted);
+            assert_eq!(router1.transport_session_id(), router2.transport_session_id());
+
+            adaptor1.close().await;
+            adaptor2.close().await;
+        }
+    }
+}
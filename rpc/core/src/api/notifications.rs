@@ -48,6 +48,18 @@ pub enum Notification {
 
     #[display(fmt = "NewBlockTemplate notification")]
     NewBlockTemplate(NewBlockTemplateNotification),
+
+    #[display(fmt = "MempoolTransactionAdded notification: {} transactions", "_0.transactions.len()")]
+    MempoolTransactionAdded(MempoolTransactionAddedNotification),
+
+    #[display(fmt = "MempoolTransactionRemoved notification: {} transactions {:?}", "_0.transactions.len()", "_0.reason")]
+    MempoolTransactionRemoved(MempoolTransactionRemovedNotification),
+
+    #[display(fmt = "MempoolTransactionReplaced notification: {} transactions replaced", "_0.replaced.len()")]
+    MempoolTransactionReplaced(MempoolTransactionReplacedNotification),
+
+    #[display(fmt = "MempoolTransactionIncluded notification: {} transactions", "_0.transactions.len()")]
+    MempoolTransactionIncluded(MempoolTransactionIncludedNotification),
 }
 }
 
@@ -64,6 +76,10 @@ impl Notification {
             Notification::VirtualDaaScoreChanged(v) => to_value(&v),
             Notification::SinkBlueScoreChanged(v) => to_value(&v),
             Notification::VirtualChainChanged(v) => to_value(&v),
+            Notification::MempoolTransactionAdded(v) => to_value(&v),
+            Notification::MempoolTransactionRemoved(v) => to_value(&v),
+            Notification::MempoolTransactionReplaced(v) => to_value(&v),
+            Notification::MempoolTransactionIncluded(v) => to_value(&v),
         }
     }
 }
@@ -105,10 +121,24 @@ impl NotificationTrait for Notification {
         context: &SubscriptionContext,
     ) -> Option<Self> {
         match subscription.active() {
-            true => {
-                let Self::UtxosChanged(notification) = self else { return None };
-                notification.apply_utxos_changed_subscription(subscription, context).map(Self::UtxosChanged)
-            }
+            true => match self {
+                Self::UtxosChanged(notification) => {
+                    notification.apply_utxos_changed_subscription(subscription, context).map(Self::UtxosChanged)
+                }
+                Self::MempoolTransactionAdded(notification) => {
+                    notification.apply_utxos_changed_subscription(subscription, context).map(Self::MempoolTransactionAdded)
+                }
+                Self::MempoolTransactionRemoved(notification) => {
+                    notification.apply_utxos_changed_subscription(subscription, context).map(Self::MempoolTransactionRemoved)
+                }
+                Self::MempoolTransactionReplaced(notification) => {
+                    notification.apply_utxos_changed_subscription(subscription, context).map(Self::MempoolTransactionReplaced)
+                }
+                Self::MempoolTransactionIncluded(notification) => {
+                    notification.apply_utxos_changed_subscription(subscription, context).map(Self::MempoolTransactionIncluded)
+                }
+                _ => None,
+            },
             false => None,
         }
     }
@@ -158,6 +188,22 @@ impl Serializer for Notification {
                 store!(u16, &8, writer)?;
                 serialize!(NewBlockTemplateNotification, notification, writer)?;
             }
+            Notification::MempoolTransactionAdded(notification) => {
+                store!(u16, &9, writer)?;
+                serialize!(MempoolTransactionAddedNotification, notification, writer)?;
+            }
+            Notification::MempoolTransactionRemoved(notification) => {
+                store!(u16, &10, writer)?;
+                serialize!(MempoolTransactionRemovedNotification, notification, writer)?;
+            }
+            Notification::MempoolTransactionReplaced(notification) => {
+                store!(u16, &11, writer)?;
+                serialize!(MempoolTransactionReplacedNotification, notification, writer)?;
+            }
+            Notification::MempoolTransactionIncluded(notification) => {
+                store!(u16, &12, writer)?;
+                serialize!(MempoolTransactionIncludedNotification, notification, writer)?;
+            }
         }
         Ok(())
     }
@@ -203,6 +249,22 @@ impl Deserializer for Notification {
                 let notification = deserialize!(NewBlockTemplateNotification, reader)?;
                 Ok(Notification::NewBlockTemplate(notification))
             }
+            9 => {
+                let notification = deserialize!(MempoolTransactionAddedNotification, reader)?;
+                Ok(Notification::MempoolTransactionAdded(notification))
+            }
+            10 => {
+                let notification = deserialize!(MempoolTransactionRemovedNotification, reader)?;
+                Ok(Notification::MempoolTransactionRemoved(notification))
+            }
+            11 => {
+                let notification = deserialize!(MempoolTransactionReplacedNotification, reader)?;
+                Ok(Notification::MempoolTransactionReplaced(notification))
+            }
+            12 => {
+                let notification = deserialize!(MempoolTransactionIncludedNotification, reader)?;
+                Ok(Notification::MempoolTransactionIncluded(notification))
+            }
             _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid variant")),
         }
     }
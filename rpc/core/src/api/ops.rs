@@ -40,6 +40,10 @@ pub enum RpcApiOps {
     NotifyVirtualDaaScoreChanged = 16,
     NotifyVirtualChainChanged = 17,
     NotifySinkBlueScoreChanged = 18,
+    NotifyMempoolTransactionAdded = 19,
+    NotifyMempoolTransactionRemoved = 20,
+    NotifyMempoolTransactionReplaced = 21,
+    NotifyMempoolTransactionIncluded = 22,
 
     // Notification ops required by wRPC
 
@@ -54,6 +58,10 @@ pub enum RpcApiOps {
     VirtualDaaScoreChangedNotification = 66,
     PruningPointUtxoSetOverrideNotification = 67,
     NewBlockTemplateNotification = 68,
+    MempoolTransactionAddedNotification = 69,
+    MempoolTransactionRemovedNotification = 70,
+    MempoolTransactionReplacedNotification = 71,
+    MempoolTransactionIncludedNotification = 72,
 
     // RPC methods
     /// Ping the node to check if connection is alive
@@ -144,6 +152,18 @@ pub enum RpcApiOps {
     GetBlockRewardInfo = 152,
     /// Get Seq-Commit Lane Proof
     GetSeqCommitLaneProof = 153,
+    /// Get an accepted or mempool transaction by id
+    GetTransaction = 154,
+    /// Get the accepted transaction history of an address
+    GetAddressHistory = 155,
+    /// Get Seq-Commit Lane Proofs for several lanes at once
+    GetSeqCommitLaneProofs = 156,
+    /// Validate a transaction and its input scripts against the UTXO set without submitting it
+    ValidateTransactionScripts = 157,
+    /// Extracts a package of transactions out of the request message and attempts to add them to the mempool as a unit
+    SubmitTransactionPackage = 158,
+    /// Get the active bans of peer IP addresses
+    GetBannedPeers = 159,
 }
 
 impl RpcApiOps {
@@ -159,6 +179,10 @@ impl RpcApiOps {
                 | RpcApiOps::NotifyFinalityConflictResolved
                 | RpcApiOps::NotifySinkBlueScoreChanged
                 | RpcApiOps::NotifyVirtualDaaScoreChanged
+                | RpcApiOps::NotifyMempoolTransactionAdded
+                | RpcApiOps::NotifyMempoolTransactionRemoved
+                | RpcApiOps::NotifyMempoolTransactionReplaced
+                | RpcApiOps::NotifyMempoolTransactionIncluded
                 | RpcApiOps::Subscribe
                 | RpcApiOps::Unsubscribe
         )
@@ -185,6 +209,10 @@ impl From<EventType> for RpcApiOps {
             EventType::VirtualDaaScoreChanged => RpcApiOps::VirtualDaaScoreChangedNotification,
             EventType::PruningPointUtxoSetOverride => RpcApiOps::PruningPointUtxoSetOverrideNotification,
             EventType::NewBlockTemplate => RpcApiOps::NewBlockTemplateNotification,
+            EventType::MempoolTransactionAdded => RpcApiOps::MempoolTransactionAddedNotification,
+            EventType::MempoolTransactionRemoved => RpcApiOps::MempoolTransactionRemovedNotification,
+            EventType::MempoolTransactionReplaced => RpcApiOps::MempoolTransactionReplacedNotification,
+            EventType::MempoolTransactionIncluded => RpcApiOps::MempoolTransactionIncludedNotification,
         }
     }
 }
@@ -14,6 +14,9 @@ use kaspa_notify::{listener::ListenerId, scope::Scope, subscription::Command};
 use std::sync::Arc;
 
 pub const MAX_SAFE_WINDOW_SIZE: u32 = 10_000;
+pub const MAX_ADDRESS_HISTORY_PAGE_SIZE: u32 = 1_000;
+pub const MAX_HEADERS_LIMIT: u64 = 1_000;
+pub const MAX_SEQ_COMMIT_LANE_PROOFS: usize = 1_000;
 
 /// Client RPC Api
 ///
@@ -226,6 +229,37 @@ pub trait RpcApi: Sync + Send + AnySync {
         request: SubmitTransactionReplacementRequest,
     ) -> RpcResult<SubmitTransactionReplacementResponse>;
 
+    /// Submits a package of transactions to the mempool as a unit.
+    ///
+    /// The transactions may spend the outputs of one another. The package is accepted or rejected as a whole,
+    /// and the minimum relay fee applies to its combined fee and mass, so a child can pay for its parents.
+    async fn submit_transaction_package(&self, transactions: Vec<RpcTransaction>) -> RpcResult<Vec<RpcTransactionId>> {
+        Ok(self.submit_transaction_package_call(None, SubmitTransactionPackageRequest { transactions }).await?.transaction_ids)
+    }
+    async fn submit_transaction_package_call(
+        &self,
+        connection: Option<&DynRpcConnection>,
+        request: SubmitTransactionPackageRequest,
+    ) -> RpcResult<SubmitTransactionPackageResponse>;
+
+    /// Validates a transaction and executes all of its input scripts against the virtual UTXO set.
+    /// The transaction is not inserted into the mempool nor relayed.
+    ///
+    /// `utxo_entries` must either be empty, in which case the spent entries are read from the UTXO set,
+    /// or contain exactly one entry per transaction input.
+    async fn validate_transaction_scripts(
+        &self,
+        transaction: RpcTransaction,
+        utxo_entries: Vec<RpcUtxoEntry>,
+    ) -> RpcResult<ValidateTransactionScriptsResponse> {
+        self.validate_transaction_scripts_call(None, ValidateTransactionScriptsRequest::new(transaction, utxo_entries)).await
+    }
+    async fn validate_transaction_scripts_call(
+        &self,
+        connection: Option<&DynRpcConnection>,
+        request: ValidateTransactionScriptsRequest,
+    ) -> RpcResult<ValidateTransactionScriptsResponse>;
+
     /// Requests information about a specific block.
     async fn get_block(&self, hash: RpcHash, include_transactions: bool) -> RpcResult<RpcBlock> {
         Ok(self.get_block_call(None, GetBlockRequest::new(hash, include_transactions)).await?.block)
@@ -245,9 +279,26 @@ pub trait RpcApi: Sync + Send + AnySync {
         request: GetSeqCommitLaneProofRequest,
     ) -> RpcResult<GetSeqCommitLaneProofResponse>;
 
-    /// Requests information about a specific subnetwork.
-    async fn get_subnetwork(&self, subnetwork_id: RpcSubnetworkId) -> RpcResult<GetSubnetworkResponse> {
-        self.get_subnetwork_call(None, GetSubnetworkRequest::new(subnetwork_id)).await
+    /// Returns a single witness proving the states of up to [`MAX_SEQ_COMMIT_LANE_PROOFS`]
+    /// KIP-21 lanes against `block_hash`'s header `seq_commit`.
+    ///
+    /// `block_hash` must be a chain (selected-parent-chain) block.
+    async fn get_seq_commit_lane_proofs(
+        &self,
+        block_hash: RpcHash,
+        lane_keys: Vec<RpcHash>,
+    ) -> RpcResult<GetSeqCommitLaneProofsResponse> {
+        self.get_seq_commit_lane_proofs_call(None, GetSeqCommitLaneProofsRequest::new(block_hash, lane_keys)).await
+    }
+    async fn get_seq_commit_lane_proofs_call(
+        &self,
+        connection: Option<&DynRpcConnection>,
+        request: GetSeqCommitLaneProofsRequest,
+    ) -> RpcResult<GetSeqCommitLaneProofsResponse>;
+
+    /// Requests the lane state of a specific subnetwork as of the given chain block (defaults to the sink).
+    async fn get_subnetwork(&self, subnetwork_id: RpcSubnetworkId, block_hash: Option<RpcHash>) -> RpcResult<GetSubnetworkResponse> {
+        self.get_subnetwork_call(None, GetSubnetworkRequest::new(subnetwork_id, block_hash)).await
     }
     async fn get_subnetwork_call(
         &self,
@@ -325,7 +376,8 @@ pub trait RpcApi: Sync + Send + AnySync {
     }
     async fn shutdown_call(&self, connection: Option<&DynRpcConnection>, request: ShutdownRequest) -> RpcResult<ShutdownResponse>;
 
-    /// Requests headers between the given `start_hash` and the current virtual, up to the given limit.
+    /// Requests up to `limit` selected chain headers starting at (and including) `start_hash`, walking towards
+    /// the sink if `is_ascending` is set, or towards the retention period root otherwise.
     async fn get_headers(&self, start_hash: RpcHash, limit: u64, is_ascending: bool) -> RpcResult<Vec<RpcHeader>> {
         Ok(self.get_headers_call(None, GetHeadersRequest::new(start_hash, limit, is_ascending)).await?.headers)
     }
@@ -363,6 +415,20 @@ pub trait RpcApi: Sync + Send + AnySync {
     async fn get_utxos_by_addresses(&self, addresses: Vec<RpcAddress>) -> RpcResult<Vec<RpcUtxosByAddressesEntry>> {
         Ok(self.get_utxos_by_addresses_call(None, GetUtxosByAddressesRequest::new(addresses)).await?.entries)
     }
+
+    /// Requests a page of the current UTXOs for the given node addresses, in the order of the addresses
+    /// and then of the UTXO outpoints, optionally skipping UTXOs worth less than `min_amount`.
+    ///
+    /// This call is only available when this node was started with `--utxoindex`.
+    async fn get_utxos_by_addresses_page(
+        &self,
+        addresses: Vec<RpcAddress>,
+        cursor: Option<RpcUtxosByAddressesCursor>,
+        limit: Option<u32>,
+        min_amount: Option<u64>,
+    ) -> RpcResult<GetUtxosByAddressesResponse> {
+        self.get_utxos_by_addresses_call(None, GetUtxosByAddressesRequest::new_paginated(addresses, cursor, limit, min_amount)).await
+    }
     async fn get_utxos_by_addresses_call(
         &self,
         connection: Option<&DynRpcConnection>,
@@ -393,6 +459,16 @@ pub trait RpcApi: Sync + Send + AnySync {
     }
     async fn unban_call(&self, connection: Option<&DynRpcConnection>, request: UnbanRequest) -> RpcResult<UnbanResponse>;
 
+    /// Returns the active bans of peer IP addresses, whether manual or caused by peer misbehavior.
+    async fn get_banned_peers(&self) -> RpcResult<Vec<RpcBannedPeer>> {
+        Ok(self.get_banned_peers_call(None, GetBannedPeersRequest {}).await?.banned_peers)
+    }
+    async fn get_banned_peers_call(
+        &self,
+        connection: Option<&DynRpcConnection>,
+        request: GetBannedPeersRequest,
+    ) -> RpcResult<GetBannedPeersResponse>;
+
     /// Returns info about the node.
     async fn get_info(&self) -> RpcResult<GetInfoResponse> {
         self.get_info_call(None, GetInfoRequest {}).await
@@ -504,6 +580,35 @@ pub trait RpcApi: Sync + Send + AnySync {
         request: GetBlockRewardInfoRequest,
     ) -> RpcResult<GetBlockRewardInfoResponse>;
 
+    /// Requests an accepted or mempool transaction by its id along with its acceptance status.
+    /// Looking up accepted transactions requires the node to run with the --txindex argument.
+    async fn get_transaction(&self, transaction_id: RpcTransactionId, include_transaction: bool) -> RpcResult<GetTransactionResponse> {
+        self.get_transaction_call(None, GetTransactionRequest::new(transaction_id, include_transaction)).await
+    }
+    async fn get_transaction_call(
+        &self,
+        connection: Option<&DynRpcConnection>,
+        request: GetTransactionRequest,
+    ) -> RpcResult<GetTransactionResponse>;
+
+    /// Requests a page of the accepted transactions funding or spending from an address, in ascending DAA score order.
+    /// Requires the node to run with the --addressindex argument.
+    async fn get_address_history(
+        &self,
+        address: RpcAddress,
+        from_daa_score: Option<u64>,
+        to_daa_score: Option<u64>,
+        cursor: Option<RpcAddressHistoryCursor>,
+        limit: Option<u32>,
+    ) -> RpcResult<GetAddressHistoryResponse> {
+        self.get_address_history_call(None, GetAddressHistoryRequest::new(address, from_daa_score, to_daa_score, cursor, limit)).await
+    }
+    async fn get_address_history_call(
+        &self,
+        connection: Option<&DynRpcConnection>,
+        request: GetAddressHistoryRequest,
+    ) -> RpcResult<GetAddressHistoryResponse>;
+
     async fn get_virtual_chain_from_block_v2(
         &self,
         start_hash: RpcHash,
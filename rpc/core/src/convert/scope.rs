@@ -1,9 +1,10 @@
 //! Conversion of Notification Scope related types
 
 use crate::{
-    NotifyBlockAddedRequest, NotifyFinalityConflictRequest, NotifyNewBlockTemplateRequest, NotifyPruningPointUtxoSetOverrideRequest,
-    NotifySinkBlueScoreChangedRequest, NotifyUtxosChangedRequest, NotifyVirtualChainChangedRequest,
-    NotifyVirtualDaaScoreChangedRequest,
+    NotifyBlockAddedRequest, NotifyFinalityConflictRequest, NotifyMempoolTransactionAddedRequest,
+    NotifyMempoolTransactionIncludedRequest, NotifyMempoolTransactionRemovedRequest, NotifyMempoolTransactionReplacedRequest,
+    NotifyNewBlockTemplateRequest, NotifyPruningPointUtxoSetOverrideRequest, NotifySinkBlueScoreChangedRequest,
+    NotifyUtxosChangedRequest, NotifyVirtualChainChangedRequest, NotifyVirtualDaaScoreChangedRequest,
 };
 use kaspa_notify::scope::*;
 
@@ -62,3 +63,15 @@ from!(SinkBlueScoreChanged);
 from!(VirtualDaaScoreChanged);
 from!(PruningPointUtxoSetOverride);
 from!(NewBlockTemplate);
+from!(item: MempoolTransactionAdded, {
+    Self::new(item.addresses.clone())
+});
+from!(item: MempoolTransactionRemoved, {
+    Self::new(item.addresses.clone())
+});
+from!(item: MempoolTransactionReplaced, {
+    Self::new(item.addresses.clone())
+});
+from!(item: MempoolTransactionIncluded, {
+    Self::new(item.addresses.clone())
+});
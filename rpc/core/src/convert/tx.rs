@@ -1,9 +1,10 @@
 //! Conversion of Transaction related types
 
 use crate::{
-    RpcError, RpcOptionalTransaction, RpcOptionalTransactionInput, RpcOptionalTransactionOutput, RpcResult, RpcTransaction,
-    RpcTransactionInput, RpcTransactionOutput,
+    RpcError, RpcInputScriptValidation, RpcOptionalTransaction, RpcOptionalTransactionInput, RpcOptionalTransactionOutput, RpcResult,
+    RpcTransaction, RpcTransactionInput, RpcTransactionOutput,
 };
+use kaspa_consensus_core::api::InputScriptReport;
 use kaspa_consensus_core::mass::{ComputeBudget, SigopCount};
 use kaspa_consensus_core::tx::{ComputeCommit, Transaction, TransactionInput, TransactionOutput};
 
@@ -242,3 +243,13 @@ impl TryFrom<RpcOptionalTransactionOutput> for TransactionOutput {
         ))
     }
 }
+
+impl From<&InputScriptReport> for RpcInputScriptValidation {
+    fn from(item: &InputScriptReport) -> Self {
+        Self {
+            error: item.result.as_ref().err().map(|err| err.to_string()),
+            used_script_units: item.used_script_units.0,
+            used_sig_ops: item.used_sig_ops,
+        }
+    }
+}
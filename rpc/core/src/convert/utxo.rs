@@ -1,9 +1,10 @@
 //! Conversion functions for UTXO related types.
 
 use crate::RpcUtxoEntry;
+use crate::RpcUtxosByAddressesCursor;
 use crate::RpcUtxosByAddressesEntry;
 use kaspa_addresses::Prefix;
-use kaspa_index_core::indexed_utxos::UtxoSetByScriptPublicKey;
+use kaspa_index_core::indexed_utxos::{UtxoCursor, UtxoPage, UtxoSetByScriptPublicKey};
 use kaspa_txscript::extract_script_pub_key_address;
 
 // ----------------------------------------------------------------------------
@@ -31,3 +32,30 @@ pub fn utxo_set_into_rpc(item: &UtxoSetByScriptPublicKey, prefix: Option<Prefix>
         })
         .collect::<Vec<_>>()
 }
+
+/// Converts a [`UtxoPage`] into rpc entries, retaining the order of the page.
+pub fn utxo_page_into_rpc(item: &UtxoPage, prefix: Prefix) -> Vec<RpcUtxosByAddressesEntry> {
+    item.entries
+        .iter()
+        .flat_map(|(script_public_key, utxos)| {
+            let address = extract_script_pub_key_address(script_public_key, prefix).ok();
+            utxos.iter().map(move |(outpoint, entry)| RpcUtxosByAddressesEntry {
+                address: address.clone(),
+                outpoint: (*outpoint).into(),
+                utxo_entry: RpcUtxoEntry::new(
+                    entry.amount,
+                    script_public_key.clone(),
+                    entry.block_daa_score,
+                    entry.is_coinbase,
+                    entry.covenant_id,
+                ),
+            })
+        })
+        .collect::<Vec<_>>()
+}
+
+/// Converts a [`UtxoCursor`] into its rpc counterpart, or `None` if its script public key has no matching address.
+pub fn utxo_cursor_into_rpc(item: &UtxoCursor, prefix: Prefix) -> Option<RpcUtxosByAddressesCursor> {
+    let address = extract_script_pub_key_address(&item.script_public_key, prefix).ok()?;
+    Some(RpcUtxosByAddressesCursor { address, outpoint: item.outpoint.into() })
+}
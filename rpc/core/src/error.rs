@@ -54,6 +54,15 @@ pub enum RpcError {
     #[error("Rejected transaction {0}: {1}")]
     RejectedTransaction(RpcTransactionId, String),
 
+    #[error("Rejected transaction package: {0}")]
+    RejectedTransactionPackage(String),
+
+    #[error("Transaction {0} cannot be validated: {1}")]
+    UnvalidatableTransaction(RpcTransactionId, String),
+
+    #[error("Provided {0} UTXO entries for a transaction with {1} inputs.")]
+    UtxoEntriesCountMismatch(usize, usize),
+
     #[error("Block {0} is invalid. No verbose data can be built.")]
     InvalidBlock(RpcHash),
 
@@ -66,6 +75,12 @@ pub enum RpcError {
     #[error("Method unavailable. Run the node with the --utxoindex argument.")]
     NoUtxoIndex,
 
+    #[error("Method unavailable. Run the node with the --txindex argument.")]
+    NoTxIndex,
+
+    #[error("Method unavailable. Run the node with the --addressindex argument.")]
+    NoAddressIndex,
+
     #[error("Method unavailable. No connection manager is currently available.")]
     NoConnectionManager,
 
@@ -84,6 +99,12 @@ pub enum RpcError {
     #[error("IP {0} is not registered as banned.")]
     IpIsNotBanned(IpAddress),
 
+    #[error("Block {0} is not a chain block within the retention period.")]
+    NotChainBlock(RpcHash),
+
+    #[error("Requested {0} lane keys, expected between 1 and {1}.")]
+    InvalidLaneKeyCount(usize, usize),
+
     #[error("Block {0} doesn't have any merger block.")]
     MergerNotFound(RpcHash),
 
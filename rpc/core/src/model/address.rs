@@ -1,4 +1,4 @@
-use crate::{RpcTransactionOutpoint, RpcUtxoEntry};
+use crate::{RpcHash, RpcTransactionId, RpcTransactionOutpoint, RpcUtxoEntry};
 use serde::{Deserialize, Serialize};
 use workflow_serializer::prelude::*;
 
@@ -32,6 +32,32 @@ impl Deserializer for RpcUtxosByAddressesEntry {
     }
 }
 
+/// Represents a position within the UTXOs of a set of addresses, used for paginating the `GetUtxosByAddresses` RPC.
+/// UTXOs are ordered by address, following the order of the request, and then by outpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcUtxosByAddressesCursor {
+    pub address: RpcAddress,
+    pub outpoint: RpcTransactionOutpoint,
+}
+
+impl Serializer for RpcUtxosByAddressesCursor {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u8, &1, writer)?; // version
+        store!(RpcAddress, &self.address, writer)?;
+        serialize!(RpcTransactionOutpoint, &self.outpoint, writer)
+    }
+}
+
+impl Deserializer for RpcUtxosByAddressesCursor {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version: u8 = load!(u8, reader)?;
+        let address = load!(RpcAddress, reader)?;
+        let outpoint = deserialize!(RpcTransactionOutpoint, reader)?;
+        Ok(Self { address, outpoint })
+    }
+}
+
 /// Represents a balance of an address returned by the `GetBalancesByAddresses` RPC.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -58,3 +84,71 @@ impl Deserializer for RpcBalancesByAddressesEntry {
         Ok(Self { address, balance })
     }
 }
+
+/// Represents a position within the history of an address, used for paginating the `GetAddressHistory` RPC.
+/// History entries are ordered by accepting DAA score and then by transaction id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAddressHistoryCursor {
+    pub accepting_daa_score: u64,
+    pub transaction_id: RpcTransactionId,
+}
+
+impl Serializer for RpcAddressHistoryCursor {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u8, &1, writer)?; // version
+        store!(u64, &self.accepting_daa_score, writer)?;
+        store!(RpcTransactionId, &self.transaction_id, writer)
+    }
+}
+
+impl Deserializer for RpcAddressHistoryCursor {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version: u8 = load!(u8, reader)?;
+        let accepting_daa_score = load!(u64, reader)?;
+        let transaction_id = load!(RpcTransactionId, reader)?;
+        Ok(Self { accepting_daa_score, transaction_id })
+    }
+}
+
+/// Represents an accepted transaction funding or spending from an address, returned by the `GetAddressHistory` RPC.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAddressHistoryEntry {
+    pub transaction_id: RpcTransactionId,
+    pub accepting_block_hash: RpcHash,
+    pub accepting_daa_score: u64,
+    /// Total amount of the transaction outputs paying to the address
+    pub received_amount: u64,
+    /// Total amount of the transaction inputs spending from the address
+    pub spent_amount: u64,
+}
+
+impl RpcAddressHistoryEntry {
+    pub fn cursor(&self) -> RpcAddressHistoryCursor {
+        RpcAddressHistoryCursor { accepting_daa_score: self.accepting_daa_score, transaction_id: self.transaction_id }
+    }
+}
+
+impl Serializer for RpcAddressHistoryEntry {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u8, &1, writer)?; // version
+        store!(RpcTransactionId, &self.transaction_id, writer)?;
+        store!(RpcHash, &self.accepting_block_hash, writer)?;
+        store!(u64, &self.accepting_daa_score, writer)?;
+        store!(u64, &self.received_amount, writer)?;
+        store!(u64, &self.spent_amount, writer)
+    }
+}
+
+impl Deserializer for RpcAddressHistoryEntry {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version: u8 = load!(u8, reader)?;
+        let transaction_id = load!(RpcTransactionId, reader)?;
+        let accepting_block_hash = load!(RpcHash, reader)?;
+        let accepting_daa_score = load!(u64, reader)?;
+        let received_amount = load!(u64, reader)?;
+        let spent_amount = load!(u64, reader)?;
+        Ok(Self { transaction_id, accepting_block_hash, accepting_daa_score, received_amount, spent_amount })
+    }
+}
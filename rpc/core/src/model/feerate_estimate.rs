@@ -63,6 +63,20 @@ impl Deserializer for RpcFeeEstimate {
     }
 }
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInclusionFeerateEstimate {
+    /// The inclusion time target in seconds
+    pub target_seconds: f64,
+
+    /// The probability, in the `[0, 1]` range, of an inclusion within `target_seconds`
+    pub confidence: f64,
+
+    /// The fee/mass ratio observed to reach the target at the requested confidence, or -1 if
+    /// the node did not observe enough block inclusions yet
+    pub feerate: f64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcFeeEstimateVerboseExperimentalData {
@@ -73,30 +87,39 @@ pub struct RpcFeeEstimateVerboseExperimentalData {
     pub next_block_template_feerate_min: f64,
     pub next_block_template_feerate_median: f64,
     pub next_block_template_feerate_max: f64,
+
+    /// Number of recent block inclusions of mempool transactions backing `inclusion_estimates`
+    pub inclusion_observation_count: u64,
+    /// Feerates needed for inclusion within given times at given confidences, learned from recent blocks
+    pub inclusion_estimates: Vec<RpcInclusionFeerateEstimate>,
 }
 
 impl Serializer for RpcFeeEstimateVerboseExperimentalData {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u16, &1, writer)?;
+        store!(u16, &2, writer)?;
         store!(u64, &self.mempool_ready_transactions_count, writer)?;
         store!(u64, &self.mempool_ready_transactions_total_mass, writer)?;
         store!(u64, &self.network_mass_per_second, writer)?;
         store!(f64, &self.next_block_template_feerate_min, writer)?;
         store!(f64, &self.next_block_template_feerate_median, writer)?;
         store!(f64, &self.next_block_template_feerate_max, writer)?;
+        store!(u64, &self.inclusion_observation_count, writer)?;
+        store!(Vec<RpcInclusionFeerateEstimate>, &self.inclusion_estimates, writer)?;
         Ok(())
     }
 }
 
 impl Deserializer for RpcFeeEstimateVerboseExperimentalData {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u16, reader)?;
+        let version = load!(u16, reader)?;
         let mempool_ready_transactions_count = load!(u64, reader)?;
         let mempool_ready_transactions_total_mass = load!(u64, reader)?;
         let network_mass_per_second = load!(u64, reader)?;
         let next_block_template_feerate_min = load!(f64, reader)?;
         let next_block_template_feerate_median = load!(f64, reader)?;
         let next_block_template_feerate_max = load!(f64, reader)?;
+        let (inclusion_observation_count, inclusion_estimates) =
+            if version > 1 { (load!(u64, reader)?, load!(Vec<RpcInclusionFeerateEstimate>, reader)?) } else { (0, vec![]) };
         Ok(Self {
             mempool_ready_transactions_count,
             mempool_ready_transactions_total_mass,
@@ -104,6 +127,8 @@ impl Deserializer for RpcFeeEstimateVerboseExperimentalData {
             next_block_template_feerate_min,
             next_block_template_feerate_median,
             next_block_template_feerate_max,
+            inclusion_observation_count,
+            inclusion_estimates,
         })
     }
 }
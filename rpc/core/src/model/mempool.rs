@@ -1,5 +1,6 @@
 use super::RpcAddress;
 use super::RpcTransaction;
+use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 use workflow_serializer::prelude::*;
 
@@ -63,6 +64,69 @@ impl Deserializer for RpcMempoolEntryByAddress {
     }
 }
 
+/// Mempool transaction reported by mempool notifications, along with the addresses it involves
+///
+/// The addresses are those of the transaction outputs and of the UTXO entries spent by its inputs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMempoolTransactionEntry {
+    pub fee: u64,
+    pub transaction: RpcTransaction,
+    pub addresses: Vec<RpcAddress>,
+}
+
+impl RpcMempoolTransactionEntry {
+    pub fn new(fee: u64, transaction: RpcTransaction, addresses: Vec<RpcAddress>) -> Self {
+        Self { fee, transaction, addresses }
+    }
+}
+
+impl Serializer for RpcMempoolTransactionEntry {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(u64, &self.fee, writer)?;
+        serialize!(RpcTransaction, &self.transaction, writer)?;
+        store!(Vec<RpcAddress>, &self.addresses, writer)
+    }
+}
+
+impl Deserializer for RpcMempoolTransactionEntry {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let fee = load!(u64, reader)?;
+        let transaction = deserialize!(RpcTransaction, reader)?;
+        let addresses = load!(Vec<RpcAddress>, reader)?;
+        Ok(Self { fee, transaction, addresses })
+    }
+}
+
+/// Reason of the removal of transactions from the mempool
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "lowercase")]
+#[borsh(use_discriminant = true)]
+#[repr(i32)]
+pub enum RpcMempoolRemovalReason {
+    /// Evicted by a double spend, for making room or because found invalid
+    Evicted = 0,
+    /// Expired without being included in a block
+    Expired = 1,
+}
+
+impl From<RpcMempoolRemovalReason> for i32 {
+    fn from(value: RpcMempoolRemovalReason) -> Self {
+        value as i32
+    }
+}
+
+impl From<i32> for RpcMempoolRemovalReason {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::Expired,
+            _ => Self::Evicted,
+        }
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature = "wasm32-sdk")] {
         use wasm_bindgen::prelude::*;
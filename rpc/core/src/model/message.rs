@@ -603,16 +603,32 @@ impl GetConnectedPeerInfoResponse {
 
 impl Serializer for GetConnectedPeerInfoResponse {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u16, &1, writer)?;
+        store!(u16, &3, writer)?;
         store!(Vec<RpcPeerInfo>, &self.peer_info, writer)?;
+        for info in self.peer_info.iter() {
+            store!(u32, &info.misbehavior_score, writer)?;
+            store!(Option<String>, &info.misbehavior_reason, writer)?;
+            store!(bool, &info.is_encrypted, writer)?;
+            store!(Option<RpcHash>, &info.transport_session_id, writer)?;
+        }
         Ok(())
     }
 }
 
 impl Deserializer for GetConnectedPeerInfoResponse {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u16, reader)?;
-        let peer_info = load!(Vec<RpcPeerInfo>, reader)?;
+        let version = load!(u16, reader)?;
+        let mut peer_info = load!(Vec<RpcPeerInfo>, reader)?;
+        if version > 1 {
+            for info in peer_info.iter_mut() {
+                info.misbehavior_score = load!(u32, reader)?;
+                info.misbehavior_reason = load!(Option<String>, reader)?;
+                if version > 2 {
+                    info.is_encrypted = load!(bool, reader)?;
+                    info.transport_session_id = load!(Option<RpcHash>, reader)?;
+                }
+            }
+        }
         Ok(Self { peer_info })
     }
 }
@@ -794,22 +810,207 @@ impl Deserializer for SubmitTransactionReplacementResponse {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitTransactionPackageRequest {
+    pub transactions: Vec<RpcTransaction>,
+}
+
+impl SubmitTransactionPackageRequest {
+    pub fn new(transactions: Vec<RpcTransaction>) -> Self {
+        Self { transactions }
+    }
+}
+
+impl Serializer for SubmitTransactionPackageRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        serialize!(Vec<RpcTransaction>, &self.transactions, writer)?;
+
+        Ok(())
+    }
+}
+
+impl Deserializer for SubmitTransactionPackageRequest {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let transactions = deserialize!(Vec<RpcTransaction>, reader)?;
+
+        Ok(Self { transactions })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitTransactionPackageResponse {
+    /// Ids of the package transactions added to the mempool, in topological order
+    pub transaction_ids: Vec<RpcTransactionId>,
+}
+
+impl SubmitTransactionPackageResponse {
+    pub fn new(transaction_ids: Vec<RpcTransactionId>) -> Self {
+        Self { transaction_ids }
+    }
+}
+
+impl Serializer for SubmitTransactionPackageResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(Vec<RpcTransactionId>, &self.transaction_ids, writer)?;
+
+        Ok(())
+    }
+}
+
+impl Deserializer for SubmitTransactionPackageResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let transaction_ids = load!(Vec<RpcTransactionId>, reader)?;
+
+        Ok(Self { transaction_ids })
+    }
+}
+
+/// Validates a transaction and executes all of its input scripts against the node's
+/// virtual UTXO set, without inserting it into the mempool.
+///
+/// `utxo_entries` is either empty, in which case all entries are read from the UTXO set,
+/// or holds one entry per input, used in place of the UTXO set.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateTransactionScriptsRequest {
+    pub transaction: RpcTransaction,
+    #[serde(default)]
+    pub utxo_entries: Vec<RpcUtxoEntry>,
+}
+
+impl ValidateTransactionScriptsRequest {
+    pub fn new(transaction: RpcTransaction, utxo_entries: Vec<RpcUtxoEntry>) -> Self {
+        Self { transaction, utxo_entries }
+    }
+}
+
+impl Serializer for ValidateTransactionScriptsRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        serialize!(RpcTransaction, &self.transaction, writer)?;
+        serialize!(Vec<RpcUtxoEntry>, &self.utxo_entries, writer)?;
+
+        Ok(())
+    }
+}
+
+impl Deserializer for ValidateTransactionScriptsRequest {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let transaction = deserialize!(RpcTransaction, reader)?;
+        let utxo_entries = deserialize!(Vec<RpcUtxoEntry>, reader)?;
+
+        Ok(Self { transaction, utxo_entries })
+    }
+}
+
+/// Outcome of executing the scripts of a single input; `error` is `None` if they succeeded.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcInputScriptValidation {
+    pub error: Option<String>,
+    pub used_script_units: u64,
+    pub used_sig_ops: u16,
+}
+
+impl Serializer for RpcInputScriptValidation {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u8, &1, writer)?;
+        store!(Option<String>, &self.error, writer)?;
+        store!(u64, &self.used_script_units, writer)?;
+        store!(u16, &self.used_sig_ops, writer)?;
+
+        Ok(())
+    }
+}
+
+impl Deserializer for RpcInputScriptValidation {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u8, reader)?;
+        let error = load!(Option<String>, reader)?;
+        let used_script_units = load!(u64, reader)?;
+        let used_sig_ops = load!(u16, reader)?;
+
+        Ok(Self { error, used_script_units, used_sig_ops })
+    }
+}
+
+/// `error` holds the first violated non-script rule (coinbase maturity, input and output amounts, sequence locks),
+/// in which case `fee` is `None`. Input scripts are executed regardless, `inputs` holding one outcome per input.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateTransactionScriptsResponse {
+    pub transaction_id: RpcTransactionId,
+    pub fee: Option<u64>,
+    pub error: Option<String>,
+    pub compute_mass: u64,
+    pub transient_mass: u64,
+    pub storage_mass: u64,
+    pub inputs: Vec<RpcInputScriptValidation>,
+}
+
+impl ValidateTransactionScriptsResponse {
+    /// Whether the transaction passed all UTXO-context and script validations
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none() && self.inputs.iter().all(|input| input.error.is_none())
+    }
+}
+
+impl Serializer for ValidateTransactionScriptsResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(RpcTransactionId, &self.transaction_id, writer)?;
+        store!(Option<u64>, &self.fee, writer)?;
+        store!(Option<String>, &self.error, writer)?;
+        store!(u64, &self.compute_mass, writer)?;
+        store!(u64, &self.transient_mass, writer)?;
+        store!(u64, &self.storage_mass, writer)?;
+        serialize!(Vec<RpcInputScriptValidation>, &self.inputs, writer)?;
+
+        Ok(())
+    }
+}
+
+impl Deserializer for ValidateTransactionScriptsResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let transaction_id = load!(RpcTransactionId, reader)?;
+        let fee = load!(Option<u64>, reader)?;
+        let error = load!(Option<String>, reader)?;
+        let compute_mass = load!(u64, reader)?;
+        let transient_mass = load!(u64, reader)?;
+        let storage_mass = load!(u64, reader)?;
+        let inputs = deserialize!(Vec<RpcInputScriptValidation>, reader)?;
+
+        Ok(Self { transaction_id, fee, error, compute_mass, transient_mass, storage_mass, inputs })
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSubnetworkRequest {
     pub subnetwork_id: RpcSubnetworkId,
+    /// Chain block to report the subnetwork lane state at. Defaults to the sink.
+    pub block_hash: Option<RpcHash>,
 }
 
 impl GetSubnetworkRequest {
-    pub fn new(subnetwork_id: RpcSubnetworkId) -> Self {
-        Self { subnetwork_id }
+    pub fn new(subnetwork_id: RpcSubnetworkId, block_hash: Option<RpcHash>) -> Self {
+        Self { subnetwork_id, block_hash }
     }
 }
 
 impl Serializer for GetSubnetworkRequest {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u16, &1, writer)?;
+        store!(u16, &2, writer)?;
         store!(RpcSubnetworkId, &self.subnetwork_id, writer)?;
+        store!(Option<RpcHash>, &self.block_hash, writer)?;
 
         Ok(())
     }
@@ -817,29 +1018,41 @@ impl Serializer for GetSubnetworkRequest {
 
 impl Deserializer for GetSubnetworkRequest {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u16, reader)?;
+        let version = load!(u16, reader)?;
         let subnetwork_id = load!(RpcSubnetworkId, reader)?;
+        let block_hash = if version > 1 { load!(Option<RpcHash>, reader)? } else { None };
 
-        Ok(Self { subnetwork_id })
+        Ok(Self { subnetwork_id, block_hash })
     }
 }
 
+/// State of the sequencing-commitment lane of a subnetwork as of `block_hash`.
+///
+/// `lane` holds the lane tip and the blue score at which the lane was last active, or `None`
+/// if the lane was never touched (or its versions were already pruned). `is_active` is set iff
+/// the lane was touched within the inactivity threshold of `block_hash`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSubnetworkResponse {
     pub gas_limit: u64,
+    pub block_hash: RpcHash,
+    pub lane: Option<RpcLaneEntry>,
+    pub is_active: bool,
 }
 
 impl GetSubnetworkResponse {
-    pub fn new(gas_limit: u64) -> Self {
-        Self { gas_limit }
+    pub fn new(gas_limit: u64, block_hash: RpcHash, lane: Option<RpcLaneEntry>, is_active: bool) -> Self {
+        Self { gas_limit, block_hash, lane, is_active }
     }
 }
 
 impl Serializer for GetSubnetworkResponse {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u16, &1, writer)?;
+        store!(u16, &2, writer)?;
         store!(u64, &self.gas_limit, writer)?;
+        store!(RpcHash, &self.block_hash, writer)?;
+        serialize!(Option<RpcLaneEntry>, &self.lane, writer)?;
+        store!(bool, &self.is_active, writer)?;
 
         Ok(())
     }
@@ -847,10 +1060,15 @@ impl Serializer for GetSubnetworkResponse {
 
 impl Deserializer for GetSubnetworkResponse {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u16, reader)?;
+        let version = load!(u16, reader)?;
         let gas_limit = load!(u64, reader)?;
+        let (block_hash, lane, is_active) = if version > 1 {
+            (load!(RpcHash, reader)?, deserialize!(Option<RpcLaneEntry>, reader)?, load!(bool, reader)?)
+        } else {
+            (RpcHash::default(), None, false)
+        };
 
-        Ok(Self { gas_limit })
+        Ok(Self { gas_limit, block_hash, lane, is_active })
     }
 }
 
@@ -1450,18 +1668,39 @@ impl Deserializer for GetSinkBlueScoreResponse {
 #[serde(rename_all = "camelCase")]
 pub struct GetUtxosByAddressesRequest {
     pub addresses: Vec<RpcAddress>,
+    /// Resume after this position, as returned by the `next_cursor` of a previous response
+    #[serde(default)]
+    pub cursor: Option<RpcUtxosByAddressesCursor>,
+    /// Maximum number of entries to return, all entries being returned if absent
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Only return entries with an amount of at least this value (in sompi)
+    #[serde(default)]
+    pub min_amount: Option<u64>,
 }
 
 impl GetUtxosByAddressesRequest {
     pub fn new(addresses: Vec<RpcAddress>) -> Self {
-        Self { addresses }
+        Self { addresses, cursor: None, limit: None, min_amount: None }
+    }
+
+    pub fn new_paginated(
+        addresses: Vec<RpcAddress>,
+        cursor: Option<RpcUtxosByAddressesCursor>,
+        limit: Option<u32>,
+        min_amount: Option<u64>,
+    ) -> Self {
+        Self { addresses, cursor, limit, min_amount }
     }
 }
 
 impl Serializer for GetUtxosByAddressesRequest {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u16, &1, writer)?;
+        store!(u16, &2, writer)?;
         store!(Vec<RpcAddress>, &self.addresses, writer)?;
+        serialize!(Option<RpcUtxosByAddressesCursor>, &self.cursor, writer)?;
+        store!(Option<u32>, &self.limit, writer)?;
+        store!(Option<u64>, &self.min_amount, writer)?;
 
         Ok(())
     }
@@ -1469,10 +1708,15 @@ impl Serializer for GetUtxosByAddressesRequest {
 
 impl Deserializer for GetUtxosByAddressesRequest {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u16, reader)?;
+        let version = load!(u16, reader)?;
         let addresses = load!(Vec<RpcAddress>, reader)?;
+        let (cursor, limit, min_amount) = if version > 1 {
+            (deserialize!(Option<RpcUtxosByAddressesCursor>, reader)?, load!(Option<u32>, reader)?, load!(Option<u64>, reader)?)
+        } else {
+            (None, None, None)
+        };
 
-        Ok(Self { addresses })
+        Ok(Self { addresses, cursor, limit, min_amount })
     }
 }
 
@@ -1480,18 +1724,22 @@ impl Deserializer for GetUtxosByAddressesRequest {
 #[serde(rename_all = "camelCase")]
 pub struct GetUtxosByAddressesResponse {
     pub entries: Vec<RpcUtxosByAddressesEntry>,
+    /// Populated when more entries are available
+    #[serde(default)]
+    pub next_cursor: Option<RpcUtxosByAddressesCursor>,
 }
 
 impl GetUtxosByAddressesResponse {
-    pub fn new(entries: Vec<RpcUtxosByAddressesEntry>) -> Self {
-        Self { entries }
+    pub fn new(entries: Vec<RpcUtxosByAddressesEntry>, next_cursor: Option<RpcUtxosByAddressesCursor>) -> Self {
+        Self { entries, next_cursor }
     }
 }
 
 impl Serializer for GetUtxosByAddressesResponse {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u16, &1, writer)?;
+        store!(u16, &2, writer)?;
         serialize!(Vec<RpcUtxosByAddressesEntry>, &self.entries, writer)?;
+        serialize!(Option<RpcUtxosByAddressesCursor>, &self.next_cursor, writer)?;
 
         Ok(())
     }
@@ -1499,10 +1747,11 @@ impl Serializer for GetUtxosByAddressesResponse {
 
 impl Deserializer for GetUtxosByAddressesResponse {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u16, reader)?;
+        let version = load!(u16, reader)?;
         let entries = deserialize!(Vec<RpcUtxosByAddressesEntry>, reader)?;
+        let next_cursor = if version > 1 { deserialize!(Option<RpcUtxosByAddressesCursor>, reader)? } else { None };
 
-        Ok(Self { entries })
+        Ok(Self { entries, next_cursor })
     }
 }
 
@@ -1602,6 +1851,52 @@ impl Deserializer for UnbanResponse {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBannedPeersRequest {}
+
+impl Serializer for GetBannedPeersRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for GetBannedPeersRequest {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        Ok(Self {})
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBannedPeersResponse {
+    pub banned_peers: Vec<RpcBannedPeer>,
+}
+
+impl GetBannedPeersResponse {
+    pub fn new(banned_peers: Vec<RpcBannedPeer>) -> Self {
+        Self { banned_peers }
+    }
+}
+
+impl Serializer for GetBannedPeersResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(Vec<RpcBannedPeer>, &self.banned_peers, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for GetBannedPeersResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let banned_peers = load!(Vec<RpcBannedPeer>, reader)?;
+        Ok(Self { banned_peers })
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EstimateNetworkHashesPerSecondRequest {
@@ -2110,11 +2405,17 @@ pub struct BandwidthMetrics {
     pub p2p_bytes_rx: u64,
     pub grpc_bytes_tx: u64,
     pub grpc_bytes_rx: u64,
+    /// Number of relay blocks received as compact blocks
+    pub p2p_compact_blocks_rx: u64,
+    /// Number of compact block transactions which were missing from the mempool and requested from peers
+    pub p2p_compact_block_txs_requested: u64,
+    /// P2P bytes saved by receiving compact blocks rather than full blocks
+    pub p2p_compact_block_bytes_saved: u64,
 }
 
 impl Serializer for BandwidthMetrics {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u16, &1, writer)?;
+        store!(u16, &2, writer)?;
         store!(u64, &self.borsh_bytes_tx, writer)?;
         store!(u64, &self.borsh_bytes_rx, writer)?;
         store!(u64, &self.json_bytes_tx, writer)?;
@@ -2123,6 +2424,9 @@ impl Serializer for BandwidthMetrics {
         store!(u64, &self.p2p_bytes_rx, writer)?;
         store!(u64, &self.grpc_bytes_tx, writer)?;
         store!(u64, &self.grpc_bytes_rx, writer)?;
+        store!(u64, &self.p2p_compact_blocks_rx, writer)?;
+        store!(u64, &self.p2p_compact_block_txs_requested, writer)?;
+        store!(u64, &self.p2p_compact_block_bytes_saved, writer)?;
 
         Ok(())
     }
@@ -2130,7 +2434,7 @@ impl Serializer for BandwidthMetrics {
 
 impl Deserializer for BandwidthMetrics {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u16, reader)?;
+        let version = load!(u16, reader)?;
         let borsh_bytes_tx = load!(u64, reader)?;
         let borsh_bytes_rx = load!(u64, reader)?;
         let json_bytes_tx = load!(u64, reader)?;
@@ -2139,6 +2443,8 @@ impl Deserializer for BandwidthMetrics {
         let p2p_bytes_rx = load!(u64, reader)?;
         let grpc_bytes_tx = load!(u64, reader)?;
         let grpc_bytes_rx = load!(u64, reader)?;
+        let (p2p_compact_blocks_rx, p2p_compact_block_txs_requested, p2p_compact_block_bytes_saved) =
+            if version > 1 { (load!(u64, reader)?, load!(u64, reader)?, load!(u64, reader)?) } else { (0, 0, 0) };
 
         Ok(Self {
             borsh_bytes_tx,
@@ -2149,6 +2455,9 @@ impl Deserializer for BandwidthMetrics {
             p2p_bytes_rx,
             grpc_bytes_tx,
             grpc_bytes_rx,
+            p2p_compact_blocks_rx,
+            p2p_compact_block_txs_requested,
+            p2p_compact_block_bytes_saved,
         })
     }
 }
@@ -2698,7 +3007,7 @@ impl From<i32> for RpcBlockColor {
 
 impl Serializer for RpcBlockColor {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u8, &1, writer)?;
+        store!(u16, &1, writer)?;
         store!(i32, &i32::from(*self), writer)?;
         Ok(())
     }
@@ -2706,7 +3015,7 @@ impl Serializer for RpcBlockColor {
 
 impl Deserializer for RpcBlockColor {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u8, reader)?;
+        let _version = load!(u16, reader)?;
         let value = load!(i32, reader)?;
         Ok(RpcBlockColor::from(value))
     }
@@ -2791,109 +3100,322 @@ impl Deserializer for GetBlockRewardInfoResponse {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GetUtxoReturnAddressRequest {
-    pub txid: RpcHash,
-    pub accepting_block_daa_score: u64,
+pub struct GetTransactionRequest {
+    pub transaction_id: RpcTransactionId,
+    pub include_transaction: bool,
 }
 
-impl GetUtxoReturnAddressRequest {
-    pub fn new(txid: RpcHash, accepting_block_daa_score: u64) -> Self {
-        Self { txid, accepting_block_daa_score }
+impl GetTransactionRequest {
+    pub fn new(transaction_id: RpcTransactionId, include_transaction: bool) -> Self {
+        Self { transaction_id, include_transaction }
     }
 }
 
-impl Serializer for GetUtxoReturnAddressRequest {
+impl Serializer for GetTransactionRequest {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
         store!(u16, &1, writer)?;
-        store!(RpcHash, &self.txid, writer)?;
-        store!(u64, &self.accepting_block_daa_score, writer)?;
-
+        store!(RpcTransactionId, &self.transaction_id, writer)?;
+        store!(bool, &self.include_transaction, writer)?;
         Ok(())
     }
 }
 
-impl Deserializer for GetUtxoReturnAddressRequest {
+impl Deserializer for GetTransactionRequest {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
         let _version = load!(u16, reader)?;
-        let txid = load!(RpcHash, reader)?;
-        let accepting_block_daa_score = load!(u64, reader)?;
-
-        Ok(Self { txid, accepting_block_daa_score })
+        let transaction_id = load!(RpcTransactionId, reader)?;
+        let include_transaction = load!(bool, reader)?;
+        Ok(Self { transaction_id, include_transaction })
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GetUtxoReturnAddressResponse {
-    pub return_address: RpcAddress,
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "lowercase")]
+#[borsh(use_discriminant = true)]
+#[repr(i32)]
+pub enum RpcTransactionStatus {
+    Unknown = 0,
+    /// The transaction was accepted by a chain block
+    Accepted = 1,
+    /// The transaction resides in the mempool
+    Pending = 2,
 }
 
-impl GetUtxoReturnAddressResponse {
-    pub fn new(return_address: RpcAddress) -> Self {
-        Self { return_address }
+impl From<RpcTransactionStatus> for i32 {
+    fn from(value: RpcTransactionStatus) -> Self {
+        value as i32
     }
 }
 
-impl Serializer for GetUtxoReturnAddressResponse {
+impl From<i32> for RpcTransactionStatus {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::Accepted,
+            2 => Self::Pending,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl Serializer for RpcTransactionStatus {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
         store!(u16, &1, writer)?;
-        store!(RpcAddress, &self.return_address, writer)?;
-
+        store!(i32, &i32::from(*self), writer)?;
         Ok(())
     }
 }
 
-impl Deserializer for GetUtxoReturnAddressResponse {
+impl Deserializer for RpcTransactionStatus {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
         let _version = load!(u16, reader)?;
-        let return_address = load!(RpcAddress, reader)?;
-
-        Ok(Self { return_address })
+        let value = load!(i32, reader)?;
+        Ok(RpcTransactionStatus::from(value))
     }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GetVirtualChainFromBlockV2Request {
-    pub start_hash: RpcHash,
-    pub data_verbosity_level: Option<RpcDataVerbosityLevel>,
-    pub min_confirmation_count: Option<u64>,
+pub struct GetTransactionResponse {
+    /// populated when the request includes the transaction
+    pub transaction: Option<RpcTransaction>,
+    pub status: RpcTransactionStatus,
+    /// guaranteed to be populated when status == Accepted
+    pub including_block_hash: Option<RpcHash>,
+    /// guaranteed to be populated when status == Accepted
+    pub accepting_block_hash: Option<RpcHash>,
+    /// guaranteed to be populated when status == Accepted
+    pub accepting_block_daa_score: Option<u64>,
+    /// guaranteed to be populated when status == Accepted
+    pub confirmation_count: Option<u64>,
 }
 
-impl GetVirtualChainFromBlockV2Request {
-    pub fn new(start_hash: RpcHash, data_verbosity_level: Option<RpcDataVerbosityLevel>, min_confirmation_count: Option<u64>) -> Self {
-        Self { start_hash, data_verbosity_level, min_confirmation_count }
+impl GetTransactionResponse {
+    pub fn new(
+        transaction: Option<RpcTransaction>,
+        status: RpcTransactionStatus,
+        including_block_hash: Option<RpcHash>,
+        accepting_block_hash: Option<RpcHash>,
+        accepting_block_daa_score: Option<u64>,
+        confirmation_count: Option<u64>,
+    ) -> Self {
+        Self { transaction, status, including_block_hash, accepting_block_hash, accepting_block_daa_score, confirmation_count }
     }
 }
 
-impl Serializer for GetVirtualChainFromBlockV2Request {
+impl Serializer for GetTransactionResponse {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
         store!(u16, &1, writer)?;
-        store!(RpcHash, &self.start_hash, writer)?;
-        serialize!(Option<RpcDataVerbosityLevel>, &self.data_verbosity_level, writer)?;
-        store!(Option<u64>, &self.min_confirmation_count, writer)?;
-
+        serialize!(Option<RpcTransaction>, &self.transaction, writer)?;
+        store!(RpcTransactionStatus, &self.status, writer)?;
+        store!(Option<RpcHash>, &self.including_block_hash, writer)?;
+        store!(Option<RpcHash>, &self.accepting_block_hash, writer)?;
+        store!(Option<u64>, &self.accepting_block_daa_score, writer)?;
+        store!(Option<u64>, &self.confirmation_count, writer)?;
         Ok(())
     }
 }
 
-impl Deserializer for GetVirtualChainFromBlockV2Request {
+impl Deserializer for GetTransactionResponse {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
         let _version = load!(u16, reader)?;
-        let start_hash = load!(RpcHash, reader)?;
-        let data_verbosity_level = deserialize!(Option<RpcDataVerbosityLevel>, reader)?;
-        let min_confirmation_count = load!(Option<u64>, reader)?;
-
-        Ok(Self { start_hash, data_verbosity_level, min_confirmation_count })
+        let transaction = deserialize!(Option<RpcTransaction>, reader)?;
+        let status = load!(RpcTransactionStatus, reader)?;
+        let including_block_hash = load!(Option<RpcHash>, reader)?;
+        let accepting_block_hash = load!(Option<RpcHash>, reader)?;
+        let accepting_block_daa_score = load!(Option<u64>, reader)?;
+        let confirmation_count = load!(Option<u64>, reader)?;
+        Ok(Self { transaction, status, including_block_hash, accepting_block_hash, accepting_block_daa_score, confirmation_count })
     }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GetVirtualChainFromBlockV2Response {
-    /// always present, no matter the verbosity level
-    pub removed_chain_block_hashes: Arc<Vec<RpcHash>>,
-    /// always present, no matter the verbosity level
+pub struct GetAddressHistoryRequest {
+    pub address: RpcAddress,
+    /// Lowest accepting DAA score (inclusive) of the returned entries
+    pub from_daa_score: Option<u64>,
+    /// Highest accepting DAA score (inclusive) of the returned entries
+    pub to_daa_score: Option<u64>,
+    /// Resume after this position, as returned by the `next_cursor` of a previous response
+    pub cursor: Option<RpcAddressHistoryCursor>,
+    /// Maximum number of entries to return, capped by the node
+    pub limit: Option<u32>,
+}
+
+impl GetAddressHistoryRequest {
+    pub fn new(
+        address: RpcAddress,
+        from_daa_score: Option<u64>,
+        to_daa_score: Option<u64>,
+        cursor: Option<RpcAddressHistoryCursor>,
+        limit: Option<u32>,
+    ) -> Self {
+        Self { address, from_daa_score, to_daa_score, cursor, limit }
+    }
+}
+
+impl Serializer for GetAddressHistoryRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(RpcAddress, &self.address, writer)?;
+        store!(Option<u64>, &self.from_daa_score, writer)?;
+        store!(Option<u64>, &self.to_daa_score, writer)?;
+        serialize!(Option<RpcAddressHistoryCursor>, &self.cursor, writer)?;
+        store!(Option<u32>, &self.limit, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for GetAddressHistoryRequest {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let address = load!(RpcAddress, reader)?;
+        let from_daa_score = load!(Option<u64>, reader)?;
+        let to_daa_score = load!(Option<u64>, reader)?;
+        let cursor = deserialize!(Option<RpcAddressHistoryCursor>, reader)?;
+        let limit = load!(Option<u32>, reader)?;
+        Ok(Self { address, from_daa_score, to_daa_score, cursor, limit })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAddressHistoryResponse {
+    /// History entries in ascending accepting DAA score order
+    pub entries: Vec<RpcAddressHistoryEntry>,
+    /// Populated when more entries are available; pass it as the `cursor` of the next request
+    pub next_cursor: Option<RpcAddressHistoryCursor>,
+}
+
+impl GetAddressHistoryResponse {
+    pub fn new(entries: Vec<RpcAddressHistoryEntry>, next_cursor: Option<RpcAddressHistoryCursor>) -> Self {
+        Self { entries, next_cursor }
+    }
+}
+
+impl Serializer for GetAddressHistoryResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        serialize!(Vec<RpcAddressHistoryEntry>, &self.entries, writer)?;
+        serialize!(Option<RpcAddressHistoryCursor>, &self.next_cursor, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for GetAddressHistoryResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let entries = deserialize!(Vec<RpcAddressHistoryEntry>, reader)?;
+        let next_cursor = deserialize!(Option<RpcAddressHistoryCursor>, reader)?;
+        Ok(Self { entries, next_cursor })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxoReturnAddressRequest {
+    pub txid: RpcHash,
+    pub accepting_block_daa_score: u64,
+}
+
+impl GetUtxoReturnAddressRequest {
+    pub fn new(txid: RpcHash, accepting_block_daa_score: u64) -> Self {
+        Self { txid, accepting_block_daa_score }
+    }
+}
+
+impl Serializer for GetUtxoReturnAddressRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(RpcHash, &self.txid, writer)?;
+        store!(u64, &self.accepting_block_daa_score, writer)?;
+
+        Ok(())
+    }
+}
+
+impl Deserializer for GetUtxoReturnAddressRequest {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let txid = load!(RpcHash, reader)?;
+        let accepting_block_daa_score = load!(u64, reader)?;
+
+        Ok(Self { txid, accepting_block_daa_score })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUtxoReturnAddressResponse {
+    pub return_address: RpcAddress,
+}
+
+impl GetUtxoReturnAddressResponse {
+    pub fn new(return_address: RpcAddress) -> Self {
+        Self { return_address }
+    }
+}
+
+impl Serializer for GetUtxoReturnAddressResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(RpcAddress, &self.return_address, writer)?;
+
+        Ok(())
+    }
+}
+
+impl Deserializer for GetUtxoReturnAddressResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let return_address = load!(RpcAddress, reader)?;
+
+        Ok(Self { return_address })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVirtualChainFromBlockV2Request {
+    pub start_hash: RpcHash,
+    pub data_verbosity_level: Option<RpcDataVerbosityLevel>,
+    pub min_confirmation_count: Option<u64>,
+}
+
+impl GetVirtualChainFromBlockV2Request {
+    pub fn new(start_hash: RpcHash, data_verbosity_level: Option<RpcDataVerbosityLevel>, min_confirmation_count: Option<u64>) -> Self {
+        Self { start_hash, data_verbosity_level, min_confirmation_count }
+    }
+}
+
+impl Serializer for GetVirtualChainFromBlockV2Request {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(RpcHash, &self.start_hash, writer)?;
+        serialize!(Option<RpcDataVerbosityLevel>, &self.data_verbosity_level, writer)?;
+        store!(Option<u64>, &self.min_confirmation_count, writer)?;
+
+        Ok(())
+    }
+}
+
+impl Deserializer for GetVirtualChainFromBlockV2Request {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let start_hash = load!(RpcHash, reader)?;
+        let data_verbosity_level = deserialize!(Option<RpcDataVerbosityLevel>, reader)?;
+        let min_confirmation_count = load!(Option<u64>, reader)?;
+
+        Ok(Self { start_hash, data_verbosity_level, min_confirmation_count })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetVirtualChainFromBlockV2Response {
+    /// always present, no matter the verbosity level
+    pub removed_chain_block_hashes: Arc<Vec<RpcHash>>,
+    /// always present, no matter the verbosity level
     pub added_chain_block_hashes: Arc<Vec<RpcHash>>,
     /// struct properties are optionally returned depending on the verbosity level
     pub chain_block_accepted_transactions: Arc<Vec<RpcChainBlockAcceptedTransactions>>,
@@ -3655,126 +4177,552 @@ impl Deserializer for NewBlockTemplateNotification {
     }
 }
 
-///
-///  wRPC response for RpcApiOps::Subscribe request
-///
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// Mempool transaction notifications (address scoped)
+
+/// Keeps the mempool transaction entries involving at least one of the subscribed addresses
+fn filter_mempool_transaction_entries(
+    entries: &[RpcMempoolTransactionEntry],
+    subscription: &UtxosChangedSubscription,
+    context: &SubscriptionContext,
+) -> Vec<RpcMempoolTransactionEntry> {
+    let subscription_data = subscription.data();
+    entries
+        .iter()
+        .filter(|x| x.addresses.iter().any(|address| subscription_data.contains_address(address, context)))
+        .cloned()
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// MempoolTransactionAddedNotification
+
+// NotifyMempoolTransactionAddedRequest registers this connection for mempoolTransactionAdded notifications
+// for the given addresses. Depending on the provided `command`, notifications will
+// start or stop for the provided `addresses`.
+//
+// If `addresses` is empty, the notifications will start or stop for all addresses.
+//
+// See: MempoolTransactionAddedNotification
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SubscribeResponse {
-    id: u64,
+pub struct NotifyMempoolTransactionAddedRequest {
+    pub addresses: Vec<RpcAddress>,
+    pub command: Command,
 }
 
-impl SubscribeResponse {
-    pub fn new(id: u64) -> Self {
-        Self { id }
+impl NotifyMempoolTransactionAddedRequest {
+    pub fn new(addresses: Vec<RpcAddress>, command: Command) -> Self {
+        Self { addresses, command }
     }
 }
 
-impl Serializer for SubscribeResponse {
+impl Serializer for NotifyMempoolTransactionAddedRequest {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
         store!(u16, &1, writer)?;
-        store!(u64, &self.id, writer)?;
+        store!(Vec<RpcAddress>, &self.addresses, writer)?;
+        store!(Command, &self.command, writer)?;
         Ok(())
     }
 }
 
-impl Deserializer for SubscribeResponse {
+impl Deserializer for NotifyMempoolTransactionAddedRequest {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
         let _version = load!(u16, reader)?;
-        let id = load!(u64, reader)?;
-        Ok(Self { id })
+        let addresses = load!(Vec<RpcAddress>, reader)?;
+        let command = load!(Command, reader)?;
+        Ok(Self { addresses, command })
     }
 }
 
-///
-///  wRPC response for RpcApiOps::Unsubscribe request
-///
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct UnsubscribeResponse {}
+pub struct NotifyMempoolTransactionAddedResponse {}
 
-impl Serializer for UnsubscribeResponse {
+impl Serializer for NotifyMempoolTransactionAddedResponse {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u16, &1, writer)
+        store!(u16, &1, writer)?;
+        Ok(())
     }
 }
 
-impl Deserializer for UnsubscribeResponse {
+impl Deserializer for NotifyMempoolTransactionAddedResponse {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u16, reader);
+        let _version = load!(u16, reader)?;
         Ok(Self {})
     }
 }
 
-/// Request data needed to prove a single KIP-21 lane's state against the
-/// `seq_commit` carried in `block_hash`'s header.
-///
-/// `block_hash` must be a chain (selected-parent-chain) block.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// MempoolTransactionAddedNotification is sent whenever transactions are accepted into the mempool.
+// Orphan transactions are only reported once they get unorphaned.
+//
+// See: NotifyMempoolTransactionAddedRequest
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct GetSeqCommitLaneProofRequest {
-    pub block_hash: RpcHash,
-    pub lane_key: RpcHash,
+pub struct MempoolTransactionAddedNotification {
+    pub transactions: Arc<Vec<RpcMempoolTransactionEntry>>,
 }
 
-impl GetSeqCommitLaneProofRequest {
-    pub fn new(block_hash: RpcHash, lane_key: RpcHash) -> Self {
-        Self { block_hash, lane_key }
+impl MempoolTransactionAddedNotification {
+    pub(crate) fn apply_utxos_changed_subscription(
+        &self,
+        subscription: &UtxosChangedSubscription,
+        context: &SubscriptionContext,
+    ) -> Option<Self> {
+        if subscription.to_all() {
+            return Some(self.clone());
+        }
+        let transactions = filter_mempool_transaction_entries(&self.transactions, subscription, context);
+        (!transactions.is_empty()).then(|| Self { transactions: Arc::new(transactions) })
     }
 }
 
-impl Serializer for GetSeqCommitLaneProofRequest {
+impl Serializer for MempoolTransactionAddedNotification {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
         store!(u16, &1, writer)?;
-        store!(RpcHash, &self.block_hash, writer)?;
-        store!(RpcHash, &self.lane_key, writer)?;
+        serialize!(Vec<RpcMempoolTransactionEntry>, &self.transactions, writer)?;
         Ok(())
     }
 }
 
-impl Deserializer for GetSeqCommitLaneProofRequest {
+impl Deserializer for MempoolTransactionAddedNotification {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
         let _version = load!(u16, reader)?;
-        let block_hash = load!(RpcHash, reader)?;
-        let lane_key = load!(RpcHash, reader)?;
-        Ok(Self { block_hash, lane_key })
+        let transactions = deserialize!(Vec<RpcMempoolTransactionEntry>, reader)?;
+        Ok(Self { transactions: transactions.into() })
     }
 }
 
-/// Self-contained witness a client can verify locally against the block header's
-/// `seq_commit` (= `accepted_id_merkle_root`).
-///
-/// `smt_proof` is the `OwnedSmtProof` wire format (`bitmap || terminal || siblings`),
-/// parsed via `kaspa_smt::proof::OwnedSmtProof::from_bytes`.
-///
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// MempoolTransactionRemovedNotification
+
+// NotifyMempoolTransactionRemovedRequest registers this connection for mempoolTransactionRemoved notifications
+// for the given addresses. Depending on the provided `command`, notifications will
+// start or stop for the provided `addresses`.
+//
+// If `addresses` is empty, the notifications will start or stop for all addresses.
+//
+// See: MempoolTransactionRemovedNotification
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct RpcLaneEntry {
-    pub tip: RpcHash,
-    pub blue_score: u64,
+pub struct NotifyMempoolTransactionRemovedRequest {
+    pub addresses: Vec<RpcAddress>,
+    pub command: Command,
 }
 
-impl Serializer for RpcLaneEntry {
+impl NotifyMempoolTransactionRemovedRequest {
+    pub fn new(addresses: Vec<RpcAddress>, command: Command) -> Self {
+        Self { addresses, command }
+    }
+}
+
+impl Serializer for NotifyMempoolTransactionRemovedRequest {
     fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
-        store!(u8, &1, writer)?;
-        store!(RpcHash, &self.tip, writer)?;
-        store!(u64, &self.blue_score, writer)?;
+        store!(u16, &1, writer)?;
+        store!(Vec<RpcAddress>, &self.addresses, writer)?;
+        store!(Command, &self.command, writer)?;
         Ok(())
     }
 }
 
-impl Deserializer for RpcLaneEntry {
+impl Deserializer for NotifyMempoolTransactionRemovedRequest {
     fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
-        let _version = load!(u8, reader)?;
-        let tip = load!(RpcHash, reader)?;
-        let blue_score = load!(u64, reader)?;
-        Ok(Self { tip, blue_score })
+        let _version = load!(u16, reader)?;
+        let addresses = load!(Vec<RpcAddress>, reader)?;
+        let command = load!(Command, reader)?;
+        Ok(Self { addresses, command })
     }
 }
 
-/// When the lane has no entry in the active-lanes SMT at this block's POV,
-/// `lane` is `None` and `smt_proof` is a non-inclusion proof.
-///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyMempoolTransactionRemovedResponse {}
+
+impl Serializer for NotifyMempoolTransactionRemovedResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for NotifyMempoolTransactionRemovedResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        Ok(Self {})
+    }
+}
+
+// MempoolTransactionRemovedNotification is sent whenever transactions get evicted from or expire in
+// the mempool. Transactions replaced by fee or included in a block are reported by dedicated notifications.
+//
+// See: NotifyMempoolTransactionRemovedRequest
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolTransactionRemovedNotification {
+    pub reason: RpcMempoolRemovalReason,
+    pub transactions: Arc<Vec<RpcMempoolTransactionEntry>>,
+}
+
+impl MempoolTransactionRemovedNotification {
+    pub(crate) fn apply_utxos_changed_subscription(
+        &self,
+        subscription: &UtxosChangedSubscription,
+        context: &SubscriptionContext,
+    ) -> Option<Self> {
+        if subscription.to_all() {
+            return Some(self.clone());
+        }
+        let transactions = filter_mempool_transaction_entries(&self.transactions, subscription, context);
+        (!transactions.is_empty()).then(|| Self { reason: self.reason, transactions: Arc::new(transactions) })
+    }
+}
+
+impl Serializer for MempoolTransactionRemovedNotification {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(RpcMempoolRemovalReason, &self.reason, writer)?;
+        serialize!(Vec<RpcMempoolTransactionEntry>, &self.transactions, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for MempoolTransactionRemovedNotification {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let reason = load!(RpcMempoolRemovalReason, reader)?;
+        let transactions = deserialize!(Vec<RpcMempoolTransactionEntry>, reader)?;
+        Ok(Self { reason, transactions: transactions.into() })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// MempoolTransactionReplacedNotification
+
+// NotifyMempoolTransactionReplacedRequest registers this connection for mempoolTransactionReplaced notifications
+// for the given addresses. Depending on the provided `command`, notifications will
+// start or stop for the provided `addresses`.
+//
+// If `addresses` is empty, the notifications will start or stop for all addresses.
+//
+// See: MempoolTransactionReplacedNotification
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyMempoolTransactionReplacedRequest {
+    pub addresses: Vec<RpcAddress>,
+    pub command: Command,
+}
+
+impl NotifyMempoolTransactionReplacedRequest {
+    pub fn new(addresses: Vec<RpcAddress>, command: Command) -> Self {
+        Self { addresses, command }
+    }
+}
+
+impl Serializer for NotifyMempoolTransactionReplacedRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(Vec<RpcAddress>, &self.addresses, writer)?;
+        store!(Command, &self.command, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for NotifyMempoolTransactionReplacedRequest {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let addresses = load!(Vec<RpcAddress>, reader)?;
+        let command = load!(Command, reader)?;
+        Ok(Self { addresses, command })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyMempoolTransactionReplacedResponse {}
+
+impl Serializer for NotifyMempoolTransactionReplacedResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for NotifyMempoolTransactionReplacedResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        Ok(Self {})
+    }
+}
+
+// MempoolTransactionReplacedNotification is sent whenever a transaction replaces by fee some
+// transactions of the mempool.
+//
+// When filtered by addresses, the notification is sent as a whole as soon as either the replacing
+// transaction or one of the replaced ones involves a subscribed address.
+//
+// See: NotifyMempoolTransactionReplacedRequest
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolTransactionReplacedNotification {
+    pub replacing: RpcMempoolTransactionEntry,
+    pub replaced: Arc<Vec<RpcMempoolTransactionEntry>>,
+}
+
+impl MempoolTransactionReplacedNotification {
+    pub(crate) fn apply_utxos_changed_subscription(
+        &self,
+        subscription: &UtxosChangedSubscription,
+        context: &SubscriptionContext,
+    ) -> Option<Self> {
+        if subscription.to_all() {
+            return Some(self.clone());
+        }
+        let subscription_data = subscription.data();
+        std::iter::once(&self.replacing)
+            .chain(self.replaced.iter())
+            .flat_map(|x| x.addresses.iter())
+            .any(|address| subscription_data.contains_address(address, context))
+            .then(|| self.clone())
+    }
+}
+
+impl Serializer for MempoolTransactionReplacedNotification {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        serialize!(RpcMempoolTransactionEntry, &self.replacing, writer)?;
+        serialize!(Vec<RpcMempoolTransactionEntry>, &self.replaced, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for MempoolTransactionReplacedNotification {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let replacing = deserialize!(RpcMempoolTransactionEntry, reader)?;
+        let replaced = deserialize!(Vec<RpcMempoolTransactionEntry>, reader)?;
+        Ok(Self { replacing, replaced: replaced.into() })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// MempoolTransactionIncludedNotification
+
+// NotifyMempoolTransactionIncludedRequest registers this connection for mempoolTransactionIncluded notifications
+// for the given addresses. Depending on the provided `command`, notifications will
+// start or stop for the provided `addresses`.
+//
+// If `addresses` is empty, the notifications will start or stop for all addresses.
+//
+// See: MempoolTransactionIncludedNotification
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyMempoolTransactionIncludedRequest {
+    pub addresses: Vec<RpcAddress>,
+    pub command: Command,
+}
+
+impl NotifyMempoolTransactionIncludedRequest {
+    pub fn new(addresses: Vec<RpcAddress>, command: Command) -> Self {
+        Self { addresses, command }
+    }
+}
+
+impl Serializer for NotifyMempoolTransactionIncludedRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(Vec<RpcAddress>, &self.addresses, writer)?;
+        store!(Command, &self.command, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for NotifyMempoolTransactionIncludedRequest {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let addresses = load!(Vec<RpcAddress>, reader)?;
+        let command = load!(Command, reader)?;
+        Ok(Self { addresses, command })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyMempoolTransactionIncludedResponse {}
+
+impl Serializer for NotifyMempoolTransactionIncludedResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for NotifyMempoolTransactionIncludedResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        Ok(Self {})
+    }
+}
+
+// MempoolTransactionIncludedNotification is sent whenever mempool transactions get removed
+// from the mempool because they were included in a block.
+//
+// See: NotifyMempoolTransactionIncludedRequest
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolTransactionIncludedNotification {
+    pub transactions: Arc<Vec<RpcMempoolTransactionEntry>>,
+}
+
+impl MempoolTransactionIncludedNotification {
+    pub(crate) fn apply_utxos_changed_subscription(
+        &self,
+        subscription: &UtxosChangedSubscription,
+        context: &SubscriptionContext,
+    ) -> Option<Self> {
+        if subscription.to_all() {
+            return Some(self.clone());
+        }
+        let transactions = filter_mempool_transaction_entries(&self.transactions, subscription, context);
+        (!transactions.is_empty()).then(|| Self { transactions: Arc::new(transactions) })
+    }
+}
+
+impl Serializer for MempoolTransactionIncludedNotification {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        serialize!(Vec<RpcMempoolTransactionEntry>, &self.transactions, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for MempoolTransactionIncludedNotification {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let transactions = deserialize!(Vec<RpcMempoolTransactionEntry>, reader)?;
+        Ok(Self { transactions: transactions.into() })
+    }
+}
+
+///
+///  wRPC response for RpcApiOps::Subscribe request
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribeResponse {
+    id: u64,
+}
+
+impl SubscribeResponse {
+    pub fn new(id: u64) -> Self {
+        Self { id }
+    }
+}
+
+impl Serializer for SubscribeResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(u64, &self.id, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for SubscribeResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let id = load!(u64, reader)?;
+        Ok(Self { id })
+    }
+}
+
+///
+///  wRPC response for RpcApiOps::Unsubscribe request
+///
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsubscribeResponse {}
+
+impl Serializer for UnsubscribeResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)
+    }
+}
+
+impl Deserializer for UnsubscribeResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader);
+        Ok(Self {})
+    }
+}
+
+/// Request data needed to prove a single KIP-21 lane's state against the
+/// `seq_commit` carried in `block_hash`'s header.
+///
+/// `block_hash` must be a chain (selected-parent-chain) block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSeqCommitLaneProofRequest {
+    pub block_hash: RpcHash,
+    pub lane_key: RpcHash,
+}
+
+impl GetSeqCommitLaneProofRequest {
+    pub fn new(block_hash: RpcHash, lane_key: RpcHash) -> Self {
+        Self { block_hash, lane_key }
+    }
+}
+
+impl Serializer for GetSeqCommitLaneProofRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(RpcHash, &self.block_hash, writer)?;
+        store!(RpcHash, &self.lane_key, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for GetSeqCommitLaneProofRequest {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let block_hash = load!(RpcHash, reader)?;
+        let lane_key = load!(RpcHash, reader)?;
+        Ok(Self { block_hash, lane_key })
+    }
+}
+
+/// Self-contained witness a client can verify locally against the block header's
+/// `seq_commit` (= `accepted_id_merkle_root`).
+///
+/// `smt_proof` is the `OwnedSmtProof` wire format (`bitmap || terminal || siblings`),
+/// parsed via `kaspa_smt::proof::OwnedSmtProof::from_bytes`.
+///
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcLaneEntry {
+    pub tip: RpcHash,
+    pub blue_score: u64,
+}
+
+impl Serializer for RpcLaneEntry {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u8, &1, writer)?;
+        store!(RpcHash, &self.tip, writer)?;
+        store!(u64, &self.blue_score, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for RpcLaneEntry {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u8, reader)?;
+        let tip = load!(RpcHash, reader)?;
+        let blue_score = load!(u64, reader)?;
+        Ok(Self { tip, blue_score })
+    }
+}
+
+/// When the lane has no entry in the active-lanes SMT at this block's POV,
+/// `lane` is `None` and `smt_proof` is a non-inclusion proof.
+///
 /// `inactivity_shortcut` (KIP-21): the `accepted_id_merkle_root` of the anchor
 /// block. Folded into `activity_root = H_activity_root(inactivity_shortcut, lanes_root)`
 /// during seq_commit verification.
@@ -3811,3 +4759,105 @@ impl Deserializer for GetSeqCommitLaneProofResponse {
         Ok(Self { smt_proof, lane, payload_and_ctx_digest, parent_seq_commit, inactivity_shortcut })
     }
 }
+
+/// Request data needed to prove the state of several KIP-21 lanes at once against
+/// the `seq_commit` carried in `block_hash`'s header.
+///
+/// `block_hash` must be a chain (selected-parent-chain) block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSeqCommitLaneProofsRequest {
+    pub block_hash: RpcHash,
+    pub lane_keys: Vec<RpcHash>,
+}
+
+impl GetSeqCommitLaneProofsRequest {
+    pub fn new(block_hash: RpcHash, lane_keys: Vec<RpcHash>) -> Self {
+        Self { block_hash, lane_keys }
+    }
+}
+
+impl Serializer for GetSeqCommitLaneProofsRequest {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(RpcHash, &self.block_hash, writer)?;
+        store!(Vec<RpcHash>, &self.lane_keys, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for GetSeqCommitLaneProofsRequest {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let block_hash = load!(RpcHash, reader)?;
+        let lane_keys = load!(Vec<RpcHash>, reader)?;
+        Ok(Self { block_hash, lane_keys })
+    }
+}
+
+/// A lane covered by a [`GetSeqCommitLaneProofsResponse`]; `lane` is `None`
+/// when the lane has no entry in the active-lanes SMT at the block's POV.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSeqCommitLane {
+    pub lane_key: RpcHash,
+    pub lane: Option<RpcLaneEntry>,
+}
+
+impl Serializer for RpcSeqCommitLane {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u8, &1, writer)?;
+        store!(RpcHash, &self.lane_key, writer)?;
+        serialize!(Option<RpcLaneEntry>, &self.lane, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for RpcSeqCommitLane {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u8, reader)?;
+        let lane_key = load!(RpcHash, reader)?;
+        let lane = deserialize!(Option<RpcLaneEntry>, reader)?;
+        Ok(Self { lane_key, lane })
+    }
+}
+
+/// Self-contained witness for several lanes, verifiable locally against the block
+/// header's `seq_commit` (= `accepted_id_merkle_root`).
+///
+/// `smt_proof` is the `SmtMultiProof` wire format, parsed via
+/// `kaspa_smt::multiproof::SmtMultiProof::from_bytes`. `lanes` lists each distinct
+/// requested lane key in ascending order; see `kaspa_seq_commit::verify::verify_lanes_multi_proof`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSeqCommitLaneProofsResponse {
+    pub smt_proof: Vec<u8>,
+    pub lanes: Vec<RpcSeqCommitLane>,
+    pub payload_and_ctx_digest: RpcHash,
+    pub parent_seq_commit: RpcHash,
+    pub inactivity_shortcut: RpcHash,
+}
+
+impl Serializer for GetSeqCommitLaneProofsResponse {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        store!(u16, &1, writer)?;
+        store!(Vec<u8>, &self.smt_proof, writer)?;
+        serialize!(Vec<RpcSeqCommitLane>, &self.lanes, writer)?;
+        store!(RpcHash, &self.payload_and_ctx_digest, writer)?;
+        store!(RpcHash, &self.parent_seq_commit, writer)?;
+        store!(RpcHash, &self.inactivity_shortcut, writer)?;
+        Ok(())
+    }
+}
+
+impl Deserializer for GetSeqCommitLaneProofsResponse {
+    fn deserialize<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let _version = load!(u16, reader)?;
+        let smt_proof = load!(Vec<u8>, reader)?;
+        let lanes = deserialize!(Vec<RpcSeqCommitLane>, reader)?;
+        let payload_and_ctx_digest = load!(RpcHash, reader)?;
+        let parent_seq_commit = load!(RpcHash, reader)?;
+        let inactivity_shortcut = load!(RpcHash, reader)?;
+        Ok(Self { smt_proof, lanes, payload_and_ctx_digest, parent_seq_commit, inactivity_shortcut })
+    }
+}
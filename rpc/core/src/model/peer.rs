@@ -1,3 +1,4 @@
+use crate::RpcHash;
 use borsh::{BorshDeserialize, BorshSerialize};
 use kaspa_utils::networking::{ContextualNetAddress, IpAddress, NetAddress, PeerId};
 use serde::{Deserialize, Serialize};
@@ -20,4 +21,34 @@ pub struct RpcPeerInfo {
     pub advertised_protocol_version: u32,
     pub time_connected: u64, // NOTE: i64 in gRPC protowire
     pub is_ibd_peer: bool,
+
+    // NOTE: the misbehavior and transport fields are serialized separately by `GetConnectedPeerInfoResponse`
+    // so that the borsh layout of this struct remains readable by older clients
+    /// The misbehavior score of the peer, decaying over time
+    #[serde(default)]
+    #[borsh(skip)]
+    pub misbehavior_score: u32,
+    /// The latest misbehavior of the peer, if its score is positive
+    #[serde(default)]
+    #[borsh(skip)]
+    pub misbehavior_reason: Option<String>,
+    /// Whether the connection to the peer is encrypted
+    #[serde(default)]
+    #[borsh(skip)]
+    pub is_encrypted: bool,
+    /// The session id of the encrypted connection, identical on both ends unless the connection is intercepted
+    #[serde(default)]
+    #[borsh(skip)]
+    pub transport_session_id: Option<RpcHash>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBannedPeer {
+    pub ip: RpcIpAddress,
+    /// Unix timestamp (in milliseconds) at which the ban was recorded
+    pub ban_timestamp: u64,
+    /// Unix timestamp (in milliseconds) at which the ban expires
+    pub expiry: u64,
+    pub reason: String,
 }
@@ -494,16 +494,32 @@ mod mockery {
                 advertised_protocol_version: mock(),
                 time_connected: mock(),
                 is_ibd_peer: mock(),
+                misbehavior_score: mock(),
+                misbehavior_reason: Some("timeout: timeout expired after 120s".to_string()),
+                is_encrypted: mock(),
+                transport_session_id: mock(),
             }
         }
     }
 
+    impl Mock for RpcBannedPeer {
+        fn mock() -> Self {
+            RpcBannedPeer { ip: mock(), ban_timestamp: mock(), expiry: mock(), reason: "manual ban".to_string() }
+        }
+    }
+
     impl Mock for RpcMempoolEntry {
         fn mock() -> Self {
             RpcMempoolEntry { fee: mock(), transaction: mock(), is_orphan: mock() }
         }
     }
 
+    impl Mock for RpcMempoolTransactionEntry {
+        fn mock() -> Self {
+            RpcMempoolTransactionEntry { fee: mock(), transaction: mock(), addresses: mock() }
+        }
+    }
+
     impl Mock for RpcMempoolEntryByAddress {
         fn mock() -> Self {
             RpcMempoolEntryByAddress { address: mock(), sending: mock(), receiving: mock() }
@@ -613,6 +629,9 @@ mod mockery {
                 p2p_bytes_rx: mock(),
                 grpc_bytes_tx: mock(),
                 grpc_bytes_rx: mock(),
+                p2p_compact_blocks_rx: mock(),
+                p2p_compact_block_txs_requested: mock(),
+                p2p_compact_block_bytes_saved: mock(),
             }
         }
     }
@@ -849,9 +868,59 @@ mod mockery {
 
     test!(SubmitTransactionResponse);
 
+    impl Mock for SubmitTransactionPackageRequest {
+        fn mock() -> Self {
+            SubmitTransactionPackageRequest { transactions: vec![mock(), mock()] }
+        }
+    }
+
+    test!(SubmitTransactionPackageRequest);
+
+    impl Mock for SubmitTransactionPackageResponse {
+        fn mock() -> Self {
+            SubmitTransactionPackageResponse { transaction_ids: mock() }
+        }
+    }
+
+    test!(SubmitTransactionPackageResponse);
+
+    impl Mock for ValidateTransactionScriptsRequest {
+        fn mock() -> Self {
+            ValidateTransactionScriptsRequest { transaction: mock(), utxo_entries: mock() }
+        }
+    }
+
+    test!(ValidateTransactionScriptsRequest);
+
+    impl Mock for RpcInputScriptValidation {
+        fn mock() -> Self {
+            RpcInputScriptValidation {
+                error: Some("false stack entry at end of script execution".to_string()),
+                used_script_units: mock(),
+                used_sig_ops: mock(),
+            }
+        }
+    }
+
+    impl Mock for ValidateTransactionScriptsResponse {
+        fn mock() -> Self {
+            ValidateTransactionScriptsResponse {
+                transaction_id: mock(),
+                fee: mock(),
+                error: None,
+                compute_mass: mock(),
+                transient_mass: mock(),
+                storage_mass: mock(),
+                inputs: vec![mock(), mock()],
+            }
+        }
+    }
+
+    test!(ValidateTransactionScriptsResponse);
+
     impl Mock for GetSubnetworkRequest {
         fn mock() -> Self {
-            GetSubnetworkRequest { subnetwork_id: mock() }
+            GetSubnetworkRequest { subnetwork_id: mock(), block_hash: mock() }
         }
     }
 
@@ -859,7 +928,7 @@ mod mockery {
 
     impl Mock for GetSubnetworkResponse {
         fn mock() -> Self {
-            GetSubnetworkResponse { gas_limit: mock() }
+            GetSubnetworkResponse { gas_limit: mock(), block_hash: mock(), lane: mock(), is_active: mock() }
         }
     }
 
@@ -1056,9 +1125,15 @@ mod mockery {
 
     test!(GetSinkBlueScoreResponse);
 
+    impl Mock for RpcUtxosByAddressesCursor {
+        fn mock() -> Self {
+            RpcUtxosByAddressesCursor { address: mock(), outpoint: mock() }
+        }
+    }
+
     impl Mock for GetUtxosByAddressesRequest {
         fn mock() -> Self {
-            GetUtxosByAddressesRequest { addresses: mock() }
+            GetUtxosByAddressesRequest { addresses: mock(), cursor: mock(), limit: mock(), min_amount: mock() }
         }
     }
 
@@ -1066,7 +1141,7 @@ mod mockery {
 
     impl Mock for GetUtxosByAddressesResponse {
         fn mock() -> Self {
-            GetUtxosByAddressesResponse { entries: mock() }
+            GetUtxosByAddressesResponse { entries: mock(), next_cursor: mock() }
         }
     }
 
@@ -1104,6 +1179,22 @@ mod mockery {
 
     test!(UnbanResponse);
 
+    impl Mock for GetBannedPeersRequest {
+        fn mock() -> Self {
+            GetBannedPeersRequest {}
+        }
+    }
+
+    test!(GetBannedPeersRequest);
+
+    impl Mock for GetBannedPeersResponse {
+        fn mock() -> Self {
+            GetBannedPeersResponse { banned_peers: mock() }
+        }
+    }
+
+    test!(GetBannedPeersResponse);
+
     impl Mock for EstimateNetworkHashesPerSecondRequest {
         fn mock() -> Self {
             EstimateNetworkHashesPerSecondRequest { window_size: mock(), start_hash: mock() }
@@ -1323,6 +1414,69 @@ mod mockery {
 
     test!(GetBlockRewardInfoResponse);
 
+    impl Mock for GetTransactionRequest {
+        fn mock() -> Self {
+            GetTransactionRequest { transaction_id: mock(), include_transaction: true }
+        }
+    }
+
+    test!(GetTransactionRequest);
+
+    impl Mock for RpcTransactionStatus {
+        fn mock() -> Self {
+            RpcTransactionStatus::Accepted
+        }
+    }
+
+    impl Mock for GetTransactionResponse {
+        fn mock() -> Self {
+            GetTransactionResponse {
+                transaction: mock(),
+                status: mock(),
+                including_block_hash: mock(),
+                accepting_block_hash: mock(),
+                accepting_block_daa_score: mock(),
+                confirmation_count: mock(),
+            }
+        }
+    }
+
+    test!(GetTransactionResponse);
+
+    impl Mock for RpcAddressHistoryCursor {
+        fn mock() -> Self {
+            RpcAddressHistoryCursor { accepting_daa_score: mock(), transaction_id: mock() }
+        }
+    }
+
+    impl Mock for GetAddressHistoryRequest {
+        fn mock() -> Self {
+            GetAddressHistoryRequest { address: mock(), from_daa_score: mock(), to_daa_score: mock(), cursor: mock(), limit: mock() }
+        }
+    }
+
+    test!(GetAddressHistoryRequest);
+
+    impl Mock for RpcAddressHistoryEntry {
+        fn mock() -> Self {
+            RpcAddressHistoryEntry {
+                transaction_id: mock(),
+                accepting_block_hash: mock(),
+                accepting_daa_score: mock(),
+                received_amount: mock(),
+                spent_amount: mock(),
+            }
+        }
+    }
+
+    impl Mock for GetAddressHistoryResponse {
+        fn mock() -> Self {
+            GetAddressHistoryResponse { entries: mock(), next_cursor: mock() }
+        }
+    }
+
+    test!(GetAddressHistoryResponse);
+
     impl Mock for GetVirtualChainFromBlockV2Request {
         fn mock() -> Self {
             GetVirtualChainFromBlockV2Request { start_hash: mock(), data_verbosity_level: None, min_confirmation_count: mock() }
@@ -1569,6 +1723,102 @@ mod mockery {
 
     test!(NewBlockTemplateNotification);
 
+    impl Mock for NotifyMempoolTransactionAddedRequest {
+        fn mock() -> Self {
+            NotifyMempoolTransactionAddedRequest { addresses: mock(), command: Command::Start }
+        }
+    }
+
+    test!(NotifyMempoolTransactionAddedRequest);
+
+    impl Mock for NotifyMempoolTransactionAddedResponse {
+        fn mock() -> Self {
+            NotifyMempoolTransactionAddedResponse {}
+        }
+    }
+
+    test!(NotifyMempoolTransactionAddedResponse);
+
+    impl Mock for MempoolTransactionAddedNotification {
+        fn mock() -> Self {
+            MempoolTransactionAddedNotification { transactions: mock() }
+        }
+    }
+
+    test!(MempoolTransactionAddedNotification);
+
+    impl Mock for NotifyMempoolTransactionRemovedRequest {
+        fn mock() -> Self {
+            NotifyMempoolTransactionRemovedRequest { addresses: mock(), command: Command::Start }
+        }
+    }
+
+    test!(NotifyMempoolTransactionRemovedRequest);
+
+    impl Mock for NotifyMempoolTransactionRemovedResponse {
+        fn mock() -> Self {
+            NotifyMempoolTransactionRemovedResponse {}
+        }
+    }
+
+    test!(NotifyMempoolTransactionRemovedResponse);
+
+    impl Mock for MempoolTransactionRemovedNotification {
+        fn mock() -> Self {
+            MempoolTransactionRemovedNotification { reason: RpcMempoolRemovalReason::Expired, transactions: mock() }
+        }
+    }
+
+    test!(MempoolTransactionRemovedNotification);
+
+    impl Mock for NotifyMempoolTransactionReplacedRequest {
+        fn mock() -> Self {
+            NotifyMempoolTransactionReplacedRequest { addresses: mock(), command: Command::Start }
+        }
+    }
+
+    test!(NotifyMempoolTransactionReplacedRequest);
+
+    impl Mock for NotifyMempoolTransactionReplacedResponse {
+        fn mock() -> Self {
+            NotifyMempoolTransactionReplacedResponse {}
+        }
+    }
+
+    test!(NotifyMempoolTransactionReplacedResponse);
+
+    impl Mock for MempoolTransactionReplacedNotification {
+        fn mock() -> Self {
+            MempoolTransactionReplacedNotification { replacing: mock(), replaced: mock() }
+        }
+    }
+
+    test!(MempoolTransactionReplacedNotification);
+
+    impl Mock for NotifyMempoolTransactionIncludedRequest {
+        fn mock() -> Self {
+            NotifyMempoolTransactionIncludedRequest { addresses: mock(), command: Command::Start }
+        }
+    }
+
+    test!(NotifyMempoolTransactionIncludedRequest);
+
+    impl Mock for NotifyMempoolTransactionIncludedResponse {
+        fn mock() -> Self {
+            NotifyMempoolTransactionIncludedResponse {}
+        }
+    }
+
+    test!(NotifyMempoolTransactionIncludedResponse);
+
+    impl Mock for MempoolTransactionIncludedNotification {
+        fn mock() -> Self {
+            MempoolTransactionIncludedNotification { transactions: mock() }
+        }
+    }
+
+    test!(MempoolTransactionIncludedNotification);
+
     impl Mock for SubscribeResponse {
         fn mock() -> Self {
             SubscribeResponse::new(mock())
@@ -1613,6 +1863,34 @@ mod mockery {
 
     test!(GetSeqCommitLaneProofResponse);
 
+    impl Mock for GetSeqCommitLaneProofsRequest {
+        fn mock() -> Self {
+            GetSeqCommitLaneProofsRequest { block_hash: mock(), lane_keys: mock() }
+        }
+    }
+
+    test!(GetSeqCommitLaneProofsRequest);
+
+    impl Mock for RpcSeqCommitLane {
+        fn mock() -> Self {
+            RpcSeqCommitLane { lane_key: mock(), lane: mock() }
+        }
+    }
+
+    impl Mock for GetSeqCommitLaneProofsResponse {
+        fn mock() -> Self {
+            GetSeqCommitLaneProofsResponse {
+                smt_proof: vec![mock(), mock(), mock(), mock()],
+                lanes: mock(),
+                payload_and_ctx_digest: mock(),
+                parent_seq_commit: mock(),
+                inactivity_shortcut: mock(),
+            }
+        }
+    }
+
+    test!(GetSeqCommitLaneProofsResponse);
+
     struct Misalign;
 
     impl Mock for Misalign {
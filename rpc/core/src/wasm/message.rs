@@ -963,6 +963,108 @@ try_from! ( args: GetBlockRewardInfoResponse, IGetBlockRewardInfoResponse, {
 
 // ---
 
+declare! {
+    IGetTransactionRequest,
+    r#"
+    /**
+     * @category Node RPC
+     */
+    export interface IGetTransactionRequest {
+        transactionId: HexString;
+        includeTransaction: boolean;
+    }
+    "#,
+}
+
+try_from! ( args: IGetTransactionRequest, GetTransactionRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetTransactionResponse,
+    r#"
+    /**
+     * @category Node RPC
+     */
+    export interface IGetTransactionResponse {
+        transaction?: ITransaction;
+        status: "unknown" | "accepted" | "pending";
+        includingBlockHash?: HexString;
+        acceptingBlockHash?: HexString;
+        acceptingBlockDaaScore?: bigint;
+        confirmationCount?: bigint;
+    }
+    "#,
+}
+
+try_from! ( args: GetTransactionResponse, IGetTransactionResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
+declare! {
+    IGetAddressHistoryRequest,
+    r#"
+    /**
+     * Position within the history of an address.
+     *
+     * @category Node RPC
+     */
+    export interface IAddressHistoryCursor {
+        acceptingDaaScore: bigint;
+        transactionId: HexString;
+    }
+
+    /**
+     * @category Node RPC
+     */
+    export interface IGetAddressHistoryRequest {
+        address: Address | string;
+        fromDaaScore?: bigint;
+        toDaaScore?: bigint;
+        cursor?: IAddressHistoryCursor;
+        limit?: number;
+    }
+    "#,
+}
+
+try_from! ( args: IGetAddressHistoryRequest, GetAddressHistoryRequest, {
+    Ok(from_value(args.into())?)
+});
+
+declare! {
+    IGetAddressHistoryResponse,
+    r#"
+    /**
+     * Accepted transaction funding or spending from an address.
+     *
+     * @category Node RPC
+     */
+    export interface IAddressHistoryEntry {
+        transactionId: HexString;
+        acceptingBlockHash: HexString;
+        acceptingDaaScore: bigint;
+        receivedAmount: bigint;
+        spentAmount: bigint;
+    }
+
+    /**
+     * @category Node RPC
+     */
+    export interface IGetAddressHistoryResponse {
+        entries: IAddressHistoryEntry[];
+        nextCursor?: IAddressHistoryCursor;
+    }
+    "#,
+}
+
+try_from! ( args: GetAddressHistoryResponse, IGetAddressHistoryResponse, {
+    Ok(to_value(&args)?.into())
+});
+
+// ---
+
 declare! {
     IGetDaaScoreTimestampEstimateRequest,
     r#"
@@ -1208,6 +1310,10 @@ declare! {
      */
     export interface IGetSubnetworkRequest {
         subnetworkId : HexString;
+        /**
+         * Chain block to report the lane state at. Defaults to the sink.
+         */
+        blockHash? : HexString;
     }
     "#,
 }
@@ -1226,6 +1332,12 @@ declare! {
      */
     export interface IGetSubnetworkResponse {
         gasLimit : bigint;
+        blockHash : HexString;
+        /**
+         * Lane tip and the blue score at which the lane was last active.
+         */
+        lane? : { tip : HexString; blueScore : bigint; };
+        isActive : boolean;
     }
     "#,
 }
@@ -1240,13 +1352,35 @@ declare! {
     IGetUtxosByAddressesRequest,
     "IGetUtxosByAddressesRequest | Address[] | string[]",
     r#"
+    /**
+     * Position within the UTXOs of a set of addresses.
+     *
+     * @category Node RPC
+     */
+    export interface IUtxosByAddressesCursor {
+        address : Address | string;
+        outpoint : ITransactionOutpoint;
+    }
+
     /**
      *
      *
      * @category Node RPC
      */
     export interface IGetUtxosByAddressesRequest {
-        addresses : Address[] | string[]
+        addresses : Address[] | string[];
+        /**
+         * Resume after this position, as returned by `nextCursor` of a previous response.
+         */
+        cursor? : IUtxosByAddressesCursor;
+        /**
+         * Maximum number of entries to return. If not passed, all entries are returned.
+         */
+        limit? : number;
+        /**
+         * Only return entries with an amount of at least this value (in sompi).
+         */
+        minAmount? : bigint;
     }
     "#,
 }
@@ -1254,7 +1388,7 @@ declare! {
 try_from! ( args: IGetUtxosByAddressesRequest, GetUtxosByAddressesRequest, {
     let js_value = JsValue::from(args);
     let request = if let Ok(addresses) = Vec::<Address>::try_from(AddressOrStringArrayT::from(js_value.clone())) {
-        GetUtxosByAddressesRequest { addresses }
+        GetUtxosByAddressesRequest::new(addresses)
     } else {
         from_value::<GetUtxosByAddressesRequest>(js_value)?
     };
@@ -1271,16 +1405,23 @@ declare! {
      */
     export interface IGetUtxosByAddressesResponse {
         entries : UtxoEntryReference[];
+        /**
+         * Populated when more entries are available.
+         */
+        nextCursor? : IUtxosByAddressesCursor;
     }
     "#,
 }
 
 try_from! ( args: GetUtxosByAddressesResponse, IGetUtxosByAddressesResponse, {
-    let GetUtxosByAddressesResponse { entries } = args;
+    let GetUtxosByAddressesResponse { entries, next_cursor } = args;
     let entries = entries.into_iter().map(UtxoEntryReference::from).collect::<Vec<UtxoEntryReference>>();
     let entries = js_sys::Array::from_iter(entries.into_iter().map(JsValue::from));
     let response = IGetUtxosByAddressesResponse::default();
     response.set("entries", entries.as_ref())?;
+    if let Some(next_cursor) = next_cursor {
+        response.set("nextCursor", &to_value(&next_cursor)?)?;
+    }
     Ok(response)
 });
 
@@ -1781,6 +1922,23 @@ try_from!( args: GetFeeEstimateResponse, IGetFeeEstimateResponse, {
 
 // ---
 
+declare! {
+    IInclusionFeerateEstimate,
+    r#"
+    /**
+     * Feerate needed for inclusion within `targetSeconds` at a given `confidence` (in the [0, 1] range),
+     * learned from recent blocks. The feerate is -1 if the node did not observe enough block inclusions yet.
+     *
+     * @category Node RPC
+     */
+    export interface IInclusionFeerateEstimate {
+        targetSeconds : number;
+        confidence : number;
+        feerate : number;
+    }
+    "#,
+}
+
 declare! {
     IFeeEstimateVerboseExperimentalData,
     r#"
@@ -1796,6 +1954,8 @@ declare! {
         nextBlockTemplateFeerateMin : number;
         nextBlockTemplateFeerateMedian : number;
         nextBlockTemplateFeerateMax : number;
+        inclusionObservationCount : bigint;
+        inclusionEstimates : IInclusionFeerateEstimate[];
     }
     "#,
 }
@@ -1809,6 +1969,8 @@ try_from!( data: RpcFeeEstimateVerboseExperimentalData, IFeeEstimateVerboseExper
     target.set("nextBlockTemplateFeerateMin", &data.next_block_template_feerate_min.into())?;
     target.set("nextBlockTemplateFeerateMedian", &data.next_block_template_feerate_median.into())?;
     target.set("nextBlockTemplateFeerateMax", &data.next_block_template_feerate_max.into())?;
+    target.set("inclusionObservationCount", &js_sys::BigInt::from(data.inclusion_observation_count).into())?;
+    target.set("inclusionEstimates", &to_value(&data.inclusion_estimates)?)?;
 
     Ok(target)
 });
@@ -280,6 +280,12 @@ impl RpcApi for GrpcClient {
     route!(get_utxo_return_address_call, GetUtxoReturnAddress);
     route!(get_virtual_chain_from_block_v2_call, GetVirtualChainFromBlockV2);
     route!(get_seq_commit_lane_proof_call, GetSeqCommitLaneProof);
+    route!(get_transaction_call, GetTransaction);
+    route!(get_address_history_call, GetAddressHistory);
+    route!(get_seq_commit_lane_proofs_call, GetSeqCommitLaneProofs);
+    route!(validate_transaction_scripts_call, ValidateTransactionScripts);
+    route!(submit_transaction_package_call, SubmitTransactionPackage);
+    route!(get_banned_peers_call, GetBannedPeers);
 
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
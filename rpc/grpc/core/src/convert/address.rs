@@ -1,6 +1,7 @@
 use crate::protowire;
 use crate::{from, try_from};
-use kaspa_rpc_core::RpcError;
+use kaspa_rpc_core::{RpcError, RpcHash};
+use std::str::FromStr;
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
@@ -10,6 +11,24 @@ from!(item: &kaspa_rpc_core::RpcBalancesByAddressesEntry, protowire::RpcBalances
     Self { address: (&item.address).into(), balance: item.balance.unwrap_or_default(), error: None }
 });
 
+from!(item: &kaspa_rpc_core::RpcUtxosByAddressesCursor, protowire::RpcUtxosByAddressesCursor, {
+    Self { address: (&item.address).into(), outpoint: Some((&item.outpoint).into()) }
+});
+
+from!(item: &kaspa_rpc_core::RpcAddressHistoryCursor, protowire::RpcAddressHistoryCursor, {
+    Self { accepting_daa_score: item.accepting_daa_score, transaction_id: item.transaction_id.to_string() }
+});
+
+from!(item: &kaspa_rpc_core::RpcAddressHistoryEntry, protowire::RpcAddressHistoryEntry, {
+    Self {
+        transaction_id: item.transaction_id.to_string(),
+        accepting_block_hash: item.accepting_block_hash.to_string(),
+        accepting_daa_score: item.accepting_daa_score,
+        received_amount: item.received_amount,
+        spent_amount: item.spent_amount,
+    }
+});
+
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
@@ -18,3 +37,28 @@ try_from!(item: &protowire::RpcBalancesByAddressesEntry, kaspa_rpc_core::RpcBala
     let balance = if item.error.is_some() { None } else { Some(item.balance) };
     Self { address: item.address.as_str().try_into()?, balance }
 });
+
+try_from!(item: &protowire::RpcUtxosByAddressesCursor, kaspa_rpc_core::RpcUtxosByAddressesCursor, {
+    Self {
+        address: item.address.as_str().try_into()?,
+        outpoint: item
+            .outpoint
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("UtxosByAddressesCursor".to_string(), "outpoint".to_string()))?
+            .try_into()?,
+    }
+});
+
+try_from!(item: &protowire::RpcAddressHistoryCursor, kaspa_rpc_core::RpcAddressHistoryCursor, {
+    Self { accepting_daa_score: item.accepting_daa_score, transaction_id: RpcHash::from_str(&item.transaction_id)? }
+});
+
+try_from!(item: &protowire::RpcAddressHistoryEntry, kaspa_rpc_core::RpcAddressHistoryEntry, {
+    Self {
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        accepting_block_hash: RpcHash::from_str(&item.accepting_block_hash)?,
+        accepting_daa_score: item.accepting_daa_score,
+        received_amount: item.received_amount,
+        spent_amount: item.spent_amount,
+    }
+});
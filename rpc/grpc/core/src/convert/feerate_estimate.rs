@@ -21,6 +21,14 @@ from!(item: &kaspa_rpc_core::RpcFeeEstimate, protowire::RpcFeeEstimate, {
     }
 });
 
+from!(item: &kaspa_rpc_core::RpcInclusionFeerateEstimate, protowire::RpcInclusionFeerateEstimate, {
+    Self {
+        target_seconds: item.target_seconds,
+        confidence: item.confidence,
+        feerate: item.feerate,
+    }
+});
+
 from!(item: &kaspa_rpc_core::RpcFeeEstimateVerboseExperimentalData, protowire::RpcFeeEstimateVerboseExperimentalData, {
     Self {
         network_mass_per_second: item.network_mass_per_second,
@@ -29,6 +37,8 @@ from!(item: &kaspa_rpc_core::RpcFeeEstimateVerboseExperimentalData, protowire::R
         next_block_template_feerate_min: item.next_block_template_feerate_min,
         next_block_template_feerate_median: item.next_block_template_feerate_median,
         next_block_template_feerate_max: item.next_block_template_feerate_max,
+        inclusion_observation_count: item.inclusion_observation_count,
+        inclusion_estimates: item.inclusion_estimates.iter().map(|e| e.into()).collect(),
     }
 });
 
@@ -54,6 +64,14 @@ try_from!(item: &protowire::RpcFeeEstimate, kaspa_rpc_core::RpcFeeEstimate, {
     }
 });
 
+try_from!(item: &protowire::RpcInclusionFeerateEstimate, kaspa_rpc_core::RpcInclusionFeerateEstimate, {
+    Self {
+        target_seconds: item.target_seconds,
+        confidence: item.confidence,
+        feerate: item.feerate,
+    }
+});
+
 try_from!(item: &protowire::RpcFeeEstimateVerboseExperimentalData, kaspa_rpc_core::RpcFeeEstimateVerboseExperimentalData, {
     Self {
         network_mass_per_second: item.network_mass_per_second,
@@ -62,5 +80,7 @@ try_from!(item: &protowire::RpcFeeEstimateVerboseExperimentalData, kaspa_rpc_cor
         next_block_template_feerate_min: item.next_block_template_feerate_min,
         next_block_template_feerate_median: item.next_block_template_feerate_median,
         next_block_template_feerate_max: item.next_block_template_feerate_max,
+        inclusion_observation_count: item.inclusion_observation_count,
+        inclusion_estimates: item.inclusion_estimates.iter().map(|e| e.try_into()).collect::<Result<Vec<_>, _>>()?,
     }
 });
@@ -67,6 +67,12 @@ pub mod kaspad_request_convert {
     impl_into_kaspad_request!(GetVirtualChainFromBlockV2);
     impl_into_kaspad_request!(GetBlockRewardInfo);
     impl_into_kaspad_request!(GetSeqCommitLaneProof);
+    impl_into_kaspad_request!(GetTransaction);
+    impl_into_kaspad_request!(GetAddressHistory);
+    impl_into_kaspad_request!(GetSeqCommitLaneProofs);
+    impl_into_kaspad_request!(ValidateTransactionScripts);
+    impl_into_kaspad_request!(SubmitTransactionPackage);
+    impl_into_kaspad_request!(GetBannedPeers);
 
     impl_into_kaspad_request!(NotifyBlockAdded);
     impl_into_kaspad_request!(NotifyNewBlockTemplate);
@@ -76,6 +82,10 @@ pub mod kaspad_request_convert {
     impl_into_kaspad_request!(NotifyVirtualDaaScoreChanged);
     impl_into_kaspad_request!(NotifyVirtualChainChanged);
     impl_into_kaspad_request!(NotifySinkBlueScoreChanged);
+    impl_into_kaspad_request!(NotifyMempoolTransactionAdded);
+    impl_into_kaspad_request!(NotifyMempoolTransactionRemoved);
+    impl_into_kaspad_request!(NotifyMempoolTransactionReplaced);
+    impl_into_kaspad_request!(NotifyMempoolTransactionIncluded);
 
     macro_rules! impl_into_kaspad_request {
         ($name:tt) => {
@@ -208,6 +218,12 @@ pub mod kaspad_response_convert {
     impl_into_kaspad_response!(GetVirtualChainFromBlockV2);
     impl_into_kaspad_response!(GetBlockRewardInfo);
     impl_into_kaspad_response!(GetSeqCommitLaneProof);
+    impl_into_kaspad_response!(GetTransaction);
+    impl_into_kaspad_response!(GetAddressHistory);
+    impl_into_kaspad_response!(GetSeqCommitLaneProofs);
+    impl_into_kaspad_response!(ValidateTransactionScripts);
+    impl_into_kaspad_response!(SubmitTransactionPackage);
+    impl_into_kaspad_response!(GetBannedPeers);
 
     impl_into_kaspad_notify_response!(NotifyBlockAdded);
     impl_into_kaspad_notify_response!(NotifyNewBlockTemplate);
@@ -217,6 +233,10 @@ pub mod kaspad_response_convert {
     impl_into_kaspad_notify_response!(NotifyVirtualDaaScoreChanged);
     impl_into_kaspad_notify_response!(NotifyVirtualChainChanged);
     impl_into_kaspad_notify_response!(NotifySinkBlueScoreChanged);
+    impl_into_kaspad_notify_response!(NotifyMempoolTransactionAdded);
+    impl_into_kaspad_notify_response!(NotifyMempoolTransactionRemoved);
+    impl_into_kaspad_notify_response!(NotifyMempoolTransactionReplaced);
+    impl_into_kaspad_notify_response!(NotifyMempoolTransactionIncluded);
 
     impl_into_kaspad_notify_response!(NotifyUtxosChanged, StopNotifyingUtxosChanged);
     impl_into_kaspad_notify_response!(NotifyPruningPointUtxoSetOverride, StopNotifyingPruningPointUtxoSetOverride);
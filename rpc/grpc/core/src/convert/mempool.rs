@@ -18,6 +18,14 @@ from!(item: &kaspa_rpc_core::RpcMempoolEntryByAddress, protowire::RpcMempoolEntr
     }
 });
 
+from!(item: &kaspa_rpc_core::RpcMempoolTransactionEntry, protowire::RpcMempoolTransactionEntry, {
+    Self {
+        fee: item.fee,
+        transaction: Some((&item.transaction).into()),
+        addresses: item.addresses.iter().map(|x| x.into()).collect(),
+    }
+});
+
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
@@ -40,3 +48,14 @@ try_from!(item: &protowire::RpcMempoolEntryByAddress, kaspa_rpc_core::RpcMempool
         item.receiving.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
     )
 });
+
+try_from!(item: &protowire::RpcMempoolTransactionEntry, kaspa_rpc_core::RpcMempoolTransactionEntry, {
+    Self::new(
+        item.fee,
+        item.transaction
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("RpcMempoolTransactionEntry".to_string(), "transaction".to_string()))?
+            .try_into()?,
+        item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+    )
+});
@@ -257,11 +257,44 @@ from!(item: RpcResult<&kaspa_rpc_core::SubmitTransactionReplacementResponse>, pr
     Self { transaction_id: item.transaction_id.to_string(), replaced_transaction: Some((&item.replaced_transaction).into()), error: None }
 });
 
+from!(item: &kaspa_rpc_core::SubmitTransactionPackageRequest, protowire::SubmitTransactionPackageRequestMessage, {
+    Self { transactions: item.transactions.iter().map(|x| x.into()).collect() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::SubmitTransactionPackageResponse>, protowire::SubmitTransactionPackageResponseMessage, {
+    Self { transaction_ids: item.transaction_ids.iter().map(|x| x.to_string()).collect(), error: None }
+});
+
+from!(item: &kaspa_rpc_core::ValidateTransactionScriptsRequest, protowire::ValidateTransactionScriptsRequestMessage, {
+    Self { transaction: Some((&item.transaction).into()), utxo_entries: item.utxo_entries.iter().map(|x| x.into()).collect() }
+});
+from!(item: &kaspa_rpc_core::RpcInputScriptValidation, protowire::RpcInputScriptValidation, {
+    Self { error: item.error.clone(), used_script_units: item.used_script_units, used_sig_ops: item.used_sig_ops.into() }
+});
+from!(item: RpcResult<&kaspa_rpc_core::ValidateTransactionScriptsResponse>, protowire::ValidateTransactionScriptsResponseMessage, {
+    Self {
+        transaction_id: item.transaction_id.to_string(),
+        fee: item.fee,
+        validation_error: item.error.clone(),
+        compute_mass: item.compute_mass,
+        transient_mass: item.transient_mass,
+        storage_mass: item.storage_mass,
+        inputs: item.inputs.iter().map(|x| x.into()).collect(),
+        error: None,
+    }
+});
+
 from!(item: &kaspa_rpc_core::GetSubnetworkRequest, protowire::GetSubnetworkRequestMessage, {
-    Self { subnetwork_id: item.subnetwork_id.to_string() }
+    Self { subnetwork_id: item.subnetwork_id.to_string(), block_hash: item.block_hash.map(|x| x.to_string()) }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetSubnetworkResponse>, protowire::GetSubnetworkResponseMessage, {
-    Self { gas_limit: item.gas_limit, error: None }
+    Self {
+        gas_limit: item.gas_limit,
+        block_hash: item.block_hash.to_string(),
+        lane_tip: item.lane.as_ref().map(|x| x.tip.to_string()),
+        lane_blue_score: item.lane.as_ref().map(|x| x.blue_score),
+        is_active: item.is_active,
+        error: None,
+    }
 });
 
 from!(item: &kaspa_rpc_core::GetVirtualChainFromBlockRequest, protowire::GetVirtualChainFromBlockRequestMessage, {
@@ -327,15 +360,29 @@ from!(item: &kaspa_rpc_core::GetHeadersRequest, protowire::GetHeadersRequestMess
     Self { start_hash: item.start_hash.to_string(), limit: item.limit, is_ascending: item.is_ascending }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetHeadersResponse>, protowire::GetHeadersResponseMessage, {
-    Self { headers: item.headers.iter().map(|x| x.hash.to_string()).collect(), error: None }
+    // The deprecated `headers` field carries the header hashes for clients of the former wire format
+    Self {
+        headers: item.headers.iter().map(|x| x.hash.to_string()).collect(),
+        block_headers: item.headers.iter().map(|x| x.into()).collect(),
+        error: None,
+    }
 });
 
 from!(item: &kaspa_rpc_core::GetUtxosByAddressesRequest, protowire::GetUtxosByAddressesRequestMessage, {
-    Self { addresses: item.addresses.iter().map(|x| x.into()).collect() }
+    Self {
+        addresses: item.addresses.iter().map(|x| x.into()).collect(),
+        cursor: item.cursor.as_ref().map(|x| x.into()),
+        limit: item.limit,
+        min_amount: item.min_amount,
+    }
 });
 from!(item: RpcResult<&kaspa_rpc_core::GetUtxosByAddressesResponse>, protowire::GetUtxosByAddressesResponseMessage, {
     debug!("GRPC, Creating GetUtxosByAddresses message with {} entries", item.entries.len());
-    Self { entries: item.entries.iter().map(|x| x.into()).collect(), error: None }
+    Self {
+        entries: item.entries.iter().map(|x| x.into()).collect(),
+        next_cursor: item.next_cursor.as_ref().map(|x| x.into()),
+        error: None,
+    }
 });
 
 from!(item: &kaspa_rpc_core::GetBalanceByAddressRequest, protowire::GetBalanceByAddressRequestMessage, {
@@ -365,6 +412,11 @@ from!(_item: RpcResult<&kaspa_rpc_core::BanResponse>, protowire::BanResponseMess
 from!(item: &kaspa_rpc_core::UnbanRequest, protowire::UnbanRequestMessage, { Self { ip: item.ip.to_string() } });
 from!(_item: RpcResult<&kaspa_rpc_core::UnbanResponse>, protowire::UnbanResponseMessage, { Self { error: None } });
 
+from!(&kaspa_rpc_core::GetBannedPeersRequest, protowire::GetBannedPeersRequestMessage);
+from!(item: RpcResult<&kaspa_rpc_core::GetBannedPeersResponse>, protowire::GetBannedPeersResponseMessage, {
+    Self { banned_peers: item.banned_peers.iter().map(|x| x.into()).collect(), error: None }
+});
+
 from!(item: &kaspa_rpc_core::EstimateNetworkHashesPerSecondRequest, protowire::EstimateNetworkHashesPerSecondRequestMessage, {
     Self { window_size: item.window_size, start_hash: item.start_hash.map_or(Default::default(), |x| x.to_string()) }
 });
@@ -445,6 +497,38 @@ from!(item: RpcResult<&kaspa_rpc_core::GetBlockRewardInfoResponse>, protowire::G
     }
 });
 
+from!(item: &kaspa_rpc_core::GetTransactionRequest, protowire::GetTransactionRequestMessage, {
+    Self { transaction_id: item.transaction_id.to_string(), include_transaction: item.include_transaction }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetTransactionResponse>, protowire::GetTransactionResponseMessage, {
+    Self {
+        transaction: item.transaction.as_ref().map(|x| x.into()),
+        status: item.status.into(),
+        including_block_hash: item.including_block_hash.as_ref().map(|x| x.to_string()),
+        accepting_block_hash: item.accepting_block_hash.as_ref().map(|x| x.to_string()),
+        accepting_block_daa_score: item.accepting_block_daa_score,
+        confirmation_count: item.confirmation_count,
+        error: None,
+    }
+});
+
+from!(item: &kaspa_rpc_core::GetAddressHistoryRequest, protowire::GetAddressHistoryRequestMessage, {
+    Self {
+        address: (&item.address).into(),
+        from_daa_score: item.from_daa_score,
+        to_daa_score: item.to_daa_score,
+        cursor: item.cursor.as_ref().map(|x| x.into()),
+        limit: item.limit,
+    }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetAddressHistoryResponse>, protowire::GetAddressHistoryResponseMessage, {
+    Self {
+        entries: item.entries.iter().map(|x| x.into()).collect(),
+        next_cursor: item.next_cursor.as_ref().map(|x| x.into()),
+        error: None,
+    }
+});
+
 from!(item: &kaspa_rpc_core::GetUtxoReturnAddressRequest, protowire::GetUtxoReturnAddressRequestMessage, {
     Self {
         txid: item.txid.to_string(),
@@ -558,6 +642,32 @@ from!(item: &kaspa_rpc_core::NotifyUtxosChangedRequest, protowire::StopNotifying
 from!(RpcResult<&kaspa_rpc_core::NotifyUtxosChangedResponse>, protowire::NotifyUtxosChangedResponseMessage);
 from!(RpcResult<&kaspa_rpc_core::NotifyUtxosChangedResponse>, protowire::StopNotifyingUtxosChangedResponseMessage);
 
+from!(item: &kaspa_rpc_core::NotifyMempoolTransactionAddedRequest, protowire::NotifyMempoolTransactionAddedRequestMessage, {
+    Self { addresses: item.addresses.iter().map(|x| x.into()).collect(), command: item.command.into() }
+});
+from!(RpcResult<&kaspa_rpc_core::NotifyMempoolTransactionAddedResponse>, protowire::NotifyMempoolTransactionAddedResponseMessage);
+
+from!(item: &kaspa_rpc_core::NotifyMempoolTransactionRemovedRequest, protowire::NotifyMempoolTransactionRemovedRequestMessage, {
+    Self { addresses: item.addresses.iter().map(|x| x.into()).collect(), command: item.command.into() }
+});
+from!(RpcResult<&kaspa_rpc_core::NotifyMempoolTransactionRemovedResponse>, protowire::NotifyMempoolTransactionRemovedResponseMessage);
+
+from!(item: &kaspa_rpc_core::NotifyMempoolTransactionReplacedRequest, protowire::NotifyMempoolTransactionReplacedRequestMessage, {
+    Self { addresses: item.addresses.iter().map(|x| x.into()).collect(), command: item.command.into() }
+});
+from!(
+    RpcResult<&kaspa_rpc_core::NotifyMempoolTransactionReplacedResponse>,
+    protowire::NotifyMempoolTransactionReplacedResponseMessage
+);
+
+from!(item: &kaspa_rpc_core::NotifyMempoolTransactionIncludedRequest, protowire::NotifyMempoolTransactionIncludedRequestMessage, {
+    Self { addresses: item.addresses.iter().map(|x| x.into()).collect(), command: item.command.into() }
+});
+from!(
+    RpcResult<&kaspa_rpc_core::NotifyMempoolTransactionIncludedResponse>,
+    protowire::NotifyMempoolTransactionIncludedResponseMessage
+);
+
 from!(item: &kaspa_rpc_core::NotifyPruningPointUtxoSetOverrideRequest, protowire::NotifyPruningPointUtxoSetOverrideRequestMessage, {
     Self { command: item.command.into() }
 });
@@ -606,6 +716,30 @@ from!(item: RpcResult<&kaspa_rpc_core::GetSeqCommitLaneProofResponse>, protowire
     }
 });
 
+from!(item: &kaspa_rpc_core::GetSeqCommitLaneProofsRequest, protowire::GetSeqCommitLaneProofsRequestMessage, {
+    Self {
+        block_hash: item.block_hash.as_bytes().to_vec(),
+        lane_keys: item.lane_keys.iter().map(|lane_key| lane_key.as_bytes().to_vec()).collect(),
+    }
+});
+from!(item: &kaspa_rpc_core::RpcSeqCommitLane, protowire::RpcSeqCommitLane, {
+    Self {
+        lane_key: item.lane_key.as_bytes().to_vec(),
+        lane_tip: item.lane.as_ref().map(|l| l.tip.as_bytes().to_vec()),
+        lane_blue_score: item.lane.as_ref().map(|l| l.blue_score),
+    }
+});
+from!(item: RpcResult<&kaspa_rpc_core::GetSeqCommitLaneProofsResponse>, protowire::GetSeqCommitLaneProofsResponseMessage, {
+    Self {
+        smt_proof: item.smt_proof.clone(),
+        lanes: item.lanes.iter().map(|x| x.into()).collect(),
+        payload_and_ctx_digest: item.payload_and_ctx_digest.as_bytes().to_vec(),
+        parent_seq_commit: item.parent_seq_commit.as_bytes().to_vec(),
+        inactivity_shortcut: item.inactivity_shortcut.as_bytes().to_vec(),
+        error: None,
+    }
+});
+
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
@@ -795,11 +929,55 @@ try_from!(item: &protowire::SubmitTransactionReplacementResponseMessage, RpcResu
     }
 });
 
+try_from!(item: &protowire::SubmitTransactionPackageRequestMessage, kaspa_rpc_core::SubmitTransactionPackageRequest, {
+    Self { transactions: item.transactions.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()? }
+});
+try_from!(item: &protowire::SubmitTransactionPackageResponseMessage, RpcResult<kaspa_rpc_core::SubmitTransactionPackageResponse>, {
+    Self { transaction_ids: item.transaction_ids.iter().map(|x| RpcHash::from_str(x)).collect::<Result<Vec<_>, _>>()? }
+});
+
+try_from!(item: &protowire::ValidateTransactionScriptsRequestMessage, kaspa_rpc_core::ValidateTransactionScriptsRequest, {
+    Self {
+        transaction: item
+            .transaction
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("ValidateTransactionScriptsRequestMessage".to_string(), "transaction".to_string()))?
+            .try_into()?,
+        utxo_entries: item.utxo_entries.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+try_from!(item: &protowire::RpcInputScriptValidation, kaspa_rpc_core::RpcInputScriptValidation, {
+    Self { error: item.error.clone(), used_script_units: item.used_script_units, used_sig_ops: item.used_sig_ops.try_into()? }
+});
+try_from!(item: &protowire::ValidateTransactionScriptsResponseMessage, RpcResult<kaspa_rpc_core::ValidateTransactionScriptsResponse>, {
+    Self {
+        transaction_id: RpcHash::from_str(&item.transaction_id)?,
+        fee: item.fee,
+        error: item.validation_error.clone(),
+        compute_mass: item.compute_mass,
+        transient_mass: item.transient_mass,
+        storage_mass: item.storage_mass,
+        inputs: item.inputs.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+    }
+});
+
 try_from!(item: &protowire::GetSubnetworkRequestMessage, kaspa_rpc_core::GetSubnetworkRequest, {
-    Self { subnetwork_id: kaspa_rpc_core::RpcSubnetworkId::from_str(&item.subnetwork_id)? }
+    Self {
+        subnetwork_id: kaspa_rpc_core::RpcSubnetworkId::from_str(&item.subnetwork_id)?,
+        block_hash: item.block_hash.as_ref().map(|x| RpcHash::from_str(x)).transpose()?,
+    }
 });
 try_from!(item: &protowire::GetSubnetworkResponseMessage, RpcResult<kaspa_rpc_core::GetSubnetworkResponse>, {
-    Self { gas_limit: item.gas_limit }
+    Self {
+        gas_limit: item.gas_limit,
+        block_hash: RpcHash::from_str(&item.block_hash)?,
+        lane: if let (Some(tip), Some(blue_score)) = (item.lane_tip.as_ref(), item.lane_blue_score) {
+            Some(kaspa_rpc_core::RpcLaneEntry { tip: RpcHash::from_str(tip)?, blue_score })
+        } else {
+            None
+        },
+        is_active: item.is_active,
+    }
 });
 
 try_from!(item: &protowire::GetVirtualChainFromBlockRequestMessage, kaspa_rpc_core::GetVirtualChainFromBlockRequest, {
@@ -879,15 +1057,22 @@ try_from!(item: &protowire::GetHeadersRequestMessage, kaspa_rpc_core::GetHeaders
     Self { start_hash: RpcHash::from_str(&item.start_hash)?, limit: item.limit, is_ascending: item.is_ascending }
 });
 try_from!(item: &protowire::GetHeadersResponseMessage, RpcResult<kaspa_rpc_core::GetHeadersResponse>, {
-    // TODO
-    Self { headers: vec![] }
+    Self { headers: item.block_headers.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()? }
 });
 
 try_from!(item: &protowire::GetUtxosByAddressesRequestMessage, kaspa_rpc_core::GetUtxosByAddressesRequest, {
-    Self { addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()? }
+    Self {
+        addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+        cursor: item.cursor.as_ref().map(|x| x.try_into()).transpose()?,
+        limit: item.limit,
+        min_amount: item.min_amount,
+    }
 });
 try_from!(item: &protowire::GetUtxosByAddressesResponseMessage, RpcResult<kaspa_rpc_core::GetUtxosByAddressesResponse>, {
-    Self { entries: item.entries.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()? }
+    Self {
+        entries: item.entries.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        next_cursor: item.next_cursor.as_ref().map(|x| x.try_into()).transpose()?,
+    }
 });
 
 try_from!(item: &protowire::GetBalanceByAddressRequestMessage, kaspa_rpc_core::GetBalanceByAddressRequest, {
@@ -915,6 +1100,11 @@ try_from!(&protowire::BanResponseMessage, RpcResult<kaspa_rpc_core::BanResponse>
 try_from!(item: &protowire::UnbanRequestMessage, kaspa_rpc_core::UnbanRequest, { Self { ip: RpcIpAddress::from_str(&item.ip)? } });
 try_from!(&protowire::UnbanResponseMessage, RpcResult<kaspa_rpc_core::UnbanResponse>);
 
+try_from!(&protowire::GetBannedPeersRequestMessage, kaspa_rpc_core::GetBannedPeersRequest);
+try_from!(item: &protowire::GetBannedPeersResponseMessage, RpcResult<kaspa_rpc_core::GetBannedPeersResponse>, {
+    Self { banned_peers: item.banned_peers.iter().map(kaspa_rpc_core::RpcBannedPeer::try_from).collect::<Result<Vec<_>, _>>()? }
+});
+
 try_from!(item: &protowire::EstimateNetworkHashesPerSecondRequestMessage, kaspa_rpc_core::EstimateNetworkHashesPerSecondRequest, {
     Self {
         window_size: item.window_size,
@@ -1006,6 +1196,34 @@ try_from!(item: &protowire::GetBlockRewardInfoResponseMessage, RpcResult<kaspa_r
         reward_amount: item.reward_amount,
     }
 });
+try_from!(item: &protowire::GetTransactionRequestMessage, kaspa_rpc_core::GetTransactionRequest, {
+    Self { transaction_id: RpcHash::from_str(&item.transaction_id)?, include_transaction: item.include_transaction }
+});
+try_from!(item: &protowire::GetTransactionResponseMessage, RpcResult<kaspa_rpc_core::GetTransactionResponse>, {
+    Self {
+        transaction: item.transaction.as_ref().map(|x| x.try_into()).transpose()?,
+        status: item.status.into(),
+        including_block_hash: item.including_block_hash.as_ref().map(|x| RpcHash::from_str(x)).transpose()?,
+        accepting_block_hash: item.accepting_block_hash.as_ref().map(|x| RpcHash::from_str(x)).transpose()?,
+        accepting_block_daa_score: item.accepting_block_daa_score,
+        confirmation_count: item.confirmation_count,
+    }
+});
+try_from!(item: &protowire::GetAddressHistoryRequestMessage, kaspa_rpc_core::GetAddressHistoryRequest, {
+    Self {
+        address: item.address.as_str().try_into()?,
+        from_daa_score: item.from_daa_score,
+        to_daa_score: item.to_daa_score,
+        cursor: item.cursor.as_ref().map(|x| x.try_into()).transpose()?,
+        limit: item.limit,
+    }
+});
+try_from!(item: &protowire::GetAddressHistoryResponseMessage, RpcResult<kaspa_rpc_core::GetAddressHistoryResponse>, {
+    Self {
+        entries: item.entries.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        next_cursor: item.next_cursor.as_ref().map(|x| x.try_into()).transpose()?,
+    }
+});
 try_from!(item: &protowire::GetUtxoReturnAddressRequestMessage, kaspa_rpc_core::GetUtxoReturnAddressRequest , {
     Self {
         txid: Hash::from_str(&item.txid).unwrap_or_default(),
@@ -1101,6 +1319,47 @@ try_from!(item: &protowire::StopNotifyingUtxosChangedRequestMessage, kaspa_rpc_c
 try_from!(&protowire::NotifyUtxosChangedResponseMessage, RpcResult<kaspa_rpc_core::NotifyUtxosChangedResponse>);
 try_from!(&protowire::StopNotifyingUtxosChangedResponseMessage, RpcResult<kaspa_rpc_core::NotifyUtxosChangedResponse>);
 
+try_from!(item: &protowire::NotifyMempoolTransactionAddedRequestMessage, kaspa_rpc_core::NotifyMempoolTransactionAddedRequest, {
+    Self {
+        addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+        command: item.command.into(),
+    }
+});
+try_from!(&protowire::NotifyMempoolTransactionAddedResponseMessage, RpcResult<kaspa_rpc_core::NotifyMempoolTransactionAddedResponse>);
+
+try_from!(item: &protowire::NotifyMempoolTransactionRemovedRequestMessage, kaspa_rpc_core::NotifyMempoolTransactionRemovedRequest, {
+    Self {
+        addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+        command: item.command.into(),
+    }
+});
+try_from!(
+    &protowire::NotifyMempoolTransactionRemovedResponseMessage,
+    RpcResult<kaspa_rpc_core::NotifyMempoolTransactionRemovedResponse>
+);
+
+try_from!(item: &protowire::NotifyMempoolTransactionReplacedRequestMessage, kaspa_rpc_core::NotifyMempoolTransactionReplacedRequest, {
+    Self {
+        addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+        command: item.command.into(),
+    }
+});
+try_from!(
+    &protowire::NotifyMempoolTransactionReplacedResponseMessage,
+    RpcResult<kaspa_rpc_core::NotifyMempoolTransactionReplacedResponse>
+);
+
+try_from!(item: &protowire::NotifyMempoolTransactionIncludedRequestMessage, kaspa_rpc_core::NotifyMempoolTransactionIncludedRequest, {
+    Self {
+        addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+        command: item.command.into(),
+    }
+});
+try_from!(
+    &protowire::NotifyMempoolTransactionIncludedResponseMessage,
+    RpcResult<kaspa_rpc_core::NotifyMempoolTransactionIncludedResponse>
+);
+
 try_from!(
     item: &protowire::NotifyPruningPointUtxoSetOverrideRequestMessage,
     kaspa_rpc_core::NotifyPruningPointUtxoSetOverrideRequest,
@@ -1157,6 +1416,32 @@ try_from!(item: &protowire::GetSeqCommitLaneProofResponseMessage, RpcResult<kasp
     }
 });
 
+try_from!(item: &protowire::GetSeqCommitLaneProofsRequestMessage, kaspa_rpc_core::GetSeqCommitLaneProofsRequest, {
+    Self {
+        block_hash: hash_from_bytes(&item.block_hash)?,
+        lane_keys: item.lane_keys.iter().map(|lane_key| hash_from_bytes(lane_key)).collect::<RpcResult<Vec<_>>>()?,
+    }
+});
+try_from!(item: &protowire::RpcSeqCommitLane, kaspa_rpc_core::RpcSeqCommitLane, {
+    Self {
+        lane_key: hash_from_bytes(&item.lane_key)?,
+        lane: if let (Some(tip), Some(blue_score)) = (item.lane_tip.as_ref(), item.lane_blue_score) {
+            Some(kaspa_rpc_core::RpcLaneEntry { tip: hash_from_bytes(tip)?, blue_score })
+        } else {
+            None
+        },
+    }
+});
+try_from!(item: &protowire::GetSeqCommitLaneProofsResponseMessage, RpcResult<kaspa_rpc_core::GetSeqCommitLaneProofsResponse>, {
+    Self {
+        smt_proof: item.smt_proof.clone(),
+        lanes: item.lanes.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?,
+        payload_and_ctx_digest: hash_from_bytes(&item.payload_and_ctx_digest)?,
+        parent_seq_commit: hash_from_bytes(&item.parent_seq_commit)?,
+        inactivity_shortcut: hash_from_bytes(&item.inactivity_shortcut)?,
+    }
+});
+
 fn hash_from_bytes(bytes: &[u8]) -> RpcResult<RpcHash> {
     <[u8; 32]>::try_from(bytes)
         .map(RpcHash::from_bytes)
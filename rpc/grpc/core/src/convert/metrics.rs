@@ -50,6 +50,9 @@ from!(item: &kaspa_rpc_core::BandwidthMetrics, protowire::BandwidthMetrics, {
         grpc_p2p_bytes_rx: item.p2p_bytes_rx,
         grpc_user_bytes_tx: item.grpc_bytes_tx,
         grpc_user_bytes_rx: item.grpc_bytes_rx,
+        p2p_compact_blocks_rx: item.p2p_compact_blocks_rx,
+        p2p_compact_block_txs_requested: item.p2p_compact_block_txs_requested,
+        p2p_compact_block_bytes_saved: item.p2p_compact_block_bytes_saved,
     }
 });
 
@@ -124,6 +127,9 @@ try_from!(item: &protowire::BandwidthMetrics, kaspa_rpc_core::BandwidthMetrics,
         p2p_bytes_rx: item.grpc_p2p_bytes_rx,
         grpc_bytes_tx: item.grpc_user_bytes_tx,
         grpc_bytes_rx: item.grpc_user_bytes_rx,
+        p2p_compact_blocks_rx: item.p2p_compact_blocks_rx,
+        p2p_compact_block_txs_requested: item.p2p_compact_block_txs_requested,
+        p2p_compact_block_bytes_saved: item.p2p_compact_block_bytes_saved,
     }
 });
 
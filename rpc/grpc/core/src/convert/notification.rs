@@ -2,7 +2,9 @@ use crate::protowire::{
     BlockAddedNotificationMessage, KaspadResponse, NewBlockTemplateNotificationMessage, RpcNotifyCommand, kaspad_response::Payload,
 };
 use crate::protowire::{
-    FinalityConflictNotificationMessage, FinalityConflictResolvedNotificationMessage, NotifyPruningPointUtxoSetOverrideRequestMessage,
+    FinalityConflictNotificationMessage, FinalityConflictResolvedNotificationMessage, MempoolTransactionAddedNotificationMessage,
+    MempoolTransactionIncludedNotificationMessage, MempoolTransactionRemovedNotificationMessage,
+    MempoolTransactionReplacedNotificationMessage, NotifyPruningPointUtxoSetOverrideRequestMessage,
     NotifyPruningPointUtxoSetOverrideResponseMessage, NotifyUtxosChangedRequestMessage, NotifyUtxosChangedResponseMessage,
     PruningPointUtxoSetOverrideNotificationMessage, SinkBlueScoreChangedNotificationMessage,
     StopNotifyingPruningPointUtxoSetOverrideRequestMessage, StopNotifyingPruningPointUtxoSetOverrideResponseMessage,
@@ -34,6 +36,10 @@ from!(item: &kaspa_rpc_core::Notification, Payload, {
         Notification::PruningPointUtxoSetOverride(notification) => {
             Payload::PruningPointUtxoSetOverrideNotification(notification.into())
         },
+        Notification::MempoolTransactionAdded(notification) => Payload::MempoolTransactionAddedNotification(notification.into()),
+        Notification::MempoolTransactionRemoved(notification) => Payload::MempoolTransactionRemovedNotification(notification.into()),
+        Notification::MempoolTransactionReplaced(notification) => Payload::MempoolTransactionReplacedNotification(notification.into()),
+        Notification::MempoolTransactionIncluded(notification) => Payload::MempoolTransactionIncludedNotification(notification.into()),
     }
 });
 
@@ -74,6 +80,22 @@ from!(item: &kaspa_rpc_core::VirtualDaaScoreChangedNotification, VirtualDaaScore
 
 from!(&kaspa_rpc_core::PruningPointUtxoSetOverrideNotification, PruningPointUtxoSetOverrideNotificationMessage);
 
+from!(item: &kaspa_rpc_core::MempoolTransactionAddedNotification, MempoolTransactionAddedNotificationMessage, {
+    Self { transactions: item.transactions.iter().map(|x| x.into()).collect() }
+});
+
+from!(item: &kaspa_rpc_core::MempoolTransactionRemovedNotification, MempoolTransactionRemovedNotificationMessage, {
+    Self { reason: item.reason.into(), transactions: item.transactions.iter().map(|x| x.into()).collect() }
+});
+
+from!(item: &kaspa_rpc_core::MempoolTransactionReplacedNotification, MempoolTransactionReplacedNotificationMessage, {
+    Self { replacing: Some((&item.replacing).into()), replaced: item.replaced.iter().map(|x| x.into()).collect() }
+});
+
+from!(item: &kaspa_rpc_core::MempoolTransactionIncludedNotification, MempoolTransactionIncludedNotificationMessage, {
+    Self { transactions: item.transactions.iter().map(|x| x.into()).collect() }
+});
+
 from!(item: Command, RpcNotifyCommand, {
     match item {
         Command::Start => RpcNotifyCommand::NotifyStart,
@@ -117,6 +139,18 @@ try_from!(item: &Payload, kaspa_rpc_core::Notification, {
         Payload::PruningPointUtxoSetOverrideNotification(notification) => {
             Notification::PruningPointUtxoSetOverride(notification.try_into()?)
         }
+        Payload::MempoolTransactionAddedNotification(notification) => {
+            Notification::MempoolTransactionAdded(notification.try_into()?)
+        }
+        Payload::MempoolTransactionRemovedNotification(notification) => {
+            Notification::MempoolTransactionRemoved(notification.try_into()?)
+        }
+        Payload::MempoolTransactionReplacedNotification(notification) => {
+            Notification::MempoolTransactionReplaced(notification.try_into()?)
+        }
+        Payload::MempoolTransactionIncludedNotification(notification) => {
+            Notification::MempoolTransactionIncluded(notification.try_into()?)
+        }
         _ => Err(RpcError::UnsupportedFeature)?,
     }
 });
@@ -171,6 +205,34 @@ try_from!(item: &VirtualDaaScoreChangedNotificationMessage, kaspa_rpc_core::Virt
 
 try_from!(&PruningPointUtxoSetOverrideNotificationMessage, kaspa_rpc_core::PruningPointUtxoSetOverrideNotification);
 
+try_from!(item: &MempoolTransactionAddedNotificationMessage, kaspa_rpc_core::MempoolTransactionAddedNotification, {
+    Self { transactions: Arc::new(item.transactions.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?) }
+});
+
+try_from!(item: &MempoolTransactionRemovedNotificationMessage, kaspa_rpc_core::MempoolTransactionRemovedNotification, {
+    Self {
+        reason: item.reason.into(),
+        transactions: Arc::new(item.transactions.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?),
+    }
+});
+
+try_from!(item: &MempoolTransactionReplacedNotificationMessage, kaspa_rpc_core::MempoolTransactionReplacedNotification, {
+    Self {
+        replacing: item
+            .replacing
+            .as_ref()
+            .ok_or_else(|| {
+                RpcError::MissingRpcFieldError("MempoolTransactionReplacedNotificationMessage".to_string(), "replacing".to_string())
+            })?
+            .try_into()?,
+        replaced: Arc::new(item.replaced.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?),
+    }
+});
+
+try_from!(item: &MempoolTransactionIncludedNotificationMessage, kaspa_rpc_core::MempoolTransactionIncludedNotification, {
+    Self { transactions: Arc::new(item.transactions.iter().map(|x| x.try_into()).collect::<Result<Vec<_>, _>>()?) }
+});
+
 from!(item: RpcNotifyCommand, Command, {
     match item {
         RpcNotifyCommand::NotifyStart => Command::Start,
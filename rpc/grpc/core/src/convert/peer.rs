@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use crate::protowire;
 use crate::{from, try_from};
-use kaspa_rpc_core::{RpcError, RpcNodeId, RpcPeerAddress};
+use kaspa_rpc_core::{RpcError, RpcHash, RpcIpAddress, RpcNodeId, RpcPeerAddress};
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
@@ -19,9 +19,17 @@ from!(item: &kaspa_rpc_core::RpcPeerInfo, protowire::GetConnectedPeerInfoMessage
         advertised_protocol_version: item.advertised_protocol_version,
         time_connected: item.time_connected as i64,
         is_ibd_peer: item.is_ibd_peer,
+        misbehavior_score: item.misbehavior_score,
+        misbehavior_reason: item.misbehavior_reason.clone().unwrap_or_default(),
+        is_encrypted: item.is_encrypted,
+        transport_session_id: item.transport_session_id.map(|session_id| session_id.to_string()).unwrap_or_default(),
     }
 });
 
+from!(item: &kaspa_rpc_core::RpcBannedPeer, protowire::BannedPeerMessage, {
+    Self { ip: item.ip.to_string(), ban_timestamp: item.ban_timestamp, expiry: item.expiry, reason: item.reason.clone() }
+});
+
 from!(item: &kaspa_rpc_core::RpcPeerAddress, protowire::GetPeerAddressesKnownAddressMessage, { Self { addr: item.to_string() } });
 from!(item: &kaspa_rpc_core::RpcIpAddress, protowire::GetPeerAddressesKnownAddressMessage, { Self { addr: item.to_string() } });
 
@@ -40,6 +48,23 @@ try_from!(item: &protowire::GetConnectedPeerInfoMessage, kaspa_rpc_core::RpcPeer
         advertised_protocol_version: item.advertised_protocol_version,
         time_connected: item.time_connected as u64,
         is_ibd_peer: item.is_ibd_peer,
+        misbehavior_score: item.misbehavior_score,
+        misbehavior_reason: (!item.misbehavior_reason.is_empty()).then(|| item.misbehavior_reason.clone()),
+        is_encrypted: item.is_encrypted,
+        transport_session_id: if item.transport_session_id.is_empty() {
+            None
+        } else {
+            Some(RpcHash::from_str(&item.transport_session_id)?)
+        },
+    }
+});
+
+try_from!(item: &protowire::BannedPeerMessage, kaspa_rpc_core::RpcBannedPeer, {
+    Self {
+        ip: RpcIpAddress::from_str(&item.ip)?,
+        ban_timestamp: item.ban_timestamp,
+        expiry: item.expiry,
+        reason: item.reason.clone(),
     }
 });
 
@@ -2,6 +2,8 @@ use kaspa_notify::{scope::Scope, subscription::Command};
 
 use crate::protowire::{
     KaspadRequest, KaspadResponse, NotifyBlockAddedRequestMessage, NotifyFinalityConflictRequestMessage,
+    NotifyMempoolTransactionAddedRequestMessage, NotifyMempoolTransactionIncludedRequestMessage,
+    NotifyMempoolTransactionRemovedRequestMessage, NotifyMempoolTransactionReplacedRequestMessage,
     NotifyNewBlockTemplateRequestMessage, NotifyPruningPointUtxoSetOverrideRequestMessage, NotifySinkBlueScoreChangedRequestMessage,
     NotifyUtxosChangedRequestMessage, NotifyVirtualChainChangedRequestMessage, NotifyVirtualDaaScoreChangedRequestMessage,
     kaspad_request, kaspad_response,
@@ -64,6 +66,30 @@ impl kaspad_request::Payload {
                     command: command.into(),
                 })
             }
+            Scope::MempoolTransactionAdded(scope) => {
+                kaspad_request::Payload::NotifyMempoolTransactionAddedRequest(NotifyMempoolTransactionAddedRequestMessage {
+                    addresses: scope.addresses.iter().map(|x| x.into()).collect::<Vec<String>>(),
+                    command: command.into(),
+                })
+            }
+            Scope::MempoolTransactionRemoved(scope) => {
+                kaspad_request::Payload::NotifyMempoolTransactionRemovedRequest(NotifyMempoolTransactionRemovedRequestMessage {
+                    addresses: scope.addresses.iter().map(|x| x.into()).collect::<Vec<String>>(),
+                    command: command.into(),
+                })
+            }
+            Scope::MempoolTransactionReplaced(scope) => {
+                kaspad_request::Payload::NotifyMempoolTransactionReplacedRequest(NotifyMempoolTransactionReplacedRequestMessage {
+                    addresses: scope.addresses.iter().map(|x| x.into()).collect::<Vec<String>>(),
+                    command: command.into(),
+                })
+            }
+            Scope::MempoolTransactionIncluded(scope) => {
+                kaspad_request::Payload::NotifyMempoolTransactionIncludedRequest(NotifyMempoolTransactionIncludedRequestMessage {
+                    addresses: scope.addresses.iter().map(|x| x.into()).collect::<Vec<String>>(),
+                    command: command.into(),
+                })
+            }
         }
     }
 
@@ -79,6 +105,10 @@ impl kaspad_request::Payload {
                 | Payload::NotifyVirtualDaaScoreChangedRequest(_)
                 | Payload::NotifyPruningPointUtxoSetOverrideRequest(_)
                 | Payload::NotifyNewBlockTemplateRequest(_)
+                | Payload::NotifyMempoolTransactionAddedRequest(_)
+                | Payload::NotifyMempoolTransactionRemovedRequest(_)
+                | Payload::NotifyMempoolTransactionReplacedRequest(_)
+                | Payload::NotifyMempoolTransactionIncludedRequest(_)
                 | Payload::StopNotifyingUtxosChangedRequest(_)
                 | Payload::StopNotifyingPruningPointUtxoSetOverrideRequest(_)
         )
@@ -108,6 +138,10 @@ impl kaspad_response::Payload {
             Payload::VirtualDaaScoreChangedNotification(_) => true,
             Payload::PruningPointUtxoSetOverrideNotification(_) => true,
             Payload::NewBlockTemplateNotification(_) => true,
+            Payload::MempoolTransactionAddedNotification(_) => true,
+            Payload::MempoolTransactionRemovedNotification(_) => true,
+            Payload::MempoolTransactionReplacedNotification(_) => true,
+            Payload::MempoolTransactionIncludedNotification(_) => true,
             _ => false,
         }
     }
@@ -91,6 +91,12 @@ pub enum KaspadPayloadOps {
     GetVirtualChainFromBlockV2,
     GetBlockRewardInfo,
     GetSeqCommitLaneProof,
+    GetTransaction,
+    GetAddressHistory,
+    GetSeqCommitLaneProofs,
+    ValidateTransactionScripts,
+    SubmitTransactionPackage,
+    GetBannedPeers,
 
     // Subscription commands for starting/stopping notifications
     NotifyBlockAdded,
@@ -101,6 +107,10 @@ pub enum KaspadPayloadOps {
     NotifyPruningPointUtxoSetOverride,
     NotifyVirtualDaaScoreChanged,
     NotifyVirtualChainChanged,
+    NotifyMempoolTransactionAdded,
+    NotifyMempoolTransactionRemoved,
+    NotifyMempoolTransactionReplaced,
+    NotifyMempoolTransactionIncluded,
 
     // Legacy stop subscription commands
     StopNotifyingUtxosChanged,
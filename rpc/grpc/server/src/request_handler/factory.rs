@@ -85,6 +85,12 @@ impl Factory {
                 GetUtxoReturnAddress,
                 GetVirtualChainFromBlockV2,
                 GetSeqCommitLaneProof,
+                GetTransaction,
+                GetAddressHistory,
+                GetSeqCommitLaneProofs,
+                ValidateTransactionScripts,
+                SubmitTransactionPackage,
+                GetBannedPeers,
                 NotifyBlockAdded,
                 NotifyNewBlockTemplate,
                 NotifyFinalityConflict,
@@ -93,6 +99,10 @@ impl Factory {
                 NotifyPruningPointUtxoSetOverride,
                 NotifyVirtualDaaScoreChanged,
                 NotifyVirtualChainChanged,
+                NotifyMempoolTransactionAdded,
+                NotifyMempoolTransactionRemoved,
+                NotifyMempoolTransactionReplaced,
+                NotifyMempoolTransactionIncluded,
                 StopNotifyingUtxosChanged,
                 StopNotifyingPruningPointUtxoSetOverride,
             ]
@@ -214,6 +214,30 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_seq_commit_lane_proofs_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: GetSeqCommitLaneProofsRequest,
+    ) -> RpcResult<GetSeqCommitLaneProofsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn submit_transaction_package_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: SubmitTransactionPackageRequest,
+    ) -> RpcResult<SubmitTransactionPackageResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn validate_transaction_scripts_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: ValidateTransactionScriptsRequest,
+    ) -> RpcResult<ValidateTransactionScriptsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     async fn get_subnetwork_call(
         &self,
         _connection: Option<&DynRpcConnection>,
@@ -254,6 +278,22 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_transaction_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: GetTransactionRequest,
+    ) -> RpcResult<GetTransactionResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_address_history_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: GetAddressHistoryRequest,
+    ) -> RpcResult<GetAddressHistoryResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     async fn get_block_count_call(
         &self,
         _connection: Option<&DynRpcConnection>,
@@ -330,6 +370,14 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_banned_peers_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: GetBannedPeersRequest,
+    ) -> RpcResult<GetBannedPeersResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     async fn estimate_network_hashes_per_second_call(
         &self,
         _connection: Option<&DynRpcConnection>,
@@ -1,6 +1,6 @@
-use kaspa_mining::feerate::{FeeEstimateVerbose, FeerateBucket, FeerateEstimations};
+use kaspa_mining::feerate::{FeeEstimateVerbose, FeerateBucket, FeerateEstimations, inclusion::InclusionFeerateEstimate};
 use kaspa_rpc_core::{
-    RpcFeeEstimate, RpcFeeEstimateVerboseExperimentalData as RpcFeeEstimateVerbose, RpcFeerateBucket,
+    RpcFeeEstimate, RpcFeeEstimateVerboseExperimentalData as RpcFeeEstimateVerbose, RpcFeerateBucket, RpcInclusionFeerateEstimate,
     message::GetFeeEstimateExperimentalResponse as RpcFeeEstimateVerboseResponse,
 };
 
@@ -14,6 +14,20 @@ impl FeerateBucketConverter for FeerateBucket {
     }
 }
 
+pub trait InclusionFeerateEstimateConverter {
+    fn into_rpc(self) -> RpcInclusionFeerateEstimate;
+}
+
+impl InclusionFeerateEstimateConverter for InclusionFeerateEstimate {
+    fn into_rpc(self) -> RpcInclusionFeerateEstimate {
+        RpcInclusionFeerateEstimate {
+            target_seconds: self.target_seconds,
+            confidence: self.confidence,
+            feerate: self.feerate.unwrap_or(-1.0),
+        }
+    }
+}
+
 pub trait FeeEstimateConverter {
     fn into_rpc(self) -> RpcFeeEstimate;
 }
@@ -43,6 +57,8 @@ impl FeeEstimateVerboseConverter for FeeEstimateVerbose {
                 next_block_template_feerate_min: self.next_block_template_feerate_min,
                 next_block_template_feerate_median: self.next_block_template_feerate_median,
                 next_block_template_feerate_max: self.next_block_template_feerate_max,
+                inclusion_observation_count: self.inclusion_observation_count,
+                inclusion_estimates: self.inclusion_estimations.into_iter().map(InclusionFeerateEstimateConverter::into_rpc).collect(),
             }),
         }
     }
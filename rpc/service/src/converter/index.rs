@@ -1,9 +1,12 @@
 use async_trait::async_trait;
 use kaspa_consensus_core::config::Config;
-use kaspa_index_core::indexed_utxos::UtxoSetByScriptPublicKey;
+use kaspa_index_core::indexed_utxos::{UtxoPage, UtxoSetByScriptPublicKey};
 use kaspa_index_core::notification::{self as index_notify, Notification as IndexNotification};
 use kaspa_notify::converter::Converter;
-use kaspa_rpc_core::{Notification, RpcUtxosByAddressesEntry, UtxosChangedNotification, utxo_set_into_rpc};
+use kaspa_rpc_core::{
+    Notification, RpcUtxosByAddressesCursor, RpcUtxosByAddressesEntry, UtxosChangedNotification, utxo_cursor_into_rpc,
+    utxo_page_into_rpc, utxo_set_into_rpc,
+};
 use std::sync::Arc;
 
 /// Conversion of consensus_core to rpc_core structures
@@ -27,6 +30,11 @@ impl IndexConverter {
     pub fn get_utxos_by_addresses_entries(&self, item: &UtxoSetByScriptPublicKey) -> Vec<RpcUtxosByAddressesEntry> {
         utxo_set_into_rpc(item, Some(self.config.prefix()))
     }
+
+    pub fn get_utxos_by_addresses_page(&self, item: &UtxoPage) -> (Vec<RpcUtxosByAddressesEntry>, Option<RpcUtxosByAddressesCursor>) {
+        let next_cursor = item.next_cursor.as_ref().and_then(|cursor| utxo_cursor_into_rpc(cursor, self.config.prefix()));
+        (utxo_page_into_rpc(item, self.config.prefix()), next_cursor)
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use kaspa_consensus_core::{config::Config, tx::MutableTransaction};
+use kaspa_mining::notification::{MempoolNotification, MempoolRemovalReason};
+use kaspa_notify::converter::Converter;
+use kaspa_rpc_core::{
+    MempoolTransactionAddedNotification, MempoolTransactionIncludedNotification, MempoolTransactionRemovedNotification,
+    MempoolTransactionReplacedNotification, Notification, RpcAddress, RpcMempoolRemovalReason, RpcMempoolTransactionEntry,
+};
+use kaspa_txscript::extract_script_pub_key_address;
+use std::sync::Arc;
+
+/// Conversion of mining mempool notifications to rpc_core structures
+#[derive(Debug)]
+pub struct MempoolConverter {
+    config: Arc<Config>,
+}
+
+impl MempoolConverter {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self { config }
+    }
+
+    /// Returns the addresses involved in a mempool transaction, both as recipient of an output and
+    /// as owner of a spent UTXO entry
+    pub fn get_transaction_addresses(&self, transaction: &MutableTransaction) -> Vec<RpcAddress> {
+        let mut addresses: Vec<RpcAddress> = Vec::new();
+        let output_spks = transaction.tx.outputs.iter().map(|x| &x.script_public_key);
+        let entry_spks = transaction.entries.iter().flatten().map(|x| &x.script_public_key);
+        for address in output_spks.chain(entry_spks).filter_map(|spk| extract_script_pub_key_address(spk, self.config.prefix()).ok()) {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        }
+        addresses
+    }
+
+    pub fn get_mempool_transaction_entry(&self, transaction: &MutableTransaction) -> RpcMempoolTransactionEntry {
+        RpcMempoolTransactionEntry::new(
+            transaction.calculated_fee.unwrap_or_default(),
+            (&*transaction.tx).into(),
+            self.get_transaction_addresses(transaction),
+        )
+    }
+
+    pub fn get_mempool_transaction_entries(&self, transactions: &[MutableTransaction]) -> Arc<Vec<RpcMempoolTransactionEntry>> {
+        Arc::new(transactions.iter().map(|x| self.get_mempool_transaction_entry(x)).collect())
+    }
+}
+
+#[async_trait]
+impl Converter for MempoolConverter {
+    type Incoming = MempoolNotification;
+    type Outgoing = Notification;
+
+    async fn convert(&self, incoming: MempoolNotification) -> Notification {
+        match incoming {
+            MempoolNotification::TransactionAdded(msg) => Notification::MempoolTransactionAdded(MempoolTransactionAddedNotification {
+                transactions: self.get_mempool_transaction_entries(&msg.transactions),
+            }),
+            MempoolNotification::TransactionRemoved(msg) => {
+                Notification::MempoolTransactionRemoved(MempoolTransactionRemovedNotification {
+                    reason: match msg.reason {
+                        MempoolRemovalReason::Evicted => RpcMempoolRemovalReason::Evicted,
+                        MempoolRemovalReason::Expired => RpcMempoolRemovalReason::Expired,
+                    },
+                    transactions: self.get_mempool_transaction_entries(&msg.transactions),
+                })
+            }
+            MempoolNotification::TransactionReplaced(msg) => {
+                Notification::MempoolTransactionReplaced(MempoolTransactionReplacedNotification {
+                    replacing: self.get_mempool_transaction_entry(&msg.replacing),
+                    replaced: self.get_mempool_transaction_entries(&msg.replaced),
+                })
+            }
+            MempoolNotification::TransactionIncluded(msg) => {
+                Notification::MempoolTransactionIncluded(MempoolTransactionIncludedNotification {
+                    transactions: self.get_mempool_transaction_entries(&msg.transactions),
+                })
+            }
+        }
+    }
+}
@@ -15,6 +15,7 @@ impl ProtocolConverter {
 
     fn get_peer_info(&self, peer: &Peer, ibd_peer_key: &Option<PeerKey>) -> RpcPeerInfo {
         let properties = peer.properties();
+        let misbehavior = self.flow_context.misbehavior_score(peer.net_address().ip().into());
         RpcPeerInfo {
             id: peer.identity(),
             address: peer.net_address().into(),
@@ -25,6 +26,10 @@ impl ProtocolConverter {
             user_agent: properties.user_agent.clone(),
             advertised_protocol_version: properties.advertised_protocol_version,
             time_connected: peer.time_connected(),
+            misbehavior_score: misbehavior.as_ref().map_or(0, |misbehavior| misbehavior.score),
+            misbehavior_reason: misbehavior.map(|misbehavior| misbehavior.reason),
+            is_encrypted: properties.transport_session_id.is_some(),
+            transport_session_id: properties.transport_session_id,
         }
     }
 
@@ -1,3 +1,4 @@
 pub mod collector;
 pub mod converter;
 pub mod service;
+pub(crate) mod subscription;
@@ -1,13 +1,18 @@
 //! Core server implementation for ClientAPI
 
-use super::collector::{CollectorFromConsensus, CollectorFromIndex};
+use super::collector::{CollectorFromConsensus, CollectorFromIndex, CollectorFromMempool};
 use crate::converter::feerate_estimate::{FeeEstimateConverter, FeeEstimateVerboseConverter};
-use crate::converter::{consensus::ConsensusConverter, index::IndexConverter, protocol::ProtocolConverter};
+use crate::converter::{consensus::ConsensusConverter, index::IndexConverter, mempool::MempoolConverter, protocol::ProtocolConverter};
+use crate::subscription::MempoolSubscriptionManager;
 use async_trait::async_trait;
+use kaspa_addressindex::api::AddressIndexProxy;
+use kaspa_addressindex::model::AddressHistoryCursor;
 use kaspa_consensus_core::api::counters::ProcessingCounters;
 use kaspa_consensus_core::daa_score_timestamp::DaaScoreTimestamp;
 use kaspa_consensus_core::errors::block::RuleError;
-use kaspa_consensus_core::tx::{TransactionQueryResult, TransactionType};
+use kaspa_consensus_core::errors::consensus::ConsensusResult;
+use kaspa_consensus_core::header::Header;
+use kaspa_consensus_core::tx::{ScriptPublicKey, TransactionQueryResult, TransactionType};
 use kaspa_consensus_core::utxo::utxo_inquirer::UtxoInquirerError;
 use kaspa_consensus_core::{
     block::Block,
@@ -15,7 +20,7 @@ use kaspa_consensus_core::{
     config::Config,
     constants::MAX_SOMPI,
     network::NetworkType,
-    tx::{COINBASE_TRANSACTION_INDEX, Transaction},
+    tx::{COINBASE_TRANSACTION_INDEX, MutableTransaction, Transaction, UtxoEntry},
 };
 use kaspa_consensus_notify::{
     notifier::ConsensusNotifier,
@@ -34,12 +39,14 @@ use kaspa_core::{
 };
 use kaspa_index_core::indexed_utxos::BalanceByScriptPublicKey;
 use kaspa_index_core::{
-    connection::IndexChannelConnection, indexed_utxos::UtxoSetByScriptPublicKey, notification::Notification as IndexNotification,
+    connection::IndexChannelConnection,
+    indexed_utxos::{UtxoCursor, UtxoPage},
+    notification::Notification as IndexNotification,
     notifier::IndexNotifier,
 };
 use kaspa_mining::feerate::FeeEstimateVerbose;
 use kaspa_mining::model::tx_query::TransactionQuery;
-use kaspa_mining::{manager::MiningManagerProxy, mempool::tx::Orphan};
+use kaspa_mining::{manager::MiningManagerProxy, mempool::tx::Orphan, notification::MempoolNotificationReceiver};
 use kaspa_notify::listener::ListenerLifespan;
 use kaspa_notify::subscription::context::SubscriptionContext;
 use kaspa_notify::subscription::{MutationPolicies, UtxosChangedMutationPolicy};
@@ -61,12 +68,14 @@ use kaspa_rpc_core::{
     api::{
         connection::DynRpcConnection,
         ops::{RPC_API_REVISION, RPC_API_VERSION},
-        rpc::{MAX_SAFE_WINDOW_SIZE, RpcApi},
+        rpc::{MAX_ADDRESS_HISTORY_PAGE_SIZE, MAX_HEADERS_LIMIT, MAX_SAFE_WINDOW_SIZE, MAX_SEQ_COMMIT_LANE_PROOFS, RpcApi},
     },
     model::*,
     notify::connection::ChannelConnection,
 };
+use kaspa_seq_commit::hashing::lane_key;
 use kaspa_system_info::SystemInfo;
+use kaspa_txindex::api::TxIndexProxy;
 use kaspa_txscript::{extract_script_pub_key_address, pay_to_address_script};
 use kaspa_utils::expiring_cache::ExpiringCache;
 use kaspa_utils::{channel::Channel, triggers::SingleTrigger};
@@ -74,7 +83,7 @@ use kaspa_utils_tower::counters::TowerConnectionCounters;
 use kaspa_utxoindex::api::UtxoIndexProxy;
 use std::time::Duration;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     iter::once,
     sync::{Arc, atomic::Ordering},
     vec,
@@ -105,6 +114,8 @@ pub struct RpcCoreService {
     mining_manager: MiningManagerProxy,
     flow_context: Arc<FlowContext>,
     utxoindex: Option<UtxoIndexProxy>,
+    txindex: Option<TxIndexProxy>,
+    addressindex: Option<AddressIndexProxy>,
     config: Arc<Config>,
     consensus_converter: Arc<ConsensusConverter>,
     index_converter: Arc<IndexConverter>,
@@ -135,9 +146,12 @@ impl RpcCoreService {
         consensus_notifier: Arc<ConsensusNotifier>,
         index_notifier: Option<Arc<IndexNotifier>>,
         mining_manager: MiningManagerProxy,
+        mempool_notification_receiver: Option<MempoolNotificationReceiver>,
         flow_context: Arc<FlowContext>,
         subscription_context: SubscriptionContext,
         utxoindex: Option<UtxoIndexProxy>,
+        txindex: Option<TxIndexProxy>,
+        addressindex: Option<AddressIndexProxy>,
         config: Arc<Config>,
         core: Arc<Core>,
         processing_counters: Arc<ProcessingCounters>,
@@ -166,6 +180,10 @@ impl RpcCoreService {
         let mut consensus_events: EventSwitches = EVENT_TYPE_ARRAY[..].into();
         consensus_events[EventType::UtxosChanged] = false;
         consensus_events[EventType::PruningPointUtxoSetOverride] = index_notifier.is_none();
+        consensus_events[EventType::MempoolTransactionAdded] = false;
+        consensus_events[EventType::MempoolTransactionRemoved] = false;
+        consensus_events[EventType::MempoolTransactionReplaced] = false;
+        consensus_events[EventType::MempoolTransactionIncluded] = false;
         let consensus_converter = Arc::new(ConsensusConverter::new(consensus_manager.clone(), config.clone()));
         let consensus_collector = Arc::new(CollectorFromConsensus::new(
             "rpc-core <= consensus",
@@ -197,6 +215,22 @@ impl RpcCoreService {
             subscribers.push(index_subscriber);
         }
 
+        // Prepare the mempool objects if the mining manager emits notifications
+        //
+        // The subscriber activates the mining manager notifications only while some listener is subscribed to them.
+        if let Some(mempool_notification_receiver) = mempool_notification_receiver {
+            let mempool_converter = Arc::new(MempoolConverter::new(config.clone()));
+            let mempool_collector =
+                Arc::new(CollectorFromMempool::new("rpc-core <= mempool", mempool_notification_receiver, mempool_converter));
+            let mempool_events: EventSwitches = MempoolSubscriptionManager::EVENT_TYPES.as_ref().into();
+            let mempool_subscription_manager = Arc::new(MempoolSubscriptionManager::new(mining_manager.clone()));
+            let mempool_subscriber =
+                Arc::new(Subscriber::new("rpc-core => mempool", mempool_events, mempool_subscription_manager, Default::default()));
+
+            collectors.push(mempool_collector);
+            subscribers.push(mempool_subscriber);
+        }
+
         // Protocol converter
         let protocol_converter = Arc::new(ProtocolConverter::new(flow_context.clone()));
 
@@ -210,6 +244,8 @@ impl RpcCoreService {
             mining_manager,
             flow_context,
             utxoindex,
+            txindex,
+            addressindex,
             config,
             consensus_converter,
             index_converter,
@@ -254,14 +290,17 @@ impl RpcCoreService {
         self.core_shutdown_request.listener.clone()
     }
 
-    async fn get_utxo_set_by_script_public_key<'a>(
+    async fn get_utxo_page_by_script_public_keys(
         &self,
-        addresses: impl Iterator<Item = &'a RpcAddress>,
-    ) -> UtxoSetByScriptPublicKey {
+        script_public_keys: Vec<ScriptPublicKey>,
+        resume_from: Option<UtxoCursor>,
+        min_amount: u64,
+        limit: usize,
+    ) -> UtxoPage {
         self.utxoindex
             .clone()
             .unwrap()
-            .get_utxos_by_script_public_keys(addresses.map(pay_to_address_script).collect())
+            .get_utxos_page_by_script_public_keys(script_public_keys, resume_from, min_amount, limit)
             .await
             .unwrap_or_default()
     }
@@ -489,6 +528,108 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         ))
     }
 
+    async fn get_transaction_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        request: GetTransactionRequest,
+    ) -> RpcResult<GetTransactionResponse> {
+        let session = self.consensus_manager.consensus().session().await;
+
+        // Pending transactions are served from the mempool regardless of whether the txindex is enabled
+        if let Some(transaction) =
+            self.mining_manager.clone().get_transaction(request.transaction_id, TransactionQuery::TransactionsOnly).await
+        {
+            let transaction =
+                request.include_transaction.then(|| self.consensus_converter.get_mempool_entry(&session, &transaction).transaction);
+            return Ok(GetTransactionResponse::new(transaction, RpcTransactionStatus::Pending, None, None, None, None));
+        }
+
+        let Some(txindex) = self.txindex.clone() else {
+            return Err(RpcError::NoTxIndex);
+        };
+        let Some(entry) =
+            txindex.get_transaction_entry(request.transaction_id).await.map_err(|err| RpcError::General(err.to_string()))?
+        else {
+            return Err(RpcError::TransactionNotFound(request.transaction_id));
+        };
+
+        let transaction = if request.include_transaction {
+            let including_header = session.async_get_header(entry.including_block_hash).await?;
+            let transactions =
+                session.async_get_block_transactions(entry.including_block_hash, Some(vec![entry.index_within_block])).await?;
+            let transaction = transactions.first().ok_or_else(|| {
+                RpcError::General(format!(
+                    "missing transaction {} at index {} of block {}",
+                    request.transaction_id, entry.index_within_block, entry.including_block_hash
+                ))
+            })?;
+            Some(
+                self.consensus_converter
+                    .get_transaction(&session, transaction, Some(including_header.as_ref()), true)
+                    .map_err(|err| RpcError::General(err.to_string()))?,
+            )
+        } else {
+            None
+        };
+
+        let accepting_header = session.async_get_header(entry.accepting_block_hash).await?;
+        let confirmation_count = session.async_get_sink_blue_score().await.saturating_sub(accepting_header.blue_score);
+
+        Ok(GetTransactionResponse::new(
+            transaction,
+            RpcTransactionStatus::Accepted,
+            Some(entry.including_block_hash),
+            Some(entry.accepting_block_hash),
+            Some(entry.accepting_daa_score),
+            Some(confirmation_count),
+        ))
+    }
+
+    async fn get_address_history_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        request: GetAddressHistoryRequest,
+    ) -> RpcResult<GetAddressHistoryResponse> {
+        let Some(addressindex) = self.addressindex.clone() else {
+            return Err(RpcError::NoAddressIndex);
+        };
+        if request.address.prefix != self.config.prefix() {
+            return Err(kaspa_addresses::AddressError::InvalidPrefix(request.address.prefix.to_string()))?;
+        }
+
+        let limit = request.limit.unwrap_or(MAX_ADDRESS_HISTORY_PAGE_SIZE).clamp(1, MAX_ADDRESS_HISTORY_PAGE_SIZE) as usize;
+        let cursor = request.cursor.map(|cursor| AddressHistoryCursor {
+            accepting_daa_score: cursor.accepting_daa_score,
+            transaction_id: cursor.transaction_id,
+        });
+        // Fetch one extra entry to find out whether another page follows
+        let mut entries = addressindex
+            .get_history(
+                pay_to_address_script(&request.address),
+                request.from_daa_score.unwrap_or_default(),
+                request.to_daa_score.unwrap_or(u64::MAX),
+                cursor,
+                limit + 1,
+            )
+            .await
+            .map_err(|err| RpcError::General(err.to_string()))?;
+        let has_more = entries.len() > limit;
+        entries.truncate(limit);
+
+        let entries: Vec<RpcAddressHistoryEntry> = entries
+            .into_iter()
+            .map(|entry| RpcAddressHistoryEntry {
+                transaction_id: entry.transaction_id,
+                accepting_block_hash: entry.accepting_block_hash,
+                accepting_daa_score: entry.accepting_daa_score,
+                received_amount: entry.received_amount,
+                spent_amount: entry.spent_amount,
+            })
+            .collect();
+        let next_cursor = if has_more { entries.last().map(|entry| entry.cursor()) } else { None };
+        Ok(GetAddressHistoryResponse::new(entries, next_cursor))
+    }
+
     async fn get_block_call(&self, _connection: Option<&DynRpcConnection>, request: GetBlockRequest) -> RpcResult<GetBlockResponse> {
         // TODO: test
         let session = self.consensus_manager.consensus().session().await;
@@ -517,6 +658,32 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         })
     }
 
+    async fn get_seq_commit_lane_proofs_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        request: GetSeqCommitLaneProofsRequest,
+    ) -> RpcResult<GetSeqCommitLaneProofsResponse> {
+        if request.lane_keys.is_empty() || request.lane_keys.len() > MAX_SEQ_COMMIT_LANE_PROOFS {
+            return Err(RpcError::InvalidLaneKeyCount(request.lane_keys.len(), MAX_SEQ_COMMIT_LANE_PROOFS));
+        }
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let proofs = session.async_get_seq_commit_lane_proofs(request.block_hash, request.lane_keys).await?;
+        Ok(GetSeqCommitLaneProofsResponse {
+            smt_proof: proofs.smt_proof.to_bytes(),
+            lanes: proofs
+                .lanes
+                .into_iter()
+                .map(|(lane_key, lane)| RpcSeqCommitLane {
+                    lane_key,
+                    lane: lane.map(|l| RpcLaneEntry { tip: l.tip, blue_score: l.blue_score }),
+                })
+                .collect(),
+            payload_and_ctx_digest: proofs.payload_and_ctx_digest,
+            parent_seq_commit: proofs.parent_seq_commit,
+            inactivity_shortcut: proofs.inactivity_shortcut,
+        })
+    }
+
     async fn get_blocks_call(
         &self,
         _connection: Option<&DynRpcConnection>,
@@ -675,6 +842,52 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         Ok(SubmitTransactionResponse::new(transaction_id))
     }
 
+    async fn submit_transaction_package_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        request: SubmitTransactionPackageRequest,
+    ) -> RpcResult<SubmitTransactionPackageResponse> {
+        let transactions = request.transactions.into_iter().map(Transaction::try_from).collect::<Result<Vec<_>, _>>()?;
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let accepted_transactions = self.flow_context.submit_rpc_transaction_package(&session, transactions).await.map_err(|err| {
+            let err = RpcError::RejectedTransactionPackage(err.to_string());
+            debug!("{err}");
+            err
+        })?;
+        Ok(SubmitTransactionPackageResponse::new(accepted_transactions.into_iter().map(|tx| tx.id()).collect()))
+    }
+
+    async fn validate_transaction_scripts_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        request: ValidateTransactionScriptsRequest,
+    ) -> RpcResult<ValidateTransactionScriptsResponse> {
+        let transaction: Transaction = request.transaction.try_into()?;
+        let transaction_id = transaction.id();
+        let mutable_tx = if request.utxo_entries.is_empty() {
+            MutableTransaction::from_tx(transaction)
+        } else if request.utxo_entries.len() == transaction.inputs.len() {
+            MutableTransaction::with_entries(transaction, request.utxo_entries.into_iter().map(UtxoEntry::from).collect())
+        } else {
+            return Err(RpcError::UtxoEntriesCountMismatch(request.utxo_entries.len(), transaction.inputs.len()));
+        };
+
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let report = session
+            .async_validate_transaction_scripts(mutable_tx)
+            .await
+            .map_err(|err| RpcError::UnvalidatableTransaction(transaction_id, err.to_string()))?;
+        Ok(ValidateTransactionScriptsResponse {
+            transaction_id,
+            fee: report.fee.as_ref().ok().copied(),
+            error: report.fee.as_ref().err().map(|err| err.to_string()),
+            compute_mass: report.non_contextual_masses.compute_mass,
+            transient_mass: report.non_contextual_masses.transient_mass,
+            storage_mass: report.contextual_masses.storage_mass,
+            inputs: report.inputs.iter().map(RpcInputScriptValidation::from).collect(),
+        })
+    }
+
     async fn submit_transaction_replacement_call(
         &self,
         _connection: Option<&DynRpcConnection>,
@@ -703,9 +916,21 @@ NOTE: This error usually indicates an RPC conversion error between the node and
     async fn get_subnetwork_call(
         &self,
         _connection: Option<&DynRpcConnection>,
-        _: GetSubnetworkRequest,
+        request: GetSubnetworkRequest,
     ) -> RpcResult<GetSubnetworkResponse> {
-        Err(RpcError::NotImplemented)
+        let session = self.consensus_manager.consensus().unguarded_session();
+        let block_hash = match request.block_hash {
+            Some(block_hash) => block_hash,
+            None => session.async_get_sink().await,
+        };
+        // Lanes are keyed by the hash of the 20-byte subnetwork id
+        let state = session.async_get_seq_commit_lane_state(block_hash, lane_key(request.subnetwork_id.as_bytes())).await?;
+        Ok(GetSubnetworkResponse::new(
+            self.config.block_lane_limits.gas_per_lane,
+            block_hash,
+            state.lane.map(|l| RpcLaneEntry { tip: l.tip, blue_score: l.blue_score }),
+            state.is_active,
+        ))
     }
 
     async fn get_sink_call(&self, _connection: Option<&DynRpcConnection>, _: GetSinkRequest) -> RpcResult<GetSinkResponse> {
@@ -796,10 +1021,31 @@ NOTE: This error usually indicates an RPC conversion error between the node and
             return Err(RpcError::ConsensusInTransitionalIbdState);
         }
 
-        // TODO: discuss if the entry order is part of the method requirements
-        //       (the current impl does not retain an entry order matching the request addresses order)
-        let entry_map = self.get_utxo_set_by_script_public_key(request.addresses.iter()).await;
-        Ok(GetUtxosByAddressesResponse::new(self.index_converter.get_utxos_by_addresses_entries(&entry_map)))
+        // Entries are ordered by address, following the request order, and then by outpoint
+        let mut seen = HashSet::with_capacity(request.addresses.len());
+        let script_public_keys = request
+            .addresses
+            .iter()
+            .map(pay_to_address_script)
+            .filter(|script_public_key| seen.insert(script_public_key.clone()))
+            .collect::<Vec<_>>();
+        let resume_from = match request.cursor {
+            Some(cursor) => {
+                let script_public_key = pay_to_address_script(&cursor.address);
+                if !seen.contains(&script_public_key) {
+                    return Err(RpcError::General(format!("Cursor address {} is not one of the requested addresses", cursor.address)));
+                }
+                Some(UtxoCursor::new(script_public_key, cursor.outpoint.into()))
+            }
+            None => None,
+        };
+        let limit = request.limit.map_or(usize::MAX, |limit| limit.max(1) as usize);
+
+        let page = self
+            .get_utxo_page_by_script_public_keys(script_public_keys, resume_from, request.min_amount.unwrap_or_default(), limit)
+            .await;
+        let (entries, next_cursor) = self.index_converter.get_utxos_by_addresses_page(&page);
+        Ok(GetUtxosByAddressesResponse::new(entries, next_cursor))
     }
 
     async fn get_balance_by_address_call(
@@ -1018,9 +1264,43 @@ NOTE: This error usually indicates an RPC conversion error between the node and
     async fn get_headers_call(
         &self,
         _connection: Option<&DynRpcConnection>,
-        _request: GetHeadersRequest,
+        request: GetHeadersRequest,
     ) -> RpcResult<GetHeadersResponse> {
-        Err(RpcError::NotImplemented)
+        let limit = request.limit.min(MAX_HEADERS_LIMIT) as usize;
+        if limit == 0 {
+            return Ok(GetHeadersResponse::new(vec![]));
+        }
+
+        let session = self.consensus_manager.consensus().session().await;
+        let GetHeadersRequest { start_hash, is_ascending, .. } = request;
+        let headers = session
+            .spawn_blocking(move |c| -> RpcResult<Vec<Arc<Header>>> {
+                // Headers (and ghostdag data) are only guaranteed to be available for chain blocks within the retention period
+                let retention_period_root = c.get_retention_period_root();
+                if !c.is_chain_block(start_hash)? || !c.is_chain_ancestor_of(retention_period_root, start_hash)? {
+                    return Err(RpcError::NotChainBlock(start_hash));
+                }
+
+                let hashes = if is_ascending {
+                    // Since `start_hash` is a chain block, the chain path contains no removed blocks
+                    let chain_path = c.get_virtual_chain_from_block(start_hash, Some(limit - 1))?;
+                    once(start_hash).chain(chain_path.added).collect::<Vec<_>>()
+                } else {
+                    let mut hashes = Vec::with_capacity(limit);
+                    let mut current = start_hash;
+                    hashes.push(current);
+                    while hashes.len() < limit && current != retention_period_root {
+                        current = c.get_ghostdag_data(current)?.selected_parent;
+                        hashes.push(current);
+                    }
+                    hashes
+                };
+
+                Ok(hashes.into_iter().map(|hash| c.get_header(hash)).collect::<ConsensusResult<Vec<_>>>()?)
+            })
+            .await?;
+
+        Ok(GetHeadersResponse::new(headers.iter().map(|header| header.as_ref().into()).collect()))
     }
 
     async fn get_block_dag_info_call(
@@ -1131,6 +1411,19 @@ NOTE: This error usually indicates an RPC conversion error between the node and
         Ok(UnbanResponse {})
     }
 
+    async fn get_banned_peers_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _: GetBannedPeersRequest,
+    ) -> RpcResult<GetBannedPeersResponse> {
+        let bans = self.flow_context.address_manager.lock().get_active_bans();
+        Ok(GetBannedPeersResponse::new(
+            bans.into_iter()
+                .map(|ban| RpcBannedPeer { ip: ban.ip, ban_timestamp: ban.timestamp, expiry: ban.expiry, reason: ban.reason })
+                .collect(),
+        ))
+    }
+
     async fn get_connected_peer_info_call(
         &self,
         _connection: Option<&DynRpcConnection>,
@@ -1230,6 +1523,7 @@ NOTE: This error usually indicates an RPC conversion error between the node and
             active_peers: self.flow_context.hub().active_peers_len() as u32,
         });
 
+        let compact_block_relay_counters = self.flow_context.compact_block_relay_counters();
         let bandwidth_metrics = req.bandwidth_metrics.then(|| BandwidthMetrics {
             borsh_bytes_tx: self.wrpc_borsh_counters.tx_bytes.load(Ordering::Relaxed) as u64,
             borsh_bytes_rx: self.wrpc_borsh_counters.rx_bytes.load(Ordering::Relaxed) as u64,
@@ -1239,6 +1533,9 @@ NOTE: This error usually indicates an RPC conversion error between the node and
             p2p_bytes_rx: self.p2p_tower_counters.bytes_rx.load(Ordering::Relaxed) as u64,
             grpc_bytes_tx: self.grpc_tower_counters.bytes_tx.load(Ordering::Relaxed) as u64,
             grpc_bytes_rx: self.grpc_tower_counters.bytes_rx.load(Ordering::Relaxed) as u64,
+            p2p_compact_blocks_rx: compact_block_relay_counters.blocks_received.load(Ordering::Relaxed),
+            p2p_compact_block_txs_requested: compact_block_relay_counters.transactions_requested.load(Ordering::Relaxed),
+            p2p_compact_block_bytes_saved: compact_block_relay_counters.bytes_saved.load(Ordering::Relaxed),
         });
 
         let consensus_metrics = if req.consensus_metrics {
@@ -1424,6 +1721,18 @@ NOTE: This error usually indicates an RPC conversion error between the node and
                 warn!("RPC subscription to blanket UtxosChanged called while node in safe RPC mode -- ignoring.");
                 Err(RpcError::UnavailableInSafeMode)
             }
+            Scope::MempoolTransactionAdded(_)
+            | Scope::MempoolTransactionRemoved(_)
+            | Scope::MempoolTransactionReplaced(_)
+            | Scope::MempoolTransactionIncluded(_)
+                if !self.config.unsafe_rpc && scope.addresses().is_some_and(|addresses| addresses.is_empty()) =>
+            {
+                // Same as above, blanket mempool notifications carry every transaction entering or leaving the mempool
+                // and are thus restricted to unsafe mode only.
+
+                warn!("RPC subscription to blanket {} called while node in safe RPC mode -- ignoring.", EventType::from(&scope));
+                Err(RpcError::UnavailableInSafeMode)
+            }
             _ => {
                 self.notifier.clone().start_notify(id, scope).await?;
                 Ok(())
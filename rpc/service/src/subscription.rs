@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use kaspa_mining::manager::MiningManagerProxy;
+use kaspa_notify::{
+    error::Result,
+    events::{EventArray, EventType},
+    listener::ListenerId,
+    scope::Scope,
+    subscriber::SubscriptionManager,
+    subscription::{
+        Command, DynSubscription, MutateSingle, Mutation, MutationPolicies, UtxosChangedMutationPolicy, array::ArrayBuilder,
+        context::SubscriptionContext,
+    },
+};
+use std::{fmt::Debug, sync::Mutex};
+
+/// Subscription manager activating the mempool notifications of the mining manager only while
+/// some listener is subscribed to any of them
+///
+/// The mining manager does not record the mempool changes at all while inactive.
+pub(crate) struct MempoolSubscriptionManager {
+    mining_manager: MiningManagerProxy,
+    /// Context of the subscriptions, kept apart from the one of the rpc-core notifier
+    context: SubscriptionContext,
+    state: Mutex<MempoolSubscriptionState>,
+}
+
+struct MempoolSubscriptionState {
+    subscriptions: EventArray<DynSubscription>,
+    active: bool,
+}
+
+impl MempoolSubscriptionManager {
+    pub(crate) const EVENT_TYPES: [EventType; 4] = [
+        EventType::MempoolTransactionAdded,
+        EventType::MempoolTransactionRemoved,
+        EventType::MempoolTransactionReplaced,
+        EventType::MempoolTransactionIncluded,
+    ];
+
+    const LISTENER_ID: ListenerId = 0;
+
+    pub(crate) fn new(mining_manager: MiningManagerProxy) -> Self {
+        let state = MempoolSubscriptionState { subscriptions: ArrayBuilder::single(Self::LISTENER_ID, None), active: false };
+        Self { mining_manager, context: SubscriptionContext::new(), state: Mutex::new(state) }
+    }
+
+    async fn execute(&self, scope: Scope, command: Command) -> Result<()> {
+        let active = {
+            let mut state = self.state.lock().unwrap();
+            let policies = MutationPolicies::new(UtxosChangedMutationPolicy::Wildcard);
+            state.subscriptions[scope.event_type()].mutate(Mutation::new(command, scope), policies, &self.context)?;
+            let active = Self::EVENT_TYPES.iter().any(|event_type| state.subscriptions[*event_type].active());
+            if active == state.active {
+                return Ok(());
+            }
+            state.active = active;
+            active
+        };
+        self.mining_manager.clone().set_notifications_active(active).await;
+        Ok(())
+    }
+}
+
+impl Debug for MempoolSubscriptionManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MempoolSubscriptionManager").field("active", &self.state.lock().unwrap().active).finish()
+    }
+}
+
+#[async_trait]
+impl SubscriptionManager for MempoolSubscriptionManager {
+    async fn start_notify(&self, _id: ListenerId, scope: Scope) -> Result<()> {
+        self.execute(scope, Command::Start).await
+    }
+
+    async fn stop_notify(&self, _id: ListenerId, scope: Scope) -> Result<()> {
+        self.execute(scope, Command::Stop).await
+    }
+}
@@ -76,6 +76,10 @@ impl Inner {
             RpcApiOps::VirtualDaaScoreChangedNotification,
             RpcApiOps::PruningPointUtxoSetOverrideNotification,
             RpcApiOps::NewBlockTemplateNotification,
+            RpcApiOps::MempoolTransactionAddedNotification,
+            RpcApiOps::MempoolTransactionRemovedNotification,
+            RpcApiOps::MempoolTransactionReplacedNotification,
+            RpcApiOps::MempoolTransactionIncludedNotification,
         ]
         .into_iter()
         .for_each(|notification_op| {
@@ -665,6 +669,12 @@ impl RpcApi for KaspaRpcClient {
             SubmitTransactionReplacement,
             Unban,
             GetSeqCommitLaneProof,
+            GetTransaction,
+            GetAddressHistory,
+            GetSeqCommitLaneProofs,
+            ValidateTransactionScripts,
+            SubmitTransactionPackage,
+            GetBannedPeers,
         ]
     );
 
@@ -72,6 +72,12 @@ impl Router {
                 GetVirtualChainFromBlock,
                 GetVirtualChainFromBlockV2,
                 GetSeqCommitLaneProof,
+                GetTransaction,
+                GetAddressHistory,
+                GetSeqCommitLaneProofs,
+                ValidateTransactionScripts,
+                SubmitTransactionPackage,
+                GetBannedPeers,
                 ResolveFinalityConflict,
                 Shutdown,
                 SubmitBlock,
@@ -842,6 +842,142 @@ impl RpcClient {
         Ok(())
     }
 
+    /// Subscribe for a mempool transaction added notification event.
+    /// The event is produced when transactions are accepted into the mempool.
+    /// The event notification will be scoped to the provided list of addresses,
+    /// an empty list meaning all addresses.
+    #[wasm_bindgen(js_name = subscribeMempoolTransactionAdded)]
+    pub async fn subscribe_mempool_transaction_added(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner
+                .client
+                .start_notify(listener_id, Scope::MempoolTransactionAdded(MempoolTransactionAddedScope { addresses }))
+                .await?;
+        } else {
+            log_error!("RPC subscribe on a closed connection");
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe from mempool transaction added notification event
+    /// for a specific set of addresses.
+    #[wasm_bindgen(js_name = unsubscribeMempoolTransactionAdded)]
+    pub async fn unsubscribe_mempool_transaction_added(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner
+                .client
+                .stop_notify(listener_id, Scope::MempoolTransactionAdded(MempoolTransactionAddedScope { addresses }))
+                .await?;
+        } else {
+            log_error!("RPC unsubscribe on a closed connection");
+        }
+        Ok(())
+    }
+
+    /// Subscribe for a mempool transaction removed notification event.
+    /// The event is produced when transactions get evicted from or expire in the mempool.
+    /// The event notification will be scoped to the provided list of addresses,
+    /// an empty list meaning all addresses.
+    #[wasm_bindgen(js_name = subscribeMempoolTransactionRemoved)]
+    pub async fn subscribe_mempool_transaction_removed(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner
+                .client
+                .start_notify(listener_id, Scope::MempoolTransactionRemoved(MempoolTransactionRemovedScope { addresses }))
+                .await?;
+        } else {
+            log_error!("RPC subscribe on a closed connection");
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe from mempool transaction removed notification event
+    /// for a specific set of addresses.
+    #[wasm_bindgen(js_name = unsubscribeMempoolTransactionRemoved)]
+    pub async fn unsubscribe_mempool_transaction_removed(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner
+                .client
+                .stop_notify(listener_id, Scope::MempoolTransactionRemoved(MempoolTransactionRemovedScope { addresses }))
+                .await?;
+        } else {
+            log_error!("RPC unsubscribe on a closed connection");
+        }
+        Ok(())
+    }
+
+    /// Subscribe for a mempool transaction replaced notification event.
+    /// The event is produced when a transaction replaces by fee some mempool transactions.
+    /// The event notification will be scoped to the provided list of addresses,
+    /// an empty list meaning all addresses.
+    #[wasm_bindgen(js_name = subscribeMempoolTransactionReplaced)]
+    pub async fn subscribe_mempool_transaction_replaced(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner
+                .client
+                .start_notify(listener_id, Scope::MempoolTransactionReplaced(MempoolTransactionReplacedScope { addresses }))
+                .await?;
+        } else {
+            log_error!("RPC subscribe on a closed connection");
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe from mempool transaction replaced notification event
+    /// for a specific set of addresses.
+    #[wasm_bindgen(js_name = unsubscribeMempoolTransactionReplaced)]
+    pub async fn unsubscribe_mempool_transaction_replaced(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner
+                .client
+                .stop_notify(listener_id, Scope::MempoolTransactionReplaced(MempoolTransactionReplacedScope { addresses }))
+                .await?;
+        } else {
+            log_error!("RPC unsubscribe on a closed connection");
+        }
+        Ok(())
+    }
+
+    /// Subscribe for a mempool transaction included notification event.
+    /// The event is produced when mempool transactions get included in a block.
+    /// The event notification will be scoped to the provided list of addresses,
+    /// an empty list meaning all addresses.
+    #[wasm_bindgen(js_name = subscribeMempoolTransactionIncluded)]
+    pub async fn subscribe_mempool_transaction_included(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner
+                .client
+                .start_notify(listener_id, Scope::MempoolTransactionIncluded(MempoolTransactionIncludedScope { addresses }))
+                .await?;
+        } else {
+            log_error!("RPC subscribe on a closed connection");
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe from mempool transaction included notification event
+    /// for a specific set of addresses.
+    #[wasm_bindgen(js_name = unsubscribeMempoolTransactionIncluded)]
+    pub async fn unsubscribe_mempool_transaction_included(&self, addresses: AddressOrStringArrayT) -> Result<()> {
+        if let Some(listener_id) = self.listener_id() {
+            let addresses: Vec<Address> = addresses.try_into()?;
+            self.inner
+                .client
+                .stop_notify(listener_id, Scope::MempoolTransactionIncluded(MempoolTransactionIncludedScope { addresses }))
+                .await?;
+        } else {
+            log_error!("RPC unsubscribe on a closed connection");
+        }
+        Ok(())
+    }
+
     // TODO: scope variant with field functions
 
     /// Manage subscription for a virtual chain changed notification event.
@@ -882,6 +1018,7 @@ build_wrpc_wasm_bindgen_subscriptions!([
     // Manually implemented subscriptions (above)
     // - VirtualChainChanged, // can't used this here due to non-C-style enum variant
     // - UtxosChanged, // can't used this here due to non-C-style enum variant
+    // - MempoolTransaction{Added,Removed,Replaced,Included}, // can't used this here due to non-C-style enum variant
     // - VirtualDaaScoreChanged,
     /// Manage subscription for a block added notification event.
     /// Block added notification event is produced when a new
@@ -1015,6 +1152,14 @@ build_wrpc_wasm_bindgen_interface!(
         /// Retrieves reward information for a block.
         /// Returned information: block color, confirmation count, reward, merging chain block, and header.
         GetBlockRewardInfo,
+        /// Retrieves an accepted or mempool transaction by its id.
+        /// Returned information: transaction, acceptance status, including and accepting blocks.
+        /// Requires the node to run with `--txindex` for accepted transactions.
+        GetTransaction,
+        /// Retrieves the accepted transactions funding or spending from an address, paginated via a cursor.
+        /// Returned information: history entries and the cursor of the next page.
+        /// Requires the node to run with `--addressindex`.
+        GetAddressHistory,
         /// Retrieves the estimated DAA (Difficulty Adjustment Algorithm)
         /// score timestamp estimate.
         /// Returned information: DAA score timestamp estimate.
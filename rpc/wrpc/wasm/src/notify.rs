@@ -25,6 +25,10 @@ export enum RpcEventType {
     VirtualDaaScoreChanged = "virtual-daa-score-changed",
     PruningPointUtxoSetOverride = "pruning-point-utxo-set-override",
     NewBlockTemplate = "new-block-template",
+    MempoolTransactionAdded = "mempool-transaction-added",
+    MempoolTransactionRemoved = "mempool-transaction-removed",
+    MempoolTransactionReplaced = "mempool-transaction-replaced",
+    MempoolTransactionIncluded = "mempool-transaction-included",
 }
 
 /**
@@ -40,7 +44,11 @@ export type RpcEventData = IBlockAdded
     | ISinkBlueScoreChanged 
     | IVirtualDaaScoreChanged 
     | IPruningPointUtxoSetOverride 
-    | INewBlockTemplate;
+    | INewBlockTemplate
+    | IMempoolTransactionAdded
+    | IMempoolTransactionRemoved
+    | IMempoolTransactionReplaced
+    | IMempoolTransactionIncluded;
 
 /**
  * RPC notification event data map.
@@ -59,6 +67,10 @@ export type RpcEventMap = {
     "virtual-daa-score-changed" : IVirtualDaaScoreChanged,
     "pruning-point-utxo-set-override" : IPruningPointUtxoSetOverride,
     "new-block-template" : INewBlockTemplate,
+    "mempool-transaction-added" : IMempoolTransactionAdded,
+    "mempool-transaction-removed" : IMempoolTransactionRemoved,
+    "mempool-transaction-replaced" : IMempoolTransactionReplaced,
+    "mempool-transaction-included" : IMempoolTransactionIncluded,
 }
 
 /**
@@ -84,6 +96,10 @@ export type RpcEvent = {
  * {@link RpcClient.subscribeSinkBlueScoreChanged},
  * {@link RpcClient.subscribePruningPointUtxoSetOverride},
  * {@link RpcClient.subscribeNewBlockTemplate},
+ * {@link RpcClient.subscribeMempoolTransactionAdded},
+ * {@link RpcClient.subscribeMempoolTransactionRemoved},
+ * {@link RpcClient.subscribeMempoolTransactionReplaced},
+ * {@link RpcClient.subscribeMempoolTransactionIncluded},
  * 
  * @category Node RPC
  */
@@ -241,3 +257,67 @@ declare! {
     }
     "#,
 }
+
+declare! {
+    IMempoolTransactionAdded,
+    r#"
+    /**
+     * Mempool transaction added notification event is produced when a
+     * transaction is accepted into the mempool. The event notification is
+     * scoped to the monitored list of addresses specified during the subscription.
+     * 
+     * @category Node RPC
+     */
+    export interface IMempoolTransactionAdded {
+        [key: string]: any;
+    }
+    "#,
+}
+
+declare! {
+    IMempoolTransactionRemoved,
+    r#"
+    /**
+     * Mempool transaction removed notification event is produced when a
+     * transaction is evicted from or expires in the mempool. The event notification
+     * is scoped to the monitored list of addresses specified during the subscription.
+     * 
+     * @category Node RPC
+     */
+    export interface IMempoolTransactionRemoved {
+        [key: string]: any;
+    }
+    "#,
+}
+
+declare! {
+    IMempoolTransactionReplaced,
+    r#"
+    /**
+     * Mempool transaction replaced notification event is produced when a
+     * transaction replaces by fee one or more mempool transactions. The event notification
+     * is scoped to the monitored list of addresses specified during the subscription.
+     * 
+     * @category Node RPC
+     */
+    export interface IMempoolTransactionReplaced {
+        [key: string]: any;
+    }
+    "#,
+}
+
+declare! {
+    IMempoolTransactionIncluded,
+    r#"
+    /**
+     * Mempool transaction included notification event is produced when
+     * mempool transactions get included in a block. The event notification is
+     * scoped to the monitored list of addresses specified during the subscription.
+     * 
+     * @category Node RPC
+     */
+    export interface IMempoolTransactionIncluded {
+        [key: string]: any;
+    }
+    "#,
+}
@@ -11,6 +11,7 @@ pub mod daemon;
 pub mod fee;
 pub mod json;
 pub mod listener;
+pub mod socks;
 pub mod utils;
 
 pub fn open_file(file_path: &Path) -> File {
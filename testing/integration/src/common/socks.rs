@@ -0,0 +1,123 @@
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, copy_bidirectional},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// A connection relayed by [`Socks5StandIn`]
+#[derive(Clone, Debug)]
+pub struct ProxiedConnection {
+    /// The requested destination, either `ip:port` or `domain:port`
+    pub target: String,
+    /// The username the client authenticated with, if any
+    pub username: Option<String>,
+}
+
+/// A local stand-in for a SOCKS5 proxy such as Tor. It relays CONNECT requests to IP destinations as is,
+/// and resolves domain destinations (onion addresses) through a fixed map of local addresses.
+pub struct Socks5StandIn {
+    pub address: SocketAddr,
+    connections: Arc<Mutex<Vec<ProxiedConnection>>>,
+    task: JoinHandle<()>,
+}
+
+impl Socks5StandIn {
+    pub async fn start(domains: HashMap<String, SocketAddr>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let connections: Arc<Mutex<Vec<ProxiedConnection>>> = Default::default();
+        let domains = Arc::new(domains);
+        let task = tokio::spawn({
+            let connections = connections.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let (connections, domains) = (connections.clone(), domains.clone());
+                    tokio::spawn(async move {
+                        let _ = Self::relay(stream, connections, domains).await;
+                    });
+                }
+            }
+        });
+        Self { address, connections, task }
+    }
+
+    pub fn connections(&self) -> Vec<ProxiedConnection> {
+        self.connections.lock().clone()
+    }
+
+    async fn relay(
+        mut stream: TcpStream,
+        connections: Arc<Mutex<Vec<ProxiedConnection>>>,
+        domains: Arc<HashMap<String, SocketAddr>>,
+    ) -> std::io::Result<()> {
+        // Greeting, preferring username/password authentication like Tor does
+        let mut header = [0u8; 2];
+        stream.read_exact(&mut header).await?;
+        let mut methods = vec![0u8; header[1] as usize];
+        stream.read_exact(&mut methods).await?;
+        let username = if methods.contains(&0x02) {
+            stream.write_all(&[0x05, 0x02]).await?;
+            let _version = stream.read_u8().await?;
+            let mut username = vec![0u8; stream.read_u8().await? as usize];
+            stream.read_exact(&mut username).await?;
+            let mut password = vec![0u8; stream.read_u8().await? as usize];
+            stream.read_exact(&mut password).await?;
+            stream.write_all(&[0x01, 0x00]).await?;
+            Some(String::from_utf8_lossy(&username).into_owned())
+        } else {
+            stream.write_all(&[0x05, 0x00]).await?;
+            None
+        };
+
+        // CONNECT request
+        let mut request = [0u8; 4];
+        stream.read_exact(&mut request).await?;
+        let (target, destination) = match request[3] {
+            0x01 => {
+                let mut octets = [0u8; 4];
+                stream.read_exact(&mut octets).await?;
+                let address = SocketAddr::new(Ipv4Addr::from(octets).into(), stream.read_u16().await?);
+                (address.to_string(), Some(address))
+            }
+            0x04 => {
+                let mut octets = [0u8; 16];
+                stream.read_exact(&mut octets).await?;
+                let address = SocketAddr::new(Ipv6Addr::from(octets).into(), stream.read_u16().await?);
+                (address.to_string(), Some(address))
+            }
+            _ => {
+                let mut domain = vec![0u8; stream.read_u8().await? as usize];
+                stream.read_exact(&mut domain).await?;
+                let domain = String::from_utf8_lossy(&domain).into_owned();
+                let port = stream.read_u16().await?;
+                (format!("{domain}:{port}"), domains.get(&domain).copied())
+            }
+        };
+        connections.lock().push(ProxiedConnection { target, username });
+
+        let outbound = match destination {
+            Some(destination) => TcpStream::connect(destination).await.ok(),
+            None => None,
+        };
+        let Some(mut outbound) = outbound else {
+            // Host unreachable
+            stream.write_all(&[0x05, 0x04, 0x00, 0x01, 0, 0, 0, 0, 0, 0]).await?;
+            return Ok(());
+        };
+        stream.write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0]).await?;
+        copy_bidirectional(&mut stream, &mut outbound).await?;
+        Ok(())
+    }
+}
+
+impl Drop for Socks5StandIn {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
@@ -819,6 +819,8 @@ async fn json_test(file_path: &str, concurrency: bool) {
         &notify_service.notifier(),
         subscription_context.clone(),
         Some(UtxoIndexProxy::new(utxoindex.clone())),
+        None,
+        None,
     ));
 
     let async_runtime = Arc::new(AsyncRuntime::new(2));
@@ -3,14 +3,17 @@ use crate::common::{
     client_notify::ChannelNotify,
     daemon::Daemon,
     fee,
+    socks::Socks5StandIn,
     utils::{fetch_spendable_utxos, mine_block, wait_for},
 };
 use kaspa_addresses::Address;
 use kaspa_alloc::init_allocator_with_default_settings;
 use kaspa_consensus::params::{Params, SIMNET_GENESIS, SIMNET_PARAMS};
 use kaspa_consensus_core::{
-    config::params::OverrideParams,
+    api::ConsensusApi,
+    config::{Config, params::OverrideParams},
     constants::{TX_VERSION, TX_VERSION_TOCCATA},
+    errors::consensus::ConsensusResult,
     header::Header,
     mass::ComputeBudget,
     sign::{sign, sign_with_multiple_v2},
@@ -19,21 +22,24 @@ use kaspa_consensus_core::{
 };
 use kaspa_consensusmanager::ConsensusManager;
 use kaspa_core::{task::runtime::AsyncRuntime, trace};
+use kaspa_database::utils::get_kaspa_tempdir;
 use kaspa_grpc_client::GrpcClient;
 use kaspa_hashes::Hash;
 use kaspa_notify::{
     events::EventType,
     scope::{BlockAddedScope, UtxosChangedScope, VirtualDaaScoreChangedScope},
 };
+use kaspa_p2p_flows::ibd::export_snapshot;
 use kaspa_rpc_core::{Notification, RpcTransaction, RpcTransactionId, api::rpc::RpcApi};
 use kaspa_txscript::{
     opcodes::codes, pay_to_address_script, pay_to_script_hash_script, pay_to_script_hash_signature_script,
     script_builder::ScriptBuilder,
 };
+use kaspa_utils::networking::{ContextualNetAddress, NetAddress, OnionNetAddress};
 use kaspad_lib::{args::Args, daemon::Runtime as KaspadRuntime};
 use rand::thread_rng;
 use serde_json;
-use std::{fs, path::PathBuf, sync::Arc, time::Duration};
+use std::{collections::HashMap, fs, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
 fn load_override_params(path: &PathBuf) -> Params {
     let override_params_json = fs::read_to_string(path).unwrap();
@@ -178,7 +184,15 @@ async fn daemon_mining_test() {
 
     rpc_client2.add_peer(format!("127.0.0.1:{}", kaspad1.p2p_port).try_into().unwrap(), true).await.unwrap();
     tokio::time::sleep(Duration::from_secs(1)).await; // Let it connect
-    assert_eq!(rpc_client2.get_connected_peer_info().await.unwrap().peer_info.len(), 1);
+    let peer_info2 = rpc_client2.get_connected_peer_info().await.unwrap().peer_info;
+    assert_eq!(peer_info2.len(), 1);
+
+    // Both ends negotiate the encrypted transport and report the same session id
+    let peer_info1 = rpc_client1.get_connected_peer_info().await.unwrap().peer_info;
+    assert_eq!(peer_info1.len(), 1);
+    assert!(peer_info1[0].is_encrypted && peer_info2[0].is_encrypted);
+    assert!(peer_info1[0].transport_session_id.is_some());
+    assert_eq!(peer_info1[0].transport_session_id, peer_info2[0].transport_session_id);
 
     let (sender, event_receiver) = async_channel::unbounded();
     rpc_client1.start(Some(Arc::new(ChannelNotify::new(sender)))).await;
@@ -235,6 +249,82 @@ async fn daemon_mining_test() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_socks5_proxy_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO");
+
+    let args = Args {
+        simnet: true,
+        disable_upnp: true, // UPnP registration might take some time and is not needed for this test
+        ..Default::default()
+    };
+    let total_fd_limit = 10;
+
+    let mut kaspad1 = Daemon::new_random_with_args(args.clone(), total_fd_limit);
+    let mut kaspad3 = Daemon::new_random_with_args(args.clone(), total_fd_limit);
+    let rpc_client1 = kaspad1.start().await;
+    let rpc_client3 = kaspad3.start().await;
+
+    // Daemon #1 poses as an onion service, resolved by the proxy stand-in
+    let onion: OnionNetAddress = "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion:16111".parse().unwrap();
+    let proxy =
+        Socks5StandIn::start(HashMap::from([(onion.onion.to_string(), SocketAddr::from(([127, 0, 0, 1], kaspad1.p2p_port)))])).await;
+
+    // Daemon #2 reaches all of its peers through the proxy
+    let proxied_args =
+        Args { proxy: Some(ContextualNetAddress::from(NetAddress::from(proxy.address))), onion_peers: vec![onion], ..args };
+    let mut kaspad2 = Daemon::new_random_with_args(proxied_args, total_fd_limit);
+    let rpc_client2 = kaspad2.start().await;
+
+    // The onion peer is picked from the address manager and dialed by its onion address
+    let check_client = rpc_client2.clone();
+    wait_for(
+        100,
+        50,
+        move || {
+            async fn peer_connected(client: GrpcClient) -> bool {
+                client.get_connected_peer_info().await.unwrap().peer_info.len() == 1
+            }
+            Box::pin(peer_connected(check_client.clone()))
+        },
+        "the node did not connect to the onion peer",
+    )
+    .await;
+    let peer_info = rpc_client2.get_connected_peer_info().await.unwrap().peer_info;
+    assert!(peer_info[0].is_outbound);
+    assert_eq!(peer_info[0].address, onion.placeholder_address());
+
+    // Peers requested by IP go through the proxy as well
+    rpc_client2.add_peer(format!("127.0.0.1:{}", kaspad3.p2p_port).try_into().unwrap(), false).await.unwrap();
+    let check_client = rpc_client2.clone();
+    wait_for(
+        100,
+        50,
+        move || {
+            async fn peers_connected(client: GrpcClient) -> bool {
+                client.get_connected_peer_info().await.unwrap().peer_info.len() == 2
+            }
+            Box::pin(peers_connected(check_client.clone()))
+        },
+        "the node did not connect to the IP peer",
+    )
+    .await;
+
+    let connections = proxy.connections();
+    let onion_connection = connections.iter().find(|connection| connection.target == onion.to_string()).unwrap();
+    let ip_connection = connections.iter().find(|connection| connection.target == format!("127.0.0.1:{}", kaspad3.p2p_port)).unwrap();
+    // Stream isolation uses distinct credentials per connection
+    assert!(onion_connection.username.is_some());
+    assert_ne!(onion_connection.username, ip_connection.username);
+
+    for (client, mut kaspad) in [(rpc_client1, kaspad1), (rpc_client2, kaspad2), (rpc_client3, kaspad3)] {
+        client.disconnect().await.unwrap();
+        drop(client);
+        kaspad.shutdown();
+    }
+}
+
 /// `cargo test --release --package kaspa-testing-integration --lib -- daemon_integration_tests::daemon_utxos_propagation_test`
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn daemon_utxos_propagation_test() {
@@ -1253,6 +1343,127 @@ async fn daemon_ibd_smt_state_sync_test() {
     kaspad2.shutdown();
 }
 
+fn daemon_consensus_manager(daemon: &Daemon) -> Arc<ConsensusManager> {
+    Arc::downcast::<ConsensusManager>(daemon.core.find(ConsensusManager::IDENT).unwrap().into_any_arc()).unwrap()
+}
+
+fn pruning_point_utxos(consensus: &dyn ConsensusApi, pruning_point: Hash) -> ConsensusResult<Vec<(TransactionOutpoint, UtxoEntry)>> {
+    const CHUNK_SIZE: usize = 1000;
+    let mut utxos: Vec<(TransactionOutpoint, UtxoEntry)> = vec![];
+    loop {
+        let from_outpoint = utxos.last().map(|(outpoint, _)| *outpoint);
+        let chunk = consensus.get_pruning_point_utxos(pruning_point, from_outpoint, CHUNK_SIZE, from_outpoint.is_some())?;
+        let done = chunk.len() < CHUNK_SIZE;
+        utxos.extend(chunk);
+        if done {
+            return Ok(utxos);
+        }
+    }
+}
+
+// Exports a snapshot from a node whose pruning point moved off genesis, bootstraps a fresh node from it
+// and lets the latter complete IBD from the exporting node
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn daemon_snapshot_round_trip_test() {
+    init_allocator_with_default_settings();
+    kaspa_core::log::try_init_logger("INFO,kaspa_testing_integration=trace");
+
+    let override_params_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata/params/seqcommit_sync_test_params.json");
+    let params = load_override_params(&override_params_path);
+
+    let args = Args {
+        simnet: true,
+        unsafe_rpc: true,
+        enable_unsynced_mining: true,
+        disable_upnp: true,
+        override_params_file: Some(override_params_path.to_string_lossy().to_string()),
+        ..Default::default()
+    };
+
+    let total_fd_limit = 10;
+    let mut kaspad1 = Daemon::new_random_with_args(args.clone(), total_fd_limit);
+    let rpc_client1 = kaspad1.start().await;
+
+    let (_, miner_pk) = secp256k1::generate_keypair(&mut thread_rng());
+    let miner_address =
+        Address::new(kaspad1.network.into(), kaspa_addresses::Version::PubKey, &miner_pk.x_only_public_key().0.serialize());
+
+    // Mine until the pruning point moves off genesis so that there is a snapshot to export
+    let mut dag_info = rpc_client1.get_block_dag_info().await.unwrap();
+    let mut mined_blocks = 0usize;
+    let mined_blocks_limit = params.pruning_depth().saturating_add(params.finality_depth()).saturating_add(30) as usize;
+    while dag_info.pruning_point_hash == SIMNET_GENESIS.hash && mined_blocks < mined_blocks_limit {
+        let template = rpc_client1.get_block_template(miner_address.clone(), vec![]).await.unwrap();
+        rpc_client1.submit_block(template.block, false).await.unwrap();
+        mined_blocks += 1;
+        dag_info = rpc_client1.get_block_dag_info().await.unwrap();
+    }
+    if dag_info.pruning_point_hash == SIMNET_GENESIS.hash {
+        panic!("pruning point did not advance from genesis in time");
+    }
+
+    // Export the snapshot along with the pruning point UTXO set it is expected to reproduce
+    let snapshot_dir = get_kaspa_tempdir();
+    let snapshot_path = snapshot_dir.path().join("kaspa.snapshot");
+    let config = Config::new(params);
+    let consensus_manager1 = daemon_consensus_manager(&kaspad1);
+    let export_path = snapshot_path.clone();
+    let (pruning_point, utxos) = tokio::task::spawn_blocking(move || {
+        let session = consensus_manager1.consensus().unguarded_session_blocking();
+        let pruning_point = export_snapshot(&*session, &config, &export_path).unwrap();
+        (pruning_point, pruning_point_utxos(&*session, pruning_point).unwrap())
+    })
+    .await
+    .unwrap();
+    assert!(!utxos.is_empty(), "the exported pruning point UTXO set should hold the mined coinbase outputs");
+
+    // Bootstrap a fresh node from the snapshot
+    let args2 = Args { import_snapshot: Some(snapshot_path.to_string_lossy().to_string()), ..args };
+    let mut kaspad2 = Daemon::new_random_with_args(args2, total_fd_limit);
+    let rpc_client2 = kaspad2.start().await;
+
+    let consensus_manager2 = daemon_consensus_manager(&kaspad2);
+    let utxos = Arc::new(utxos);
+    wait_for(
+        50,
+        100,
+        move || {
+            let consensus_manager = consensus_manager2.clone();
+            let utxos = utxos.clone();
+            Box::pin(async move {
+                let session = consensus_manager.consensus().unguarded_session();
+                if session.async_pruning_point().await != pruning_point || !session.async_is_pruning_smt_stable().await {
+                    return false;
+                }
+                session.spawn_blocking(move |c| pruning_point_utxos(c, pruning_point).is_ok_and(|imported| imported == *utxos)).await
+            })
+        },
+        "the snapshot was not imported",
+    )
+    .await;
+
+    // The imported node continues with IBD from the snapshot pruning point
+    rpc_client2.add_peer(format!("127.0.0.1:{}", kaspad1.p2p_port).try_into().unwrap(), true).await.unwrap();
+    let sync_check = rpc_client2.clone();
+    let target_daa_score = rpc_client1.get_server_info().await.unwrap().virtual_daa_score;
+    wait_for(
+        100,
+        60,
+        move || {
+            let client = sync_check.clone();
+            Box::pin(async move { client.get_server_info().await.unwrap().virtual_daa_score >= target_daa_score })
+        },
+        "the imported node did not complete IBD",
+    )
+    .await;
+    assert_eq!(rpc_client2.get_block_dag_info().await.unwrap().pruning_point_hash, pruning_point);
+
+    rpc_client1.disconnect().await.unwrap();
+    rpc_client2.disconnect().await.unwrap();
+    kaspad1.shutdown();
+    kaspad2.shutdown();
+}
+
 // The following test runtime parameters are required for a graceful shutdown of the gRPC server
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn daemon_cleaning_test() {
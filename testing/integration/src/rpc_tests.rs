@@ -3,7 +3,7 @@ use std::{str::FromStr, sync::Arc, time::Duration};
 use crate::common::{client_notify::ChannelNotify, daemon::Daemon};
 use futures_util::future::try_join_all;
 use kaspa_addresses::{Address, Prefix, Version};
-use kaspa_consensus::params::SIMNET_GENESIS;
+use kaspa_consensus::params::{SIMNET_GENESIS, SIMNET_PARAMS};
 use kaspa_consensus_core::{
     constants::MAX_SOMPI,
     header::Header,
@@ -16,8 +16,9 @@ use kaspa_hashes::Hash;
 use kaspa_notify::{
     connection::{ChannelConnection, ChannelType},
     scope::{
-        BlockAddedScope, FinalityConflictScope, NewBlockTemplateScope, PruningPointUtxoSetOverrideScope, Scope,
-        SinkBlueScoreChangedScope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope,
+        BlockAddedScope, FinalityConflictScope, MempoolTransactionAddedScope, MempoolTransactionIncludedScope,
+        MempoolTransactionRemovedScope, MempoolTransactionReplacedScope, NewBlockTemplateScope, PruningPointUtxoSetOverrideScope,
+        Scope, SinkBlueScoreChangedScope, UtxosChangedScope, VirtualChainChangedScope, VirtualDaaScoreChangedScope,
     },
 };
 use kaspa_rpc_core::{Notification, api::rpc::RpcApi, model::*};
@@ -54,6 +55,8 @@ async fn sanity_test() {
         enable_unsynced_mining: true,
         block_template_cache_lifetime: Some(0),
         utxoindex: true,
+        txindex: true,
+        addressindex: true,
         unsafe_rpc: true,
         ..Default::default()
     };
@@ -220,6 +223,16 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::GetTransaction => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // A transaction that was neither accepted nor submitted to the mempool
+                    let result =
+                        rpc_client.get_transaction_call(None, GetTransactionRequest::new(Hash::from_bytes([1; 32]), true)).await;
+                    assert!(result.is_err());
+                })
+            }
+
             KaspadPayloadOps::GetCurrentNetwork => {
                 let rpc_client = client.clone();
                 tst!(op, {
@@ -349,9 +362,14 @@ async fn sanity_test() {
                     let response = rpc_client.get_peer_addresses_call(None, GetPeerAddressesRequest {}).await.unwrap();
                     assert!(response.banned_addresses.contains(&ip));
 
+                    let banned_peers = rpc_client.get_banned_peers().await.unwrap();
+                    let banned_peer = banned_peers.iter().find(|peer| peer.ip == ip).unwrap();
+                    assert!(banned_peer.expiry > banned_peer.ban_timestamp);
+
                     let _ = rpc_client.unban_call(None, UnbanRequest { ip }).await.unwrap();
                     let response = rpc_client.get_peer_addresses_call(None, GetPeerAddressesRequest {}).await.unwrap();
                     assert!(!response.banned_addresses.contains(&ip));
+                    assert!(rpc_client.get_banned_peers().await.unwrap().iter().all(|peer| peer.ip != ip));
                 })
             }
 
@@ -359,6 +377,10 @@ async fn sanity_test() {
                 tst!(op, "see Ban")
             }
 
+            KaspadPayloadOps::GetBannedPeers => {
+                tst!(op, "see Ban")
+            }
+
             KaspadPayloadOps::SubmitTransaction => {
                 let rpc_client = client.clone();
                 tst!(op, {
@@ -381,13 +403,54 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::SubmitTransactionPackage => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // An empty package is rejected...
+                    let result = rpc_client.submit_transaction_package(vec![]).await;
+                    assert!(result.is_err());
+
+                    // ...and so is a package holding an erroneous transaction
+                    let transaction = Transaction::new(0, vec![], vec![], 0, SubnetworkId::default(), 0, vec![]);
+                    let result = rpc_client.submit_transaction_package(vec![(&transaction).into()]).await;
+                    assert!(result.is_err());
+                })
+            }
+
+            KaspadPayloadOps::ValidateTransactionScripts => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // An erroneous transaction fails the isolation checks...
+                    let transaction = Transaction::new(0, vec![], vec![], 0, SubnetworkId::default(), 0, vec![]);
+                    let result = rpc_client.validate_transaction_scripts((&transaction).into(), vec![]).await;
+                    assert!(result.is_err());
+
+                    // ...and the provided UTXO entries must match the inputs
+                    let utxo_entries = vec![RpcUtxoEntry::new(1, Default::default(), 0, false, None)];
+                    let result = rpc_client.validate_transaction_scripts((&transaction).into(), utxo_entries).await;
+                    assert!(result.is_err());
+                })
+            }
+
             KaspadPayloadOps::GetSubnetwork => {
                 let rpc_client = client.clone();
                 tst!(op, {
-                    let result =
-                        rpc_client.get_subnetwork_call(None, GetSubnetworkRequest { subnetwork_id: SubnetworkId::from_byte(0) }).await;
+                    // A lane which was never touched is reported as inactive with no tip
+                    let subnetwork_id = SubnetworkId::from_namespace([1, 2, 3, 4]);
+                    let response = rpc_client.get_subnetwork_call(None, GetSubnetworkRequest::new(subnetwork_id, None)).await.unwrap();
+                    assert_eq!(response.gas_limit, SIMNET_PARAMS.block_lane_limits.gas_per_lane);
+                    assert!(response.lane.is_none());
+                    assert!(!response.is_active);
 
-                    // Err because it's currently unimplemented
+                    let response = rpc_client
+                        .get_subnetwork_call(None, GetSubnetworkRequest::new(subnetwork_id, Some(SIMNET_GENESIS.hash)))
+                        .await
+                        .unwrap();
+                    assert_eq!(response.block_hash, SIMNET_GENESIS.hash);
+                    assert!(response.lane.is_none());
+
+                    // Unknown blocks are rejected
+                    let result = rpc_client.get_subnetwork_call(None, GetSubnetworkRequest::new(subnetwork_id, Some(0.into()))).await;
                     assert!(result.is_err());
                 })
             }
@@ -426,11 +489,32 @@ async fn sanity_test() {
             KaspadPayloadOps::GetHeaders => {
                 let rpc_client = client.clone();
                 tst!(op, {
+                    // Other tests may be submitting blocks concurrently, so only the start of the chain is known
+                    let response = rpc_client
+                        .get_headers_call(None, GetHeadersRequest { start_hash: SIMNET_GENESIS.hash, limit: 10, is_ascending: true })
+                        .await
+                        .unwrap();
+                    assert!(!response.headers.is_empty() && response.headers.len() <= 10);
+                    assert_eq!(response.headers[0].hash, SIMNET_GENESIS.hash);
+
+                    // Genesis is the retention period root, so walking backwards stops right away
+                    let response = rpc_client
+                        .get_headers_call(None, GetHeadersRequest { start_hash: SIMNET_GENESIS.hash, limit: 10, is_ascending: false })
+                        .await
+                        .unwrap();
+                    assert_eq!(response.headers.len(), 1);
+                    assert_eq!(response.headers[0].hash, SIMNET_GENESIS.hash);
+
+                    let response = rpc_client
+                        .get_headers_call(None, GetHeadersRequest { start_hash: SIMNET_GENESIS.hash, limit: 0, is_ascending: true })
+                        .await
+                        .unwrap();
+                    assert!(response.headers.is_empty());
+
+                    // Unknown start hashes are rejected
                     let response_result = rpc_client
-                        .get_headers_call(None, GetHeadersRequest { start_hash: SIMNET_GENESIS.hash, limit: 1, is_ascending: true })
+                        .get_headers_call(None, GetHeadersRequest { start_hash: 0.into(), limit: 1, is_ascending: true })
                         .await;
-
-                    // Err because it's currently unimplemented
                     assert!(response_result.is_err());
                 })
             }
@@ -440,7 +524,7 @@ async fn sanity_test() {
                 tst!(op, {
                     let addresses = vec![Address::new(Prefix::Simnet, Version::PubKey, &[0u8; 32])];
                     let response =
-                        rpc_client.get_utxos_by_addresses_call(None, GetUtxosByAddressesRequest { addresses }).await.unwrap();
+                        rpc_client.get_utxos_by_addresses_call(None, GetUtxosByAddressesRequest::new(addresses)).await.unwrap();
                     assert!(response.entries.is_empty());
                 })
             }
@@ -459,6 +543,19 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::GetAddressHistory => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    let address = Address::new(Prefix::Simnet, Version::PubKey, &[1u8; 32]);
+                    let response = rpc_client
+                        .get_address_history_call(None, GetAddressHistoryRequest::new(address, None, None, None, None))
+                        .await
+                        .unwrap();
+                    assert!(response.entries.is_empty());
+                    assert!(response.next_cursor.is_none());
+                })
+            }
+
             KaspadPayloadOps::GetBalancesByAddresses => {
                 let rpc_client = client.clone();
                 tst!(op, {
@@ -740,6 +837,29 @@ async fn sanity_test() {
                 })
             }
 
+            KaspadPayloadOps::GetSeqCommitLaneProofs => {
+                let rpc_client = client.clone();
+                tst!(op, {
+                    // A non-existent block must yield an error.
+                    let result = rpc_client
+                        .get_seq_commit_lane_proofs_call(
+                            None,
+                            GetSeqCommitLaneProofsRequest { block_hash: 0.into(), lane_keys: vec![0.into()] },
+                        )
+                        .await;
+                    assert!(result.is_err());
+
+                    // An empty lane key list is rejected.
+                    let result = rpc_client
+                        .get_seq_commit_lane_proofs_call(
+                            None,
+                            GetSeqCommitLaneProofsRequest { block_hash: 0.into(), lane_keys: vec![] },
+                        )
+                        .await;
+                    assert!(result.is_err());
+                })
+            }
+
             KaspadPayloadOps::NotifyBlockAdded => {
                 let rpc_client = client.clone();
                 let id = listener_id;
@@ -770,6 +890,34 @@ async fn sanity_test() {
                     rpc_client.start_notify(id, UtxosChangedScope::new(vec![]).into()).await.unwrap();
                 })
             }
+            KaspadPayloadOps::NotifyMempoolTransactionAdded => {
+                let rpc_client = client.clone();
+                let id = listener_id;
+                tst!(op, {
+                    rpc_client.start_notify(id, MempoolTransactionAddedScope::new(vec![]).into()).await.unwrap();
+                })
+            }
+            KaspadPayloadOps::NotifyMempoolTransactionRemoved => {
+                let rpc_client = client.clone();
+                let id = listener_id;
+                tst!(op, {
+                    rpc_client.start_notify(id, MempoolTransactionRemovedScope::new(vec![]).into()).await.unwrap();
+                })
+            }
+            KaspadPayloadOps::NotifyMempoolTransactionReplaced => {
+                let rpc_client = client.clone();
+                let id = listener_id;
+                tst!(op, {
+                    rpc_client.start_notify(id, MempoolTransactionReplacedScope::new(vec![]).into()).await.unwrap();
+                })
+            }
+            KaspadPayloadOps::NotifyMempoolTransactionIncluded => {
+                let rpc_client = client.clone();
+                let id = listener_id;
+                tst!(op, {
+                    rpc_client.start_notify(id, MempoolTransactionIncludedScope::new(vec![]).into()).await.unwrap();
+                })
+            }
             KaspadPayloadOps::NotifySinkBlueScoreChanged => {
                 let rpc_client = client.clone();
                 let id = listener_id;
@@ -959,6 +1107,26 @@ async fn seq_commit_lane_proof_test() {
     assert!(coinbase_proof.lane.is_some(), "coinbase lane must be populated at B2");
     verify_lane_proof_locally(&header2, coinbase_lane_key, &coinbase_proof);
 
+    // Batched proof for the populated coinbase lane and the absent lane at B2, over both transports.
+    let lane_keys = vec![lane_key, coinbase_lane_key, lane_key];
+    let grpc_proofs = grpc
+        .get_seq_commit_lane_proofs_call(None, GetSeqCommitLaneProofsRequest { block_hash: block_hash2, lane_keys: lane_keys.clone() })
+        .await
+        .unwrap();
+    assert_eq!(grpc_proofs.lanes.len(), 2, "duplicate lane keys are proven once");
+    let coinbase_lane = grpc_proofs.lanes.iter().find(|l| l.lane_key == coinbase_lane_key).unwrap();
+    assert_eq!(coinbase_lane.lane, coinbase_proof.lane);
+    assert!(grpc_proofs.lanes.iter().find(|l| l.lane_key == lane_key).unwrap().lane.is_none());
+    verify_lane_proofs_locally(&header2, &grpc_proofs);
+
+    let wrpc_proofs = wrpc
+        .get_seq_commit_lane_proofs_call(None, GetSeqCommitLaneProofsRequest { block_hash: block_hash2, lane_keys })
+        .await
+        .unwrap();
+    verify_lane_proofs_locally(&header2, &wrpc_proofs);
+    assert_eq!(grpc_proofs.smt_proof, wrpc_proofs.smt_proof);
+    assert_eq!(grpc_proofs.lanes, wrpc_proofs.lanes);
+
     wrpc.disconnect().await.unwrap();
     drop(wrpc);
     grpc.disconnect().await.unwrap();
@@ -997,3 +1165,24 @@ fn verify_lane_proof_locally(header: &Header, lane_key: Hash, response: &GetSeqC
     )
     .expect("verify_smt_metadata");
 }
+
+fn verify_lane_proofs_locally(header: &Header, response: &GetSeqCommitLaneProofsResponse) {
+    use kaspa_seq_commit::verify::{LaneClaim, verify_lanes_multi_proof};
+    use kaspa_smt::multiproof::SmtMultiProof;
+
+    let proof = SmtMultiProof::from_bytes(&response.smt_proof).expect("multi-proof wire format");
+    let claims = response
+        .lanes
+        .iter()
+        .map(|l| LaneClaim { lane_key: &l.lane_key, lane: l.lane.as_ref().map(|entry| (&entry.tip, entry.blue_score)) })
+        .collect::<Vec<_>>();
+    verify_lanes_multi_proof(
+        &proof,
+        &claims,
+        &response.payload_and_ctx_digest,
+        &response.parent_seq_commit,
+        response.inactivity_shortcut,
+        header.accepted_id_merkle_root,
+    )
+    .expect("verify_lanes_multi_proof");
+}
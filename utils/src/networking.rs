@@ -11,6 +11,8 @@ use core::{
 };
 use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use thiserror::Error;
 #[cfg(feature = "peer-id")]
 use uuid::Uuid;
 use wasm_bindgen::prelude::*;
@@ -132,6 +134,26 @@ impl IpAddress {
     pub fn prefix_bucket(&self) -> PrefixBucket {
         PrefixBucket::from(self)
     }
+
+    /// Whether this address is a placeholder standing for an onion address, see [`OnionAddress::placeholder_ip`]
+    pub fn is_onion_placeholder(&self) -> bool {
+        matches!(self.0, IpAddr::V6(ip) if ip.octets().starts_with(&ONIONCAT_PREFIX))
+    }
+
+    /// The placeholder IP identifying a peer reached by `hostname` through a proxy, which resolves the hostname
+    /// in place of the node. Distinct hostnames get distinct placeholders, so that their peers are told apart.
+    pub fn hostname_placeholder(hostname: &str) -> Self {
+        let digest = Sha3_256::digest(hostname.to_ascii_lowercase().as_bytes());
+        let mut octets = [0u8; 16];
+        octets[..HOSTNAME_PLACEHOLDER_PREFIX.len()].copy_from_slice(&HOSTNAME_PLACEHOLDER_PREFIX);
+        octets[HOSTNAME_PLACEHOLDER_PREFIX.len()..].copy_from_slice(&digest[..16 - HOSTNAME_PLACEHOLDER_PREFIX.len()]);
+        Ipv6Addr::from(octets).into()
+    }
+
+    /// Whether this address is a placeholder standing for a proxied hostname, see [`IpAddress::hostname_placeholder`]
+    pub fn is_hostname_placeholder(&self) -> bool {
+        matches!(self.0, IpAddr::V6(ip) if ip.octets().starts_with(&HOSTNAME_PLACEHOLDER_PREFIX))
+    }
 }
 
 impl From<IpAddr> for IpAddress {
@@ -346,6 +368,167 @@ impl Display for ContextualNetAddress {
     }
 }
 
+/// The length in bytes of the ed25519 public key identifying an onion v3 service
+pub const ONION_V3_PUBKEY_LEN: usize = 32;
+const ONION_V3_VERSION: u8 = 0x03;
+const ONION_V3_CHECKSUM_LEN: usize = 2;
+const ONION_V3_ENCODED_LEN: usize = 56;
+const ONION_SUFFIX: &str = ".onion";
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// The OnionCat range (fd87:d87e:eb43::/48) from which placeholder IPs of onion addresses are drawn
+const ONIONCAT_PREFIX: [u8; 6] = [0xfd, 0x87, 0xd8, 0x7e, 0xeb, 0x43];
+
+/// The unique local range (fd6b:6173:7061::/48, "kaspa") from which placeholder IPs of proxied hostnames are drawn
+const HOSTNAME_PLACEHOLDER_PREFIX: [u8; 6] = [0xfd, 0x6b, 0x61, 0x73, 0x70, 0x61];
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OnionAddressError {
+    #[error("onion address must end with `{ONION_SUFFIX}`")]
+    MissingSuffix,
+
+    #[error("onion v3 address must consist of {ONION_V3_ENCODED_LEN} base32 characters, got {0}")]
+    InvalidLength(usize),
+
+    #[error("invalid base32 character `{0}` in onion address")]
+    InvalidCharacter(char),
+
+    #[error("unsupported onion address version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("onion address checksum mismatch")]
+    ChecksumMismatch,
+
+    #[error("invalid onion address port: {0}")]
+    InvalidPort(String),
+}
+
+/// A Tor onion v3 service address, identified by the ed25519 public key of the service.
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize, Debug, BorshSerialize, BorshDeserialize)]
+pub struct OnionAddress([u8; ONION_V3_PUBKEY_LEN]);
+
+impl OnionAddress {
+    pub fn new(pubkey: [u8; ONION_V3_PUBKEY_LEN]) -> Self {
+        Self(pubkey)
+    }
+
+    pub fn pubkey(&self) -> &[u8; ONION_V3_PUBKEY_LEN] {
+        &self.0
+    }
+
+    /// Returns a deterministic IPv6 address in the OnionCat range standing for this onion address in places
+    /// which identify peers by IP (peer keys, misbehavior tracking and bans). The address is not routable.
+    pub fn placeholder_ip(&self) -> IpAddress {
+        let mut octets = [0u8; 16];
+        octets[..ONIONCAT_PREFIX.len()].copy_from_slice(&ONIONCAT_PREFIX);
+        octets[ONIONCAT_PREFIX.len()..].copy_from_slice(&self.0[..16 - ONIONCAT_PREFIX.len()]);
+        Ipv6Addr::from(octets).into()
+    }
+
+    /// The checksum as defined by the tor rend-spec-v3: `SHA3-256(".onion checksum" | PUBKEY | VERSION)[..2]`
+    fn checksum(pubkey: &[u8; ONION_V3_PUBKEY_LEN]) -> [u8; ONION_V3_CHECKSUM_LEN] {
+        let mut hasher = Sha3_256::new();
+        hasher.update(b".onion checksum");
+        hasher.update(pubkey);
+        hasher.update([ONION_V3_VERSION]);
+        let digest = hasher.finalize();
+        [digest[0], digest[1]]
+    }
+}
+
+impl FromStr for OnionAddress {
+    type Err = OnionAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let encoded = s.strip_suffix(ONION_SUFFIX).ok_or(OnionAddressError::MissingSuffix)?;
+        if encoded.len() != ONION_V3_ENCODED_LEN {
+            return Err(OnionAddressError::InvalidLength(encoded.len()));
+        }
+
+        // 56 base32 characters decode to exactly 35 bytes: PUBKEY | CHECKSUM | VERSION
+        let mut decoded = [0u8; ONION_V3_PUBKEY_LEN + ONION_V3_CHECKSUM_LEN + 1];
+        let (mut buffer, mut bits, mut index) = (0u64, 0u32, 0usize);
+        for c in encoded.chars() {
+            let value = BASE32_ALPHABET
+                .iter()
+                .position(|&x| x as char == c.to_ascii_lowercase())
+                .ok_or(OnionAddressError::InvalidCharacter(c))?;
+            buffer = (buffer << 5) | value as u64;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                decoded[index] = (buffer >> bits) as u8;
+                index += 1;
+            }
+        }
+
+        let version = decoded[ONION_V3_PUBKEY_LEN + ONION_V3_CHECKSUM_LEN];
+        if version != ONION_V3_VERSION {
+            return Err(OnionAddressError::UnsupportedVersion(version));
+        }
+        let pubkey: [u8; ONION_V3_PUBKEY_LEN] = decoded[..ONION_V3_PUBKEY_LEN].try_into().unwrap();
+        if decoded[ONION_V3_PUBKEY_LEN..ONION_V3_PUBKEY_LEN + ONION_V3_CHECKSUM_LEN] != Self::checksum(&pubkey) {
+            return Err(OnionAddressError::ChecksumMismatch);
+        }
+        Ok(Self(pubkey))
+    }
+}
+
+impl Display for OnionAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut bytes = [0u8; ONION_V3_PUBKEY_LEN + ONION_V3_CHECKSUM_LEN + 1];
+        bytes[..ONION_V3_PUBKEY_LEN].copy_from_slice(&self.0);
+        bytes[ONION_V3_PUBKEY_LEN..ONION_V3_PUBKEY_LEN + ONION_V3_CHECKSUM_LEN].copy_from_slice(&Self::checksum(&self.0));
+        bytes[ONION_V3_PUBKEY_LEN + ONION_V3_CHECKSUM_LEN] = ONION_V3_VERSION;
+
+        // 35 bytes are exactly 280 bits, so no padding is ever required
+        let (mut buffer, mut bits) = (0u64, 0u32);
+        for byte in bytes {
+            buffer = (buffer << 8) | byte as u64;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                write!(f, "{}", BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char)?;
+            }
+        }
+        f.write_str(ONION_SUFFIX)
+    }
+}
+
+/// An onion v3 service address along with its port, the onion equivalent of [`NetAddress`].
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize, Debug, BorshSerialize, BorshDeserialize)]
+pub struct OnionNetAddress {
+    pub onion: OnionAddress,
+    pub port: u16,
+}
+
+impl OnionNetAddress {
+    pub fn new(onion: OnionAddress, port: u16) -> Self {
+        Self { onion, port }
+    }
+
+    /// The placeholder [`NetAddress`] by which the peer at this address is identified once connected
+    pub fn placeholder_address(&self) -> NetAddress {
+        NetAddress::new(self.onion.placeholder_ip(), self.port)
+    }
+}
+
+impl FromStr for OnionNetAddress {
+    type Err = OnionAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (onion, port) = s.rsplit_once(':').ok_or_else(|| OnionAddressError::InvalidPort(s.to_owned()))?;
+        let port = u16::from_str(port).map_err(|_| OnionAddressError::InvalidPort(port.to_owned()))?;
+        Ok(Self::new(OnionAddress::from_str(onion)?, port))
+    }
+}
+
+impl Display for OnionNetAddress {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}", self.onion, self.port)
+    }
+}
+
 #[cfg(feature = "peer-id")]
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Serialize, Deserialize, Debug, Default)]
 #[repr(transparent)]
@@ -462,6 +645,33 @@ mod tests {
         assert!(addr_v6.is_ok());
     }
 
+    #[test]
+    fn test_onion_address() {
+        let encoded = "2gzyxa5ihm7nsggfxnu52rck2vv4rvmdlkiu3zzui5du4xyclen53wid.onion";
+        let onion = OnionAddress::from_str(encoded).unwrap();
+        assert_eq!(onion.pubkey()[..4], [0xd1, 0xb3, 0x8b, 0x83]);
+        assert_eq!(onion.to_string(), encoded);
+        assert_eq!(OnionAddress::from_str(&encoded.to_uppercase().replace(".ONION", ".onion")).unwrap(), onion);
+
+        let address = OnionNetAddress::from_str(&format!("{encoded}:16111")).unwrap();
+        assert_eq!(address, OnionNetAddress::new(onion, 16111));
+        assert_eq!(address.to_string(), format!("{encoded}:16111"));
+        let placeholder = address.placeholder_address();
+        assert_eq!(placeholder.ip.to_string(), "fd87:d87e:eb43:d1b3:8b83:a83b:3ed9:18c5");
+        assert!(!placeholder.ip.is_publicly_routable());
+        assert!(placeholder.ip.is_onion_placeholder());
+        assert!(!placeholder.ip.is_hostname_placeholder());
+        assert!(!IpAddress::from_str("2001:db8::1").unwrap().is_onion_placeholder());
+
+        // A single altered character breaks the checksum
+        let corrupted = encoded.replacen('2', "3", 1);
+        assert_eq!(OnionAddress::from_str(&corrupted), Err(OnionAddressError::ChecksumMismatch));
+        assert_eq!(OnionAddress::from_str("2gzyxa5ihm7nsggf.onion"), Err(OnionAddressError::InvalidLength(16)));
+        assert_eq!(OnionAddress::from_str(&encoded.replace(".onion", "")), Err(OnionAddressError::MissingSuffix));
+        assert_eq!(OnionAddress::from_str(&encoded.replacen('2', "1", 1)), Err(OnionAddressError::InvalidCharacter('1')));
+        assert!(matches!(OnionNetAddress::from_str(encoded), Err(OnionAddressError::InvalidPort(_))));
+    }
+
     #[test]
     fn test_prefix_bucket() {
         let prefix_bytes: [u8; 2] = [42u8, 43u8];
@@ -10,7 +10,7 @@ use crate::tx::{Fees, GeneratorSummary, PaymentDestination};
 use kaspa_addresses::Address;
 use kaspa_consensus_client::{TransactionOutpoint, UtxoEntry};
 use kaspa_hashes::Hash;
-use kaspa_rpc_core::RpcFeerateBucket;
+use kaspa_rpc_core::{RpcFeerateBucket, RpcInclusionFeerateEstimate};
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
@@ -710,9 +710,34 @@ impl From<&RpcFeerateBucket> for FeeRateEstimateBucket {
     }
 }
 
+/// Feerate observed by the node to get transactions included within `target_seconds`
+/// with probability `confidence`
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct FeeRateEstimateRequest {}
+pub struct FeeRateInclusionEstimate {
+    pub target_seconds: f64,
+    pub confidence: f64,
+    /// `None` if the node did not observe enough block inclusions yet
+    pub feerate: Option<f64>,
+}
+
+impl From<&RpcInclusionFeerateEstimate> for FeeRateInclusionEstimate {
+    fn from(estimate: &RpcInclusionFeerateEstimate) -> Self {
+        Self {
+            target_seconds: estimate.target_seconds,
+            confidence: estimate.confidence,
+            feerate: (estimate.feerate >= 0.0).then_some(estimate.feerate),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeRateEstimateRequest {
+    /// Also request the feerates learned from recent block inclusions, which costs the node a verbose estimate
+    #[serde(default)]
+    pub inclusion: bool,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 #[serde(rename_all = "camelCase")]
@@ -720,6 +745,8 @@ pub struct FeeRateEstimateResponse {
     pub priority: FeeRateEstimateBucket,
     pub normal: FeeRateEstimateBucket,
     pub low: FeeRateEstimateBucket,
+    /// Feerates learned from recent block inclusions, empty unless requested or if the node does not provide them
+    pub inclusion: Vec<FeeRateInclusionEstimate>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
@@ -488,7 +488,7 @@ pub trait WalletApi: Send + Sync + AnySync {
 
     /// Wrapper around [`fee_rate_estimate_call()`](Self::fee_rate_estimate_call)
     async fn fee_rate_estimate(self: Arc<Self>) -> Result<FeeRateEstimateResponse> {
-        Ok(self.fee_rate_estimate_call(FeeRateEstimateRequest {}).await?)
+        Ok(self.fee_rate_estimate_call(FeeRateEstimateRequest { inclusion: false }).await?)
     }
 
     /// Estimate current network fee rate. Returns a [`FeeRateEstimateResponse`]
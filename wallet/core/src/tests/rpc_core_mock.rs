@@ -231,6 +231,30 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_seq_commit_lane_proofs_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: GetSeqCommitLaneProofsRequest,
+    ) -> RpcResult<GetSeqCommitLaneProofsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn submit_transaction_package_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: SubmitTransactionPackageRequest,
+    ) -> RpcResult<SubmitTransactionPackageResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn validate_transaction_scripts_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: ValidateTransactionScriptsRequest,
+    ) -> RpcResult<ValidateTransactionScriptsResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     async fn get_subnetwork_call(
         &self,
         _connection: Option<&DynRpcConnection>,
@@ -331,6 +355,14 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_banned_peers_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: GetBannedPeersRequest,
+    ) -> RpcResult<GetBannedPeersResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     async fn estimate_network_hashes_per_second_call(
         &self,
         _connection: Option<&DynRpcConnection>,
@@ -395,6 +427,22 @@ impl RpcApi for RpcCoreMock {
         Err(RpcError::NotImplemented)
     }
 
+    async fn get_transaction_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: GetTransactionRequest,
+    ) -> RpcResult<GetTransactionResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
+    async fn get_address_history_call(
+        &self,
+        _connection: Option<&DynRpcConnection>,
+        _request: GetAddressHistoryRequest,
+    ) -> RpcResult<GetAddressHistoryResponse> {
+        Err(RpcError::NotImplemented)
+    }
+
     async fn get_utxo_return_address_call(
         &self,
         _connection: Option<&DynRpcConnection>,
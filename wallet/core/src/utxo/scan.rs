@@ -11,6 +11,8 @@ use crate::utxo::{UtxoContext, UtxoEntryReference, UtxoEntryReferenceExtension};
 use std::cmp::max;
 
 pub const DEFAULT_WINDOW_SIZE: usize = 8;
+/// Maximum number of UTXOs requested from the node at once
+pub const UTXO_PAGE_SIZE: u32 = 10_000;
 
 #[derive(Default, Clone, Copy)]
 pub enum ScanExtent {
@@ -88,41 +90,45 @@ impl Scan {
             utxo_context.register_addresses(&addresses).await?;
 
             let ts = Instant::now();
-            let resp = utxo_context.processor().rpc_api().get_utxos_by_addresses(addresses).await?;
-            let elapsed_sec = ts.elapsed().as_secs_f32();
-            if elapsed_sec > 1.0 {
-                log_warn!("get_utxos_by_address() fetched {} entries in: {} msec", resp.len(), elapsed_sec);
-            }
-            yield_executor().await;
-
-            if !resp.is_empty() {
-                let refs: Vec<UtxoEntryReference> = resp.into_iter().map(UtxoEntryReference::from).collect();
-                for utxo_ref in refs.iter() {
-                    if let Some(address) = utxo_ref.utxo.address.as_ref() {
-                        if let Some(utxo_address_index) = address_manager.inner().address_to_index_map.get(address) {
-                            if last_address_index < *utxo_address_index {
-                                last_address_index = *utxo_address_index;
+            let mut entry_count = 0;
+            let mut page_cursor = None;
+            loop {
+                let page = utxo_context
+                    .processor()
+                    .rpc_api()
+                    .get_utxos_by_addresses_page(addresses.clone(), page_cursor, Some(UTXO_PAGE_SIZE), None)
+                    .await?;
+                entry_count += page.entries.len();
+
+                if !page.entries.is_empty() {
+                    let refs: Vec<UtxoEntryReference> = page.entries.into_iter().map(UtxoEntryReference::from).collect();
+                    for utxo_ref in refs.iter() {
+                        if let Some(address) = utxo_ref.utxo.address.as_ref() {
+                            if let Some(utxo_address_index) = address_manager.inner().address_to_index_map.get(address) {
+                                if last_address_index < *utxo_address_index {
+                                    last_address_index = *utxo_address_index;
+                                }
+                            } else {
+                                panic!("Account::scan_address_manager() has received an unknown address: `{address}`");
                             }
-                        } else {
-                            panic!("Account::scan_address_manager() has received an unknown address: `{address}`");
                         }
                     }
-                }
 
-                let balance: Balance = refs.iter().fold(Balance::default(), |mut balance, r| {
-                    let entry_balance = r.balance(params, self.current_daa_score);
-                    balance.mature += entry_balance.mature;
-                    balance.pending += entry_balance.pending;
-                    balance.mature_utxo_count += entry_balance.mature_utxo_count;
-                    balance.pending_utxo_count += entry_balance.pending_utxo_count;
-                    balance.stasis_utxo_count += entry_balance.stasis_utxo_count;
-                    balance
-                });
+                    self.extend_from_page(refs, params, utxo_context).await?;
+                }
 
-                utxo_context.extend_from_scan(refs, self.current_daa_score).await?;
+                page_cursor = page.next_cursor;
+                if page_cursor.is_none() {
+                    break;
+                }
+                yield_executor().await;
+            }
+            let elapsed_sec = ts.elapsed().as_secs_f32();
+            if elapsed_sec > 1.0 {
+                log_warn!("get_utxos_by_address() fetched {} entries in: {} msec", entry_count, elapsed_sec);
+            }
 
-                self.balance.add(balance);
-            } else {
+            if entry_count == 0 {
                 match &extent {
                     ScanExtent::EmptyWindow => {
                         if cursor > last_address_index + window_size {
@@ -150,9 +156,28 @@ impl Scan {
         let address_vec = address_set.iter().cloned().collect::<Vec<_>>();
 
         utxo_context.register_addresses(&address_vec).await?;
-        let resp = utxo_context.processor().rpc_api().get_utxos_by_addresses(address_vec).await?;
-        let refs: Vec<UtxoEntryReference> = resp.into_iter().map(UtxoEntryReference::from).collect();
 
+        let mut page_cursor = None;
+        loop {
+            let page = utxo_context
+                .processor()
+                .rpc_api()
+                .get_utxos_by_addresses_page(address_vec.clone(), page_cursor, Some(UTXO_PAGE_SIZE), None)
+                .await?;
+            let refs: Vec<UtxoEntryReference> = page.entries.into_iter().map(UtxoEntryReference::from).collect();
+            self.extend_from_page(refs, params, utxo_context).await?;
+
+            page_cursor = page.next_cursor;
+            if page_cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds a page of scanned UTXOs to the UTXO context and accounts for their balance.
+    async fn extend_from_page(&self, refs: Vec<UtxoEntryReference>, params: &NetworkParams, utxo_context: &UtxoContext) -> Result<()> {
         let balance: Balance = refs.iter().fold(Balance::default(), |mut balance, r| {
             let entry_balance = r.balance(params, self.current_daa_score);
             balance.mature += entry_balance.mature;
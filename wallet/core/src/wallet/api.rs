@@ -9,7 +9,7 @@ use crate::result::Result;
 use crate::storage::Binding;
 use crate::storage::interface::TransactionRangeResult;
 use crate::tx::Fees;
-use kaspa_rpc_core::RpcFeeEstimate;
+use kaspa_rpc_core::{GetFeeEstimateExperimentalResponse, RpcError, RpcFeeEstimate};
 use kaspa_wallet_pskt::bundle::Bundle;
 use workflow_core::channel::Receiver;
 #[async_trait]
@@ -662,13 +662,30 @@ impl WalletApi for super::Wallet {
         return Err(Error::NotImplemented);
     }
 
-    async fn fee_rate_estimate_call(self: Arc<Self>, _request: FeeRateEstimateRequest) -> Result<FeeRateEstimateResponse> {
-        let RpcFeeEstimate { priority_bucket, normal_buckets, low_buckets } = self.rpc_api().get_fee_estimate().await?;
+    async fn fee_rate_estimate_call(self: Arc<Self>, request: FeeRateEstimateRequest) -> Result<FeeRateEstimateResponse> {
+        // The verbose experimental estimate additionally carries the feerates learned from recent block
+        // inclusions; fall back to the regular estimate for nodes not serving it
+        let (estimate, inclusion) = if request.inclusion {
+            match self.rpc_api().get_fee_estimate_experimental(true).await {
+                Ok(GetFeeEstimateExperimentalResponse { estimate, verbose }) => {
+                    let inclusion = verbose
+                        .map(|verbose| verbose.inclusion_estimates.iter().map(FeeRateInclusionEstimate::from).collect())
+                        .unwrap_or_default();
+                    (estimate, inclusion)
+                }
+                Err(err) if is_unsupported_method(&err) => (self.rpc_api().get_fee_estimate().await?, vec![]),
+                Err(err) => return Err(err.into()),
+            }
+        } else {
+            (self.rpc_api().get_fee_estimate().await?, vec![])
+        };
+        let RpcFeeEstimate { priority_bucket, normal_buckets, low_buckets } = estimate;
 
         Ok(FeeRateEstimateResponse {
             priority: priority_bucket.into(),
             normal: normal_buckets.first().ok_or(Error::custom("missing normal feerate bucket"))?.into(),
             low: low_buckets.first().ok_or(Error::custom("missing normal feerate bucket"))?.into(),
+            inclusion,
         })
     }
 
@@ -686,3 +703,16 @@ impl WalletApi for super::Wallet {
         Ok(FeeRatePollerDisableResponse {})
     }
 }
+
+/// Whether `err` reports a node not serving the requested RPC method. Errors of remote nodes may only
+/// reach the client as text.
+fn is_unsupported_method(err: &RpcError) -> bool {
+    match err {
+        RpcError::NotImplemented | RpcError::UnsupportedFeature => true,
+        RpcError::General(message) | RpcError::RpcSubsystem(message) => {
+            let message = message.to_lowercase();
+            message.contains("not implemented") || message.contains("not found") || message.contains("unknown method")
+        }
+        _ => false,
+    }
+}
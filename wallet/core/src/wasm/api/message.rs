@@ -1823,15 +1823,35 @@ declare! {
     "#,
 }
 
+declare! {
+    IFeeRateInclusionEstimate,
+    r#"
+    /**
+     * Feerate observed by the node to get transactions included within `targetSeconds`
+     * with probability `confidence` (in the [0, 1] range). The feerate is undefined if
+     * the node did not observe enough block inclusions yet.
+     */
+    export interface IFeeRateInclusionEstimate {
+        targetSeconds : number;
+        confidence : number;
+        feerate? : number;
+    }
+    "#,
+}
+
 declare! {
     IFeeRateEstimateRequest,
     r#"
-    export interface IFeeRateEstimateRequest { }
+    export interface IFeeRateEstimateRequest {
+        // also request the feerates learned from recent block inclusions
+        inclusion? : boolean;
+    }
     "#,
 }
 
-try_from! ( _args: IFeeRateEstimateRequest, FeeRateEstimateRequest, {
-    Ok(FeeRateEstimateRequest { })
+try_from! ( args: IFeeRateEstimateRequest, FeeRateEstimateRequest, {
+    let inclusion = args.try_get_bool("inclusion")?.unwrap_or(false);
+    Ok(FeeRateEstimateRequest { inclusion })
 });
 
 declare! {
@@ -1841,6 +1861,7 @@ declare! {
         priority : IFeeRateEstimateBucket,
         normal : IFeeRateEstimateBucket,
         low : IFeeRateEstimateBucket,
+        inclusion : IFeeRateInclusionEstimate[],
     }
     "#,
 }